@@ -32,5 +32,57 @@ impl<F: Into<T>, T> IntoOption<T> for Option<F> {
 /// Pre-init the Platform information.
 pub use platform::init_cluster_info_or_panic;
 
-/// Prefix for all keys stored in the persistent store (ETCD)
+/// Default prefix for all keys stored in the persistent store (ETCD)
 pub const ETCD_KEY_PREFIX: &str = "/openebs.io/mayastor";
+
+/// Environment variable which overrides the default `ETCD_KEY_PREFIX`
+pub const ETCD_KEY_PREFIX_ENV: &str = "STORE_KEY_PREFIX";
+
+/// The key prefix in use, set once either explicitly or on first use.
+static ETCD_KEY_PREFIX_OVERRIDE: once_cell::sync::OnceCell<String> =
+    once_cell::sync::OnceCell::new();
+
+/// Parse a prefix for the keys stored in the persistent store, which must start with '/'.
+/// Any trailing '/' is removed.
+pub fn parse_etcd_key_prefix(prefix: &str) -> Result<String, String> {
+    let prefix = prefix.trim_end_matches('/');
+    if !prefix.starts_with('/') {
+        return Err(format!("Key prefix '{}' must start with '/'", prefix));
+    }
+    Ok(prefix.to_string())
+}
+
+/// Override the prefix for all keys stored in the persistent store.
+/// Must be called before any key is built, otherwise an error is returned.
+pub fn set_etcd_key_prefix(prefix: &str) -> Result<(), String> {
+    ETCD_KEY_PREFIX_OVERRIDE
+        .set(parse_etcd_key_prefix(prefix)?)
+        .map_err(|_| format!("Key prefix already set to '{}'", etcd_key_prefix()))
+}
+
+/// Get the prefix for all keys stored in the persistent store.
+/// This is the override set with `set_etcd_key_prefix`, or else the `ETCD_KEY_PREFIX_ENV`
+/// environment variable, or else the default `ETCD_KEY_PREFIX`.
+/// Panics if the environment variable is not a valid key prefix, as there is no sensible
+/// fallback when the keys of a different prefix were requested.
+pub fn etcd_key_prefix() -> &'static str {
+    ETCD_KEY_PREFIX_OVERRIDE.get_or_init(|| match std::env::var(ETCD_KEY_PREFIX_ENV) {
+        Ok(prefix) if !prefix.is_empty() => parse_etcd_key_prefix(&prefix)
+            .unwrap_or_else(|error| panic!("Invalid {}: {}", ETCD_KEY_PREFIX_ENV, error)),
+        _ => ETCD_KEY_PREFIX.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_etcd_key_prefix;
+
+    #[test]
+    fn key_prefix() {
+        assert_eq!(parse_etcd_key_prefix("/cp/").unwrap(), "/cp");
+        assert_eq!(parse_etcd_key_prefix("/cp/store").unwrap(), "/cp/store");
+        assert!(parse_etcd_key_prefix("cp").is_err());
+        assert!(parse_etcd_key_prefix("/").is_err());
+        assert!(parse_etcd_key_prefix("").is_err());
+    }
+}
@@ -1,13 +1,21 @@
 use super::*;
 use nats::asynk::Connection;
 use once_cell::sync::OnceCell;
+use std::{convert::Infallible, path::PathBuf, sync::Arc};
 use tracing::{info, warn};
 
 static NATS_MSG_BUS: OnceCell<NatsMessageBus> = OnceCell::new();
 
 /// Initialise the Nats Message Bus
 pub async fn message_bus_init(server: String) {
-    let nc = NatsMessageBus::new(None, &server, BusOptions::new(), TimeoutOptions::new()).await;
+    let nc = NatsMessageBus::new(
+        None,
+        &server,
+        BusConnectOptions::default(),
+        TimeoutOptions::new(),
+    )
+    .await
+    .expect("Should connect to the nats server");
     NATS_MSG_BUS
         .set(nc)
         .ok()
@@ -19,12 +27,14 @@ pub async fn message_bus_init(server: String) {
 pub async fn message_bus_init_options(
     client: impl Into<Option<BusClient>>,
     server: String,
+    connect_options: BusConnectOptions,
     timeouts: TimeoutOptions,
-) {
+) -> BusResult<()> {
     if NATS_MSG_BUS.get().is_none() {
-        let nc = NatsMessageBus::new(client.into(), &server, BusOptions::new(), timeouts).await;
+        let nc = NatsMessageBus::new(client.into(), &server, connect_options, timeouts).await?;
         NATS_MSG_BUS.set(nc).ok();
     }
+    Ok(())
 }
 
 /// Get the static `NatsMessageBus` as a boxed `MessageBus`
@@ -37,6 +47,197 @@ pub fn bus() -> DynBus {
     )
 }
 
+/// A secret used to authenticate with the nats server, which is redacted when printed
+#[derive(Clone, Eq, PartialEq)]
+pub struct BusSecret(String);
+
+impl BusSecret {
+    /// Get the secret value
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for BusSecret {
+    type Err = Infallible;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Ok(Self(source.to_string()))
+    }
+}
+
+impl std::fmt::Debug for BusSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Credentials used to authenticate with the nats server
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BusCredentials {
+    /// username and password
+    UserPassword {
+        /// the username
+        user: String,
+        /// the password of the user
+        password: BusSecret,
+    },
+    /// authentication token
+    Token(BusSecret),
+    /// path to a nats credentials file, holding the user JWT and NKey seed
+    CredsFile(PathBuf),
+}
+
+/// Options for the nats server connection
+#[derive(Debug, Clone, Default)]
+pub struct BusConnectOptions {
+    /// credentials used to authenticate with the nats server
+    credentials: Option<BusCredentials>,
+    /// path to the PEM encoded CA certificate used to verify the nats server
+    ca_cert: Option<PathBuf>,
+    /// paths to the PEM encoded client certificate and key used to authenticate with the server
+    client_identity: Option<(PathBuf, PathBuf)>,
+    /// don't verify the certificate of the nats server
+    insecure_skip_verify: bool,
+}
+
+impl BusConnectOptions {
+    /// Return new `Self` from the provided credentials.
+    /// Fails if only one of the username and password is provided, if more than one kind of
+    /// credentials is provided or if the credentials file does not exist.
+    pub fn new(
+        user: Option<String>,
+        password: Option<BusSecret>,
+        token: Option<BusSecret>,
+        creds_file: Option<PathBuf>,
+    ) -> Result<Self, Error> {
+        let user_password = match (user, password) {
+            (Some(user), Some(password)) => Some(BusCredentials::UserPassword { user, password }),
+            (None, None) => None,
+            _ => {
+                return Err(Error::InvalidConnectOptions {
+                    reason: "both the username and password must be provided".to_string(),
+                })
+            }
+        };
+        let mut credentials = user_password
+            .into_iter()
+            .chain(token.map(BusCredentials::Token))
+            .chain(creds_file.map(BusCredentials::CredsFile));
+        let first = credentials.next();
+        if credentials.next().is_some() {
+            return Err(Error::InvalidConnectOptions {
+                reason: "only one of username/password, token or credentials file may be provided"
+                    .to_string(),
+            });
+        }
+        if let Some(BusCredentials::CredsFile(path)) = &first {
+            if !path.is_file() {
+                return Err(Error::InvalidConnectOptions {
+                    reason: format!("the credentials file '{}' does not exist", path.display()),
+                });
+            }
+        }
+        Ok(Self {
+            credentials: first,
+            ..Default::default()
+        })
+    }
+    /// Connect to the nats server over TLS with the provided TLS file paths, or without verifying
+    /// the server certificate if `insecure_skip_verify` is set.
+    /// TLS is only required if any of these options is provided.
+    /// Fails if only one of the client certificate and key is provided.
+    pub fn with_tls(
+        mut self,
+        ca_cert: Option<PathBuf>,
+        client_cert: Option<PathBuf>,
+        client_key: Option<PathBuf>,
+        insecure_skip_verify: bool,
+    ) -> Result<Self, Error> {
+        self.client_identity = match (client_cert, client_key) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            (None, None) => None,
+            _ => {
+                return Err(Error::InvalidConnectOptions {
+                    reason: "both the client certificate and key must be provided".to_string(),
+                })
+            }
+        };
+        self.ca_cert = ca_cert;
+        self.insecure_skip_verify = insecure_skip_verify;
+        Ok(self)
+    }
+    /// Check if TLS is configured
+    pub fn tls(&self) -> bool {
+        self.ca_cert.is_some() || self.client_identity.is_some() || self.insecure_skip_verify
+    }
+    /// Get the credentials used to authenticate with the nats server, if any
+    pub fn credentials(&self) -> Option<&BusCredentials> {
+        self.credentials.as_ref()
+    }
+    /// Get new nats `BusOptions` with the configured credentials and TLS
+    fn bus_options(&self) -> BusOptions {
+        let mut options = match &self.credentials {
+            None => BusOptions::new(),
+            Some(BusCredentials::UserPassword { user, password }) => {
+                BusOptions::with_user_pass(user, password.expose())
+            }
+            Some(BusCredentials::Token(token)) => BusOptions::with_token(token.expose()),
+            Some(BusCredentials::CredsFile(path)) => BusOptions::with_credentials(path),
+        };
+        if !self.tls() {
+            return options;
+        }
+        options = options.tls_required(true);
+        if self.insecure_skip_verify {
+            let mut config = rustls::ClientConfig::new();
+            config
+                .dangerous()
+                .set_certificate_verifier(Arc::new(NoServerCertVerification {}));
+            options = options.tls_client_config(config);
+        }
+        if let Some(ca_cert) = &self.ca_cert {
+            options = options.add_root_certificate(ca_cert);
+        }
+        if let Some((cert, key)) = &self.client_identity {
+            options = options.client_cert(cert, key);
+        }
+        options
+    }
+}
+
+/// Accepts any nats server certificate, for when `insecure_skip_verify` is set
+struct NoServerCertVerification {}
+
+impl rustls::ServerCertVerifier for NoServerCertVerification {
+    fn verify_server_cert(
+        &self,
+        _roots: &rustls::RootCertStore,
+        _presented_certs: &[rustls::Certificate],
+        _dns_name: webpki::DNSNameRef<'_>,
+        _ocsp_response: &[u8],
+    ) -> Result<rustls::ServerCertVerified, rustls::TLSError> {
+        Ok(rustls::ServerCertVerified::assertion())
+    }
+}
+
+/// Check if the connection `error` is due to the server rejecting our credentials, in which
+/// case retrying is pointless
+/// The nats client reports an error reply of the server during the connection handshake, eg:
+/// an authorization violation, with the `PermissionDenied` kind.
+fn auth_rejected(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::PermissionDenied
+}
+
+/// Check if the connection `error` is due to a TLS failure, eg: the server requires TLS but we
+/// don't trust its certificate, in which case retrying is pointless
+fn tls_failed(error: &io::Error) -> bool {
+    error
+        .get_ref()
+        .map(|source| source.is::<rustls::TLSError>() || source.is::<webpki::Error>())
+        .unwrap_or(false)
+}
+
 // Would we want to have both sync and async clients?
 /// Nats implementation of the Bus
 #[derive(Clone)]
@@ -47,15 +248,22 @@ pub struct NatsMessageBus {
 }
 impl NatsMessageBus {
     /// Connect to the provided server
-    /// Logs the first error and quietly continues retrying forever
-    pub async fn connect(timeout_opts: TimeoutOptions, server: &str) -> Connection {
+    /// Logs the first error and quietly continues retrying forever, unless the server rejects
+    /// the credentials or the TLS handshake fails, in which case the error is returned as
+    /// retrying won't help
+    pub async fn connect(
+        timeout_opts: TimeoutOptions,
+        connect_options: &BusConnectOptions,
+        server: &str,
+    ) -> BusResult<Connection> {
         info!("Connecting to the nats server {}...", server);
         // We retry in a loop until successful. Once connected the nats
         // library will handle reconnections for us.
         let interval = std::time::Duration::from_millis(500);
         let mut log_error = true;
         loop {
-            match BusOptions::new()
+            match connect_options
+                .bus_options()
                 .max_reconnects(None)
                 .tcp_read_timeout(timeout_opts.tcp_read_timeout())
                 .tcp_connect_timeout(timeout_opts.tcp_read_timeout())
@@ -67,7 +275,25 @@ impl NatsMessageBus {
             {
                 Ok(connection) => {
                     info!("Successfully connected to the nats server {}", server);
-                    return connection;
+                    return Ok(connection);
+                }
+                Err(error) if auth_rejected(&error) => {
+                    return Err(Error::ConnectAuth {
+                        server: server.to_string(),
+                        source: error,
+                    });
+                }
+                Err(error) if tls_failed(&error) => {
+                    if !connect_options.tls() {
+                        warn!(
+                            "The nats server {} requires TLS, but no TLS options were provided",
+                            server
+                        );
+                    }
+                    return Err(Error::ConnectTls {
+                        server: server.to_string(),
+                        source: error,
+                    });
                 }
                 Err(error) => {
                     if log_error {
@@ -85,14 +311,14 @@ impl NatsMessageBus {
     pub async fn new(
         client_name: Option<BusClient>,
         server: &str,
-        _bus_options: BusOptions,
+        connect_options: BusConnectOptions,
         timeout_options: TimeoutOptions,
-    ) -> Self {
-        Self {
+    ) -> BusResult<Self> {
+        Ok(Self {
             timeout_options: timeout_options.clone(),
-            connection: Self::connect(timeout_options, server).await,
+            connection: Self::connect(timeout_options, &connect_options, server).await?,
             client_name: client_name.unwrap_or(BusClient::Unnamed),
-        }
+        })
     }
 }
 
@@ -182,3 +408,151 @@ impl Bus for NatsMessageBus {
         &self.timeout_options
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(secret: &str) -> Option<BusSecret> {
+        Some(BusSecret::from_str(secret).unwrap())
+    }
+
+    #[test]
+    fn bus_connect_options() {
+        let options = BusConnectOptions::new(None, None, None, None).unwrap();
+        assert_eq!(options.credentials(), None);
+
+        let options =
+            BusConnectOptions::new(Some("user".to_string()), secret("pass"), None, None).unwrap();
+        assert_eq!(
+            options.credentials(),
+            Some(&BusCredentials::UserPassword {
+                user: "user".to_string(),
+                password: BusSecret("pass".to_string()),
+            })
+        );
+        // the secrets are not printed
+        assert!(!format!("{:?}", options).contains("\"pass\""));
+
+        let options = BusConnectOptions::new(None, None, secret("token"), None).unwrap();
+        assert_eq!(
+            options.credentials(),
+            Some(&BusCredentials::Token(BusSecret("token".to_string())))
+        );
+
+        // the username and password must be provided together
+        assert!(BusConnectOptions::new(Some("user".to_string()), None, None, None).is_err());
+        assert!(BusConnectOptions::new(None, secret("pass"), None, None).is_err());
+        // only one kind of credentials may be provided
+        assert!(BusConnectOptions::new(
+            Some("user".to_string()),
+            secret("pass"),
+            secret("token"),
+            None
+        )
+        .is_err());
+        // the credentials file must exist
+        let creds = PathBuf::from("/nonexistent/nats.creds");
+        assert!(BusConnectOptions::new(None, None, None, Some(creds)).is_err());
+    }
+
+    #[test]
+    fn bus_connect_tls_options() {
+        let options = BusConnectOptions::default();
+        assert!(!options.tls());
+        let options = options.with_tls(None, None, None, false).unwrap();
+        assert!(!options.tls());
+
+        let ca = Some(PathBuf::from("/certs/ca.pem"));
+        let options = BusConnectOptions::default()
+            .with_tls(ca.clone(), None, None, false)
+            .unwrap();
+        assert!(options.tls());
+        let options = BusConnectOptions::default()
+            .with_tls(None, None, None, true)
+            .unwrap();
+        assert!(options.tls());
+
+        // the client certificate and key must be provided together
+        let cert = Some(PathBuf::from("/certs/client.pem"));
+        let key = Some(PathBuf::from("/certs/client-key.pem"));
+        assert!(BusConnectOptions::default()
+            .with_tls(ca.clone(), cert.clone(), None, false)
+            .is_err());
+        assert!(BusConnectOptions::default()
+            .with_tls(ca.clone(), None, key.clone(), false)
+            .is_err());
+        assert!(BusConnectOptions::default()
+            .with_tls(ca, cert, key, false)
+            .unwrap()
+            .tls());
+    }
+
+    /// Serve the nats protocol `INFO` of a server which requires TLS and/or authentication to
+    /// every connection, replying with `reply` to whatever the client sends first.
+    fn fake_server(tls_required: bool, auth_required: bool, reply: &'static [u8]) -> String {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let info = format!(
+            "INFO {{\"server_id\":\"test\",\"version\":\"2.6.0\",\"go\":\"go\",\
+            \"host\":\"127.0.0.1\",\"port\":4222,\"max_payload\":1048576,\"proto\":1,\
+            \"tls_required\":{},\"auth_required\":{}}}\r\n",
+            tls_required, auth_required
+        );
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                stream.write_all(info.as_bytes()).ok();
+                let mut request = [0; 512];
+                let _ = stream.read(&mut request);
+                stream.write_all(reply).ok();
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn bus_connect_tls_required() {
+        // the reply is not a valid TLS handshake
+        let server = fake_server(true, false, b"-ERR 'not a tls server'\r\n");
+        let connect = NatsMessageBus::connect(
+            TimeoutOptions::new(),
+            &BusConnectOptions::default(),
+            &server,
+        );
+        // the connection is not retried, as the TLS handshake can't succeed
+        let result = tokio::time::timeout(Duration::from_secs(10), connect)
+            .await
+            .expect("Should not retry the connection");
+        match result {
+            Err(Error::ConnectTls { .. }) => {}
+            Err(error) => panic!("Unexpected error: {}", error),
+            Ok(_) => panic!("Should not connect to the server"),
+        }
+    }
+
+    #[test]
+    fn bus_auth_rejected() {
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "Authorization Violation");
+        assert!(auth_rejected(&denied));
+        // only the error kind matters, not what the message happens to say
+        let refused = io::Error::new(io::ErrorKind::ConnectionRefused, "authentication service");
+        assert!(!auth_rejected(&refused));
+    }
+
+    #[tokio::test]
+    async fn bus_connect_auth_rejected() {
+        let server = fake_server(false, true, b"-ERR 'Authorization Violation'\r\n");
+        let options = BusConnectOptions::new(None, None, secret("token"), None).unwrap();
+        let connect = NatsMessageBus::connect(TimeoutOptions::new(), &options, &server);
+        // the connection is not retried, as the credentials won't be accepted
+        let result = tokio::time::timeout(Duration::from_secs(10), connect)
+            .await
+            .expect("Should not retry the connection");
+        match result {
+            Err(Error::ConnectAuth { .. }) => {}
+            Err(error) => panic!("Unexpected error: {}", error),
+            Ok(_) => panic!("Should not connect to the server"),
+        }
+    }
+}
@@ -19,7 +19,10 @@ use crate::types::{
 };
 use async_trait::async_trait;
 use dyn_clonable::clonable;
-pub use mbus_nats::{bus, message_bus_init, message_bus_init_options, NatsMessageBus};
+pub use mbus_nats::{
+    bus, message_bus_init, message_bus_init_options, BusConnectOptions, BusCredentials, BusSecret,
+    NatsMessageBus,
+};
 use opentelemetry::propagation::{Extractor, Injector};
 pub use receive::*;
 pub use send::*;
@@ -109,6 +112,15 @@ pub enum Error {
     Subscribe { channel: String, source: io::Error },
     #[snafu(display("Reply message came back with an error"))]
     ReplyWithError { source: ReplyError },
+    #[snafu(display("Invalid message bus connection options: {}", reason))]
+    InvalidConnectOptions { reason: String },
+    #[snafu(display("The nats server '{}' rejected the authentication credentials", server))]
+    ConnectAuth { server: String, source: io::Error },
+    #[snafu(display(
+        "Failed to establish a TLS connection with the nats server '{}'",
+        server
+    ))]
+    ConnectTls { server: String, source: io::Error },
 }
 
 /// Report error chain
@@ -137,14 +149,15 @@ where
 /// Message id which uniquely identifies every type of unsolicited message
 /// The solicited (replies) message do not currently carry an id as they
 /// are sent to a specific requested channel
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 #[allow(non_camel_case_types)]
 pub enum MessageId {
     /// Version 0
     v0(MessageIdVs),
 }
 
-/// Exposes specific timeouts for different MessageId's
+/// Exposes specific timeouts for different MessageId's.
+/// The message timeout overrides of the bus take precedence over these.
 pub trait MessageIdTimeout: Send {
     /// Get the default `TimeoutOptions` for this message
     fn timeout_opts(&self, opts: TimeoutOptions, bus: &DynBus) -> TimeoutOptions;
@@ -154,11 +167,17 @@ pub trait MessageIdTimeout: Send {
 
 impl MessageIdTimeout for MessageId {
     fn timeout_opts(&self, opts: TimeoutOptions, bus: &DynBus) -> TimeoutOptions {
+        if let Some(timeout) = bus.timeout_opts().message_timeout(self) {
+            return opts.with_timeout(timeout);
+        }
         match self {
             MessageId::v0(id) => id.timeout_opts(opts, bus),
         }
     }
     fn timeout(&self, timeout: Duration, bus: &DynBus) -> Duration {
+        if let Some(timeout) = bus.timeout_opts().message_timeout(self) {
+            return timeout;
+        }
         match self {
             MessageId::v0(id) => id.timeout(timeout, bus),
         }
@@ -250,6 +269,26 @@ pub trait Message {
         channel: C,
         bus: DynBus,
     ) -> BusResult<Self::Reply>;
+    /// publish a message with a request for a `Self::Reply` reply, parenting its span
+    /// to the given trace `context` rather than to the current context
+    async fn request_with_context(
+        &self,
+        options: Option<TimeoutOptions>,
+        context: opentelemetry::Context,
+    ) -> BusResult<Self::Reply>;
+}
+
+/// Extract the remote trace context from a W3C `traceparent` (and optional `tracestate`)
+/// header value, eg: from an incoming REST request, so that it can be used as the parent of
+/// the message span when it wasn't installed into the current context.
+/// Uses the globally configured text map propagator.
+pub fn traceparent_context(traceparent: &str, tracestate: Option<&str>) -> opentelemetry::Context {
+    let mut carrier = TraceContext::new();
+    carrier.set("traceparent", traceparent.to_string());
+    if let Some(tracestate) = tracestate {
+        carrier.set("tracestate", tracestate.to_string());
+    }
+    opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(&carrier))
 }
 
 /// The preamble is used to peek into messages so allowing for them to be routed
@@ -302,7 +341,7 @@ impl<T> Deref for SendPayload<T> {
 }
 
 /// All the different variants of Resources
-#[derive(Serialize, Deserialize, Debug, Clone, AsRefStr, ToString)]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, AsRefStr, ToString)]
 pub enum ResourceKind {
     /// Unknown or unspecified resource
     Unknown,
@@ -330,6 +369,8 @@ pub enum ResourceKind {
     Watch,
     /// Spec
     Spec,
+    /// Volume snapshot
+    VolumeSnapshot,
 }
 
 /// Error type which is returned over the bus
@@ -382,6 +423,15 @@ impl From<JoinError> for ReplyError {
 }
 
 impl StdError for ReplyError {}
+/// Details of a validation error in the `field=<field>; reason=<reason>` format, which lets the
+/// clients map the error back to the offending field
+pub fn validation_details(field: Option<&str>, reason: &str) -> String {
+    match field {
+        Some(field) => format!("field={}; reason={}", field, reason),
+        None => format!("reason={}", reason),
+    }
+}
+
 impl ReplyError {
     /// extend error with source
     /// useful when another error wraps around a `ReplyError` and we want to
@@ -431,8 +481,8 @@ impl ReplyError {
         Self {
             kind: ReplyErrorKind::InvalidArgument,
             resource,
+            extra: validation_details(Some(arg_name), &error),
             source: error,
-            extra: format!("Invalid {} was provided", arg_name),
         }
     }
     /// used when we encounter a missing argument
@@ -441,7 +491,7 @@ impl ReplyError {
             kind: ReplyErrorKind::InvalidArgument,
             resource,
             source: arg_name.to_string(),
-            extra: format!("Argument {} was not provided", arg_name),
+            extra: validation_details(Some(arg_name), "argument was not provided"),
         }
     }
     /// for errors that can occur when serializing or deserializing JSON data
@@ -506,6 +556,7 @@ pub enum ReplyErrorKind {
     ReplicaCreateNumber,
     VolumeNoReplicas,
     InUse,
+    DeviceInUse,
 }
 
 impl From<tonic::Code> for ReplyErrorKind {
@@ -584,6 +635,8 @@ pub struct TimeoutOptions {
 
     /// Request specific minimum timeouts
     request_timeout: Option<RequestMinTimeout>,
+    /// Timeouts of specific messages, which take precedence over any other timeout
+    message_timeouts: MessageTimeouts,
 
     /// Http2 keep alive interval.
     keep_alive_interval: std::time::Duration,
@@ -619,6 +672,69 @@ impl RequestMinTimeout {
     }
 }
 
+/// Environment variable with the per message timeout overrides
+pub const MESSAGE_TIMEOUTS_ENV: &str = "MBUS_MESSAGE_TIMEOUTS";
+/// Environment variable with the path of a file containing the per message timeout overrides
+pub const MESSAGE_TIMEOUTS_FILE_ENV: &str = "MBUS_MESSAGE_TIMEOUTS_FILE";
+
+/// Timeout overrides for specific messages, in the form of comma or newline separated
+/// `message-id=duration` pairs, eg: `v0/createVolume=2m,publishVolume=45s`
+/// When not specified, the version of the message id defaults to the current version
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageTimeouts(HashMap<MessageId, Duration>);
+
+impl MessageTimeouts {
+    /// Load the overrides from the `MESSAGE_TIMEOUTS_FILE_ENV` file, if set, and then from the
+    /// `MESSAGE_TIMEOUTS_ENV` variable, which takes precedence for the same messages
+    pub fn from_env() -> Result<Self, String> {
+        let mut timeouts = Self::default();
+        if let Ok(path) = std::env::var(MESSAGE_TIMEOUTS_FILE_ENV) {
+            let overrides = std::fs::read_to_string(&path)
+                .map_err(|error| format!("Failed to read '{}': {}", path, error))?;
+            timeouts.0.extend(overrides.parse::<Self>()?.0);
+        }
+        if let Ok(overrides) = std::env::var(MESSAGE_TIMEOUTS_ENV) {
+            timeouts.0.extend(overrides.parse::<Self>()?.0);
+        }
+        Ok(timeouts)
+    }
+    /// Override the timeout of the given message
+    pub fn insert(&mut self, id: MessageId, timeout: Duration) {
+        self.0.insert(id, timeout);
+    }
+    /// Get the timeout override of the given message, if any
+    pub fn get(&self, id: &MessageId) -> Option<Duration> {
+        self.0.get(id).cloned()
+    }
+}
+
+impl FromStr for MessageTimeouts {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut timeouts = Self::default();
+        for pair in source
+            .split(|c| c == ',' || c == '\n')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            let (id, timeout) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected 'message-id=duration' but found '{}'", pair))?;
+            let id = id.trim();
+            let id = match id.contains('/') {
+                true => id.parse::<MessageId>(),
+                false => format!("{}/{}", VERSION, id).parse::<MessageId>(),
+            }
+            .map_err(|error| format!("Invalid message id '{}': {}", id, error))?;
+            let timeout = humantime::parse_duration(timeout.trim())
+                .map_err(|error| format!("Invalid timeout for '{}': {}", pair, error))?;
+            timeouts.insert(id, timeout);
+        }
+        Ok(timeouts)
+    }
+}
+
 impl TimeoutOptions {
     /// Default timeout waiting for a reply.
     pub(crate) fn default_timeout() -> Duration {
@@ -666,6 +782,7 @@ impl Default for TimeoutOptions {
             max_retries: Some(Self::default_max_retries()),
             tcp_read_timeout: Self::default_tcp_read_timeout(),
             request_timeout: Self::default_request_timeouts(),
+            message_timeouts: MessageTimeouts::default(),
             keep_alive_timeout: Self::default_keep_alive_timeout(),
             keep_alive_interval: Self::default_keep_alive_interval(),
         }
@@ -720,6 +837,18 @@ impl TimeoutOptions {
         self.request_timeout.as_ref()
     }
 
+    /// Timeouts of specific messages, which override any other timeout for those messages
+    #[must_use]
+    pub fn with_message_timeouts(mut self, timeouts: MessageTimeouts) -> Self {
+        self.message_timeouts = timeouts;
+        self
+    }
+
+    /// Get the timeout override of the given message, if any
+    pub fn message_timeout(&self, id: &MessageId) -> Option<Duration> {
+        self.message_timeouts.get(id)
+    }
+
     /// Get the http2 Keep Alive interval.
     pub fn keep_alive_interval(&self) -> Duration {
         self.keep_alive_interval
@@ -775,3 +904,104 @@ pub enum BusClient {
     /// Not Specified
     Unnamed,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A bus which is never connected, only used to query its timeout options
+    #[derive(Clone)]
+    struct OfflineBus {
+        client: BusClient,
+        timeout_opts: TimeoutOptions,
+    }
+
+    #[async_trait]
+    impl Bus for OfflineBus {
+        async fn publish(&self, _channel: Channel, _message: &[u8]) -> BusResult<()> {
+            unimplemented!()
+        }
+        async fn send(&self, _channel: Channel, _message: &[u8]) -> BusResult<()> {
+            unimplemented!()
+        }
+        async fn request(
+            &self,
+            _channel: Channel,
+            _message: &[u8],
+            _options: Option<TimeoutOptions>,
+        ) -> BusResult<BusMessage> {
+            unimplemented!()
+        }
+        async fn flush(&self) -> BusResult<()> {
+            unimplemented!()
+        }
+        async fn subscribe(&self, _channel: Channel) -> BusResult<BusSubscription> {
+            unimplemented!()
+        }
+        fn client_name(&self) -> &BusClient {
+            &self.client
+        }
+        fn timeout_opts(&self) -> &TimeoutOptions {
+            &self.timeout_opts
+        }
+    }
+
+    #[test]
+    fn validation_error_details() {
+        let error = ReplyError::invalid_argument(
+            ResourceKind::Volume,
+            "size",
+            "must be a multiple of 512".to_string(),
+        );
+        assert_eq!(error.extra, "field=size; reason=must be a multiple of 512");
+        let error = ReplyError::missing_argument(ResourceKind::Pool, "disks");
+        assert_eq!(error.extra, "field=disks; reason=argument was not provided");
+        assert_eq!(
+            validation_details(None, "node is cordoned"),
+            "reason=node is cordoned"
+        );
+    }
+
+    #[test]
+    fn message_timeout_override() {
+        let timeouts = "v0/createVolume=2m, publishVolume = 45s\ndestroyPool=90s"
+            .parse::<MessageTimeouts>()
+            .unwrap();
+        let bus: DynBus = Box::new(OfflineBus {
+            client: BusClient::CoreAgent,
+            timeout_opts: TimeoutOptions::new().with_message_timeouts(timeouts),
+        });
+        let opts = bus.timeout_opts().clone();
+
+        let create = MessageId::v0(MessageIdVs::CreateVolume);
+        let timeout = create.timeout_opts(opts.clone(), &bus).base_timeout();
+        assert_eq!(timeout, Duration::from_secs(120));
+        assert_eq!(
+            create.timeout(opts.base_timeout(), &bus),
+            Duration::from_secs(120)
+        );
+        let publish = MessageId::v0(MessageIdVs::PublishVolume);
+        let timeout = publish.timeout_opts(opts.clone(), &bus).base_timeout();
+        assert_eq!(timeout, Duration::from_secs(45));
+        let destroy = MessageId::v0(MessageIdVs::DestroyPool);
+        assert_eq!(
+            bus.timeout_opts().message_timeout(&destroy),
+            Some(Duration::from_secs(90))
+        );
+
+        // not overridden ids fall back to the default timeout
+        let get = MessageId::v0(MessageIdVs::GetVolumes);
+        assert_eq!(bus.timeout_opts().message_timeout(&get), None);
+        let timeout = get.timeout_opts(opts.clone(), &bus).base_timeout();
+        assert_eq!(
+            timeout,
+            MessageIdVs::GetVolumes
+                .timeout_opts(opts, &bus)
+                .base_timeout()
+        );
+
+        assert!("createVolume".parse::<MessageTimeouts>().is_err());
+        assert!("createVolume=soon".parse::<MessageTimeouts>().is_err());
+        assert!("v0/notAMessage=1s".parse::<MessageTimeouts>().is_err());
+    }
+}
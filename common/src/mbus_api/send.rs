@@ -173,6 +173,13 @@ macro_rules! bus_impl_message {
             ) -> BusResult<$R> {
                 $T::Request(self, channel.into(), bus).await
             }
+            async fn request_with_context(
+                &self,
+                options: Option<TimeoutOptions>,
+                context: opentelemetry::Context,
+            ) -> BusResult<$R> {
+                $T::Request_Ctx(self, self.channel(), bus(), options, context).await
+            }
         }
     };
 }
@@ -205,6 +212,12 @@ macro_rules! bus_impl_vector_request_token {
             /// The token to use in subsequent requests.
             pub next_token: Option<u64>,
         }
+        impl $Request {
+            /// returns the entries and consumes self
+            pub fn into_inner(self) -> Vec<$Inner> {
+                self.entries
+            }
+        }
     };
 }
 
@@ -226,7 +239,7 @@ where
         channel: C,
         bus: DynBus,
     ) -> BusResult<R> {
-        let mut msg = SendMessage::<S, R>::new(payload, channel.into(), bus);
+        let mut msg = SendMessage::<S, R>::new(payload, channel.into(), bus, None);
         msg.request(None).await
     }
 
@@ -240,9 +253,25 @@ where
         bus: DynBus,
         options: TimeoutOptions,
     ) -> BusResult<R> {
-        let mut msg = SendMessage::<S, R>::new(payload, channel, bus);
+        let mut msg = SendMessage::<S, R>::new(payload, channel, bus, None);
         msg.request(Some(options)).await
     }
+
+    /// Sends the message and requests a reply
+    /// May fail if the bus fails to publish the message.
+    /// The message span is parented to the given trace `context` rather than to the current
+    /// context, eg: to the context extracted from the `traceparent` of an incoming request.
+    #[allow(non_snake_case)]
+    async fn Request_Ctx(
+        payload: &'a S,
+        channel: Channel,
+        bus: DynBus,
+        options: Option<TimeoutOptions>,
+        context: Context,
+    ) -> BusResult<R> {
+        let mut msg = SendMessage::<S, R>::new(payload, channel, bus, Some(context));
+        msg.request(options).await
+    }
 }
 
 /// Trait to send a message `bus` publish with the `payload` type `S` via a
@@ -259,7 +288,7 @@ where
     /// May fail if the bus fails to publish the message
     #[allow(non_snake_case)]
     async fn Publish(payload: &'a S, channel: Channel, bus: DynBus) -> BusResult<()> {
-        let msg = SendMessage::<S, R>::new(payload, channel, bus);
+        let msg = SendMessage::<S, R>::new(payload, channel, bus, None);
         msg.publish().await
     }
 }
@@ -276,6 +305,8 @@ struct SendMessage<'a, S, R> {
     bus: DynBus,
     channel: Channel,
     reply_type: PhantomData<R>,
+    /// trace context the message span is parented to, if not the current context
+    parent_context: Option<Context>,
 }
 
 impl<T> Injector for SendPayload<T> {
@@ -307,7 +338,14 @@ where
     /// Creates a new request `Message` with the required payload
     /// using an existing `bus` which is used to sent the payload
     /// via the `channel`.
-    pub(crate) fn new(payload: &'a S, channel: Channel, bus: DynBus) -> Self {
+    /// The message span is parented to the `parent_context`, if specified, otherwise to the
+    /// current context.
+    pub(crate) fn new(
+        payload: &'a S,
+        channel: Channel,
+        bus: DynBus,
+        parent_context: Option<Context>,
+    ) -> Self {
         Self {
             payload: SendPayload {
                 preamble: Preamble {
@@ -320,6 +358,7 @@ where
             reply_type: Default::default(),
             bus,
             channel,
+            parent_context,
         }
     }
 
@@ -351,7 +390,7 @@ where
 
         // todo: investigate difference
         //let ctx = tracing::Span::current().context();
-        let ctx = Context::current();
+        let ctx = self.parent_context.clone().unwrap_or_else(Context::current);
         let span = tracer
             .span_builder(format!(
                 "Request {} {}",
@@ -405,11 +444,9 @@ where
         reply.0.context(ReplyWithError {})
     }
 
-    /// Get the default timeout opts for this message
+    /// Get the timeout opts for this message, which may be overridden through the bus options
     fn timeout_opts(&self, options: Option<TimeoutOptions>) -> TimeoutOptions {
         let opts = options.unwrap_or_else(|| self.bus.timeout_opts().clone());
-        match &self.payload.id {
-            MessageId::v0(id) => id.timeout_opts(opts, &self.bus),
-        }
+        self.payload.id.timeout_opts(opts, &self.bus)
     }
 }
@@ -31,16 +31,24 @@ bus_impl_message_all!(Deregister, Deregister, (), Registry);
 
 bus_impl_vector_request!(Nodes, Node);
 bus_impl_message_all!(GetNodes, GetNodes, Nodes, Node);
+bus_impl_message_all!(CordonNode, CordonNode, Node, Node);
+bus_impl_message_all!(UncordonNode, UncordonNode, Node, Node);
+bus_impl_message_all!(DrainNode, DrainNode, Node, Node);
+bus_impl_message_all!(SetNodeTimeouts, SetNodeTimeouts, Node, Node);
 
 bus_impl_message_all!(CreatePool, CreatePool, Pool, Pool);
+bus_impl_message_all!(ImportPool, ImportPool, Pool, Pool);
 
 bus_impl_message_all!(DestroyPool, DestroyPool, (), Pool);
+bus_impl_message_all!(ReconcilePool, ReconcilePool, Pool, Pool);
+bus_impl_message_all!(SetPoolMaintenance, SetPoolMaintenance, Pool, Pool);
 
 bus_impl_vector_request!(Pools, Pool);
 bus_impl_message_all!(GetPools, GetPools, Pools, Pool);
 
-bus_impl_vector_request!(Replicas, Replica);
+bus_impl_vector_request_token!(Replicas, Replica);
 bus_impl_message_all!(GetReplicas, GetReplicas, Replicas, Pool);
+bus_impl_message_all!(GetReplica, GetReplica, Replica, Pool);
 bus_impl_message_all!(CreateReplica, CreateReplica, Replica, Pool);
 
 bus_impl_message_all!(DestroyReplica, DestroyReplica, (), Pool);
@@ -49,7 +57,11 @@ bus_impl_message_all!(ShareReplica, ShareReplica, String, Pool);
 
 bus_impl_message_all!(UnshareReplica, UnshareReplica, (), Pool);
 
-bus_impl_vector_request!(Nexuses, Nexus);
+bus_impl_message_all!(ResizeReplica, ResizeReplica, Replica, Pool);
+
+bus_impl_message_all!(MoveReplica, MoveReplica, Replica, Pool);
+
+bus_impl_vector_request_token!(Nexuses, Nexus);
 bus_impl_message_all!(GetNexuses, GetNexuses, Nexuses, Nexus);
 
 bus_impl_message_all!(CreateNexus, CreateNexus, Nexus, Nexus);
@@ -60,10 +72,20 @@ bus_impl_message_all!(ShareNexus, ShareNexus, String, Nexus);
 
 bus_impl_message_all!(UnshareNexus, UnshareNexus, (), Nexus);
 
+bus_impl_message_all!(ResizeNexus, ResizeNexus, (), Nexus);
+
 bus_impl_message_all!(RemoveNexusChild, RemoveNexusChild, (), Nexus);
 
 bus_impl_message_all!(AddNexusChild, AddNexusChild, Child, Nexus);
 
+bus_impl_message_all!(OnlineNexusChild, OnlineNexusChild, (), Nexus);
+
+bus_impl_message_all!(GetRebuildHistory, GetRebuildHistory, RebuildHistory, Nexus);
+
+bus_impl_message_all!(GetRebuilds, GetRebuilds, Rebuilds, Nexus);
+
+bus_impl_message_all!(CancelRebuild, CancelRebuild, (), Nexus);
+
 bus_impl_vector_request_token!(Volumes, Volume);
 bus_impl_message_all!(GetVolumes, GetVolumes, Volumes, Volume);
 
@@ -75,6 +97,8 @@ bus_impl_message_all!(UnshareVolume, UnshareVolume, (), Volume);
 
 bus_impl_message_all!(PublishVolume, PublishVolume, Volume, Volume);
 
+bus_impl_message_all!(RepublishVolume, RepublishVolume, Volume, Volume);
+
 bus_impl_message_all!(UnpublishVolume, UnpublishVolume, Volume, Volume);
 
 bus_impl_message_all!(DestroyVolume, DestroyVolume, (), Volume);
@@ -83,6 +107,16 @@ bus_impl_message_all!(AddVolumeNexus, AddVolumeNexus, Nexus, Volume);
 bus_impl_message_all!(RemoveVolumeNexus, RemoveVolumeNexus, (), Volume);
 
 bus_impl_message_all!(SetVolumeReplica, SetVolumeReplica, Volume, Volume);
+bus_impl_message_all!(AddVolumeReplica, AddVolumeReplica, Volume, Volume);
+bus_impl_message_all!(RemoveVolumeReplica, RemoveVolumeReplica, Volume, Volume);
+
+bus_impl_message_all!(ExpandVolume, ExpandVolume, Volume, Volume);
+
+bus_impl_message_all!(SetVolumeLabels, SetVolumeLabels, Volume, Volume);
+
+bus_impl_message_all!(CreateSnapshot, CreateSnapshot, VolumeSnapshot, Volume);
+bus_impl_message_all!(ListSnapshots, ListSnapshots, VolumeSnapshots, Volume);
+bus_impl_message_all!(DeleteSnapshot, DeleteSnapshot, (), Volume);
 
 bus_impl_message_all!(JsonGrpcRequest, JsonGrpc, Value, JsonGrpc);
 
@@ -100,3 +134,21 @@ bus_impl_message_all!(DeleteWatch, DeleteWatch, (), Watcher);
 bus_impl_message_all!(GetSpecs, GetSpecs, Specs, Registry);
 
 bus_impl_message_all!(GetStates, GetStates, States, Registry);
+
+bus_impl_message_all!(GetRebuildLimit, GetRebuildLimit, RebuildLimit, Registry);
+
+bus_impl_message_all!(SetRebuildLimit, SetRebuildLimit, RebuildLimit, Registry);
+
+bus_impl_message_all!(
+    CheckOrphanedReplicas,
+    CheckOrphanedReplicas,
+    OrphanedReplicas,
+    Registry
+);
+
+bus_impl_vector_request!(Events, Event);
+bus_impl_message_all!(GetEvents, GetEvents, Events, Registry);
+
+bus_impl_message_all!(GetOperations, GetOperations, PendingOperations, Registry);
+bus_impl_message_all!(ClearOperation, ClearOperation, (), Registry);
+bus_impl_message_all!(ReconcileResource, ReconcileResource, (), Registry);
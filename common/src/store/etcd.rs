@@ -3,25 +3,172 @@ use crate::{
     types::v0::store::{
         definitions::{
             Connect, Delete, DeserialiseValue, Get, GetPrefix, KeyString, ObjectKey, Put,
-            SerialiseValue, StorableObject, Store, StoreError, StoreError::MissingEntry, StoreKey,
-            StoreValue, ValueString, Watch, WatchEvent,
+            SerialiseValue, StorableObject, StorableObjectType, Store, StoreError,
+            StoreError::MissingEntry, StoreKey, StoreValue, ValueString, Watch, WatchEvent,
         },
         registry::ControlPlaneService,
     },
 };
 use async_trait::async_trait;
 use etcd_client::{
-    Client, Compare, CompareOp, EventType, GetOptions, KeyValue, Txn, TxnOp, WatchStream, Watcher,
+    Certificate, Client, Compare, CompareOp, ConnectOptions, EventType, GetOptions, Identity,
+    KeyValue, TlsOptions, Txn, TxnOp, TxnOpResponse, WatchOptions, WatchStream, Watcher,
 };
 use serde_json::Value;
 use snafu::ResultExt;
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
+/// Status of the etcd lease of a leased client
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LeaseStatus {
+    /// the lease id, or the last one if the lease is not currently held
+    pub lease_id: i64,
+    /// the remaining time to live of the lease
+    pub remaining_ttl: std::time::Duration,
+    /// whether the lease and its lock are currently held
+    pub held: bool,
+}
+
+/// Retry policy for the etcd operations which fail with transient connection errors
+#[derive(Debug, Clone, Copy)]
+pub struct StoreRetryPolicy {
+    /// maximum number of times an operation is retried
+    retries: u32,
+    /// backoff before the first retry, doubled on every subsequent retry
+    backoff: Duration,
+}
+
+impl Default for StoreRetryPolicy {
+    fn default() -> Self {
+        Self::new(3, Duration::from_millis(100))
+    }
+}
+
+impl StoreRetryPolicy {
+    /// Return new `Self` which retries up to `retries` times, starting with a `backoff`
+    pub fn new(retries: u32, backoff: Duration) -> Self {
+        Self { retries, backoff }
+    }
+    /// Get the backoff before the retry following the given `attempt`
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff * 2u32.saturating_pow(attempt)
+    }
+}
+
+/// Run the `operation`, retrying it as per the `policy` while it fails with transient errors.
+/// The `operation` is given the number of the attempt, starting at 0.
+async fn with_retries<T, F, Fut>(
+    policy: &StoreRetryPolicy,
+    mut operation: F,
+) -> Result<T, StoreError>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T, StoreError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation(attempt).await {
+            Err(error) if error.transient() && attempt < policy.retries => {
+                let backoff = policy.backoff(attempt);
+                tracing::warn!(
+                    error = %error,
+                    attempt,
+                    "Retrying the etcd operation in {:?}",
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Options for the etcd client connections
+#[derive(Debug, Clone, Default)]
+pub struct StoreConnectOptions {
+    /// path to the PEM encoded CA certificate used to verify the etcd server
+    ca_cert: Option<PathBuf>,
+    /// paths to the PEM encoded client certificate and key used to authenticate with etcd
+    client_identity: Option<(PathBuf, PathBuf)>,
+    /// retry policy for the operations which fail with transient connection errors
+    retry_policy: StoreRetryPolicy,
+}
+
+impl StoreConnectOptions {
+    /// Return new `Self` from the provided TLS file paths.
+    /// Fails if only one of the client certificate and key is provided.
+    pub fn new(
+        ca_cert: Option<PathBuf>,
+        client_cert: Option<PathBuf>,
+        client_key: Option<PathBuf>,
+    ) -> Result<Self, StoreError> {
+        let client_identity = match (client_cert, client_key) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            (None, None) => None,
+            _ => {
+                return Err(StoreError::InvalidTls {
+                    reason: "both the client certificate and key must be provided".to_string(),
+                })
+            }
+        };
+        Ok(Self {
+            ca_cert,
+            client_identity,
+            retry_policy: Default::default(),
+        })
+    }
+    /// Use the given `StoreRetryPolicy` for the operations
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: StoreRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    /// Check if TLS is configured
+    pub fn tls(&self) -> bool {
+        self.ca_cert.is_some() || self.client_identity.is_some()
+    }
+    /// Get the etcd `ConnectOptions`, loading the TLS files
+    fn connect_options(&self) -> Result<Option<ConnectOptions>, StoreError> {
+        if !self.tls() {
+            return Ok(None);
+        }
+        let mut tls = TlsOptions::new();
+        if let Some(ca_cert) = &self.ca_cert {
+            tls = tls.ca_certificate(Certificate::from_pem(read_tls_file(ca_cert)?));
+        }
+        if let Some((cert, key)) = &self.client_identity {
+            tls = tls.identity(Identity::from_pem(
+                read_tls_file(cert)?,
+                read_tls_file(key)?,
+            ));
+        }
+        Ok(Some(ConnectOptions::new().with_tls(tls)))
+    }
+}
+
+/// Read the TLS file at `path`.
+fn read_tls_file(path: &Path) -> Result<Vec<u8>, StoreError> {
+    std::fs::read(path).map_err(|error| StoreError::InvalidTls {
+        reason: format!("failed to read '{}': {}", path.display(), error),
+    })
+}
+
 /// etcd client
 #[derive(Clone)]
 pub struct Etcd {
-    client: Client,
+    /// the client, shared by all the clones so they all benefit from a reconnect
+    client: Arc<parking_lot::RwLock<Client>>,
     lease_lock_info: Option<LeaseLockInfo>,
+    /// the endpoints to reconnect to, if known
+    endpoints: Vec<String>,
+    options: StoreConnectOptions,
 }
 
 impl std::fmt::Debug for Etcd {
@@ -33,31 +180,54 @@ impl std::fmt::Debug for Etcd {
 impl Etcd {
     /// Create a new instance of the etcd client
     pub async fn new(endpoint: &str) -> Result<Etcd, StoreError> {
+        Self::new_with_options(endpoint, &StoreConnectOptions::default()).await
+    }
+    /// Create a new instance of the etcd client with the given connection options
+    pub async fn new_with_options(
+        endpoint: &str,
+        options: &StoreConnectOptions,
+    ) -> Result<Etcd, StoreError> {
         let _ = crate::platform::init_cluster_info()
             .await
             .map_err(|error| StoreError::NotReady {
                 reason: format!("Platform not ready: {}", error),
             })?;
-        Ok(Self::from(
-            &Client::connect([endpoint], None)
-                .await
-                .context(Connect {})?,
-            None,
-        ))
+        let client = Client::connect([endpoint], options.connect_options()?)
+            .await
+            .context(Connect {})?;
+        Ok(Self::from(&client, None).with_reconnect([endpoint], options))
     }
     /// Create `Etcd` from an existing instance of the etcd `Client`
     pub(crate) fn from(client: &Client, lease_lock_info: Option<LeaseLockInfo>) -> Etcd {
         Etcd {
-            client: client.clone(),
+            client: Arc::new(parking_lot::RwLock::new(client.clone())),
             lease_lock_info,
+            endpoints: vec![],
+            options: StoreConnectOptions::default(),
         }
     }
+    /// Reconnect to the `endpoints` with the `options` when an operation fails with a
+    /// transient error
+    fn with_reconnect<E: AsRef<str>, S: AsRef<[E]>>(
+        mut self,
+        endpoints: S,
+        options: &StoreConnectOptions,
+    ) -> Self {
+        self.endpoints = endpoints
+            .as_ref()
+            .iter()
+            .map(|e| e.as_ref().to_string())
+            .collect();
+        self.options = options.clone();
+        self
+    }
     /// Create a new instance of the etcd client with a lease associated with `service_name`.
     /// See `EtcdLeaseLockKeeper` for more information.
     pub async fn new_leased<E: AsRef<str>, S: AsRef<[E]>>(
         endpoints: S,
         service_name: ControlPlaneService,
         lease_time: std::time::Duration,
+        options: &StoreConnectOptions,
     ) -> Result<Etcd, StoreError> {
         let _ = crate::platform::init_cluster_info()
             .await
@@ -65,10 +235,53 @@ impl Etcd {
                 reason: format!("Platform not ready: {}", error),
             })?;
 
-        let client = Client::connect(endpoints, None).await.context(Connect {})?;
+        let client = Client::connect(endpoints.as_ref(), options.connect_options()?)
+            .await
+            .context(Connect {})?;
 
         let lease_info = EtcdSingletonLock::start(client.clone(), service_name, lease_time).await?;
-        Ok(Self::from(&client, Some(lease_info)))
+        Ok(Self::from(&client, Some(lease_info)).with_reconnect(endpoints, options))
+    }
+
+    /// Get a clone of the current etcd `Client`
+    fn client(&self) -> Client {
+        self.client.read().clone()
+    }
+
+    /// Replace the shared etcd `Client` with a newly connected one, if the endpoints are known
+    async fn reconnect(&self) {
+        if self.endpoints.is_empty() {
+            return;
+        }
+        let connect_options = match self.options.connect_options() {
+            Ok(connect_options) => connect_options,
+            Err(error) => {
+                tracing::error!(error = %error, "Failed to reconnect to etcd");
+                return;
+            }
+        };
+        match Client::connect(&self.endpoints, connect_options).await {
+            Ok(client) => *self.client.write() = client,
+            Err(error) => tracing::warn!(error = %error, "Failed to reconnect to etcd"),
+        }
+    }
+
+    /// Run the `operation` with the current etcd `Client`, retrying it as per the retry policy
+    /// while it fails with transient errors, reconnecting to etcd before each retry.
+    async fn retry<T, F, Fut>(&self, operation: F) -> Result<T, StoreError>
+    where
+        F: Fn(Client) -> Fut + Send + Sync,
+        Fut: Future<Output = Result<T, StoreError>> + Send,
+        T: Send,
+    {
+        let operation = &operation;
+        with_retries(&self.options.retry_policy, |attempt| async move {
+            if attempt > 0 {
+                self.reconnect().await;
+            }
+            operation(self.client()).await
+        })
+        .await
     }
 
     /// Get the lease lock pair, (lease_id, lock_key)
@@ -80,14 +293,117 @@ impl Etcd {
         }
     }
 
+    /// Get the status of the lease associated with the client, if any
+    pub fn lease_status(&self) -> Option<LeaseStatus> {
+        self.lease_lock_info
+            .as_ref()
+            .map(|info| info.lease_status())
+    }
+
     /// Revokes the lease and releases the associated lock
     pub async fn revoke(&self) {
         if let Some(info) = &self.lease_lock_info {
             info.revoke().await;
         }
     }
+
+    /// Dump all the control-plane entries, ie all the keys under `etcd_key_prefix()`, so they
+    /// can later be written back with `restore`, possibly into a different etcd cluster.
+    /// The values are dumped as raw bytes so that entries which cannot be parsed are still
+    /// backed up as they are.
+    /// The lease lock and lease owner entries are skipped as they are bound to a lease of the
+    /// source cluster.
+    pub async fn dump(&mut self) -> Result<Vec<(String, Vec<u8>)>, StoreError> {
+        // the trailing '/' keeps the keys of a sibling prefix out of the range, eg: `/cp2` for `/cp`
+        let prefix = &format!("{}/", crate::etcd_key_prefix());
+        // the lease keys are prefixed by their object type, within the cluster key prefix
+        let lease_prefixes = [
+            format!("/{}/", StorableObjectType::StoreLeaseLock),
+            format!("/{}/", StorableObjectType::StoreLeaseOwner),
+        ];
+        let mut entries = vec![];
+        let mut start_key = prefix.clone();
+        loop {
+            let page_start = &start_key;
+            let resp = self
+                .retry(|mut client| async move {
+                    let options = GetOptions::new()
+                        .with_range(prefix_range_end(prefix))
+                        .with_limit(DUMP_PAGE_SIZE);
+                    client
+                        .get(page_start.as_str(), Some(options))
+                        .await
+                        .context(GetPrefix { prefix })
+                })
+                .await?;
+            let mut last_key = None;
+            for kv in resp.kvs() {
+                let key = kv.key_str().context(KeyString {})?;
+                last_key = Some(key.to_string());
+                if lease_prefixes
+                    .iter()
+                    .any(|lease| key.contains(lease.as_str()))
+                {
+                    continue;
+                }
+                entries.push((key.to_string(), kv.value().to_vec()));
+            }
+            // the next page starts at the smallest key which is greater than the last returned key
+            match last_key {
+                Some(key) if resp.more() => start_key = format!("{}\0", key),
+                _ => return Ok(entries),
+            }
+        }
+    }
+
+    /// Restore the raw entries of a previous `dump`.
+    /// Each entry is written under the lease lock, if any, so the restore fails if the lease
+    /// is lost midway.
+    pub async fn restore(&mut self, entries: Vec<(String, Vec<u8>)>) -> Result<(), StoreError> {
+        for (key, value) in entries {
+            self.put_raw(&key, value).await?;
+        }
+        Ok(())
+    }
+
+    /// 'Put' a key with a raw value into etcd, under the lease lock if any.
+    async fn put_raw(&self, key: &str, value: Vec<u8>) -> Result<(), StoreError> {
+        let value = &value;
+        self.retry(|mut client| async move {
+            let context = || Put {
+                key: key.to_string(),
+                value: String::from_utf8_lossy(value).to_string(),
+            };
+            if let Some((lease_id, lock_key)) = self.lease_lock()? {
+                let cmp = Compare::lease(lock_key.clone(), CompareOp::Equal, lease_id);
+                let put = TxnOp::put(key, value.clone(), None);
+                let resp = client
+                    .txn(Txn::new().when([cmp]).and_then([put]))
+                    .await
+                    .with_context(context)?;
+                if !resp.succeeded() {
+                    return Err(StoreError::FailedLock {
+                        reason: format!(
+                            "Etcd Txn Compare key '{}' to lease id '{:x}' failed",
+                            lock_key, lease_id
+                        ),
+                    });
+                }
+            } else {
+                client
+                    .put(key, value.clone(), None)
+                    .await
+                    .with_context(context)?;
+            }
+            Ok(())
+        })
+        .await
+    }
 }
 
+/// Number of entries read in each page of a `dump`.
+const DUMP_PAGE_SIZE: i64 = 500;
+
 #[async_trait]
 impl Store for Etcd {
     /// 'Put' a key-value pair into etcd.
@@ -96,86 +412,93 @@ impl Store for Etcd {
         key: &K,
         value: &V,
     ) -> Result<(), StoreError> {
-        let vec_value = serde_json::to_vec(value).context(SerialiseValue)?;
-        if let Some((lease_id, lock_key)) = self.lease_lock()? {
-            let cmp = Compare::lease(lock_key.clone(), CompareOp::Equal, lease_id);
-            let put = TxnOp::put(key.to_string(), vec_value, None);
-            let resp = self
-                .client
-                .txn(Txn::new().when([cmp]).and_then([put]))
-                .await
-                .context(Put {
-                    key: key.to_string(),
-                    value: serde_json::to_string(value).context(SerialiseValue)?,
-                })?;
-            if !resp.succeeded() {
-                return Err(StoreError::FailedLock {
-                    reason: format!(
-                        "Etcd Txn Compare key '{}' to lease id '{:x}' failed",
-                        lock_key, lease_id
-                    ),
-                });
-            }
-        } else {
-            self.client
-                .put(key.to_string(), vec_value, None)
-                .await
-                .context(Put {
-                    key: key.to_string(),
-                    value: serde_json::to_string(value).context(SerialiseValue)?,
-                })?;
-        };
+        let this = &*self;
+        this.retry(|mut client| async move {
+            let vec_value = serde_json::to_vec(value).context(SerialiseValue)?;
+            if let Some((lease_id, lock_key)) = this.lease_lock()? {
+                let cmp = Compare::lease(lock_key.clone(), CompareOp::Equal, lease_id);
+                let put = TxnOp::put(key.to_string(), vec_value, None);
+                let resp = client
+                    .txn(Txn::new().when([cmp]).and_then([put]))
+                    .await
+                    .context(Put {
+                        key: key.to_string(),
+                        value: serde_json::to_string(value).context(SerialiseValue)?,
+                    })?;
+                if !resp.succeeded() {
+                    return Err(StoreError::FailedLock {
+                        reason: format!(
+                            "Etcd Txn Compare key '{}' to lease id '{:x}' failed",
+                            lock_key, lease_id
+                        ),
+                    });
+                }
+            } else {
+                client
+                    .put(key.to_string(), vec_value, None)
+                    .await
+                    .context(Put {
+                        key: key.to_string(),
+                        value: serde_json::to_string(value).context(SerialiseValue)?,
+                    })?;
+            };
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     /// 'Get' the value for the given key from etcd.
     async fn get_kv<K: StoreKey>(&mut self, key: &K) -> Result<Value, StoreError> {
-        let resp = self.client.get(key.to_string(), None).await.context(Get {
-            key: key.to_string(),
-        })?;
-        match resp.kvs().first() {
-            Some(kv) => Ok(
-                serde_json::from_slice(kv.value()).context(DeserialiseValue {
-                    value: kv.value_str().context(ValueString {})?,
-                })?,
-            ),
-            None => Err(MissingEntry {
+        let this = &*self;
+        this.retry(|mut client| async move {
+            let resp = client.get(key.to_string(), None).await.context(Get {
                 key: key.to_string(),
-            }),
-        }
+            })?;
+            match resp.kvs().first() {
+                Some(kv) => Ok(
+                    serde_json::from_slice(kv.value()).context(DeserialiseValue {
+                        value: kv.value_str().context(ValueString {})?,
+                    })?,
+                ),
+                None => Err(MissingEntry {
+                    key: key.to_string(),
+                }),
+            }
+        })
+        .await
     }
 
     /// 'Delete' the entry with the given key from etcd.
     async fn delete_kv<K: StoreKey>(&mut self, key: &K) -> Result<(), StoreError> {
-        if let Some((lease_id, lock_key)) = self.lease_lock()? {
-            let cmp = Compare::lease(lock_key.clone(), CompareOp::Equal, lease_id);
-            let del = TxnOp::delete(key.to_string(), None);
-            let resp = self
-                .client
-                .txn(Txn::new().when([cmp]).and_then([del]))
-                .await
-                .context(Delete {
-                    key: key.to_string(),
-                })?;
-            if !resp.succeeded() {
-                return Err(StoreError::FailedLock {
-                    reason: format!(
-                        "Etcd Txn Compare key '{}' to lease id '{:x}' failed",
-                        lock_key, lease_id
-                    ),
-                });
-            }
-        } else {
-            self.client
-                .delete(key.to_string(), None)
-                .await
-                .context(Delete {
+        let this = &*self;
+        this.retry(|mut client| async move {
+            if let Some((lease_id, lock_key)) = this.lease_lock()? {
+                let cmp = Compare::lease(lock_key.clone(), CompareOp::Equal, lease_id);
+                let del = TxnOp::delete(key.to_string(), None);
+                let resp = client
+                    .txn(Txn::new().when([cmp]).and_then([del]))
+                    .await
+                    .context(Delete {
+                        key: key.to_string(),
+                    })?;
+                if !resp.succeeded() {
+                    return Err(StoreError::FailedLock {
+                        reason: format!(
+                            "Etcd Txn Compare key '{}' to lease id '{:x}' failed",
+                            lock_key, lease_id
+                        ),
+                    });
+                }
+            } else {
+                client.delete(key.to_string(), None).await.context(Delete {
                     key: key.to_string(),
                 })?;
-        };
+            };
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     /// 'Watch' the etcd entry with the given key.
@@ -186,33 +509,100 @@ impl Store for Etcd {
         key: &K,
     ) -> Result<Receiver<Result<WatchEvent, StoreError>>, StoreError> {
         let (sender, receiver) = channel(100);
-        let (watcher, stream) = self
-            .client
-            .watch(key.to_string(), None)
-            .await
-            .context(Watch {
-                key: key.to_string(),
-            })?;
-        watch(watcher, stream, sender);
+        let mut client = self.client();
+        let (watcher, stream) = client.watch(key.to_string(), None).await.context(Watch {
+            key: key.to_string(),
+        })?;
+        watch(watcher, stream, sender, WatchMode::Key);
         Ok(receiver)
     }
 
     async fn put_obj<O: StorableObject>(&mut self, object: &O) -> Result<(), StoreError> {
-        let key = object.key().key();
-        let vec_value = serde_json::to_vec(object).context(SerialiseValue)?;
+        let this = &*self;
+        this.retry(|mut client| async move {
+            let key = object.key().key();
+            let vec_value = serde_json::to_vec(object).context(SerialiseValue)?;
 
-        if let Some((lease_id, lock_key)) = self.lease_lock()? {
-            let cmp = Compare::lease(lock_key.clone(), CompareOp::Equal, lease_id);
-            let put = TxnOp::put(key.to_string(), vec_value, None);
-            let resp = self
-                .client
-                .txn(Txn::new().when([cmp]).and_then([put]))
-                .await
-                .context(Put {
+            if let Some((lease_id, lock_key)) = this.lease_lock()? {
+                let cmp = Compare::lease(lock_key.clone(), CompareOp::Equal, lease_id);
+                let put = TxnOp::put(key.to_string(), vec_value, None);
+                let resp = client
+                    .txn(Txn::new().when([cmp]).and_then([put]))
+                    .await
+                    .context(Put {
+                        key: object.key().key(),
+                        value: serde_json::to_string(object).context(SerialiseValue)?,
+                    })?;
+                if !resp.succeeded() {
+                    return Err(StoreError::FailedLock {
+                        reason: format!(
+                            "Etcd Txn Compare key '{}' to lease id '{:x}' failed",
+                            lock_key, lease_id
+                        ),
+                    });
+                }
+            } else {
+                client.put(key, vec_value, None).await.context(Put {
                     key: object.key().key(),
                     value: serde_json::to_string(object).context(SerialiseValue)?,
                 })?;
-            if !resp.succeeded() {
+            };
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// 'Put' the object into etcd only if its mod revision matches the expected revision.
+    /// The comparison is made within the same `Txn` as the lease lock comparison, if any.
+    async fn put_obj_cas<O: StorableObject>(
+        &mut self,
+        object: &O,
+        expected_revision: Option<i64>,
+    ) -> Result<i64, StoreError> {
+        let key = object.key().key();
+        let vec_value = serde_json::to_vec(object).context(SerialiseValue)?;
+        let lease_lock = self.lease_lock()?;
+
+        // an entry which does not exist has a mod revision of 0
+        let mut cmps = vec![Compare::mod_revision(
+            key.clone(),
+            CompareOp::Equal,
+            expected_revision.unwrap_or_default(),
+        )];
+        // on failure, fetch the entry and the lock to figure out which comparison failed
+        let mut gets = vec![TxnOp::get(key.clone(), None)];
+        if let Some((lease_id, lock_key)) = &lease_lock {
+            cmps.push(Compare::lease(
+                lock_key.clone(),
+                CompareOp::Equal,
+                *lease_id,
+            ));
+            gets.push(TxnOp::get(lock_key.clone(), None));
+        }
+        let put = TxnOp::put(key.clone(), vec_value, None);
+
+        let resp = self
+            .client()
+            .txn(Txn::new().when(cmps).and_then([put]).or_else(gets))
+            .await
+            .context(Put {
+                key: key.clone(),
+                value: serde_json::to_string(object).context(SerialiseValue)?,
+            })?;
+        if resp.succeeded() {
+            return Ok(resp.header().map(|h| h.revision()).unwrap_or_default());
+        }
+
+        // (mod_revision, lease) of each of the fetched entries
+        let mut responses = resp.op_responses().into_iter().map(|op| match op {
+            TxnOpResponse::Get(get) => get.kvs().first().map(|kv| (kv.mod_revision(), kv.lease())),
+            _ => None,
+        });
+        let actual = responses.next().flatten().map(|(revision, _)| revision);
+        if let Some((lease_id, lock_key)) = lease_lock {
+            let lock_lease = responses.next().flatten().map(|(_, lease)| lease);
+            if lock_lease != Some(lease_id) {
                 return Err(StoreError::FailedLock {
                     reason: format!(
                         "Etcd Txn Compare key '{}' to lease id '{:x}' failed",
@@ -220,30 +610,31 @@ impl Store for Etcd {
                     ),
                 });
             }
-        } else {
-            self.client.put(key, vec_value, None).await.context(Put {
-                key: object.key().key(),
-                value: serde_json::to_string(object).context(SerialiseValue)?,
-            })?;
-        };
-
-        Ok(())
+        }
+        Err(StoreError::RevisionMismatch {
+            key,
+            expected: expected_revision,
+            actual,
+        })
     }
 
     async fn get_obj<O: StorableObject>(&mut self, key: &O::Key) -> Result<O, StoreError> {
-        let resp = self
-            .client
-            .get(key.key(), None)
-            .await
-            .context(Get { key: key.key() })?;
-        match resp.kvs().first() {
-            Some(kv) => Ok(
-                serde_json::from_slice(kv.value()).context(DeserialiseValue {
-                    value: kv.value_str().context(ValueString {})?,
-                })?,
-            ),
-            None => Err(MissingEntry { key: key.key() }),
-        }
+        let this = &*self;
+        this.retry(|mut client| async move {
+            let resp = client
+                .get(key.key(), None)
+                .await
+                .context(Get { key: key.key() })?;
+            match resp.kvs().first() {
+                Some(kv) => Ok(
+                    serde_json::from_slice(kv.value()).context(DeserialiseValue {
+                        value: kv.value_str().context(ValueString {})?,
+                    })?,
+                ),
+                None => Err(MissingEntry { key: key.key() }),
+            }
+        })
+        .await
     }
 
     /// Retrieve objects with the given key prefix
@@ -251,11 +642,40 @@ impl Store for Etcd {
         &mut self,
         key_prefix: &str,
     ) -> Result<Vec<(String, Value)>, StoreError> {
+        let mut result = vec![];
+        let mut start_key = None;
+        loop {
+            let (mut page, next_key) = self
+                .get_values_prefix_paged(key_prefix, 0, start_key)
+                .await?;
+            result.append(&mut page);
+            match next_key {
+                Some(next_key) => start_key = Some(next_key),
+                None => return Ok(result),
+            }
+        }
+    }
+
+    /// Retrieve a page of objects with the given key prefix.
+    /// A `limit` of 0 means no limit.
+    async fn get_values_prefix_paged(
+        &mut self,
+        key_prefix: &str,
+        limit: i64,
+        start_key: Option<String>,
+    ) -> Result<(Vec<(String, Value)>, Option<String>), StoreError> {
+        let start_key = &start_key.unwrap_or_else(|| key_prefix.to_string());
         let resp = self
-            .client
-            .get(key_prefix, Some(GetOptions::new().with_prefix()))
-            .await
-            .context(GetPrefix { prefix: key_prefix })?;
+            .retry(|mut client| async move {
+                let options = GetOptions::new()
+                    .with_range(prefix_range_end(key_prefix))
+                    .with_limit(limit);
+                client
+                    .get(start_key.as_str(), Some(options))
+                    .await
+                    .context(GetPrefix { prefix: key_prefix })
+            })
+            .await?;
         let result = resp
             .kvs()
             .iter()
@@ -267,8 +687,13 @@ impl Store for Etcd {
                     serde_json::from_slice(kv.value()).unwrap_or_default(),
                 )
             })
-            .collect();
-        Ok(result)
+            .collect::<Vec<_>>();
+        // the next page starts at the smallest key which is greater than the last returned key
+        let next_key = match result.last() {
+            Some((key, _)) if resp.more() => Some(format!("{}\0", key)),
+            _ => None,
+        };
+        Ok((result, next_key))
     }
 
     async fn watch_obj<K: ObjectKey>(
@@ -277,31 +702,58 @@ impl Store for Etcd {
     ) -> Result<Receiver<Result<WatchEvent, StoreError>>, StoreError> {
         let (sender, receiver) = channel(100);
         let (watcher, stream) = self
-            .client
+            .client()
             .watch(key.key(), None)
             .await
             .context(Watch { key: key.key() })?;
-        watch(watcher, stream, sender);
+        watch(watcher, stream, sender, WatchMode::Key);
+        Ok(receiver)
+    }
+
+    async fn watch_prefix(
+        &mut self,
+        key_prefix: &str,
+    ) -> Result<Receiver<Result<WatchEvent, StoreError>>, StoreError> {
+        let (sender, receiver) = channel(100);
+        let (watcher, stream) = self
+            .client()
+            .watch(key_prefix, Some(WatchOptions::new().with_prefix()))
+            .await
+            .context(Watch {
+                key: key_prefix.to_string(),
+            })?;
+        watch(watcher, stream, sender, WatchMode::Prefix);
         Ok(receiver)
     }
 
     async fn online(&mut self) -> bool {
-        self.client.status().await.is_ok()
+        self.client().status().await.is_ok()
     }
 }
 
+/// What is being watched by a watcher.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum WatchMode {
+    /// A single key, which stops being watched once it's deleted.
+    Key,
+    /// All keys with a given prefix.
+    Prefix,
+}
+
 /// Watch for events in the key-value store.
 /// When an event occurs, a WatchEvent is sent over the channel.
-/// When a 'delete' event is received, the watcher stops watching.
+/// When a 'delete' event is received for a single key watch, the watcher stops watching.
+/// A prefix watch multiplexes the events of all the matching keys on the same watcher and
+/// channel, and it keeps on watching until the receiver is closed.
 fn watch(
-    _watcher: Watcher,
+    watcher: Watcher,
     mut stream: WatchStream,
     sender: Sender<Result<WatchEvent, StoreError>>,
+    mode: WatchMode,
 ) {
-    // For now we spawn a thread for each value that is watched.
-    // If we find that we are watching lots of events, this can be optimised.
-    // TODO: Optimise the spawning of threads if required.
     tokio::spawn(async move {
+        // keep the watcher alive for as long as we're watching
+        let _watcher = watcher;
         loop {
             let response = match stream.message().await {
                 Ok(msg) => {
@@ -320,26 +772,31 @@ fn watch(
             };
 
             for event in response.events() {
+                let kv = match event.kv() {
+                    Some(kv) => kv,
+                    None => continue,
+                };
                 match event.event_type() {
                     EventType::Put => {
-                        if let Some(kv) = event.kv() {
-                            let result = match deserialise_kv(kv) {
-                                Ok((key, value)) => Ok(WatchEvent::Put(key, value)),
-                                Err(e) => Err(e),
-                            };
-                            if sender.send(result).await.is_err() {
-                                // Send only fails if the receiver is closed, so
-                                // just stop watching.
-                                return;
-                            }
+                        let result = match deserialise_kv(kv) {
+                            Ok((key, value)) => Ok(WatchEvent::Put(key, value)),
+                            Err(e) => Err(e),
+                        };
+                        if sender.send(result).await.is_err() {
+                            // Send only fails if the receiver is closed, so
+                            // just stop watching.
+                            return;
                         }
                     }
                     EventType::Delete => {
-                        // Send only fails if the receiver is closed. We are
-                        // returning here anyway, so the error doesn't need to
-                        // be handled.
-                        let _ = sender.send(Ok(WatchEvent::Delete)).await;
-                        return;
+                        let result = kv
+                            .key_str()
+                            .context(KeyString {})
+                            .map(|key| WatchEvent::Delete(key.to_string()));
+                        // Send only fails if the receiver is closed, so just stop watching.
+                        if sender.send(result).await.is_err() || mode == WatchMode::Key {
+                            return;
+                        }
                     }
                 }
             }
@@ -347,6 +804,19 @@ fn watch(
     });
 }
 
+/// Returns the end of the key range which covers all the keys with the given prefix.
+fn prefix_range_end(key_prefix: &str) -> Vec<u8> {
+    let mut end = key_prefix.as_bytes().to_vec();
+    while let Some(last) = end.pop() {
+        if last < 0xff {
+            end.push(last + 1);
+            return end;
+        }
+    }
+    // the prefix is empty or made of 0xff only, so the range covers all the keys
+    vec![0]
+}
+
 /// Deserialise a key-value pair into serde_json::Value representations.
 fn deserialise_kv(kv: &KeyValue) -> Result<(String, Value), StoreError> {
     let key_str = kv.key_str().context(KeyString {})?.to_string();
@@ -362,3 +832,55 @@ fn deserialise_kv(kv: &KeyValue) -> Result<(String, Value), StoreError> {
 pub fn build_key_prefix(platform: impl crate::platform::PlatformInfo, namespace: String) -> String {
     crate::types::v0::store::definitions::build_key_prefix(&platform, namespace)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn unavailable() -> StoreError {
+        StoreError::Get {
+            key: "key".to_string(),
+            source: etcd_client::Error::GRpcStatus(tonic::Status::unavailable("no leader")),
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_transient_errors() {
+        let policy = StoreRetryPolicy::new(2, Duration::from_millis(1));
+        let attempts = &AtomicU32::new(0);
+
+        // a flaky operation which only succeeds on its second attempt
+        let result = with_retries(&policy, |_| async move {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(unavailable()),
+                _ => Ok(42),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        // the number of retries is bounded
+        attempts.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = with_retries(&policy, |_| async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(unavailable())
+        })
+        .await;
+        assert!(result.unwrap_err().transient());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // errors which are not transient are not retried
+        attempts.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = with_retries(&policy, |_| async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(StoreError::MissingEntry {
+                key: "key".to_string(),
+            })
+        })
+        .await;
+        assert!(matches!(result, Err(StoreError::MissingEntry { .. })));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,10 +1,15 @@
-use super::etcd::Etcd;
+use super::etcd::{Etcd, LeaseStatus};
 use crate::types::v0::store::{
     definitions::{ObjectKey, Store, StoreError},
     registry::{ControlPlaneService, StoreLeaseLockKey, StoreLeaseOwner, StoreLeaseOwnerKey},
 };
 use etcd_client::{Client, LeaseGrantOptions, LeaseKeepAliveStream, LeaseKeeper, LockOptions};
-use std::{cmp::max, ops::Deref, sync::Arc, time::Duration};
+use std::{
+    cmp::max,
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// Worker that keeps an etcd lease lock alive by sending keep alives
 /// It removes the lease from `LeaseLockInfo` when it expires and adds it back once it
@@ -32,6 +37,20 @@ impl LeaseLockInfo {
             }),
         }
     }
+    /// Get the current status of the lease
+    pub(crate) fn lease_status(&self) -> LeaseStatus {
+        let info = self.0.lock();
+        let remaining = Duration::from_secs(max(info.renewed_ttl, 0) as u64)
+            .saturating_sub(info.renewed_at.elapsed());
+        LeaseStatus {
+            lease_id: info.lease_id.unwrap_or(info.last_lease_id),
+            remaining_ttl: match info.lease_id {
+                Some(_) => remaining,
+                None => Duration::ZERO,
+            },
+            held: info.lease_id.is_some(),
+        }
+    }
     /// Revokes the lease and releases the associated lock
     pub(crate) async fn revoke(&self) {
         let info = self.lease_info_inner();
@@ -48,12 +67,21 @@ impl LeaseLockInfo {
     fn set_lease(&self, lease_id: Option<i64>) {
         let mut lease_info = self.0.lock();
         lease_info.lease_id = lease_id;
+        if let Some(lease_id) = lease_id {
+            lease_info.last_lease_id = lease_id;
+        }
+    }
+    /// Record that the lease was renewed with the given `ttl` (in seconds).
+    fn set_renewed(&self, ttl: i64) {
+        let mut lease_info = self.0.lock();
+        lease_info.renewed_at = Instant::now();
+        lease_info.renewed_ttl = ttl;
     }
 
-    /// New `Self` with the provided `lease_id` and `lock_key`
-    fn new(lease_id: i64, lock_key: &str, client: &Client) -> Self {
+    /// New `Self` with the provided `lease_id`, its `ttl` (in seconds) and `lock_key`
+    fn new(lease_id: i64, ttl: i64, lock_key: &str, client: &Client) -> Self {
         Self(Arc::new(parking_lot::Mutex::new(LeaseLockInfoInner::new(
-            lease_id, lock_key, client,
+            lease_id, ttl, lock_key, client,
         ))))
     }
     fn lease_info_inner(&self) -> LeaseLockInfoInner {
@@ -65,15 +93,24 @@ impl LeaseLockInfo {
 struct LeaseLockInfoInner {
     /// The etcd lease id, if active. Otherwise we cannot issue requests until we've renewed it.
     lease_id: Option<i64>,
+    /// The last active etcd lease id
+    last_lease_id: i64,
+    /// When the lease was last granted or kept alive
+    renewed_at: Instant,
+    /// The ttl (in seconds) of the lease when it was last granted or kept alive
+    renewed_ttl: i64,
     /// The key value for the etcd lock
     lock_key: String,
     /// etcd client
     client: Client,
 }
 impl LeaseLockInfoInner {
-    fn new(lease_id: i64, lock_key: &str, client: &Client) -> Self {
+    fn new(lease_id: i64, ttl: i64, lock_key: &str, client: &Client) -> Self {
         Self {
             lease_id: Some(lease_id),
+            last_lease_id: lease_id,
+            renewed_at: Instant::now(),
+            renewed_ttl: ttl,
             lock_key: lock_key.to_string(),
             client: client.clone(),
         }
@@ -152,7 +189,7 @@ impl EtcdSingletonLock {
             })?;
         let lease_id = lease_resp.id();
 
-        let lease_info = LeaseLockInfo::new(lease_id, &lock_key, &client);
+        let lease_info = LeaseLockInfo::new(lease_id, lease_resp.ttl(), &lock_key, &client);
 
         let mut keeper = Self {
             client,
@@ -252,7 +289,12 @@ impl EtcdSingletonLock {
     async fn set_owner_lease(&mut self, lease_id: i64, lock_key: &str) -> Result<(), StoreError> {
         Etcd::from(
             &self.client,
-            Some(LeaseLockInfo::new(lease_id, lock_key, &self.client)),
+            Some(LeaseLockInfo::new(
+                lease_id,
+                *self.lease_ttl(),
+                lock_key,
+                &self.client,
+            )),
         )
         .put_obj(&StoreLeaseOwner::new(&self.service_name, self.lease_id))
         .await
@@ -529,6 +571,7 @@ impl LeaseLockKeeperClocking<KeepAlive> for EtcdSingletonLock {
                 // was not connectable for the TTL... try again
                 return Ok(LeaseKeeperState::LeaseGrant(LeaseGrant {}));
             } else {
+                self.lease_info.set_renewed(resp.ttl());
                 sleep = Duration::from_secs(resp.ttl() as u64 / 2);
             }
         }
@@ -96,7 +96,7 @@ async fn etcd() {
         .recv_timeout(Duration::from_secs(1))
         .expect("Timed out waiting for message");
     match msg {
-        WatchEvent::Delete => {
+        WatchEvent::Delete(_) => {
             // The entry is deleted. Let's check that a subsequent 'get' fails.
             store
                 .get_kv(&key)
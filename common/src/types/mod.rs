@@ -1,5 +1,5 @@
 use crate::{
-    mbus_api::{ReplyError, ReplyErrorKind},
+    mbus_api::{validation_details, ReplyError, ReplyErrorKind},
     types::v0::{
         message_bus::ChannelVs,
         openapi::{
@@ -50,6 +50,16 @@ impl Default for Channel {
     }
 }
 
+/// Validation errors carry their details in the `field=<field>; reason=<reason>` format, any
+/// details which don't are used as the reason
+fn structured(details: String) -> String {
+    if details.starts_with("field=") || details.starts_with("reason=") {
+        details
+    } else {
+        validation_details(None, &details)
+    }
+}
+
 impl From<crate::mbus_api::Error> for RestError<openapi::models::RestJsonError> {
     fn from(src: crate::mbus_api::Error) -> Self {
         Self::from(ReplyError::from(src))
@@ -78,7 +88,7 @@ impl From<ReplyError> for RestError<RestJsonError> {
                 (StatusCode::REQUEST_TIMEOUT, error)
             }
             ReplyErrorKind::InvalidArgument => {
-                let error = RestJsonError::new(details, message, Kind::InvalidArgument);
+                let error = RestJsonError::new(structured(details), message, Kind::InvalidArgument);
                 (StatusCode::BAD_REQUEST, error)
             }
             ReplyErrorKind::DeadlineExceeded => {
@@ -102,7 +112,8 @@ impl From<ReplyError> for RestError<RestJsonError> {
                 (StatusCode::INSUFFICIENT_STORAGE, error)
             }
             ReplyErrorKind::FailedPrecondition => {
-                let error = RestJsonError::new(details, message, Kind::FailedPrecondition);
+                let error =
+                    RestJsonError::new(structured(details), message, Kind::FailedPrecondition);
                 (StatusCode::PRECONDITION_FAILED, error)
             }
             ReplyErrorKind::Aborted => {
@@ -158,27 +169,36 @@ impl From<ReplyError> for RestError<RestJsonError> {
                 (StatusCode::CONFLICT, error)
             }
             ReplyErrorKind::ReplicaCountAchieved => {
-                let error = RestJsonError::new(details, message, Kind::FailedPrecondition);
+                let error =
+                    RestJsonError::new(structured(details), message, Kind::FailedPrecondition);
                 (StatusCode::PRECONDITION_FAILED, error)
             }
             ReplyErrorKind::ReplicaChangeCount => {
-                let error = RestJsonError::new(details, message, Kind::FailedPrecondition);
+                let error =
+                    RestJsonError::new(structured(details), message, Kind::FailedPrecondition);
                 (StatusCode::PRECONDITION_FAILED, error)
             }
             ReplyErrorKind::ReplicaIncrease => {
-                let error = RestJsonError::new(details, message, Kind::FailedPrecondition);
+                let error =
+                    RestJsonError::new(structured(details), message, Kind::FailedPrecondition);
                 (StatusCode::PRECONDITION_FAILED, error)
             }
             ReplyErrorKind::VolumeNoReplicas => {
-                let error = RestJsonError::new(details, message, Kind::FailedPrecondition);
+                let error =
+                    RestJsonError::new(structured(details), message, Kind::FailedPrecondition);
                 (StatusCode::PRECONDITION_FAILED, error)
             }
             ReplyErrorKind::InUse => {
                 let error = RestJsonError::new(details, message, Kind::InUse);
                 (StatusCode::CONFLICT, error)
             }
+            ReplyErrorKind::DeviceInUse => {
+                let error = RestJsonError::new(details, message, Kind::DeviceInUse);
+                (StatusCode::CONFLICT, error)
+            }
             ReplyErrorKind::ReplicaCreateNumber => {
-                let error = RestJsonError::new(details, message, Kind::FailedPrecondition);
+                let error =
+                    RestJsonError::new(structured(details), message, Kind::FailedPrecondition);
                 (StatusCode::PRECONDITION_FAILED, error)
             }
         };
@@ -77,12 +77,28 @@ pub struct BlockDevice {
     /// identifies if device is available for use (ie. is not "currently" in
     /// use)
     pub available: bool,
+    /// the pool which the device is claimed by, according to the pool specs
+    pub claimed_by: Option<PoolId>,
+}
+
+impl BlockDevice {
+    /// Check if the given pool disk refers to this device, either by its name, its path or one
+    /// of its links. The disk may be a plain path or a uri such as aio:///dev/sda.
+    pub fn matches_disk(&self, disk: &str) -> bool {
+        let disk = url::Url::parse(disk)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| disk.to_string());
+        self.devname == disk || self.devpath == disk || self.devlinks.iter().any(|d| *d == disk)
+    }
 }
 
 impl From<BlockDevice> for models::BlockDevice {
     fn from(src: BlockDevice) -> Self {
-        models::BlockDevice::new(
+        models::BlockDevice::new_all(
             src.available,
+            src.claimed_by.map(Into::into),
             src.devlinks,
             src.devmajor as i32,
             src.devminor as i32,
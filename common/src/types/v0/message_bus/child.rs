@@ -14,6 +14,9 @@ pub struct Child {
     pub state: ChildState,
     /// current rebuild progress (%)
     pub rebuild_progress: Option<u8>,
+    /// last time the state of the child was observed to change
+    #[serde(default)]
+    pub state_changed_at: Option<std::time::SystemTime>,
 }
 
 impl From<Child> for models::Child {
@@ -21,6 +24,9 @@ impl From<Child> for models::Child {
         Self {
             rebuild_progress: src.rebuild_progress,
             state: src.state.into(),
+            state_changed_at: src
+                .state_changed_at
+                .map(|t| humantime::format_rfc3339(t).to_string()),
             uri: src.uri.into(),
         }
     }
@@ -29,6 +35,21 @@ impl From<Child> for models::Child {
 bus_impl_string_id_percent_decoding!(ChildUri, "URI of a nexus child");
 
 impl ChildUri {
+    /// Build Self from a uri which has already been percent-decoded, as decoding it again could
+    /// mangle it
+    pub fn from_decoded<T: Into<String>>(uri: T) -> Self {
+        Self(uri.into())
+    }
+    /// Append the `query` to the uri, extending the query which the uri may already have
+    pub fn with_query(self, query: &str) -> Self {
+        if query.is_empty() {
+            self
+        } else if self.0.contains('?') {
+            Self(format!("{}&{}", self.0, query))
+        } else {
+            Self(format!("{}?{}", self.0, query))
+        }
+    }
     /// Get the io-engine bdev uuid from the ChildUri
     pub fn uuid_str(&self) -> Option<String> {
         match url::Url::from_str(self.as_str()) {
@@ -39,6 +60,14 @@ impl ChildUri {
             Err(_) => None,
         }
     }
+    /// Check if the child is local to the nexus, ie: it's opened directly as a bdev rather
+    /// than through a remote transport
+    pub fn is_local(&self) -> bool {
+        match url::Url::from_str(self.as_str()) {
+            Ok(url) => url.scheme() == "bdev",
+            Err(_) => false,
+        }
+    }
 }
 impl PartialEq<Child> for ChildUri {
     fn eq(&self, other: &Child) -> bool {
@@ -157,6 +186,29 @@ impl From<AddNexusChild> for RemoveNexusChild {
     }
 }
 
+/// Online a nexus child Request
+/// Brings a recovered (degraded) child back online, rather than replacing it
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OnlineNexusChild {
+    /// id of the io-engine instance
+    pub node: NodeId,
+    /// uuid of the nexus
+    pub nexus: NexusId,
+    /// URI of the child device to be onlined
+    pub uri: ChildUri,
+}
+impl OnlineNexusChild {
+    /// Return new `Self`
+    pub fn new(node: &NodeId, nexus: &NexusId, uri: &ChildUri) -> Self {
+        Self {
+            node: node.clone(),
+            nexus: nexus.clone(),
+            uri: uri.clone(),
+        }
+    }
+}
+
 /// Add child to Nexus Request
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -169,4 +221,8 @@ pub struct AddNexusChild {
     pub uri: ChildUri,
     /// auto start rebuilding
     pub auto_rebuild: bool,
+    /// preferred healthy child to rebuild from, if any
+    /// when not set, or not honoured, the io-engine picks the rebuild source
+    #[serde(default)]
+    pub rebuild_source: Option<ChildUri>,
 }
@@ -0,0 +1,105 @@
+use super::*;
+
+use crate::mbus_api::ResourceKind;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Debug, time::SystemTime};
+
+/// Type of a control-plane event
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EventType {
+    /// the event is informational, eg: a resource was created
+    Normal,
+    /// the event may require attention, eg: an operation failed
+    Warning,
+}
+
+impl From<EventType> for models::event::EventType {
+    fn from(src: EventType) -> Self {
+        match src {
+            EventType::Normal => Self::Normal,
+            EventType::Warning => Self::Warning,
+        }
+    }
+}
+
+/// A control-plane event about a resource, eg: a volume was created or a faulted nexus child
+/// could not be removed
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    /// when the event occurred
+    pub timestamp: SystemTime,
+    /// kind of the resource which the event is about
+    pub kind: ResourceKind,
+    /// id of the resource which the event is about
+    pub id: String,
+    /// type of the event
+    pub event_type: EventType,
+    /// short, machine readable reason of the event, eg: `Created`
+    pub reason: String,
+    /// human readable description of the event
+    pub message: String,
+}
+
+impl Event {
+    /// Return a new `Self` about the resource of the given `kind` and `id`, which occurred now
+    pub fn new(
+        kind: ResourceKind,
+        id: impl Into<String>,
+        event_type: EventType,
+        reason: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            kind,
+            id: id.into(),
+            event_type,
+            reason: reason.into(),
+            message: message.into(),
+        }
+    }
+    /// Check if the event is about the resource of the filter.
+    /// Returns None if the filter is not supported, ie: it's not `Filter::None` nor a filter by
+    /// a single node, pool, replica, nexus or volume.
+    pub fn matches(&self, filter: &Filter) -> Option<bool> {
+        let (kind, id) = match filter {
+            Filter::None => return Some(true),
+            Filter::Node(id) => (ResourceKind::Node, id.to_string()),
+            Filter::Pool(id) => (ResourceKind::Pool, id.to_string()),
+            Filter::Replica(id) => (ResourceKind::Replica, id.to_string()),
+            Filter::Nexus(id) => (ResourceKind::Nexus, id.to_string()),
+            Filter::Volume(id) => (ResourceKind::Volume, id.to_string()),
+            _ => return None,
+        };
+        Some(self.kind == kind && self.id == id)
+    }
+}
+
+impl From<Event> for models::Event {
+    fn from(src: Event) -> Self {
+        Self {
+            id: src.id,
+            kind: src.kind.to_string(),
+            message: src.message,
+            reason: src.reason,
+            timestamp: humantime::format_rfc3339(src.timestamp).to_string(),
+            event_type: src.event_type.into(),
+        }
+    }
+}
+
+/// Get the recent control-plane events, oldest first, optionally only those about the resource
+/// of the filter
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetEvents {
+    /// filter the events by the resource which they're about
+    pub filter: Filter,
+}
+impl GetEvents {
+    /// Return new `Self` to get the events about the resource of the filter
+    pub fn new(filter: Filter) -> Self {
+        Self { filter }
+    }
+}
@@ -13,10 +13,17 @@ bus_impl_string_id!(JsonGrpcMethod, "JSON gRPC method");
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonGrpcRequest {
-    /// id of the io-engine instance
+    /// id of the io-engine instance, looked up in the registry to find its endpoint
     pub node: NodeId,
     /// JSON gRPC method to call
     pub method: JsonGrpcMethod,
     /// parameters to be passed to the above method
     pub params: JsonGrpcParams,
+    /// data-plane endpoint of the io-engine instance (eg: "10.0.0.1:10124") which, when set,
+    /// is connected to directly, bypassing the node lookup; this is useful to reach instances
+    /// which are not registered yet.
+    /// The `node` and the `endpoint` are mutually exclusive: when the endpoint is set the node
+    /// must be left unset, otherwise the request is rejected.
+    #[serde(default)]
+    pub endpoint: Option<String>,
 }
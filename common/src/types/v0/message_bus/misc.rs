@@ -1,5 +1,6 @@
 use super::*;
 
+use crate::mbus_api::{ReplyError, ResourceKind};
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, fmt::Debug};
 
@@ -23,6 +24,8 @@ pub enum Filter {
     Pool(PoolId),
     /// Filter by Node and Pool id
     NodePool(NodeId, PoolId),
+    /// Filter by Pool labels, where a pool must have all of the given labels
+    PoolLabel(crate::types::v0::store::pool::PoolLabel),
     /// Filter by Node and Replica id
     NodeReplica(NodeId, ReplicaId),
     /// Filter by Node, Pool and Replica id
@@ -39,6 +42,8 @@ pub enum Filter {
     Nexus(NexusId),
     /// Filter by Volume
     Volume(VolumeId),
+    /// Filter by Volume annotations, where a volume must have all of the given annotations
+    VolumeAnnotations(VolumeAnnotations),
 }
 impl Default for Filter {
     fn default() -> Self {
@@ -224,10 +229,9 @@ macro_rules! bus_impl_string_uuid {
     ($Name:ident, $Doc:literal) => {
         bus_impl_string_uuid_inner!($Name, $Doc);
         impl Default for $Name {
-            /// Generates new blank identifier
+            /// Generates new random identifier, so that two default identifiers never collide
             fn default() -> Self {
-                let uuid = uuid::Uuid::default();
-                $Name(uuid.clone(), uuid.to_string())
+                Self::new()
             }
         }
         impl $Name {
@@ -236,6 +240,11 @@ macro_rules! bus_impl_string_uuid {
                 let uuid = uuid::Uuid::new_v4();
                 $Name(uuid.clone(), uuid.to_string())
             }
+            /// Generates new blank identifier, which is not a valid resource identifier
+            pub fn nil() -> Self {
+                let uuid = uuid::Uuid::nil();
+                $Name(uuid.clone(), uuid.to_string())
+            }
         }
     };
 }
@@ -340,3 +349,104 @@ impl From<Protocol> for models::Protocol {
 /// Liveness Probe
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Liveness {}
+
+/// Reference to a single resource of any kind, used to address pools, replicas, nexuses, volumes
+/// and nodes uniformly, eg: in administrative operations which apply to any resource.
+/// The textual form is `<kind>/<id>` or, when the node is specified, `<kind>/<node>/<id>`.
+/// # Example:
+/// let replica = "replica/node-1/2b0f7c1e-5d4a-4e3b-9c8d-7a6b5c4d3e2f".parse::<ResourceRef>();
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceRef {
+    /// the kind of the resource
+    pub kind: ResourceKind,
+    /// the id of the resource, which must be a uuid for replicas, nexuses and volumes
+    pub id: String,
+    /// the node the resource is on, if any
+    pub node: Option<NodeId>,
+}
+
+impl ResourceRef {
+    /// Return a new validated `Self`
+    pub fn new(kind: ResourceKind, id: String, node: Option<NodeId>) -> Result<Self, ReplyError> {
+        let resource = Self { kind, id, node };
+        resource.validate()?;
+        Ok(resource)
+    }
+    /// Validate the reference: only nodes, pools, replicas, nexuses and volumes can be referenced,
+    /// the id must be valid for the kind of the resource and nodes and volumes can't specify a node
+    pub fn validate(&self) -> Result<(), ReplyError> {
+        let invalid = |error: String| {
+            ReplyError::invalid_argument(self.kind.clone(), "resource reference", error)
+        };
+        if self.id.is_empty() {
+            return Err(invalid("The resource id is empty".to_string()));
+        }
+        if let Some(node) = &self.node {
+            if node.as_str().is_empty() {
+                return Err(invalid("The resource node is empty".to_string()));
+            }
+        }
+        let (uuid, node_allowed) = match self.kind {
+            ResourceKind::Node => (false, false),
+            ResourceKind::Pool => (false, true),
+            ResourceKind::Replica | ResourceKind::Nexus => (true, true),
+            ResourceKind::Volume => (true, false),
+            _ => {
+                return Err(invalid(format!(
+                    "A {} can't be referenced",
+                    self.kind.as_ref()
+                )))
+            }
+        };
+        if uuid {
+            uuid::Uuid::parse_str(&self.id).map_err(|error| invalid(error.to_string()))?;
+        }
+        if !node_allowed && self.node.is_some() {
+            return Err(invalid(format!(
+                "A {} reference can't specify a node",
+                self.kind.as_ref()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ResourceRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = self.kind.as_ref().to_lowercase();
+        match &self.node {
+            Some(node) => write!(f, "{}/{}/{}", kind, node, self.id),
+            None => write!(f, "{}/{}", kind, self.id),
+        }
+    }
+}
+
+impl FromStr for ResourceRef {
+    type Err = ReplyError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let invalid = |error: &str| {
+            ReplyError::invalid_argument(
+                ResourceKind::Unknown,
+                "resource reference",
+                format!("'{}': {}", source, error),
+            )
+        };
+        let mut parts = source.splitn(3, '/');
+        let kind = match parts.next().map(|kind| kind.to_lowercase()).as_deref() {
+            Some("node") => ResourceKind::Node,
+            Some("pool") => ResourceKind::Pool,
+            Some("replica") => ResourceKind::Replica,
+            Some("nexus") => ResourceKind::Nexus,
+            Some("volume") => ResourceKind::Volume,
+            _ => return Err(invalid("unknown resource kind")),
+        };
+        let (node, id) = match (parts.next(), parts.next()) {
+            (Some(id), None) => (None, id),
+            (Some(node), Some(id)) => (Some(NodeId::from(node)), id),
+            _ => return Err(invalid("expected <kind>/<id> or <kind>/<node>/<id>")),
+        };
+        Self::new(kind, id.to_string(), node)
+    }
+}
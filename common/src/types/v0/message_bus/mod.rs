@@ -1,9 +1,11 @@
 pub mod blockdevice;
 pub mod child;
+pub mod event;
 pub mod jsongrpc;
 pub mod misc;
 pub mod nexus;
 pub mod node;
+pub mod operation;
 pub mod pool;
 pub mod replica;
 pub mod spec;
@@ -13,10 +15,12 @@ pub mod watch;
 
 pub use blockdevice::*;
 pub use child::*;
+pub use event::*;
 pub use jsongrpc::*;
 pub use misc::*;
 pub use nexus::*;
 pub use node::*;
+pub use operation::*;
 pub use pool::*;
 pub use replica::*;
 pub use spec::*;
@@ -77,7 +81,7 @@ impl From<ChannelVs> for Channel {
 }
 
 /// Versioned Message Id's
-#[derive(Debug, PartialEq, Clone, ToString, EnumString)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, ToString, EnumString)]
 #[strum(serialize_all = "camelCase")]
 pub enum MessageIdVs {
     /// Default
@@ -95,16 +99,32 @@ pub enum MessageIdVs {
     /// Node Service
     /// Get all node information
     GetNodes,
+    /// Cordon a node
+    CordonNode,
+    /// Uncordon a node
+    UncordonNode,
+    /// Drain a node
+    DrainNode,
+    /// Set the communication timeouts of a node
+    SetNodeTimeouts,
     /// Pool Service
     ///
     /// Get pools with filter
     GetPools,
     /// Create Pool,
     CreatePool,
+    /// Import Pool,
+    ImportPool,
     /// Destroy Pool,
     DestroyPool,
+    /// Reconcile Pool,
+    ReconcilePool,
+    /// Set Pool Maintenance,
+    SetPoolMaintenance,
     /// Get replicas with filter
     GetReplicas,
+    /// Get a single replica,
+    GetReplica,
     /// Create Replica,
     CreateReplica,
     /// Destroy Replica,
@@ -113,6 +133,10 @@ pub enum MessageIdVs {
     ShareReplica,
     /// Unshare Replica,
     UnshareReplica,
+    /// Resize Replica,
+    ResizeReplica,
+    /// Move Replica,
+    MoveReplica,
     /// Volume Service
     ///
     /// Get nexuses with filter
@@ -125,10 +149,24 @@ pub enum MessageIdVs {
     ShareNexus,
     /// Unshare Nexus
     UnshareNexus,
+    /// Resize Nexus
+    ResizeNexus,
     /// Remove a child from its parent nexus
     RemoveNexusChild,
     /// Add a child to a nexus
     AddNexusChild,
+    /// Online a child of a nexus
+    OnlineNexusChild,
+    /// Get the rebuild history of a nexus
+    GetRebuildHistory,
+    /// Get the rebuilds in progress
+    GetRebuilds,
+    /// Cancel the rebuild of a nexus child
+    CancelRebuild,
+    /// Get the limit on the number of concurrent rebuilds
+    GetRebuildLimit,
+    /// Set the limit on the number of concurrent rebuilds
+    SetRebuildLimit,
     /// Get all volumes
     GetVolumes,
     /// Create Volume,
@@ -137,6 +175,8 @@ pub enum MessageIdVs {
     DestroyVolume,
     /// Publish Volume,
     PublishVolume,
+    /// Republish Volume on a different node
+    RepublishVolume,
     /// Unpublish Volume
     UnpublishVolume,
     /// Share Volume
@@ -149,6 +189,20 @@ pub enum MessageIdVs {
     RemoveVolumeNexus,
     /// Set replica count
     SetVolumeReplica,
+    /// Add a specific replica to a volume
+    AddVolumeReplica,
+    /// Remove a specific replica from a volume
+    RemoveVolumeReplica,
+    /// Expand Volume
+    ExpandVolume,
+    /// Set volume labels
+    SetVolumeLabels,
+    /// Create a volume snapshot
+    CreateSnapshot,
+    /// List the volume snapshots
+    ListSnapshots,
+    /// Delete a volume snapshot
+    DeleteSnapshot,
     /// Generic JSON gRPC message
     JsonGrpc,
     /// Get block devices
@@ -163,6 +217,94 @@ pub enum MessageIdVs {
     GetSpecs,
     /// Get States
     GetStates,
+    /// Check for replicas which are not referenced by any spec
+    CheckOrphanedReplicas,
+    /// Get the recent control-plane events
+    GetEvents,
+    /// Get the pending operations of the resource specs
+    GetOperations,
+    /// Clear the pending operation of a resource spec
+    ClearOperation,
+    /// Reconcile a resource now
+    ReconcileResource,
+}
+
+impl MessageIdVs {
+    /// Check if the message mutates the state of the cluster, as opposed to merely querying it.
+    /// Keep alive registrations are not deemed mutating as they're sent periodically.
+    pub fn mutating(&self) -> bool {
+        match self {
+            MessageIdVs::Default
+            | MessageIdVs::Liveness
+            | MessageIdVs::ConfigGetCurrent
+            | MessageIdVs::Register
+            | MessageIdVs::Deregister
+            | MessageIdVs::GetNodes
+            | MessageIdVs::GetPools
+            | MessageIdVs::GetReplicas
+            | MessageIdVs::GetReplica
+            | MessageIdVs::GetNexuses
+            | MessageIdVs::GetRebuildHistory
+            | MessageIdVs::GetRebuilds
+            | MessageIdVs::GetRebuildLimit
+            | MessageIdVs::GetVolumes
+            | MessageIdVs::ListSnapshots
+            | MessageIdVs::GetBlockDevices
+            | MessageIdVs::GetWatches
+            | MessageIdVs::GetSpecs
+            | MessageIdVs::GetStates
+            | MessageIdVs::GetEvents
+            | MessageIdVs::GetOperations => false,
+            MessageIdVs::ConfigUpdate
+            | MessageIdVs::CordonNode
+            | MessageIdVs::UncordonNode
+            | MessageIdVs::DrainNode
+            | MessageIdVs::SetNodeTimeouts
+            | MessageIdVs::CreatePool
+            | MessageIdVs::ImportPool
+            | MessageIdVs::DestroyPool
+            | MessageIdVs::ReconcilePool
+            | MessageIdVs::SetPoolMaintenance
+            | MessageIdVs::CreateReplica
+            | MessageIdVs::DestroyReplica
+            | MessageIdVs::ShareReplica
+            | MessageIdVs::UnshareReplica
+            | MessageIdVs::ResizeReplica
+            | MessageIdVs::MoveReplica
+            | MessageIdVs::CreateNexus
+            | MessageIdVs::DestroyNexus
+            | MessageIdVs::ShareNexus
+            | MessageIdVs::UnshareNexus
+            | MessageIdVs::ResizeNexus
+            | MessageIdVs::RemoveNexusChild
+            | MessageIdVs::AddNexusChild
+            | MessageIdVs::OnlineNexusChild
+            | MessageIdVs::CancelRebuild
+            | MessageIdVs::SetRebuildLimit
+            | MessageIdVs::CreateVolume
+            | MessageIdVs::DestroyVolume
+            | MessageIdVs::PublishVolume
+            | MessageIdVs::RepublishVolume
+            | MessageIdVs::UnpublishVolume
+            | MessageIdVs::ShareVolume
+            | MessageIdVs::UnshareVolume
+            | MessageIdVs::AddVolumeNexus
+            | MessageIdVs::RemoveVolumeNexus
+            | MessageIdVs::SetVolumeReplica
+            | MessageIdVs::AddVolumeReplica
+            | MessageIdVs::RemoveVolumeReplica
+            | MessageIdVs::ExpandVolume
+            | MessageIdVs::SetVolumeLabels
+            | MessageIdVs::CreateSnapshot
+            | MessageIdVs::DeleteSnapshot
+            | MessageIdVs::JsonGrpc
+            | MessageIdVs::CreateWatch
+            | MessageIdVs::DeleteWatch
+            | MessageIdVs::CheckOrphanedReplicas
+            | MessageIdVs::ClearOperation
+            | MessageIdVs::ReconcileResource => true,
+        }
+    }
 }
 
 impl MessageIdTimeout for MessageIdVs {
@@ -178,13 +320,24 @@ impl MessageIdTimeout for MessageIdVs {
                     MessageIdVs::CreateVolume => min_timeouts.replica() * 3 + min_timeouts.nexus(),
                     MessageIdVs::DestroyVolume => min_timeouts.replica() * 3 + min_timeouts.nexus(),
                     MessageIdVs::PublishVolume => min_timeouts.nexus(),
+                    MessageIdVs::RepublishVolume => min_timeouts.nexus() * 2,
                     MessageIdVs::UnpublishVolume => min_timeouts.nexus(),
+                    MessageIdVs::ExpandVolume => min_timeouts.replica() * 3 + min_timeouts.nexus(),
+                    MessageIdVs::AddVolumeReplica => min_timeouts.replica() + min_timeouts.nexus(),
+                    MessageIdVs::RemoveVolumeReplica => {
+                        min_timeouts.replica() + min_timeouts.nexus()
+                    }
+                    MessageIdVs::CreateSnapshot => min_timeouts.nexus(),
 
                     MessageIdVs::CreateNexus => min_timeouts.nexus(),
                     MessageIdVs::DestroyNexus => min_timeouts.nexus(),
+                    MessageIdVs::ResizeNexus => min_timeouts.nexus(),
 
                     MessageIdVs::CreateReplica => min_timeouts.replica(),
                     MessageIdVs::DestroyReplica => min_timeouts.replica(),
+                    MessageIdVs::ResizeReplica => min_timeouts.replica(),
+                    MessageIdVs::MoveReplica => min_timeouts.replica() * 2 + min_timeouts.nexus(),
+                    MessageIdVs::CheckOrphanedReplicas => min_timeouts.replica() * 3,
                     _ => timeout,
                 },
             )
@@ -7,7 +7,7 @@ use crate::{
     },
 };
 use serde::{Deserialize, Serialize};
-use std::{convert::TryFrom, fmt::Debug, ops::RangeInclusive};
+use std::{collections::HashMap, convert::TryFrom, fmt::Debug, ops::RangeInclusive};
 use strum_macros::{EnumString, ToString};
 
 /// Volume Nexuses
@@ -48,6 +48,27 @@ impl Nexus {
     pub fn contains_child(&self, uri: &ChildUri) -> bool {
         self.children.iter().any(|c| &c.uri == uri)
     }
+    /// Get the preferred source child for a rebuild, if any
+    /// A healthy child which is local to the nexus is preferred as it avoids copying the data
+    /// over the network twice. Otherwise the choice is left to the io-engine.
+    pub fn preferred_rebuild_source(&self) -> Option<ChildUri> {
+        self.children
+            .iter()
+            .find(|c| c.state == ChildState::Online && c.uri.is_local())
+            .map(|c| c.uri.clone())
+    }
+    /// Get the rebuild source to use, given the `preferred` one, if any
+    /// The preference is only honoured if it's still a healthy child of the nexus, otherwise
+    /// we fall back to the io-engine's default choice of source.
+    pub fn rebuild_source(&self, preferred: Option<&ChildUri>) -> Option<ChildUri> {
+        preferred
+            .filter(|uri| {
+                self.children
+                    .iter()
+                    .any(|c| &c.uri == *uri && c.state == ChildState::Online)
+            })
+            .cloned()
+    }
 }
 
 impl From<Nexus> for models::Nexus {
@@ -281,8 +302,50 @@ impl NexusNvmfConfig {
     pub fn preempt_reservation_key(&self) -> Option<u64> {
         self.preempt_reservation_key
     }
+    /// Return `Self` configured with the given NVMe-oF transport parameters.
+    /// Only the parameters supported by the io-engine nvmf target are accepted, which are
+    /// currently the controller id range bounds: `cntlidMin` and `cntlidMax`.
+    pub fn with_transport_params(
+        mut self,
+        params: &NvmfTransportParams,
+    ) -> Result<Self, ReplyError> {
+        let mut min = self.min_cntl_id();
+        let mut max = self.max_cntl_id();
+        for (name, value) in params {
+            let bound = match name.as_str() {
+                NVMF_PARAM_CNTLID_MIN => &mut min,
+                NVMF_PARAM_CNTLID_MAX => &mut max,
+                _ => {
+                    return Err(ReplyError::invalid_argument(
+                        ResourceKind::Nexus,
+                        "transport_params",
+                        format!(
+                        "unsupported transport parameter '{}', supported parameters are: {}, {}",
+                        name, NVMF_PARAM_CNTLID_MIN, NVMF_PARAM_CNTLID_MAX
+                    ),
+                    ))
+                }
+            };
+            *bound = value.parse::<u16>().map_err(|error| {
+                ReplyError::invalid_argument(
+                    ResourceKind::Nexus,
+                    "transport_params",
+                    format!("invalid value '{}' for '{}': {}", value, name, error),
+                )
+            })?;
+        }
+        self.controller_id_range = NvmfControllerIdRange::new(min, max)?;
+        Ok(self)
+    }
 }
 
+/// NVMe-oF transport parameters of a nexus target, keyed by the parameter name
+pub type NvmfTransportParams = HashMap<String, String>;
+/// Transport parameter which sets the minimum controller id of the nvmf target
+pub const NVMF_PARAM_CNTLID_MIN: &str = "cntlidMin";
+/// Transport parameter which sets the maximum controller id of the nvmf target
+pub const NVMF_PARAM_CNTLID_MAX: &str = "cntlidMax";
+
 impl Default for NexusNvmfConfig {
     fn default() -> Self {
         if std::env::var("TEST_NEXUS_NVMF_ANA_ENABLE").is_ok() {
@@ -369,6 +432,9 @@ pub struct ShareNexus {
     pub key: Option<String>,
     /// share protocol
     pub protocol: NexusShareProtocol,
+    /// NVMe-oF transport parameters, which must match the ones the nexus was created with
+    #[serde(default)]
+    pub transport_params: NvmfTransportParams,
 }
 
 impl From<(&Nexus, Option<String>, NexusShareProtocol)> for ShareNexus {
@@ -378,6 +444,7 @@ impl From<(&Nexus, Option<String>, NexusShareProtocol)> for ShareNexus {
             uuid: nexus.uuid.clone(),
             key,
             protocol,
+            transport_params: NvmfTransportParams::new(),
         }
     }
 }
@@ -398,6 +465,29 @@ impl From<ShareNexus> for UnshareNexus {
     }
 }
 
+/// Resize Nexus Request
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResizeNexus {
+    /// id of the io-engine instance
+    pub node: NodeId,
+    /// uuid of the nexus
+    pub uuid: NexusId,
+    /// requested new size of the nexus in bytes
+    pub requested_size: u64,
+}
+
+impl ResizeNexus {
+    /// Create new `Self` to resize the given nexus to `requested_size` bytes
+    pub fn new(nexus: &Nexus, requested_size: u64) -> Self {
+        Self {
+            node: nexus.node.clone(),
+            uuid: nexus.uuid.clone(),
+            requested_size,
+        }
+    }
+}
+
 /// Unshare Nexus Request
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -407,3 +497,270 @@ pub struct UnshareNexus {
     /// uuid of the nexus
     pub uuid: NexusId,
 }
+
+/// Get the rebuild history of a nexus
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRebuildHistory {
+    /// uuid of the nexus
+    pub nexus: NexusId,
+}
+impl GetRebuildHistory {
+    /// Return new `Self` to get the rebuild history of the given nexus
+    pub fn new(nexus: &NexusId) -> Self {
+        Self {
+            nexus: nexus.clone(),
+        }
+    }
+}
+
+/// State of a rebuild job
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, EnumString, ToString, Eq, PartialEq)]
+#[strum(serialize_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub enum RebuildJobState {
+    /// the job has been created but it's not running yet
+    Init = 0,
+    /// the job is rebuilding the child
+    Rebuilding = 1,
+    /// the job was stopped before it could complete
+    Stopped = 2,
+    /// the job is paused
+    Paused = 3,
+    /// the job failed to rebuild the child
+    Failed = 4,
+    /// the child was rebuilt successfully
+    Completed = 5,
+}
+impl From<i32> for RebuildJobState {
+    fn from(src: i32) -> Self {
+        match src {
+            1 => Self::Rebuilding,
+            2 => Self::Stopped,
+            3 => Self::Paused,
+            4 => Self::Failed,
+            5 => Self::Completed,
+            _ => Self::Init,
+        }
+    }
+}
+impl From<RebuildJobState> for models::RebuildJobState {
+    fn from(src: RebuildJobState) -> Self {
+        match src {
+            RebuildJobState::Init => Self::Init,
+            RebuildJobState::Rebuilding => Self::Rebuilding,
+            RebuildJobState::Stopped => Self::Stopped,
+            RebuildJobState::Paused => Self::Paused,
+            RebuildJobState::Failed => Self::Failed,
+            RebuildJobState::Completed => Self::Completed,
+        }
+    }
+}
+
+/// Record of a rebuild of a nexus child
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildRecord {
+    /// URI of the rebuilt child
+    pub child_uri: ChildUri,
+    /// URI of the child the data was rebuilt from
+    pub src_uri: ChildUri,
+    /// final state of the rebuild job
+    pub state: RebuildJobState,
+    /// total number of blocks to rebuild
+    pub blocks_total: u64,
+    /// number of blocks processed
+    pub blocks_recovered: u64,
+    /// number of blocks actually copied from the source child
+    pub blocks_transferred: u64,
+    /// size of a block in bytes
+    pub block_size: u64,
+    /// whether only the blocks which were written to while the child was away were rebuilt
+    pub is_partial: bool,
+    /// when the rebuild started
+    pub start_time: std::time::SystemTime,
+    /// when the rebuild ended
+    pub end_time: std::time::SystemTime,
+}
+impl From<RebuildRecord> for models::RebuildRecord {
+    fn from(src: RebuildRecord) -> Self {
+        Self::new(
+            src.child_uri,
+            src.src_uri,
+            src.state,
+            src.blocks_total,
+            src.blocks_recovered,
+            src.blocks_transferred,
+            src.block_size,
+            src.is_partial,
+            humantime::format_rfc3339(src.start_time).to_string(),
+            humantime::format_rfc3339(src.end_time).to_string(),
+        )
+    }
+}
+
+/// Rebuild history of a nexus, ordered from the oldest to the most recent rebuild
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildHistory {
+    /// uuid of the nexus
+    pub nexus: NexusId,
+    /// records of the completed rebuilds
+    pub records: Vec<RebuildRecord>,
+}
+impl From<RebuildHistory> for models::RebuildHistory {
+    fn from(src: RebuildHistory) -> Self {
+        Self::new(
+            src.nexus,
+            src.records.into_iter().map(From::from).collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// Get the rebuilds in progress, of all the nexuses or of the given nexus only
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRebuilds {
+    /// uuid of the nexus, or all nexuses if None
+    pub nexus: Option<NexusId>,
+}
+impl GetRebuilds {
+    /// Return new `Self` to get the rebuilds in progress of all the nexuses
+    pub fn all() -> Self {
+        Self { nexus: None }
+    }
+    /// Return new `Self` to get the rebuilds in progress of the given nexus
+    pub fn nexus(nexus: &NexusId) -> Self {
+        Self {
+            nexus: Some(nexus.clone()),
+        }
+    }
+}
+
+/// Rebuild of a nexus child which is in progress
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Rebuild {
+    /// uuid of the nexus
+    pub nexus: NexusId,
+    /// URI of the child being rebuilt
+    pub child_uri: ChildUri,
+    /// rebuild progress (%)
+    pub progress: u8,
+    /// when the rebuild started, if it could be retrieved from the io-engine
+    pub start_time: Option<std::time::SystemTime>,
+}
+impl From<Rebuild> for models::Rebuild {
+    fn from(src: Rebuild) -> Self {
+        Self::new_all(
+            src.nexus,
+            src.child_uri,
+            src.progress,
+            src.start_time
+                .map(|time| humantime::format_rfc3339(time).to_string()),
+        )
+    }
+}
+
+/// Rebuilds in progress
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Rebuilds {
+    /// the rebuilds in progress
+    pub entries: Vec<Rebuild>,
+}
+impl From<Rebuilds> for Vec<models::Rebuild> {
+    fn from(src: Rebuilds) -> Self {
+        src.entries.into_iter().map(From::from).collect()
+    }
+}
+
+/// Cancel the rebuild of a nexus child
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRebuild {
+    /// uuid of the nexus
+    pub nexus: NexusId,
+    /// URI of the child being rebuilt
+    pub child_uri: ChildUri,
+}
+impl CancelRebuild {
+    /// Return new `Self` to cancel the rebuild of the given nexus child
+    pub fn new(nexus: &NexusId, child_uri: &ChildUri) -> Self {
+        Self {
+            nexus: nexus.clone(),
+            child_uri: child_uri.clone(),
+        }
+    }
+}
+
+/// Get the system-wide limit on the number of concurrent rebuilds
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRebuildLimit {}
+
+/// Set the system-wide limit on the number of concurrent rebuilds
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRebuildLimit {
+    /// maximum number of concurrent rebuilds, or unlimited if None
+    pub max_rebuilds: Option<u32>,
+}
+impl SetRebuildLimit {
+    /// Return new `Self` to set the given maximum number of concurrent rebuilds
+    pub fn new(max_rebuilds: Option<u32>) -> Self {
+        Self { max_rebuilds }
+    }
+}
+
+/// The system-wide limit on the number of concurrent rebuilds
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildLimit {
+    /// maximum number of concurrent rebuilds, or unlimited if None
+    pub max_rebuilds: Option<u32>,
+    /// number of rebuilds currently in progress
+    pub num_rebuilds: u32,
+}
+impl From<RebuildLimit> for models::RebuildLimit {
+    fn from(src: RebuildLimit) -> Self {
+        Self::new_all(src.max_rebuilds, src.num_rebuilds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebuild_source() {
+        let child = |uri: &str, state: ChildState| Child {
+            uri: uri.into(),
+            state,
+            ..Default::default()
+        };
+        let nexus = Nexus {
+            children: vec![
+                child("bdev:///local", ChildState::Online),
+                child("nvmf://remote", ChildState::Online),
+                child("nvmf://faulted", ChildState::Faulted),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            nexus.preferred_rebuild_source(),
+            Some("bdev:///local".into())
+        );
+
+        // a healthy child of the nexus is honoured
+        let preferred = ChildUri::from("nvmf://remote");
+        assert_eq!(nexus.rebuild_source(Some(&preferred)), Some(preferred));
+
+        // otherwise we fall back to the io-engine's default
+        let faulted = ChildUri::from("nvmf://faulted");
+        assert_eq!(nexus.rebuild_source(Some(&faulted)), None);
+        let unknown = ChildUri::from("nvmf://unknown");
+        assert_eq!(nexus.rebuild_source(Some(&unknown)), None);
+        assert_eq!(nexus.rebuild_source(None), None);
+    }
+}
@@ -3,7 +3,7 @@ use super::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
-use crate::types::v0::store::node::NodeSpec;
+use crate::types::v0::store::node::{NodeSpec, NodeTimeouts};
 use strum_macros::{EnumString, ToString};
 
 /// Registration
@@ -50,6 +50,73 @@ impl GetNodes {
     }
 }
 
+/// Cordon the node with the given label, so that no new resources are placed on it
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CordonNode {
+    /// id of the io-engine instance
+    pub id: NodeId,
+    /// label of the cordon
+    pub label: String,
+}
+impl CordonNode {
+    /// Return a new `Self`
+    pub fn new(id: NodeId, label: String) -> Self {
+        Self { id, label }
+    }
+}
+
+/// Drain the node: cordon it with the given label and then move all of its volume replicas
+/// to other nodes
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DrainNode {
+    /// id of the io-engine instance
+    pub id: NodeId,
+    /// label of the cordon
+    pub label: String,
+}
+impl DrainNode {
+    /// Return a new `Self`
+    pub fn new(id: NodeId, label: String) -> Self {
+        Self { id, label }
+    }
+}
+
+/// Set the communication timeouts of the node, which override the defaults of the core agent
+/// A timeout which is not set falls back to the default
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetNodeTimeouts {
+    /// id of the io-engine instance
+    pub id: NodeId,
+    /// the timeouts overrides
+    pub timeouts: NodeTimeouts,
+}
+impl SetNodeTimeouts {
+    /// Return a new `Self`
+    pub fn new(id: NodeId, timeouts: NodeTimeouts) -> Self {
+        Self { id, timeouts }
+    }
+}
+
+/// Remove the cordon with the given label from the node
+/// The node becomes schedulable again once all of its cordons are removed
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UncordonNode {
+    /// id of the io-engine instance
+    pub id: NodeId,
+    /// label of the cordon
+    pub label: String,
+}
+impl UncordonNode {
+    /// Return a new `Self`
+    pub fn new(id: NodeId, label: String) -> Self {
+        Self { id, label }
+    }
+}
+
 /// Node information
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -97,6 +164,9 @@ pub enum NodeStatus {
     /// Node is deemed offline if has missed the
     /// registration keep alive deadline
     Offline,
+    /// Node has missed the registration keep alive deadline but it's not yet deemed offline
+    /// as it's still within the allowed number of consecutive misses
+    Suspect,
 }
 
 impl Default for NodeStatus {
@@ -159,6 +229,7 @@ impl From<NodeStatus> for models::NodeStatus {
             NodeStatus::Unknown => Self::Unknown,
             NodeStatus::Online => Self::Online,
             NodeStatus::Offline => Self::Offline,
+            NodeStatus::Suspect => Self::Suspect,
         }
     }
 }
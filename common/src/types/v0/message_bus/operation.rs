@@ -0,0 +1,88 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Get the operations which were started on the resource specs but which haven't completed,
+/// optionally only those of the referenced resource or, for a node, of the resources on it
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOperations {
+    /// the resource whose pending operations to get, or all of them if None
+    pub resource: Option<ResourceRef>,
+}
+impl GetOperations {
+    /// Return new `Self` to get the pending operations of the `resource`, if any
+    pub fn new(resource: Option<ResourceRef>) -> Self {
+        Self { resource }
+    }
+}
+
+/// An operation which was started on a resource spec but which hasn't completed
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingOperation {
+    /// the resource with the pending operation
+    pub resource: ResourceRef,
+    /// the pending operation
+    pub operation: String,
+    /// the result of the operation, if known
+    pub result: Option<bool>,
+}
+impl PendingOperation {
+    /// Return new `Self` for the pending `operation` of the `resource`
+    pub fn new(resource: ResourceRef, operation: &impl Debug, result: Option<bool>) -> Self {
+        Self {
+            resource,
+            operation: format!("{:?}", operation),
+            result,
+        }
+    }
+}
+impl From<PendingOperation> for models::PendingOperation {
+    fn from(src: PendingOperation) -> Self {
+        Self::new_all(src.resource.to_string(), src.operation, src.result)
+    }
+}
+
+/// The pending operations found by a `GetOperations` request
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingOperations {
+    /// the pending operations
+    pub entries: Vec<PendingOperation>,
+}
+impl From<PendingOperations> for Vec<models::PendingOperation> {
+    fn from(src: PendingOperations) -> Self {
+        src.entries.into_iter().map(Into::into).collect()
+    }
+}
+
+/// Clear the pending operation of the referenced resource, eg: when it's stuck because its
+/// result can't be determined. The spec is left as it was before the operation started.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearOperation {
+    /// the resource whose pending operation to clear
+    pub resource: ResourceRef,
+}
+impl ClearOperation {
+    /// Return new `Self` to clear the pending operation of the `resource`
+    pub fn new(resource: ResourceRef) -> Self {
+        Self { resource }
+    }
+}
+
+/// Reconcile the referenced resource now, rather than waiting for the next reconcile period
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileResource {
+    /// the resource to reconcile
+    pub resource: ResourceRef,
+}
+impl ReconcileResource {
+    /// Return new `Self` to reconcile the `resource`
+    pub fn new(resource: ResourceRef) -> Self {
+        Self { resource }
+    }
+}
@@ -5,7 +5,12 @@ use crate::{
     IntoOption,
 };
 use serde::{Deserialize, Serialize};
-use std::{cmp::Ordering, fmt::Debug, ops::Deref};
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    ops::Deref,
+    time::{Duration, SystemTime},
+};
 use strum_macros::{EnumString, ToString};
 
 /// Pool Service
@@ -86,7 +91,227 @@ impl From<PoolState> for models::PoolState {
     }
 }
 
+/// Default maximum discrepancy, in bytes, between the pool used space and the summed sizes of
+/// its replicas before the pool is flagged as inconsistent.
+/// Allows some slack for the pool metadata.
+pub const POOL_CONSISTENCY_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// Consistency report of a pool, comparing the used space reported by io-engine against the
+/// summed sizes of the replicas allocated from it
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolConsistency {
+    /// id of the pool
+    pub id: PoolId,
+    /// id of the io-engine instance
+    pub node: NodeId,
+    /// size of the pool in bytes
+    pub capacity: u64,
+    /// used bytes from the pool, as reported by io-engine
+    pub used: u64,
+    /// number of replicas allocated from the pool
+    pub replicas: u32,
+    /// summed size in bytes of the thick provisioned replicas
+    pub replicas_size: u64,
+    /// difference in bytes between the used space and the summed replica sizes
+    pub discrepancy: i64,
+    /// maximum absolute discrepancy in bytes tolerated before flagging the pool
+    pub threshold: u64,
+}
+
+impl PoolConsistency {
+    /// Create a new `Self` from the pool state and the replicas allocated from it
+    /// Thin provisioned replicas don't have their full size allocated so they're not accounted
+    /// for in the summed replica size.
+    pub fn new(pool: &PoolState, replicas: &[Replica], threshold: u64) -> Self {
+        let replicas = replicas.iter().filter(|r| r.pool == pool.id);
+        let replicas_size = replicas
+            .clone()
+            .filter(|r| !r.thin)
+            .map(|r| r.size)
+            .sum::<u64>();
+        Self {
+            id: pool.id.clone(),
+            node: pool.node.clone(),
+            capacity: pool.capacity,
+            used: pool.used,
+            replicas: replicas.count() as u32,
+            replicas_size,
+            discrepancy: pool.used as i64 - replicas_size as i64,
+            threshold,
+        }
+    }
+    /// Check if the discrepancy is within the threshold
+    pub fn consistent(&self) -> bool {
+        self.discrepancy.unsigned_abs() <= self.threshold
+    }
+}
+
+impl From<PoolConsistency> for models::PoolConsistency {
+    fn from(src: PoolConsistency) -> Self {
+        Self {
+            consistent: src.consistent(),
+            id: src.id.into(),
+            node: src.node.into(),
+            capacity: src.capacity,
+            used: src.used,
+            replicas: src.replicas,
+            replicas_size: src.replicas_size,
+            discrepancy: src.discrepancy,
+            threshold: src.threshold,
+        }
+    }
+}
+
+/// Sample of the capacity usage of a pool, as seen by the control plane at a given time
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PoolUsageSample {
+    /// when the sample was taken
+    pub timestamp: SystemTime,
+    /// size of the pool in bytes
+    pub capacity: u64,
+    /// used bytes from the pool
+    pub used: u64,
+}
+impl PoolUsageSample {
+    /// Create a new `Self` from the pool state, sampled now
+    pub fn new(pool: &PoolState) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            capacity: pool.capacity,
+            used: pool.used,
+        }
+    }
+}
+
+/// Linear forecast of the capacity usage of a pool, or of a set of pools, based on the growth
+/// rate of the used capacity over its recent usage samples
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityForecast {
+    /// size in bytes, as of the latest sample
+    pub capacity: u64,
+    /// used bytes, as of the latest sample
+    pub used: u64,
+    /// growth rate of the used capacity, in bytes per second
+    /// a negative rate means the used capacity is shrinking
+    pub growth_rate: f64,
+    /// number of samples the forecast is based on
+    pub samples: u32,
+    /// when the latest sample was taken
+    pub timestamp: SystemTime,
+    /// projected time at which the capacity is exhausted, if the used capacity is growing
+    pub exhaustion: Option<SystemTime>,
+}
+
+impl CapacityForecast {
+    /// Create a new `Self` with the least squares fit of the used capacity over the given
+    /// samples, oldest first. Returns `None` if there are no samples.
+    pub fn new(samples: &[PoolUsageSample]) -> Option<Self> {
+        let first = samples.first()?;
+        let latest = samples.last()?;
+        let points = samples
+            .iter()
+            .map(|s| {
+                let time = s
+                    .timestamp
+                    .duration_since(first.timestamp)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                (time, s.used as f64)
+            })
+            .collect::<Vec<_>>();
+        let count = points.len() as f64;
+        let mean_time = points.iter().map(|(t, _)| t).sum::<f64>() / count;
+        let mean_used = points.iter().map(|(_, u)| u).sum::<f64>() / count;
+        let (covariance, variance) =
+            points
+                .iter()
+                .fold((0.0, 0.0), |(covariance, variance), (time, used)| {
+                    let time = time - mean_time;
+                    (
+                        covariance + time * (used - mean_used),
+                        variance + time * time,
+                    )
+                });
+        let growth_rate = if variance > 0.0 {
+            covariance / variance
+        } else {
+            0.0
+        };
+        Some(Self::with_growth_rate(
+            latest.capacity,
+            latest.used,
+            growth_rate,
+            samples.len() as u32,
+            latest.timestamp,
+        ))
+    }
+    /// Aggregate the given forecasts into a single forecast, eg: for the whole cluster.
+    /// The capacities, used capacities and growth rates add up and the exhaustion is projected
+    /// from the latest sample. Returns `None` if there are no forecasts.
+    pub fn aggregate(forecasts: &[CapacityForecast]) -> Option<Self> {
+        let timestamp = forecasts.iter().map(|f| f.timestamp).max()?;
+        Some(Self::with_growth_rate(
+            forecasts.iter().map(|f| f.capacity).sum(),
+            forecasts.iter().map(|f| f.used).sum(),
+            forecasts.iter().map(|f| f.growth_rate).sum(),
+            forecasts
+                .iter()
+                .map(|f| f.samples)
+                .min()
+                .unwrap_or_default(),
+            timestamp,
+        ))
+    }
+    fn with_growth_rate(
+        capacity: u64,
+        used: u64,
+        growth_rate: f64,
+        samples: u32,
+        timestamp: SystemTime,
+    ) -> Self {
+        let exhaustion = if used >= capacity {
+            Some(timestamp)
+        } else if growth_rate > 0.0 {
+            let remaining = (capacity - used) as f64 / growth_rate;
+            // beyond this horizon the projection is meaningless
+            if remaining < u32::MAX as f64 {
+                timestamp.checked_add(Duration::from_secs_f64(remaining))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        Self {
+            capacity,
+            used,
+            growth_rate,
+            samples,
+            timestamp,
+            exhaustion,
+        }
+    }
+}
+
+impl From<CapacityForecast> for models::CapacityForecast {
+    fn from(src: CapacityForecast) -> Self {
+        Self {
+            capacity: src.capacity,
+            used: src.used,
+            growth_rate: src.growth_rate,
+            samples: src.samples,
+            timestamp: humantime::format_rfc3339(src.timestamp).to_string(),
+            exhaustion: src
+                .exhaustion
+                .map(|time| humantime::format_rfc3339(time).to_string()),
+        }
+    }
+}
+
 bus_impl_string_id!(PoolId, "ID of a pool");
+bus_impl_string_uuid!(PoolUuid, "UUID of a pool");
 
 // online > degraded > unknown/faulted
 impl PartialOrd for PoolStatus {
@@ -132,6 +357,9 @@ pub struct Pool {
     spec: Option<PoolSpec>,
     /// Runtime state of the pool.
     state: Option<PoolState>,
+    /// Capacity forecast of the pool, based on its recent usage samples.
+    #[serde(default)]
+    forecast: Option<CapacityForecast>,
 }
 
 impl Pool {
@@ -141,6 +369,7 @@ impl Pool {
             id: spec.id.clone(),
             spec: Some(spec),
             state: Some(state),
+            forecast: None,
         }
     }
     /// Construct a new pool with spec but no state
@@ -149,6 +378,7 @@ impl Pool {
             id: spec.id.clone(),
             spec: Some(spec),
             state: None,
+            forecast: None,
         }
     }
     /// Construct a new pool with optional spec and state
@@ -157,6 +387,7 @@ impl Pool {
             id: state.id.clone(),
             spec,
             state: Some(state),
+            forecast: None,
         }
     }
     /// Try to construct a new pool from spec and state
@@ -180,6 +411,16 @@ impl Pool {
     pub fn state(&self) -> Option<PoolState> {
         self.state.clone()
     }
+    /// Set the capacity forecast of the pool.
+    #[must_use]
+    pub fn with_forecast(mut self, forecast: Option<CapacityForecast>) -> Self {
+        self.forecast = forecast;
+        self
+    }
+    /// Get the capacity forecast of the pool.
+    pub fn forecast(&self) -> Option<CapacityForecast> {
+        self.forecast.clone()
+    }
     /// Get the node identification
     pub fn node(&self) -> NodeId {
         match &self.spec {
@@ -252,6 +493,9 @@ pub struct CreatePool {
     pub disks: Vec<PoolDeviceUri>,
     /// labels to be set on the pool
     pub labels: Option<PoolLabel>,
+    /// percentage of the pool capacity kept free when placing new replicas, which overrides
+    /// the core agent's default
+    pub reserved_capacity_percent: Option<u8>,
 }
 
 impl CreatePool {
@@ -267,10 +511,54 @@ impl CreatePool {
             id: id.clone(),
             disks: disks.to_vec(),
             labels: labels.clone(),
+            reserved_capacity_percent: None,
         }
     }
 }
 
+/// Import Pool Request
+/// Imports a pre-existing pool from its disks, preserving its on-disk identity, rather than
+/// creating a new one
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPool {
+    /// id of the io-engine instance
+    pub node: NodeId,
+    /// id of the pool
+    pub id: PoolId,
+    /// uuid of the pool, which identifies it across nodes
+    pub uuid: PoolUuid,
+    /// disk device paths or URIs claimed by the pool
+    pub disks: Vec<PoolDeviceUri>,
+    /// labels to be set on the pool
+    pub labels: Option<PoolLabel>,
+}
+
+impl ImportPool {
+    /// Create new `Self` from the given parameters
+    pub fn new(
+        node: &NodeId,
+        id: &PoolId,
+        uuid: &PoolUuid,
+        disks: &[PoolDeviceUri],
+        labels: &Option<PoolLabel>,
+    ) -> Self {
+        Self {
+            node: node.clone(),
+            id: id.clone(),
+            uuid: uuid.clone(),
+            disks: disks.to_vec(),
+            labels: labels.clone(),
+        }
+    }
+}
+
+impl From<&ImportPool> for CreatePool {
+    fn from(request: &ImportPool) -> Self {
+        CreatePool::new(&request.node, &request.id, &request.disks, &request.labels)
+    }
+}
+
 /// Destroy Pool Request
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -280,3 +568,92 @@ pub struct DestroyPool {
     /// id of the pool
     pub id: PoolId,
 }
+
+/// Reconcile Pool Request
+/// Forces an immediate re-check of the pool state with the io-engine
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcilePool {
+    /// id of the pool
+    pub id: PoolId,
+}
+
+impl ReconcilePool {
+    /// Create new `Self` from the given pool id
+    pub fn new(id: &PoolId) -> Self {
+        Self { id: id.clone() }
+    }
+}
+
+/// Set Pool Maintenance Request
+/// A pool under maintenance is kept as it is, but no new replicas are placed on it
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPoolMaintenance {
+    /// id of the pool
+    pub id: PoolId,
+    /// whether the pool is under maintenance
+    pub maintenance: bool,
+}
+
+impl SetPoolMaintenance {
+    /// Create new `Self` from the given pool id and maintenance flag
+    pub fn new(id: &PoolId, maintenance: bool) -> Self {
+        Self {
+            id: id.clone(),
+            maintenance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_consistency() {
+        const MIB: u64 = 1024 * 1024;
+        let pool = PoolState {
+            node: "node-1".into(),
+            id: "pool-1".into(),
+            capacity: 1024 * MIB,
+            used: 200 * MIB,
+            ..Default::default()
+        };
+        let replica = |pool: &str, size: u64, thin: bool| Replica {
+            pool: pool.into(),
+            size,
+            thin,
+            ..Default::default()
+        };
+
+        // the used space matches the thick replicas, allowing for the pool metadata
+        let replicas = vec![
+            replica("pool-1", 100 * MIB, false),
+            replica("pool-1", 96 * MIB, false),
+            replica("pool-1", 500 * MIB, true),
+            replica("pool-2", 300 * MIB, false),
+        ];
+        let consistency = PoolConsistency::new(&pool, &replicas, POOL_CONSISTENCY_THRESHOLD);
+        assert_eq!(consistency.replicas, 3);
+        assert_eq!(consistency.replicas_size, 196 * MIB);
+        assert_eq!(consistency.discrepancy, 4 * MIB as i64);
+        assert!(consistency.consistent());
+
+        // space which is used without any replica to account for it is leaked
+        let consistency = PoolConsistency::new(&pool, &replicas[2 ..], POOL_CONSISTENCY_THRESHOLD);
+        assert_eq!(consistency.replicas, 1);
+        assert_eq!(consistency.discrepancy, 200 * MIB as i64);
+        assert!(!consistency.consistent());
+
+        // a tighter threshold doesn't allow for the pool metadata
+        let consistency = PoolConsistency::new(&pool, &replicas, 2 * MIB);
+        assert!(!consistency.consistent());
+
+        // replicas which don't use their space are flagged as well
+        let pool = PoolState { used: 0, ..pool };
+        let consistency = PoolConsistency::new(&pool, &replicas, POOL_CONSISTENCY_THRESHOLD);
+        assert_eq!(consistency.discrepancy, -(196 * MIB as i64));
+        assert!(!consistency.consistent());
+    }
+}
@@ -2,7 +2,7 @@ use super::*;
 
 use crate::{types::v0::store::nexus::ReplicaUri, IntoOption};
 use serde::{Deserialize, Serialize};
-use std::{convert::TryFrom, fmt::Debug, ops::Deref};
+use std::{collections::HashMap, convert::TryFrom, fmt::Debug, ops::Deref};
 use strum_macros::{EnumString, ToString};
 
 /// Get all the replicas from specific node and pool
@@ -21,6 +21,28 @@ impl GetReplicas {
     }
 }
 
+/// Get a single replica from a specific node and pool
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReplica {
+    /// id of the io-engine instance
+    pub node: NodeId,
+    /// id of the pool
+    pub pool: PoolId,
+    /// uuid of the replica
+    pub uuid: ReplicaId,
+}
+impl GetReplica {
+    /// Return new `Self` to fetch the replica `uuid` from the `pool` on the `node`
+    pub fn new(node: &NodeId, pool: &PoolId, uuid: &ReplicaId) -> Self {
+        Self {
+            node: node.clone(),
+            pool: pool.clone(),
+            uuid: uuid.clone(),
+        }
+    }
+}
+
 /// Replica information
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -43,6 +65,9 @@ pub struct Replica {
     pub uri: String,
     /// status of the replica
     pub status: ReplicaStatus,
+    /// last time the status of the replica was observed to change
+    #[serde(default)]
+    pub state_changed_at: Option<std::time::SystemTime>,
 }
 impl Replica {
     /// check if the replica is online
@@ -107,12 +132,14 @@ impl From<ReplicaName> for String {
 
 impl From<Replica> for models::Replica {
     fn from(src: Replica) -> Self {
-        Self::new(
+        Self::new_all(
             src.node,
             src.pool,
             src.share,
             src.size,
             src.status,
+            src.state_changed_at
+                .map(|t| humantime::format_rfc3339(t).to_string()),
             src.thin,
             src.uri,
             apis::Uuid::try_from(src.uuid).unwrap(),
@@ -130,6 +157,7 @@ impl From<Replica> for DestroyReplica {
             uuid: replica.uuid,
             name: replica.name.into(),
             disowners: Default::default(),
+            force: false,
         }
     }
 }
@@ -156,8 +184,74 @@ pub struct CreateReplica {
     pub managed: bool,
     /// Owners of the resource
     pub owners: ReplicaOwners,
+    /// Replica annotations
+    #[serde(default)]
+    pub annotations: ReplicaAnnotations,
+    /// At-rest encryption of the replica data
+    #[serde(default)]
+    pub encryption: Option<ReplicaEncryption>,
+}
+
+/// Cipher used to encrypt the replica data at rest
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, EnumString, ToString, Eq, PartialEq)]
+#[strum(serialize_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub enum EncryptionCipher {
+    /// AES in CBC mode
+    AesCbc = 0,
+    /// AES in XTS mode
+    AesXts = 1,
 }
 
+/// At-rest encryption parameters of a replica
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ReplicaEncryption {
+    /// cipher used to encrypt the data
+    pub cipher: EncryptionCipher,
+    /// reference to the encryption key, eg: the name of the key in a key store.
+    /// The key itself is never part of the request or of the spec.
+    pub key: String,
+}
+impl ReplicaEncryption {
+    /// Return new `Self` with the given cipher and key reference
+    pub fn new(cipher: EncryptionCipher, key: impl Into<String>) -> Self {
+        Self {
+            cipher,
+            key: key.into(),
+        }
+    }
+}
+impl From<EncryptionCipher> for models::EncryptionCipher {
+    fn from(src: EncryptionCipher) -> Self {
+        match src {
+            EncryptionCipher::AesCbc => Self::AesCbc,
+            EncryptionCipher::AesXts => Self::AesXts,
+        }
+    }
+}
+impl From<models::EncryptionCipher> for EncryptionCipher {
+    fn from(src: models::EncryptionCipher) -> Self {
+        match src {
+            models::EncryptionCipher::AesCbc => Self::AesCbc,
+            models::EncryptionCipher::AesXts => Self::AesXts,
+        }
+    }
+}
+impl From<ReplicaEncryption> for models::ReplicaEncryption {
+    fn from(src: ReplicaEncryption) -> Self {
+        Self::new(src.cipher, src.key)
+    }
+}
+impl From<models::ReplicaEncryption> for ReplicaEncryption {
+    fn from(src: models::ReplicaEncryption) -> Self {
+        Self::new(src.cipher.into(), src.key)
+    }
+}
+
+/// Free-form replica metadata, eg: owner or ticket id.
+/// Annotations are never used for scheduling.
+pub type ReplicaAnnotations = HashMap<String, String>;
+
 /// Replica owners which is a volume or none and a list of nexuses
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 pub struct ReplicaOwners {
@@ -201,6 +295,10 @@ impl ReplicaOwners {
             nexuses: vec![],
         }
     }
+    /// The replica is now part of the volume
+    pub fn set_volume_owner(&mut self, volume: &VolumeId) {
+        self.volume = Some(volume.clone());
+    }
     /// The replica is no longer part of the volume
     pub fn disowned_by_volume(&mut self) {
         let _ = self.volume.take();
@@ -261,6 +359,10 @@ pub struct DestroyReplica {
     pub name: Option<ReplicaName>,
     /// delete by owners
     pub disowners: ReplicaOwners,
+    /// disown the replica from all of its owners and destroy it even if an owner still
+    /// references it, eg: a stuck replica
+    #[serde(default)]
+    pub force: bool,
 }
 impl DestroyReplica {
     /// Return a new `Self` from the provided arguments
@@ -277,8 +379,15 @@ impl DestroyReplica {
             uuid: uuid.clone(),
             name: name.clone().into(),
             disowners: disowners.clone(),
+            force: false,
         }
     }
+    /// Force the destruction of the replica, regardless of its owners
+    #[must_use]
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
 }
 
 /// Share Replica Request
@@ -355,6 +464,73 @@ pub struct UnshareReplica {
     pub name: Option<ReplicaName>,
 }
 
+/// Resize Replica Request
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResizeReplica {
+    /// id of the io-engine instance
+    pub node: NodeId,
+    /// id of the pool
+    pub pool: PoolId,
+    /// uuid of the replica
+    pub uuid: ReplicaId,
+    /// name of the replica
+    pub name: Option<ReplicaName>,
+    /// requested new size of the replica in bytes
+    pub requested_size: u64,
+}
+
+impl ResizeReplica {
+    /// Create new `Self` to resize the given replica to `requested_size` bytes
+    pub fn new(replica: &Replica, requested_size: u64) -> Self {
+        Self {
+            node: replica.node.clone(),
+            pool: replica.pool.clone(),
+            uuid: replica.uuid.clone(),
+            name: replica.name.clone().into(),
+            requested_size,
+        }
+    }
+}
+
+/// Move Replica Request
+/// Moves a volume replica to another pool on the same node: a new replica is created on the
+/// destination pool and rebuilt by the volume nexus, after which the source replica is destroyed
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveReplica {
+    /// id of the io-engine instance
+    pub node: NodeId,
+    /// id of the pool which the replica is moved from
+    pub pool: PoolId,
+    /// id of the pool which the replica is moved to
+    pub destination_pool: PoolId,
+    /// uuid of the replica
+    pub uuid: ReplicaId,
+    /// move the replica even if it's the only healthy child of its nexus
+    #[serde(default)]
+    pub force: bool,
+}
+
+impl MoveReplica {
+    /// Create new `Self` to move the given replica to the `destination_pool`
+    pub fn new(replica: &Replica, destination_pool: &PoolId) -> Self {
+        Self {
+            node: replica.node.clone(),
+            pool: replica.pool.clone(),
+            destination_pool: destination_pool.clone(),
+            uuid: replica.uuid.clone(),
+            force: false,
+        }
+    }
+    /// Move the replica even if it's the only healthy child of its nexus
+    #[must_use]
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
 /// The protocol used to share the replica.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, EnumString, ToString, Eq, PartialEq)]
 #[strum(serialize_all = "camelCase")]
@@ -449,6 +625,9 @@ pub struct AddNexusReplica {
     pub replica: ReplicaUri,
     /// auto start rebuilding
     pub auto_rebuild: bool,
+    /// preferred healthy child to rebuild from, if any
+    #[serde(default)]
+    pub rebuild_source: Option<ChildUri>,
 }
 impl AddNexusReplica {
     /// Return new `Self` from it's properties
@@ -458,6 +637,7 @@ impl AddNexusReplica {
             nexus: nexus.clone(),
             replica: replica.clone(),
             auto_rebuild,
+            rebuild_source: None,
         }
     }
 }
@@ -470,6 +650,7 @@ impl From<&AddNexusReplica> for AddNexusChild {
             nexus: add.nexus,
             uri: add.replica.uri().clone(),
             auto_rebuild: add.auto_rebuild,
+            rebuild_source: add.rebuild_source,
         }
     }
 }
@@ -504,3 +685,46 @@ impl From<&RemoveNexusReplica> for RemoveNexusChild {
         }
     }
 }
+
+/// Check for replicas which exist on the io-engine nodes but which are not referenced by any
+/// spec, eg: after a failed destroy. Only replicas named by the control plane are considered.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckOrphanedReplicas {
+    /// destroy the orphaned replicas rather than only reporting them
+    pub destroy: bool,
+}
+impl CheckOrphanedReplicas {
+    /// Return new `Self`, destroying the orphaned replicas if `destroy` is set
+    pub fn new(destroy: bool) -> Self {
+        Self { destroy }
+    }
+}
+
+/// A replica which is not referenced by any spec
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedReplica {
+    /// the replica as reported by its io-engine
+    pub replica: Replica,
+    /// whether the replica has been destroyed
+    pub destroyed: bool,
+}
+impl From<OrphanedReplica> for models::OrphanedReplica {
+    fn from(src: OrphanedReplica) -> Self {
+        Self::new_all(src.replica, src.destroyed)
+    }
+}
+
+/// The orphaned replicas found by a `CheckOrphanedReplicas` request
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedReplicas {
+    /// the orphaned replicas
+    pub entries: Vec<OrphanedReplica>,
+}
+impl From<OrphanedReplicas> for Vec<models::OrphanedReplica> {
+    fn from(src: OrphanedReplicas) -> Self {
+        src.entries.into_iter().map(Into::into).collect()
+    }
+}
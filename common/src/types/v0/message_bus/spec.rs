@@ -23,6 +23,52 @@ pub struct Specs {
     pub replicas: Vec<replica::ReplicaSpec>,
 }
 
+impl Specs {
+    /// Split the specs into chunks of at most `chunk_size` specs of a single resource kind.
+    /// The chunks are ordered by resource kind and preserve the order of the specs within each.
+    pub fn into_chunks(self, chunk_size: usize) -> Vec<Specs> {
+        fn split<T>(mut specs: Vec<T>, chunk_size: usize) -> Vec<Vec<T>> {
+            let mut chunks = vec![];
+            while !specs.is_empty() {
+                let rest = specs.split_off(chunk_size.min(specs.len()));
+                chunks.push(std::mem::replace(&mut specs, rest));
+            }
+            chunks
+        }
+        let chunk_size = chunk_size.max(1);
+        let mut chunks = vec![];
+        chunks.extend(
+            split(self.volumes, chunk_size)
+                .into_iter()
+                .map(|volumes| Self {
+                    volumes,
+                    ..Default::default()
+                }),
+        );
+        chunks.extend(
+            split(self.nexuses, chunk_size)
+                .into_iter()
+                .map(|nexuses| Self {
+                    nexuses,
+                    ..Default::default()
+                }),
+        );
+        chunks.extend(split(self.pools, chunk_size).into_iter().map(|pools| Self {
+            pools,
+            ..Default::default()
+        }));
+        chunks.extend(
+            split(self.replicas, chunk_size)
+                .into_iter()
+                .map(|replicas| Self {
+                    replicas,
+                    ..Default::default()
+                }),
+        );
+        chunks
+    }
+}
+
 impl From<Specs> for models::Specs {
     fn from(src: Specs) -> Self {
         Self::new(src.nexuses, src.pools, src.replicas, src.volumes)
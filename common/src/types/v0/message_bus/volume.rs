@@ -65,6 +65,9 @@ pub struct VolumeState {
     pub target: Option<Nexus>,
     /// replica topology information
     pub replica_topology: HashMap<ReplicaId, ReplicaTopology>,
+    /// last time the target state was successfully refreshed from its node
+    #[serde(default)]
+    pub target_refreshed: Option<std::time::SystemTime>,
 }
 
 impl From<VolumeState> for models::VolumeState {
@@ -79,6 +82,9 @@ impl From<VolumeState> for models::VolumeState {
                 .iter()
                 .map(|(k, v)| (k.into(), v.into()))
                 .collect(),
+            target_refreshed: volume
+                .target_refreshed
+                .map(|t| humantime::format_rfc3339(t).to_string()),
         }
     }
 }
@@ -100,6 +106,57 @@ impl VolumeState {
     }
 }
 
+/// Rebuild progress of the children of the volume target
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeRebuildProgress {
+    /// children of the volume target
+    pub children: Vec<Child>,
+}
+
+impl VolumeRebuildProgress {
+    /// Create a new `Self` from the volume state
+    /// An unpublished volume has no target and therefore no children to rebuild.
+    pub fn new(state: &VolumeState) -> Self {
+        Self {
+            children: state
+                .target
+                .as_ref()
+                .map(|nexus| nexus.children.clone())
+                .unwrap_or_default(),
+        }
+    }
+    /// Check if any child is being rebuilt
+    pub fn rebuilding(&self) -> bool {
+        self.children.iter().any(|c| c.rebuild_progress.is_some())
+    }
+    /// Overall rebuild progress (%), averaged across all children being rebuilt
+    /// When no child is being rebuilt there's nothing left to rebuild, so it's 100%.
+    pub fn progress(&self) -> u8 {
+        let progress = self
+            .children
+            .iter()
+            .filter_map(|c| c.rebuild_progress)
+            .map(u32::from)
+            .collect::<Vec<_>>();
+        if progress.is_empty() {
+            100
+        } else {
+            (progress.iter().sum::<u32>() / progress.len() as u32) as u8
+        }
+    }
+}
+
+impl From<VolumeRebuildProgress> for models::VolumeRebuildProgress {
+    fn from(src: VolumeRebuildProgress) -> Self {
+        Self {
+            progress: src.progress(),
+            rebuilding: src.rebuilding(),
+            children: src.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 impl From<(&VolumeId, &Nexus)> for VolumeState {
     fn from(src: (&VolumeId, &Nexus)) -> Self {
         let uuid = src.0.clone();
@@ -110,6 +167,7 @@ impl From<(&VolumeId, &Nexus)> for VolumeState {
             status: nexus.status.clone(),
             target: Some(nexus.clone()),
             replica_topology: HashMap::new(),
+            target_refreshed: None,
         }
     }
 }
@@ -180,6 +238,14 @@ impl From<LabelledTopology> for models::LabelledTopology {
 pub struct Topology {
     pub node: Option<NodeTopology>,
     pub pool: Option<PoolTopology>,
+    /// Node label key whose distinct values are the failure domains the replicas are spread
+    /// across, eg: "topology.kubernetes.io/zone"
+    #[serde(default)]
+    pub spread_topology_key: Option<String>,
+    /// Pools to try first when placing the replicas, in order of preference.
+    /// The scheduler falls back to the other suitable pools if these can't be used.
+    #[serde(default)]
+    pub preferred_pools: Vec<PoolId>,
 }
 impl Topology {
     /// Get a reference to the explicit topology
@@ -189,7 +255,13 @@ impl Topology {
 }
 impl From<Topology> for models::Topology {
     fn from(src: Topology) -> Self {
-        Self::new_all(src.node.into_opt(), src.pool.into_opt())
+        Self::new_all(
+            src.node.into_opt(),
+            src.pool.into_opt(),
+            src.spread_topology_key,
+            (!src.preferred_pools.is_empty())
+                .then(|| src.preferred_pools.into_iter().map(From::from).collect()),
+        )
     }
 }
 impl From<models::Topology> for Topology {
@@ -197,6 +269,13 @@ impl From<models::Topology> for Topology {
         Self {
             node: src.node_topology.into_opt(),
             pool: src.pool_topology.into_opt(),
+            spread_topology_key: src.spread_topology_key,
+            preferred_pools: src
+                .preferred_pools
+                .into_iter()
+                .flatten()
+                .map(From::from)
+                .collect(),
         }
     }
 }
@@ -359,11 +438,27 @@ pub struct CreateVolume {
     pub topology: Option<Topology>,
     /// volume labels
     pub labels: Option<VolumeLabels>,
+    /// volume annotations
+    #[serde(default)]
+    pub annotations: VolumeAnnotations,
+    /// at-rest encryption of the data of all the volume replicas
+    #[serde(default)]
+    pub encryption: Option<ReplicaEncryption>,
+    /// key which makes retries of the creation safe
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// snapshot whose replicas are cloned to provision the volume
+    #[serde(default)]
+    pub source_snapshot: Option<SnapshotId>,
 }
 
 /// Volume label information
 pub type VolumeLabels = HashMap<String, String>;
 
+/// Free-form volume metadata, eg: owner or ticket id.
+/// Unlike the labels, annotations are never used for scheduling.
+pub type VolumeAnnotations = HashMap<String, String>;
+
 impl CreateVolume {
     /// explicitly selected allowed_nodes
     pub fn allowed_nodes(&self) -> Vec<NodeId> {
@@ -409,6 +504,15 @@ pub struct PublishVolume {
     pub target_node: Option<NodeId>,
     /// share protocol
     pub share: Option<VolumeShareProtocol>,
+    /// NVMe-oF transport parameters used to create the volume target
+    #[serde(default)]
+    pub transport_params: NvmfTransportParams,
+    /// publish the volume read-only, eg: for many readers of a shared dataset
+    #[serde(default)]
+    pub read_only: bool,
+    /// allow publishing read-write a volume which is currently published read-only
+    #[serde(default)]
+    pub force_read_write: bool,
 }
 impl PublishVolume {
     /// Create new `PublishVolume` based on the provided arguments
@@ -417,6 +521,45 @@ impl PublishVolume {
         target_node: Option<NodeId>,
         share: Option<VolumeShareProtocol>,
     ) -> Self {
+        Self {
+            uuid,
+            target_node,
+            share,
+            transport_params: NvmfTransportParams::new(),
+            read_only: false,
+            force_read_write: false,
+        }
+    }
+    /// Return `Self` with the given NVMe-oF transport parameters
+    pub fn with_transport_params(mut self, transport_params: NvmfTransportParams) -> Self {
+        self.transport_params = transport_params;
+        self
+    }
+    /// Return `Self` publishing the volume read-only or read-write, overriding a current
+    /// read-only target if `force_read_write` is set
+    pub fn with_read_only(mut self, read_only: bool, force_read_write: bool) -> Self {
+        self.read_only = read_only;
+        self.force_read_write = force_read_write;
+        self
+    }
+}
+
+/// Republish a published volume on a different node
+/// Creates and shares a new nexus on the given node and only then destroys the current nexus,
+/// so that the front-end IO can fail over to the new target with minimal interruption.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RepublishVolume {
+    /// uuid of the volume
+    pub uuid: VolumeId,
+    /// the node where front-end IO will be sent to
+    pub target_node: NodeId,
+    /// share protocol, defaults to the protocol of the current target
+    pub share: Option<VolumeShareProtocol>,
+}
+impl RepublishVolume {
+    /// Create new `RepublishVolume` based on the provided arguments
+    pub fn new(uuid: VolumeId, target_node: NodeId, share: Option<VolumeShareProtocol>) -> Self {
         Self {
             uuid,
             target_node,
@@ -496,6 +639,126 @@ impl SetVolumeReplica {
     }
 }
 
+/// Add an existing replica to the volume, increasing the volume replica count by 1
+/// The replica must not be owned by another volume and must be large enough for the volume.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AddVolumeReplica {
+    /// uuid of the volume
+    pub uuid: VolumeId,
+    /// uuid of the replica
+    pub replica: ReplicaId,
+}
+impl AddVolumeReplica {
+    /// Create new `Self` based on the provided arguments
+    pub fn new(uuid: VolumeId, replica: ReplicaId) -> Self {
+        Self { uuid, replica }
+    }
+}
+
+/// Remove a replica from the volume, decreasing the volume replica count by 1
+/// The replica is removed from the volume target, if any, and destroyed.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveVolumeReplica {
+    /// uuid of the volume
+    pub uuid: VolumeId,
+    /// uuid of the replica
+    pub replica: ReplicaId,
+}
+impl RemoveVolumeReplica {
+    /// Create new `Self` based on the provided arguments
+    pub fn new(uuid: VolumeId, replica: ReplicaId) -> Self {
+        Self { uuid, replica }
+    }
+}
+
+/// Set the volume labels and, optionally, its annotations, either replacing or merging into
+/// the existing ones.
+/// Neither the labels nor the annotations are used for the replica placement, which is driven
+/// by the volume topology, so the volume target and replicas are left alone.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetVolumeLabels {
+    /// uuid of the volume
+    pub uuid: VolumeId,
+    /// new labels of the volume
+    pub labels: VolumeLabels,
+    /// new annotations of the volume, if they're to be changed
+    #[serde(default)]
+    pub annotations: Option<VolumeAnnotations>,
+    /// merge the new labels and annotations into the existing ones, overwriting any existing
+    /// values of the same keys, rather than replacing them altogether
+    #[serde(default)]
+    pub merge: bool,
+}
+impl SetVolumeLabels {
+    /// Create new `Self` based on the provided arguments
+    pub fn new(uuid: VolumeId, labels: VolumeLabels) -> Self {
+        Self {
+            uuid,
+            labels,
+            annotations: None,
+            merge: false,
+        }
+    }
+    /// Also set the volume annotations
+    #[must_use]
+    pub fn with_annotations(mut self, annotations: VolumeAnnotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+    /// Merge into the existing labels and annotations rather than replacing them
+    #[must_use]
+    pub fn with_merge(mut self, merge: bool) -> Self {
+        self.merge = merge;
+        self
+    }
+    /// Get the resulting labels and annotations when applied to the current ones
+    pub fn apply(
+        &self,
+        labels: Option<&VolumeLabels>,
+        annotations: &VolumeAnnotations,
+    ) -> (VolumeLabels, VolumeAnnotations) {
+        let merge = |current: Option<&HashMap<String, String>>, new: &HashMap<String, String>| {
+            let mut merged = current.cloned().unwrap_or_default();
+            merged.extend(new.clone());
+            merged
+        };
+        match self.merge {
+            true => (
+                merge(labels, &self.labels),
+                match &self.annotations {
+                    Some(new) => merge(Some(annotations), new),
+                    None => annotations.clone(),
+                },
+            ),
+            false => (
+                self.labels.clone(),
+                self.annotations
+                    .clone()
+                    .unwrap_or_else(|| annotations.clone()),
+            ),
+        }
+    }
+}
+
+/// Expand the volume size
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpandVolume {
+    /// uuid of the volume
+    pub uuid: VolumeId,
+    /// new size of the volume in bytes
+    pub size: u64,
+}
+impl ExpandVolume {
+    /// Create new `Self` based on the provided arguments
+    pub fn new(uuid: VolumeId, size: u64) -> Self {
+        Self { uuid, size }
+    }
+}
+
 /// Delete volume
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -516,6 +779,112 @@ impl DestroyVolume {
     }
 }
 
+bus_impl_string_uuid!(SnapshotId, "UUID of a volume snapshot");
+
+/// Create a snapshot of a volume
+/// The snapshot is taken through the volume target, so the volume must be published.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSnapshot {
+    /// uuid of the volume
+    pub volume_id: VolumeId,
+    /// uuid of the snapshot
+    pub snapshot_id: SnapshotId,
+}
+impl CreateSnapshot {
+    /// Create new `Self` based on the provided arguments
+    pub fn new(volume_id: VolumeId, snapshot_id: SnapshotId) -> Self {
+        Self {
+            volume_id,
+            snapshot_id,
+        }
+    }
+}
+
+/// List the snapshots of a volume
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSnapshots {
+    /// uuid of the volume
+    pub volume_id: VolumeId,
+}
+impl ListSnapshots {
+    /// Create new `Self` to list the snapshots of the given volume
+    pub fn new(volume_id: VolumeId) -> Self {
+        Self { volume_id }
+    }
+}
+
+/// Delete a snapshot of a volume
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSnapshot {
+    /// uuid of the volume
+    pub volume_id: VolumeId,
+    /// uuid of the snapshot
+    pub snapshot_id: SnapshotId,
+}
+impl DeleteSnapshot {
+    /// Create new `Self` based on the provided arguments
+    pub fn new(volume_id: VolumeId, snapshot_id: SnapshotId) -> Self {
+        Self {
+            volume_id,
+            snapshot_id,
+        }
+    }
+}
+
+/// Snapshot of a volume
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeSnapshot {
+    /// uuid of the snapshot
+    pub snapshot_id: SnapshotId,
+    /// uuid of the snapshotted volume
+    pub volume_id: VolumeId,
+    /// name of the snapshot on the data plane
+    pub name: String,
+    /// size of the volume when the snapshot was taken
+    pub size: u64,
+    /// when the snapshot was taken
+    pub timestamp: std::time::SystemTime,
+}
+impl Default for VolumeSnapshot {
+    fn default() -> Self {
+        Self {
+            snapshot_id: Default::default(),
+            volume_id: Default::default(),
+            name: Default::default(),
+            size: Default::default(),
+            timestamp: std::time::UNIX_EPOCH,
+        }
+    }
+}
+impl From<VolumeSnapshot> for models::VolumeSnapshot {
+    fn from(src: VolumeSnapshot) -> Self {
+        Self::new_all(
+            src.name,
+            src.size,
+            src.snapshot_id,
+            humantime::format_rfc3339(src.timestamp).to_string(),
+            src.volume_id,
+        )
+    }
+}
+
+/// Snapshots of a volume
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeSnapshots {
+    /// the snapshots, oldest first
+    pub entries: Vec<VolumeSnapshot>,
+}
+impl From<VolumeSnapshots> for Vec<models::VolumeSnapshot> {
+    fn from(src: VolumeSnapshots) -> Self {
+        src.entries.into_iter().map(From::from).collect()
+    }
+}
+
 /// Replica topology information
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -559,3 +928,46 @@ impl From<&ReplicaTopology> for models::ReplicaTopology {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_rebuild_progress() {
+        // an unpublished volume has nothing to rebuild
+        let progress = VolumeRebuildProgress::new(&VolumeState::default());
+        assert!(progress.children.is_empty());
+        assert!(!progress.rebuilding());
+        assert_eq!(progress.progress(), 100);
+
+        let child = |uri: &str, state: ChildState, rebuild_progress: Option<u8>| Child {
+            uri: uri.into(),
+            state,
+            rebuild_progress,
+            state_changed_at: None,
+        };
+        let state = VolumeState {
+            target: Some(Nexus {
+                children: vec![
+                    child("malloc:///healthy", ChildState::Online, None),
+                    child("malloc:///rebuild-1", ChildState::Degraded, Some(20)),
+                    child("malloc:///rebuild-2", ChildState::Degraded, Some(70)),
+                ],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // the progress is averaged across the children being rebuilt only
+        let progress = VolumeRebuildProgress::new(&state);
+        assert_eq!(progress.children.len(), 3);
+        assert!(progress.rebuilding());
+        assert_eq!(progress.progress(), 45);
+
+        let model = models::VolumeRebuildProgress::from(progress);
+        assert!(model.rebuilding);
+        assert_eq!(model.progress, 45);
+        assert_eq!(model.children[1].rebuild_progress, Some(20));
+    }
+}
@@ -62,6 +62,44 @@ pub enum StoreError {
     FailedLock { reason: String },
     #[snafu(display("Etcd is not ready, reason: '{}'", reason))]
     NotReady { reason: String },
+    /// Failed to load the TLS configuration of the store connections.
+    #[snafu(display("Invalid store TLS configuration, reason: '{}'", reason))]
+    InvalidTls { reason: String },
+    /// The revision of the entry did not match the expected revision.
+    #[snafu(display(
+        "Revision mismatch for key '{}', expected '{:?}' but found '{:?}'",
+        key,
+        expected,
+        actual
+    ))]
+    RevisionMismatch {
+        key: String,
+        expected: Option<i64>,
+        actual: Option<i64>,
+    },
+}
+
+impl StoreError {
+    /// Check if the error is due to a transient connection failure, such as while etcd is
+    /// electing a new leader, in which case the operation may be retried
+    pub fn transient(&self) -> bool {
+        match self {
+            StoreError::Connect { source }
+            | StoreError::Put { source, .. }
+            | StoreError::Get { source, .. }
+            | StoreError::GetPrefix { source, .. }
+            | StoreError::Delete { source, .. }
+            | StoreError::Watch { source, .. } => match source {
+                Error::TransportError(_) | Error::IoError(_) => true,
+                Error::GRpcStatus(status) => matches!(
+                    status.code(),
+                    tonic::Code::Unavailable | tonic::Code::DeadlineExceeded
+                ),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
 }
 
 /// Representation of a watch event.
@@ -69,8 +107,18 @@ pub enum StoreError {
 pub enum WatchEvent {
     // Put operation containing the key and value
     Put(String, Value),
-    // Delete operation
-    Delete,
+    // Delete operation containing the key
+    Delete(String),
+}
+
+impl WatchEvent {
+    /// Get the key of the entry which triggered the event.
+    pub fn key(&self) -> &str {
+        match self {
+            WatchEvent::Put(key, _) => key,
+            WatchEvent::Delete(key) => key,
+        }
+    }
 }
 
 /// Store keys type trait
@@ -102,6 +150,15 @@ pub trait Store: Sync + Send + Clone {
 
     async fn put_obj<O: StorableObject>(&mut self, object: &O) -> Result<(), StoreError>;
 
+    /// Put the object into the store only if its current revision matches the
+    /// `expected_revision`, where `None` means the object must not exist yet.
+    /// Returns the store revision of the write.
+    async fn put_obj_cas<O: StorableObject>(
+        &mut self,
+        object: &O,
+        expected_revision: Option<i64>,
+    ) -> Result<i64, StoreError>;
+
     async fn get_obj<O: StorableObject>(&mut self, _key: &O::Key) -> Result<O, StoreError>;
 
     /// Returns a vector of tuples. Each tuple represents a key-value pair.
@@ -110,8 +167,24 @@ pub trait Store: Sync + Send + Clone {
         key_prefix: &str,
     ) -> Result<Vec<(String, Value)>, StoreError>;
 
+    /// Returns a page of at most `limit` key-value pairs with the given key prefix, starting
+    /// from `start_key` (or from the start of the prefix if `None`).
+    /// The second element is the start key of the next page, if there are more entries.
+    async fn get_values_prefix_paged(
+        &mut self,
+        key_prefix: &str,
+        limit: i64,
+        start_key: Option<String>,
+    ) -> Result<(Vec<(String, Value)>, Option<String>), StoreError>;
+
     async fn watch_obj<K: ObjectKey>(&mut self, key: &K) -> Result<StoreWatchReceiver, StoreError>;
 
+    /// Watch for changes to all entries whose key starts with the given prefix.
+    /// A single watcher is used for all entries and the events are multiplexed onto the
+    /// returned channel, each tagged with the key of the entry which changed.
+    /// Unlike the single key watches, this does not stop when an entry is deleted.
+    async fn watch_prefix(&mut self, key_prefix: &str) -> Result<StoreWatchReceiver, StoreError>;
+
     async fn online(&mut self) -> bool;
 }
 
@@ -152,6 +225,7 @@ pub enum StorableObjectType {
     ReplicaSpec,
     VolumeSpec,
     VolumeState,
+    VolumeSnapshot,
     ChildSpec,
     ChildState,
     CoreRegistryConfig,
@@ -173,7 +247,7 @@ pub fn build_key_prefix(
     let api_version = 0;
     format!(
         "{}/apis/v{}/clusters/{}/namespaces/{}",
-        crate::ETCD_KEY_PREFIX,
+        crate::etcd_key_prefix(),
         api_version,
         cluster_uid.uid(),
         namespace
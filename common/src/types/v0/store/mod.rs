@@ -7,6 +7,7 @@ pub mod node;
 pub mod pool;
 pub mod registry;
 pub mod replica;
+pub mod snapshot;
 pub mod volume;
 pub mod watch;
 
@@ -94,6 +95,10 @@ impl OperationSequence {
             state: Default::default(),
         }
     }
+    /// Check if an operation is in progress, ie: an operation guard is held
+    pub fn busy(&self) -> bool {
+        self.state != OperationSequenceState::Idle
+    }
 }
 
 /// Sequence operations
@@ -3,7 +3,7 @@
 use crate::types::v0::{
     message_bus::{
         self, ChildState, ChildUri, CreateNexus, DestroyNexus, Nexus as MbusNexus, NexusId,
-        NexusShareProtocol, NodeId, Protocol, ReplicaId, VolumeId,
+        NexusNvmfConfig, NexusShareProtocol, NodeId, Protocol, ReplicaId, VolumeId,
     },
     openapi::models,
     store::{
@@ -102,6 +102,9 @@ pub struct NexusSpec {
     pub sequencer: OperationSequence,
     /// Record of the operation in progress
     pub operation: Option<NexusOperationState>,
+    /// Nvmf target configuration the nexus was created with
+    #[serde(default)]
+    pub config: Option<NexusNvmfConfig>,
 }
 impl NexusSpec {
     /// Check if the spec contains the provided replica by it's `ReplicaId`
@@ -170,7 +173,7 @@ impl From<&NexusSpec> for CreateNexus {
             &spec.children,
             spec.managed,
             spec.owner.as_ref(),
-            None,
+            spec.config.clone(),
         )
     }
 }
@@ -263,6 +266,7 @@ impl SpecTransaction<NexusOperation> for NexusSpec {
                 }
                 NexusOperation::AddChild(uri) => self.children.push(uri),
                 NexusOperation::RemoveChild(uri) => self.children.retain(|c| c != &uri),
+                NexusOperation::Resize(size) => self.size = size,
             }
         }
         self.clear_op();
@@ -295,6 +299,7 @@ pub enum NexusOperation {
     Unshare,
     AddChild(NexusChild),
     RemoveChild(NexusChild),
+    Resize(u64),
 }
 
 /// Key used by the store to uniquely identify a NexusSpec structure.
@@ -338,6 +343,7 @@ impl From<&CreateNexus> for NexusSpec {
             owner: request.owner.clone(),
             sequencer: OperationSequence::new(request.uuid.clone()),
             operation: None,
+            config: request.config.clone(),
         }
     }
 }
@@ -371,6 +377,7 @@ impl From<&NexusSpec> for message_bus::Nexus {
                     uri: child.uri(),
                     state: ChildState::Unknown,
                     rebuild_progress: None,
+                    state_changed_at: None,
                 })
                 .collect(),
             device_uri: "".to_string(),
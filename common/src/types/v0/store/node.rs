@@ -1,7 +1,7 @@
 //! Definition of node types that can be saved to the persistent store.
 
 use crate::types::v0::{
-    message_bus::{self, NodeId},
+    message_bus::{self, NodeId, ReplicaId, VolumeId},
     openapi::models,
     store::{
         definitions::{ObjectKey, StorableObject, StorableObjectType},
@@ -9,7 +9,7 @@ use crate::types::v0::{
     },
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 pub type NodeLabels = HashMap<String, String>;
 
@@ -34,7 +34,69 @@ pub struct NodeSpec {
     endpoint: String,
     /// Node labels.
     labels: NodeLabels,
+    /// Cordon labels: a node with any cordon label is unschedulable for new resources.
+    #[serde(default)]
+    cordon_labels: Vec<String>,
+    /// Drain state of the node, if a drain was requested.
+    #[serde(default)]
+    drain_state: Option<NodeDrainState>,
+    /// Moves in progress of volume replicas away from this node.
+    #[serde(default)]
+    replica_moves: Vec<ReplicaMove>,
+    /// Communication timeouts which override the defaults of the core agent for this node.
+    #[serde(default)]
+    timeouts: NodeTimeouts,
 }
+
+/// Node communication timeouts, where each one which is not set falls back to the default
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeTimeouts {
+    /// gRPC connection timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect: Option<Duration>,
+    /// gRPC request timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<Duration>,
+}
+impl NodeTimeouts {
+    /// Return a new `Self`
+    pub fn new(connect: Option<Duration>, request: Option<Duration>) -> Self {
+        Self { connect, request }
+    }
+}
+
+/// A replica move which is in progress: the `target` replica has been added to the volume nexus
+/// and, once it's rebuilt, the `source` replica is removed from the nexus and destroyed
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicaMove {
+    /// the volume whose replica is being moved
+    pub volume: VolumeId,
+    /// the replica being moved away, eg: from an over utilized pool
+    pub source: ReplicaId,
+    /// the replica which is replacing the `source` replica
+    pub target: ReplicaId,
+}
+
+/// Drain state of a node
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum NodeDrainState {
+    /// The volume replicas are being moved away from the node.
+    Draining,
+    /// All the volume replicas have been moved away from the node.
+    Drained,
+}
+
+impl From<NodeDrainState> for models::NodeDrainState {
+    fn from(src: NodeDrainState) -> Self {
+        match src {
+            NodeDrainState::Draining => Self::Draining,
+            NodeDrainState::Drained => Self::Drained,
+        }
+    }
+}
+
 impl NodeSpec {
     /// Return a new `Self`
     pub fn new(id: NodeId, endpoint: String, labels: NodeLabels) -> Self {
@@ -42,6 +104,10 @@ impl NodeSpec {
             id,
             endpoint,
             labels,
+            cordon_labels: vec![],
+            drain_state: None,
+            replica_moves: vec![],
+            timeouts: NodeTimeouts::default(),
         }
     }
     /// Node identification
@@ -60,11 +126,89 @@ impl NodeSpec {
     pub fn set_endpoint(&mut self, endpoint: String) {
         self.endpoint = endpoint
     }
+    /// Node cordon labels
+    pub fn cordon_labels(&self) -> &Vec<String> {
+        &self.cordon_labels
+    }
+    /// Whether the node is cordoned, ie: new resources must not be placed on it
+    pub fn cordoned(&self) -> bool {
+        !self.cordon_labels.is_empty()
+    }
+    /// Cordon the node with the given label, returning false if it was already set
+    pub fn cordon(&mut self, label: String) -> bool {
+        if self.cordon_labels.contains(&label) {
+            false
+        } else {
+            self.cordon_labels.push(label);
+            true
+        }
+    }
+    /// Remove the given cordon label from the node, returning false if it wasn't set
+    /// Once the node has no cordon labels left it's no longer drained either.
+    pub fn uncordon(&mut self, label: &str) -> bool {
+        let count = self.cordon_labels.len();
+        self.cordon_labels.retain(|l| l != label);
+        if !self.cordoned() {
+            self.drain_state = None;
+        }
+        count != self.cordon_labels.len()
+    }
+    /// Node drain state
+    pub fn drain_state(&self) -> Option<&NodeDrainState> {
+        self.drain_state.as_ref()
+    }
+    /// Whether the node is being drained
+    pub fn draining(&self) -> bool {
+        self.drain_state == Some(NodeDrainState::Draining)
+    }
+    /// Set the node drain state
+    pub fn set_drain_state(&mut self, drain_state: Option<NodeDrainState>) {
+        self.drain_state = drain_state;
+    }
+    /// Moves in progress of volume replicas away from the node
+    pub fn replica_moves(&self) -> &Vec<ReplicaMove> {
+        &self.replica_moves
+    }
+    /// Add a move in progress of a volume replica away from the node
+    pub fn add_replica_move(&mut self, replica_move: ReplicaMove) {
+        self.replica_moves.push(replica_move);
+    }
+    /// Remove the move in progress of the given volume's replica, returning false if there's none
+    pub fn remove_replica_move(&mut self, volume: &VolumeId) -> bool {
+        let count = self.replica_moves.len();
+        self.replica_moves.retain(|m| &m.volume != volume);
+        count != self.replica_moves.len()
+    }
+    /// Node communication timeouts overrides
+    pub fn timeouts(&self) -> &NodeTimeouts {
+        &self.timeouts
+    }
+    /// Set the node communication timeouts overrides, returning false if they're unchanged
+    pub fn set_timeouts(&mut self, timeouts: NodeTimeouts) -> bool {
+        if self.timeouts == timeouts {
+            false
+        } else {
+            self.timeouts = timeouts;
+            true
+        }
+    }
 }
 
 impl From<NodeSpec> for models::NodeSpec {
     fn from(src: NodeSpec) -> Self {
-        Self::new(src.endpoint, src.id)
+        let cordon_labels = (!src.cordon_labels.is_empty()).then(|| src.cordon_labels);
+        Self::new_all(
+            src.endpoint,
+            src.id,
+            cordon_labels,
+            src.drain_state.map(Into::into),
+            src.timeouts
+                .connect
+                .map(|timeout| timeout.as_millis() as u64),
+            src.timeouts
+                .request
+                .map(|timeout| timeout.as_millis() as u64),
+        )
     }
 }
 
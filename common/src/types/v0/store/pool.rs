@@ -1,7 +1,7 @@
 //! Definition of pool types that can be saved to the persistent store.
 
 use crate::types::v0::{
-    message_bus::{self, CreatePool, NodeId, PoolDeviceUri, PoolId},
+    message_bus::{self, CreatePool, NodeId, PoolDeviceUri, PoolId, PoolUuid},
     openapi::models,
     store::{
         definitions::{ObjectKey, StorableObject, StorableObjectType},
@@ -55,6 +55,9 @@ impl From<&CreatePool> for PoolSpec {
             disks: request.disks.clone(),
             status: PoolSpecStatus::Creating,
             labels: request.labels.clone(),
+            maintenance: false,
+            reserved_capacity_percent: request.reserved_capacity_percent,
+            uuid: None,
             sequencer: OperationSequence::new(request.id.clone()),
             operation: None,
         }
@@ -65,6 +68,8 @@ impl PartialEq<CreatePool> for PoolSpec {
         let mut other = PoolSpec::from(other);
         other.status = self.status.clone();
         other.sequencer = self.sequencer.clone();
+        other.maintenance = self.maintenance;
+        other.uuid = self.uuid.clone();
         &other == self
     }
 }
@@ -82,6 +87,15 @@ pub struct PoolSpec {
     pub status: PoolSpecStatus,
     /// labels to be set on the pool
     pub labels: Option<PoolLabel>,
+    /// the pool is under maintenance: no new replicas are placed on it
+    #[serde(default)]
+    pub maintenance: bool,
+    /// percentage of the pool capacity kept free when placing new replicas, which overrides
+    /// the core agent's default
+    pub reserved_capacity_percent: Option<u8>,
+    /// uuid of the pool, if it was imported by its uuid, which identifies it across nodes
+    #[serde(default)]
+    pub uuid: Option<PoolUuid>,
     /// Update in progress
     #[serde(skip)]
     pub sequencer: OperationSequence,
@@ -123,7 +137,16 @@ impl ResourceUuid for PoolSpec {
 
 impl From<PoolSpec> for models::PoolSpec {
     fn from(src: PoolSpec) -> Self {
-        Self::new_all(src.disks, src.id, src.labels, src.node, src.status)
+        Self::new_all(
+            src.disks,
+            src.id,
+            src.labels,
+            src.maintenance,
+            src.node,
+            src.reserved_capacity_percent,
+            src.status,
+            src.uuid.map(Into::into),
+        )
     }
 }
 
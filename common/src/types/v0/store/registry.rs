@@ -14,6 +14,36 @@ pub struct CoreRegistryConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     mayastor_compat_v1: Option<bool>,
+    /// Maximum number of system-wide rebuilds set at runtime, which overrides the one the
+    /// core agent was started with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    max_rebuilds: Option<MaxRebuilds>,
+}
+
+/// Maximum number of system-wide rebuilds
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MaxRebuilds {
+    /// Do not limit the number of rebuilds
+    Unlimited,
+    /// At most this many rebuilds at any given time
+    Limited(u32),
+}
+impl From<MaxRebuilds> for Option<u32> {
+    fn from(src: MaxRebuilds) -> Self {
+        match src {
+            MaxRebuilds::Unlimited => None,
+            MaxRebuilds::Limited(max) => Some(max),
+        }
+    }
+}
+impl From<Option<u32>> for MaxRebuilds {
+    fn from(src: Option<u32>) -> Self {
+        match src {
+            None => Self::Unlimited,
+            Some(max) => Self::Limited(max),
+        }
+    }
 }
 
 impl CoreRegistryConfig {
@@ -23,12 +53,21 @@ impl CoreRegistryConfig {
             id: CoreRegistryConfigKey::default(),
             registration,
             mayastor_compat_v1: None,
+            max_rebuilds: None,
         }
     }
     /// Get the `mayastor_compat_v1`.
     pub fn mayastor_compat_v1(&self) -> bool {
         self.mayastor_compat_v1.unwrap_or(false)
     }
+    /// Get the `max_rebuilds` override, if it was ever set at runtime.
+    pub fn max_rebuilds(&self) -> Option<MaxRebuilds> {
+        self.max_rebuilds
+    }
+    /// Override the `max_rebuilds`.
+    pub fn set_max_rebuilds(&mut self, max_rebuilds: MaxRebuilds) {
+        self.max_rebuilds = Some(max_rebuilds);
+    }
     /// Get a reference to the `NodeRegistration`
     pub fn node_registration(&self) -> &NodeRegistration {
         &self.registration
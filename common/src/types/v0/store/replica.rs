@@ -2,8 +2,8 @@
 
 use crate::types::v0::{
     message_bus::{
-        self, CreateReplica, NodeId, PoolId, Protocol, Replica as MbusReplica, ReplicaId,
-        ReplicaName, ReplicaOwners, ReplicaShareProtocol,
+        self, CreateReplica, NodeId, PoolId, Protocol, Replica as MbusReplica, ReplicaAnnotations,
+        ReplicaEncryption, ReplicaId, ReplicaName, ReplicaOwners, ReplicaShareProtocol,
     },
     openapi::models,
     store::{
@@ -90,6 +90,12 @@ pub struct ReplicaSpec {
     pub sequencer: OperationSequence,
     /// Record of the operation in progress
     pub operation: Option<ReplicaOperationState>,
+    /// Replica annotations, free-form metadata which doesn't affect placement.
+    #[serde(default)]
+    pub annotations: ReplicaAnnotations,
+    /// At-rest encryption of the replica data.
+    #[serde(default)]
+    pub encryption: Option<ReplicaEncryption>,
 }
 
 impl OperationSequencer for ReplicaSpec {
@@ -111,16 +117,19 @@ impl ResourceUuid for ReplicaSpec {
 
 impl From<ReplicaSpec> for models::ReplicaSpec {
     fn from(src: ReplicaSpec) -> Self {
-        Self::new(
-            src.managed,
-            src.owners,
-            src.pool,
-            src.share,
-            src.size,
-            src.status,
-            src.thin,
-            openapi::apis::Uuid::try_from(src.uuid).unwrap(),
-        )
+        Self {
+            annotations: (!src.annotations.is_empty()).then(|| src.annotations),
+            ..Self::new(
+                src.managed,
+                src.owners,
+                src.pool,
+                src.share,
+                src.size,
+                src.status,
+                src.thin,
+                openapi::apis::Uuid::try_from(src.uuid).unwrap(),
+            )
+        }
     }
 }
 
@@ -152,6 +161,9 @@ impl SpecTransaction<ReplicaOperation> for ReplicaSpec {
                 ReplicaOperation::Unshare => {
                     self.share = Protocol::None;
                 }
+                ReplicaOperation::Resize(size) => {
+                    self.size = size;
+                }
             }
         }
         self.clear_op();
@@ -182,6 +194,7 @@ pub enum ReplicaOperation {
     Destroy,
     Share(ReplicaShareProtocol),
     Unshare,
+    Resize(u64),
 }
 
 /// Key used by the store to uniquely identify a ReplicaSpec structure.
@@ -244,6 +257,8 @@ impl From<&CreateReplica> for ReplicaSpec {
             owners: request.owners.clone(),
             sequencer: OperationSequence::new(request.uuid.clone()),
             operation: None,
+            annotations: request.annotations.clone(),
+            encryption: request.encryption.clone(),
         }
     }
 }
@@ -0,0 +1,166 @@
+//! Definition of volume snapshot types that can be saved to the persistent store.
+
+use crate::types::v0::{
+    message_bus::{self, PoolId, ReplicaId, SnapshotId, VolumeId},
+    store::{
+        definitions::{ObjectKey, StorableObject, StorableObjectType},
+        ResourceUuid, SpecStatus,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// User specification of a volume snapshot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VolumeSnapshotSpec {
+    /// uuid of the snapshot
+    pub uuid: SnapshotId,
+    /// uuid of the snapshotted volume
+    pub volume: VolumeId,
+    /// name of the snapshot on the data plane
+    pub name: String,
+    /// size of the volume when the snapshot was taken
+    pub size: u64,
+    /// when the snapshot was taken
+    pub timestamp: SystemTime,
+    /// replicas of the volume which were snapshotted
+    #[serde(default)]
+    pub replicas: Vec<VolumeSnapshotReplica>,
+    /// status of the snapshot, only a created snapshot is complete
+    /// Snapshots persisted before the status was introduced were only stored once taken, so
+    /// they're loaded as created.
+    #[serde(default = "created_status")]
+    pub status: SpecStatus<()>,
+}
+
+/// The status of a snapshot persisted without one
+fn created_status() -> SpecStatus<()> {
+    SpecStatus::Created(())
+}
+
+impl VolumeSnapshotSpec {
+    /// Check if the snapshot was completed, ie: it was taken on the volume replicas
+    pub fn complete(&self) -> bool {
+        self.status.created()
+    }
+    /// Name of the snapshot of the given `replica` on the data plane, if the snapshot was taken.
+    /// The io-engine names the snapshot of each replica after the replica itself, with the same
+    /// timestamp suffix as the snapshot name returned by the nexus.
+    pub fn replica_snapshot_name(&self, replica: &VolumeSnapshotReplica) -> Option<String> {
+        let (_, timestamp) = self.name.rsplit_once(SNAPSHOT_NAME_SEPARATOR)?;
+        Some(format!(
+            "{}{}{}",
+            replica.uuid, SNAPSHOT_NAME_SEPARATOR, timestamp
+        ))
+    }
+}
+
+/// Separator between the base name and the timestamp of a snapshot name on the data plane.
+const SNAPSHOT_NAME_SEPARATOR: &str = "-snap-";
+
+/// A volume replica which was snapshotted as part of a volume snapshot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VolumeSnapshotReplica {
+    /// uuid of the replica
+    pub uuid: ReplicaId,
+    /// pool where the replica and its snapshot live
+    pub pool: PoolId,
+}
+
+impl ResourceUuid for VolumeSnapshotSpec {
+    type Id = SnapshotId;
+    fn uuid(&self) -> Self::Id {
+        self.uuid.clone()
+    }
+}
+
+impl From<&VolumeSnapshotSpec> for message_bus::VolumeSnapshot {
+    fn from(spec: &VolumeSnapshotSpec) -> Self {
+        Self {
+            snapshot_id: spec.uuid.clone(),
+            volume_id: spec.volume.clone(),
+            name: spec.name.clone(),
+            size: spec.size,
+            timestamp: spec.timestamp,
+        }
+    }
+}
+
+/// Key used by the store to uniquely identify a VolumeSnapshotSpec structure.
+pub struct VolumeSnapshotKey(SnapshotId);
+
+impl From<&SnapshotId> for VolumeSnapshotKey {
+    fn from(id: &SnapshotId) -> Self {
+        Self(id.clone())
+    }
+}
+
+impl ObjectKey for VolumeSnapshotKey {
+    fn key_type(&self) -> StorableObjectType {
+        StorableObjectType::VolumeSnapshot
+    }
+
+    fn key_uuid(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl StorableObject for VolumeSnapshotSpec {
+    type Key = VolumeSnapshotKey;
+
+    fn key(&self) -> Self::Key {
+        VolumeSnapshotKey(self.uuid.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_snapshot_is_created() {
+        // a snapshot spec as persisted before the replicas and the status were recorded
+        let snapshot = VolumeSnapshotSpec {
+            uuid: SnapshotId::new(),
+            volume: VolumeId::new(),
+            name: "snapshot".to_string(),
+            size: 5242880,
+            timestamp: SystemTime::now(),
+            replicas: vec![],
+            status: SpecStatus::Creating,
+        };
+        let mut value = serde_json::to_value(&snapshot).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("replicas");
+        object.remove("status");
+
+        let legacy: VolumeSnapshotSpec = serde_json::from_value(value).unwrap();
+        assert!(legacy.complete());
+        assert!(legacy.replicas.is_empty());
+    }
+
+    #[test]
+    fn replica_snapshot_name() {
+        let replica = VolumeSnapshotReplica {
+            uuid: ReplicaId::new(),
+            pool: PoolId::from("pool"),
+        };
+        let mut snapshot = VolumeSnapshotSpec {
+            uuid: SnapshotId::new(),
+            volume: VolumeId::new(),
+            name: String::new(),
+            size: 5242880,
+            timestamp: SystemTime::now(),
+            replicas: vec![replica.clone()],
+            status: SpecStatus::Creating,
+        };
+        // the snapshot was not taken yet
+        assert_eq!(snapshot.replica_snapshot_name(&replica), None);
+
+        snapshot.name = format!("{}-snap-1634567890", snapshot.volume);
+        assert_eq!(
+            snapshot.replica_snapshot_name(&replica),
+            Some(format!("{}-snap-1634567890", replica.uuid))
+        );
+    }
+}
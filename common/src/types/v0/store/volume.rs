@@ -10,7 +10,10 @@ use crate::types::v0::{
 
 use crate::{
     types::v0::{
-        message_bus::{ReplicaId, Topology, VolumeLabels, VolumePolicy, VolumeStatus},
+        message_bus::{
+            PoolId, ReplicaEncryption, ReplicaId, SnapshotId, Topology, VolumeAnnotations,
+            VolumeLabels, VolumePolicy, VolumeStatus,
+        },
         openapi::models,
         store::{OperationSequence, OperationSequencer, ResourceUuid},
     },
@@ -47,6 +50,9 @@ pub struct VolumeTarget {
     nexus: NexusId,
     /// The protocol to use on the target
     protocol: Option<VolumeShareProtocol>,
+    /// The target is published read-only
+    #[serde(default)]
+    read_only: bool,
 }
 impl VolumeTarget {
     /// Create a new `Self` based on the given parameters
@@ -55,8 +61,14 @@ impl VolumeTarget {
             node,
             nexus,
             protocol,
+            read_only: false,
         }
     }
+    /// Return `Self` published read-only or read-write
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
     /// Get a reference to the node identification
     pub fn node(&self) -> &NodeId {
         &self.node
@@ -69,10 +81,14 @@ impl VolumeTarget {
     pub fn protocol(&self) -> Option<&VolumeShareProtocol> {
         self.protocol.as_ref()
     }
+    /// Check if the target is published read-only
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
 }
 impl From<VolumeTarget> for models::VolumeTarget {
     fn from(src: VolumeTarget) -> Self {
-        Self::new_all(src.node, src.protocol.into_opt())
+        Self::new_all(src.node, src.protocol.into_opt(), src.read_only)
     }
 }
 
@@ -85,12 +101,32 @@ pub struct VolumeSpec {
     pub size: u64,
     /// Volume labels.
     pub labels: Option<VolumeLabels>,
+    /// Volume annotations, free-form metadata which doesn't affect placement.
+    #[serde(default)]
+    pub annotations: VolumeAnnotations,
+    /// At-rest encryption of the data of all the volume replicas.
+    #[serde(default)]
+    pub encryption: Option<ReplicaEncryption>,
+    /// Idempotency key of the create request, if any.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// The original create request, kept when it has an idempotency key so that retries can be
+    /// matched against it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub create_request: Option<CreateVolume>,
+    /// Snapshot from which the volume was cloned, if any.
+    #[serde(default)]
+    pub source_snapshot: Option<SnapshotId>,
     /// Number of children the volume should have.
     pub num_replicas: u8,
     /// Status that the volume should eventually achieve.
     pub status: VolumeSpecStatus,
     /// The target where front-end IO will be sent to
     pub target: Option<VolumeTarget>,
+    /// The volume was last published read-only, so it may only be published read-write again
+    /// when forced.
+    #[serde(default)]
+    pub read_only: bool,
     /// volume policy
     pub policy: VolumePolicy,
     /// replica placement topology for the volume creation only
@@ -102,6 +138,86 @@ pub struct VolumeSpec {
     pub last_nexus_id: Option<NexusId>,
     /// Record of the operation in progress
     pub operation: Option<VolumeOperationState>,
+    /// Bounded history of the volume lifecycle events, oldest first
+    #[serde(default)]
+    pub events: Vec<VolumeEvent>,
+}
+
+/// Type of a volume lifecycle event
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum VolumeEventType {
+    /// the volume was created
+    Created,
+    /// the volume was published for the first time
+    Published,
+    /// the volume was published again, with a new target
+    Republished,
+    /// the volume was unpublished
+    Unpublished,
+    /// the volume target was shared
+    Shared,
+    /// the volume target was unshared
+    Unshared,
+    /// the volume replica count was changed
+    ReplicaCountChanged,
+    /// the volume became degraded
+    Degraded,
+    /// the volume was healed after being degraded
+    Healed,
+    /// the volume size was expanded
+    Expanded,
+    /// the volume replicas could not be placed as requested, eg: not spread across enough
+    /// failure domains
+    PlacementWarning,
+}
+
+impl From<VolumeEventType> for models::volume_event::Event {
+    fn from(src: VolumeEventType) -> Self {
+        match src {
+            VolumeEventType::Created => Self::Created,
+            VolumeEventType::Published => Self::Published,
+            VolumeEventType::Republished => Self::Republished,
+            VolumeEventType::Unpublished => Self::Unpublished,
+            VolumeEventType::Shared => Self::Shared,
+            VolumeEventType::Unshared => Self::Unshared,
+            VolumeEventType::ReplicaCountChanged => Self::ReplicaCountChanged,
+            VolumeEventType::Degraded => Self::Degraded,
+            VolumeEventType::Healed => Self::Healed,
+            VolumeEventType::Expanded => Self::Expanded,
+            VolumeEventType::PlacementWarning => Self::PlacementWarning,
+        }
+    }
+}
+
+/// Volume lifecycle event, retained in the volume event history
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VolumeEvent {
+    /// when the event happened
+    pub timestamp: std::time::SystemTime,
+    /// type of the event
+    pub event: VolumeEventType,
+    /// human readable detail of the event
+    pub detail: String,
+}
+impl VolumeEvent {
+    /// Create a new `Self` which happened now
+    pub fn new(event: VolumeEventType, detail: impl Into<String>) -> Self {
+        Self {
+            timestamp: std::time::SystemTime::now(),
+            event,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl From<VolumeEvent> for models::VolumeEvent {
+    fn from(src: VolumeEvent) -> Self {
+        Self {
+            detail: src.detail,
+            event: src.event.into(),
+            timestamp: humantime::format_rfc3339(src.timestamp).to_string(),
+        }
+    }
 }
 
 macro_rules! volume_log {
@@ -155,6 +271,24 @@ impl VolumeSpec {
                 .unwrap_or_default(),
         }
     }
+    /// node label key used to spread the replicas across failure domains
+    pub fn spread_topology_key(&self) -> Option<&String> {
+        self.topology
+            .as_ref()
+            .and_then(|t| t.spread_topology_key.as_ref())
+    }
+    /// check if the volume was created by the given `CreateVolume` request, regardless of any
+    /// changes made to the volume since its creation
+    pub fn created_with(&self, request: &CreateVolume) -> bool {
+        self.create_request.as_ref() == Some(request)
+    }
+    /// pools to try first when placing the replicas, in order of preference
+    pub fn preferred_pools(&self) -> &[PoolId] {
+        self.topology
+            .as_ref()
+            .map(|t| t.preferred_pools.as_slice())
+            .unwrap_or_default()
+    }
     /// desired volume replica count if during `SetReplica` operation
     /// or otherwise the current num_replicas
     pub fn desired_num_replicas(&self) -> u8 {
@@ -166,6 +300,22 @@ impl VolumeSpec {
             _ => self.num_replicas,
         }
     }
+    /// Append the event to the volume event history, evicting the oldest events so that no
+    /// more than `max_events` are retained
+    pub fn push_event(&mut self, event: VolumeEvent, max_events: usize) {
+        self.events.push(event);
+        if self.events.len() > max_events {
+            let excess = self.events.len() - max_events;
+            self.events.drain(.. excess);
+        }
+    }
+    /// Get the last recorded health event (`Degraded` or `Healed`), if any
+    pub fn last_health_event(&self) -> Option<&VolumeEvent> {
+        self.events
+            .iter()
+            .rev()
+            .find(|e| matches!(e.event, VolumeEventType::Degraded | VolumeEventType::Healed))
+    }
 }
 
 impl ResourceUuid for VolumeSpec {
@@ -215,9 +365,29 @@ impl SpecTransaction<VolumeOperation> for VolumeSpec {
                     }
                 }
                 VolumeOperation::SetReplica(count) => self.num_replicas = count,
+                VolumeOperation::Expand(size) => self.size = size,
+                VolumeOperation::SetLabels((labels, annotations)) => {
+                    self.labels = if labels.is_empty() {
+                        None
+                    } else {
+                        Some(labels)
+                    };
+                    self.annotations = annotations;
+                }
                 VolumeOperation::RemoveUnusedReplica(_) => {}
-                VolumeOperation::Publish((node, nexus, protocol)) => {
-                    self.target = Some(VolumeTarget::new(node, nexus.clone(), protocol));
+                VolumeOperation::Publish((node, nexus, protocol, read_only)) => {
+                    self.target = Some(
+                        VolumeTarget::new(node, nexus.clone(), protocol).with_read_only(read_only),
+                    );
+                    self.read_only = read_only;
+                    self.last_nexus_id = Some(nexus);
+                }
+                VolumeOperation::Republish((node, nexus, protocol)) => {
+                    // the new target keeps the access mode of the current one
+                    let read_only = self.target.as_ref().map_or(false, VolumeTarget::read_only);
+                    self.target = Some(
+                        VolumeTarget::new(node, nexus.clone(), protocol).with_read_only(read_only),
+                    );
                     self.last_nexus_id = Some(nexus);
                 }
                 VolumeOperation::Unpublish => {
@@ -254,7 +424,10 @@ pub enum VolumeOperation {
     Share(VolumeShareProtocol),
     Unshare,
     SetReplica(u8),
-    Publish((NodeId, NexusId, Option<VolumeShareProtocol>)),
+    Expand(u64),
+    SetLabels((VolumeLabels, VolumeAnnotations)),
+    Publish((NodeId, NexusId, Option<VolumeShareProtocol>, bool)),
+    Republish((NodeId, NexusId, Option<VolumeShareProtocol>)),
     Unpublish,
     RemoveUnusedReplica(ReplicaId),
 }
@@ -267,7 +440,10 @@ impl From<VolumeOperation> for models::volume_spec_operation::Operation {
             VolumeOperation::Share(_) => models::volume_spec_operation::Operation::Share,
             VolumeOperation::Unshare => models::volume_spec_operation::Operation::Unshare,
             VolumeOperation::SetReplica(_) => models::volume_spec_operation::Operation::SetReplica,
+            VolumeOperation::Expand(_) => models::volume_spec_operation::Operation::Expand,
+            VolumeOperation::SetLabels(_) => models::volume_spec_operation::Operation::SetLabels,
             VolumeOperation::Publish(_) => models::volume_spec_operation::Operation::Publish,
+            VolumeOperation::Republish(_) => models::volume_spec_operation::Operation::Republish,
             VolumeOperation::Unpublish => models::volume_spec_operation::Operation::Unpublish,
             VolumeOperation::RemoveUnusedReplica(_) => {
                 models::volume_spec_operation::Operation::RemoveUnusedReplica
@@ -312,14 +488,21 @@ impl From<&CreateVolume> for VolumeSpec {
             uuid: request.uuid.clone(),
             size: request.size,
             labels: request.labels.clone(),
+            annotations: request.annotations.clone(),
+            encryption: request.encryption.clone(),
+            idempotency_key: request.idempotency_key.clone(),
+            create_request: request.idempotency_key.as_ref().map(|_| request.clone()),
+            source_snapshot: request.source_snapshot.clone(),
             num_replicas: request.replicas as u8,
             status: VolumeSpecStatus::Creating,
             target: None,
+            read_only: false,
             policy: request.policy.clone(),
             topology: request.topology.clone(),
             sequencer: OperationSequence::new(request.uuid.clone()),
             last_nexus_id: None,
             operation: None,
+            events: vec![],
         }
     }
 }
@@ -328,6 +511,7 @@ impl PartialEq<CreateVolume> for VolumeSpec {
         let mut other = VolumeSpec::from(other);
         other.status = self.status.clone();
         other.sequencer = self.sequencer.clone();
+        other.events = self.events.clone();
         &other == self
     }
 }
@@ -339,6 +523,7 @@ impl From<&VolumeSpec> for message_bus::VolumeState {
             status: message_bus::VolumeStatus::Unknown,
             target: None,
             replica_topology: HashMap::new(),
+            target_refreshed: None,
         }
     }
 }
@@ -367,6 +552,10 @@ impl From<VolumeSpec> for models::VolumeSpec {
             src.uuid,
             src.topology.into_opt(),
             src.policy,
+            (!src.annotations.is_empty()).then(|| src.annotations),
+            src.encryption.into_opt(),
+            src.idempotency_key,
+            src.source_snapshot.into_opt(),
         )
     }
 }
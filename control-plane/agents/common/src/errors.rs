@@ -20,6 +20,8 @@ pub enum SvcError {
     BusGetNodes { source: BusError },
     #[snafu(display("Node '{}' is not online", node))]
     NodeNotOnline { node: NodeId },
+    #[snafu(display("Node '{}' is cordoned", node))]
+    NodeCordoned { node: NodeId },
     #[snafu(display("No available online nodes"))]
     NoNodes {},
     #[snafu(display(
@@ -76,6 +78,8 @@ pub enum SvcError {
     ChildNotFound { nexus: String, child: String },
     #[snafu(display("Child '{}' already exists in Nexus '{}'", child, nexus))]
     ChildAlreadyExists { nexus: String, child: String },
+    #[snafu(display("Child '{}' of Nexus '{}' is not being rebuilt", child, nexus))]
+    ChildNotRebuilding { nexus: String, child: String },
     #[snafu(display("Volume '{}' not found", vol_id))]
     VolumeNotFound { vol_id: String },
     #[snafu(display("Volume '{}' not published", vol_id))]
@@ -97,6 +101,11 @@ pub enum SvcError {
         node: String,
         protocol: String,
     },
+    #[snafu(display(
+        "Volume '{}' was published read-only, it can only be published read-write when forced",
+        vol_id
+    ))]
+    VolumePublishedReadOnly { vol_id: String },
     #[snafu(display("Replica '{}' not found", replica_id))]
     ReplicaNotFound { replica_id: ReplicaId },
     #[snafu(display("{} '{}' is already shared over {}", kind.to_string(), id, share))]
@@ -130,6 +139,14 @@ pub enum SvcError {
     MBusError { source: mbus_api::Error },
     #[snafu(display("Invalid Arguments"))]
     InvalidArguments {},
+    #[snafu(display(
+        "JSON gRPC request must target either node '{}' or endpoint '{}', not both",
+        node,
+        endpoint
+    ))]
+    JsonGrpcTarget { node: String, endpoint: String },
+    #[snafu(display("Invalid NVMe-oF transport parameters: {}", details))]
+    InvalidTransportParams { details: String },
     #[snafu(display("Multiple nexuses not supported"))]
     MultipleNexuses {},
     #[snafu(display("Storage Error: {}", source))]
@@ -159,10 +176,44 @@ pub enum SvcError {
         resource: String,
         request: String,
     },
+    #[snafu(display(
+        "Idempotency key '{}' was already used to create volume {} with different parameters",
+        key,
+        id
+    ))]
+    IdempotencyKeyMismatch { key: String, id: String },
     #[snafu(display("{} Resource id {} needs to be reconciled. Please retry", kind.to_string(), id))]
     NotReady { kind: ResourceKind, id: String },
     #[snafu(display("{} Resource id {} still in use", kind.to_string(), id))]
     InUse { kind: ResourceKind, id: String },
+    #[snafu(display("Disk '{}' is already in use by pool '{}'", disk, pool))]
+    DeviceInUse { disk: String, pool: String },
+    #[snafu(display(
+        "Pool '{}' has only {} bytes free outside of its {}% reserved capacity, {} bytes are required",
+        pool,
+        free,
+        reserved_percent,
+        required
+    ))]
+    PoolCapacityReserved {
+        pool: String,
+        free: u64,
+        reserved_percent: u8,
+        required: u64,
+    },
+    #[snafu(display("Invalid reserved capacity of {}%, must be at most 100%", percent))]
+    InvalidReservedCapacity { percent: u8 },
+    #[snafu(display(
+        "Pool '{}' already exists with uuid '{}', which differs from the requested uuid '{}'",
+        pool,
+        uuid,
+        requested
+    ))]
+    PoolUuidMismatch {
+        pool: String,
+        uuid: String,
+        requested: String,
+    },
     #[snafu(display("{} Resource id {} already exists", kind.to_string(), id))]
     AlreadyExists { kind: ResourceKind, id: String },
     #[snafu(display("Cannot remove the last replica '{}' of volume '{}'", replica, volume))]
@@ -203,6 +254,89 @@ pub enum SvcError {
         max_rebuilds
     ))]
     MaxRebuilds { max_rebuilds: u32 },
+    #[snafu(display(
+        "The maximum number of rebuilds ({}) may not be lower than the number of rebuilds in progress ({})",
+        max_rebuilds,
+        num_rebuilds
+    ))]
+    MaxRebuildsBelowRunning {
+        max_rebuilds: u32,
+        num_rebuilds: u32,
+    },
+    #[snafu(display(
+        "Unable to resize {} '{}' from {} to {} bytes, the new size must be larger than the current size",
+        kind.to_string(),
+        id,
+        current,
+        requested
+    ))]
+    InvalidResize {
+        kind: ResourceKind,
+        id: String,
+        current: u64,
+        requested: u64,
+    },
+    #[snafu(display(
+        "Failed to expand Volume '{}', replicas '{}' could not be resized",
+        vol_id,
+        replicas
+    ))]
+    VolumeExpandReplicas { vol_id: String, replicas: String },
+    #[snafu(display("Snapshot '{}' is not complete and cannot be cloned", snapshot))]
+    SnapshotIncomplete { snapshot: String },
+    #[snafu(display(
+        "Snapshot '{}' has a size of {} bytes, which differs from the requested {} bytes",
+        snapshot,
+        size,
+        requested
+    ))]
+    SnapshotSizeMismatch {
+        snapshot: String,
+        size: u64,
+        requested: u64,
+    },
+    #[snafu(display(
+        "Unable to clone replica '{}' from a snapshot, the io-engine on node '{}' does not support clones",
+        replica,
+        node
+    ))]
+    CloneUnsupported { replica: ReplicaId, node: NodeId },
+    #[snafu(display(
+        "Unable to create the encrypted replica '{}', the io-engine on node '{}' does not support encryption",
+        replica,
+        node
+    ))]
+    EncryptionUnsupported { replica: ReplicaId, node: NodeId },
+    #[snafu(display(
+        "Replica '{}' was not created on node '{}' with the requested provisioning (thin: {})",
+        replica,
+        node,
+        thin
+    ))]
+    ReplicaProvisioningMismatch {
+        replica: ReplicaId,
+        node: NodeId,
+        thin: bool,
+    },
+    #[snafu(display("Replica '{}' cannot be moved, {}", replica, reason))]
+    ReplicaNotMovable { replica: ReplicaId, reason: String },
+    #[snafu(display(
+        "Replica '{}' cannot be added to volume '{}', {}",
+        replica,
+        volume,
+        reason
+    ))]
+    ReplicaNotAddable {
+        replica: ReplicaId,
+        volume: String,
+        reason: String,
+    },
+    #[snafu(display("{} '{}' has no operations to {}", kind.to_string(), id, request))]
+    NoOperations {
+        kind: ResourceKind,
+        id: String,
+        request: String,
+    },
 }
 
 impl From<StoreError> for SvcError {
@@ -268,12 +402,42 @@ impl From<SvcError> for ReplyError {
                 source: desc.to_string(),
                 extra: error.full_string(),
             },
+            SvcError::ChildNotRebuilding { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::Child,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
             SvcError::InUse { kind, id } => ReplyError {
                 kind: ReplyErrorKind::InUse,
                 resource: kind,
                 source: desc.to_string(),
                 extra: format!("id: {}", id),
             },
+            SvcError::DeviceInUse { .. } => ReplyError {
+                kind: ReplyErrorKind::DeviceInUse,
+                resource: ResourceKind::Pool,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::PoolCapacityReserved { .. } => ReplyError {
+                kind: ReplyErrorKind::ResourceExhausted,
+                resource: ResourceKind::Pool,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::InvalidReservedCapacity { .. } => ReplyError {
+                kind: ReplyErrorKind::InvalidArgument,
+                resource: ResourceKind::Pool,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::PoolUuidMismatch { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::Pool,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
             SvcError::AlreadyExists { kind, id } => ReplyError {
                 kind: ReplyErrorKind::AlreadyExists,
                 resource: kind,
@@ -306,6 +470,12 @@ impl From<SvcError> for ReplyError {
                 source: desc.to_string(),
                 extra: error.full_string(),
             },
+            SvcError::IdempotencyKeyMismatch { .. } => ReplyError {
+                kind: ReplyErrorKind::Conflict,
+                resource: ResourceKind::Volume,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
             SvcError::BusGetNode { source, .. } => source,
             SvcError::BusGetNodes { source } => source,
             SvcError::GrpcRequestError {
@@ -332,6 +502,13 @@ impl From<SvcError> for ReplyError {
                 extra: error.full_string(),
             },
 
+            SvcError::NodeCordoned { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::Node,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+
             SvcError::NoNodes { .. } => ReplyError {
                 kind: ReplyErrorKind::FailedPrecondition,
                 resource: ResourceKind::Node,
@@ -388,6 +565,18 @@ impl From<SvcError> for ReplyError {
                 source: desc.to_string(),
                 extra: error_str,
             },
+            SvcError::JsonGrpcTarget { .. } => ReplyError {
+                kind: ReplyErrorKind::InvalidArgument,
+                resource: ResourceKind::JsonGrpc,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::InvalidTransportParams { .. } => ReplyError {
+                kind: ReplyErrorKind::InvalidArgument,
+                resource: ResourceKind::Nexus,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
             SvcError::JsonRpc { .. } => ReplyError {
                 kind: ReplyErrorKind::Internal,
                 resource: ResourceKind::JsonGrpc,
@@ -454,6 +643,12 @@ impl From<SvcError> for ReplyError {
                 source: desc.to_string(),
                 extra: error.full_string(),
             },
+            SvcError::VolumePublishedReadOnly { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::Volume,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
             SvcError::WatchResourceNotFound { kind } => ReplyError {
                 kind: ReplyErrorKind::NotFound,
                 resource: kind,
@@ -557,6 +752,72 @@ impl From<SvcError> for ReplyError {
                 source: desc.to_string(),
                 extra: error.full_string(),
             },
+            SvcError::MaxRebuildsBelowRunning { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::Nexus,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::InvalidResize { ref kind, .. } => ReplyError {
+                kind: ReplyErrorKind::InvalidArgument,
+                resource: kind.clone(),
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::VolumeExpandReplicas { .. } => ReplyError {
+                kind: ReplyErrorKind::Aborted,
+                resource: ResourceKind::Volume,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::EncryptionUnsupported { .. } => ReplyError {
+                kind: ReplyErrorKind::Unimplemented,
+                resource: ResourceKind::Replica,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::ReplicaProvisioningMismatch { .. } => ReplyError {
+                kind: ReplyErrorKind::Internal,
+                resource: ResourceKind::Replica,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::SnapshotIncomplete { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::VolumeSnapshot,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::SnapshotSizeMismatch { .. } => ReplyError {
+                kind: ReplyErrorKind::InvalidArgument,
+                resource: ResourceKind::Volume,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::CloneUnsupported { .. } => ReplyError {
+                kind: ReplyErrorKind::Unimplemented,
+                resource: ResourceKind::Replica,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::ReplicaNotMovable { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::Replica,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::ReplicaNotAddable { .. } => ReplyError {
+                kind: ReplyErrorKind::FailedPrecondition,
+                resource: ResourceKind::Replica,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::NoOperations { kind, .. } => ReplyError {
+                kind: ReplyErrorKind::InvalidArgument,
+                resource: kind,
+                source: desc.to_string(),
+                extra: error_str,
+            },
         }
     }
 }
@@ -603,10 +864,46 @@ fn grpc_to_reply_error(error: SvcError) -> ReplyError {
 #[derive(Debug, Snafu)]
 #[allow(missing_docs)]
 pub enum NotEnough {
-    #[snafu(display("Not enough suitable pools available, {}/{}", have, need))]
-    OfPools { have: u64, need: u64 },
+    #[snafu(display(
+        "Not enough suitable pools available, {}/{}{}",
+        have,
+        need,
+        PoolRejections(rejected)
+    ))]
+    OfPools {
+        have: u64,
+        need: u64,
+        rejected: Vec<PoolRejection>,
+    },
     #[snafu(display("Not enough replicas available, {}/{}", have, need))]
     OfReplicas { have: u64, need: u64 },
     #[snafu(display("Not enough nexuses available, {}/{}", have, need))]
     OfNexuses { have: u64, need: u64 },
 }
+
+/// A pool which was rejected as a candidate for a volume replica, and the reason why
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PoolRejection {
+    /// The rejected pool
+    pub pool: PoolId,
+    /// Why the pool was rejected
+    pub reason: String,
+}
+
+/// Displays the rejected pools, if any, as a suffix of the `NotEnough::OfPools` error
+struct PoolRejections<'a>(&'a [PoolRejection]);
+impl std::fmt::Display for PoolRejections<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        write!(f, ", rejected pools: ")?;
+        for (index, rejection) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "'{}' ({})", rejection.pool, rejection.reason)?;
+        }
+        Ok(())
+    }
+}
@@ -13,7 +13,10 @@ use std::{
 
 use async_trait::async_trait;
 use dyn_clonable::clonable;
-use futures::{future::join_all, Future};
+use futures::{
+    future::{join_all, BoxFuture, Shared},
+    Future, FutureExt,
+};
 
 use snafu::{OptionExt, ResultExt, Snafu};
 use state::Container;
@@ -23,8 +26,8 @@ use crate::errors::SvcError;
 use common_lib::{
     mbus_api,
     mbus_api::{
-        BusClient, DynBus, Error, ErrorChain, Message, MessageId, ReceivedMessage,
-        ReceivedRawMessage, TimeoutOptions,
+        BusClient, BusConnectOptions, DynBus, Error, ErrorChain, Message, MessageId,
+        MessageTimeouts, ReceivedMessage, ReceivedRawMessage, TimeoutOptions,
     },
     types::{
         v0::message_bus::{ChannelVs, Liveness},
@@ -32,7 +35,7 @@ use common_lib::{
     },
 };
 
-use opentelemetry::trace::FutureExt;
+use opentelemetry::trace::FutureExt as OtelFutureExt;
 use tokio::task::JoinHandle;
 
 /// Agent level errors
@@ -68,6 +71,7 @@ pub enum ServiceError {
 pub struct Service {
     server: Option<String>,
     server_connected: bool,
+    bus_options: BusConnectOptions,
     no_min_timeouts: bool,
     channel: Channel,
     subscriptions: HashMap<String, Vec<Box<dyn ServiceSubscriber>>>,
@@ -79,6 +83,7 @@ impl Default for Service {
         Self {
             server: None,
             server_connected: false,
+            bus_options: Default::default(),
             channel: Default::default(),
             subscriptions: Default::default(),
             shared_state: std::sync::Arc::new(<Container![Send + Sync]>::new()),
@@ -140,6 +145,34 @@ impl Context {
 /// Service Request received via the message bus
 pub type Request<'a> = ReceivedRawMessage<'a>;
 
+/// Signal which resolves once the service should shut down
+pub type ShutdownSignal = Shared<BoxFuture<'static, ()>>;
+
+/// Get a `ShutdownSignal` which resolves when the process receives either TERM or INT.
+/// When received the opentel traces are also immediately flushed.
+pub fn termination_signal() -> ShutdownSignal {
+    let signal_term = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate());
+    let signal_int = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt());
+    async move {
+        match (signal_term, signal_int) {
+            (Ok(mut term), Ok(mut int)) => {
+                tokio::select! {
+                    _term = term.recv() => tracing::info!("SIGTERM received"),
+                    _int = int.recv() => tracing::info!("SIGINT received"),
+                }
+            }
+            (Ok(mut signal), Err(_)) | (Err(_), Ok(mut signal)) => {
+                signal.recv().await;
+                tracing::info!("Termination signal received");
+            }
+            _ => futures::future::pending().await,
+        }
+        opentelemetry::global::force_flush_tracer_provider();
+    }
+    .boxed()
+    .shared()
+}
+
 #[async_trait]
 #[clonable]
 /// Trait which must be implemented by each subscriber with the handler
@@ -167,6 +200,14 @@ impl Service {
         self.server.is_some()
     }
 
+    /// Use the given `BusConnectOptions` when connecting to the message bus server, eg: to
+    /// authenticate with it
+    #[must_use]
+    pub fn with_bus_options(mut self, options: BusConnectOptions) -> Self {
+        self.bus_options = options;
+        self
+    }
+
     /// Connect to the provided message bus server immediately
     /// Useful for when dealing with async shared data which might required the
     /// message bus before the builder is complete
@@ -185,14 +226,19 @@ impl Service {
         client: impl Into<Option<BusClient>>,
     ) {
         if !self.server_connected {
+            let message_timeouts =
+                MessageTimeouts::from_env().expect("Invalid message timeout overrides");
             let timeout_opts = if no_min_timeouts {
                 TimeoutOptions::new_no_retries().with_req_timeout(None)
             } else {
                 TimeoutOptions::new_no_retries()
-            };
-            // todo: parse connection options when nats has better support
+            }
+            .with_message_timeouts(message_timeouts);
             if let Some(server) = self.server.clone() {
-                mbus_api::message_bus_init_options(client, server, timeout_opts).await;
+                let options = self.bus_options.clone();
+                mbus_api::message_bus_init_options(client, server, options, timeout_opts)
+                    .await
+                    .expect("Should connect to the nats server");
                 self.server_connected = true;
             }
             self.no_min_timeouts = no_min_timeouts;
@@ -333,6 +379,7 @@ impl Service {
         channel: Channel,
         subscriptions: Vec<Box<dyn ServiceSubscriber>>,
         state: std::sync::Arc<Container![Send + Sync]>,
+        shutdown: ShutdownSignal,
     ) -> Result<(), ServiceError> {
         let bus = Arc::new(bus);
         let handle = bus.subscribe(channel.clone()).await.context(Subscribe {
@@ -348,43 +395,14 @@ impl Service {
                 .collect::<Vec<_>>(),
         );
 
-        let mut signal_term =
-            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate());
-        let mut signal_int =
-            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt());
         loop {
             let state = state.clone();
             let bus = bus.clone();
             let gated_subs = gated_subs.clone();
 
-            let message = match (signal_term.as_mut(), signal_int.as_mut()) {
-                (Ok(term), Ok(int)) => {
-                    tokio::select! {
-                        _evt = term.recv() => {
-                             opentelemetry::global::force_flush_tracer_provider();
-                            return Ok(())
-                        },
-                        _evt = int.recv() => {
-                             opentelemetry::global::force_flush_tracer_provider();
-                            return Ok(())
-                        },
-                        message = handle.next() => {
-                            message
-                        }
-                    }
-                }
-                (Ok(signal), Err(_)) | (Err(_), Ok(signal)) => {
-                    tokio::select! {
-                        _evt = signal.recv() => {
-                             opentelemetry::global::force_flush_tracer_provider();
-                            return Ok(())
-                        },
-                        message = handle.next() => {
-                            message
-                        }
-                    }
-                }
-                _ => handle.next().await,
+            let message = tokio::select! {
+                _shutdown = shutdown.clone() => return Ok(()),
+                message = handle.next() => message,
             }
             .context(GetMessage {
                 channel: channel.clone(),
@@ -476,7 +494,17 @@ impl Service {
     /// each channel benefits from a tokio thread which routes messages
     /// accordingly todo: only one subscriber per message id supported at
     /// the moment.
-    pub async fn mbus_handles(mut self) -> Vec<JoinHandle<Result<(), ServiceError>>> {
+    pub async fn mbus_handles(self) -> Vec<JoinHandle<Result<(), ServiceError>>> {
+        self.mbus_handles_with_shutdown(termination_signal()).await
+    }
+
+    /// Get a list of handles to services which run all mbus subscribers asynchronously until
+    /// either all subscribers are closed or the given `shutdown` signal resolves, at which point
+    /// no further messages are received.
+    pub async fn mbus_handles_with_shutdown(
+        mut self,
+        shutdown: ShutdownSignal,
+    ) -> Vec<JoinHandle<Result<(), ServiceError>>> {
         let mut threads = vec![];
 
         self.message_bus_init(self.no_min_timeouts, BusClient::CoreAgent)
@@ -488,9 +516,17 @@ impl Service {
             let channel = subscriptions.0.clone();
             let subscriptions = subscriptions.1.clone();
             let state = self.shared_state.clone();
+            let shutdown = shutdown.clone();
 
             let handle = tokio::spawn(async move {
-                Self::run_channel(bus, channel.parse().unwrap(), subscriptions, state).await
+                Self::run_channel(
+                    bus,
+                    channel.parse().unwrap(),
+                    subscriptions,
+                    state,
+                    shutdown,
+                )
+                .await
             });
 
             threads.push(handle);
@@ -11,7 +11,10 @@ use common_lib::{
     },
 };
 use rpc::io_engine as rpc;
-use std::convert::TryFrom;
+use std::{
+    convert::TryFrom,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 /// Trait for converting rpc messages to message bus messages.
 pub trait TryRpcToMessageBus {
@@ -81,6 +84,7 @@ impl RpcToMessageBus for rpc::BlockDevice {
                 },
             },
             available: self.available,
+            claimed_by: None,
         }
     }
 }
@@ -117,6 +121,7 @@ impl TryRpcToMessageBus for rpc::ReplicaV2 {
             share: self.share.into(),
             uri: self.uri.clone(),
             status: ReplicaStatus::Online,
+            state_changed_at: None,
         })
     }
 }
@@ -173,6 +178,7 @@ impl RpcToMessageBus for rpc::Child {
             uri: self.uri.clone().into(),
             state: ChildState::from(self.state),
             rebuild_progress: u8::try_from(self.rebuild_progress).ok(),
+            state_changed_at: None,
         }
     }
 }
@@ -222,6 +228,16 @@ impl MessageBusToRpc for message_bus::UnshareReplica {
     }
 }
 
+impl MessageBusToRpc for message_bus::ResizeReplica {
+    type RpcMessage = rpc::ResizeReplicaRequest;
+    fn to_rpc(&self) -> Self::RpcMessage {
+        Self::RpcMessage {
+            uuid: self.uuid.clone().into(),
+            requested_size: self.requested_size,
+        }
+    }
+}
+
 impl MessageBusToRpc for message_bus::CreatePool {
     type RpcMessage = rpc::CreatePoolRequest;
     fn to_rpc(&self) -> Self::RpcMessage {
@@ -232,6 +248,18 @@ impl MessageBusToRpc for message_bus::CreatePool {
     }
 }
 
+impl MessageBusToRpc for message_bus::ImportPool {
+    type RpcMessage = ::rpc::v1::pool::ImportPoolRequest;
+    fn to_rpc(&self) -> Self::RpcMessage {
+        Self::RpcMessage {
+            name: self.id.clone().into(),
+            uuid: Some(self.uuid.to_string()),
+            disks: self.disks.iter().map(|d| d.to_string()).collect(),
+            pooltype: ::rpc::v1::pool::PoolType::Lvs as i32,
+        }
+    }
+}
+
 impl MessageBusToRpc for message_bus::DestroyReplica {
     type RpcMessage = rpc::DestroyReplicaRequest;
     fn to_rpc(&self) -> Self::RpcMessage {
@@ -290,6 +318,16 @@ impl MessageBusToRpc for message_bus::UnshareNexus {
     }
 }
 
+impl MessageBusToRpc for message_bus::ResizeNexus {
+    type RpcMessage = rpc::ResizeNexusRequest;
+    fn to_rpc(&self) -> Self::RpcMessage {
+        Self::RpcMessage {
+            uuid: self.uuid.clone().into(),
+            requested_size: self.requested_size,
+        }
+    }
+}
+
 impl MessageBusToRpc for message_bus::DestroyNexus {
     type RpcMessage = rpc::DestroyNexusRequest;
     fn to_rpc(&self) -> Self::RpcMessage {
@@ -310,6 +348,63 @@ impl MessageBusToRpc for message_bus::AddNexusChild {
     }
 }
 
+impl MessageBusToRpc for message_bus::GetRebuildHistory {
+    type RpcMessage = rpc::RebuildHistoryRequest;
+    fn to_rpc(&self) -> Self::RpcMessage {
+        Self::RpcMessage {
+            uuid: self.nexus.clone().into(),
+        }
+    }
+}
+
+impl RpcToMessageBus for rpc::RebuildHistoryRecord {
+    type BusMessage = message_bus::RebuildRecord;
+    fn to_mbus(&self) -> Self::BusMessage {
+        // the io-engine always sets the times of its records, so the epoch is never used
+        let start_time = self
+            .start_time
+            .clone()
+            .and_then(|t| SystemTime::try_from(t).ok());
+        let end_time = self
+            .end_time
+            .clone()
+            .and_then(|t| SystemTime::try_from(t).ok());
+        Self::BusMessage {
+            child_uri: self.child_uri.clone().into(),
+            src_uri: self.src_uri.clone().into(),
+            state: self.state.into(),
+            blocks_total: self.blocks_total,
+            blocks_recovered: self.blocks_recovered,
+            blocks_transferred: self.blocks_transferred,
+            block_size: self.block_size,
+            is_partial: self.is_partial,
+            start_time: start_time.unwrap_or(UNIX_EPOCH),
+            end_time: end_time.unwrap_or(UNIX_EPOCH),
+        }
+    }
+}
+
+impl MessageBusToRpc for message_bus::CancelRebuild {
+    type RpcMessage = rpc::StopRebuildRequest;
+    fn to_rpc(&self) -> Self::RpcMessage {
+        Self::RpcMessage {
+            uuid: self.nexus.clone().into(),
+            uri: self.child_uri.clone().into(),
+        }
+    }
+}
+
+impl MessageBusToRpc for message_bus::OnlineNexusChild {
+    type RpcMessage = rpc::ChildNexusRequest;
+    fn to_rpc(&self) -> Self::RpcMessage {
+        Self::RpcMessage {
+            uuid: self.nexus.clone().into(),
+            uri: self.uri.clone().into(),
+            action: rpc::ChildAction::Online as i32,
+        }
+    }
+}
+
 impl MessageBusToRpc for message_bus::RemoveNexusChild {
     type RpcMessage = rpc::RemoveChildNexusRequest;
     fn to_rpc(&self) -> Self::RpcMessage {
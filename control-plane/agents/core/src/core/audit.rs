@@ -0,0 +1,174 @@
+use common::errors::SvcError;
+use common_lib::mbus_api::{ErrorChain, Message, MessageId};
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{fs::OpenOptions, io::Write, path::PathBuf, str::FromStr};
+
+/// The tracing target of the audit records when they're emitted as log events.
+const AUDIT_LOG_TARGET: &str = "audit";
+/// The sender of the requests received over gRPC, which don't carry a sender identifier.
+const GRPC_SENDER: &str = "grpc";
+
+/// The audit sink, which is only set when auditing is enabled.
+static AUDIT_SINK: OnceCell<AuditSink> = OnceCell::new();
+
+/// Where the audit records of the mutating requests are written to.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum AuditTarget {
+    /// Emitted as log events with the `audit` tracing target.
+    Log,
+    /// Appended to the file, one json record per line.
+    File(PathBuf),
+}
+
+impl FromStr for AuditTarget {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "" => Err("The audit target must be either 'log' or a file path".to_string()),
+            "log" => Ok(Self::Log),
+            path => Ok(Self::File(PathBuf::from(path))),
+        }
+    }
+}
+
+/// An opened `AuditTarget`.
+enum AuditSink {
+    Log,
+    File(Mutex<std::fs::File>),
+}
+
+impl AuditSink {
+    fn open(target: &AuditTarget) -> std::io::Result<Self> {
+        Ok(match target {
+            AuditTarget::Log => Self::Log,
+            AuditTarget::File(path) => Self::File(Mutex::new(
+                OpenOptions::new().create(true).append(true).open(path)?,
+            )),
+        })
+    }
+    fn write(&self, record: &AuditRecord) {
+        let record = match serde_json::to_string(record) {
+            Ok(record) => record,
+            Err(error) => {
+                tracing::error!(error=%error, "Failed to serialize the audit record");
+                return;
+            }
+        };
+        match self {
+            Self::Log => tracing::info!(target: AUDIT_LOG_TARGET, "{}", record),
+            Self::File(file) => {
+                let mut file = file.lock();
+                if let Err(error) = writeln!(file, "{}", record).and_then(|_| file.flush()) {
+                    tracing::error!(error=%error, %record, "Failed to write the audit record");
+                }
+            }
+        }
+    }
+}
+
+/// The outcome of an audited request.
+#[derive(Serialize, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/// An audit record of a mutating request: who requested what, on which resource and with which
+/// outcome.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct AuditRecord<'a> {
+    timestamp: String,
+    sender: &'a str,
+    operation: String,
+    resource: Option<String>,
+    outcome: AuditOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Enable the auditing of the mutating requests, writing the records to the given target.
+pub(crate) fn init(target: &AuditTarget) -> std::io::Result<()> {
+    let sink = AuditSink::open(target)?;
+    if AUDIT_SINK.set(sink).is_err() {
+        tracing::warn!("The audit target has already been initialised");
+    }
+    Ok(())
+}
+
+/// Record the outcome of the request from the `sender`, if auditing is enabled and the request
+/// is a mutating one.
+pub fn record<R: Message + Serialize, T>(sender: &str, request: &R, result: &Result<T, SvcError>) {
+    let sink = match AUDIT_SINK.get() {
+        Some(sink) => sink,
+        None => return,
+    };
+    let operation = match request.id() {
+        MessageId::v0(id) if id.mutating() => id.to_string(),
+        MessageId::v0(_) => return,
+    };
+    sink.write(&AuditRecord {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        sender,
+        operation,
+        resource: resource_id(request),
+        outcome: match result {
+            Ok(_) => AuditOutcome::Success,
+            Err(_) => AuditOutcome::Failure,
+        },
+        error: result.as_ref().err().map(|error| error.full_string()),
+    });
+}
+
+/// Record the outcome of the request received over gRPC, passing the result through.
+pub(crate) fn grpc<R: Message + Serialize, T>(
+    request: &R,
+    result: Result<T, SvcError>,
+) -> Result<T, SvcError> {
+    record(GRPC_SENDER, request, &result);
+    result
+}
+
+/// Get the identifier of the resource which the request targets, if any.
+fn resource_id(request: &impl Serialize) -> Option<String> {
+    let request = serde_json::to_value(request).ok()?;
+    ["uuid", "id", "volumeId", "node"]
+        .iter()
+        .find_map(|field| match request.get(field)? {
+            serde_json::Value::String(id) => Some(id.clone()),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_lib::types::v0::message_bus::{CreatePool, GetVolumes, MessageIdVs};
+
+    #[test]
+    fn audit_target() {
+        assert_eq!(AuditTarget::from_str("log"), Ok(AuditTarget::Log));
+        assert_eq!(
+            AuditTarget::from_str("/var/log/audit.log"),
+            Ok(AuditTarget::File(PathBuf::from("/var/log/audit.log")))
+        );
+        assert!(AuditTarget::from_str("").is_err());
+    }
+
+    #[test]
+    fn audit_resource() {
+        let request = CreatePool {
+            node: "node-1".into(),
+            id: "pool-1".into(),
+            ..Default::default()
+        };
+        assert!(MessageIdVs::CreatePool.mutating());
+        assert_eq!(resource_id(&request), Some("pool-1".to_string()));
+        assert!(!MessageIdVs::GetVolumes.mutating());
+        assert_eq!(resource_id(&GetVolumes::default()), None);
+    }
+}
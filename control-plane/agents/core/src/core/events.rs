@@ -0,0 +1,93 @@
+use common::errors::SvcError;
+use common_lib::types::v0::message_bus::{Event, Filter};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// Bounded in-memory store of the recent control-plane events, eg: resources being created or
+/// destroyed, or reconcilers failing to fix a resource.
+/// The oldest events are evicted first once the store is full.
+#[derive(Debug)]
+pub(crate) struct EventStore {
+    /// the retained events, oldest first
+    events: Mutex<VecDeque<Event>>,
+    /// maximum number of retained events
+    capacity: usize,
+}
+
+impl EventStore {
+    /// Return a new `Self` which retains up to `capacity` events, where 0 disables the store
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+    /// Record the event, evicting the oldest events if the store is full
+    pub(crate) fn record(&self, event: Event) {
+        if self.capacity == 0 {
+            return;
+        }
+        tracing::trace!(?event, "Recording event");
+        let mut events = self.events.lock();
+        while events.len() >= self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+    /// Get the retained events about the resource of the filter, oldest first
+    pub(crate) fn get(&self, filter: &Filter) -> Result<Vec<Event>, SvcError> {
+        let events = self.events.lock();
+        let mut filtered = Vec::new();
+        for event in events.iter() {
+            match event.matches(filter) {
+                Some(true) => filtered.push(event.clone()),
+                Some(false) => {}
+                None => {
+                    return Err(SvcError::InvalidFilter {
+                        filter: filter.clone(),
+                    })
+                }
+            }
+        }
+        Ok(filtered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_lib::{
+        mbus_api::ResourceKind,
+        types::v0::message_bus::{EventType, NodeId, PoolId},
+    };
+
+    fn event(kind: ResourceKind, id: &str, reason: &str) -> Event {
+        Event::new(kind, id, EventType::Normal, reason, "")
+    }
+    fn reasons(store: &EventStore, filter: Filter) -> Vec<String> {
+        let events = store.get(&filter).unwrap();
+        events.into_iter().map(|event| event.reason).collect()
+    }
+
+    #[test]
+    fn event_store() {
+        let store = EventStore::new(3);
+        store.record(event(ResourceKind::Node, "a", "1"));
+        store.record(event(ResourceKind::Pool, "a", "2"));
+        store.record(event(ResourceKind::Node, "a", "3"));
+        store.record(event(ResourceKind::Node, "b", "4"));
+
+        // the oldest event was evicted
+        assert_eq!(reasons(&store, Filter::None), vec!["2", "3", "4"]);
+        // the events are filtered by both the resource kind and id
+        assert_eq!(reasons(&store, Filter::Node(NodeId::from("a"))), vec!["3"]);
+        assert_eq!(reasons(&store, Filter::Pool(PoolId::from("a"))), vec!["2"]);
+        assert!(reasons(&store, Filter::Pool(PoolId::from("b"))).is_empty());
+        let filter = Filter::NodePool(NodeId::from("a"), PoolId::from("a"));
+        assert!(store.get(&filter).is_err());
+
+        let store = EventStore::new(0);
+        store.record(event(ResourceKind::Node, "a", "1"));
+        assert!(reasons(&store, Filter::None).is_empty());
+    }
+}
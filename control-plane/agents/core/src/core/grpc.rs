@@ -1,7 +1,7 @@
 use crate::node::service::NodeCommsTimeout;
 use common::errors::{GrpcConnect, GrpcConnectUri, SvcError};
 use common_lib::{mbus_api::MessageIdTimeout, types::v0::message_bus::NodeId};
-use rpc::io_engine::IoEngineClient;
+use rpc::{io_engine::IoEngineClient, v1::pool::pool_rpc_client::PoolRpcClient};
 use snafu::ResultExt;
 use std::{
     ops::{Deref, DerefMut},
@@ -85,13 +85,16 @@ pub(crate) struct GrpcClient {
     context: GrpcContext,
     /// gRPC IoEngine Client
     pub(crate) io_engine: MayaClient,
+    /// gRPC IoEngine v1 Pool Client
+    pub(crate) pool: PoolClient,
 }
 pub(crate) type MayaClient = IoEngineClient<Channel>;
+pub(crate) type PoolClient = PoolRpcClient<Channel>;
 impl GrpcClient {
     pub(crate) async fn new(context: &GrpcContext) -> Result<Self, SvcError> {
-        let client = match tokio::time::timeout(
+        let channel = match tokio::time::timeout(
             context.comms_timeouts.connect(),
-            MayaClient::connect(context.endpoint.clone()),
+            context.endpoint.connect(),
         )
         .await
         {
@@ -100,7 +103,7 @@ impl GrpcClient {
                 endpoint: context.endpoint.uri().to_string(),
                 timeout: context.comms_timeouts.connect(),
             }),
-            Ok(client) => Ok(client.context(GrpcConnect {
+            Ok(channel) => Ok(channel.context(GrpcConnect {
                 node_id: context.node.to_string(),
                 endpoint: context.endpoint.uri().to_string(),
             })?),
@@ -108,7 +111,8 @@ impl GrpcClient {
 
         Ok(Self {
             context: context.clone(),
-            io_engine: client,
+            io_engine: MayaClient::new(channel.clone()),
+            pool: PoolClient::new(channel),
         })
     }
 }
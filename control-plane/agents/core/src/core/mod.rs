@@ -1,5 +1,9 @@
 //! Common modules used by the different core services
 
+/// audit trail of the mutating requests
+pub mod audit;
+/// in-memory store of the recent control-plane events
+pub(crate) mod events;
 /// gRPC helpers
 pub mod grpc;
 /// reconciliation logic
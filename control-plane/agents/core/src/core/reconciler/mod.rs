@@ -1,4 +1,5 @@
 mod nexus;
+mod node;
 mod persistent_store;
 pub mod poller;
 mod pool;
@@ -42,7 +43,6 @@ impl ReconcilerControl {
 
     /// Send the shutdown signal to the poller's main loop
     /// (does not wait for the pollers to stop)
-    #[allow(dead_code)]
     pub(crate) async fn shutdown(&self) {
         self.shutdown_channel.send(()).await.ok();
     }
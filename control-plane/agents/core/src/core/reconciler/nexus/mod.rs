@@ -1,9 +1,10 @@
 mod garbage_collector;
+mod replica_rebalancer;
 
 use crate::{
     core::{
         scheduling::resources::HealthyChildItems,
-        specs::{OperationSequenceGuard, SpecOperations},
+        specs::{OperationSequenceGuard, ResourceSpecsLocked, SpecOperations},
         task_poller::{
             squash_results, PollContext, PollPeriods, PollResult, PollTimer, PollerState,
             TaskPoller,
@@ -13,9 +14,12 @@ use crate::{
     nexus::scheduling::get_healthy_nexus_children,
 };
 use common_lib::{
-    mbus_api::ErrorChain,
+    mbus_api::{ErrorChain, ResourceKind},
     types::v0::{
-        message_bus::{CreateNexus, NexusShareProtocol, NodeStatus, ShareNexus, UnshareNexus},
+        message_bus::{
+            ChildState, CreateNexus, Event, EventType, NexusShareProtocol, NodeStatus,
+            OnlineNexusChild, ShareNexus, UnshareNexus,
+        },
         store::{
             nexus::{NexusSpec, ReplicaUri},
             nexus_child::NexusChild,
@@ -24,6 +28,8 @@ use common_lib::{
     },
 };
 use garbage_collector::GarbageCollector;
+pub(crate) use replica_rebalancer::move_replica;
+use replica_rebalancer::ReplicaRebalancer;
 
 use crate::core::wrapper::NodeWrapper;
 use common_lib::types::v0::message_bus::NexusStatus;
@@ -43,7 +49,10 @@ impl NexusReconciler {
     pub fn from(period: PollPeriods) -> Self {
         NexusReconciler {
             counter: PollTimer::from(period),
-            poll_targets: vec![Box::new(GarbageCollector::new())],
+            poll_targets: vec![
+                Box::new(GarbageCollector::new()),
+                Box::new(ReplicaRebalancer::new()),
+            ],
         }
     }
     /// Return new `Self` with the default period
@@ -94,6 +103,7 @@ async fn nexus_reconciler(
     let mut results = Vec::with_capacity(5);
     if created {
         results.push(faulted_children_remover(nexus_spec, context, mode).await);
+        results.push(degraded_children_onliner(nexus_spec, context).await);
         results.push(unknown_children_remover(nexus_spec, context, mode).await);
         results.push(missing_children_remover(nexus_spec, context, mode).await);
         results.push(missing_nexus_recreate(nexus_spec, context, mode).await);
@@ -115,11 +125,20 @@ pub(super) async fn faulted_children_remover(
     let nexus_state = context.registry().get_nexus(&nexus_uuid).await?;
     let child_count = nexus_state.children.len();
 
+    // Children are only removed once they've been faulted for longer than the grace period, so
+    // that transient faults don't trigger a full rebuild
+    let faulted = nexus_state
+        .children
+        .iter()
+        .filter(|c| context.registry().child_faulted_past_grace(c))
+        .map(|c| c.uri.clone())
+        .collect::<Vec<_>>();
+
     // Remove faulted children only from a degraded nexus with other healthy children left
-    if nexus_state.status == NexusStatus::Degraded && child_count > 1 {
+    if nexus_state.status == NexusStatus::Degraded && child_count > 1 && !faulted.is_empty() {
         async {
             let nexus_spec_clone = nexus_spec.lock().clone();
-            for child in nexus_state.children.iter().filter(|c| c.state.faulted()) {
+            for child in nexus_state.children.iter().filter(|c| faulted.contains(&c.uri)) {
                 nexus_spec_clone
                     .warn_span(|| tracing::warn!("Attempting to remove faulted child '{}'", child.uri));
                 if let Err(error) = context
@@ -134,6 +153,17 @@ pub(super) async fn faulted_children_remover(
                         "Failed to remove faulted child"
                     )
                     });
+                    context.registry().record_event(Event::new(
+                        ResourceKind::Nexus,
+                        nexus_uuid.to_string(),
+                        EventType::Warning,
+                        "FaultedChildRemoveFailed",
+                        format!(
+                            "Failed to remove faulted child '{}': {}",
+                            child.uri,
+                            error.full_string()
+                        ),
+                    ));
                 } else {
                     nexus_spec_clone.info_span(|| {
                         tracing::info!(
@@ -141,6 +171,13 @@ pub(super) async fn faulted_children_remover(
                         "Successfully removed faulted child",
                     )
                     });
+                    context.registry().record_event(Event::new(
+                        ResourceKind::Nexus,
+                        nexus_uuid.to_string(),
+                        EventType::Normal,
+                        "FaultedChildRemoved",
+                        format!("Removed faulted child '{}'", child.uri),
+                    ));
                 }
             }
         }
@@ -151,6 +188,92 @@ pub(super) async fn faulted_children_remover(
     PollResult::Ok(PollerState::Idle)
 }
 
+/// Record on the nexus trace span that the reconcile `step` has been deferred because starting
+/// another rebuild would exceed the system-wide rebuild limit
+pub(super) fn defer_for_rebuild_limit(nexus_spec: &NexusSpec, step: &str) {
+    nexus_spec.info_span(|| {
+        tracing::info!(
+            reconcile.step = step,
+            "Deferring the reconcile step as the maximum number of rebuilds are in progress"
+        )
+    });
+}
+
+/// Find degraded children of the given nexus which are not rebuilding and whose replica's node is
+/// online, and bring them back online
+/// This avoids a full rebuild of a child which only briefly lost connectivity, eg: node flap
+#[tracing::instrument(skip(nexus_spec, context), level = "trace", fields(nexus.uuid = %nexus_spec.lock().uuid, request.reconcile = true))]
+pub(super) async fn degraded_children_onliner(
+    nexus_spec: &Arc<Mutex<NexusSpec>>,
+    context: &PollContext,
+) -> PollResult {
+    let nexus_spec_clone = nexus_spec.lock().clone();
+    let nexus_state = context.registry().get_nexus(&nexus_spec_clone.uuid).await?;
+
+    let mut result = PollResult::Ok(PollerState::Idle);
+    for child in nexus_state
+        .children
+        .iter()
+        .filter(|c| c.state == ChildState::Degraded && c.rebuild_progress.is_none())
+    {
+        // only replica children can be onlined as we know nothing about the other devices
+        let replica = match nexus_spec_clone
+            .children
+            .iter()
+            .find(|c| c.uri() == child.uri)
+            .and_then(|c| c.as_replica())
+        {
+            Some(replica) => replica,
+            None => continue,
+        };
+        let pool = match context.specs().get_replica(replica.uuid()) {
+            Some(replica) => replica.lock().pool.clone(),
+            None => continue,
+        };
+        let replica_node_online =
+            match ResourceSpecsLocked::get_pool_node(context.registry(), pool).await {
+                Some(node) => match context.registry().get_node_wrapper(&node).await {
+                    Ok(node) => node.read().await.is_online(),
+                    Err(_) => false,
+                },
+                None => false,
+            };
+        if !replica_node_online {
+            continue;
+        }
+
+        nexus_spec_clone
+            .info_span(|| tracing::info!("Attempting to online degraded child '{}'", child.uri));
+        let request = OnlineNexusChild::new(&nexus_state.node, &nexus_state.uuid, &child.uri);
+        let online = match context.registry().get_node_wrapper(&nexus_state.node).await {
+            Ok(node) => node.online_child(&request).await,
+            Err(error) => Err(error),
+        };
+        match online {
+            Ok(_) => {
+                nexus_spec_clone.info_span(|| {
+                    tracing::info!(
+                        child.uri = %child.uri.as_str(),
+                        "Successfully onlined degraded child"
+                    )
+                });
+            }
+            Err(error) => {
+                nexus_spec_clone.error_span(|| {
+                    tracing::error!(
+                        error = %error.full_string().as_str(),
+                        child.uri = %child.uri.as_str(),
+                        "Failed to online degraded child"
+                    )
+                });
+                result = PollResult::Err(error);
+            }
+        }
+    }
+
+    result
+}
+
 /// Find and removes unknown children from the given nexus
 /// If the child is a replica it also disowns and destroys it
 #[tracing::instrument(skip(nexus_spec, context, mode), level = "trace", fields(nexus.uuid = %nexus_spec.lock().uuid, request.reconcile = true))]
@@ -297,6 +420,12 @@ pub(super) async fn missing_nexus_recreate(
             Ok(node) => node,
         };
 
+        // the recreated nexus gets its missing children replaced, which must be rebuilt
+        if context.registry().available_rebuilds().await == Some(0) {
+            defer_for_rebuild_limit(&nexus, "missing_nexus_recreate");
+            return PollResult::Ok(PollerState::Busy);
+        }
+
         nexus.warn_span(|| tracing::warn!("Attempting to recreate missing nexus"));
 
         let children = get_healthy_nexus_children(&nexus, context.registry()).await?;
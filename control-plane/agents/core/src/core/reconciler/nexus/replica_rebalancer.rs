@@ -0,0 +1,390 @@
+use crate::{
+    core::{
+        reconciler::{PollContext, TaskPoller},
+        specs::OperationSequenceGuard,
+        task_poller::{PollEvent, PollResult, PollTimer, PollTriggerEvent, PollerState},
+    },
+    volume::specs::get_volume_replica_candidates,
+};
+use common::errors::{NexusNotFound, SvcError};
+use common_lib::{
+    mbus_api::ErrorChain,
+    types::v0::{
+        message_bus::{ChildState, CreateReplica, PoolId, PoolState, ReplicaId, VolumeStatus},
+        store::{
+            node::ReplicaMove, replica::ReplicaSpec, volume::VolumeSpec, OperationMode, TraceSpan,
+            TraceStrLog,
+        },
+    },
+};
+
+use parking_lot::Mutex;
+use snafu::OptionExt;
+use std::{collections::HashMap, sync::Arc};
+
+/// Replica Rebalancer reconciler
+/// Moves volume replicas away from pools which are utilized more than the configured threshold
+/// It also completes all replica moves in progress, including the ones started by other
+/// reconcilers (eg: when draining a node)
+#[derive(Debug)]
+pub(super) struct ReplicaRebalancer {
+    counter: PollTimer,
+}
+impl ReplicaRebalancer {
+    /// Return a new `Self`
+    pub(super) fn new() -> Self {
+        Self {
+            counter: PollTimer::from(5),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TaskPoller for ReplicaRebalancer {
+    async fn poll(&mut self, context: &PollContext) -> PollResult {
+        let moves = context.specs().get_replica_moves();
+        if !moves.is_empty() {
+            let mut results = Vec::with_capacity(moves.len());
+            for replica_move in moves {
+                results.push(complete_replica_move(&replica_move, context).await);
+            }
+            return Self::squash_results(results);
+        }
+
+        match context.registry().rebalance_threshold() {
+            Some(threshold) => start_replica_move(threshold, context).await,
+            None => PollResult::Ok(PollerState::Idle),
+        }
+    }
+
+    async fn poll_timer(&mut self, _context: &PollContext) -> bool {
+        self.counter.poll()
+    }
+
+    async fn poll_event(&mut self, context: &PollContext) -> bool {
+        if context.registry().rebalance_threshold().is_none()
+            && context.specs().get_replica_moves().is_empty()
+        {
+            return false;
+        }
+        match context.event() {
+            PollEvent::TimedRun
+            | PollEvent::Triggered(PollTriggerEvent::Start)
+            | PollEvent::Triggered(PollTriggerEvent::ReconcileRequested) => true,
+            PollEvent::Shutdown | PollEvent::Triggered(_) => false,
+        }
+    }
+}
+
+/// Utilization of the pool, in percentage of its capacity
+fn pool_utilization(pool: &PoolState) -> u64 {
+    if pool.capacity == 0 {
+        return 100;
+    }
+    pool.used.saturating_mul(100) / pool.capacity
+}
+
+/// Given a managed volume which is online
+/// When one of its replicas is on a pool utilized above the rebalance threshold
+/// Then a new replica should be created on a less utilized pool and added to the volume nexus
+/// And only a single replica move is started at a time
+async fn start_replica_move(threshold: u8, context: &PollContext) -> PollResult {
+    let pools = context
+        .registry()
+        .get_pool_states_inner()
+        .await
+        .into_iter()
+        .map(|p| (p.id.clone(), p))
+        .collect::<HashMap<PoolId, PoolState>>();
+    let threshold = threshold as u64;
+    if !pools.values().any(|p| pool_utilization(p) > threshold) {
+        return PollResult::Ok(PollerState::Idle);
+    }
+
+    for volume_spec in context.specs().get_locked_volumes() {
+        match rebalance_volume(&volume_spec, &pools, threshold, context).await {
+            Ok(true) => return PollResult::Ok(PollerState::Busy),
+            Ok(false) => {}
+            Err(SvcError::MaxRebuilds { .. }) => return PollResult::Ok(PollerState::Idle),
+            Err(error) => {
+                volume_spec.lock().error(&format!(
+                    "Failed to rebalance the volume replicas, error: '{}'",
+                    error.full_string()
+                ));
+            }
+        }
+    }
+
+    PollResult::Ok(PollerState::Idle)
+}
+
+/// Attempt to start moving a replica of the given volume away from an over utilized pool
+/// Returns true if a replica move was started
+#[tracing::instrument(level = "debug", skip(volume_spec, pools, threshold, context), fields(volume.uuid = %volume_spec.lock().uuid, request.reconcile = true))]
+async fn rebalance_volume(
+    volume_spec: &Arc<Mutex<VolumeSpec>>,
+    pools: &HashMap<PoolId, PoolState>,
+    threshold: u64,
+    context: &PollContext,
+) -> Result<bool, SvcError> {
+    let vol_spec_clone = {
+        let volume = volume_spec.lock();
+        if !volume.policy.self_heal || !volume.status.created() {
+            return Ok(false);
+        }
+        volume.clone()
+    };
+
+    let replicas = context
+        .specs()
+        .get_cloned_volume_replicas(&vol_spec_clone.uuid);
+    let source = match replicas.into_iter().find(|replica| {
+        pools
+            .get(&replica.pool)
+            .map(|pool| pool_utilization(pool) > threshold)
+            .unwrap_or_default()
+    }) {
+        Some(replica) => replica,
+        None => return Ok(false),
+    };
+
+    // only move to pools which will still be under the threshold with the new replica
+    let under_threshold = |candidate: &CreateReplica| {
+        pools
+            .get(&candidate.pool)
+            .map(|pool| {
+                let mut pool = pool.clone();
+                pool.used += candidate.size;
+                pool_utilization(&pool) <= threshold
+            })
+            .unwrap_or_default()
+    };
+    move_replica(
+        volume_spec,
+        &source,
+        "Attempting to move replica away from over utilized pool",
+        under_threshold,
+        context,
+    )
+    .await
+}
+
+/// Start moving the `source` replica of the given volume to one of the candidate pools which
+/// satisfy the `filter`: a new replica is created and added to the volume nexus and, once it's
+/// rebuilt, the `source` replica is removed from the nexus by the `ReplicaRebalancer`.
+/// Only online published volumes can have their replicas moved, as the new replica is rebuilt
+/// by the volume nexus.
+/// Returns true if a replica move was started
+pub(crate) async fn move_replica(
+    volume_spec: &Arc<Mutex<VolumeSpec>>,
+    source: &ReplicaSpec,
+    reason: &str,
+    filter: impl Fn(&CreateReplica) -> bool,
+    context: &PollContext,
+) -> Result<bool, SvcError> {
+    let vol_spec_clone = {
+        let volume = volume_spec.lock();
+        if !volume.status.created() {
+            return Ok(false);
+        }
+        volume.clone()
+    };
+
+    let volume_state = context
+        .registry()
+        .get_volume_state(&vol_spec_clone.uuid)
+        .await?;
+    let nexus = match &volume_state.target {
+        Some(nexus) if volume_state.status == VolumeStatus::Online => nexus.clone(),
+        _ => return Ok(false),
+    };
+    let nexus_spec = context
+        .specs()
+        .get_nexus(&nexus.uuid)
+        .context(NexusNotFound {
+            nexus_id: nexus.uuid.to_string(),
+        })?;
+
+    let _guard = match volume_spec.operation_guard(OperationMode::ReconcileStart) {
+        Ok(guard) => guard,
+        Err(_) => return Ok(false),
+    };
+    let _nexus_guard = match nexus_spec.operation_guard(OperationMode::ReconcileStart) {
+        Ok(guard) => guard,
+        Err(_) => return Ok(false),
+    };
+    let mode = OperationMode::ReconcileStep;
+
+    // the new replica is going to be rebuilt, so don't even try if we're at the rebuild limit
+    context.registry().rebuild_allowed().await?;
+    // the move is registered with the node the replica is moved away from
+    let source_node = context.specs().get_pool(&source.pool)?.node;
+
+    let candidates = get_volume_replica_candidates(context.registry(), &vol_spec_clone)
+        .await?
+        .into_iter()
+        .filter(|candidate| filter(candidate))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        vol_spec_clone.debug(&format!(
+            "No pool is available to move replica '{}' to",
+            source.uuid
+        ));
+        return Ok(false);
+    }
+
+    vol_spec_clone.info_span(|| {
+        tracing::info!(
+            replica.uuid = %source.uuid,
+            pool.uuid = %source.pool,
+            "{}",
+            reason
+        )
+    });
+
+    let replica = context
+        .specs()
+        .create_volume_replica(context.registry(), &volume_state, &candidates, mode)
+        .await?;
+    context
+        .specs()
+        .attach_replica_to_nexus(
+            context.registry(),
+            &vol_spec_clone.uuid,
+            &nexus,
+            &replica,
+            mode,
+        )
+        .await?;
+
+    vol_spec_clone.info_span(|| {
+        tracing::info!(
+            replica.uuid = %source.uuid,
+            replica.target = %replica.uuid,
+            "Started moving replica, waiting for the new replica to be rebuilt"
+        )
+    });
+    context
+        .specs()
+        .add_replica_move(
+            context.registry(),
+            &source_node,
+            ReplicaMove {
+                volume: vol_spec_clone.uuid.clone(),
+                source: source.uuid.clone(),
+                target: replica.uuid,
+            },
+        )
+        .await?;
+    Ok(true)
+}
+
+/// Given a replica move in progress
+/// When the new replica has been rebuilt
+/// Then the old replica should be removed from the volume nexus
+/// And it should eventually be destroyed
+#[tracing::instrument(level = "debug", skip(replica_move, context), fields(volume.uuid = %replica_move.volume, request.reconcile = true))]
+async fn complete_replica_move(replica_move: &ReplicaMove, context: &PollContext) -> PollResult {
+    let volume = &replica_move.volume;
+    let volume_spec = match context.specs().get_locked_volume(volume) {
+        Some(volume_spec) => volume_spec,
+        None => {
+            context
+                .specs()
+                .remove_replica_move(context.registry(), volume)
+                .await?;
+            return PollResult::Ok(PollerState::Idle);
+        }
+    };
+    let vol_spec_clone = volume_spec.lock().clone();
+    let nexus_spec = match context.specs().get_volume_target_nexus(&vol_spec_clone) {
+        Some(nexus_spec) => nexus_spec,
+        None => {
+            vol_spec_clone.warn(&format!(
+                "Volume target is gone, abandoning the move of replica '{}'",
+                replica_move.source
+            ));
+            context
+                .specs()
+                .remove_replica_move(context.registry(), volume)
+                .await?;
+            return PollResult::Ok(PollerState::Idle);
+        }
+    };
+
+    let _guard = match volume_spec.operation_guard(OperationMode::ReconcileStart) {
+        Ok(guard) => guard,
+        Err(_) => return PollResult::Ok(PollerState::Busy),
+    };
+    let _nexus_guard = match nexus_spec.operation_guard(OperationMode::ReconcileStart) {
+        Ok(guard) => guard,
+        Err(_) => return PollResult::Ok(PollerState::Busy),
+    };
+    let mode = OperationMode::ReconcileStep;
+
+    let nexus_spec_clone = nexus_spec.lock().clone();
+    let child_uri = |replica: &ReplicaId| {
+        nexus_spec_clone
+            .children
+            .iter()
+            .find(|c| {
+                c.as_replica()
+                    .map(|r| r.uuid() == replica)
+                    .unwrap_or_default()
+            })
+            .map(|c| c.uri())
+    };
+    let (source_uri, target_uri) = match (
+        child_uri(&replica_move.source),
+        child_uri(&replica_move.target),
+    ) {
+        (Some(source), Some(target)) => (source, target),
+        _ => {
+            vol_spec_clone.warn(&format!(
+                "Nexus children changed, abandoning the move of replica '{}'",
+                replica_move.source
+            ));
+            context
+                .specs()
+                .remove_replica_move(context.registry(), volume)
+                .await?;
+            return PollResult::Ok(PollerState::Idle);
+        }
+    };
+
+    let nexus_state = context.registry().get_nexus(&nexus_spec_clone.uuid).await?;
+    match nexus_state.children.iter().find(|c| c.uri == target_uri) {
+        Some(child) if child.state == ChildState::Online => {}
+        Some(child) if child.state.faulted() => {
+            vol_spec_clone.warn(&format!(
+                "New replica '{}' is faulted, abandoning the move of replica '{}'",
+                replica_move.target, replica_move.source
+            ));
+            context
+                .specs()
+                .remove_replica_move(context.registry(), volume)
+                .await?;
+            return PollResult::Ok(PollerState::Idle);
+        }
+        // still rebuilding
+        _ => return PollResult::Ok(PollerState::Busy),
+    }
+
+    context
+        .specs()
+        .remove_nexus_child_by_uri(context.registry(), &nexus_state, &source_uri, true, mode)
+        .await?;
+    context
+        .specs()
+        .remove_replica_move(context.registry(), volume)
+        .await?;
+    vol_spec_clone.info_span(|| {
+        tracing::info!(
+            replica.uuid = %replica_move.source,
+            replica.target = %replica_move.target,
+            "Successfully moved replica"
+        )
+    });
+
+    PollResult::Ok(PollerState::Idle)
+}
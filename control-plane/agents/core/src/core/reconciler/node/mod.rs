@@ -0,0 +1,255 @@
+use crate::{
+    core::{
+        reconciler::nexus::move_replica,
+        specs::OperationSequenceGuard,
+        task_poller::{
+            PollContext, PollEvent, PollResult, PollTimer, PollTriggerEvent, PollerState,
+            TaskPoller,
+        },
+    },
+    volume::specs::{get_volume_replica_candidates, select_target_node},
+};
+use common::errors::SvcError;
+use common_lib::{
+    mbus_api::ErrorChain,
+    types::v0::{
+        message_bus::RepublishVolume,
+        store::{
+            node::NodeSpec, replica::ReplicaSpec, volume::VolumeSpec, OperationMode, TraceSpan,
+            TraceStrLog,
+        },
+    },
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// Node reconciler
+/// Drains the nodes which have been requested to, by moving the volume replicas away from them
+#[derive(Debug)]
+pub struct NodeReconciler {
+    counter: PollTimer,
+}
+
+impl NodeReconciler {
+    /// Return a new `Self`
+    pub fn new() -> Self {
+        Self {
+            counter: PollTimer::from(5),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TaskPoller for NodeReconciler {
+    async fn poll(&mut self, context: &PollContext) -> PollResult {
+        let nodes = context.specs().get_nodes();
+        let mut results = vec![];
+        for node in nodes.into_iter().filter(|node| node.draining()) {
+            results.push(drain_node(&node, context).await);
+        }
+        Self::squash_results(results)
+    }
+
+    async fn poll_timer(&mut self, _context: &PollContext) -> bool {
+        self.counter.poll()
+    }
+
+    async fn poll_event(&mut self, context: &PollContext) -> bool {
+        match context.event() {
+            PollEvent::TimedRun
+            | PollEvent::Triggered(PollTriggerEvent::NodeDrain)
+            | PollEvent::Triggered(PollTriggerEvent::Start)
+            | PollEvent::Triggered(PollTriggerEvent::ReconcileRequested) => true,
+            PollEvent::Shutdown | PollEvent::Triggered(_) => false,
+        }
+    }
+}
+
+/// Given a node which is draining
+/// When a volume has its target or a replica on the node
+/// Then the target should be republished on another node
+/// And the replica should be moved to a pool on another node, one volume at a time
+/// And once no volume targets nor replicas are left on the node, it should be marked as drained
+/// The replicas of published volumes are moved by the volume nexus, which rebuilds them. The
+/// replicas of unpublished volumes are recreated on another node and rebuilt once the volume is
+/// published again, provided another replica of the volume is healthy.
+#[tracing::instrument(level = "debug", skip(node, context), fields(node.uuid = %node.id(), request.reconcile = true))]
+async fn drain_node(node: &NodeSpec, context: &PollContext) -> PollResult {
+    let mut remaining = 0;
+    for volume_spec in context.specs().get_locked_volumes() {
+        let vol_spec_clone = volume_spec.lock().clone();
+        let volume_uuid = vol_spec_clone.uuid.clone();
+
+        let result = if vol_spec_clone
+            .target
+            .as_ref()
+            .map(|target| target.node() == node.id())
+            .unwrap_or_default()
+        {
+            remaining += 1;
+            republish_volume(&volume_spec, context).await
+        } else {
+            let replicas = context.specs().get_cloned_volume_replicas(&volume_uuid);
+            let source = replicas.iter().find(|replica| {
+                context
+                    .specs()
+                    .get_pool(&replica.pool)
+                    .map(|pool| &pool.node == node.id())
+                    .unwrap_or_default()
+            });
+            let source = match source {
+                Some(source) if source.managed => source,
+                _ => continue,
+            };
+            remaining += 1;
+
+            // wait for the moves in progress to complete before starting another one
+            if context.specs().replica_move_pending(&volume_uuid)
+                || replicas.len() > vol_spec_clone.num_replicas as usize
+            {
+                continue;
+            }
+            if vol_spec_clone.target.is_some() {
+                move_replica(
+                    &volume_spec,
+                    source,
+                    "Attempting to move replica away from draining node",
+                    |_| true,
+                    context,
+                )
+                .await
+                .map(|_| ())
+            } else {
+                recreate_replica(&volume_spec, node, source, context).await
+            }
+        };
+
+        match result {
+            Ok(_) => {}
+            Err(SvcError::MaxRebuilds { .. }) => return PollResult::Ok(PollerState::Idle),
+            Err(error) => {
+                vol_spec_clone.error(&format!(
+                    "Failed to move the volume away from draining node '{}', error: '{}'",
+                    node.id(),
+                    error.full_string()
+                ));
+            }
+        }
+    }
+
+    if remaining > 0 {
+        return PollResult::Ok(PollerState::Busy);
+    }
+    context
+        .specs()
+        .set_node_drained(context.registry(), node.id())
+        .await?;
+    tracing::info!(node.uuid = %node.id(), "Node has been drained");
+    PollResult::Ok(PollerState::Idle)
+}
+
+/// Republish the target of the given volume on another node, which is not cordoned
+async fn republish_volume(
+    volume_spec: &Arc<Mutex<VolumeSpec>>,
+    context: &PollContext,
+) -> Result<(), SvcError> {
+    let _guard = match volume_spec.operation_guard(OperationMode::ReconcileStart) {
+        Ok(guard) => guard,
+        Err(_) => return Ok(()),
+    };
+    let volume_uuid = volume_spec.lock().uuid.clone();
+    let target_node = select_target_node(context.registry()).await?;
+
+    volume_spec.lock().info_span(|| {
+        tracing::info!(
+            node.uuid = %target_node,
+            "Attempting to republish the volume target away from draining node"
+        )
+    });
+    context
+        .specs()
+        .republish_volume(
+            context.registry(),
+            &RepublishVolume::new(volume_uuid, target_node, None),
+            OperationMode::ReconcileStep,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Recreate the `source` replica of the given unpublished volume on a pool on another node
+/// As there's no volume nexus to rebuild the new replica, it's only rebuilt once the volume is
+/// published again, so the `source` replica may only go if another replica of the volume is
+/// healthy as per the persisted nexus info of the volume's last target.
+async fn recreate_replica(
+    volume_spec: &Arc<Mutex<VolumeSpec>>,
+    node: &NodeSpec,
+    source: &ReplicaSpec,
+    context: &PollContext,
+) -> Result<(), SvcError> {
+    let _guard = match volume_spec.operation_guard(OperationMode::ReconcileStart) {
+        Ok(guard) => guard,
+        Err(_) => return Ok(()),
+    };
+    let mode = OperationMode::ReconcileStep;
+    let vol_spec_clone = volume_spec.lock().clone();
+    if !vol_spec_clone.status.created() || vol_spec_clone.target.is_some() {
+        return Ok(());
+    }
+
+    let other_replica_healthy = match &vol_spec_clone.last_nexus_id {
+        // the volume has never been published, so no replica has any data yet
+        None => true,
+        Some(nexus_id) => match context
+            .registry()
+            .get_nexus_info(Some(&vol_spec_clone.uuid), Some(nexus_id), true)
+            .await?
+        {
+            Some(info) => context
+                .specs()
+                .get_cloned_volume_replicas(&vol_spec_clone.uuid)
+                .iter()
+                .any(|replica| {
+                    replica.uuid != source.uuid && info.is_replica_healthy(&replica.uuid)
+                }),
+            None => false,
+        },
+    };
+    if !other_replica_healthy {
+        vol_spec_clone.warn(&format!(
+            "Replica '{}' may hold the only healthy copy of the volume data, it can only be moved once the volume is published",
+            source.uuid
+        ));
+        return Ok(());
+    }
+
+    let volume_state = context
+        .registry()
+        .get_volume_state(&vol_spec_clone.uuid)
+        .await?;
+    let candidates = get_volume_replica_candidates(context.registry(), &vol_spec_clone).await?;
+
+    vol_spec_clone.info_span(|| {
+        tracing::info!(
+            replica.uuid = %source.uuid,
+            pool.uuid = %source.pool,
+            "Attempting to recreate replica away from draining node"
+        )
+    });
+    let replica = context
+        .specs()
+        .create_volume_replica(context.registry(), &volume_state, &candidates, mode)
+        .await?;
+    context
+        .specs()
+        .disown_and_destroy_replica(context.registry(), node.id(), &source.uuid)
+        .await?;
+    vol_spec_clone.info_span(|| {
+        tracing::info!(
+            replica.uuid = %source.uuid,
+            replica.target = %replica.uuid,
+            "Successfully recreated replica"
+        )
+    });
+    Ok(())
+}
@@ -4,7 +4,7 @@ use crate::core::{
     wrapper::ClientOps,
 };
 use common_lib::types::v0::{
-    message_bus::{CreatePool, DestroyPool, NodeStatus},
+    message_bus::{CreatePool, DestroyPool, ImportPool, NodeStatus},
     store::{pool::PoolSpec, OperationMode, TraceSpan},
 };
 use parking_lot::Mutex;
@@ -98,8 +98,20 @@ async fn missing_pool_state_reconciler(
         async {
             pool.warn_span(|| tracing::warn!("Attempting to recreate missing pool"));
 
-            let request = CreatePool::new(&pool.node, &pool.id, &pool.disks, &pool.labels);
-            match node.create_pool(&request).await {
+            // imported pools are re-imported with their uuid, so they keep their identity
+            let result = match &pool.uuid {
+                Some(uuid) => {
+                    let request =
+                        ImportPool::new(&pool.node, &pool.id, uuid, &pool.disks, &pool.labels);
+                    node.import_pool(&request).await
+                }
+                None => {
+                    let request =
+                        CreatePool::new(&pool.node, &pool.id, &pool.disks, &pool.labels);
+                    node.create_pool(&request).await
+                }
+            };
+            match result {
                 Ok(_) => {
                     pool.info_span(|| tracing::info!("Pool successfully recreated"));
                     PollResult::Ok(PollerState::Idle)
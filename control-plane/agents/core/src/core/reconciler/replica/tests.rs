@@ -37,6 +37,8 @@ async fn test_disown_missing_owners() {
                 share: None,
                 size: 5242880,
                 thin: false,
+                annotations: None,
+                encryption: None,
             },
         )
         .await
@@ -46,9 +48,10 @@ async fn test_disown_missing_owners() {
     let num_replicas = cluster
         .rest_v00()
         .replicas_api()
-        .get_replicas()
+        .get_replicas(0, None)
         .await
         .expect("Failed to get replicas.")
+        .entries
         .len();
     assert_eq!(num_replicas, 1);
 
@@ -78,9 +81,10 @@ async fn test_disown_missing_owners() {
     let num_replicas = cluster
         .rest_v00()
         .replicas_api()
-        .get_replicas()
+        .get_replicas(0, None)
         .await
         .expect("Failed to get replicas.")
+        .entries
         .len();
     assert_eq!(num_replicas, 0);
 }
@@ -50,7 +50,8 @@ impl TaskPoller for GarbageCollector {
         match context.event() {
             PollEvent::TimedRun
             | PollEvent::Triggered(PollTriggerEvent::VolumeDegraded)
-            | PollEvent::Triggered(PollTriggerEvent::Start) => true,
+            | PollEvent::Triggered(PollTriggerEvent::Start)
+            | PollEvent::Triggered(PollTriggerEvent::ReconcileRequested) => true,
             PollEvent::Shutdown | PollEvent::Triggered(_) => false,
         }
     }
@@ -294,7 +295,14 @@ mod tests {
             .unwrap();
 
         let volume = volumes_api
-            .put_volume_target(&volume.spec.uuid, &node, models::VolumeShareProtocol::Nvmf)
+            .put_volume_target(
+                &volume.spec.uuid,
+                &node,
+                models::VolumeShareProtocol::Nvmf,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
 
@@ -9,7 +9,11 @@ use common_lib::{
     mbus_api::ErrorChain,
     types::v0::{
         message_bus::{VolumeState, VolumeStatus},
-        store::{nexus::NexusSpec, volume::VolumeSpec, OperationMode},
+        store::{
+            nexus::NexusSpec,
+            volume::{VolumeEventType, VolumeSpec},
+            OperationMode,
+        },
     },
 };
 
@@ -56,6 +60,8 @@ async fn hot_spare_reconcile(
     };
     let mode = OperationMode::ReconcileStep;
 
+    volume_health_event_recorder(volume_spec, &volume_state, context).await;
+
     if !volume_spec.lock().policy.self_heal {
         return PollResult::Ok(PollerState::Idle);
     }
@@ -72,6 +78,39 @@ async fn hot_spare_reconcile(
     }
 }
 
+/// Given a created volume
+/// When the volume becomes degraded, or is back online after being degraded
+/// Then the transition should be recorded in the volume event history
+async fn volume_health_event_recorder(
+    volume_spec: &Arc<Mutex<VolumeSpec>>,
+    volume_state: &VolumeState,
+    context: &PollContext,
+) {
+    let event = {
+        let spec = volume_spec.lock();
+        if !spec.status.created() {
+            return;
+        }
+        let degraded = matches!(
+            spec.last_health_event().map(|e| e.event),
+            Some(VolumeEventType::Degraded)
+        );
+        match volume_state.status {
+            VolumeStatus::Degraded if !degraded => VolumeEventType::Degraded,
+            VolumeStatus::Online if degraded => VolumeEventType::Healed,
+            _ => return,
+        }
+    };
+    let detail = match event {
+        VolumeEventType::Degraded => "volume is degraded",
+        _ => "volume is back online",
+    };
+    context
+        .specs()
+        .record_volume_event(context.registry(), volume_spec, event, detail)
+        .await;
+}
+
 async fn hot_spare_nexus_reconcile(
     volume_spec: &Arc<Mutex<VolumeSpec>>,
     volume_state: &VolumeState,
@@ -113,6 +152,7 @@ async fn generic_nexus_reconciler(
 ) -> PollResult {
     let mut results = vec![];
     results.push(faulted_children_remover(nexus_spec, context, mode).await);
+    results.push(degraded_children_onliner(nexus_spec, context).await);
     results.push(unknown_children_remover(nexus_spec, context, mode).await);
     results.push(missing_children_remover(nexus_spec, context, mode).await);
     squash_results(results)
@@ -130,6 +170,16 @@ async fn faulted_children_remover(
     nexus::faulted_children_remover(nexus_spec, context, mode).await
 }
 
+/// Given a degraded volume
+/// When a nexus state has degraded children which are not rebuilding and their node is online
+/// Then they should eventually be brought back online
+async fn degraded_children_onliner(
+    nexus_spec: &Arc<Mutex<NexusSpec>>,
+    context: &PollContext,
+) -> PollResult {
+    nexus::degraded_children_onliner(nexus_spec, context).await
+}
+
 /// Given a degraded volume
 /// When a nexus state has children that are not present in the spec
 /// Then the children should eventually be removed from the state
@@ -167,6 +217,10 @@ async fn nexus_replica_count_reconciler(
     let nexus_state = context.registry().get_nexus(&nexus_uuid).await?;
 
     let vol_spec_clone = volume_spec.lock().clone();
+    // a replica move keeps an extra child while the new replica rebuilds, leave it be
+    if context.specs().replica_move_pending(&vol_spec_clone.uuid) {
+        return PollResult::Ok(PollerState::Busy);
+    }
     let nexus_spec_clone = nexus_spec.lock().clone();
     let volume_replicas = vol_spec_clone.num_replicas as usize;
     let nexus_replica_children =
@@ -220,6 +274,11 @@ async fn nexus_replica_count_reconciler_traced(
                     volume_replicas
                 )
             });
+            // each attached replica must be rebuilt, so wait for a rebuild to be available
+            if context.registry().available_rebuilds().await == Some(0) {
+                nexus::defer_for_rebuild_limit(&nexus_spec_clone, "nexus_replica_count_reconciler");
+                return PollResult::Ok(PollerState::Busy);
+            }
             context
                 .specs()
                 .attach_replicas_to_nexus(
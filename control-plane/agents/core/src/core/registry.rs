@@ -15,23 +15,24 @@
 //! said instance.
 use super::{specs::*, wrapper::NodeWrapper};
 use crate::core::{
+    events::EventStore,
     reconciler::ReconcilerControl,
     task_poller::{PollEvent, PollTriggerEvent},
     wrapper::InternalOps,
 };
 use common::errors::SvcError;
 use common_lib::{
-    store::etcd::Etcd,
+    store::etcd::{Etcd, LeaseStatus, StoreConnectOptions},
     types::v0::{
-        message_bus::NodeId,
+        message_bus::{CapacityForecast, Child, Event, Filter, NodeId, PoolId, PoolUsageSample},
         store::{
             definitions::{StorableObject, Store, StoreError, StoreKey},
-            registry::{ControlPlaneService, CoreRegistryConfig, NodeRegistration},
+            registry::{ControlPlaneService, CoreRegistryConfig, MaxRebuilds, NodeRegistration},
         },
     },
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     ops::{Deref, DerefMut},
     sync::Arc,
 };
@@ -73,35 +74,81 @@ pub struct RegistryInner<S: Store> {
     reconcile_idle_period: std::time::Duration,
     /// reconciliation period when work is pending
     reconcile_period: std::time::Duration,
+    /// period for which a nexus child must be continuously faulted before it's removed
+    faulted_child_grace_period: std::time::Duration,
     reconciler: ReconcilerControl,
     config: CoreRegistryConfig,
     /// system-wide maximum number of concurrent rebuilds allowed
-    max_rebuilds: Option<NumRebuilds>,
+    max_rebuilds: parking_lot::RwLock<Option<NumRebuilds>>,
+    /// serializes the updates of the maximum number of concurrent rebuilds
+    max_rebuilds_update: Mutex<()>,
+    /// pool utilization (%) above which volume replicas are moved to other pools
+    rebalance_threshold: Option<u8>,
+    /// maximum number of events retained in each volume's event history
+    volume_event_history: usize,
+    /// maximum number of specs loaded from the persistent store in a single batch during init
+    store_init_batch_size: u32,
+    /// recent capacity usage samples of each pool, oldest first
+    pool_usage: parking_lot::Mutex<HashMap<PoolId, VecDeque<PoolUsageSample>>>,
+    /// maximum number of capacity usage samples retained for each pool
+    pool_usage_samples: usize,
+    /// percentage of the pools' capacity kept free when placing new replicas, unless overridden
+    /// by the pool spec
+    reserved_capacity_percent: u8,
+    /// recent control-plane events
+    events: EventStore,
 }
 
 impl Registry {
     /// Create a new registry with the `cache_period` to reload the cache, the
-    /// `store_url` to connect to, a `store_timeout` for store operations
-    /// and a `reconcile_period` for reconcile operations
+    /// `store_url` to connect to with the `store_options`, a `store_timeout` for store
+    /// operations, an optional `store_key_prefix` override and a `reconcile_period` for
+    /// reconcile operations
+    /// Fails if the `store_key_prefix` is invalid or a different prefix is already in use
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         cache_period: std::time::Duration,
         store_url: String,
+        store_options: StoreConnectOptions,
         store_timeout: std::time::Duration,
         store_lease_tll: std::time::Duration,
+        store_key_prefix: Option<String>,
         reconcile_period: std::time::Duration,
         reconcile_idle_period: std::time::Duration,
+        faulted_child_grace_period: std::time::Duration,
         max_rebuilds: Option<NumRebuilds>,
-    ) -> Self {
+        rebalance_threshold: Option<u8>,
+        volume_event_history: usize,
+        store_init_batch_size: u32,
+        pool_usage_samples: usize,
+        reserved_capacity_percent: u8,
+        event_history: usize,
+    ) -> Result<Self, SvcError> {
+        if let Some(prefix) = store_key_prefix {
+            common_lib::set_etcd_key_prefix(&prefix)
+                .map_err(|details| SvcError::Internal { details })?;
+        }
+        tracing::info!(
+            "Using persistent store key prefix {}",
+            common_lib::etcd_key_prefix()
+        );
         let store_endpoint = Self::format_store_endpoint(&store_url);
         tracing::info!("Connecting to persistent store at {}", store_endpoint);
         let store = Etcd::new_leased(
             [&store_endpoint],
             ControlPlaneService::CoreAgent,
             store_lease_tll,
+            &store_options,
         )
         .await
         .expect("Should connect to the persistent store");
         tracing::info!("Connected to persistent store at {}", store_endpoint);
+        let config = Self::get_config_or_panic(store.clone()).await;
+        // an override set at runtime takes precedence over the startup argument
+        let max_rebuilds = config
+            .max_rebuilds()
+            .map(Into::into)
+            .unwrap_or(max_rebuilds);
         let registry = Self {
             inner: Arc::new(RegistryInner {
                 nodes: Default::default(),
@@ -111,13 +158,22 @@ impl Registry {
                 store_timeout,
                 reconcile_period,
                 reconcile_idle_period,
+                faulted_child_grace_period,
                 reconciler: ReconcilerControl::new(),
-                config: Self::get_config_or_panic(store).await,
-                max_rebuilds,
+                config,
+                max_rebuilds: parking_lot::RwLock::new(max_rebuilds),
+                max_rebuilds_update: Mutex::new(()),
+                rebalance_threshold,
+                volume_event_history,
+                store_init_batch_size,
+                pool_usage: Default::default(),
+                pool_usage_samples,
+                reserved_capacity_percent,
+                events: EventStore::new(event_history),
             }),
         };
         registry.init().await;
-        registry
+        Ok(registry)
     }
 
     /// Formats the store endpoint with a default port if one isn't supplied.
@@ -231,6 +287,11 @@ impl Registry {
         &self.store
     }
 
+    /// Get the status of the persistent store lease, if any
+    pub(crate) async fn store_lease_status(&self) -> Option<LeaseStatus> {
+        self.store.lock().await.lease_status()
+    }
+
     /// Check if the persistent store is currently online
     pub async fn store_online(&self) -> bool {
         let mut store = self.store.lock().await;
@@ -249,6 +310,28 @@ impl Registry {
         self.reconciler.start(registry).await;
     }
 
+    /// Drain the core registry: stop the reconcilers and wait, at most for the given `timeout`,
+    /// for the in-flight operations to release their operation guards
+    pub(crate) async fn drain(&self, timeout: std::time::Duration) {
+        self.reconciler.shutdown().await;
+        let started = std::time::Instant::now();
+        loop {
+            let busy = self.specs.busy_resources();
+            if busy == 0 {
+                tracing::info!("All in-flight operations have completed");
+                break;
+            }
+            if started.elapsed() >= timeout {
+                tracing::warn!(
+                    busy,
+                    "Timed out waiting for the in-flight operations to complete"
+                );
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    }
+
     /// Stops the core registry, which at the moment only revokes the persistent store lease
     pub(crate) async fn stop(&self) {
         tokio::time::timeout(std::time::Duration::from_secs(1), async move {
@@ -262,7 +345,9 @@ impl Registry {
     /// Initialise the registry with the content of the persistent store.
     async fn init(&self) {
         let mut store = self.store.lock().await;
-        self.specs.init(store.deref_mut()).await;
+        self.specs
+            .init(store.deref_mut(), self.store_init_batch_size)
+            .await;
     }
 
     /// Send a triggered event signal to the reconciler module
@@ -289,6 +374,7 @@ impl Registry {
                     }
                 }
             }
+            self.sample_pool_usage().await;
             tokio::time::sleep(self.cache_period).await;
         }
     }
@@ -297,14 +383,9 @@ impl Registry {
     /// Constrain the number of system-wide rebuilds to the maximum specified.
     /// If a maximum is not specified, do not limit the number of rebuilds.
     pub(crate) async fn rebuild_allowed(&self) -> Result<(), SvcError> {
-        match self.max_rebuilds {
+        match self.max_rebuilds() {
             Some(max_rebuilds) => {
-                let mut num_rebuilds = 0;
-                for (_id, node_wrapper) in self.nodes.read().await.iter() {
-                    num_rebuilds += node_wrapper.read().await.num_rebuilds();
-                }
-
-                if num_rebuilds < max_rebuilds {
+                if self.num_rebuilds().await < max_rebuilds {
                     Ok(())
                 } else {
                     Err(SvcError::MaxRebuilds { max_rebuilds })
@@ -313,4 +394,117 @@ impl Registry {
             None => Ok(()),
         }
     }
+
+    /// Get the number of rebuilds which may still be started without exceeding the system-wide
+    /// maximum, or None if the number of rebuilds is not limited.
+    pub(crate) async fn available_rebuilds(&self) -> Option<NumRebuilds> {
+        let max_rebuilds = self.max_rebuilds()?;
+        Some(max_rebuilds.saturating_sub(self.num_rebuilds().await))
+    }
+
+    /// Get the system-wide maximum number of concurrent rebuilds, if limited
+    pub(crate) fn max_rebuilds(&self) -> Option<NumRebuilds> {
+        *self.max_rebuilds.read()
+    }
+
+    /// Get the number of system-wide rebuilds currently in progress
+    pub(crate) async fn num_rebuilds(&self) -> NumRebuilds {
+        let mut num_rebuilds = 0;
+        for (_id, node_wrapper) in self.nodes.read().await.iter() {
+            num_rebuilds += node_wrapper.read().await.num_rebuilds();
+        }
+        num_rebuilds
+    }
+
+    /// Set the system-wide maximum number of concurrent rebuilds, or remove the limit if None.
+    /// The maximum may not be lower than the number of rebuilds currently in progress.
+    /// The new maximum is persisted so it outlives a restart of the core agent.
+    pub(crate) async fn set_max_rebuilds(
+        &self,
+        max_rebuilds: Option<NumRebuilds>,
+    ) -> Result<(), SvcError> {
+        let _guard = self.max_rebuilds_update.lock().await;
+        if let Some(max_rebuilds) = max_rebuilds {
+            let num_rebuilds = self.num_rebuilds().await;
+            if max_rebuilds < num_rebuilds {
+                return Err(SvcError::MaxRebuildsBelowRunning {
+                    max_rebuilds,
+                    num_rebuilds,
+                });
+            }
+        }
+
+        let mut config: CoreRegistryConfig = self.load_obj(&self.config.key()).await?;
+        config.set_max_rebuilds(MaxRebuilds::from(max_rebuilds));
+        self.store_obj(&config).await?;
+
+        *self.max_rebuilds.write() = max_rebuilds;
+        tracing::info!(
+            ?max_rebuilds,
+            "Updated the maximum number of concurrent rebuilds"
+        );
+        Ok(())
+    }
+
+    /// Get the percentage of the capacity of the given pool which is kept free when placing new
+    /// replicas: the pool spec's own reservation, or the default one
+    pub(crate) fn reserved_capacity_percent(&self, pool: &PoolId) -> u8 {
+        self.specs()
+            .get_pool(pool)
+            .ok()
+            .and_then(|spec| spec.reserved_capacity_percent)
+            .unwrap_or(self.reserved_capacity_percent)
+    }
+
+    /// Get the pool utilization (%) above which volume replicas are rebalanced, if enabled
+    pub(crate) fn rebalance_threshold(&self) -> Option<u8> {
+        self.rebalance_threshold
+    }
+    /// Check if the nexus child has been continuously faulted for longer than the faulted child
+    /// grace period, ie: it was last observed to change its state to faulted before that.
+    pub(crate) fn child_faulted_past_grace(&self, child: &Child) -> bool {
+        child.state.faulted()
+            && child
+                .state_changed_at
+                .and_then(|since| since.elapsed().ok())
+                .map(|faulted_for| faulted_for >= self.faulted_child_grace_period)
+                .unwrap_or_default()
+    }
+    /// Get the maximum number of events retained in each volume's event history
+    pub(crate) fn volume_event_history(&self) -> usize {
+        self.volume_event_history
+    }
+    /// Record a control-plane event, evicting the oldest events once `event_history` are retained
+    pub(crate) fn record_event(&self, event: Event) {
+        self.events.record(event);
+    }
+    /// Get the recent control-plane events about the resource of the filter, oldest first
+    pub(crate) fn get_events(&self, filter: &Filter) -> Result<Vec<Event>, SvcError> {
+        self.events.get(filter)
+    }
+
+    /// Sample the capacity usage of all the pools with a known state, retaining no more than
+    /// `pool_usage_samples` samples per pool.
+    /// The samples of pools which are no longer known are dropped.
+    async fn sample_pool_usage(&self) {
+        if self.pool_usage_samples == 0 {
+            return;
+        }
+        let pools = self.get_pool_states_inner().await;
+        let mut usage = self.pool_usage.lock();
+        usage.retain(|id, _| pools.iter().any(|p| &p.id == id));
+        for pool in pools {
+            let samples = usage.entry(pool.id.clone()).or_default();
+            samples.push_back(PoolUsageSample::new(&pool));
+            while samples.len() > self.pool_usage_samples {
+                samples.pop_front();
+            }
+        }
+    }
+    /// Get the capacity forecast of the given pool, based on its retained usage samples
+    pub(crate) fn pool_forecast(&self, pool: &PoolId) -> Option<CapacityForecast> {
+        let mut usage = self.pool_usage.lock();
+        let samples = usage.get_mut(pool)?;
+        CapacityForecast::new(samples.make_contiguous())
+    }
 }
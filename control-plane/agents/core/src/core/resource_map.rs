@@ -55,6 +55,16 @@ where
         }
     }
 
+    /// Extend the resource map with new elements, allowing it to be populated in batches.
+    /// Should only be called with elements which are not yet in the map because a new Arc is
+    /// created thereby invalidating any references to the previous value.
+    pub fn extend(&mut self, values: impl IntoVec<S>) {
+        for value in values.into_vec() {
+            let previous = self.map.insert(value.uuid(), Arc::new(Mutex::new(value)));
+            assert!(previous.is_none());
+        }
+    }
+
     /// Get all the resources as a vector.
     pub fn to_vec(&self) -> Vec<Arc<Mutex<S>>> {
         self.map.values().cloned().collect()
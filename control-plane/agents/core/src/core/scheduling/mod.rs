@@ -2,6 +2,9 @@ pub(crate) mod nexus;
 pub(crate) mod resources;
 pub(crate) mod volume;
 
+#[cfg(test)]
+mod tests;
+
 use crate::core::scheduling::{
     nexus::GetPersistedNexusChildrenCtx,
     resources::{ChildItem, PoolItem, ReplicaItem},
@@ -49,6 +52,10 @@ impl NodeFilters {
     pub(crate) fn online(_request: &GetSuitablePoolsContext, item: &PoolItem) -> bool {
         item.node.is_online()
     }
+    /// Should only attempt to use nodes which are not cordoned
+    pub(crate) fn cordoned(request: &GetSuitablePoolsContext, item: &PoolItem) -> bool {
+        !request.registry().specs().node_cordoned(&item.pool.node)
+    }
     /// Should only attempt to use allowed nodes (by the topology)
     pub(crate) fn allowed(request: &GetSuitablePoolsContext, item: &PoolItem) -> bool {
         request.allowed_nodes().is_empty() || request.allowed_nodes().contains(&item.pool.node)
@@ -64,9 +71,31 @@ impl NodeFilters {
 /// Filter pools used for replica creation
 pub(crate) struct PoolFilters {}
 impl PoolFilters {
-    /// Should only attempt to use pools with sufficient free space
+    /// Should only attempt to use pools which are not under maintenance
+    pub(crate) fn maintenance(request: &GetSuitablePoolsContext, item: &PoolItem) -> bool {
+        match request.registry().specs().get_pool(&item.pool.id) {
+            Ok(spec) => !spec.maintenance,
+            Err(_) => false,
+        }
+    }
+    /// Should only attempt to use pools with sufficient free space, not counting the reserved
+    /// capacity of the pool
     pub(crate) fn free_space(request: &GetSuitablePoolsContext, item: &PoolItem) -> bool {
-        item.pool.free_space() > request.size
+        let reserved = request.registry().reserved_capacity_percent(&item.pool.id);
+        item.pool.free_space_reserved(reserved) > request.size
+    }
+    /// When creating a volume from a snapshot, should only attempt to use the pools where the
+    /// snapshot was taken, as that's where the replicas can be cloned from
+    pub(crate) fn snapshot(request: &GetSuitablePoolsContext, item: &PoolItem) -> bool {
+        match &request.source_snapshot {
+            Some(snapshot) if request.status.creating() => {
+                match request.registry().specs().get_snapshot_spec(snapshot) {
+                    Some(spec) => spec.replicas.iter().any(|r| r.pool == item.pool.id),
+                    None => false,
+                }
+            }
+            _ => true,
+        }
     }
     /// Should only attempt to use usable (not faulted) pools
     pub(crate) fn usable(_: &GetSuitablePoolsContext, item: &PoolItem) -> bool {
@@ -112,6 +141,21 @@ impl PoolSorters {
     pub(crate) fn sort_by_replica_count(a: &PoolItem, b: &PoolItem) -> std::cmp::Ordering {
         a.pool.cmp(&b.pool)
     }
+    /// Sort the volume's preferred pools first, in their order of preference
+    pub(crate) fn sort_by_preference(
+        request: &GetSuitablePoolsContext,
+        a: &PoolItem,
+        b: &PoolItem,
+    ) -> std::cmp::Ordering {
+        let preference = |item: &PoolItem| {
+            request
+                .preferred_pools()
+                .iter()
+                .position(|pool| pool == &item.pool.id)
+                .unwrap_or(usize::MAX)
+        };
+        preference(a).cmp(&preference(b))
+    }
 }
 
 /// Sort the nexus children for removal when decreasing a volume's replica count
@@ -251,6 +295,12 @@ impl AddReplicaFilters {
     pub(crate) fn size(request: &VolumeReplicasForNexusCtx, item: &ChildItem) -> bool {
         item.state().size >= request.vol_spec().size
     }
+
+    /// Should not allow children which the persisted nexus info marks as unhealthy, as their data
+    /// may be stale, eg: after a network partition
+    pub(crate) fn healthy(_request: &VolumeReplicasForNexusCtx, item: &ChildItem) -> bool {
+        item.info().as_ref().map(|i| i.healthy).unwrap_or(true)
+    }
 }
 
 /// Sort replicas to pick the best choice to add to a given nexus
@@ -0,0 +1,98 @@
+use super::volume::spread_by_domain;
+use common_lib::types::v0::{message_bus::NodeId, store::node::NodeLabels};
+use std::collections::{HashMap, HashSet};
+
+const ZONE: &str = "topology.kubernetes.io/zone";
+
+/// Mocked registry of nodes labelled with their zone
+fn labelled_nodes(zones: &[(&str, Option<&str>)]) -> HashMap<NodeId, NodeLabels> {
+    zones
+        .iter()
+        .map(|(node, zone)| {
+            let labels = zone
+                .iter()
+                .map(|zone| (ZONE.to_string(), zone.to_string()))
+                .collect();
+            (NodeId::from(*node), labels)
+        })
+        .collect()
+}
+
+/// Spread the pool candidates, given as (node, pool), across the zones of their nodes
+fn spread(
+    nodes: &HashMap<NodeId, NodeLabels>,
+    candidates: &[(&str, &str)],
+    used: &[&str],
+) -> Vec<String> {
+    let candidates = candidates
+        .iter()
+        .map(|(node, pool)| (NodeId::from(*node), pool.to_string()))
+        .collect::<Vec<_>>();
+    let used = used.iter().map(|z| z.to_string()).collect::<HashSet<_>>();
+    spread_by_domain(
+        candidates,
+        |(node, _)| nodes.get(node).and_then(|labels| labels.get(ZONE)),
+        &used,
+    )
+    .into_iter()
+    .map(|(_, pool)| pool)
+    .collect()
+}
+
+#[test]
+fn spread_across_zones() {
+    let nodes = labelled_nodes(&[
+        ("node-1", Some("zone-a")),
+        ("node-2", Some("zone-a")),
+        ("node-3", Some("zone-b")),
+        ("node-4", Some("zone-b")),
+        ("node-5", Some("zone-c")),
+        ("node-6", None),
+    ]);
+    let candidates = [
+        ("node-1", "pool-1"),
+        ("node-2", "pool-2"),
+        ("node-6", "pool-6"),
+        ("node-3", "pool-3"),
+        ("node-4", "pool-4"),
+        ("node-5", "pool-5"),
+    ];
+
+    // one candidate of each zone first, keeping the preference order within a zone
+    assert_eq!(
+        spread(&nodes, &candidates, &[]),
+        vec!["pool-1", "pool-3", "pool-5", "pool-2", "pool-4", "pool-6"]
+    );
+
+    // zones already used by the volume go last
+    assert_eq!(
+        spread(&nodes, &candidates, &["zone-a"]),
+        vec!["pool-3", "pool-5", "pool-1", "pool-4", "pool-2", "pool-6"]
+    );
+}
+
+#[test]
+fn spread_best_effort() {
+    // not enough zones: all candidates are still kept, so the replicas can be placed anyway
+    let nodes = labelled_nodes(&[
+        ("node-1", Some("zone-a")),
+        ("node-2", Some("zone-a")),
+        ("node-3", Some("zone-a")),
+    ]);
+    let candidates = [
+        ("node-1", "pool-1"),
+        ("node-2", "pool-2"),
+        ("node-3", "pool-3"),
+    ];
+    assert_eq!(
+        spread(&nodes, &candidates, &["zone-a"]),
+        vec!["pool-1", "pool-2", "pool-3"]
+    );
+
+    // no labels at all: the order is preserved
+    let nodes = labelled_nodes(&[("node-1", None), ("node-2", None)]);
+    assert_eq!(
+        spread(&nodes, &[("node-2", "pool-2"), ("node-1", "pool-1")], &[]),
+        vec!["pool-2", "pool-1"]
+    );
+}
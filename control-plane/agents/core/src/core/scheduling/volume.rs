@@ -7,14 +7,17 @@ use crate::core::{
     },
 };
 
-use common::errors::SvcError;
+use common::errors::{PoolRejection, SvcError};
 use common_lib::types::v0::{
     message_bus::{ChildUri, CreateVolume, VolumeState},
     store::{nexus::NexusSpec, nexus_persistence::NexusInfo, volume::VolumeSpec},
 };
 
 use itertools::Itertools;
-use std::{collections::HashMap, ops::Deref};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Deref,
+};
 
 #[derive(Clone)]
 pub(crate) struct GetSuitablePools {
@@ -67,6 +70,46 @@ impl Deref for GetSuitablePools {
 pub(crate) struct AddVolumeReplica {
     context: GetSuitablePoolsContext,
     list: Vec<PoolItem>,
+    /// The pools which were filtered out, and why
+    rejected: Vec<PoolRejection>,
+}
+
+/// Why a pool was rejected as a candidate for a volume replica
+#[derive(Debug, Clone, Copy)]
+enum PoolRejectReason {
+    NodeOffline,
+    NodeCordoned,
+    NodeNotAllowed,
+    NodeInUse,
+    Unusable,
+    Maintenance,
+    TooSmall,
+    Topology,
+    Snapshot,
+}
+impl PoolRejectReason {
+    /// Describe why the pool `item` was rejected for the volume `request`
+    fn describe(&self, request: &GetSuitablePoolsContext, item: &PoolItem) -> String {
+        let node = &item.pool.node;
+        match self {
+            Self::NodeOffline => format!("node '{}' is not online", node),
+            Self::NodeCordoned => format!("node '{}' is cordoned", node),
+            Self::NodeNotAllowed => format!("node '{}' is not an allowed node", node),
+            Self::NodeInUse => format!("node '{}' already has a replica of the volume", node),
+            Self::Unusable => format!("pool status is {:?}", item.pool.status),
+            Self::Maintenance => "pool is under maintenance".to_string(),
+            Self::TooSmall => {
+                let reserved = request.registry().reserved_capacity_percent(&item.pool.id);
+                format!(
+                    "pool is too small, {} bytes free for a {} bytes replica",
+                    item.pool.free_space_reserved(reserved),
+                    request.size
+                )
+            }
+            Self::Topology => "pool labels don't match the volume pool topology".to_string(),
+            Self::Snapshot => "pool has no replica of the source snapshot".to_string(),
+        }
+    }
 }
 
 impl AddVolumeReplica {
@@ -78,6 +121,7 @@ impl AddVolumeReplica {
                 spec: request.spec.clone(),
             },
             list: PoolItemLister::list(registry).await,
+            rejected: vec![],
         }
     }
     /// Default rules for pool selection when creating replicas for a volume
@@ -95,15 +139,112 @@ impl AddVolumeReplica {
             // 3. ideally use only healthy(online) pools with degraded pools as a
             // fallback
             // 4. only one replica per node
-            .filter(NodeFilters::online)
-            .filter(NodeFilters::allowed)
-            .filter(NodeFilters::unused)
-            .filter(PoolFilters::usable)
-            .filter(PoolFilters::free_space)
-            .filter(PoolFilters::topology)
+            // 5. cordoned nodes can't be used for new replicas
+            // 6. pools under maintenance can't be used for new replicas
+            // 7. a volume created from a snapshot can only use the pools of the snapshot
+            .filter_or_reject(NodeFilters::online, PoolRejectReason::NodeOffline)
+            .filter_or_reject(NodeFilters::cordoned, PoolRejectReason::NodeCordoned)
+            .filter_or_reject(NodeFilters::allowed, PoolRejectReason::NodeNotAllowed)
+            .filter_or_reject(NodeFilters::unused, PoolRejectReason::NodeInUse)
+            .filter_or_reject(PoolFilters::usable, PoolRejectReason::Unusable)
+            .filter_or_reject(PoolFilters::maintenance, PoolRejectReason::Maintenance)
+            .filter_or_reject(PoolFilters::free_space, PoolRejectReason::TooSmall)
+            .filter_or_reject(PoolFilters::topology, PoolRejectReason::Topology)
+            .filter_or_reject(PoolFilters::snapshot, PoolRejectReason::Snapshot)
             // sort pools in order of preference (from least to most number of replicas)
             .sort(PoolSorters::sort_by_replica_count)
+            // and spread them across the failure domains, if requested
+            .spread_domains()
+            // but always try the preferred pools first
+            .sort_ctx(PoolSorters::sort_by_preference)
+    }
+    /// Filter the candidates, keeping track of the rejected ones along with the `reason`
+    fn filter_or_reject(
+        mut self,
+        filter: fn(&GetSuitablePoolsContext, &PoolItem) -> bool,
+        reason: PoolRejectReason,
+    ) -> Self {
+        let context = self.context.clone();
+        let (list, rejected): (Vec<_>, Vec<_>) = self
+            .list
+            .into_iter()
+            .partition(|item| filter(&context, item));
+        self.rejected
+            .extend(rejected.iter().map(|item| PoolRejection {
+                pool: item.pool.id.clone(),
+                reason: reason.describe(&context, item),
+            }));
+        self.list = list;
+        self
+    }
+    /// Get the candidates along with the pools which were rejected
+    pub(crate) fn collect_with_rejected(self) -> (Vec<PoolItem>, Vec<PoolRejection>) {
+        (self.list, self.rejected)
+    }
+    /// Reorder the candidates so that they're spread across the failure domains given by the
+    /// volume's spread topology key, preferring the domains not used by the volume yet.
+    /// This is best-effort only: no candidate is filtered out.
+    fn spread_domains(mut self) -> Self {
+        let key = match self.context.spread_topology_key() {
+            Some(key) => key.clone(),
+            None => return self,
+        };
+        let specs = self.context.registry().specs();
+        let domains = specs
+            .get_nodes()
+            .into_iter()
+            .filter_map(|node| {
+                node.labels()
+                    .get(&key)
+                    .map(|domain| (node.id().clone(), domain.clone()))
+            })
+            .collect::<HashMap<_, _>>();
+        let used = specs
+            .get_volume_data_nodes(&self.context.uuid)
+            .iter()
+            .filter_map(|node| domains.get(node).cloned())
+            .collect::<HashSet<_>>();
+        self.list = spread_by_domain(self.list, |item| domains.get(item.node.id()), &used);
+        self
+    }
+}
+
+/// Interleave the `items` across their failure domains, keeping the relative order of the items
+/// within each domain: the first item of each domain comes before the second item of any domain.
+/// The domains which are not `used` yet come first and the items without a domain come last.
+pub(crate) fn spread_by_domain<T, D: Into<String>, F: Fn(&T) -> Option<D>>(
+    items: Vec<T>,
+    domain: F,
+    used: &HashSet<String>,
+) -> Vec<T> {
+    let mut groups = Vec::<(String, VecDeque<T>)>::new();
+    let mut no_domain = vec![];
+    for item in items {
+        let item_domain = match domain(&item) {
+            Some(item_domain) => item_domain.into(),
+            None => {
+                no_domain.push(item);
+                continue;
+            }
+        };
+        match groups.iter_mut().find(|(d, _)| d == &item_domain) {
+            Some((_, group)) => group.push_back(item),
+            None => groups.push((item_domain, VecDeque::from(vec![item]))),
+        }
+    }
+    // stable, so the unused domains keep their order of preference
+    groups.sort_by_key(|(d, _)| used.contains(d));
+
+    let mut spread = Vec::with_capacity(groups.iter().map(|(_, g)| g.len()).sum());
+    while groups.iter().any(|(_, group)| !group.is_empty()) {
+        for (_, group) in groups.iter_mut() {
+            if let Some(item) = group.pop_front() {
+                spread.push(item);
+            }
+        }
     }
+    spread.extend(no_domain);
+    spread
 }
 
 #[async_trait::async_trait(?Send)]
@@ -126,6 +267,19 @@ impl ResourceFilter for AddVolumeReplica {
         self
     }
 
+    fn sort_ctx<P: FnMut(&Self::Request, &Self::Item, &Self::Item) -> std::cmp::Ordering>(
+        mut self,
+        mut sort: P,
+    ) -> Self {
+        let context = self.context.clone();
+        self.list = self
+            .list
+            .into_iter()
+            .sorted_by(|a, b| sort(&context, a, b))
+            .collect();
+        self
+    }
+
     fn collect(self) -> Vec<Self::Item> {
         self.list
     }
@@ -403,7 +557,6 @@ impl VolumeReplicasForNexusCtx {
         &self.nexus_spec
     }
     /// Get the current nexus persistent information
-    #[allow(dead_code)]
     pub(crate) fn nexus_info(&self) -> &Option<NexusInfo> {
         &self.nexus_info
     }
@@ -490,12 +643,18 @@ impl AddVolumeNexusReplicas {
         Ok(Self { list, context })
     }
 
+    /// Get the inner context
+    pub(crate) fn context(&self) -> &VolumeReplicasForNexusCtx {
+        &self.context
+    }
+
     /// Builder used to retrieve a list of healthy replicas to add to a volume nexus.
     /// The list follows a set of filters for replicas according to the following
     /// criteria (any order):
     /// 1. replicas which are not part of the given nexus already
     /// 2. use only replicas which report the status of online by their state
     /// 3. use only replicas which are large enough for the volume
+    /// 4. use only replicas which the persisted nexus info does not mark as unhealthy
     /// Sorted by:
     /// 1. nexus local replicas
     /// 2. replicas which have never been marked as faulted by io-engine
@@ -509,6 +668,7 @@ impl AddVolumeNexusReplicas {
             .await?
             .filter(AddReplicaFilters::online)
             .filter(AddReplicaFilters::size)
+            .filter(AddReplicaFilters::healthy)
             .sort_ctx(AddReplicaSorters::sort))
     }
 }
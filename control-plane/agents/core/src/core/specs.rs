@@ -3,7 +3,7 @@ use common::errors::SvcError;
 use common_lib::{
     mbus_api::ResourceKind,
     types::v0::{
-        message_bus::{NexusId, NodeId, PoolId, ReplicaId, VolumeId},
+        message_bus::{Event, EventType, NexusId, NodeId, PoolId, ReplicaId, SnapshotId, VolumeId},
         openapi::apis::Uuid,
         store::{
             definitions::{
@@ -13,6 +13,7 @@ use common_lib::{
             node::NodeSpec,
             pool::PoolSpec,
             replica::ReplicaSpec,
+            snapshot::VolumeSnapshotSpec,
             volume::VolumeSpec,
             OperationGuard, OperationMode, OperationSequence, OperationSequencer, SpecStatus,
             SpecTransaction,
@@ -42,6 +43,68 @@ enum SpecError {
     KeyUuid {},
 }
 
+/// Spec operations whose outcome is recorded as a control-plane event
+#[derive(Debug, Clone, Copy)]
+enum SpecEvent {
+    Create,
+    Destroy,
+}
+impl SpecEvent {
+    /// The reason of the event, eg: `Created` or `CreateFailed`
+    fn reason(&self, success: bool) -> &'static str {
+        match (self, success) {
+            (Self::Create, true) => "Created",
+            (Self::Create, false) => "CreateFailed",
+            (Self::Destroy, true) => "Destroyed",
+            (Self::Destroy, false) => "DestroyFailed",
+        }
+    }
+    fn action(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Destroy => "destroy",
+        }
+    }
+    fn done(&self) -> &'static str {
+        match self {
+            Self::Create => "created",
+            Self::Destroy => "destroyed",
+        }
+    }
+}
+
+/// Record the outcome of the operation on the resource of the spec as a control-plane event
+fn record_spec_event<S: SpecOperations, R>(
+    registry: &Registry,
+    locked_spec: &Arc<Mutex<S>>,
+    operation: SpecEvent,
+    result: &Result<R, SvcError>,
+) {
+    let (kind, id) = {
+        let spec = locked_spec.lock();
+        (spec.kind(), spec.uuid())
+    };
+    let (event_type, message) = match result {
+        Ok(_) => (
+            EventType::Normal,
+            format!("{} '{}' has been {}", kind.as_ref(), id, operation.done()),
+        ),
+        Err(error) => (
+            EventType::Warning,
+            format!(
+                "Failed to {} {} '{}': {}",
+                operation.action(),
+                kind.as_ref(),
+                id,
+                error.full_string()
+            ),
+        ),
+    };
+    let reason = operation.reason(result.is_ok());
+    let event = Event::new(kind, id, event_type, reason, message);
+    registry.record_event(event);
+}
+
 /// This trait is used to encapsulate common behaviour for all different types of resources,
 /// including validation rules and error handling.
 #[async_trait]
@@ -138,7 +201,7 @@ pub trait SpecOperations: Clone + Debug + Sized + StorableObject + OperationSequ
     where
         Self: SpecTransaction<O>,
     {
-        match result {
+        let result = match result {
             Ok(val) => {
                 let mut spec_clone = locked_spec.lock().clone();
                 spec_clone.commit_op();
@@ -160,7 +223,9 @@ pub trait SpecOperations: Clone + Debug + Sized + StorableObject + OperationSequ
                 Self::delete_spec(registry, locked_spec).await.ok();
                 Err(error)
             }
-        }
+        };
+        record_spec_event(registry, locked_spec, SpecEvent::Create, &result);
+        result
     }
 
     /// Validates the outcome of a create step.
@@ -314,7 +379,7 @@ pub trait SpecOperations: Clone + Debug + Sized + StorableObject + OperationSequ
         Self: StorableObject,
     {
         let key = locked_spec.lock().key();
-        match result {
+        let result = match result {
             Ok(val) => {
                 let mut spec_clone = locked_spec.lock().clone();
                 spec_clone.commit_op();
@@ -349,7 +414,9 @@ pub trait SpecOperations: Clone + Debug + Sized + StorableObject + OperationSequ
                     }
                 }
             }
-        }
+        };
+        record_spec_event(registry, locked_spec, SpecEvent::Destroy, &result);
+        result
     }
 
     /// Start an update operation and attempt to log the transaction to the store.
@@ -558,6 +625,27 @@ pub trait SpecOperations: Clone + Debug + Sized + StorableObject + OperationSequ
             None => true,
         }
     }
+    /// Clear the pending operation, if any, eg: when it's stuck because its result can't be
+    /// determined. The spec is left as it was before the operation started.
+    /// Fails with a conflict if an operation is currently in progress.
+    async fn clear_pending_op<O>(
+        locked_spec: &Arc<Mutex<Self>>,
+        registry: &Registry,
+    ) -> Result<(), SvcError>
+    where
+        Self: SpecTransaction<O>,
+        Self: StorableObject,
+    {
+        let _guard = locked_spec.operation_guard(OperationMode::Exclusive)?;
+        let mut spec_clone = locked_spec.lock().clone();
+        if spec_clone.operation_result().is_none() {
+            return Ok(());
+        }
+        spec_clone.clear_op();
+        registry.store_obj(&spec_clone).await?;
+        locked_spec.lock().clear_op();
+        Ok(())
+    }
 
     /// Check if the object is free to be modified or if it's still busy
     fn busy(&self) -> Result<(), SvcError> {
@@ -713,6 +801,7 @@ pub(crate) struct ResourceSpecs {
     pub(crate) nexuses: ResourceMap<NexusId, NexusSpec>,
     pub(crate) pools: ResourceMap<PoolId, PoolSpec>,
     pub(crate) replicas: ResourceMap<ReplicaId, ReplicaSpec>,
+    pub(crate) snapshots: ResourceMap<SnapshotId, VolumeSnapshotSpec>,
 }
 
 impl ResourceSpecsLocked {
@@ -720,17 +809,33 @@ impl ResourceSpecsLocked {
         ResourceSpecsLocked::default()
     }
 
+    /// Get the number of resources which currently have an operation in progress, ie: for which
+    /// an operation guard is held
+    pub(crate) fn busy_resources(&self) -> usize {
+        fn busy<T: OperationSequencer>(spec: &Arc<Mutex<T>>) -> bool {
+            spec.lock().as_ref().busy()
+        }
+        let specs = self.read();
+        specs.volumes.values().filter(|s| busy(s)).count()
+            + specs.nexuses.values().filter(|s| busy(s)).count()
+            + specs.pools.values().filter(|s| busy(s)).count()
+            + specs.replicas.values().filter(|s| busy(s)).count()
+    }
+
     /// Initialise the resource specs with the content from the persistent store.
-    pub(crate) async fn init<S: Store>(&self, store: &mut S) {
+    /// The specs are loaded in batches of at most `batch_size` specs, bounding the memory used
+    /// by each store read.
+    pub(crate) async fn init<S: Store>(&self, store: &mut S, batch_size: u32) {
         let spec_types = [
             StorableObjectType::VolumeSpec,
             StorableObjectType::NodeSpec,
             StorableObjectType::NexusSpec,
             StorableObjectType::PoolSpec,
             StorableObjectType::ReplicaSpec,
+            StorableObjectType::VolumeSnapshot,
         ];
         for spec in &spec_types {
-            if let Err(e) = self.populate_specs(store, *spec).await {
+            if let Err(e) = self.populate_specs(store, *spec, batch_size).await {
                 panic!("Failed to initialise resource specs. Err {}.", e);
             }
         }
@@ -773,21 +878,43 @@ impl ResourceSpecsLocked {
     }
 
     /// Populate the resource specs with data from the persistent store.
+    /// The specs are read in batches of at most `batch_size` specs and each batch is added to
+    /// the resource specs before the next one is read, releasing the specs lock in between.
     async fn populate_specs<S: Store>(
         &self,
         store: &mut S,
         spec_type: StorableObjectType,
+        batch_size: u32,
     ) -> Result<(), SpecError> {
         let prefix = key_prefix_obj(spec_type);
-        let store_entries =
-            store
-                .get_values_prefix(&prefix)
+        let mut start_key = None;
+        let mut count = 0;
+        loop {
+            let (store_entries, next_key) = store
+                .get_values_prefix_paged(&prefix, batch_size as i64, start_key)
                 .await
                 .map_err(|e| SpecError::StoreGet {
                     source: Box::new(e),
                 })?;
-        let store_values = store_entries.iter().map(|e| e.1.clone()).collect();
+            count += store_entries.len();
+            let store_values = store_entries.into_iter().map(|e| e.1).collect();
+            self.populate_specs_batch(spec_type, store_values)?;
+
+            if next_key.is_none() {
+                break;
+            }
+            start_key = next_key;
+        }
+        tracing::debug!(count, "Loaded {} from the persistent store", spec_type);
+        Ok(())
+    }
 
+    /// Add a batch of specs read from the persistent store to the resource specs.
+    fn populate_specs_batch(
+        &self,
+        spec_type: StorableObjectType,
+        store_values: Vec<serde_json::Value>,
+    ) -> Result<(), SpecError> {
         let mut resource_specs = self.0.write();
         match spec_type {
             StorableObjectType::VolumeSpec => {
@@ -795,35 +922,43 @@ impl ResourceSpecsLocked {
                     Self::deserialise_specs::<VolumeSpec>(store_values).context(Deserialise {
                         obj_type: StorableObjectType::VolumeSpec,
                     })?;
-                resource_specs.volumes.populate(specs);
+                resource_specs.volumes.extend(specs);
             }
             StorableObjectType::NodeSpec => {
                 let specs =
                     Self::deserialise_specs::<NodeSpec>(store_values).context(Deserialise {
                         obj_type: StorableObjectType::NodeSpec,
                     })?;
-                resource_specs.nodes.populate(specs);
+                resource_specs.nodes.extend(specs);
             }
             StorableObjectType::NexusSpec => {
                 let specs =
                     Self::deserialise_specs::<NexusSpec>(store_values).context(Deserialise {
                         obj_type: StorableObjectType::NexusSpec,
                     })?;
-                resource_specs.nexuses.populate(specs);
+                resource_specs.nexuses.extend(specs);
             }
             StorableObjectType::PoolSpec => {
                 let specs =
                     Self::deserialise_specs::<PoolSpec>(store_values).context(Deserialise {
                         obj_type: StorableObjectType::PoolSpec,
                     })?;
-                resource_specs.pools.populate(specs);
+                resource_specs.pools.extend(specs);
             }
             StorableObjectType::ReplicaSpec => {
                 let specs =
                     Self::deserialise_specs::<ReplicaSpec>(store_values).context(Deserialise {
                         obj_type: StorableObjectType::ReplicaSpec,
                     })?;
-                resource_specs.replicas.populate(specs);
+                resource_specs.replicas.extend(specs);
+            }
+            StorableObjectType::VolumeSnapshot => {
+                let specs = Self::deserialise_specs::<VolumeSnapshotSpec>(store_values).context(
+                    Deserialise {
+                        obj_type: StorableObjectType::VolumeSnapshot,
+                    },
+                )?;
+                resource_specs.snapshots.extend(specs);
             }
             _ => {
                 // Not all spec types are persisted in the store.
@@ -833,3 +968,25 @@ impl ResourceSpecsLocked {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_resources() {
+        let specs = ResourceSpecsLocked::new();
+        let pool = specs.write().pools.insert(PoolSpec {
+            id: PoolId::from("pool"),
+            ..Default::default()
+        });
+        assert_eq!(specs.busy_resources(), 0);
+
+        let guard = pool.operation_guard(OperationMode::Exclusive).unwrap();
+        assert_eq!(specs.busy_resources(), 1);
+
+        // the operation completes once its guard is dropped
+        drop(guard);
+        assert_eq!(specs.busy_resources(), 0);
+    }
+}
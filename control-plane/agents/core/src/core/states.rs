@@ -3,7 +3,7 @@ use common_lib::types::v0::{
     store::{nexus::NexusState, pool::PoolState, replica::ReplicaState},
 };
 use indexmap::map::Values;
-use std::{ops::Deref, sync::Arc};
+use std::{ops::Deref, sync::Arc, time::SystemTime};
 
 use super::resource_map::ResourceMap;
 use parking_lot::{Mutex, RwLock};
@@ -31,6 +31,9 @@ pub(crate) struct ResourceStates {
     nexuses: ResourceMap<NexusId, NexusState>,
     pools: ResourceMap<PoolId, PoolState>,
     replicas: ResourceMap<ReplicaId, ReplicaState>,
+    /// Last time the nexus states were successfully refreshed from the io-engine.
+    /// This is not reset when the states are cleared so we can tell how stale they are.
+    nexuses_refreshed: Option<std::time::SystemTime>,
 }
 
 impl ResourceStates {
@@ -47,9 +50,28 @@ impl ResourceStates {
     }
 
     /// Update nexus states.
-    pub(crate) fn update_nexuses(&mut self, nexuses: Vec<Nexus>) {
+    /// The time at which each child last changed its state is carried over from the previous
+    /// states of the nexus, unless the child state has changed since.
+    pub(crate) fn update_nexuses(&mut self, mut nexuses: Vec<Nexus>) {
+        let now = SystemTime::now();
+        for nexus in &mut nexuses {
+            let previous = self.get_nexus_state(&nexus.uuid);
+            for child in &mut nexus.children {
+                let previous = previous
+                    .as_ref()
+                    .and_then(|state| state.nexus.children.iter().find(|c| c.uri == child.uri))
+                    .map(|c| (c.state.clone(), c.state_changed_at));
+                child.state_changed_at = state_changed_at(previous, &child.state, now);
+            }
+        }
         self.nexuses.clear();
         self.nexuses.populate(nexuses);
+        self.nexuses_refreshed = Some(std::time::SystemTime::now());
+    }
+
+    /// Returns the last time the nexus states were successfully refreshed.
+    pub(crate) fn nexuses_refreshed(&self) -> Option<std::time::SystemTime> {
+        self.nexuses_refreshed
     }
 
     /// Returns a vector of cloned nexus states.
@@ -90,7 +112,17 @@ impl ResourceStates {
     }
 
     /// Update replica states.
-    pub(crate) fn update_replicas(&mut self, replicas: Vec<Replica>) {
+    /// The time at which each replica last changed its status is carried over from the previous
+    /// states, unless the replica status has changed since.
+    pub(crate) fn update_replicas(&mut self, mut replicas: Vec<Replica>) {
+        let now = SystemTime::now();
+        for replica in &mut replicas {
+            let previous = self.replicas.get(&replica.uuid).map(|state| {
+                let state = state.lock();
+                (state.replica.status.clone(), state.replica.state_changed_at)
+            });
+            replica.state_changed_at = state_changed_at(previous, &replica.status, now);
+        }
         self.replicas.clear();
         self.replicas.populate(replicas);
     }
@@ -129,3 +161,86 @@ impl ResourceStates {
             .collect()
     }
 }
+
+/// Get the time at which a resource last changed its `state`, given its `previous` state and the
+/// time at which that changed, if the resource was observed before.
+fn state_changed_at<S: PartialEq>(
+    previous: Option<(S, Option<SystemTime>)>,
+    state: &S,
+    now: SystemTime,
+) -> Option<SystemTime> {
+    match previous {
+        Some((previous, Some(changed_at))) if &previous == state => Some(changed_at),
+        _ => Some(now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_lib::types::v0::message_bus::{Child, ChildState, ReplicaStatus};
+    use std::time::Duration;
+
+    #[test]
+    fn replica_state_changed_at() {
+        let mut states = ResourceStates::default();
+        let replica = Replica {
+            uuid: ReplicaId::new(),
+            status: ReplicaStatus::Online,
+            ..Default::default()
+        };
+        let changed_at = |states: &ResourceStates| {
+            let state = states.get_replica_state(&replica.uuid).unwrap().lock();
+            state.replica.state_changed_at
+        };
+
+        states.update_replicas(vec![replica.clone()]);
+        let observed = changed_at(&states);
+        assert!(observed.is_some());
+
+        // the timestamp is only updated when the status actually changes
+        std::thread::sleep(Duration::from_millis(10));
+        states.update_replicas(vec![replica.clone()]);
+        assert_eq!(changed_at(&states), observed);
+
+        states.update_replicas(vec![Replica {
+            status: ReplicaStatus::Faulted,
+            ..replica.clone()
+        }]);
+        assert!(changed_at(&states) > observed);
+    }
+
+    #[test]
+    fn child_state_changed_at() {
+        let mut states = ResourceStates::default();
+        let child = |state: ChildState| Child {
+            uri: "bdev:///child".into(),
+            state,
+            ..Default::default()
+        };
+        let nexus = Nexus {
+            uuid: NexusId::new(),
+            children: vec![child(ChildState::Online)],
+            ..Default::default()
+        };
+        let changed_at = |states: &ResourceStates| {
+            let state = states.get_nexus_state(&nexus.uuid).unwrap();
+            state.nexus.children[0].state_changed_at
+        };
+
+        states.update_nexuses(vec![nexus.clone()]);
+        let observed = changed_at(&states);
+        assert!(observed.is_some());
+
+        // the timestamp is only updated when the state actually changes
+        std::thread::sleep(Duration::from_millis(10));
+        states.update_nexuses(vec![nexus.clone()]);
+        assert_eq!(changed_at(&states), observed);
+
+        states.update_nexuses(vec![Nexus {
+            children: vec![child(ChildState::Faulted)],
+            ..nexus.clone()
+        }]);
+        assert!(changed_at(&states) > observed);
+    }
+}
@@ -24,6 +24,10 @@ pub(crate) enum PollTriggerEvent {
     VolumeDegraded,
     /// The Agent is starting up
     Start,
+    /// A node drain has been requested
+    NodeDrain,
+    /// A reconciliation has been requested through the registry service, eg: by an admin
+    ReconcileRequested,
 }
 
 /// State of a poller
@@ -2,17 +2,22 @@
 
 use common_lib::{
     mbus_api::Message,
-    store::etcd::Etcd,
+    store::etcd::{Etcd, StoreConnectOptions},
     types::v0::{
         message_bus::{self, ChannelVs, Liveness},
         openapi::models,
         store::{
-            definitions::Store,
+            definitions::{
+                StorableObject, StorableObjectType, Store, StoreError, StoreWatchReceiver,
+                WatchEvent,
+            },
+            node::{NodeLabels, NodeSpec},
             registry::{ControlPlaneService, StoreLeaseOwner, StoreLeaseOwnerKey},
         },
     },
 };
 use deployer_cluster::{etcd_client::Client, *};
+use grpc::operations::{pool::traits::PoolOperations, replica::traits::ReplicaOperations};
 
 /// Test that the content of the registry is correctly loaded from the persistent store on start up.
 #[tokio::test]
@@ -83,9 +88,14 @@ async fn store_lease_lock() {
         .unwrap();
 
     let lease_ttl = std::time::Duration::from_secs(2);
-    let _core_agent = Etcd::new_leased(["0.0.0.0:2379"], ControlPlaneService::CoreAgent, lease_ttl)
-        .await
-        .unwrap();
+    let _core_agent = Etcd::new_leased(
+        ["0.0.0.0:2379"],
+        ControlPlaneService::CoreAgent,
+        lease_ttl,
+        &Default::default(),
+    )
+    .await
+    .unwrap();
 
     let mut store = Client::connect(["0.0.0.0:2379"], None)
         .await
@@ -110,10 +120,14 @@ async fn store_lease_lock() {
         "Lease should be the same!"
     );
 
-    let _core_agent2 =
-        Etcd::new_leased(["0.0.0.0:2379"], ControlPlaneService::CoreAgent, lease_ttl)
-            .await
-            .expect_err("One core-agent is already running!");
+    let _core_agent2 = Etcd::new_leased(
+        ["0.0.0.0:2379"],
+        ControlPlaneService::CoreAgent,
+        lease_ttl,
+        &Default::default(),
+    )
+    .await
+    .expect_err("One core-agent is already running!");
 }
 
 /// Test that store lease lock works as expected
@@ -152,10 +166,14 @@ async fn core_agent_lease_lock() {
         "Lease should be the same!"
     );
 
-    let _core_agent2 =
-        Etcd::new_leased(["0.0.0.0:2379"], ControlPlaneService::CoreAgent, lease_ttl)
-            .await
-            .expect_err("One core-agent is already running!");
+    let _core_agent2 = Etcd::new_leased(
+        ["0.0.0.0:2379"],
+        ControlPlaneService::CoreAgent,
+        lease_ttl,
+        &Default::default(),
+    )
+    .await
+    .expect_err("One core-agent is already running!");
 
     // pause the core agent
     cluster.composer().pause("core").await.unwrap();
@@ -183,10 +201,14 @@ async fn core_agent_lease_lock() {
     // let its lease expire
     tokio::time::sleep(lease_ttl_wait).await;
 
-    let _core_agent2 =
-        Etcd::new_leased(["0.0.0.0:2379"], ControlPlaneService::CoreAgent, lease_ttl)
-            .await
-            .expect("First core-agent expired, the second one can now run!");
+    let _core_agent2 = Etcd::new_leased(
+        ["0.0.0.0:2379"],
+        ControlPlaneService::CoreAgent,
+        lease_ttl,
+        &Default::default(),
+    )
+    .await
+    .expect("First core-agent expired, the second one can now run!");
 
     let leases = store.leases().await.unwrap();
     let current_lease_id = leases.leases().first().unwrap().id();
@@ -203,3 +225,403 @@ async fn core_agent_lease_lock() {
     tracing::info!("core: {:?}", core.state);
     assert_eq!(Some(false), core.state.unwrap().running);
 }
+
+/// Test that the store prefix values can be read in pages
+#[tokio::test]
+async fn store_paged_prefix_reads() {
+    // deploy etcd only...
+    let _cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_jaeger(false)
+        .with_nats(false)
+        .with_io_engines(0)
+        .with_agents(vec![])
+        .build()
+        .await
+        .unwrap();
+
+    let mut etcd = Etcd::new("0.0.0.0:2379").await.unwrap();
+    let prefix = "/paged/test/";
+    for index in 0 .. 5 {
+        etcd.put_kv(&format!("{}{}", prefix, index), &index)
+            .await
+            .unwrap();
+    }
+    // an entry right after the prefix range, which should not be returned
+    etcd.put_kv(&"/paged/test0", &5).await.unwrap();
+
+    let mut pages = vec![];
+    let mut start_key = None;
+    loop {
+        let (page, next_key) = etcd
+            .get_values_prefix_paged(prefix, 2, start_key)
+            .await
+            .unwrap();
+        pages.push(page);
+        match next_key {
+            Some(next_key) => start_key = Some(next_key),
+            None => break,
+        }
+    }
+    assert_eq!(
+        pages.iter().map(|p| p.len()).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    let values = pages
+        .into_iter()
+        .flatten()
+        .map(|(_, value)| value)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        (0 .. 5).map(serde_json::Value::from).collect::<Vec<_>>()
+    );
+
+    let all = etcd.get_values_prefix(prefix).await.unwrap();
+    assert_eq!(all.len(), 5);
+}
+
+/// Test that a prefix watch multiplexes the events of all the entries with the prefix
+#[tokio::test]
+async fn store_watch_prefix() {
+    // deploy etcd only...
+    let _cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_jaeger(false)
+        .with_nats(false)
+        .with_io_engines(0)
+        .with_agents(vec![])
+        .build()
+        .await
+        .unwrap();
+
+    let mut etcd = Etcd::new("0.0.0.0:2379").await.unwrap();
+    let prefix = "/watch/test/";
+    let mut receiver = etcd.watch_prefix(prefix).await.unwrap();
+    async fn next_event(receiver: &mut StoreWatchReceiver) -> WatchEvent {
+        tokio::time::timeout(std::time::Duration::from_secs(5), receiver.recv())
+            .await
+            .expect("Timed out waiting for a watch event")
+            .expect("The watch should not stop")
+            .unwrap()
+    }
+
+    etcd.put_kv(&format!("{}a", prefix), &1).await.unwrap();
+    // an entry right after the prefix range, which should not be watched
+    etcd.put_kv(&"/watch/test0", &2).await.unwrap();
+    etcd.put_kv(&format!("{}b", prefix), &3).await.unwrap();
+
+    let event = next_event(&mut receiver).await;
+    assert!(
+        matches!(&event, WatchEvent::Put(key, value) if key == "/watch/test/a" && value == 1),
+        "{:?}",
+        event
+    );
+    let event = next_event(&mut receiver).await;
+    assert!(
+        matches!(&event, WatchEvent::Put(key, value) if key == "/watch/test/b" && value == 3),
+        "{:?}",
+        event
+    );
+
+    // deleting an entry doesn't stop the watch
+    etcd.delete_kv(&format!("{}a", prefix)).await.unwrap();
+    let event = next_event(&mut receiver).await;
+    assert!(
+        matches!(&event, WatchEvent::Delete(key) if key == "/watch/test/a"),
+        "{:?}",
+        event
+    );
+    etcd.put_kv(&format!("{}a", prefix), &4).await.unwrap();
+    let event = next_event(&mut receiver).await;
+    assert!(
+        matches!(&event, WatchEvent::Put(key, value) if key == "/watch/test/a" && value == 4),
+        "{:?}",
+        event
+    );
+}
+
+/// Test that the compare-and-swap writes only succeed with the current revision
+#[tokio::test]
+async fn store_put_obj_cas() {
+    // deploy etcd only...
+    let _cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_jaeger(false)
+        .with_nats(false)
+        .with_io_engines(0)
+        .with_agents(vec![])
+        .build()
+        .await
+        .unwrap();
+
+    let mut etcd = Etcd::new("0.0.0.0:2379").await.unwrap();
+    let node = NodeSpec::new(
+        "cas-node".into(),
+        "10.1.0.1:10124".into(),
+        NodeLabels::new(),
+    );
+
+    let created = etcd.put_obj_cas(&node, None).await.unwrap();
+    // the object exists already, so it can't be created again
+    let error = etcd.put_obj_cas(&node, None).await.unwrap_err();
+    assert!(
+        matches!(
+            error,
+            StoreError::RevisionMismatch {
+                expected: None,
+                actual: Some(actual),
+                ..
+            } if actual == created
+        ),
+        "{}",
+        error
+    );
+
+    let updated = etcd.put_obj_cas(&node, Some(created)).await.unwrap();
+    assert!(updated > created);
+    // a concurrent writer which read the object before the update conflicts with it
+    let error = etcd.put_obj_cas(&node, Some(created)).await.unwrap_err();
+    assert!(
+        matches!(
+            error,
+            StoreError::RevisionMismatch {
+                expected: Some(expected),
+                actual: Some(actual),
+                ..
+            } if expected == created && actual == updated
+        ),
+        "{}",
+        error
+    );
+
+    let stored: NodeSpec = etcd.get_obj(&node.key()).await.unwrap();
+    assert_eq!(stored, node);
+}
+
+/// Test that the control-plane entries can be dumped and restored
+#[tokio::test]
+async fn store_dump_restore() {
+    // deploy etcd only...
+    let _cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_jaeger(false)
+        .with_nats(false)
+        .with_io_engines(0)
+        .with_agents(vec![])
+        .build()
+        .await
+        .unwrap();
+
+    let mut etcd = Etcd::new("0.0.0.0:2379").await.unwrap();
+    let prefix = format!("{}/dump/test/", common_lib::etcd_key_prefix());
+    for index in 0 .. 5 {
+        etcd.put_kv(&format!("{}{}", prefix, index), &index)
+            .await
+            .unwrap();
+    }
+    // an entry which cannot be parsed, which should be dumped as it is
+    let mut client = Client::connect(["0.0.0.0:2379"], None).await.unwrap();
+    client
+        .put(format!("{}raw", prefix), "not json", None)
+        .await
+        .unwrap();
+    // an entry outside of the control-plane prefix, which should not be dumped
+    etcd.put_kv(&"/dump/test/0", &0).await.unwrap();
+    // a lease entry, which should not be dumped
+    let lease_key = format!(
+        "{}/{}/core",
+        common_lib::etcd_key_prefix(),
+        StorableObjectType::StoreLeaseOwner
+    );
+    etcd.put_kv(&lease_key, &0).await.unwrap();
+
+    let dump = etcd.dump().await.unwrap();
+    assert_eq!(dump.len(), 6);
+    assert!(dump.iter().all(|(key, _)| key.starts_with(&prefix)));
+    assert!(dump.contains(&(format!("{}raw", prefix), b"not json".to_vec())));
+
+    for (key, _) in &dump {
+        etcd.delete_kv(key).await.unwrap();
+    }
+    etcd.delete_kv(&lease_key).await.unwrap();
+    assert!(etcd.dump().await.unwrap().is_empty());
+
+    etcd.restore(dump.clone()).await.unwrap();
+    assert_eq!(etcd.dump().await.unwrap(), dump);
+}
+
+/// Test that the dump does not include the entries of a sibling key prefix, eg: `/cp2` for `/cp`
+#[tokio::test]
+async fn store_dump_sibling_prefix() {
+    // deploy etcd only...
+    let _cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_jaeger(false)
+        .with_nats(false)
+        .with_io_engines(0)
+        .with_agents(vec![])
+        .build()
+        .await
+        .unwrap();
+
+    let mut etcd = Etcd::new("0.0.0.0:2379").await.unwrap();
+    let key = format!("{}/dump/test/0", common_lib::etcd_key_prefix());
+    let sibling_key = format!("{}2/dump/test/0", common_lib::etcd_key_prefix());
+    etcd.put_kv(&key, &0).await.unwrap();
+    etcd.put_kv(&sibling_key, &0).await.unwrap();
+
+    let dump = etcd.dump().await.unwrap();
+    assert_eq!(
+        dump.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+        vec![&key]
+    );
+
+    etcd.delete_kv(&key).await.unwrap();
+    etcd.delete_kv(&sibling_key).await.unwrap();
+}
+
+/// Test that all the keys are stored under the overridden key prefix
+#[tokio::test]
+async fn store_key_prefix_override() {
+    let prefix = "/custom/prefix";
+    let _cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_io_engines(1)
+        .with_pools(1)
+        .with_agents(vec!["core"])
+        .with_store_key_prefix(prefix)
+        .build()
+        .await
+        .unwrap();
+    let mut etcd = Etcd::new("0.0.0.0:2379").await.unwrap();
+    let keys = etcd.get_values_prefix("/").await.unwrap();
+    assert!(!keys.is_empty());
+    assert!(
+        keys.iter().all(|(key, _)| key.starts_with(prefix)),
+        "{:?}",
+        keys.iter().map(|(key, _)| key).collect::<Vec<_>>()
+    );
+    // this process did not override its prefix, so the dump is empty
+    assert!(etcd.dump().await.unwrap().is_empty());
+}
+
+/// Test that an invalid store TLS configuration is rejected before connecting
+#[tokio::test]
+async fn store_invalid_tls() {
+    let error = StoreConnectOptions::new(None, Some("/tmp/cert.pem".into()), None)
+        .expect_err("The client key is missing");
+    assert!(matches!(error, StoreError::InvalidTls { .. }), "{}", error);
+
+    let options = StoreConnectOptions::new(Some("/nonexistent/ca.pem".into()), None, None).unwrap();
+    let error = Etcd::new_with_options("0.0.0.0:2379", &options)
+        .await
+        .expect_err("The CA certificate does not exist");
+    assert!(matches!(error, StoreError::InvalidTls { .. }), "{}", error);
+}
+
+/// Test that the lease status reflects a freshly acquired lease
+#[tokio::test]
+async fn store_lease_status() {
+    // deploy etcd only...
+    let _cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_jaeger(false)
+        .with_nats(false)
+        .with_io_engines(0)
+        .with_agents(vec![])
+        .build()
+        .await
+        .unwrap();
+
+    let etcd = Etcd::new("0.0.0.0:2379").await.unwrap();
+    assert_eq!(etcd.lease_status(), None, "Not a leased client");
+
+    let lease_ttl = std::time::Duration::from_secs(5);
+    let core_agent = Etcd::new_leased(
+        ["0.0.0.0:2379"],
+        ControlPlaneService::CoreAgent,
+        lease_ttl,
+        &Default::default(),
+    )
+    .await
+    .unwrap();
+
+    let mut store = Client::connect(["0.0.0.0:2379"], None)
+        .await
+        .expect("Failed to connect to etcd.");
+    let leases = store.leases().await.unwrap();
+    let lease_id = leases.leases().first().unwrap().id();
+
+    let status = core_agent.lease_status().expect("Should have a lease");
+    assert!(status.held);
+    assert_eq!(status.lease_id, lease_id);
+    assert!(status.remaining_ttl > std::time::Duration::ZERO);
+    assert!(status.remaining_ttl <= lease_ttl);
+}
+
+/// Test that the core agent rejects new requests once it is signalled to shut down, while it
+/// drains its in-flight operations
+#[tokio::test]
+async fn shutdown_rejects_new_requests() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_io_engines(1)
+        .with_pools(1)
+        .with_agents(vec!["core"])
+        .with_req_timeouts(
+            std::time::Duration::from_millis(500),
+            std::time::Duration::from_secs(5),
+        )
+        .build()
+        .await
+        .unwrap();
+    let replica_client = cluster.grpc_client().replica();
+    let pool_client = cluster.grpc_client().pool();
+
+    // an in-flight operation which the core agent has to drain on shutdown
+    cluster
+        .composer()
+        .pause(cluster.node(0).as_str())
+        .await
+        .unwrap();
+    let in_flight = replica_client.create(
+        &message_bus::CreateReplica {
+            node: cluster.node(0),
+            uuid: message_bus::ReplicaId::new(),
+            pool: cluster.pool(0, 0),
+            size: 5 * 1024 * 1024,
+            thin: false,
+            share: message_bus::Protocol::None,
+            ..Default::default()
+        },
+        None,
+    );
+    let stop = async {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        cluster.composer().stop("core").await.unwrap();
+    };
+    let late_request = async {
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        let result = pool_client.get(message_bus::Filter::None, None).await;
+        let core = cluster.composer().inspect("core").await.unwrap();
+        (result, core.state.unwrap().running)
+    };
+
+    let (_, _, (result, running)) = tokio::join!(in_flight, stop, late_request);
+    assert_eq!(
+        running,
+        Some(true),
+        "The core agent should still be draining"
+    );
+    assert!(
+        result.is_err(),
+        "Requests after the shutdown signal should be rejected"
+    );
+
+    cluster
+        .composer()
+        .thaw(cluster.node(0).as_str())
+        .await
+        .unwrap();
+}
@@ -16,10 +16,11 @@ use common_lib::{
     mbus_api::{Message, MessageId, MessageIdTimeout, ResourceKind},
     types::v0::{
         message_bus::{
-            AddNexusChild, Child, CreateNexus, CreatePool, CreateReplica, DestroyNexus,
-            DestroyPool, DestroyReplica, MessageIdVs, Nexus, NexusId, NodeId, NodeState,
-            NodeStatus, PoolId, PoolState, PoolStatus, Protocol, RemoveNexusChild, Replica,
-            ReplicaId, ShareNexus, ShareReplica, UnshareNexus, UnshareReplica,
+            AddNexusChild, Child, CreateNexus, CreatePool, CreateReplica, CreateSnapshot,
+            DeleteSnapshot, DestroyNexus, DestroyPool, DestroyReplica, ImportPool, MessageIdVs,
+            Nexus, NexusId, NodeId, NodeState, NodeStatus, OnlineNexusChild, PoolId, PoolState,
+            PoolStatus, Protocol, RemoveNexusChild, Replica, ReplicaId, ResizeNexus, ResizeReplica,
+            ShareNexus, ShareReplica, UnshareNexus, UnshareReplica,
         },
         store,
         store::{nexus::NexusState, replica::ReplicaState},
@@ -63,6 +64,10 @@ pub(crate) struct NodeWrapper {
     /// indicates whether the node has already missed its deadline and in such case we don't
     /// need to keep posting duplicate error events
     missed_deadline: bool,
+    /// number of consecutive deadlines missed while the node was also not responding to gRPC
+    consecutive_misses: u32,
+    /// number of consecutive missed deadlines after which the node is deemed offline
+    offline_misses: u32,
     /// gRPC CRUD lock
     lock: Arc<tokio::sync::Mutex<()>>,
     /// node communication timeouts
@@ -74,10 +79,12 @@ pub(crate) struct NodeWrapper {
 }
 
 impl NodeWrapper {
-    /// Create a new wrapper for a `Node` with a `deadline` for its watchdog
+    /// Create a new wrapper for a `Node` with a `deadline` for its watchdog, which is deemed
+    /// offline once it misses `offline_misses` consecutive deadlines
     pub(crate) fn new(
         node: &NodeState,
         deadline: std::time::Duration,
+        offline_misses: u32,
         comms_timeouts: NodeCommsTimeout,
     ) -> Self {
         tracing::debug!("Creating new node {:?}", node);
@@ -85,6 +92,8 @@ impl NodeWrapper {
             node_state: node.clone(),
             watchdog: Watchdog::new(&node.id, deadline),
             missed_deadline: false,
+            consecutive_misses: 0,
+            offline_misses: offline_misses.max(1),
             lock: Default::default(),
             comms_timeouts,
             states: ResourceStatesLocked::new(),
@@ -92,6 +101,11 @@ impl NodeWrapper {
         }
     }
 
+    /// Set the node communication timeouts, used by the next gRPC connections
+    pub(crate) fn set_comms_timeouts(&mut self, comms_timeouts: NodeCommsTimeout) {
+        self.comms_timeouts = comms_timeouts;
+    }
+
     /// Get `GrpcClient` for this node
     async fn grpc_client(&self) -> Result<GrpcClient, SvcError> {
         GrpcClient::new(&self.grpc_context()?).await
@@ -159,10 +173,16 @@ impl NodeWrapper {
             tracing::info!(node.uuid=%self.id(), "The node had missed the heartbeat deadline but it's now re-registered itself");
         }
         self.missed_deadline = false;
+        self.consecutive_misses = 0;
+        if self.status() == NodeStatus::Suspect {
+            self.set_status(NodeStatus::Online);
+        }
     }
 
     /// Update the node liveness if the watchdog's registration expired
     /// If the node is still responding to gRPC then consider it as online and reset the watchdog.
+    /// Otherwise the node is deemed suspect until it misses `offline_misses` consecutive
+    /// deadlines, after which it's deemed offline.
     pub(crate) async fn update_liveness(&mut self) {
         if self.registration_expired() {
             if !self.missed_deadline {
@@ -180,6 +200,19 @@ impl NodeWrapper {
                 if !self.missed_deadline {
                     tracing::warn!(node.uuid=%self.id(), "The node missed the heartbeat deadline but it's still responding to gRPC so we're considering it online");
                 }
+                self.consecutive_misses = 0;
+                if self.status() == NodeStatus::Suspect {
+                    self.set_status(NodeStatus::Online);
+                }
+            } else if self.consecutive_misses + 1 < self.offline_misses {
+                self.consecutive_misses += 1;
+                tracing::warn!(
+                    node.uuid=%self.id(),
+                    misses = self.consecutive_misses,
+                    offline_misses = self.offline_misses,
+                    "The node missed the heartbeat deadline and it's not responding to gRPC, so we're considering it suspect"
+                );
+                self.set_status(NodeStatus::Suspect);
             } else {
                 if self.missed_deadline {
                     tracing::error!(
@@ -361,6 +394,10 @@ impl NodeWrapper {
     pub(crate) fn nexus_states(&self) -> Vec<NexusState> {
         self.resources().get_cloned_nexus_states()
     }
+    /// Get the last time the nexus states were successfully refreshed from the io-engine
+    pub(crate) fn nexus_states_refreshed(&self) -> Option<std::time::SystemTime> {
+        self.resources().nexuses_refreshed()
+    }
     /// Get nexus
     fn nexus(&self, nexus_id: &NexusId) -> Option<Nexus> {
         self.resources().get_nexus_state(nexus_id).map(|s| s.nexus)
@@ -372,8 +409,9 @@ impl NodeWrapper {
             .map(|r| r.lock().replica.clone())
     }
     /// Is the node online
+    /// A suspect node is still considered online as it might just be a transient issue
     pub(crate) fn is_online(&self) -> bool {
-        self.status() == NodeStatus::Online
+        matches!(self.status(), NodeStatus::Online | NodeStatus::Suspect)
     }
 
     /// Load the node by fetching information from io-engine
@@ -638,14 +676,24 @@ pub(crate) trait ClientOps {
     ) -> Result<GrpcClientLocked, SvcError>;
     /// Create a pool on the node via gRPC
     async fn create_pool(&self, request: &CreatePool) -> Result<PoolState, SvcError>;
+    /// Import a pool from its disks on the node via gRPC, using the given pool uuid
+    async fn import_pool(&self, request: &ImportPool) -> Result<PoolState, SvcError>;
     /// Destroy a pool on the node via gRPC
     async fn destroy_pool(&self, request: &DestroyPool) -> Result<(), SvcError>;
     /// Create a replica on the pool via gRPC
     async fn create_replica(&self, request: &CreateReplica) -> Result<Replica, SvcError>;
+    /// Create a replica on the pool as a clone of the given snapshot via gRPC
+    async fn clone_replica(
+        &self,
+        request: &CreateReplica,
+        snapshot: &str,
+    ) -> Result<Replica, SvcError>;
     /// Share a replica on the pool via gRPC
     async fn share_replica(&self, request: &ShareReplica) -> Result<String, SvcError>;
     /// Unshare a replica on the pool via gRPC
     async fn unshare_replica(&self, request: &UnshareReplica) -> Result<String, SvcError>;
+    /// Resize a replica on the pool via gRPC
+    async fn resize_replica(&self, request: &ResizeReplica) -> Result<Replica, SvcError>;
     /// Destroy a replica on the pool via gRPC
     async fn destroy_replica(&self, request: &DestroyReplica) -> Result<(), SvcError>;
 
@@ -657,10 +705,22 @@ pub(crate) trait ClientOps {
     async fn share_nexus(&self, request: &ShareNexus) -> Result<String, SvcError>;
     /// Unshare a nexus on the node via gRPC
     async fn unshare_nexus(&self, request: &UnshareNexus) -> Result<(), SvcError>;
+    /// Resize a nexus on the node via gRPC
+    async fn resize_nexus(&self, request: &ResizeNexus) -> Result<(), SvcError>;
     /// Add a child to a nexus via gRPC
     async fn add_child(&self, request: &AddNexusChild) -> Result<Child, SvcError>;
     /// Remove a child from its parent nexus via gRPC
     async fn remove_child(&self, request: &RemoveNexusChild) -> Result<(), SvcError>;
+    /// Bring a child of a nexus back online via gRPC
+    async fn online_child(&self, request: &OnlineNexusChild) -> Result<(), SvcError>;
+    /// Snapshot the volume through its nexus via gRPC, returning the name of the snapshot
+    async fn create_snapshot(
+        &self,
+        request: &CreateSnapshot,
+        nexus: &NexusId,
+    ) -> Result<String, SvcError>;
+    /// Destroy the snapshot of a replica, with the given `name`, via gRPC
+    async fn destroy_snapshot(&self, request: &DeleteSnapshot, name: &str) -> Result<(), SvcError>;
 }
 
 /// Internal Operations on a io-engine locked `NodeWrapper` for the implementor
@@ -815,6 +875,27 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
         self.update_replica_states(ctx.deref_mut()).await?;
         Ok(pool)
     }
+    /// Import a pool from its disks on the node via gRPC, using the given pool uuid
+    async fn import_pool(&self, request: &ImportPool) -> Result<PoolState, SvcError> {
+        let mut ctx = self.grpc_client_locked(request.id()).await?;
+        let _ = ctx
+            .pool
+            .import_pool(request.to_rpc())
+            .await
+            .context(GrpcRequestError {
+                resource: ResourceKind::Pool,
+                request: "import_pool",
+            })?;
+        let mut ctx = ctx.reconnect(GETS_TIMEOUT).await?;
+        self.update_pool_states(ctx.deref_mut()).await?;
+        self.update_replica_states(ctx.deref_mut()).await?;
+        match self.pool(&request.id).await {
+            Some(pool) => Ok(pool),
+            None => Err(SvcError::PoolNotFound {
+                pool_id: request.id.clone(),
+            }),
+        }
+    }
     /// Destroy a pool on the node via gRPC
     async fn destroy_pool(&self, request: &DestroyPool) -> Result<(), SvcError> {
         let mut ctx = self.grpc_client_locked(request.id()).await?;
@@ -833,12 +914,19 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
 
     /// Create a replica on the pool via gRPC
     async fn create_replica(&self, request: &CreateReplica) -> Result<Replica, SvcError> {
-        if request.uuid == ReplicaId::default() {
+        if request.uuid.is_nil() {
             return Err(SvcError::InvalidUuid {
                 uuid: request.uuid.to_string(),
                 kind: ResourceKind::Replica,
             });
         }
+        if request.encryption.is_some() {
+            // the io-engine replica api has no means of creating an encrypted bdev
+            return Err(SvcError::EncryptionUnsupported {
+                replica: request.uuid.clone(),
+                node: request.node.clone(),
+            });
+        }
         let mut ctx = self.grpc_client_locked(request.id()).await?;
         let rpc_replica = ctx
             .io_engine
@@ -853,7 +941,27 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
         let mut ctx = ctx.reconnect(GETS_TIMEOUT).await?;
         self.update_replica_states(ctx.deref_mut()).await?;
         self.update_pool_states(ctx.deref_mut()).await?;
-        Ok(replica)
+        // prefer the replica as observed by the registry, which knows when its status changed
+        Ok(self.read().await.replica(&replica.uuid).unwrap_or(replica))
+    }
+
+    /// Create a replica on the pool as a clone of the given snapshot via gRPC
+    async fn clone_replica(
+        &self,
+        request: &CreateReplica,
+        _snapshot: &str,
+    ) -> Result<Replica, SvcError> {
+        if request.uuid.is_nil() {
+            return Err(SvcError::InvalidUuid {
+                uuid: request.uuid.to_string(),
+                kind: ResourceKind::Replica,
+            });
+        }
+        // the io-engine replica api has no means of cloning a snapshot
+        Err(SvcError::CloneUnsupported {
+            replica: request.uuid.clone(),
+            node: request.node.clone(),
+        })
     }
 
     /// Share a replica on the pool via gRPC
@@ -892,6 +1000,26 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
         Ok(local_uri)
     }
 
+    /// Resize a replica on the pool via gRPC
+    async fn resize_replica(&self, request: &ResizeReplica) -> Result<Replica, SvcError> {
+        let mut ctx = self.grpc_client_locked(request.id()).await?;
+        let rpc_replica = ctx
+            .io_engine
+            .resize_replica(request.to_rpc())
+            .await
+            .context(GrpcRequestError {
+                resource: ResourceKind::Replica,
+                request: "resize_replica",
+            })?;
+
+        let replica = rpc_replica_to_bus(&rpc_replica.into_inner(), &request.node)?;
+        let mut ctx = ctx.reconnect(GETS_TIMEOUT).await?;
+        self.update_replica_states(ctx.deref_mut()).await?;
+        self.update_pool_states(ctx.deref_mut()).await?;
+        // prefer the replica as observed by the registry, which knows when its status changed
+        Ok(self.read().await.replica(&replica.uuid).unwrap_or(replica))
+    }
+
     /// Destroy a replica on the pool via gRPC
     async fn destroy_replica(&self, request: &DestroyReplica) -> Result<(), SvcError> {
         let mut ctx = self.grpc_client_locked(request.id()).await?;
@@ -919,7 +1047,7 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
 
     /// Create a nexus on the node via gRPC
     async fn create_nexus(&self, request: &CreateNexus) -> Result<Nexus, SvcError> {
-        if request.uuid == NexusId::default() {
+        if request.uuid.is_nil() {
             return Err(SvcError::InvalidUuid {
                 uuid: request.uuid.to_string(),
                 kind: ResourceKind::Nexus,
@@ -940,7 +1068,8 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
         nexus.uuid = request.uuid.clone();
         let mut ctx = ctx.reconnect(GETS_TIMEOUT).await?;
         self.update_nexus_states(ctx.deref_mut()).await?;
-        Ok(nexus)
+        // prefer the nexus as observed by the registry, which knows when its children changed
+        Ok(self.read().await.nexus(&nexus.uuid).unwrap_or(nexus))
     }
 
     /// Destroy a nexus on the node via gRPC
@@ -992,8 +1121,45 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
         Ok(())
     }
 
+    /// Resize a nexus on the node via gRPC
+    async fn resize_nexus(&self, request: &ResizeNexus) -> Result<(), SvcError> {
+        let mut ctx = self.grpc_client_locked(request.id()).await?;
+        let _ = ctx
+            .io_engine
+            .resize_nexus(request.to_rpc())
+            .await
+            .context(GrpcRequestError {
+                resource: ResourceKind::Nexus,
+                request: "resize_nexus",
+            })?;
+        let mut ctx = ctx.reconnect(GETS_TIMEOUT).await?;
+        self.update_nexus_states(ctx.deref_mut()).await?;
+        Ok(())
+    }
+
     /// Add a child to a nexus via gRPC
     async fn add_child(&self, request: &AddNexusChild) -> Result<Child, SvcError> {
+        if let Some(preferred) = &request.rebuild_source {
+            let source = self
+                .read()
+                .await
+                .nexus(&request.nexus)
+                .and_then(|nexus| nexus.rebuild_source(Some(preferred)));
+            match source {
+                Some(source) => tracing::debug!(
+                    "Preferred rebuild source for child '{}' of nexus '{}' is '{}'",
+                    request.uri,
+                    request.nexus,
+                    source
+                ),
+                None => tracing::warn!(
+                    "Preferred rebuild source '{}' for child '{}' of nexus '{}' is not available, falling back to the io-engine's default",
+                    preferred,
+                    request.uri,
+                    request.nexus
+                ),
+            }
+        }
         let mut ctx = self.grpc_client_locked(request.id()).await?;
         let result = ctx.io_engine.add_child_nexus(request.to_rpc()).await;
         let mut ctx = ctx.reconnect(GETS_TIMEOUT).await?;
@@ -1021,7 +1187,12 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
             request: "add_child_nexus",
         })?;
         let child = rpc_child.into_inner().to_mbus();
-        Ok(child)
+        let observed = self
+            .read()
+            .await
+            .nexus(&request.nexus)
+            .and_then(|nexus| nexus.children.into_iter().find(|c| c.uri == child.uri));
+        Ok(observed.unwrap_or(child))
     }
 
     /// Remove a child from its parent nexus via gRPC
@@ -1052,6 +1223,58 @@ impl ClientOps for Arc<tokio::sync::RwLock<NodeWrapper>> {
             request: "remove_child_nexus",
         })
     }
+
+    /// Bring a child of a nexus back online via gRPC
+    async fn online_child(&self, request: &OnlineNexusChild) -> Result<(), SvcError> {
+        let mut ctx = self.grpc_client_locked(request.id()).await?;
+        let result = ctx.io_engine.child_operation(request.to_rpc()).await;
+
+        let mut ctx = ctx.reconnect(GETS_TIMEOUT).await?;
+        self.update_nexus_states(ctx.deref_mut()).await?;
+        result.context(GrpcRequestError {
+            resource: ResourceKind::Child,
+            request: "child_operation",
+        })?;
+        Ok(())
+    }
+    /// Snapshot the volume through its nexus via gRPC, returning the name of the snapshot
+    async fn create_snapshot(
+        &self,
+        request: &CreateSnapshot,
+        nexus: &NexusId,
+    ) -> Result<String, SvcError> {
+        let mut ctx = self.grpc_client_locked(request.id()).await?;
+        let snapshot = ctx
+            .io_engine
+            .create_snapshot(rpc::io_engine::CreateSnapshotRequest {
+                uuid: nexus.to_string(),
+            })
+            .await
+            .context(GrpcRequestError {
+                resource: ResourceKind::VolumeSnapshot,
+                request: "create_snapshot",
+            })?;
+        Ok(snapshot.into_inner().name)
+    }
+    /// Destroy the snapshot of a replica, with the given `name`, via gRPC
+    /// The replica snapshots are lvols of the pool, so they're destroyed as replicas.
+    async fn destroy_snapshot(&self, request: &DeleteSnapshot, name: &str) -> Result<(), SvcError> {
+        let mut ctx = self.grpc_client_locked(request.id()).await?;
+        let result = ctx
+            .io_engine
+            .destroy_replica(rpc::io_engine::DestroyReplicaRequest {
+                uuid: name.to_string(),
+            })
+            .await;
+        match result {
+            // the snapshot was already destroyed by a previous attempt
+            Err(status) if status.code() == tonic::Code::NotFound => Ok(()),
+            result => result.map(|_| ()).context(GrpcRequestError {
+                resource: ResourceKind::VolumeSnapshot,
+                request: "destroy_snapshot",
+            }),
+        }
+    }
 }
 
 /// convert rpc pool to a message bus pool
@@ -1138,6 +1361,13 @@ impl PoolWrapper {
         }
     }
 
+    /// Get the free space left once the given percentage of the capacity is reserved
+    pub fn free_space_reserved(&self, reserved_percent: u8) -> u64 {
+        let reserved =
+            (self.state.capacity as u128 * reserved_percent.min(100) as u128 / 100) as u64;
+        self.free_space().saturating_sub(reserved)
+    }
+
     /// Set pool state as unknown
     pub fn set_unknown(&mut self) {
         self.state.status = PoolStatus::Unknown;
@@ -1,7 +1,7 @@
 #![warn(missing_docs)]
 
 use common::ServiceError;
-use futures::{future::join_all, FutureExt};
+use futures::{future::join_all, Future};
 use grpc::{
     operations::{
         nexus::server::NexusServer, node::server::NodeServer, pool::server::PoolServer,
@@ -11,7 +11,9 @@ use grpc::{
     tracing::OpenTelServer,
 };
 use http::Uri;
-use tracing::error;
+use std::time::Duration;
+use tokio::task::JoinError;
+use tracing::{error, warn};
 
 /// the gprc service that encapsulates the base_service and the server for rpc
 pub struct Service {
@@ -29,7 +31,10 @@ impl Service {
     }
 
     /// launch each of the services and the grpc server
-    pub async fn run(self) {
+    /// Once TERM or INT is received the message bus handlers and the grpc server stop accepting
+    /// new requests and the given `drain` future is awaited, allowing the in-flight operations
+    /// to complete, along with the server threads, for at most the `shutdown_timeout`
+    pub async fn run(self, drain: impl Future<Output = ()>, shutdown_timeout: Duration) {
         let grpc_addr = self.base_service.get_shared_state::<Uri>().clone();
         let pool_service = self.base_service.get_shared_state::<PoolServer>().clone();
         let replica_service = self
@@ -59,17 +64,21 @@ impl Service {
             .add_service(registry_service.into_grpc_server())
             .add_service(nexus_service.into_grpc_server());
 
+        let shutdown = common::termination_signal();
         let mut threads = if self.base_service.nats_enabled() {
-            self.base_service.mbus_handles().await
+            self.base_service
+                .mbus_handles_with_shutdown(shutdown.clone())
+                .await
         } else {
             vec![]
         };
 
+        let tonic_shutdown = shutdown.clone();
         let tonic_thread = tokio::spawn(async move {
             tonic_router
                 .serve_with_shutdown(
                     grpc_addr.authority().unwrap().to_string().parse().unwrap(),
-                    Self::shutdown_signal().map(|_| ()),
+                    tonic_shutdown,
                 )
                 .await
                 .map_err(|source| ServiceError::GrpcServer { source })
@@ -77,37 +86,30 @@ impl Service {
 
         threads.push(tonic_thread);
 
-        join_all(threads)
-            .await
-            .iter()
-            .for_each(|result| match result {
-                Err(error) => error!("Failed to wait for thread: {:?}", error),
-                Ok(Err(error)) => {
-                    error!(error=?error, "Error running service thread");
+        let mut threads = join_all(threads);
+        tokio::select! {
+            biased;
+            _shutdown = shutdown => {
+                let drained = tokio::time::timeout(shutdown_timeout, async move {
+                    let (_, results) = futures::join!(drain, threads);
+                    Self::log_results(results);
+                });
+                if drained.await.is_err() {
+                    warn!("Timed out waiting for the service to shut down");
                 }
-                _ => {}
-            });
+            },
+            results = &mut threads => Self::log_results(results),
+        }
     }
 
-    /// Get a shutdown_signal as a oneshot channel when the process receives either TERM or INT.
-    /// When received the opentel traces are also immediately flushed.
-    fn shutdown_signal() -> tokio::sync::oneshot::Receiver<()> {
-        let mut signal_term =
-            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).unwrap();
-        let mut signal_int =
-            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt()).unwrap();
-        let (stop_sender, stop_receiver) = tokio::sync::oneshot::channel();
-        tokio::spawn(async move {
-            tokio::select! {
-                _term = signal_term.recv() => {tracing::info!("SIGTERM received")},
-                _int = signal_int.recv() => {tracing::info!("SIGINT received")},
-            }
-            opentelemetry::global::force_flush_tracer_provider();
-            if stop_sender.send(()).is_err() {
-                // should we panic here?
-                tracing::warn!("Failed to stop the tonic server");
+    /// Log the failures of the service threads
+    fn log_results(results: Vec<Result<Result<(), ServiceError>, JoinError>>) {
+        results.iter().for_each(|result| match result {
+            Err(error) => error!("Failed to wait for thread: {:?}", error),
+            Ok(Err(error)) => {
+                error!(error=?error, "Error running service thread");
             }
+            _ => {}
         });
-        stop_receiver
     }
 }
@@ -4,6 +4,7 @@ use common_lib::types::v0::{
     message_bus::{Nexus, NexusId, NodeId, VolumeId},
     store::nexus_persistence::{NexusInfo, NexusInfoKey},
 };
+use grpc::operations::{PaginatedResult, Pagination};
 use snafu::OptionExt;
 
 /// Nexus helpers
@@ -61,6 +62,16 @@ impl Registry {
         nexuses
     }
 
+    /// Get a paginated subset of the nexuses from all nodes, ordered by their uuid
+    pub(crate) async fn get_paginated_nexuses(
+        &self,
+        pagination: &Pagination,
+    ) -> PaginatedResult<Nexus> {
+        let mut nexuses = self.get_nexuses().await;
+        nexuses.sort_by(|a, b| a.uuid.as_str().cmp(b.uuid.as_str()));
+        PaginatedResult::paginate(nexuses, pagination)
+    }
+
     /// Fetch the `NexusInfo` from the persistent store
     /// Returns an error if we fail to query the persistent store
     /// Returns Ok(None) if the entry does not exist or if no nexus_uuid was provided
@@ -1,10 +1,14 @@
-use crate::core::{registry::Registry, specs::ResourceSpecsLocked};
-use common::errors::SvcError;
+use crate::core::{audit, registry::Registry, specs::ResourceSpecsLocked};
+use common::{
+    errors::{GrpcRequestError, SvcError},
+    v0::msg_translation::{MessageBusToRpc, RpcToMessageBus},
+};
 use common_lib::{
-    mbus_api::{message_bus::v0::Nexuses, ReplyError},
+    mbus_api::{message_bus::v0::Nexuses, ReplyError, ResourceKind},
     types::v0::{
         message_bus::{
-            AddNexusChild, Child, CreateNexus, DestroyNexus, Filter, GetNexuses, Nexus,
+            AddNexusChild, CancelRebuild, Child, ChildUri, CreateNexus, DestroyNexus, Filter,
+            GetNexuses, GetRebuildHistory, GetRebuilds, Nexus, Rebuild, RebuildHistory, Rebuilds,
             RemoveNexusChild, ShareNexus, UnshareNexus,
         },
         store::OperationMode,
@@ -12,11 +16,17 @@ use common_lib::{
 };
 use grpc::{
     context::Context,
-    operations::nexus::traits::{
-        AddNexusChildInfo, CreateNexusInfo, DestroyNexusInfo, NexusOperations,
-        RemoveNexusChildInfo, ShareNexusInfo, UnshareNexusInfo,
+    operations::{
+        nexus::traits::{
+            AddNexusChildInfo, CancelRebuildInfo, CreateNexusInfo, DestroyNexusInfo,
+            GetRebuildHistoryInfo, GetRebuildsInfo, NexusOperations, RebuildOperations,
+            RemoveNexusChildInfo, ShareNexusInfo, UnshareNexusInfo,
+        },
+        Pagination,
     },
 };
+use snafu::ResultExt;
+use std::{convert::TryFrom, time::SystemTime};
 
 #[derive(Debug, Clone)]
 pub(super) struct Service {
@@ -32,13 +42,20 @@ impl NexusOperations for Service {
     ) -> Result<Nexus, ReplyError> {
         let req = nexus.into();
         let service = self.clone();
-        let nexus = Context::spawn(async move { service.create_nexus(&req).await }).await??;
+        let nexus =
+            Context::spawn(async move { audit::grpc(&req, service.create_nexus(&req).await) })
+                .await??;
         Ok(nexus)
     }
 
-    async fn get(&self, filter: Filter, _ctx: Option<Context>) -> Result<Nexuses, ReplyError> {
+    async fn get(
+        &self,
+        filter: Filter,
+        pagination: Option<Pagination>,
+        _ctx: Option<Context>,
+    ) -> Result<Nexuses, ReplyError> {
         let req = GetNexuses { filter };
-        let nexuses = self.get_nexuses(&req).await?;
+        let nexuses = self.get_nexuses(&req, pagination).await?;
         Ok(nexuses)
     }
 
@@ -49,7 +66,10 @@ impl NexusOperations for Service {
     ) -> Result<(), ReplyError> {
         let destroy_nexus = req.into();
         let service = self.clone();
-        Context::spawn(async move { service.destroy_nexus(&destroy_nexus).await }).await??;
+        Context::spawn(async move {
+            audit::grpc(&destroy_nexus, service.destroy_nexus(&destroy_nexus).await)
+        })
+        .await??;
         Ok(())
     }
 
@@ -60,8 +80,10 @@ impl NexusOperations for Service {
     ) -> Result<String, ReplyError> {
         let share_nexus = req.into();
         let service = self.clone();
-        let response =
-            Context::spawn(async move { service.share_nexus(&share_nexus).await }).await??;
+        let response = Context::spawn(async move {
+            audit::grpc(&share_nexus, service.share_nexus(&share_nexus).await)
+        })
+        .await??;
         Ok(response)
     }
 
@@ -72,7 +94,10 @@ impl NexusOperations for Service {
     ) -> Result<(), ReplyError> {
         let unshare_nexus = req.into();
         let service = self.clone();
-        Context::spawn(async move { service.unshare_nexus(&unshare_nexus).await }).await??;
+        Context::spawn(async move {
+            audit::grpc(&unshare_nexus, service.unshare_nexus(&unshare_nexus).await)
+        })
+        .await??;
         Ok(())
     }
 
@@ -83,8 +108,13 @@ impl NexusOperations for Service {
     ) -> Result<Child, ReplyError> {
         let add_nexus_child = req.into();
         let service = self.clone();
-        let child = Context::spawn(async move { service.add_nexus_child(&add_nexus_child).await })
-            .await??;
+        let child = Context::spawn(async move {
+            audit::grpc(
+                &add_nexus_child,
+                service.add_nexus_child(&add_nexus_child).await,
+            )
+        })
+        .await??;
         Ok(child)
     }
 
@@ -95,7 +125,57 @@ impl NexusOperations for Service {
     ) -> Result<(), ReplyError> {
         let unshare_nexus = req.into();
         let service = self.clone();
-        Context::spawn(async move { service.remove_nexus_child(&unshare_nexus).await }).await??;
+        Context::spawn(async move {
+            audit::grpc(
+                &unshare_nexus,
+                service.remove_nexus_child(&unshare_nexus).await,
+            )
+        })
+        .await??;
+        Ok(())
+    }
+
+    async fn get_nexus_rebuild_history(
+        &self,
+        req: &dyn GetRebuildHistoryInfo,
+        _ctx: Option<Context>,
+    ) -> Result<RebuildHistory, ReplyError> {
+        let req = req.into();
+        let history = self.get_rebuild_history(&req).await?;
+        Ok(history)
+    }
+
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        return Ok(true);
+    }
+}
+
+#[tonic::async_trait]
+impl RebuildOperations for Service {
+    async fn get_rebuilds(
+        &self,
+        req: &dyn GetRebuildsInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Rebuilds, ReplyError> {
+        let req = req.into();
+        let rebuilds = self.get_rebuilds(&req).await?;
+        Ok(rebuilds)
+    }
+
+    async fn cancel_rebuild(
+        &self,
+        req: &dyn CancelRebuildInfo,
+        _ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        let cancel_rebuild = req.into();
+        let service = self.clone();
+        Context::spawn(async move {
+            audit::grpc(
+                &cancel_rebuild,
+                service.cancel_rebuild(&cancel_rebuild).await,
+            )
+        })
+        .await??;
         Ok(())
     }
 }
@@ -109,10 +189,24 @@ impl Service {
 
     /// Get nexuses according to the filter
     #[tracing::instrument(level = "info", skip(self), err)]
-    pub(super) async fn get_nexuses(&self, request: &GetNexuses) -> Result<Nexuses, SvcError> {
+    pub(super) async fn get_nexuses(
+        &self,
+        request: &GetNexuses,
+        pagination: Option<Pagination>,
+    ) -> Result<Nexuses, SvcError> {
+        // The last result can only ever be false if using pagination.
+        let mut last_result = true;
+
         let filter = request.filter.clone();
         let nexuses = match filter {
-            Filter::None => self.registry.get_node_opt_nexuses(None).await?,
+            Filter::None => match &pagination {
+                Some(p) => {
+                    let paginated_nexuses = self.registry.get_paginated_nexuses(p).await;
+                    last_result = paginated_nexuses.last();
+                    paginated_nexuses.result()
+                }
+                None => self.registry.get_node_opt_nexuses(None).await?,
+            },
             Filter::Node(node_id) => self.registry.get_node_nexuses(&node_id).await?,
             Filter::NodeNexus(node_id, nexus_id) => {
                 let nexus = self.registry.get_node_nexus(&node_id, &nexus_id).await?;
@@ -124,7 +218,13 @@ impl Service {
             }
             _ => return Err(SvcError::InvalidFilter { filter }),
         };
-        Ok(Nexuses(nexuses))
+        Ok(Nexuses {
+            entries: nexuses,
+            next_token: match last_result {
+                true => None,
+                false => pagination.map(|p| p.starting_token() + p.max_entries()),
+            },
+        })
     }
 
     /// Create nexus
@@ -177,4 +277,136 @@ impl Service {
             .remove_nexus_child(&self.registry, request, OperationMode::Exclusive)
             .await
     }
+
+    /// Get the rebuild history of a nexus from its io-engine
+    #[tracing::instrument(level = "info", skip(self), err, fields(nexus.uuid = %request.nexus))]
+    pub(super) async fn get_rebuild_history(
+        &self,
+        request: &GetRebuildHistory,
+    ) -> Result<RebuildHistory, SvcError> {
+        let nexus = self.registry.get_nexus(&request.nexus).await?;
+        let node = self.registry.get_node_wrapper(&nexus.node).await?;
+
+        let grpc = node.read().await.grpc_context()?;
+        let mut client = grpc.connect().await?;
+
+        let response = client
+            .io_engine
+            .get_rebuild_history(request.to_rpc())
+            .await
+            .context(GrpcRequestError {
+                resource: ResourceKind::Nexus,
+                request: "get_rebuild_history",
+            })?
+            .into_inner();
+
+        Ok(RebuildHistory {
+            nexus: request.nexus.clone(),
+            records: response.records.iter().map(|r| r.to_mbus()).collect(),
+        })
+    }
+
+    /// Get the rebuilds in progress, of all the nexuses or of the given nexus only
+    /// The progress is known from the nexus state, so a rebuild is still listed if its start
+    /// time can't be retrieved from the io-engine, eg: if its node is unreachable.
+    #[tracing::instrument(level = "info", skip(self), err)]
+    pub(super) async fn get_rebuilds(&self, request: &GetRebuilds) -> Result<Rebuilds, SvcError> {
+        let nexuses = match &request.nexus {
+            Some(nexus) => vec![self.registry.get_nexus(nexus).await?],
+            None => self.registry.get_nexuses().await,
+        };
+
+        let mut entries = vec![];
+        for nexus in nexuses {
+            let children = nexus
+                .children
+                .iter()
+                .filter_map(|c| c.rebuild_progress.map(|p| (c.uri.clone(), p)))
+                .collect::<Vec<_>>();
+            if children.is_empty() {
+                continue;
+            }
+
+            for (child_uri, progress) in children {
+                let start_time = match self.rebuild_start_time(&nexus, &child_uri).await {
+                    Ok(start_time) => start_time,
+                    Err(error) => {
+                        tracing::warn!(
+                            nexus.uuid = %nexus.uuid,
+                            child.uri = %child_uri,
+                            error = %error,
+                            "Failed to get the start time of the rebuild"
+                        );
+                        None
+                    }
+                };
+                entries.push(Rebuild {
+                    nexus: nexus.uuid.clone(),
+                    child_uri,
+                    progress,
+                    start_time,
+                });
+            }
+        }
+        Ok(Rebuilds { entries })
+    }
+
+    /// Get the start time of the rebuild of the given nexus child from its io-engine
+    async fn rebuild_start_time(
+        &self,
+        nexus: &Nexus,
+        child_uri: &ChildUri,
+    ) -> Result<Option<SystemTime>, SvcError> {
+        let node = self.registry.get_node_wrapper(&nexus.node).await?;
+        let grpc = node.read().await.grpc_context()?;
+        let mut client = grpc.connect().await?;
+
+        let stats = client
+            .io_engine
+            .get_rebuild_stats(rpc::io_engine::RebuildStatsRequest {
+                uuid: nexus.uuid.to_string(),
+                uri: child_uri.to_string(),
+            })
+            .await
+            .context(GrpcRequestError {
+                resource: ResourceKind::Nexus,
+                request: "get_rebuild_stats",
+            })?
+            .into_inner();
+        Ok(stats.start_time.and_then(|t| SystemTime::try_from(t).ok()))
+    }
+
+    /// Cancel the rebuild of a nexus child on its io-engine
+    #[tracing::instrument(level = "info", skip(self), err, fields(nexus.uuid = %request.nexus))]
+    pub(super) async fn cancel_rebuild(&self, request: &CancelRebuild) -> Result<(), SvcError> {
+        let nexus = self.registry.get_nexus(&request.nexus).await?;
+        let child = nexus
+            .children
+            .iter()
+            .find(|c| c.uri == request.child_uri)
+            .ok_or(SvcError::ChildNotFound {
+                nexus: request.nexus.to_string(),
+                child: request.child_uri.to_string(),
+            })?;
+        if child.rebuild_progress.is_none() {
+            return Err(SvcError::ChildNotRebuilding {
+                nexus: request.nexus.to_string(),
+                child: request.child_uri.to_string(),
+            });
+        }
+
+        let node = self.registry.get_node_wrapper(&nexus.node).await?;
+        let grpc = node.read().await.grpc_context()?;
+        let mut client = grpc.connect().await?;
+
+        client
+            .io_engine
+            .stop_rebuild(request.to_rpc())
+            .await
+            .context(GrpcRequestError {
+                resource: ResourceKind::Nexus,
+                request: "stop_rebuild",
+            })?;
+        Ok(())
+    }
 }
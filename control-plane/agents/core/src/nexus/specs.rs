@@ -9,8 +9,8 @@ use common_lib::{
     types::v0::{
         message_bus::{
             AddNexusChild, AddNexusReplica, Child, ChildUri, CreateNexus, DestroyNexus, Nexus,
-            NexusId, NexusStatus, RemoveNexusChild, RemoveNexusReplica, ReplicaOwners, ShareNexus,
-            UnshareNexus,
+            NexusId, NexusNvmfConfig, NexusStatus, RemoveNexusChild, RemoveNexusReplica,
+            ReplicaOwners, ResizeNexus, ShareNexus, UnshareNexus,
         },
         store::{
             nexus::{NexusOperation, NexusSpec},
@@ -25,6 +25,33 @@ use parking_lot::Mutex;
 use snafu::OptionExt;
 use std::sync::Arc;
 
+/// The io-engine applies the nvmf transport parameters when the nexus is created, so the ones
+/// requested when sharing the nexus must be valid and match the nexus configuration
+fn validate_share_transport_params(
+    request: &ShareNexus,
+    config: Option<NexusNvmfConfig>,
+) -> Result<(), SvcError> {
+    if request.transport_params.is_empty() {
+        return Ok(());
+    }
+    let config = config.unwrap_or_default();
+    let requested = config
+        .clone()
+        .with_transport_params(&request.transport_params)
+        .map_err(|error| SvcError::InvalidTransportParams {
+            details: error.source,
+        })?;
+    if requested != config {
+        return Err(SvcError::InvalidTransportParams {
+            details: format!(
+                "nexus '{}' was created with a different configuration, the transport parameters can only be set when it is created",
+                request.uuid
+            ),
+        });
+    }
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl SpecOperations for NexusSpec {
     type Create = CreateNexus;
@@ -67,6 +94,13 @@ impl SpecOperations for NexusSpec {
                 })
             }
             NexusOperation::RemoveChild(_) => Ok(()),
+            NexusOperation::Resize(size) if *size <= state.size => Err(SvcError::InvalidResize {
+                kind: ResourceKind::Nexus,
+                id: self.uuid(),
+                current: state.size,
+                requested: *size,
+            }),
+            NexusOperation::Resize(_) => Ok(()),
             _ => unreachable!(),
         }?;
         self.start_op(op);
@@ -244,6 +278,9 @@ impl ResourceSpecsLocked {
         let node = registry.get_node_wrapper(&request.node).await?;
 
         if let Some(nexus_spec) = self.get_nexus(&request.uuid) {
+            let config = nexus_spec.lock().config.clone();
+            validate_share_transport_params(request, config)?;
+
             let status = registry.get_nexus(&request.uuid).await?;
             let (spec_clone, _guard) = SpecOperations::start_update(
                 registry,
@@ -257,6 +294,7 @@ impl ResourceSpecsLocked {
             let result = node.share_nexus(request).await;
             SpecOperations::complete_update(registry, result, nexus_spec, spec_clone).await
         } else {
+            validate_share_transport_params(request, None)?;
             node.share_nexus(request).await
         }
     }
@@ -287,6 +325,32 @@ impl ResourceSpecsLocked {
         }
     }
 
+    pub async fn resize_nexus(
+        &self,
+        registry: &Registry,
+        request: &ResizeNexus,
+        mode: OperationMode,
+    ) -> Result<(), SvcError> {
+        let node = registry.get_node_wrapper(&request.node).await?;
+
+        if let Some(nexus_spec) = self.get_nexus(&request.uuid) {
+            let status = registry.get_nexus(&request.uuid).await?;
+            let (spec_clone, _guard) = SpecOperations::start_update(
+                registry,
+                &nexus_spec,
+                &status,
+                NexusOperation::Resize(request.requested_size),
+                mode,
+            )
+            .await?;
+
+            let result = node.resize_nexus(request).await;
+            SpecOperations::complete_update(registry, result, nexus_spec, spec_clone).await
+        } else {
+            node.resize_nexus(request).await
+        }
+    }
+
     pub async fn add_nexus_child(
         &self,
         registry: &Registry,
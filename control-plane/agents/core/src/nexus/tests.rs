@@ -4,17 +4,21 @@ use common_lib::{
     mbus_api::*,
     types::v0::{
         message_bus::{
-            AddNexusChild, CreateNexus, CreateReplica, DestroyNexus, DestroyReplica, Filter,
-            GetNexuses, GetSpecs, Nexus, NexusId, NexusShareProtocol, Protocol, RemoveNexusChild,
-            ReplicaId, ShareNexus, UnshareNexus,
+            AddNexusChild, CancelRebuild, ChildUri, ClearOperation, CreateNexus, CreateReplica,
+            DestroyNexus, DestroyReplica, Filter, GetNexuses, GetOperations, GetRebuildHistory,
+            GetRebuilds, GetSpecs, Nexus, NexusId, NexusShareProtocol, Protocol, RebuildJobState,
+            ReconcileResource, RemoveNexusChild, ReplicaId, ResourceRef, ShareNexus, UnshareNexus,
+            VolumeId,
         },
         store::nexus::NexusSpec,
     },
 };
 use deployer_cluster::{Cluster, ClusterBuilder};
 use grpc::operations::{
-    nexus::traits::NexusOperations, node::traits::NodeOperations,
-    registry::traits::RegistryOperations, replica::traits::ReplicaOperations,
+    nexus::traits::{NexusOperations, RebuildOperations},
+    node::traits::NodeOperations,
+    registry::traits::RegistryOperations,
+    replica::traits::ReplicaOperations,
 };
 use std::{convert::TryFrom, time::Duration};
 
@@ -71,10 +75,10 @@ async fn nexus() {
         .unwrap();
 
     let nexuses = nexus_client
-        .get(GetNexuses::default().filter, None)
+        .get(GetNexuses::default().filter, None, None)
         .await
         .unwrap()
-        .0;
+        .entries;
     tracing::info!("Nexuses: {:?}", nexuses);
     assert_eq!(Some(&nexus), nexuses.first());
 
@@ -85,12 +89,37 @@ async fn nexus() {
                 uuid: NexusId::try_from("f086f12c-1728-449e-be32-9415051090d6").unwrap(),
                 key: None,
                 protocol: NexusShareProtocol::Nvmf,
+                transport_params: Default::default(),
             },
             None,
         )
         .await
         .unwrap();
 
+    // the children of a new nexus have never been rebuilt
+    let history = nexus_client
+        .get_nexus_rebuild_history(&GetRebuildHistory::new(&nexus.uuid), None)
+        .await
+        .unwrap();
+    assert_eq!(history.nexus, nexus.uuid);
+    assert!(history.records.is_empty());
+
+    // nor are they being rebuilt, so there's no rebuild to cancel either
+    let rebuild_client = cluster.grpc_client().rebuild();
+    let rebuilds = rebuild_client
+        .get_rebuilds(&GetRebuilds::nexus(&nexus.uuid), None)
+        .await
+        .unwrap();
+    assert!(rebuilds.entries.is_empty());
+    let error = rebuild_client
+        .cancel_rebuild(
+            &CancelRebuild::new(&nexus.uuid, &nexus.children[0].uri),
+            None,
+        )
+        .await
+        .expect_err("the child is not being rebuilt");
+    assert_eq!(error.kind, ReplyErrorKind::FailedPrecondition);
+
     nexus_client
         .destroy(
             &DestroyNexus {
@@ -102,16 +131,22 @@ async fn nexus() {
         .await
         .unwrap();
 
+    let error = nexus_client
+        .get_nexus_rebuild_history(&GetRebuildHistory::new(&nexus.uuid), None)
+        .await
+        .expect_err("the nexus no longer exists");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+
     rep_client
         .destroy(&DestroyReplica::from(replica), None)
         .await
         .unwrap();
 
     assert!(nexus_client
-        .get(GetNexuses::default().filter, None)
+        .get(GetNexuses::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
 }
 
@@ -134,6 +169,258 @@ async fn nexus_spec(replica: &Nexus, client: &dyn RegistryOperations) -> Option<
 }
 
 /// Tests nexus share and unshare operations as a transaction
+/// Tests the listing of a rebuild which is in progress
+#[tokio::test]
+async fn nexus_rebuilds() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_io_engines(2)
+        .with_pools(1)
+        .with_cache_period("250ms")
+        .build()
+        .await
+        .unwrap();
+
+    let nexus_node = cluster.node(0);
+    let replica_node = cluster.node(1);
+    let nexus_client = cluster.grpc_client().nexus();
+    let rebuild_client = cluster.grpc_client().rebuild();
+    let rep_client = cluster.grpc_client().replica();
+
+    let replica = rep_client
+        .create(
+            &CreateReplica {
+                node: replica_node.clone(),
+                uuid: ReplicaId::new(),
+                pool: cluster.pool(1, 0),
+                size: 64 * 1024 * 1024,
+                thin: false,
+                share: Protocol::Nvmf,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let local = "malloc:///local?size_mb=64&uuid=2f6c1a3e-9b4d-4e8a-a5c7-1d3e5f7a9b2c".into();
+    let nexus = nexus_client
+        .create(
+            &CreateNexus {
+                node: nexus_node.clone(),
+                uuid: NexusId::new(),
+                size: 32 * 1024 * 1024,
+                children: vec![local],
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    // add the replica without rebuilding it, and then start the rebuild whilst the replica's
+    // io-engine is paused, so that the rebuild can't complete
+    let child_uri: ChildUri = replica.uri.clone().into();
+    nexus_client
+        .add_nexus_child(
+            &AddNexusChild {
+                node: nexus_node.clone(),
+                nexus: nexus.uuid.clone(),
+                uri: child_uri.clone(),
+                auto_rebuild: false,
+                rebuild_source: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    cluster
+        .composer()
+        .pause(replica_node.as_str())
+        .await
+        .unwrap();
+    let mut rpc_handle = cluster.grpc_handle(nexus_node.as_str()).await.unwrap();
+    rpc_handle
+        .io_engine
+        .start_rebuild(rpc::io_engine::StartRebuildRequest {
+            uuid: nexus.uuid.to_string(),
+            uri: child_uri.to_string(),
+        })
+        .await
+        .unwrap();
+
+    let mut rebuilds = vec![];
+    for _ in 0 .. 20 {
+        rebuilds = rebuild_client
+            .get_rebuilds(&GetRebuilds::all(), None)
+            .await
+            .unwrap()
+            .entries;
+        if !rebuilds.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    cluster
+        .composer()
+        .thaw(replica_node.as_str())
+        .await
+        .unwrap();
+    tracing::info!("Rebuilds: {:?}", rebuilds);
+    assert_eq!(rebuilds.len(), 1, "The rebuild should be in progress");
+    assert_eq!(rebuilds[0].nexus, nexus.uuid);
+    assert_eq!(rebuilds[0].child_uri, child_uri);
+    assert!(rebuilds[0].progress <= 100);
+    assert!(rebuilds[0].start_time.is_some());
+
+    // once the replica's io-engine is back the rebuild completes
+    for _ in 0 .. 40 {
+        rebuilds = rebuild_client
+            .get_rebuilds(&GetRebuilds::nexus(&nexus.uuid), None)
+            .await
+            .unwrap()
+            .entries;
+        if rebuilds.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    assert!(rebuilds.is_empty(), "The rebuild should have completed");
+
+    // and it's recorded in the rebuild history of the nexus
+    let history = nexus_client
+        .get_nexus_rebuild_history(&GetRebuildHistory::new(&nexus.uuid), None)
+        .await
+        .unwrap();
+    tracing::info!("History: {:?}", history);
+    assert_eq!(history.nexus, nexus.uuid);
+    let record = history
+        .records
+        .last()
+        .expect("The rebuild should be recorded");
+    assert_eq!(record.child_uri, child_uri);
+    assert_eq!(record.src_uri, nexus.children[0].uri);
+    assert_eq!(record.state, RebuildJobState::Completed);
+    assert_eq!(record.blocks_recovered, record.blocks_total);
+    assert!(record.start_time <= record.end_time);
+
+    nexus_client
+        .destroy(
+            &DestroyNexus {
+                node: nexus_node.clone(),
+                uuid: nexus.uuid.clone(),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    rep_client
+        .destroy(&DestroyReplica::from(replica), None)
+        .await
+        .unwrap();
+}
+
+/// Tests that adding a child whose preferred rebuild source isn't available falls back to the
+/// io-engine's default source
+#[tokio::test]
+async fn nexus_rebuild_source_fallback() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_io_engines(2)
+        .with_pools(1)
+        .with_cache_period("250ms")
+        .build()
+        .await
+        .unwrap();
+
+    let nexus_node = cluster.node(0);
+    let replica_node = cluster.node(1);
+    let nexus_client = cluster.grpc_client().nexus();
+    let rep_client = cluster.grpc_client().replica();
+
+    let replica = rep_client
+        .create(
+            &CreateReplica {
+                node: replica_node.clone(),
+                uuid: ReplicaId::new(),
+                pool: cluster.pool(1, 0),
+                size: 64 * 1024 * 1024,
+                thin: false,
+                share: Protocol::Nvmf,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let local = format!("malloc:///local?size_mb=64&uuid={}", ReplicaId::new()).into();
+    let nexus = nexus_client
+        .create(
+            &CreateNexus {
+                node: nexus_node.clone(),
+                uuid: NexusId::new(),
+                size: 32 * 1024 * 1024,
+                children: vec![local],
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    // the preferred source is not a child of the nexus, so the io-engine picks the source
+    let child_uri: ChildUri = replica.uri.clone().into();
+    let unavailable = format!("malloc:///unavailable?size_mb=64&uuid={}", ReplicaId::new());
+    nexus_client
+        .add_nexus_child(
+            &AddNexusChild {
+                node: nexus_node.clone(),
+                nexus: nexus.uuid.clone(),
+                uri: child_uri.clone(),
+                auto_rebuild: true,
+                rebuild_source: Some(unavailable.into()),
+            },
+            None,
+        )
+        .await
+        .expect("The child should be added despite the unavailable rebuild source");
+
+    let mut record = None;
+    for _ in 0 .. 40 {
+        let history = nexus_client
+            .get_nexus_rebuild_history(&GetRebuildHistory::new(&nexus.uuid), None)
+            .await
+            .unwrap();
+        record = history
+            .records
+            .into_iter()
+            .find(|r| r.child_uri == child_uri && r.state == RebuildJobState::Completed);
+        if record.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    let record = record.expect("The rebuild should have completed");
+    tracing::info!("Rebuild: {:?}", record);
+    assert_eq!(record.src_uri, nexus.children[0].uri);
+
+    nexus_client
+        .destroy(
+            &DestroyNexus {
+                node: nexus_node.clone(),
+                uuid: nexus.uuid.clone(),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    rep_client
+        .destroy(&DestroyReplica::from(replica), None)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn nexus_share_transaction() {
     let cluster = ClusterBuilder::builder()
@@ -441,6 +728,7 @@ async fn nexus_child_transaction() {
         nexus: nexus.uuid.clone(),
         uri: child2.into(),
         auto_rebuild: true,
+        rebuild_source: None,
     };
     let rm_child = RemoveNexusChild {
         node: io_engine.clone(),
@@ -567,6 +855,7 @@ async fn nexus_child_transaction_store() {
         nexus: nexus.uuid.clone(),
         uri: child2.into(),
         auto_rebuild: true,
+        rebuild_source: None,
     };
     nexus_child_op_transaction_store(
         &nexus,
@@ -593,3 +882,127 @@ async fn nexus_child_transaction_store() {
     )
     .await;
 }
+
+/// Tests the registry operations which address the pending operation of a nexus through a
+/// `ResourceRef`
+#[tokio::test]
+async fn nexus_pending_operation() {
+    let grpc_timeout = Duration::from_secs(2);
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_pools(1)
+        .with_agents(vec!["core"])
+        .with_req_timeouts(grpc_timeout, grpc_timeout)
+        .with_bus_timeouts(bus_timeout_opts())
+        .build()
+        .await
+        .unwrap();
+    let io_engine = cluster.node(0);
+    let registry_client = cluster.grpc_client().registry();
+    let nexus_client = cluster.grpc_client().nexus();
+
+    let local = format!("malloc:///local?size_mb=12&uuid={}", ReplicaId::new()).into();
+    let nexus = nexus_client
+        .create(
+            &CreateNexus {
+                node: io_engine.clone(),
+                uuid: NexusId::new(),
+                size: 5242880,
+                children: vec![local],
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let nexus_ref: ResourceRef = format!("nexus/{}/{}", io_engine, nexus.uuid)
+        .parse()
+        .unwrap();
+    let node_ref: ResourceRef = format!("node/{}", io_engine).parse().unwrap();
+
+    let operations = registry_client
+        .get_operations(&GetOperations::new(None), None)
+        .await
+        .unwrap();
+    assert!(operations.entries.is_empty(), "{:?}", operations);
+    // clearing a resource without a pending operation is a no-op
+    registry_client
+        .clear_operation(&ClearOperation::new(nexus_ref.clone()), None)
+        .await
+        .unwrap();
+
+    // the share can't complete whilst the io-engine is paused
+    cluster.composer().pause(io_engine.as_str()).await.unwrap();
+    let share = ShareNexus::from((&nexus, None, NexusShareProtocol::Nvmf));
+    nexus_client
+        .share(&share, None)
+        .await
+        .expect_err("io_engine down");
+
+    for resource in vec![None, Some(nexus_ref.clone()), Some(node_ref.clone())] {
+        let operations = registry_client
+            .get_operations(&GetOperations::new(resource), None)
+            .await
+            .unwrap();
+        assert_eq!(operations.entries.len(), 1, "{:?}", operations);
+        assert_eq!(operations.entries[0].resource, nexus_ref);
+        assert_eq!(operations.entries[0].result, None);
+    }
+    // the operation is still in progress so it can't be cleared
+    let error = registry_client
+        .clear_operation(&ClearOperation::new(nexus_ref.clone()), None)
+        .await
+        .expect_err("operation in progress");
+    assert_eq!(error.kind, ReplyErrorKind::Conflict);
+
+    // once the io-engine is back the share completes
+    cluster.composer().thaw(io_engine.as_str()).await.unwrap();
+    let mut operations = None;
+    for _ in 0 .. 20 {
+        let pending = registry_client
+            .get_operations(&GetOperations::new(Some(nexus_ref.clone())), None)
+            .await
+            .unwrap();
+        if pending.entries.is_empty() {
+            operations = Some(pending);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    assert!(operations.is_some(), "The share should have completed");
+    assert_eq!(
+        nexus_spec(&nexus, &registry_client).await.unwrap().share,
+        Protocol::Nvmf
+    );
+
+    registry_client
+        .reconcile_resource(&ReconcileResource::new(nexus_ref), None)
+        .await
+        .unwrap();
+
+    // nodes have no operations to clear
+    let error = registry_client
+        .clear_operation(&ClearOperation::new(node_ref), None)
+        .await
+        .expect_err("nodes have no operations");
+    assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+
+    // resources which don't exist, or which aren't on the referenced node, are not found
+    let unknown: ResourceRef = format!("volume/{}", VolumeId::new()).parse().unwrap();
+    let error = registry_client
+        .reconcile_resource(&ReconcileResource::new(unknown), None)
+        .await
+        .expect_err("unknown volume");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+    let elsewhere: ResourceRef = format!("nexus/elsewhere/{}", nexus.uuid).parse().unwrap();
+    let error = registry_client
+        .get_operations(&GetOperations::new(Some(elsewhere)), None)
+        .await
+        .expect_err("nexus is not on the node");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+
+    nexus_client
+        .destroy(&DestroyNexus::from(nexus), None)
+        .await
+        .unwrap();
+}
@@ -30,21 +30,30 @@ pub(crate) async fn configure(builder: Service) -> Service {
 async fn create_node_service(builder: &Service) -> service::Service {
     let registry = builder.get_shared_state::<Registry>().clone();
     let deadline = CliArgs::args().deadline.into();
+    let offline_misses = CliArgs::args().offline_misses;
     let request = CliArgs::args().request_timeout.into();
     let connect = CliArgs::args().connect_timeout.into();
 
-    service::Service::new(registry.clone(), deadline, request, connect).await
+    service::Service::new(registry.clone(), deadline, offline_misses, request, connect).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use common_lib::types::v0::{
-        message_bus::{Filter, Node, NodeId, NodeState, NodeStatus},
-        store::node::{NodeLabels, NodeSpec},
+        message_bus::{
+            BlockDevice, CordonNode, CreatePool, DrainNode, Filter, Node, NodeId, NodeState,
+            NodeStatus, SetNodeTimeouts, UncordonNode,
+        },
+        store::{
+            node::{NodeLabels, NodeSpec, NodeTimeouts},
+            pool::PoolSpec,
+        },
     };
     use deployer_cluster::ClusterBuilder;
-    use grpc::operations::node::traits::NodeOperations;
+    use grpc::operations::{
+        blockdevice::traits::BlockDeviceOperations, node::traits::NodeOperations,
+    };
     use std::time::Duration;
 
     /// Get new `Node` from the given parameters
@@ -135,6 +144,312 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn node_offline_misses() {
+        let cluster = ClusterBuilder::builder()
+            .with_rest(false)
+            .with_agents(vec!["core"])
+            .with_node_deadline("2s")
+            .with_node_offline_misses(3)
+            .build()
+            .await
+            .unwrap();
+
+        let node_client = cluster.grpc_client().node();
+        let node_id = cluster.node(0);
+        let (node_client, node_id) = (&node_client, &node_id);
+        // wait for the node status to change from `current`, returning the new status
+        let status_change = move |current: NodeStatus| async move {
+            for _ in 0 .. 40 {
+                let nodes = node_client
+                    .get(Filter::Node(node_id.clone()), None)
+                    .await
+                    .unwrap();
+                let status = nodes.0[0].state().unwrap().status.clone();
+                if status != current {
+                    return status;
+                }
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+            panic!("The node status did not change from {:?}", current);
+        };
+
+        // a node which misses a deadline is suspect at first
+        cluster.composer().pause(node_id.as_str()).await.unwrap();
+        assert_eq!(status_change(NodeStatus::Online).await, NodeStatus::Suspect);
+        // and it's online again as soon as it's back
+        cluster.composer().thaw(node_id.as_str()).await.unwrap();
+        assert_eq!(status_change(NodeStatus::Suspect).await, NodeStatus::Online);
+
+        // the misses are counted afresh, until the node misses all of the allowed deadlines
+        cluster.composer().pause(node_id.as_str()).await.unwrap();
+        assert_eq!(status_change(NodeStatus::Online).await, NodeStatus::Suspect);
+        let suspect = std::time::Instant::now();
+        let status = status_change(NodeStatus::Suspect).await;
+        let elapsed = suspect.elapsed();
+        cluster.composer().thaw(node_id.as_str()).await.unwrap();
+        assert_eq!(status, NodeStatus::Offline);
+        assert!(elapsed >= Duration::from_secs(3), "{:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn block_devices() {
+        let cluster = ClusterBuilder::builder()
+            .with_rest(false)
+            .with_agents(vec!["core"])
+            .build()
+            .await
+            .unwrap();
+
+        let blockdevice_client = cluster.grpc_client().blockdevice();
+        let request = GetBlockDevices {
+            node: cluster.node(0),
+            all: true,
+        };
+        let devices = blockdevice_client
+            .get(&request, None)
+            .await
+            .unwrap()
+            .into_inner();
+        tracing::info!("BlockDevices: {:?}", devices);
+
+        // the devices are the ones listed by the io-engine itself
+        let mut rpc_handle = cluster.grpc_handle(cluster.node(0).as_str()).await.unwrap();
+        let io_engine_devices = rpc_handle
+            .io_engine
+            .list_block_devices(rpc::io_engine::ListBlockDevicesRequest { all: true })
+            .await
+            .unwrap()
+            .into_inner()
+            .devices;
+        assert_eq!(
+            devices.iter().map(|d| &d.devname).collect::<Vec<_>>(),
+            io_engine_devices
+                .iter()
+                .map(|d| &d.devname)
+                .collect::<Vec<_>>()
+        );
+        // the node service returns the very same devices
+        let node_devices = cluster
+            .grpc_client()
+            .node()
+            .get_block_devices(&request, None)
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(devices, node_devices);
+
+        let request = GetBlockDevices {
+            node: "unknown".into(),
+            all: true,
+        };
+        let error = blockdevice_client
+            .get(&request, None)
+            .await
+            .expect_err("node does not exist");
+        assert_eq!(error.kind, ReplyErrorKind::NotFound);
+    }
+
+    #[test]
+    fn claimed_block_devices() {
+        let node = NodeId::from("node-1");
+        let device = |name: &str| BlockDevice {
+            devname: format!("/dev/{}", name),
+            devlinks: vec![format!("/dev/disk/by-id/{}", name)],
+            available: true,
+            ..Default::default()
+        };
+        let mut devices = vec![device("sda"), device("sdb")];
+        let pools = vec![
+            PoolSpec::from(&CreatePool {
+                node: node.clone(),
+                id: "pool-1".into(),
+                disks: vec!["aio:///dev/disk/by-id/sda".into()],
+                ..Default::default()
+            }),
+            // a pool on another node which uses a device of the same name
+            PoolSpec::from(&CreatePool {
+                node: "node-2".into(),
+                id: "pool-2".into(),
+                disks: vec!["/dev/sdb".into()],
+                ..Default::default()
+            }),
+        ];
+
+        service::claim_block_devices(&mut devices, &node, &pools);
+        assert_eq!(devices[0].claimed_by, Some("pool-1".into()));
+        assert_eq!(devices[1].claimed_by, None);
+    }
+
+    #[test]
+    fn node_comms_timeouts() {
+        let defaults =
+            service::NodeCommsTimeout::new(Duration::from_secs(1), Duration::from_secs(5));
+        let timeouts = defaults.with_overrides(&NodeTimeouts::default());
+        assert_eq!(timeouts.connect(), Duration::from_secs(1));
+        assert_eq!(timeouts.request(), Duration::from_secs(5));
+
+        let timeouts =
+            defaults.with_overrides(&NodeTimeouts::new(None, Some(Duration::from_secs(30))));
+        assert_eq!(timeouts.connect(), Duration::from_secs(1));
+        assert_eq!(timeouts.request(), Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn node_timeouts() {
+        let cluster = ClusterBuilder::builder()
+            .with_rest(false)
+            .with_agents(vec!["core"])
+            .with_req_timeouts(Duration::from_secs(5), Duration::from_secs(5))
+            .with_store_timeout(Duration::from_millis(250))
+            .build()
+            .await
+            .unwrap();
+
+        let node_client = cluster.grpc_client().node();
+        let blockdevice_client = cluster.grpc_client().blockdevice();
+        let node_id = cluster.node(0);
+        let request = GetBlockDevices {
+            node: node_id.clone(),
+            all: true,
+        };
+
+        let timeouts = NodeTimeouts::new(
+            Some(Duration::from_millis(500)),
+            Some(Duration::from_millis(500)),
+        );
+        let node = node_client
+            .set_timeouts(
+                &SetNodeTimeouts::new(node_id.clone(), timeouts.clone()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(node.spec().unwrap().timeouts(), &timeouts);
+
+        // the unresponsive node now times out well before the default timeouts elapse
+        cluster.composer().pause(node_id.as_str()).await.unwrap();
+        let start = std::time::Instant::now();
+        let result = blockdevice_client.get(&request, None).await;
+        let elapsed = start.elapsed();
+        cluster.composer().thaw(node_id.as_str()).await.unwrap();
+        result.expect_err("the node is paused");
+        assert!(elapsed < Duration::from_secs(3), "{:?}", elapsed);
+
+        // removing the overrides falls back to the defaults
+        let node = node_client
+            .set_timeouts(
+                &SetNodeTimeouts::new(node_id.clone(), NodeTimeouts::default()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(node.spec().unwrap().timeouts(), &NodeTimeouts::default());
+        blockdevice_client.get(&request, None).await.unwrap();
+
+        // timeouts which can't be stored are not applied either
+        cluster.composer().pause("etcd").await.unwrap();
+        let result = node_client
+            .set_timeouts(&SetNodeTimeouts::new(node_id.clone(), timeouts), None)
+            .await;
+        cluster.composer().thaw("etcd").await.unwrap();
+        result.expect_err("the store is paused");
+        let nodes = node_client
+            .get(Filter::Node(node_id.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            nodes.0[0].spec().unwrap().timeouts(),
+            &NodeTimeouts::default()
+        );
+
+        let error = node_client
+            .set_timeouts(
+                &SetNodeTimeouts::new(node_id, NodeTimeouts::new(Some(Duration::ZERO), None)),
+                None,
+            )
+            .await
+            .expect_err("the timeouts must not be zero");
+        assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn cordon_store_failure() {
+        let cluster = ClusterBuilder::builder()
+            .with_rest(false)
+            .with_agents(vec!["core"])
+            .with_store_timeout(Duration::from_millis(250))
+            .build()
+            .await
+            .unwrap();
+
+        let node_client = cluster.grpc_client().node();
+        let node_id = cluster.node(0);
+        let (node_client, node_id) = (&node_client, &node_id);
+        let cordoned = move || async move {
+            let nodes = node_client
+                .get(Filter::Node(node_id.clone()), None)
+                .await
+                .unwrap();
+            nodes.0[0].spec().unwrap().cordoned()
+        };
+        let cordon = CordonNode::new(node_id.clone(), "maintenance".to_string());
+        let uncordon = UncordonNode::new(node_id.clone(), "maintenance".to_string());
+
+        // the cordon can't be stored, and so it's not applied either
+        cluster.composer().pause("etcd").await.unwrap();
+        let result = node_client.cordon(&cordon, None).await;
+        cluster.composer().thaw("etcd").await.unwrap();
+        result.expect_err("the store is paused");
+        assert!(!cordoned().await);
+
+        node_client.cordon(&cordon, None).await.unwrap();
+        assert!(cordoned().await);
+
+        // and likewise for the uncordon
+        cluster.composer().pause("etcd").await.unwrap();
+        let result = node_client.uncordon(&uncordon, None).await;
+        cluster.composer().thaw("etcd").await.unwrap();
+        result.expect_err("the store is paused");
+        assert!(cordoned().await);
+
+        node_client.uncordon(&uncordon, None).await.unwrap();
+        assert!(!cordoned().await);
+    }
+
+    #[tokio::test]
+    async fn drain_store_failure() {
+        let cluster = ClusterBuilder::builder()
+            .with_rest(false)
+            .with_agents(vec!["core"])
+            .with_store_timeout(Duration::from_millis(250))
+            .build()
+            .await
+            .unwrap();
+
+        let node_client = cluster.grpc_client().node();
+        let node_id = cluster.node(0);
+        let drain = DrainNode::new(node_id.clone(), "drain".to_string());
+
+        // the drain can't be stored, and so the node is neither cordoned nor draining
+        cluster.composer().pause("etcd").await.unwrap();
+        let result = node_client.drain(&drain, None).await;
+        cluster.composer().thaw("etcd").await.unwrap();
+        result.expect_err("the store is paused");
+        let nodes = node_client
+            .get(Filter::Node(node_id.clone()), None)
+            .await
+            .unwrap();
+        let spec = nodes.0[0].spec().unwrap();
+        assert!(!spec.cordoned());
+        assert!(!spec.draining());
+
+        let node = node_client.drain(&drain, None).await.unwrap();
+        let spec = node.spec().unwrap();
+        assert!(spec.cordoned());
+        assert!(spec.drain_state().is_some());
+    }
+
     #[tokio::test]
     async fn large_cluster() {
         let expected_nodes = 2;
@@ -1,21 +1,28 @@
 use super::*;
 use crate::core::{
-    reconciler::PollTriggerEvent, registry::Registry, specs::ResourceSpecsLocked,
+    audit, reconciler::PollTriggerEvent, registry::Registry, specs::ResourceSpecsLocked,
     wrapper::NodeWrapper,
 };
 use common::{
     errors::{GrpcRequestError, SvcError},
     v0::msg_translation::RpcToMessageBus,
 };
-use common_lib::types::v0::message_bus::{
-    Deregister, Filter, Node, NodeId, NodeState, NodeStatus, Register, States,
+use common_lib::types::v0::{
+    message_bus::{
+        BlockDevice, CordonNode, Deregister, DrainNode, Filter, Node, NodeId, NodeState,
+        NodeStatus, Register, SetNodeTimeouts, States, UncordonNode,
+    },
+    store::{node::NodeTimeouts, pool::PoolSpec},
 };
 
 use crate::core::wrapper::InternalOps;
 use grpc::{
     context::Context,
     operations::{
-        node::traits::{GetBlockDeviceInfo, NodeOperations},
+        blockdevice::traits::GetBlockDeviceInfo,
+        node::traits::{
+            CordonNodeInfo, DrainNodeInfo, NodeOperations, SetNodeTimeoutsInfo, UncordonNodeInfo,
+        },
         registration::traits::{DeregisterInfo, RegisterInfo, RegistrationOperations},
     },
 };
@@ -29,6 +36,8 @@ pub(crate) struct Service {
     registry: Registry,
     /// deadline for receiving keepalive/Register messages
     deadline: std::time::Duration,
+    /// number of consecutive missed deadlines before a node is deemed offline
+    offline_misses: u32,
     /// node communication timeouts
     comms_timeouts: NodeCommsTimeout,
 }
@@ -56,6 +65,13 @@ impl NodeCommsTimeout {
     pub fn request(&self) -> std::time::Duration {
         self.request
     }
+    /// return a new `Self` with the timeouts overridden by the node specific `timeouts`
+    pub(crate) fn with_overrides(&self, timeouts: &NodeTimeouts) -> Self {
+        Self {
+            connect: timeouts.connect.unwrap_or(self.connect),
+            request: timeouts.request.unwrap_or(self.request),
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -66,7 +82,26 @@ impl NodeOperations for Service {
         Ok(nodes)
     }
     async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
-        return Ok(true);
+        // we're only ready while we hold the persistent store lease, as otherwise another
+        // instance may have taken over
+        match self.registry.store_lease_status().await {
+            Some(status) if !status.held => {
+                tracing::warn!(
+                    lease.id = status.lease_id,
+                    "Not ready as the persistent store lease is not held"
+                );
+                Ok(false)
+            }
+            Some(status) => {
+                tracing::trace!(
+                    lease.id = status.lease_id,
+                    lease.remaining_ttl = ?status.remaining_ttl,
+                    "Persistent store lease is held"
+                );
+                Ok(true)
+            }
+            None => Ok(true),
+        }
     }
 
     async fn get_block_devices(
@@ -78,6 +113,55 @@ impl NodeOperations for Service {
         let blockdevices = self.get_block_devices(&req).await?;
         Ok(blockdevices)
     }
+
+    async fn cordon(
+        &self,
+        cordon: &dyn CordonNodeInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = cordon.into();
+        let service = self.clone();
+        let node =
+            Context::spawn(async move { audit::grpc(&req, service.cordon(&req).await) }).await??;
+        Ok(node)
+    }
+
+    async fn uncordon(
+        &self,
+        uncordon: &dyn UncordonNodeInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = uncordon.into();
+        let service = self.clone();
+        let node = Context::spawn(async move { audit::grpc(&req, service.uncordon(&req).await) })
+            .await??;
+        Ok(node)
+    }
+
+    async fn drain(
+        &self,
+        drain: &dyn DrainNodeInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = drain.into();
+        let service = self.clone();
+        let node =
+            Context::spawn(async move { audit::grpc(&req, service.drain(&req).await) }).await??;
+        Ok(node)
+    }
+
+    async fn set_timeouts(
+        &self,
+        set_timeouts: &dyn SetNodeTimeoutsInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = set_timeouts.into();
+        let service = self.clone();
+        let node =
+            Context::spawn(async move { audit::grpc(&req, service.set_timeouts(&req).await) })
+                .await??;
+        Ok(node)
+    }
 }
 
 #[tonic::async_trait]
@@ -99,16 +183,19 @@ impl RegistrationOperations for Service {
 
 impl Service {
     /// New Node Service which uses the `registry` as its node cache and sets
-    /// the `deadline` to each node's watchdog
+    /// the `deadline` to each node's watchdog, deeming a node offline once it has missed
+    /// `offline_misses` consecutive deadlines
     pub(super) async fn new(
         registry: Registry,
         deadline: std::time::Duration,
+        offline_misses: u32,
         request: std::time::Duration,
         connect: std::time::Duration,
     ) -> Self {
         let service = Self {
             registry,
             deadline,
+            offline_misses,
             comms_timeouts: NodeCommsTimeout::new(connect, request),
         };
         // attempt to reload the node state based on the specification
@@ -165,8 +252,12 @@ impl Service {
         let node = nodes.write().await.get_mut(&node_state.id).cloned();
         let send_event = match node {
             None => {
-                let mut node =
-                    NodeWrapper::new(&node_state, self.deadline, self.comms_timeouts.clone());
+                let mut node = NodeWrapper::new(
+                    &node_state,
+                    self.deadline,
+                    self.offline_misses,
+                    self.node_comms_timeouts(&node_state.id),
+                );
 
                 let mut result = node.liveness_probe().await;
                 if result.is_ok() {
@@ -267,6 +358,66 @@ impl Service {
         }
     }
 
+    /// Get the node with the given id
+    async fn get_node(&self, node_id: &NodeId) -> Result<Node, SvcError> {
+        let nodes = self
+            .get_nodes(&GetNodes::new(Filter::Node(node_id.clone())))
+            .await?;
+        nodes
+            .into_inner()
+            .into_iter()
+            .next()
+            .ok_or_else(|| SvcError::NodeNotFound {
+                node_id: node_id.clone(),
+            })
+    }
+
+    /// Cordon the node, so that no new resources are placed on it
+    pub(crate) async fn cordon(&self, request: &CordonNode) -> Result<Node, SvcError> {
+        self.specs()
+            .cordon_node(&self.registry, &request.id, request.label.clone())
+            .await?;
+        self.get_node(&request.id).await
+    }
+
+    /// Remove a cordon from the node
+    pub(crate) async fn uncordon(&self, request: &UncordonNode) -> Result<Node, SvcError> {
+        self.specs()
+            .uncordon_node(&self.registry, &request.id, &request.label)
+            .await?;
+        self.get_node(&request.id).await
+    }
+
+    /// Drain the node: cordon it and then let the reconciler move its volume replicas away
+    pub(crate) async fn drain(&self, request: &DrainNode) -> Result<Node, SvcError> {
+        self.specs()
+            .drain_node(&self.registry, &request.id, request.label.clone())
+            .await?;
+        self.registry.notify(PollTriggerEvent::NodeDrain).await;
+        self.get_node(&request.id).await
+    }
+
+    /// Set the communication timeouts of the node, which take effect on its next connection
+    pub(crate) async fn set_timeouts(&self, request: &SetNodeTimeouts) -> Result<Node, SvcError> {
+        self.specs()
+            .set_node_timeouts(&self.registry, &request.id, request.timeouts.clone())
+            .await?;
+        if let Ok(node) = self.registry.get_node_wrapper(&request.id).await {
+            node.write()
+                .await
+                .set_comms_timeouts(self.node_comms_timeouts(&request.id));
+        }
+        self.get_node(&request.id).await
+    }
+
+    /// Get the communication timeouts of the node: the defaults with the node's overrides
+    fn node_comms_timeouts(&self, node_id: &NodeId) -> NodeCommsTimeout {
+        match self.specs().get_node(node_id) {
+            Ok(node) => self.comms_timeouts.with_overrides(node.timeouts()),
+            Err(_) => self.comms_timeouts.clone(),
+        }
+    }
+
     /// Get block devices from a node
     pub(crate) async fn get_block_devices(
         &self,
@@ -289,11 +440,12 @@ impl Service {
             })?
             .into_inner();
 
-        let bdevs = response
+        let mut bdevs = response
             .devices
             .iter()
             .map(|rpc_bdev| rpc_bdev.to_mbus())
-            .collect();
+            .collect::<Vec<_>>();
+        claim_block_devices(&mut bdevs, &request.node, &self.specs().get_pools());
         Ok(BlockDevices(bdevs))
     }
 
@@ -319,3 +471,18 @@ impl Service {
         })
     }
 }
+
+/// Mark the block devices of the `node` which are used by any of the given pools as claimed by
+/// that pool.
+pub(super) fn claim_block_devices(devices: &mut [BlockDevice], node: &NodeId, pools: &[PoolSpec]) {
+    let node_pools = pools
+        .iter()
+        .filter(|pool| &pool.node == node)
+        .collect::<Vec<_>>();
+    for device in devices {
+        device.claimed_by = node_pools
+            .iter()
+            .find(|pool| pool.disks.iter().any(|disk| device.matches_disk(disk)))
+            .map(|pool| pool.id.clone());
+    }
+}
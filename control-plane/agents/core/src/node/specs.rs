@@ -1,12 +1,12 @@
 use crate::core::{registry::Registry, specs::ResourceSpecsLocked};
 use common::errors::{NodeNotFound, SvcError};
 use common_lib::types::v0::{
-    message_bus::{NodeId, Register},
-    store::node::{NodeLabels, NodeSpec},
+    message_bus::{NodeId, Register, VolumeId},
+    store::node::{NodeDrainState, NodeLabels, NodeSpec, NodeTimeouts, ReplicaMove},
 };
 use parking_lot::Mutex;
 use snafu::OptionExt;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 impl ResourceSpecsLocked {
     /// Create a node spec for the register request
@@ -42,6 +42,158 @@ impl ResourceSpecsLocked {
         Ok(node)
     }
 
+    /// Cordon the node with the given label, so that no new resources are placed on it.
+    /// Existing resources on the node are left untouched.
+    pub(crate) async fn cordon_node(
+        &self,
+        registry: &Registry,
+        node_id: &NodeId,
+        label: String,
+    ) -> Result<NodeSpec, SvcError> {
+        self.update_node(registry, node_id, |node| node.cordon(label.clone()))
+            .await
+    }
+
+    /// Remove the cordon with the given label from the node
+    pub(crate) async fn uncordon_node(
+        &self,
+        registry: &Registry,
+        node_id: &NodeId,
+        label: &str,
+    ) -> Result<NodeSpec, SvcError> {
+        self.update_node(registry, node_id, |node| node.uncordon(label))
+            .await
+    }
+
+    /// Drain the node: cordon it with the given label and mark it as draining, so that the
+    /// reconciler moves its volume replicas away.
+    /// The drain state is persisted, so the drain resumes after a restart.
+    pub(crate) async fn drain_node(
+        &self,
+        registry: &Registry,
+        node_id: &NodeId,
+        label: String,
+    ) -> Result<NodeSpec, SvcError> {
+        self.update_node(registry, node_id, |node| {
+            let mut changed = node.cordon(label.clone());
+            if !node.draining() {
+                node.set_drain_state(Some(NodeDrainState::Draining));
+                changed = true;
+            }
+            changed
+        })
+        .await
+    }
+
+    /// Mark the draining node as drained, once all of its volume replicas have been moved
+    pub(crate) async fn set_node_drained(
+        &self,
+        registry: &Registry,
+        node_id: &NodeId,
+    ) -> Result<NodeSpec, SvcError> {
+        self.update_node(registry, node_id, |node| {
+            let changed = node.draining();
+            if changed {
+                node.set_drain_state(Some(NodeDrainState::Drained));
+            }
+            changed
+        })
+        .await
+    }
+
+    /// Set the communication timeouts of the node, which override the defaults
+    pub(crate) async fn set_node_timeouts(
+        &self,
+        registry: &Registry,
+        node_id: &NodeId,
+        timeouts: NodeTimeouts,
+    ) -> Result<NodeSpec, SvcError> {
+        if [timeouts.connect, timeouts.request].contains(&Some(Duration::ZERO)) {
+            return Err(SvcError::InvalidArguments {});
+        }
+        self.update_node(registry, node_id, |node| {
+            node.set_timeouts(timeouts.clone())
+        })
+        .await
+    }
+
+    /// Update the node spec with the given `update`, which returns whether it changed the spec.
+    /// A changed spec is stored before the update is committed to the node spec, so that a
+    /// failure to store it leaves the node spec unchanged.
+    async fn update_node(
+        &self,
+        registry: &Registry,
+        node_id: &NodeId,
+        update: impl Fn(&mut NodeSpec) -> bool,
+    ) -> Result<NodeSpec, SvcError> {
+        let node = self.get_locked_node(node_id)?;
+        let mut node_clone = node.lock().clone();
+        if !update(&mut node_clone) {
+            return Ok(node_clone);
+        }
+        registry.store_obj(&node_clone).await?;
+        let mut node = node.lock();
+        update(&mut node);
+        Ok(node.clone())
+    }
+
+    /// Get all the replica moves in progress, from all nodes
+    pub(crate) fn get_replica_moves(&self) -> Vec<ReplicaMove> {
+        self.get_locked_nodes()
+            .into_iter()
+            .flat_map(|node| node.lock().replica_moves().clone())
+            .collect()
+    }
+
+    /// Check if a replica of the given volume is being moved
+    pub(crate) fn replica_move_pending(&self, volume: &VolumeId) -> bool {
+        self.get_locked_nodes().into_iter().any(|node| {
+            node.lock()
+                .replica_moves()
+                .iter()
+                .any(|m| &m.volume == volume)
+        })
+    }
+
+    /// Register a replica move in progress with the node which the replica is moved away from.
+    /// The move is persisted, so it's completed even after a restart.
+    pub(crate) async fn add_replica_move(
+        &self,
+        registry: &Registry,
+        node_id: &NodeId,
+        replica_move: ReplicaMove,
+    ) -> Result<(), SvcError> {
+        let node = self.get_locked_node(node_id)?;
+        let mut node_clone = node.lock().clone();
+        node_clone.add_replica_move(replica_move.clone());
+        registry.store_obj(&node_clone).await?;
+        node.lock().add_replica_move(replica_move);
+        Ok(())
+    }
+
+    /// Remove the replica move in progress for the given volume
+    pub(crate) async fn remove_replica_move(
+        &self,
+        registry: &Registry,
+        volume: &VolumeId,
+    ) -> Result<(), SvcError> {
+        for node in self.get_locked_nodes() {
+            let mut node_clone = node.lock().clone();
+            if node_clone.remove_replica_move(volume) {
+                registry.store_obj(&node_clone).await?;
+                node.lock().remove_replica_move(volume);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the node is cordoned, ie: new resources must not be placed on it
+    pub(crate) fn node_cordoned(&self, node_id: &NodeId) -> bool {
+        self.get_node(node_id)
+            .map(|node| node.cordoned())
+            .unwrap_or_default()
+    }
+
     /// Get node spec by its `NodeId`
     pub(crate) fn get_locked_node(
         &self,
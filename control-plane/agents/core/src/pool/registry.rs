@@ -4,6 +4,7 @@ use crate::core::{
 };
 use common::errors::{self, SvcError, SvcError::PoolNotFound};
 use common_lib::types::v0::message_bus::{NodeId, Pool, PoolId, PoolState, Replica, ReplicaId};
+use grpc::operations::{PaginatedResult, Pagination};
 use snafu::OptionExt;
 
 /// Pool helpers
@@ -142,6 +143,16 @@ impl Registry {
         replicas
     }
 
+    /// Get a paginated subset of the replicas from all nodes, ordered by their uuid
+    pub(crate) async fn get_paginated_replicas(
+        &self,
+        pagination: &Pagination,
+    ) -> PaginatedResult<Replica> {
+        let mut replicas = self.get_replicas().await;
+        replicas.sort_by(|a, b| a.uuid.as_str().cmp(b.uuid.as_str()));
+        PaginatedResult::paginate(replicas, pagination)
+    }
+
     /// Get replica `replica_id`
     pub(crate) async fn get_replica(&self, replica_id: &ReplicaId) -> Result<Replica, SvcError> {
         let nodes = self.get_node_wrappers().await;
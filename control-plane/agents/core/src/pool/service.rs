@@ -1,4 +1,10 @@
-use crate::core::{registry::Registry, specs::ResourceSpecsLocked, wrapper::GetterOps};
+use crate::core::{
+    audit,
+    reconciler::PollTriggerEvent,
+    registry::Registry,
+    specs::ResourceSpecsLocked,
+    wrapper::{GetterOps, InternalOps},
+};
 use common::errors::{PoolNotFound, ReplicaNotFound, SvcError};
 use common_lib::{
     mbus_api::{
@@ -7,20 +13,25 @@ use common_lib::{
     },
     types::v0::{
         message_bus::{
-            CreatePool, CreateReplica, DestroyPool, DestroyReplica, Filter, GetPools, GetReplicas,
-            NodeId, Pool, PoolId, Replica, ShareReplica, UnshareReplica,
+            CreatePool, CreateReplica, DestroyPool, DestroyReplica, Filter, GetPools, GetReplica,
+            GetReplicas, ImportPool, MoveReplica, NodeId, Pool, PoolId, ReconcilePool, Replica,
+            ReplicaId, ResizeReplica, SetPoolMaintenance, ShareReplica, UnshareReplica,
         },
-        store::OperationMode,
+        store::{pool::PoolLabel, OperationMode},
     },
 };
 use grpc::{
     context::Context,
     operations::{
-        pool::traits::{CreatePoolInfo, DestroyPoolInfo, PoolOperations},
+        pool::traits::{
+            CreatePoolInfo, DestroyPoolInfo, ImportPoolInfo, PoolOperations, ReconcilePoolInfo,
+            SetPoolMaintenanceInfo,
+        },
         replica::traits::{
-            CreateReplicaInfo, DestroyReplicaInfo, ReplicaOperations, ShareReplicaInfo,
-            UnshareReplicaInfo,
+            CreateReplicaInfo, DestroyReplicaInfo, MoveReplicaInfo, ReplicaOperations,
+            ResizeReplicaInfo, ShareReplicaInfo, UnshareReplicaInfo,
         },
+        Pagination,
     },
 };
 use snafu::OptionExt;
@@ -39,7 +50,22 @@ impl PoolOperations for Service {
     ) -> Result<Pool, ReplyError> {
         let req = pool.into();
         let service = self.clone();
-        let pool = Context::spawn(async move { service.create_pool(&req).await }).await??;
+        let pool =
+            Context::spawn(async move { audit::grpc(&req, service.create_pool(&req).await) })
+                .await??;
+        Ok(pool)
+    }
+
+    async fn import(
+        &self,
+        pool: &dyn ImportPoolInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError> {
+        let req = pool.into();
+        let service = self.clone();
+        let pool =
+            Context::spawn(async move { audit::grpc(&req, service.import_pool(&req).await) })
+                .await??;
         Ok(pool)
     }
 
@@ -50,7 +76,8 @@ impl PoolOperations for Service {
     ) -> Result<(), ReplyError> {
         let req = pool.into();
         let service = self.clone();
-        Context::spawn(async move { service.destroy_pool(&req).await }).await??;
+        Context::spawn(async move { audit::grpc(&req, service.destroy_pool(&req).await) })
+            .await??;
         Ok(())
     }
 
@@ -59,6 +86,38 @@ impl PoolOperations for Service {
         let pools = self.get_pools(&req).await?;
         Ok(pools)
     }
+
+    async fn reconcile(
+        &self,
+        pool: &dyn ReconcilePoolInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError> {
+        let req = pool.into();
+        let service = self.clone();
+        let pool =
+            Context::spawn(async move { audit::grpc(&req, service.reconcile_pool(&req).await) })
+                .await??;
+        Ok(pool)
+    }
+
+    async fn set_maintenance(
+        &self,
+        pool: &dyn SetPoolMaintenanceInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError> {
+        let req = pool.into();
+        let service = self.clone();
+        let pool =
+            Context::spawn(
+                async move { audit::grpc(&req, service.set_pool_maintenance(&req).await) },
+            )
+            .await??;
+        Ok(pool)
+    }
+
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        return Ok(true);
+    }
 }
 
 #[tonic::async_trait]
@@ -70,17 +129,39 @@ impl ReplicaOperations for Service {
     ) -> Result<Replica, ReplyError> {
         let create_replica = req.into();
         let service = self.clone();
-        let replica =
-            Context::spawn(async move { service.create_replica(&create_replica).await }).await??;
+        let replica = Context::spawn(async move {
+            audit::grpc(
+                &create_replica,
+                service.create_replica(&create_replica).await,
+            )
+        })
+        .await??;
         Ok(replica)
     }
 
-    async fn get(&self, filter: Filter, _ctx: Option<Context>) -> Result<Replicas, ReplyError> {
+    async fn get(
+        &self,
+        filter: Filter,
+        pagination: Option<Pagination>,
+        _ctx: Option<Context>,
+    ) -> Result<Replicas, ReplyError> {
         let req = GetReplicas { filter };
-        let replicas = self.get_replicas(&req).await?;
+        let replicas = self.get_replicas(&req, pagination).await?;
         Ok(replicas)
     }
 
+    async fn get_one(
+        &self,
+        node: &NodeId,
+        pool: &PoolId,
+        uuid: &ReplicaId,
+        _ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError> {
+        let req = GetReplica::new(node, pool, uuid);
+        let replica = self.get_replica(&req).await?;
+        Ok(replica)
+    }
+
     async fn destroy(
         &self,
         req: &dyn DestroyReplicaInfo,
@@ -88,7 +169,13 @@ impl ReplicaOperations for Service {
     ) -> Result<(), ReplyError> {
         let destroy_replica = req.into();
         let service = self.clone();
-        Context::spawn(async move { service.destroy_replica(&destroy_replica).await }).await??;
+        Context::spawn(async move {
+            audit::grpc(
+                &destroy_replica,
+                service.destroy_replica(&destroy_replica).await,
+            )
+        })
+        .await??;
         Ok(())
     }
 
@@ -99,8 +186,10 @@ impl ReplicaOperations for Service {
     ) -> Result<String, ReplyError> {
         let share_replica = req.into();
         let service = self.clone();
-        let response =
-            Context::spawn(async move { service.share_replica(&share_replica).await }).await??;
+        let response = Context::spawn(async move {
+            audit::grpc(&share_replica, service.share_replica(&share_replica).await)
+        })
+        .await??;
         Ok(response)
     }
 
@@ -111,9 +200,50 @@ impl ReplicaOperations for Service {
     ) -> Result<(), ReplyError> {
         let unshare_replica = req.into();
         let service = self.clone();
-        Context::spawn(async move { service.unshare_replica(&unshare_replica).await }).await??;
+        Context::spawn(async move {
+            audit::grpc(
+                &unshare_replica,
+                service.unshare_replica(&unshare_replica).await,
+            )
+        })
+        .await??;
         Ok(())
     }
+
+    async fn resize(
+        &self,
+        req: &dyn ResizeReplicaInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError> {
+        let resize_replica = req.into();
+        let service = self.clone();
+        let replica = Context::spawn(async move {
+            audit::grpc(
+                &resize_replica,
+                service.resize_replica(&resize_replica).await,
+            )
+        })
+        .await??;
+        Ok(replica)
+    }
+
+    async fn move_replica(
+        &self,
+        req: &dyn MoveReplicaInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError> {
+        let move_replica = req.into();
+        let service = self.clone();
+        let replica = Context::spawn(async move {
+            audit::grpc(&move_replica, service.move_replica(&move_replica).await)
+        })
+        .await??;
+        Ok(replica)
+    }
+
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        return Ok(true);
+    }
 }
 
 impl Service {
@@ -139,6 +269,16 @@ impl Service {
                 tracing::Span::current().record("pool.uuid", &pool_id.as_str());
                 self.node_pools(None, Some(pool_id)).await
             }
+            Filter::PoolLabel(selector) => {
+                let pools = self.node_pools(None, None).await?;
+                Ok(Pools(
+                    pools
+                        .into_inner()
+                        .into_iter()
+                        .filter(|pool| labels_match(pool, &selector))
+                        .collect(),
+                ))
+            }
             _ => Err(SvcError::InvalidFilter { filter }),
         }
     }
@@ -163,15 +303,36 @@ impl Service {
             }
             None => self.registry.get_node_opt_pools(node_id).await?,
         };
+        let pools = pools
+            .into_iter()
+            .map(|pool| {
+                let forecast = self.registry.pool_forecast(pool.id());
+                pool.with_forecast(forecast)
+            })
+            .collect();
         Ok(Pools(pools))
     }
 
     /// Get replicas according to the filter
     #[tracing::instrument(level = "info", skip(self), err)]
-    pub(super) async fn get_replicas(&self, request: &GetReplicas) -> Result<Replicas, SvcError> {
+    pub(super) async fn get_replicas(
+        &self,
+        request: &GetReplicas,
+        pagination: Option<Pagination>,
+    ) -> Result<Replicas, SvcError> {
+        // The last result can only ever be false if using pagination.
+        let mut last_result = true;
+
         let filter = request.filter.clone();
-        match filter {
-            Filter::None => Ok(self.registry.get_replicas().await),
+        let replicas = match filter {
+            Filter::None => match &pagination {
+                Some(p) => {
+                    let paginated_replicas = self.registry.get_paginated_replicas(p).await;
+                    last_result = paginated_replicas.last();
+                    Ok(paginated_replicas.result())
+                }
+                None => Ok(self.registry.get_replicas().await),
+            },
             Filter::Node(node_id) => self.registry.get_node_replicas(&node_id).await,
             Filter::NodePool(node_id, pool_id) => {
                 let node = self.registry.get_node_wrapper(&node_id).await?;
@@ -185,16 +346,9 @@ impl Service {
                 let pool_wrapper = self.registry.get_node_pool_wrapper(pool_id).await?;
                 Ok(pool_wrapper.replicas())
             }
-            Filter::NodePoolReplica(node_id, pool_id, replica_id) => {
-                let node = self.registry.get_node_wrapper(&node_id).await?;
-                let pool_wrapper = node
-                    .pool_wrapper(&pool_id)
-                    .await
-                    .context(PoolNotFound { pool_id })?;
-                let replica = pool_wrapper
-                    .replica(&replica_id)
-                    .context(ReplicaNotFound { replica_id })?;
-                Ok(vec![replica.clone()])
+            Filter::NodePoolReplica(node, pool, uuid) => {
+                let replica = self.get_replica(&GetReplica { node, pool, uuid }).await?;
+                Ok(vec![replica])
             }
             Filter::NodeReplica(node_id, replica_id) => {
                 let node = self.registry.get_node_wrapper(&node_id).await?;
@@ -231,8 +385,33 @@ impl Service {
                 Ok(replicas)
             }
             _ => Err(SvcError::InvalidFilter { filter }),
-        }
-        .map(Replicas)
+        }?;
+
+        Ok(Replicas {
+            entries: replicas,
+            next_token: match last_result {
+                true => None,
+                false => pagination.map(|p| p.starting_token() + p.max_entries()),
+            },
+        })
+    }
+
+    /// Get a single replica from a specific node and pool
+    #[tracing::instrument(level = "info", skip(self), err, fields(replica.uuid = %request.uuid))]
+    pub(super) async fn get_replica(&self, request: &GetReplica) -> Result<Replica, SvcError> {
+        let node = self.registry.get_node_wrapper(&request.node).await?;
+        let pool_wrapper = node
+            .pool_wrapper(&request.pool)
+            .await
+            .context(PoolNotFound {
+                pool_id: request.pool.clone(),
+            })?;
+        let replica = pool_wrapper
+            .replica(&request.uuid)
+            .context(ReplicaNotFound {
+                replica_id: request.uuid.clone(),
+            })?;
+        Ok(replica.clone())
     }
 
     /// Create pool
@@ -243,6 +422,14 @@ impl Service {
             .await
     }
 
+    /// Import pool
+    #[tracing::instrument(level = "info", skip(self), err, fields(pool.uuid = %request.id))]
+    pub(super) async fn import_pool(&self, request: &ImportPool) -> Result<Pool, SvcError> {
+        self.specs()
+            .import_pool(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
     /// Destroy pool
     #[tracing::instrument(level = "info", skip(self), err, fields(pool.uuid = %request.id))]
     pub(super) async fn destroy_pool(&self, request: &DestroyPool) -> Result<(), SvcError> {
@@ -251,6 +438,44 @@ impl Service {
             .await
     }
 
+    /// Reconcile pool
+    /// Forces an immediate re-check of the pool state with the io-engine, bypassing the cache
+    /// period. If the node is not online it's probed and brought back online if it responds.
+    #[tracing::instrument(level = "info", skip(self), err, fields(pool.uuid = %request.id))]
+    pub(super) async fn reconcile_pool(&self, request: &ReconcilePool) -> Result<Pool, SvcError> {
+        let node_id = match self.specs().get_pool(&request.id) {
+            Ok(spec) => spec.node,
+            Err(error) => match self.registry.get_pool_state(&request.id).await {
+                Ok(state) => state.node,
+                Err(_) => return Err(error),
+            },
+        };
+        let node = self.registry.get_node_wrapper(&node_id).await?;
+        let setting_online = !node.read().await.is_online();
+        if setting_online {
+            node.write().await.liveness_probe().await?;
+        }
+        node.update_all(setting_online).await?;
+        if setting_online {
+            self.registry
+                .notify(PollTriggerEvent::NodeStateChangeOnline)
+                .await;
+        }
+        self.registry.get_pool(&request.id).await
+    }
+
+    /// Set or clear the pool maintenance flag
+    #[tracing::instrument(level = "info", skip(self), err, fields(pool.uuid = %request.id))]
+    pub(super) async fn set_pool_maintenance(
+        &self,
+        request: &SetPoolMaintenance,
+    ) -> Result<Pool, SvcError> {
+        self.specs()
+            .set_pool_maintenance(&self.registry, request, OperationMode::Exclusive)
+            .await?;
+        self.registry.get_pool(&request.id).await
+    }
+
     /// Create replica
     #[tracing::instrument(level = "info", skip(self), err, fields(replica.uuid = %request.uuid))]
     pub(super) async fn create_replica(
@@ -265,8 +490,19 @@ impl Service {
     /// Destroy replica
     #[tracing::instrument(level = "info", skip(self), err, fields(replica.uuid = %request.uuid))]
     pub(super) async fn destroy_replica(&self, request: &DestroyReplica) -> Result<(), SvcError> {
+        if request.force {
+            tracing::warn!(
+                replica.uuid = %request.uuid,
+                "Force destroying the replica, disowning it from all of its owners"
+            );
+        }
         self.specs()
-            .destroy_replica(&self.registry, request, false, OperationMode::Exclusive)
+            .destroy_replica(
+                &self.registry,
+                request,
+                request.force,
+                OperationMode::Exclusive,
+            )
             .await
     }
 
@@ -286,4 +522,33 @@ impl Service {
             .await?;
         Ok(())
     }
+
+    /// Resize replica
+    #[tracing::instrument(level = "info", skip(self), err, fields(replica.uuid = %request.uuid))]
+    pub(super) async fn resize_replica(
+        &self,
+        request: &ResizeReplica,
+    ) -> Result<Replica, SvcError> {
+        self.specs()
+            .resize_replica(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
+    /// Move replica to another pool on the same node
+    #[tracing::instrument(level = "info", skip(self), err, fields(replica.uuid = %request.uuid))]
+    pub(super) async fn move_replica(&self, request: &MoveReplica) -> Result<Replica, SvcError> {
+        self.specs()
+            .move_replica(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+}
+
+/// Check if the pool has all of the labels of the equality based `selector`, eg: `zone=us-east`
+/// only matches the pools which have the label `zone` set to `us-east`.
+/// An empty selector matches all pools.
+fn labels_match(pool: &Pool, selector: &PoolLabel) -> bool {
+    let labels = pool.spec().and_then(|spec| spec.labels).unwrap_or_default();
+    selector
+        .iter()
+        .all(|(key, value)| labels.get(key) == Some(value))
 }
@@ -1,20 +1,21 @@
 use crate::core::{
     registry::Registry,
-    specs::{ResourceSpecs, ResourceSpecsLocked, SpecOperations},
-    wrapper::ClientOps,
+    specs::{OperationSequenceGuard, ResourceSpecs, ResourceSpecsLocked, SpecOperations},
+    wrapper::{ClientOps, GetterOps, NodeWrapper},
 };
 use common::errors::{SvcError, SvcError::PoolNotFound};
 use common_lib::{
     mbus_api::ResourceKind,
     types::v0::{
         message_bus::{
-            CreatePool, CreateReplica, DestroyPool, DestroyReplica, Pool, PoolId, PoolState,
-            PoolStatus, Replica, ReplicaId, ReplicaOwners, ReplicaStatus, ShareReplica,
-            UnshareReplica,
+            CreatePool, CreateReplica, DestroyPool, DestroyReplica, ImportPool, Pool, PoolId,
+            PoolState, PoolStatus, PoolUuid, Replica, ReplicaId, ReplicaOwners, ReplicaStatus,
+            ResizeReplica, SetPoolMaintenance, ShareReplica, UnshareReplica,
         },
         store::{
             pool::{PoolOperation, PoolSpec},
             replica::{ReplicaOperation, ReplicaSpec},
+            snapshot::VolumeSnapshotSpec,
             OperationMode, SpecStatus, SpecTransaction,
         },
     },
@@ -107,6 +108,7 @@ impl SpecOperations for ReplicaSpec {
                 })
             }
             ReplicaOperation::Unshare => Ok(()),
+            ReplicaOperation::Resize(size) => validate_resize(state, size),
             _ => unreachable!(),
         }?;
         self.start_op(op);
@@ -154,6 +156,19 @@ impl SpecOperations for ReplicaSpec {
     }
 }
 
+/// Replicas can only grow, so the requested size must be larger than the current replica size
+fn validate_resize(replica: &Replica, requested_size: u64) -> Result<(), SvcError> {
+    if requested_size <= replica.size {
+        return Err(SvcError::InvalidResize {
+            kind: ResourceKind::Replica,
+            id: replica.uuid.to_string(),
+            current: replica.size,
+            requested: requested_size,
+        });
+    }
+    Ok(())
+}
+
 /// Implementation of the ResourceSpecs which is retrieved from the ResourceSpecsLocked
 /// During these calls, no other thread can add/remove elements from the list
 impl ResourceSpecs {
@@ -187,25 +202,128 @@ impl ResourceSpecs {
     }
 }
 
+/// Get the device path of a pool disk which may be a URI, eg: "aio:///dev/sdb?blk_size=4096"
+fn disk_path(disk: &str) -> &str {
+    let path = disk.split_once("://").map(|(_, path)| path).unwrap_or(disk);
+    path.split('?').next().unwrap_or(path)
+}
+
 impl ResourceSpecsLocked {
     pub(crate) async fn create_pool(
         &self,
         registry: &Registry,
         request: &CreatePool,
         mode: OperationMode,
+    ) -> Result<Pool, SvcError> {
+        self.create_or_import_pool(registry, request, None, mode)
+            .await
+    }
+
+    /// Import a pre-existing pool by its uuid, which is recorded in its spec so that it keeps its
+    /// identity across nodes. The io-engine imports the pool from the disks which already contain
+    /// it, rather than creating a new one.
+    pub(crate) async fn import_pool(
+        &self,
+        registry: &Registry,
+        request: &ImportPool,
+        mode: OperationMode,
+    ) -> Result<Pool, SvcError> {
+        if request.uuid.is_nil() {
+            return Err(SvcError::InvalidUuid {
+                uuid: request.uuid.to_string(),
+                kind: ResourceKind::Pool,
+            });
+        }
+        self.create_or_import_pool(registry, &request.into(), Some(request), mode)
+            .await
+    }
+
+    async fn create_or_import_pool(
+        &self,
+        registry: &Registry,
+        request: &CreatePool,
+        import: Option<&ImportPool>,
+        mode: OperationMode,
     ) -> Result<Pool, SvcError> {
         let node = registry.get_node_wrapper(&request.node).await?;
+        if let Some(percent) = request.reserved_capacity_percent {
+            if percent > 100 {
+                return Err(SvcError::InvalidReservedCapacity { percent });
+            }
+        }
+        self.validate_pool_disks(registry, request).await?;
 
         let pool_spec = self.get_or_create_pool(request);
+        if let Some(import) = import {
+            let mut spec = pool_spec.lock();
+            match &spec.uuid {
+                Some(existing) if existing != &import.uuid => {
+                    return Err(SvcError::PoolUuidMismatch {
+                        pool: request.id.to_string(),
+                        uuid: existing.to_string(),
+                        requested: import.uuid.to_string(),
+                    });
+                }
+                _ if spec.status.creating() => spec.uuid = Some(import.uuid.clone()),
+                // already imported with the same uuid, `start_create` reports it as existing
+                Some(_) => {}
+                None => {
+                    return Err(SvcError::AlreadyExists {
+                        kind: ResourceKind::Pool,
+                        id: request.id.to_string(),
+                    });
+                }
+            }
+        }
         let (_, _g) = SpecOperations::start_create(&pool_spec, registry, request, mode).await?;
 
-        let result = node.create_pool(request).await;
+        let result = match import {
+            Some(import) => node.import_pool(import).await,
+            None => node.create_pool(request).await,
+        };
 
         let pool_state = SpecOperations::complete_create(result, &pool_spec, registry).await?;
         let pool_spec = pool_spec.lock().clone();
         Ok(Pool::new(pool_spec, pool_state))
     }
 
+    /// Check that none of the requested disks is already claimed by another pool on the node,
+    /// either through its spec or as reported by the io-engine
+    async fn validate_pool_disks(
+        &self,
+        registry: &Registry,
+        request: &CreatePool,
+    ) -> Result<(), SvcError> {
+        let mut claimed = self
+            .get_pools()
+            .into_iter()
+            .filter(|spec| spec.node == request.node && spec.id != request.id)
+            .map(|spec| (spec.id, spec.disks))
+            .collect::<Vec<_>>();
+        claimed.extend(
+            registry
+                .get_node_pools(&request.node)
+                .await?
+                .into_iter()
+                .filter(|state| state.id != request.id)
+                .map(|state| (state.id, state.disks)),
+        );
+        for disk in &request.disks {
+            let in_use = claimed.iter().find(|(_, disks)| {
+                disks
+                    .iter()
+                    .any(|claimed| disk_path(claimed) == disk_path(disk))
+            });
+            if let Some((pool, _)) = in_use {
+                return Err(SvcError::DeviceInUse {
+                    disk: disk.to_string(),
+                    pool: pool.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) async fn destroy_pool(
         &self,
         registry: &Registry,
@@ -227,6 +345,33 @@ impl ResourceSpecsLocked {
         }
     }
 
+    /// Set or clear the maintenance flag of the pool, persisting it to the store.
+    /// The operation guard makes sure we don't race with a pending create or destroy operation.
+    pub(crate) async fn set_pool_maintenance(
+        &self,
+        registry: &Registry,
+        request: &SetPoolMaintenance,
+        mode: OperationMode,
+    ) -> Result<PoolSpec, SvcError> {
+        let pool_spec = self.get_locked_pool(&request.id).ok_or(PoolNotFound {
+            pool_id: request.id.clone(),
+        })?;
+        let _guard = pool_spec.operation_guard_wait(mode).await?;
+        let (changed, spec) = {
+            let mut spec = pool_spec.lock();
+            let changed = spec.maintenance != request.maintenance;
+            spec.maintenance = request.maintenance;
+            (changed, spec.clone())
+        };
+        if changed {
+            if let Err(error) = registry.store_obj(&spec).await {
+                pool_spec.lock().maintenance = !request.maintenance;
+                return Err(error);
+            }
+        }
+        Ok(spec)
+    }
+
     pub(crate) async fn create_replica(
         &self,
         registry: &Registry,
@@ -234,15 +379,72 @@ impl ResourceSpecsLocked {
         mode: OperationMode,
     ) -> Result<Replica, SvcError> {
         let node = registry.get_node_wrapper(&request.node).await?;
+        Self::validate_replica_capacity(registry, &node, request).await?;
 
         let replica_spec = self.get_or_create_replica(request);
         let (_, _guard) =
             SpecOperations::start_create(&replica_spec, registry, request, mode).await?;
 
-        let result = node.create_replica(request).await;
+        let result = match node.create_replica(request).await {
+            Ok(replica) if replica.thin != request.thin => {
+                // don't leave behind a replica which doesn't match its spec
+                node.destroy_replica(&DestroyReplica::from(replica))
+                    .await
+                    .ok();
+                Err(SvcError::ReplicaProvisioningMismatch {
+                    replica: request.uuid.clone(),
+                    node: request.node.clone(),
+                    thin: request.thin,
+                })
+            }
+            result => result,
+        };
         SpecOperations::complete_create(result, &replica_spec, registry).await
     }
 
+    /// Create a replica as a clone of the given snapshot, which must have been taken on the
+    /// replica's pool. A clone shares the data of the snapshot, so no capacity is allocated.
+    pub(crate) async fn create_replica_clone(
+        &self,
+        registry: &Registry,
+        request: &CreateReplica,
+        snapshot: &VolumeSnapshotSpec,
+        mode: OperationMode,
+    ) -> Result<Replica, SvcError> {
+        let node = registry.get_node_wrapper(&request.node).await?;
+
+        let replica_spec = self.get_or_create_replica(request);
+        let (_, _guard) =
+            SpecOperations::start_create(&replica_spec, registry, request, mode).await?;
+
+        let result = node.clone_replica(request, &snapshot.name).await;
+        SpecOperations::complete_create(result, &replica_spec, registry).await
+    }
+
+    /// Check that the new replica fits in the pool without eating into its reserved capacity.
+    /// Replicas which already exist are not checked as they've already been allocated.
+    async fn validate_replica_capacity(
+        registry: &Registry,
+        node: &Arc<tokio::sync::RwLock<NodeWrapper>>,
+        request: &CreateReplica,
+    ) -> Result<(), SvcError> {
+        let pool = match node.pool_wrapper(&request.pool).await {
+            Some(pool) if pool.replica(&request.uuid).is_none() => pool,
+            _ => return Ok(()),
+        };
+        let reserved_percent = registry.reserved_capacity_percent(&request.pool);
+        let free = pool.free_space_reserved(reserved_percent);
+        if free < request.size {
+            return Err(SvcError::PoolCapacityReserved {
+                pool: request.pool.to_string(),
+                free,
+                reserved_percent,
+                required: request.size,
+            });
+        }
+        Ok(())
+    }
+
     pub(crate) async fn destroy_replica_spec(
         &self,
         registry: &Registry,
@@ -345,6 +547,33 @@ impl ResourceSpecsLocked {
         }
     }
 
+    pub(crate) async fn resize_replica(
+        &self,
+        registry: &Registry,
+        request: &ResizeReplica,
+        mode: OperationMode,
+    ) -> Result<Replica, SvcError> {
+        let node = registry.get_node_wrapper(&request.node).await?;
+        let status = registry.get_replica(&request.uuid).await?;
+
+        if let Some(replica_spec) = self.get_replica(&request.uuid) {
+            let (spec_clone, _guard) = SpecOperations::start_update(
+                registry,
+                &replica_spec,
+                &status,
+                ReplicaOperation::Resize(request.requested_size),
+                mode,
+            )
+            .await?;
+
+            let result = node.resize_replica(request).await;
+            SpecOperations::complete_update(registry, result, replica_spec, spec_clone).await
+        } else {
+            validate_resize(&status, request.requested_size)?;
+            node.resize_replica(request).await
+        }
+    }
+
     /// Get or Create the protected ReplicaSpec for the given request
     fn get_or_create_replica(&self, request: &CreateReplica) -> Arc<Mutex<ReplicaSpec>> {
         let mut specs = self.write();
@@ -1,12 +1,15 @@
 #![cfg(test)]
 
 use common_lib::{
-    mbus_api::{ReplyError, ReplyErrorKind, ResourceKind, TimeoutOptions},
+    mbus_api::{v0::Events, ReplyError, ReplyErrorKind, ResourceKind, TimeoutOptions},
     types::v0::{
         message_bus::{
-            CreatePool, CreateReplica, DestroyPool, DestroyReplica, Filter, GetSpecs, NodeId,
-            Protocol, Replica, ReplicaId, ReplicaName, ReplicaShareProtocol, ReplicaStatus,
-            ShareReplica, UnshareReplica, VolumeId,
+            CapacityForecast, CheckOrphanedReplicas, CreatePool, CreateReplica, CreateVolume,
+            DestroyPool, DestroyReplica, EncryptionCipher, EventType, Filter, GetSpecs, ImportPool,
+            NexusId, NodeId, NodeStatus, PoolId, PoolUuid, Protocol, ReconcilePool, Replica,
+            ReplicaEncryption, ReplicaId, ReplicaName, ReplicaOwners, ReplicaShareProtocol,
+            ReplicaStatus, ResizeReplica, SetPoolMaintenance, ShareReplica, UnshareReplica,
+            VolumeId,
         },
         openapi::{
             apis::StatusCode,
@@ -22,6 +25,7 @@ use grpc::{
     operations::{
         node::traits::NodeOperations, pool::traits::PoolOperations,
         registry::traits::RegistryOperations, replica::traits::ReplicaOperations,
+        volume::traits::VolumeOperations,
     },
 };
 use itertools::Itertools;
@@ -51,6 +55,7 @@ async fn pool() {
                 id: "pooloop".into(),
                 disks: vec!["malloc:///disk0?size_mb=100".into()],
                 labels: None,
+                reserved_capacity_percent: None,
             },
             None,
         )
@@ -58,6 +63,28 @@ async fn pool() {
         .unwrap();
     tracing::info!("Pools: {:?}", pool);
 
+    let error = pool_client
+        .create(
+            &CreatePool {
+                node: io_engine.clone(),
+                id: "pooloop2".into(),
+                disks: vec!["malloc:///disk0?size_mb=100".into()],
+                labels: None,
+                reserved_capacity_percent: None,
+            },
+            None,
+        )
+        .await
+        .expect_err("Should fail to create a pool on a disk claimed by another pool.");
+    assert!(matches!(
+        error,
+        ReplyError {
+            kind: ReplyErrorKind::DeviceInUse,
+            resource: ResourceKind::Pool,
+            ..
+        }
+    ));
+
     let pools = pool_client.get(Filter::None, None).await.unwrap();
     tracing::info!("Pools: {:?}", pools);
 
@@ -80,10 +107,11 @@ async fn pool() {
         .unwrap();
     tracing::info!("Replicas: {:?}", replica);
 
-    let replicas = rep_client.get(Filter::None, None).await.unwrap();
+    let replicas = rep_client.get(Filter::None, None, None).await.unwrap();
     tracing::info!("Replicas: {:?}", replicas);
 
     let uri = replica.uri.clone();
+    assert!(replica.state_changed_at.is_some());
     assert_eq!(
         replica,
         Replica {
@@ -91,14 +119,26 @@ async fn pool() {
             name: ReplicaName::from("cf36a440-74c6-4042-b16c-4f7eddfc24da"),
             uuid: ReplicaId::try_from("cf36a440-74c6-4042-b16c-4f7eddfc24da").unwrap(),
             pool: "pooloop".into(),
-            thin: false,
+            thin: true,
             size: 12582912,
             share: Protocol::None,
             uri,
-            status: ReplicaStatus::Online
+            status: ReplicaStatus::Online,
+            state_changed_at: replica.state_changed_at,
         }
     );
 
+    let fetched = rep_client
+        .get_one(&io_engine, &replica.pool, &replica.uuid, None)
+        .await
+        .unwrap();
+    assert_eq!(fetched, replica);
+    let error = rep_client
+        .get_one(&io_engine, &replica.pool, &ReplicaId::new(), None)
+        .await
+        .expect_err("The replica does not exist");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+
     let uri = rep_client
         .share(
             &ShareReplica {
@@ -116,10 +156,23 @@ async fn pool() {
     let mut replica_updated = replica;
     replica_updated.uri = uri;
     replica_updated.share = Protocol::Nvmf;
-    let replica = rep_client.get(Filter::None, None).await.unwrap();
-    let replica = replica.0.first().unwrap();
+    let replica = rep_client.get(Filter::None, None, None).await.unwrap();
+    let replica = replica.entries.first().unwrap();
     assert_eq!(replica, &replica_updated);
 
+    let error = rep_client
+        .resize(&ResizeReplica::new(replica, replica.size), None)
+        .await
+        .expect_err("Should fail to resize a replica to its current size");
+    assert!(matches!(
+        error,
+        ReplyError {
+            kind: ReplyErrorKind::InvalidArgument,
+            resource: ResourceKind::Replica,
+            ..
+        }
+    ));
+
     let error = pool_client
         .destroy(
             &DestroyPool {
@@ -154,10 +207,10 @@ async fn pool() {
         .unwrap();
 
     assert!(rep_client
-        .get(Filter::None, None)
+        .get(Filter::None, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
 
     pool_client
@@ -202,6 +255,83 @@ async fn replica_spec(replica: &Replica, client: &dyn RegistryOperations) -> Opt
         .cloned()
 }
 
+/// A thin provisioned replica should be reported as such by both its spec and its state
+#[tokio::test]
+async fn thin_replica() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_pools(1)
+        .build()
+        .await
+        .unwrap();
+    let rep_client = cluster.grpc_client().replica();
+    let registry_client = cluster.grpc_client().registry();
+
+    let replica = rep_client
+        .create(
+            &CreateReplica {
+                node: cluster.node(0),
+                uuid: ReplicaId::new(),
+                pool: cluster.pool(0, 0),
+                size: 5 * 1024 * 1024,
+                thin: true,
+                share: Protocol::None,
+                managed: true,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(replica.thin);
+
+    let state = rep_client
+        .get(Filter::Replica(replica.uuid.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
+    assert!(state.thin, "the replica state should be thin provisioned");
+    let spec = replica_spec(&replica, &registry_client).await.unwrap();
+    assert!(spec.thin, "the replica spec should be thin provisioned");
+}
+
+#[tokio::test]
+async fn encrypted_replica() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_pools(1)
+        .build()
+        .await
+        .unwrap();
+    let rep_client = cluster.grpc_client().replica();
+    let registry_client = cluster.grpc_client().registry();
+
+    let replica = CreateReplica {
+        node: cluster.node(0),
+        uuid: ReplicaId::new(),
+        pool: cluster.pool(0, 0),
+        size: 5 * 1024 * 1024,
+        thin: false,
+        share: Protocol::None,
+        managed: true,
+        encryption: Some(ReplicaEncryption::new(EncryptionCipher::AesXts, "key-1")),
+        ..Default::default()
+    };
+    let error = rep_client
+        .create(&replica, None)
+        .await
+        .expect_err("the io-engine can't create encrypted replicas");
+    assert_eq!(error.kind, ReplyErrorKind::Unimplemented);
+    assert_eq!(error.resource, ResourceKind::Replica);
+
+    // the failed creation doesn't leave a spec behind
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    assert!(!specs.replicas.iter().any(|r| r.uuid == replica.uuid));
+}
+
 /// Tests replica share and unshare operations as a transaction
 #[tokio::test]
 async fn replica_transaction() {
@@ -448,6 +578,388 @@ async fn replica_transaction_store() {
     .await;
 }
 
+/// Forcing a pool reconcile should refresh its state right away, even with a large cache period.
+#[tokio::test]
+async fn reconcile_pool() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_pools(1)
+        .with_agents(vec!["core"])
+        .with_cache_period("1h")
+        .build()
+        .await
+        .unwrap();
+    let pool_client = cluster.grpc_client().pool();
+    let pool_id = cluster.pool(0, 0);
+
+    let pool = pool_client
+        .reconcile(&ReconcilePool::new(&pool_id), None)
+        .await
+        .unwrap();
+    tracing::info!("Pool: {:?}", pool);
+    assert_eq!(pool.id(), &pool_id);
+    assert!(pool.state().is_some());
+
+    let error = pool_client
+        .reconcile(&ReconcilePool::new(&"unknown".into()), None)
+        .await
+        .expect_err("The pool does not exist");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+}
+
+/// Pools under maintenance keep their spec and state but are not used for new replicas
+#[tokio::test]
+async fn pool_maintenance() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_pools(1)
+        .with_agents(vec!["core"])
+        .build()
+        .await
+        .unwrap();
+    let pool_client = cluster.grpc_client().pool();
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let pool_id = cluster.pool(0, 0);
+
+    let pool = pool_client
+        .set_maintenance(&SetPoolMaintenance::new(&pool_id, true), None)
+        .await
+        .unwrap();
+    assert!(pool.spec().unwrap().maintenance);
+    assert!(pool.state().is_some());
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let spec = specs.pools.iter().find(|p| p.id == pool_id).unwrap();
+    assert!(spec.maintenance, "the maintenance flag is persisted");
+
+    let create = CreateVolume {
+        uuid: VolumeId::try_from("5e3c2a1f-8b7d-4c6e-9f0a-1d2b3c4e5f6a").unwrap(),
+        size: 5242880,
+        replicas: 1,
+        ..Default::default()
+    };
+    let error = volume_client
+        .create(&create, None)
+        .await
+        .expect_err("the only pool is under maintenance");
+    assert_eq!(error.kind, ReplyErrorKind::ResourceExhausted);
+
+    let pool = pool_client
+        .set_maintenance(&SetPoolMaintenance::new(&pool_id, false), None)
+        .await
+        .unwrap();
+    assert!(!pool.spec().unwrap().maintenance);
+    volume_client.create(&create, None).await.unwrap();
+
+    let error = pool_client
+        .set_maintenance(&SetPoolMaintenance::new(&"unknown".into(), true), None)
+        .await
+        .expect_err("The pool does not exist");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn pool_reserved_capacity() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .build()
+        .await
+        .unwrap();
+    let pool_client = cluster.grpc_client().pool();
+    let rep_client = cluster.grpc_client().replica();
+    let registry_client = cluster.grpc_client().registry();
+    let io_engine = cluster.node(0);
+
+    let error = pool_client
+        .create(
+            &CreatePool {
+                node: io_engine.clone(),
+                id: "pooloop".into(),
+                disks: vec!["malloc:///disk0?size_mb=100".into()],
+                labels: None,
+                reserved_capacity_percent: Some(101),
+            },
+            None,
+        )
+        .await
+        .expect_err("Cannot reserve more than the whole pool");
+    assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+
+    pool_client
+        .create(
+            &CreatePool {
+                node: io_engine.clone(),
+                id: "pooloop".into(),
+                disks: vec!["malloc:///disk0?size_mb=100".into()],
+                labels: None,
+                reserved_capacity_percent: Some(90),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let spec = specs
+        .pools
+        .iter()
+        .find(|p| p.id.as_str() == "pooloop")
+        .unwrap();
+    assert_eq!(spec.reserved_capacity_percent, Some(90));
+
+    let create = CreateReplica {
+        node: io_engine.clone(),
+        uuid: ReplicaId::try_from("2f6b1d3e-5a4c-4e8f-9b7a-0c1d2e3f4a5b").unwrap(),
+        pool: "pooloop".into(),
+        size: 20 * 1024 * 1024,
+        thin: false,
+        share: Protocol::None,
+        ..Default::default()
+    };
+    let error = rep_client
+        .create(&create, None)
+        .await
+        .expect_err("The replica would eat into the reserved capacity");
+    assert_eq!(error.kind, ReplyErrorKind::ResourceExhausted);
+    assert_eq!(error.resource, ResourceKind::Pool);
+
+    rep_client
+        .create(
+            &CreateReplica {
+                size: 4 * 1024 * 1024,
+                ..create
+            },
+            None,
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn pool_import() {
+    let disk = deployer_cluster::TmpDiskFile::new("import.img", POOL_SIZE_BYTES);
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_cache_period("1s")
+        .build()
+        .await
+        .unwrap();
+    let pool_client = cluster.grpc_client().pool();
+    let registry_client = cluster.grpc_client().registry();
+    let io_engine = cluster.node(0);
+    let uuid = PoolUuid::try_from("8d2f7a1c-3b4e-4f5a-9c6d-7e8f9a0b1c2d").unwrap();
+    let import = ImportPool::new(
+        &io_engine,
+        &"pooloop".into(),
+        &uuid,
+        &[disk.uri().into()],
+        &None,
+    );
+
+    // there's no pool on the disk yet, so there's nothing to import
+    let error = pool_client
+        .import(&import, None)
+        .await
+        .expect_err("The disk does not contain a pool");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    assert!(!specs.pools.iter().any(|p| p.id.as_str() == "pooloop"));
+
+    // create the pool with the uuid on the io-engine, under the control-plane, and then restart
+    // the io-engine so that the pool only lives on the disk
+    let mut rpc_handle = cluster.grpc_handle(io_engine.as_str()).await.unwrap();
+    rpc_handle
+        .pool
+        .create_pool(rpc::v1::pool::CreatePoolRequest {
+            name: "pooloop".to_string(),
+            uuid: Some(uuid.to_string()),
+            disks: vec![disk.uri().to_string()],
+            pooltype: rpc::v1::pool::PoolType::Lvs as i32,
+        })
+        .await
+        .unwrap();
+    cluster.composer().kill(io_engine.as_str()).await.unwrap();
+    wait_till_node_online(&cluster, &io_engine, false).await;
+    cluster.composer().start(io_engine.as_str()).await.unwrap();
+    wait_till_node_online(&cluster, &io_engine, true).await;
+
+    // the pool is imported from its disk, keeping its uuid
+    let pool = pool_client.import(&import, None).await.unwrap();
+    assert_eq!(pool.spec().unwrap().uuid, Some(uuid.clone()));
+    assert_eq!(pool.state().unwrap().disks, vec![disk.uri().into()]);
+
+    // importing it again is rejected, as the pool already exists
+    let error = pool_client
+        .import(&import, None)
+        .await
+        .expect_err("The pool has already been imported");
+    assert_eq!(error.kind, ReplyErrorKind::AlreadyExists);
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let spec = specs
+        .pools
+        .iter()
+        .find(|p| p.id.as_str() == "pooloop")
+        .unwrap();
+    assert_eq!(spec.uuid, Some(uuid));
+
+    let error = pool_client
+        .import(
+            &ImportPool {
+                uuid: PoolUuid::try_from("0a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d").unwrap(),
+                ..import
+            },
+            None,
+        )
+        .await
+        .expect_err("The pool has a different uuid");
+    assert_eq!(error.kind, ReplyErrorKind::FailedPrecondition);
+    assert_eq!(error.resource, ResourceKind::Pool);
+}
+
+#[tokio::test]
+async fn orphaned_replicas() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_pools(1)
+        .with_cache_period("1s")
+        .build()
+        .await
+        .unwrap();
+    let rep_client = cluster.grpc_client().replica();
+    let registry_client = cluster.grpc_client().registry();
+    let io_engine = cluster.node(0);
+    let pool = cluster.pool(0, 0);
+
+    // a replica managed by the control-plane is never an orphan
+    let managed = rep_client
+        .create(
+            &CreateReplica {
+                node: io_engine.clone(),
+                uuid: ReplicaId::new(),
+                pool: pool.clone(),
+                size: 5 * 1024 * 1024,
+                thin: false,
+                share: Protocol::None,
+                managed: true,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    // a replica named after its uuid but left behind without a spec
+    let orphan = ReplicaId::new();
+    let mut rpc_handle = cluster.grpc_handle(io_engine.as_str()).await.unwrap();
+    rpc_handle
+        .io_engine
+        .create_replica_v2(rpc::io_engine::CreateReplicaRequestV2 {
+            name: ReplicaName::from_uuid(&orphan).into(),
+            uuid: orphan.to_string(),
+            pool: pool.to_string(),
+            thin: false,
+            size: 5 * 1024 * 1024,
+            share: Protocol::None as i32,
+        })
+        .await
+        .unwrap();
+    // a replica which was not created by the control-plane is left alone
+    rpc_handle
+        .io_engine
+        .create_replica_v2(rpc::io_engine::CreateReplicaRequestV2 {
+            name: "foreign".to_string(),
+            uuid: ReplicaId::new().to_string(),
+            pool: pool.to_string(),
+            thin: false,
+            size: 5 * 1024 * 1024,
+            share: Protocol::None as i32,
+        })
+        .await
+        .unwrap();
+
+    // wait for the registry cache to pick up the new replicas
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let orphans = registry_client
+        .check_orphaned_replicas(&CheckOrphanedReplicas::new(false), None)
+        .await
+        .unwrap();
+    assert_eq!(orphans.entries.len(), 1, "{:?}", orphans);
+    assert_eq!(orphans.entries[0].replica.uuid, orphan);
+    assert!(!orphans.entries[0].destroyed);
+    assert_ne!(orphans.entries[0].replica.uuid, managed.uuid);
+
+    let orphans = registry_client
+        .check_orphaned_replicas(&CheckOrphanedReplicas::new(true), None)
+        .await
+        .unwrap();
+    assert_eq!(orphans.entries.len(), 1, "{:?}", orphans);
+    assert!(orphans.entries[0].destroyed);
+
+    let replicas = rpc_handle
+        .io_engine
+        .list_replicas_v2(rpc::io_engine::Null {})
+        .await
+        .unwrap()
+        .into_inner()
+        .replicas;
+    assert!(!replicas.iter().any(|r| r.uuid == orphan.to_string()));
+    assert!(replicas.iter().any(|r| r.uuid == managed.uuid.to_string()));
+    assert!(replicas.iter().any(|r| r.name == "foreign"));
+}
+
+#[tokio::test]
+async fn replica_force_destroy() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_pools(1)
+        .build()
+        .await
+        .unwrap();
+    let rep_client = cluster.grpc_client().replica();
+    let io_engine = cluster.node(0);
+    let pool = cluster.pool(0, 0);
+
+    // a replica which is still owned by a nexus, eg: left behind by a crash
+    let replica = rep_client
+        .create(
+            &CreateReplica {
+                node: io_engine.clone(),
+                uuid: ReplicaId::new(),
+                pool: pool.clone(),
+                size: 5 * 1024 * 1024,
+                thin: false,
+                share: Protocol::None,
+                managed: true,
+                owners: ReplicaOwners::new(None, vec![NexusId::new()]),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let destroy = DestroyReplica::from(replica.clone());
+
+    let error = rep_client
+        .destroy(&destroy, None)
+        .await
+        .expect_err("the replica is still owned");
+    assert_eq!(error.kind, ReplyErrorKind::InUse);
+
+    rep_client
+        .destroy(&destroy.with_force(true), None)
+        .await
+        .unwrap();
+    let error = rep_client
+        .get_one(&replica.node, &replica.pool, &replica.uuid, None)
+        .await
+        .expect_err("the replica is destroyed");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+}
+
 const RECONCILE_TIMEOUT_SECS: u64 = 7;
 const POOL_FILE_NAME: &str = "disk1.img";
 const POOL_SIZE_BYTES: u64 = 128 * 1024 * 1024;
@@ -486,7 +998,12 @@ async fn reconciler_missing_pool_state() {
         let volume = VolumeId::new();
         volumes_api.put_volume(&volume, body).await.unwrap();
     }
-    let replicas = client.replicas_api().get_replicas().await.unwrap();
+    let replicas = client
+        .replicas_api()
+        .get_replicas(0, None)
+        .await
+        .unwrap()
+        .entries;
 
     let pool = pools_api
         .get_pool(cluster.pool(0, 0).as_str())
@@ -519,7 +1036,12 @@ async fn reconciler_missing_pool_state() {
     pool_checker(&cluster, pool.state.as_ref()).await;
 
     // we should have also "imported" the same replicas, perhaps in a different order...
-    let current_replicas = client.replicas_api().get_replicas().await.unwrap();
+    let current_replicas = client
+        .replicas_api()
+        .get_replicas(0, None)
+        .await
+        .unwrap()
+        .entries;
     assert_eq!(
         replicas
             .iter()
@@ -532,6 +1054,92 @@ async fn reconciler_missing_pool_state() {
     );
 }
 
+/// Creates a pool on a io_engine instance, which will have both spec and state.
+/// The pool is then destroyed directly on the io_engine, under the control plane, leaving the pool
+/// with no state whilst its node is still online: the pool reconciler should then recreate it.
+#[tokio::test]
+async fn reconciler_missing_pool_state_node_online() {
+    let disk = deployer_cluster::TmpDiskFile::new(POOL_FILE_NAME, POOL_SIZE_BYTES);
+
+    let cluster = ClusterBuilder::builder()
+        .with_rest(true)
+        .with_agents(vec!["core"])
+        .with_io_engines(1)
+        .with_pool(0, disk.uri())
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .build()
+        .await
+        .unwrap();
+
+    let pool = wait_till_pool_state(&cluster, (0, 0), true).await;
+    let pool_id = cluster.pool(0, 0);
+
+    let mut rpc_handle = cluster.grpc_handle(cluster.node(0).as_str()).await.unwrap();
+    rpc_handle
+        .io_engine
+        .destroy_pool(rpc::io_engine::DestroyPoolRequest {
+            name: pool_id.to_string(),
+        })
+        .await
+        .unwrap();
+
+    // wait for the pool to be recreated on the io_engine
+    let timeout = Duration::from_secs(RECONCILE_TIMEOUT_SECS);
+    let start = std::time::Instant::now();
+    loop {
+        let pools = rpc_handle
+            .io_engine
+            .list_pools(rpc::io_engine::Null {})
+            .await
+            .unwrap()
+            .into_inner()
+            .pools;
+        if pools.iter().any(|p| p.name == pool_id.as_str()) {
+            break;
+        }
+        if std::time::Instant::now() > (start + timeout) {
+            panic!("Timeout waiting for the pool to be recreated");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let recreated = wait_till_pool_state(&cluster, (0, 0), true).await;
+    assert_eq!(recreated.spec, pool.spec);
+    assert_eq!(
+        recreated.state.map(|s| s.disks),
+        pool.state.map(|s| s.disks)
+    );
+}
+
+/// Wait until the specified node's online status matches the `online` flag
+async fn wait_till_node_online(cluster: &Cluster, node: &NodeId, online: bool) {
+    let timeout = Duration::from_secs(RECONCILE_TIMEOUT_SECS);
+    let node_client = cluster.grpc_client().node();
+    let start = std::time::Instant::now();
+    loop {
+        let nodes = node_client
+            .get(Filter::Node(node.clone()), None)
+            .await
+            .unwrap();
+        let is_online = nodes
+            .into_inner()
+            .iter()
+            .any(|n| n.state().map(|s| &s.status) == Some(&NodeStatus::Online));
+        if is_online == online {
+            return;
+        }
+
+        if std::time::Instant::now() > (start + timeout) {
+            panic!(
+                "Timeout waiting for the node '{}' to have 'online': '{}'",
+                node, online
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
 /// Wait until the specified pool state option presence matches the `has_state` flag
 async fn wait_till_pool_state(cluster: &Cluster, pool: (u32, u32), has_state: bool) -> Pool {
     let pool_id = cluster.pool(pool.0, pool.1);
@@ -738,3 +1346,245 @@ async fn reconciler_deleting_dirty_pool() {
         }
     }
 }
+
+#[tokio::test]
+async fn pool_capacity_forecast() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(true)
+        .with_agents(vec!["core"])
+        .with_io_engines(1)
+        .with_pools(1)
+        .with_cache_period("1s")
+        .build()
+        .await
+        .unwrap();
+
+    async fn forecast(cluster: &Cluster) -> Option<CapacityForecast> {
+        let pools = cluster
+            .grpc_client()
+            .pool()
+            .get(Filter::Pool(cluster.pool(0, 0)), None)
+            .await
+            .unwrap();
+        pools.into_inner()[0].forecast()
+    }
+
+    // wait for a few usage samples to be taken
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let before = forecast(&cluster)
+        .await
+        .expect("the pool should have usage samples");
+    assert!(before.samples > 1);
+    assert_eq!(before.growth_rate, 0.0);
+    assert_eq!(before.exhaustion, None);
+
+    // fill up some of the pool space, then let the usage samples catch up
+    let volumes_api = cluster.rest_v00().volumes_api();
+    for _ in 0 .. 4 {
+        let body = CreateVolumeBody::new(VolumePolicy::default(), 1, 8388608u64);
+        volumes_api
+            .put_volume(&VolumeId::new(), body)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+    }
+    let after = forecast(&cluster).await.unwrap();
+    assert!(after.used > before.used);
+    assert!(after.growth_rate > 0.0);
+    let exhaustion = after
+        .exhaustion
+        .expect("the pool should run out of space eventually");
+    assert!(exhaustion > after.timestamp);
+
+    let cluster_forecast = cluster
+        .rest_v00()
+        .pools_api()
+        .get_capacity_forecast()
+        .await
+        .unwrap();
+    assert_eq!(cluster_forecast.capacity, after.capacity);
+}
+
+#[tokio::test]
+async fn pool_label_filter() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(true)
+        .with_agents(vec!["core"])
+        .with_io_engines(1)
+        .build()
+        .await
+        .unwrap();
+
+    let pool_client = cluster.grpc_client().pool();
+    let labels = vec![
+        ("pool-east-ssd", vec![("zone", "us-east"), ("ssd", "true")]),
+        ("pool-east-hdd", vec![("zone", "us-east"), ("ssd", "false")]),
+        ("pool-west-ssd", vec![("zone", "us-west"), ("ssd", "true")]),
+    ];
+    for (index, (pool, labels)) in labels.into_iter().enumerate() {
+        pool_client
+            .create(
+                &CreatePool {
+                    node: cluster.node(0),
+                    id: pool.into(),
+                    disks: vec![format!("malloc:///disk{}?size_mb=50", index).into()],
+                    labels: Some(
+                        labels
+                            .into_iter()
+                            .map(|(k, v)| (k.to_string(), v.to_string()))
+                            .collect(),
+                    ),
+                    reserved_capacity_percent: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn selected(client: &impl PoolOperations, selector: &[(&str, &str)]) -> Vec<String> {
+        let filter = Filter::PoolLabel(
+            selector
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        client
+            .get(filter, None)
+            .await
+            .unwrap()
+            .into_inner()
+            .into_iter()
+            .map(|pool| pool.id().to_string())
+            .sorted()
+            .collect()
+    }
+    assert_eq!(
+        selected(&pool_client, &[("zone", "us-east")]).await,
+        vec!["pool-east-hdd", "pool-east-ssd"]
+    );
+    assert_eq!(
+        selected(&pool_client, &[("zone", "us-east"), ("ssd", "true")]).await,
+        vec!["pool-east-ssd"]
+    );
+    assert!(selected(&pool_client, &[("zone", "eu-central")])
+        .await
+        .is_empty());
+    assert_eq!(selected(&pool_client, &[]).await.len(), 3);
+
+    let pools_api = cluster.rest_v00().pools_api();
+    let pools = pools_api.get_pools(Some("ssd=true")).await.unwrap();
+    assert_eq!(
+        pools
+            .iter()
+            .map(|p| p.id.as_str())
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec!["pool-east-ssd", "pool-west-ssd"]
+    );
+    let error = pools_api
+        .get_pools(Some("zone"))
+        .await
+        .expect_err("a selector without a value is invalid");
+    match error {
+        Error::Response(response) => assert_eq!(response.status(), StatusCode::BAD_REQUEST),
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+/// The outcome of the pool and replica operations is recorded as control-plane events
+#[tokio::test]
+async fn pool_events() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .build()
+        .await
+        .unwrap();
+    let pool_client = cluster.grpc_client().pool();
+    let rep_client = cluster.grpc_client().replica();
+    let registry_client = cluster.grpc_client().registry();
+    let io_engine = cluster.node(0);
+    let pool_id = PoolId::from("pooloop");
+    let replica_id = ReplicaId::try_from("7d1c5e2a-3b4f-4a6d-8e9c-0f1a2b3c4d5e").unwrap();
+
+    pool_client
+        .create(
+            &CreatePool {
+                node: io_engine.clone(),
+                id: pool_id.clone(),
+                disks: vec!["malloc:///disk0?size_mb=100".into()],
+                labels: None,
+                reserved_capacity_percent: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let create = CreateReplica {
+        node: io_engine.clone(),
+        uuid: replica_id.clone(),
+        pool: pool_id.clone(),
+        size: 4 * 1024 * 1024,
+        thin: false,
+        share: Protocol::None,
+        ..Default::default()
+    };
+    rep_client.create(&create, None).await.unwrap();
+    rep_client
+        .create(&create, None)
+        .await
+        .expect_err("The replica already exists");
+    rep_client
+        .destroy(
+            &DestroyReplica {
+                node: io_engine.clone(),
+                uuid: replica_id.clone(),
+                pool: pool_id.clone(),
+                name: None,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    pool_client
+        .destroy(
+            &DestroyPool {
+                node: io_engine.clone(),
+                id: pool_id.clone(),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let reasons = |events: Events| {
+        let events = events.into_inner();
+        assert!(events.iter().all(|e| e.event_type == EventType::Normal));
+        events.into_iter().map(|e| e.reason).collect::<Vec<_>>()
+    };
+    let events = registry_client
+        .get_events(Filter::Pool(pool_id.clone()), None)
+        .await
+        .unwrap();
+    assert_eq!(reasons(events), vec!["Created", "Destroyed"]);
+    // the failed re-create is rejected before the spec operation starts, so it's not recorded
+    let events = registry_client
+        .get_events(Filter::Replica(replica_id.clone()), None)
+        .await
+        .unwrap();
+    assert_eq!(reasons(events), vec!["Created", "Destroyed"]);
+
+    let events = registry_client
+        .get_events(Filter::None, None)
+        .await
+        .unwrap();
+    assert_eq!(events.into_inner().len(), 4);
+
+    let error = registry_client
+        .get_events(Filter::NodePool(io_engine, pool_id), None)
+        .await
+        .expect_err("Only filters by a single resource are supported");
+    assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+}
@@ -1,13 +1,34 @@
-use crate::{core, core::specs::ResourceSpecsLocked};
+use crate::{
+    core,
+    core::{
+        audit,
+        reconciler::PollTriggerEvent,
+        specs::{ResourceSpecsLocked, SpecOperations},
+        wrapper::ClientOps,
+    },
+};
 use common::errors::SvcError;
 use common_lib::{
-    mbus_api::ReplyError,
-    types::v0::message_bus::{GetSpecs, Specs},
+    mbus_api::{v0::Events, ReplyError, ResourceKind},
+    types::v0::{
+        message_bus::{
+            CheckOrphanedReplicas, ChildUri, ClearOperation, DestroyReplica, Filter, GetEvents,
+            GetOperations, GetSpecs, NexusId, NodeId, OrphanedReplica, OrphanedReplicas,
+            PendingOperation, PendingOperations, PoolId, RebuildLimit, ReconcileResource, Replica,
+            ReplicaId, ReplicaName, ResourceRef, SetRebuildLimit, Specs, VolumeId,
+        },
+        store::{nexus::NexusSpec, pool::PoolSpec, replica::ReplicaSpec, volume::VolumeSpec},
+    },
 };
 use grpc::{
     context::Context,
-    operations::registry::traits::{GetSpecsInfo, RegistryOperations},
+    operations::registry::traits::{
+        CheckOrphanedReplicasInfo, GetSpecsInfo, RegistryOperations, SetRebuildLimitInfo,
+        SpecsStream, DEFAULT_SPECS_CHUNK_SIZE,
+    },
 };
+use parking_lot::Mutex;
+use std::{collections::HashMap, convert::TryFrom, sync::Arc};
 
 /// Registry Service
 #[derive(Debug, Clone)]
@@ -26,6 +47,90 @@ impl RegistryOperations for Service {
         let specs = self.get_specs(&req).await?;
         Ok(specs)
     }
+
+    async fn get_filtered_specs(
+        &self,
+        filter: Filter,
+        _ctx: Option<Context>,
+    ) -> Result<Specs, ReplyError> {
+        let specs = self.get_filtered_specs(&filter).await?;
+        Ok(specs)
+    }
+
+    async fn list_specs_stream(
+        &self,
+        chunk_size: Option<usize>,
+        _ctx: Option<Context>,
+    ) -> Result<SpecsStream, ReplyError> {
+        let specs = self.get_specs(&GetSpecs {}).await?;
+        let chunks = specs.into_chunks(chunk_size.unwrap_or(DEFAULT_SPECS_CHUNK_SIZE));
+        Ok(Box::pin(futures::stream::iter(chunks.into_iter().map(Ok))))
+    }
+
+    async fn get_rebuild_limit(&self, _ctx: Option<Context>) -> Result<RebuildLimit, ReplyError> {
+        let limit = self.get_rebuild_limit().await?;
+        Ok(limit)
+    }
+
+    async fn set_rebuild_limit(
+        &self,
+        req: &dyn SetRebuildLimitInfo,
+        _ctx: Option<Context>,
+    ) -> Result<RebuildLimit, ReplyError> {
+        let req = req.into();
+        let limit = self.set_rebuild_limit(&req).await?;
+        Ok(limit)
+    }
+
+    async fn check_orphaned_replicas(
+        &self,
+        req: &dyn CheckOrphanedReplicasInfo,
+        _ctx: Option<Context>,
+    ) -> Result<OrphanedReplicas, ReplyError> {
+        let req = req.into();
+        let replicas = self.check_orphaned_replicas(&req).await?;
+        Ok(replicas)
+    }
+
+    async fn get_events(
+        &self,
+        filter: Filter,
+        _ctx: Option<Context>,
+    ) -> Result<Events, ReplyError> {
+        let events = self.get_events(&GetEvents::new(filter)).await?;
+        Ok(events)
+    }
+
+    async fn get_operations(
+        &self,
+        req: &GetOperations,
+        _ctx: Option<Context>,
+    ) -> Result<PendingOperations, ReplyError> {
+        let operations = self.get_operations(req).await?;
+        Ok(operations)
+    }
+
+    async fn clear_operation(
+        &self,
+        req: &ClearOperation,
+        _ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        audit::grpc(req, self.clear_operation(req).await)?;
+        Ok(())
+    }
+
+    async fn reconcile_resource(
+        &self,
+        req: &ReconcileResource,
+        _ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        audit::grpc(req, self.reconcile_resource(req).await)?;
+        Ok(())
+    }
+
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        Ok(self.registry.store_online().await)
+    }
 }
 
 impl Service {
@@ -48,4 +153,336 @@ impl Service {
             pools: specs.get_pools(),
         })
     }
+
+    /// Get the specs related to the resource of the filter from the registry:
+    /// Node: the pools and nexuses on the node, the replicas of those pools and the volumes
+    /// which have their target or a replica on the node.
+    /// Pool: the pool, its replicas and the volumes and nexuses which own them.
+    /// Volume: the volume, its nexuses and replicas and the pools of the replicas.
+    /// Only the matching specs are cloned out of the registry.
+    pub(crate) async fn get_filtered_specs(&self, filter: &Filter) -> Result<Specs, SvcError> {
+        let specs = self.specs().read();
+        let pools = specs.pools.values().map(|p| p.lock());
+        let replicas = specs.replicas.values().map(|r| r.lock());
+        let nexuses = specs.nexuses.values().map(|n| n.lock());
+        let volumes = specs.volumes.values().map(|v| v.lock());
+
+        let filtered = match filter {
+            Filter::Node(node) => {
+                let pools = pools.filter(|p| &p.node == node).map(|p| p.clone());
+                let pools = pools.collect::<Vec<_>>();
+                let replicas = replicas
+                    .filter(|r| pools.iter().any(|p| p.id == r.pool))
+                    .map(|r| r.clone())
+                    .collect::<Vec<_>>();
+                Specs {
+                    volumes: volumes
+                        .filter(|v| {
+                            v.target.as_ref().map(|t| t.node()) == Some(node)
+                                || replicas.iter().any(|r| r.owners.owned_by(&v.uuid))
+                        })
+                        .map(|v| v.clone())
+                        .collect(),
+                    nexuses: nexuses
+                        .filter(|n| &n.node == node)
+                        .map(|n| n.clone())
+                        .collect(),
+                    pools,
+                    replicas,
+                }
+            }
+            Filter::Pool(pool) => {
+                let replicas = replicas
+                    .filter(|r| &r.pool == pool)
+                    .map(|r| r.clone())
+                    .collect::<Vec<_>>();
+                Specs {
+                    volumes: volumes
+                        .filter(|v| replicas.iter().any(|r| r.owners.owned_by(&v.uuid)))
+                        .map(|v| v.clone())
+                        .collect(),
+                    nexuses: nexuses
+                        .filter(|n| replicas.iter().any(|r| n.contains_replica(&r.uuid)))
+                        .map(|n| n.clone())
+                        .collect(),
+                    pools: pools.filter(|p| &p.id == pool).map(|p| p.clone()).collect(),
+                    replicas,
+                }
+            }
+            Filter::Volume(volume) => {
+                let replicas = replicas
+                    .filter(|r| r.owners.owned_by(volume))
+                    .map(|r| r.clone())
+                    .collect::<Vec<_>>();
+                Specs {
+                    volumes: volumes
+                        .filter(|v| &v.uuid == volume)
+                        .map(|v| v.clone())
+                        .collect(),
+                    nexuses: nexuses
+                        .filter(|n| n.owner.as_ref() == Some(volume))
+                        .map(|n| n.clone())
+                        .collect(),
+                    pools: pools
+                        .filter(|p| replicas.iter().any(|r| r.pool == p.id))
+                        .map(|p| p.clone())
+                        .collect(),
+                    replicas,
+                }
+            }
+            _ => {
+                return Err(SvcError::InvalidFilter {
+                    filter: filter.clone(),
+                })
+            }
+        };
+        Ok(filtered)
+    }
+
+    /// Get the system-wide limit on the number of concurrent rebuilds
+    pub(crate) async fn get_rebuild_limit(&self) -> Result<RebuildLimit, SvcError> {
+        Ok(RebuildLimit {
+            max_rebuilds: self.registry.max_rebuilds(),
+            num_rebuilds: self.registry.num_rebuilds().await,
+        })
+    }
+
+    /// Set the system-wide limit on the number of concurrent rebuilds
+    #[tracing::instrument(level = "info", skip(self), err)]
+    pub(crate) async fn set_rebuild_limit(
+        &self,
+        request: &SetRebuildLimit,
+    ) -> Result<RebuildLimit, SvcError> {
+        self.registry.set_max_rebuilds(request.max_rebuilds).await?;
+        self.get_rebuild_limit().await
+    }
+
+    /// Find the replicas which were created by the control-plane but are no longer referenced
+    /// by any replica or nexus spec nor by any nexus, and optionally destroy them
+    #[tracing::instrument(level = "info", skip(self), err)]
+    pub(crate) async fn check_orphaned_replicas(
+        &self,
+        request: &CheckOrphanedReplicas,
+    ) -> Result<OrphanedReplicas, SvcError> {
+        let nexuses = self.registry.get_nexuses().await;
+        let replicas = self.registry.get_replicas().await;
+        let orphans = {
+            let specs = self.specs().read();
+            let nexus_specs = specs.nexuses.values().map(|n| n.lock().clone());
+            let nexus_specs = nexus_specs.collect::<Vec<_>>();
+            let is_orphan = |replica: &Replica| {
+                let uri = ChildUri::from(replica.uri.as_str());
+                replica.name == ReplicaName::from_uuid(&replica.uuid)
+                    && specs.replicas.get(&replica.uuid).is_none()
+                    && !nexus_specs
+                        .iter()
+                        .any(|n| n.children.iter().any(|c| c.uri() == uri))
+                    && !nexuses
+                        .iter()
+                        .any(|n| n.children.iter().any(|c| c.uri == uri))
+            };
+            replicas.into_iter().filter(is_orphan).collect::<Vec<_>>()
+        };
+
+        let mut entries = Vec::with_capacity(orphans.len());
+        for replica in orphans {
+            let destroyed = request.destroy && self.destroy_orphaned_replica(&replica).await;
+            entries.push(OrphanedReplica { replica, destroyed });
+        }
+        Ok(OrphanedReplicas { entries })
+    }
+
+    /// Get the recent control-plane events about the resource of the filter, oldest first
+    pub(crate) async fn get_events(&self, request: &GetEvents) -> Result<Events, SvcError> {
+        let events = self.registry.get_events(&request.filter)?;
+        Ok(Events(events))
+    }
+
+    /// Get the operations which were started on the resource specs but which haven't completed,
+    /// optionally only those of the referenced resource or, for a node, of the resources on it
+    pub(crate) async fn get_operations(
+        &self,
+        request: &GetOperations,
+    ) -> Result<PendingOperations, SvcError> {
+        if let Some(resource) = &request.resource {
+            self.resource_spec(resource)?;
+        }
+        let specs = self.specs().read();
+        let pool_nodes = specs
+            .pools
+            .values()
+            .map(|p| {
+                let pool = p.lock();
+                (pool.id.clone(), pool.node.clone())
+            })
+            .collect::<HashMap<_, _>>();
+        let resource = |kind, id: String, node| ResourceRef { kind, id, node };
+
+        let mut entries = vec![];
+        for pool in specs.pools.values() {
+            let pool = pool.lock();
+            if let Some(op) = &pool.operation {
+                let node = Some(pool.node.clone());
+                let pool_ref = resource(ResourceKind::Pool, pool.id.to_string(), node);
+                entries.push(PendingOperation::new(pool_ref, &op.operation, op.result));
+            }
+        }
+        for replica in specs.replicas.values() {
+            let replica = replica.lock();
+            if let Some(op) = &replica.operation {
+                let node = pool_nodes.get(&replica.pool).cloned();
+                let replica_ref = resource(ResourceKind::Replica, replica.uuid.to_string(), node);
+                entries.push(PendingOperation::new(replica_ref, &op.operation, op.result));
+            }
+        }
+        for nexus in specs.nexuses.values() {
+            let nexus = nexus.lock();
+            if let Some(op) = &nexus.operation {
+                let node = Some(nexus.node.clone());
+                let nexus_ref = resource(ResourceKind::Nexus, nexus.uuid.to_string(), node);
+                entries.push(PendingOperation::new(nexus_ref, &op.operation, op.result));
+            }
+        }
+        for volume in specs.volumes.values() {
+            let volume = volume.lock();
+            if let Some(op) = &volume.operation {
+                let volume_ref = resource(ResourceKind::Volume, volume.uuid.to_string(), None);
+                entries.push(PendingOperation::new(volume_ref, &op.operation, op.result));
+            }
+        }
+
+        if let Some(resource) = &request.resource {
+            entries.retain(|entry| match resource.kind {
+                ResourceKind::Node => {
+                    entry.resource.node.as_ref().map(NodeId::as_str) == Some(resource.id.as_str())
+                }
+                _ => entry.resource.kind == resource.kind && entry.resource.id == resource.id,
+            });
+        }
+        Ok(PendingOperations { entries })
+    }
+
+    /// Clear the pending operation of the referenced resource, if any, leaving its spec as it
+    /// was before the operation started
+    #[tracing::instrument(level = "info", skip(self), err)]
+    pub(crate) async fn clear_operation(&self, request: &ClearOperation) -> Result<(), SvcError> {
+        let registry = &self.registry;
+        match self.resource_spec(&request.resource)? {
+            ResourceSpec::Node => Err(SvcError::NoOperations {
+                kind: ResourceKind::Node,
+                id: request.resource.id.clone(),
+                request: "clear".to_string(),
+            }),
+            ResourceSpec::Pool(pool) => SpecOperations::clear_pending_op(&pool, registry).await,
+            ResourceSpec::Replica(replica) => {
+                SpecOperations::clear_pending_op(&replica, registry).await
+            }
+            ResourceSpec::Nexus(nexus) => SpecOperations::clear_pending_op(&nexus, registry).await,
+            ResourceSpec::Volume(volume) => {
+                SpecOperations::clear_pending_op(&volume, registry).await
+            }
+        }
+    }
+
+    /// Reconcile the referenced resource now, rather than waiting for the next reconcile period
+    #[tracing::instrument(level = "info", skip(self), err)]
+    pub(crate) async fn reconcile_resource(
+        &self,
+        request: &ReconcileResource,
+    ) -> Result<(), SvcError> {
+        self.resource_spec(&request.resource)?;
+        self.registry
+            .notify(PollTriggerEvent::ReconcileRequested)
+            .await;
+        Ok(())
+    }
+
+    /// Get the spec of the referenced resource, which must be on the node of the reference,
+    /// if one is specified
+    fn resource_spec(&self, resource: &ResourceRef) -> Result<ResourceSpec, SvcError> {
+        let not_found = || SvcError::NotFound {
+            kind: resource.kind.clone(),
+            id: resource.to_string(),
+        };
+        let on_node = |node: &NodeId| match &resource.node {
+            Some(resource_node) => resource_node == node,
+            None => true,
+        };
+        let specs = self.specs();
+        let id = resource.id.as_str();
+        let spec = match resource.kind {
+            ResourceKind::Node => {
+                specs.get_locked_node(&NodeId::from(id))?;
+                ResourceSpec::Node
+            }
+            ResourceKind::Pool => {
+                let pool = specs.get_locked_pool(&PoolId::from(id));
+                let pool = pool.filter(|p| on_node(&p.lock().node));
+                ResourceSpec::Pool(pool.ok_or_else(not_found)?)
+            }
+            ResourceKind::Replica => {
+                let replica_id = ReplicaId::try_from(id).map_err(|_| not_found())?;
+                let replica = specs.get_replica(&replica_id).ok_or_else(not_found)?;
+                if resource.node.is_some() {
+                    let pool_id = replica.lock().pool.clone();
+                    let pool = specs.get_locked_pool(&pool_id);
+                    if !pool.map(|p| on_node(&p.lock().node)).unwrap_or(false) {
+                        return Err(not_found());
+                    }
+                }
+                ResourceSpec::Replica(replica)
+            }
+            ResourceKind::Nexus => {
+                let nexus_id = NexusId::try_from(id).map_err(|_| not_found())?;
+                let nexus = specs.get_nexus(&nexus_id);
+                let nexus = nexus.filter(|n| on_node(&n.lock().node));
+                ResourceSpec::Nexus(nexus.ok_or_else(not_found)?)
+            }
+            ResourceKind::Volume => {
+                let volume_id = VolumeId::try_from(id).map_err(|_| not_found())?;
+                let volume = specs.get_locked_volume(&volume_id);
+                ResourceSpec::Volume(volume.ok_or_else(not_found)?)
+            }
+            _ => return Err(not_found()),
+        };
+        Ok(spec)
+    }
+
+    async fn destroy_orphaned_replica(&self, replica: &Replica) -> bool {
+        let request = DestroyReplica {
+            node: replica.node.clone(),
+            pool: replica.pool.clone(),
+            uuid: replica.uuid.clone(),
+            name: Some(replica.name.clone()),
+            disowners: Default::default(),
+            force: false,
+        };
+        let result = match self.registry.get_node_wrapper(&replica.node).await {
+            Ok(node) => node.destroy_replica(&request).await,
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(_) => {
+                tracing::info!(replica.uuid = %replica.uuid, "Destroyed orphaned replica");
+                true
+            }
+            Err(error) => {
+                tracing::error!(
+                    replica.uuid = %replica.uuid,
+                    error = %error,
+                    "Failed to destroy orphaned replica"
+                );
+                false
+            }
+        }
+    }
+}
+
+/// The spec of a resource referenced by a `ResourceRef`
+enum ResourceSpec {
+    Node,
+    Pool(Arc<Mutex<PoolSpec>>),
+    Replica(Arc<Mutex<ReplicaSpec>>),
+    Nexus(Arc<Mutex<NexusSpec>>),
+    Volume(Arc<Mutex<VolumeSpec>>),
 }
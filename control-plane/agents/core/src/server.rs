@@ -12,10 +12,13 @@ use common_lib::types::v0::message_bus::ChannelVs;
 use http::Uri;
 
 use crate::core::registry::NumRebuilds;
-use common_lib::mbus_api::BusClient;
+use common_lib::{
+    mbus_api::{BusClient, BusConnectOptions, BusSecret},
+    store::etcd::{StoreConnectOptions, StoreRetryPolicy},
+};
 use opentelemetry::{global, KeyValue};
 use structopt::StructOpt;
-use utils::{version_info_str, DEFAULT_GRPC_SERVER_ADDR};
+use utils::{tracing_telemetry::LogFormat, version_info_str, DEFAULT_GRPC_SERVER_ADDR};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = utils::package_description!(), version = version_info_str!())]
@@ -25,6 +28,48 @@ pub(crate) struct CliArgs {
     #[structopt(long, short)]
     pub(crate) nats: Option<String>,
 
+    /// The username used to authenticate with the Nats Server.
+    /// Requires `--nats-password`
+    #[structopt(long, requires = "nats-password")]
+    pub(crate) nats_user: Option<String>,
+
+    /// The password used to authenticate with the Nats Server.
+    /// Requires `--nats-user`
+    #[structopt(
+        long,
+        env = "NATS_PASSWORD",
+        hide_env_values = true,
+        requires = "nats-user"
+    )]
+    pub(crate) nats_password: Option<BusSecret>,
+
+    /// The token used to authenticate with the Nats Server
+    #[structopt(long, env = "NATS_TOKEN", hide_env_values = true)]
+    pub(crate) nats_token: Option<BusSecret>,
+
+    /// The Nats credentials file, holding the user JWT and NKey seed, used to authenticate with
+    /// the Nats Server
+    #[structopt(long)]
+    pub(crate) nats_creds: Option<std::path::PathBuf>,
+
+    /// The PEM encoded CA certificate used to verify the Nats Server's certificate
+    #[structopt(long)]
+    pub(crate) nats_ca: Option<std::path::PathBuf>,
+
+    /// The PEM encoded client certificate used to authenticate with the Nats Server.
+    /// Requires `--nats-key`
+    #[structopt(long, requires = "nats-key")]
+    pub(crate) nats_cert: Option<std::path::PathBuf>,
+
+    /// The PEM encoded client key used to authenticate with the Nats Server.
+    /// Requires `--nats-cert`
+    #[structopt(long, requires = "nats-cert")]
+    pub(crate) nats_key: Option<std::path::PathBuf>,
+
+    /// Connect to the Nats Server over TLS without verifying its certificate (for testing)
+    #[structopt(long)]
+    pub(crate) nats_insecure_skip_verify: bool,
+
     /// The period at which the registry updates its cache of all
     /// resources from all nodes
     #[structopt(long, short, default_value = utils::CACHE_POLL_PERIOD)]
@@ -38,10 +83,21 @@ pub(crate) struct CliArgs {
     #[structopt(long, default_value = "10s")]
     pub(crate) reconcile_period: humantime::Duration,
 
+    /// The period for which a nexus child must be continuously faulted before the reconcile loop
+    /// removes it from its nexus
+    #[structopt(long, default_value = "0s")]
+    pub(crate) faulted_child_grace_period: humantime::Duration,
+
     /// Deadline for the io-engine instance keep alive registration
     #[structopt(long, short, default_value = "10s")]
     pub(crate) deadline: humantime::Duration,
 
+    /// The number of consecutive keep alive deadlines an io-engine instance may miss before it's
+    /// deemed offline. Until then it's deemed suspect, though still treated as online, so that
+    /// transient connectivity issues don't trigger the reconcilers
+    #[structopt(long, default_value = "1")]
+    pub(crate) offline_misses: u32,
+
     /// The Persistent Store URLs to connect to
     /// (supports the http/https schema)
     #[structopt(long, short, default_value = "http://localhost:2379")]
@@ -55,6 +111,30 @@ pub(crate) struct CliArgs {
     #[structopt(long, default_value = utils::STORE_LEASE_LOCK_TTL)]
     pub(crate) store_lease_ttl: humantime::Duration,
 
+    /// Override the prefix of all the keys in the persistent store, which allows multiple
+    /// control planes to share the same persistent store
+    #[structopt(long, env = "STORE_KEY_PREFIX", parse(try_from_str = common_lib::parse_etcd_key_prefix))]
+    pub(crate) store_key_prefix: Option<String>,
+
+    /// The PEM encoded CA certificate used to verify the Persistent Store's certificate
+    #[structopt(long)]
+    pub(crate) store_ca: Option<std::path::PathBuf>,
+
+    /// The PEM encoded client certificate used to authenticate with the Persistent Store.
+    /// Requires `--store-key`
+    #[structopt(long, requires = "store-key")]
+    pub(crate) store_cert: Option<std::path::PathBuf>,
+
+    /// The PEM encoded client key used to authenticate with the Persistent Store.
+    /// Requires `--store-cert`
+    #[structopt(long, requires = "store-cert")]
+    pub(crate) store_key: Option<std::path::PathBuf>,
+
+    /// The maximum number of times a Persistent Store operation is retried when it fails due to
+    /// a transient connection error, eg: while the store is electing a new leader
+    #[structopt(long, default_value = "3")]
+    pub(crate) store_retries: u32,
+
     /// The timeout for every node connection (gRPC)
     #[structopt(long, default_value = utils::DEFAULT_CONN_TIMEOUT)]
     pub(crate) connect_timeout: humantime::Duration,
@@ -73,14 +153,60 @@ pub(crate) struct CliArgs {
     /// Trace rest requests to the Jaeger endpoint agent
     #[structopt(long, short)]
     jaeger: Option<String>,
+
+    /// The format of the logs: text or json.
+    #[structopt(long, env = "LOG_FORMAT", default_value = "text")]
+    log_format: LogFormat,
     /// The GRPC Server URLs to connect to
     /// (supports the http/https schema)
     #[structopt(long, short, default_value = DEFAULT_GRPC_SERVER_ADDR)]
     pub(crate) grpc_server_addr: Uri,
     /// The maximum number of system-wide rebuilds permitted at any given time.
     /// If `None` do not limit the number of rebuilds.
+    /// Ignored if the maximum was since changed at runtime, as that change is persisted.
     #[structopt(long)]
     max_rebuilds: Option<NumRebuilds>,
+
+    /// The pool utilization (%) above which volume replicas are moved to less utilized pools.
+    /// If `None` do not rebalance the volume replicas.
+    #[structopt(long, parse(try_from_str = parse_percentage))]
+    rebalance_threshold: Option<u8>,
+
+    /// The maximum number of lifecycle events retained in each volume's event history.
+    /// The oldest events are evicted first.
+    #[structopt(long, default_value = "32")]
+    volume_event_history: usize,
+
+    /// The maximum number of specs loaded from the persistent store in a single batch when
+    /// initialising the registry. 0 loads all specs of each type in a single batch.
+    #[structopt(long, default_value = "500")]
+    store_init_batch_size: u32,
+
+    /// The maximum number of capacity usage samples retained for each pool, taken at every
+    /// cache period, from which the pool capacity forecasts are computed.
+    /// 0 disables the capacity forecasts.
+    #[structopt(long, default_value = "60")]
+    pool_usage_samples: usize,
+
+    /// The percentage of each pool's capacity which is kept free when placing new replicas, so
+    /// that pools don't fill up completely. Pools may override it when they're created.
+    #[structopt(long, default_value = "0")]
+    reserved_capacity_percent: u8,
+
+    /// The maximum number of recent control-plane events retained in memory, eg: resources being
+    /// created or destroyed. The oldest events are evicted first. 0 disables the events.
+    #[structopt(long, default_value = "1024")]
+    event_history: usize,
+
+    /// Audit the mutating requests, recording who requested what, on which resource and with
+    /// which outcome. The records are either logged with the `audit` target ('log') or appended
+    /// as json lines to the given file path.
+    #[structopt(long)]
+    audit_log: Option<core::audit::AuditTarget>,
+
+    /// The maximum time to wait on shutdown for the in-flight operations to complete.
+    #[structopt(long, default_value = "20s")]
+    shutdown_timeout: humantime::Duration,
 }
 impl CliArgs {
     fn args() -> Self {
@@ -88,31 +214,81 @@ impl CliArgs {
     }
 }
 
+/// Parse a percentage, which must not be greater than 100
+fn parse_percentage(source: &str) -> Result<u8, String> {
+    match source.parse::<u8>() {
+        Ok(percentage) if percentage <= 100 => Ok(percentage),
+        Ok(percentage) => Err(format!("'{}' is greater than 100%", percentage)),
+        Err(error) => Err(format!("'{}' is not a valid percentage: {}", source, error)),
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let cli_args = CliArgs::args();
     utils::print_package_info!();
     println!("Using options: {:?}", &cli_args);
-    utils::tracing_telemetry::init_tracing(
+    utils::tracing_telemetry::init_tracing_format(
         "core-agent",
         cli_args.tracing_tags.clone(),
         cli_args.jaeger.clone(),
+        None,
+        cli_args.log_format,
     );
     server(cli_args).await;
 }
 
 async fn server(cli_args: CliArgs) {
     common_lib::init_cluster_info_or_panic().await;
+    if let Some(target) = &cli_args.audit_log {
+        core::audit::init(target).expect("Should be able to open the audit log");
+    }
+    let store_options = StoreConnectOptions::new(
+        cli_args.store_ca.clone(),
+        cli_args.store_cert.clone(),
+        cli_args.store_key.clone(),
+    )
+    .expect("Should have a valid persistent store TLS configuration")
+    .with_retry_policy(StoreRetryPolicy::new(
+        cli_args.store_retries,
+        std::time::Duration::from_millis(100),
+    ));
     let registry = core::registry::Registry::new(
         cli_args.cache_period.into(),
         cli_args.store.clone(),
+        store_options,
         cli_args.store_timeout.into(),
         cli_args.store_lease_ttl.into(),
+        cli_args.store_key_prefix.clone(),
         cli_args.reconcile_period.into(),
         cli_args.reconcile_idle_period.into(),
+        cli_args.faulted_child_grace_period.into(),
         cli_args.max_rebuilds,
+        cli_args.rebalance_threshold,
+        cli_args.volume_event_history,
+        cli_args.store_init_batch_size,
+        cli_args.pool_usage_samples,
+        cli_args.reserved_capacity_percent,
+        cli_args.event_history,
+    )
+    .await
+    .expect("Should have a valid persistent store configuration");
+
+    let bus_options = BusConnectOptions::new(
+        cli_args.nats_user.clone(),
+        cli_args.nats_password.clone(),
+        cli_args.nats_token.clone(),
+        cli_args.nats_creds.clone(),
     )
-    .await;
+    .and_then(|options| {
+        options.with_tls(
+            cli_args.nats_ca.clone(),
+            cli_args.nats_cert.clone(),
+            cli_args.nats_key.clone(),
+            cli_args.nats_insecure_skip_verify,
+        )
+    })
+    .expect("Should have valid message bus credentials and TLS configuration");
 
     let base_service = common::Service::builder(cli_args.nats.clone(), ChannelVs::Core)
         .with_shared_state(global::tracer_with_version(
@@ -120,6 +296,7 @@ async fn server(cli_args: CliArgs) {
             env!("CARGO_PKG_VERSION"),
         ))
         .with_default_liveness()
+        .with_bus_options(bus_options)
         .connect_message_bus(cli_args.no_min_timeouts, BusClient::CoreAgent)
         .await
         .with_shared_state(registry.clone())
@@ -134,7 +311,10 @@ async fn server(cli_args: CliArgs) {
 
     let service = lib::Service::new(base_service);
     registry.start().await;
-    service.run().await;
+    let shutdown_timeout = cli_args.shutdown_timeout.into();
+    service
+        .run(registry.drain(shutdown_timeout), shutdown_timeout)
+        .await;
     registry.stop().await;
     opentelemetry::global::shutdown_tracer_provider();
 }
@@ -159,7 +339,9 @@ macro_rules! impl_request_handler {
                 ) -> Result<<$RequestType as Message>::Reply, SvcError> {
                     let request: ReceivedMessage<$RequestType> = args.request.try_into()?;
                     let service: &service::Service = args.context.get_state()?;
-                    match service.$ServiceFnName(&request.inner()).await {
+                    let result = service.$ServiceFnName(&request.inner()).await;
+                    $crate::core::audit::record(&request.sender(), &request.inner(), &result);
+                    match result {
                         Ok(reply) => {
                             if let Ok(result_str) = serde_json::to_string(&reply) {
                                 if result_str.len() < 2048 {
@@ -35,11 +35,20 @@ impl Registry {
             .collect::<Vec<_>>();
 
         let nexus_spec = self.specs().get_volume_target_nexus(volume_spec);
-        let nexus_state = match nexus_spec {
-            None => None,
+        let (nexus_state, target_refreshed) = match nexus_spec {
+            None => (None, None),
             Some(spec) => {
-                let nexus_id = spec.lock().uuid.clone();
-                self.get_nexus(&nexus_id).await.ok()
+                let (nexus_id, nexus_node) = {
+                    let spec = spec.lock();
+                    (spec.uuid.clone(), spec.node.clone())
+                };
+                // the target state may be stale (or missing) if its node is not online, so let
+                // the caller know when it was last refreshed
+                let target_refreshed = match self.get_node_wrapper(&nexus_node).await {
+                    Ok(node) => node.read().await.nexus_states_refreshed(),
+                    Err(_) => None,
+                };
+                (self.get_nexus(&nexus_id).await.ok(), target_refreshed)
             }
         };
 
@@ -66,6 +75,7 @@ impl Registry {
                 },
                 target: Some(nexus_state),
                 replica_topology,
+                target_refreshed,
             }
         } else {
             VolumeState {
@@ -84,6 +94,7 @@ impl Registry {
                 },
                 target: None,
                 replica_topology,
+                target_refreshed,
             }
         })
     }
@@ -10,20 +10,19 @@ use crate::core::{
     },
     wrapper::PoolWrapper,
 };
-use common::errors::SvcError;
+use common::errors::{PoolRejection, SvcError};
 use common_lib::types::v0::store::{nexus::NexusSpec, volume::VolumeSpec};
 
-/// Return a list of pre sorted pools to be used by a volume
+/// Return a list of pre sorted pools to be used by a volume, along with the pools which were
+/// rejected and why
 pub(crate) async fn get_volume_pool_candidates(
     request: impl Into<GetSuitablePools>,
     registry: &Registry,
-) -> Vec<PoolWrapper> {
-    volume::AddVolumeReplica::builder_with_defaults(request, registry)
+) -> (Vec<PoolWrapper>, Vec<PoolRejection>) {
+    let (pools, rejected) = volume::AddVolumeReplica::builder_with_defaults(request, registry)
         .await
-        .collect()
-        .into_iter()
-        .map(|e| e.collect())
-        .collect()
+        .collect_with_rejected();
+    (pools.into_iter().map(|e| e.collect()).collect(), rejected)
 }
 
 /// Return a volume child candidate to be removed from a volume
@@ -1,11 +1,13 @@
-use crate::core::{registry::Registry, specs::ResourceSpecsLocked};
+use crate::core::{audit, registry::Registry, specs::ResourceSpecsLocked};
 use common::errors::SvcError;
 use common_lib::{
     mbus_api::{message_bus::v0::Volumes, ReplyError},
     types::v0::{
         message_bus::{
-            CreateVolume, DestroyVolume, Filter, GetVolumes, PublishVolume, SetVolumeReplica,
-            ShareVolume, UnpublishVolume, UnshareVolume, Volume,
+            AddVolumeReplica, CreateSnapshot, CreateVolume, DeleteSnapshot, DestroyVolume,
+            ExpandVolume, Filter, GetVolumes, ListSnapshots, PublishVolume, RemoveVolumeReplica,
+            RepublishVolume, SetVolumeLabels, SetVolumeReplica, ShareVolume, UnpublishVolume,
+            UnshareVolume, Volume, VolumeSnapshot, VolumeSnapshots,
         },
         store::OperationMode,
     },
@@ -14,10 +16,12 @@ use grpc::{
     context::Context,
     operations::{
         volume::traits::{
-            CreateVolumeInfo, DestroyVolumeInfo, PublishVolumeInfo, SetVolumeReplicaInfo,
-            ShareVolumeInfo, UnpublishVolumeInfo, UnshareVolumeInfo, VolumeOperations,
+            CreateSnapshotInfo, CreateVolumeInfo, DeleteSnapshotInfo, DestroyVolumeInfo,
+            ExpandVolumeInfo, ListSnapshotsInfo, PublishVolumeInfo, RepublishVolumeInfo,
+            SetVolumeLabelsInfo, SetVolumeReplicaInfo, ShareVolumeInfo, UnpublishVolumeInfo,
+            UnshareVolumeInfo, VolumeOperations, VolumeReplicaInfo,
         },
-        Pagination,
+        PaginatedResult, Pagination,
     },
 };
 
@@ -35,8 +39,10 @@ impl VolumeOperations for Service {
     ) -> Result<Volume, ReplyError> {
         let create_volume = req.into();
         let service = self.clone();
-        let volume =
-            Context::spawn(async move { service.create_volume(&create_volume).await }).await??;
+        let volume = Context::spawn(async move {
+            audit::grpc(&create_volume, service.create_volume(&create_volume).await)
+        })
+        .await??;
         Ok(volume)
     }
 
@@ -58,7 +64,13 @@ impl VolumeOperations for Service {
     ) -> Result<(), ReplyError> {
         let destroy_volume = req.into();
         let service = self.clone();
-        Context::spawn(async move { service.destroy_volume(&destroy_volume).await }).await??;
+        Context::spawn(async move {
+            audit::grpc(
+                &destroy_volume,
+                service.destroy_volume(&destroy_volume).await,
+            )
+        })
+        .await??;
         Ok(())
     }
 
@@ -69,8 +81,10 @@ impl VolumeOperations for Service {
     ) -> Result<String, ReplyError> {
         let share_volume = req.into();
         let service = self.clone();
-        let response =
-            Context::spawn(async move { service.share_volume(&share_volume).await }).await??;
+        let response = Context::spawn(async move {
+            audit::grpc(&share_volume, service.share_volume(&share_volume).await)
+        })
+        .await??;
         Ok(response)
     }
 
@@ -81,7 +95,13 @@ impl VolumeOperations for Service {
     ) -> Result<(), ReplyError> {
         let unshare_volume = req.into();
         let service = self.clone();
-        Context::spawn(async move { service.unshare_volume(&unshare_volume).await }).await??;
+        Context::spawn(async move {
+            audit::grpc(
+                &unshare_volume,
+                service.unshare_volume(&unshare_volume).await,
+            )
+        })
+        .await??;
         Ok(())
     }
 
@@ -92,8 +112,30 @@ impl VolumeOperations for Service {
     ) -> Result<Volume, ReplyError> {
         let publish_volume = req.into();
         let service = self.clone();
-        let volume =
-            Context::spawn(async move { service.publish_volume(&publish_volume).await }).await??;
+        let volume = Context::spawn(async move {
+            audit::grpc(
+                &publish_volume,
+                service.publish_volume(&publish_volume).await,
+            )
+        })
+        .await??;
+        Ok(volume)
+    }
+
+    async fn republish(
+        &self,
+        req: &dyn RepublishVolumeInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let republish_volume = req.into();
+        let service = self.clone();
+        let volume = Context::spawn(async move {
+            audit::grpc(
+                &republish_volume,
+                service.republish_volume(&republish_volume).await,
+            )
+        })
+        .await??;
         Ok(volume)
     }
 
@@ -104,9 +146,13 @@ impl VolumeOperations for Service {
     ) -> Result<Volume, ReplyError> {
         let unpublish_volume = req.into();
         let service = self.clone();
-        let volume =
-            Context::spawn(async move { service.unpublish_volume(&unpublish_volume).await })
-                .await??;
+        let volume = Context::spawn(async move {
+            audit::grpc(
+                &unpublish_volume,
+                service.unpublish_volume(&unpublish_volume).await,
+            )
+        })
+        .await??;
         Ok(volume)
     }
 
@@ -117,12 +163,125 @@ impl VolumeOperations for Service {
     ) -> Result<Volume, ReplyError> {
         let set_volume_replica = req.into();
         let service = self.clone();
-        let volume =
-            Context::spawn(async move { service.set_volume_replica(&set_volume_replica).await })
-                .await??;
+        let volume = Context::spawn(async move {
+            audit::grpc(
+                &set_volume_replica,
+                service.set_volume_replica(&set_volume_replica).await,
+            )
+        })
+        .await??;
+        Ok(volume)
+    }
+
+    async fn add_replica(
+        &self,
+        req: &dyn VolumeReplicaInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let add_volume_replica = req.into();
+        let service = self.clone();
+        let volume = Context::spawn(async move {
+            audit::grpc(
+                &add_volume_replica,
+                service.add_volume_replica(&add_volume_replica).await,
+            )
+        })
+        .await??;
+        Ok(volume)
+    }
+
+    async fn remove_replica(
+        &self,
+        req: &dyn VolumeReplicaInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let remove_replica = req.into();
+        let service = self.clone();
+        let volume = Context::spawn(async move {
+            audit::grpc(
+                &remove_replica,
+                service.remove_volume_replica(&remove_replica).await,
+            )
+        })
+        .await??;
+        Ok(volume)
+    }
+
+    async fn expand(
+        &self,
+        req: &dyn ExpandVolumeInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let expand_volume = req.into();
+        let service = self.clone();
+        let volume = Context::spawn(async move {
+            audit::grpc(&expand_volume, service.expand_volume(&expand_volume).await)
+        })
+        .await??;
+        Ok(volume)
+    }
+
+    async fn set_labels(
+        &self,
+        req: &dyn SetVolumeLabelsInfo,
+        _ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let set_volume_labels = req.into();
+        let service = self.clone();
+        let volume = Context::spawn(async move {
+            audit::grpc(
+                &set_volume_labels,
+                service.set_volume_labels(&set_volume_labels).await,
+            )
+        })
+        .await??;
         Ok(volume)
     }
 
+    async fn create_snapshot(
+        &self,
+        req: &dyn CreateSnapshotInfo,
+        _ctx: Option<Context>,
+    ) -> Result<VolumeSnapshot, ReplyError> {
+        let create_snapshot = req.into();
+        let service = self.clone();
+        let snapshot = Context::spawn(async move {
+            audit::grpc(
+                &create_snapshot,
+                service.create_snapshot(&create_snapshot).await,
+            )
+        })
+        .await??;
+        Ok(snapshot)
+    }
+
+    async fn list_snapshots(
+        &self,
+        req: &dyn ListSnapshotsInfo,
+        _ctx: Option<Context>,
+    ) -> Result<VolumeSnapshots, ReplyError> {
+        let list_snapshots = req.into();
+        let snapshots = self.list_snapshots(&list_snapshots).await?;
+        Ok(snapshots)
+    }
+
+    async fn delete_snapshot(
+        &self,
+        req: &dyn DeleteSnapshotInfo,
+        _ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        let delete_snapshot = req.into();
+        let service = self.clone();
+        Context::spawn(async move {
+            audit::grpc(
+                &delete_snapshot,
+                service.delete_snapshot(&delete_snapshot).await,
+            )
+        })
+        .await??;
+        Ok(())
+    }
+
     async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
         return Ok(true);
     }
@@ -160,6 +319,29 @@ impl Service {
                 tracing::Span::current().record("volume.uuid", &volume_id.as_str());
                 vec![self.registry.get_volume(volume_id).await?]
             }
+            Filter::VolumeAnnotations(annotations) => {
+                // Filter before paginating, so each page is filled with matching volumes.
+                let volumes = self
+                    .registry
+                    .get_volumes()
+                    .await
+                    .into_iter()
+                    .filter(|volume| {
+                        let volume_annotations = volume.spec().annotations;
+                        annotations
+                            .iter()
+                            .all(|(key, value)| volume_annotations.get(key) == Some(value))
+                    })
+                    .collect();
+                match &pagination {
+                    Some(p) => {
+                        let paginated_volumes = PaginatedResult::paginate(volumes, p);
+                        last_result = paginated_volumes.last();
+                        paginated_volumes.result()
+                    }
+                    None => volumes,
+                }
+            }
             filter => {
                 return Err(SvcError::InvalidFilter {
                     filter: filter.clone(),
@@ -216,6 +398,17 @@ impl Service {
             .await
     }
 
+    /// Republish volume
+    #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.uuid))]
+    pub(super) async fn republish_volume(
+        &self,
+        request: &RepublishVolume,
+    ) -> Result<Volume, SvcError> {
+        self.specs()
+            .republish_volume(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
     /// Unpublish volume
     #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.uuid))]
     pub(super) async fn unpublish_volume(
@@ -237,4 +430,73 @@ impl Service {
             .set_volume_replica(&self.registry, request, OperationMode::Exclusive)
             .await
     }
+
+    /// Add a replica to the volume
+    #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.uuid))]
+    pub(super) async fn add_volume_replica(
+        &self,
+        request: &AddVolumeReplica,
+    ) -> Result<Volume, SvcError> {
+        self.specs()
+            .add_volume_replica(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
+    /// Remove a replica from the volume
+    #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.uuid))]
+    pub(super) async fn remove_volume_replica(
+        &self,
+        request: &RemoveVolumeReplica,
+    ) -> Result<Volume, SvcError> {
+        self.specs()
+            .remove_volume_replica(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
+    /// Expand volume
+    #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.uuid))]
+    pub(super) async fn expand_volume(&self, request: &ExpandVolume) -> Result<Volume, SvcError> {
+        self.specs()
+            .expand_volume(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
+    /// Set volume labels
+    #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.uuid))]
+    pub(super) async fn set_volume_labels(
+        &self,
+        request: &SetVolumeLabels,
+    ) -> Result<Volume, SvcError> {
+        self.specs()
+            .set_volume_labels(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
+    /// Create volume snapshot
+    #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.volume_id))]
+    pub(super) async fn create_snapshot(
+        &self,
+        request: &CreateSnapshot,
+    ) -> Result<VolumeSnapshot, SvcError> {
+        self.specs()
+            .create_snapshot(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
+
+    /// List volume snapshots
+    #[tracing::instrument(level = "debug", skip(self), err, fields(volume.uuid = %request.volume_id))]
+    pub(super) async fn list_snapshots(
+        &self,
+        request: &ListSnapshots,
+    ) -> Result<VolumeSnapshots, SvcError> {
+        self.specs().list_snapshots(&request.volume_id)
+    }
+
+    /// Delete volume snapshot
+    #[tracing::instrument(level = "info", skip(self), err, fields(volume.uuid = %request.volume_id))]
+    pub(super) async fn delete_snapshot(&self, request: &DeleteSnapshot) -> Result<(), SvcError> {
+        self.specs()
+            .delete_snapshot(&self.registry, request, OperationMode::Exclusive)
+            .await
+    }
 }
@@ -11,14 +11,15 @@ use crate::{
             },
             ResourceFilter,
         },
-        specs::{ResourceSpecs, ResourceSpecsLocked, SpecOperations},
+        specs::{OperationSequenceGuard, ResourceSpecs, ResourceSpecsLocked, SpecOperations},
+        wrapper::ClientOps,
     },
     volume::scheduling,
 };
 use common::{
     errors,
     errors::{
-        NotEnough, SvcError,
+        NotEnough, PoolRejection, SvcError,
         SvcError::{ReplicaRemovalNoCandidates, VolumeNotFound},
     },
 };
@@ -26,19 +27,24 @@ use common_lib::{
     mbus_api::{ErrorChain, ResourceKind},
     types::v0::{
         message_bus::{
-            AddNexusReplica, ChildUri, CreateNexus, CreateReplica, CreateVolume, DestroyNexus,
-            DestroyReplica, DestroyVolume, Nexus, NexusId, NodeId, PoolId, Protocol, PublishVolume,
-            RemoveNexusReplica, Replica, ReplicaId, ReplicaName, ReplicaOwners, SetVolumeReplica,
-            ShareNexus, ShareVolume, UnpublishVolume, UnshareNexus, UnshareVolume, Volume,
-            VolumeId, VolumeShareProtocol, VolumeState, VolumeStatus,
+            AddNexusReplica, AddVolumeReplica, ChildState, ChildUri, CreateNexus, CreateReplica,
+            CreateSnapshot, CreateVolume, DeleteSnapshot, DestroyNexus, DestroyReplica,
+            DestroyVolume, Event, EventType, ExpandVolume, MoveReplica, Nexus, NexusId,
+            NexusNvmfConfig, NodeId, PoolId, Protocol, PublishVolume, RemoveNexusReplica,
+            RemoveVolumeReplica, Replica, ReplicaId, ReplicaName, ReplicaOwners, RepublishVolume,
+            ResizeNexus, ResizeReplica, SetVolumeLabels, SetVolumeReplica, ShareNexus, ShareVolume,
+            SnapshotId, UnpublishVolume, UnshareNexus, UnshareVolume, Volume, VolumeId,
+            VolumeShareProtocol, VolumeSnapshot, VolumeSnapshots, VolumeState, VolumeStatus,
         },
         store::{
             definitions::ObjectKey,
             nexus::{NexusSpec, ReplicaUri},
             nexus_child::NexusChild,
             nexus_persistence::NexusInfoKey,
+            node::ReplicaMove,
             replica::ReplicaSpec,
-            volume::{VolumeOperation, VolumeSpec},
+            snapshot::{VolumeSnapshotKey, VolumeSnapshotReplica, VolumeSnapshotSpec},
+            volume::{VolumeEvent, VolumeEventType, VolumeOperation, VolumeSpec},
             OperationMode, SpecStatus, SpecTransaction, TraceSpan, TraceStrLog,
         },
     },
@@ -46,7 +52,7 @@ use common_lib::{
 use grpc::operations::{PaginatedResult, Pagination};
 use parking_lot::Mutex;
 use snafu::OptionExt;
-use std::{convert::From, ops::Deref, sync::Arc};
+use std::{collections::HashSet, convert::From, ops::Deref, sync::Arc};
 
 /// Select a replica to be removed from the volume
 pub(crate) async fn get_volume_replica_remove_candidate(
@@ -114,9 +120,23 @@ pub(crate) async fn get_nexus_attach_candidates(
     nexus_spec: &NexusSpec,
     registry: &Registry,
 ) -> Result<Vec<ChildItem>, SvcError> {
-    let candidates = AddVolumeNexusReplicas::builder_with_defaults(vol_spec, nexus_spec, registry)
-        .await?
-        .collect();
+    let builder =
+        AddVolumeNexusReplicas::builder_with_defaults(vol_spec, nexus_spec, registry).await?;
+    if let Some(info) = builder.context().nexus_info() {
+        // without an authoritative child we can't tell which replicas hold the latest data, so
+        // adding any of them could silently regress the volume's data
+        if info.no_healthy_replicas() {
+            nexus_spec.error_span(|| {
+                tracing::error!(
+                    "No nexus child is authoritative, the latest data cannot be determined - manual intervention required"
+                )
+            });
+            return Err(SvcError::NoHealthyReplicas {
+                id: vol_spec.uuid.to_string(),
+            });
+        }
+    }
+    let candidates = builder.collect();
 
     nexus_spec.debug(&format!(
         "Nexus replica attach candidates: {:?}",
@@ -133,21 +153,35 @@ pub(crate) async fn get_volume_replica_candidates(
     registry: &Registry,
     request: impl Into<GetSuitablePools>,
 ) -> Result<Vec<CreateReplica>, SvcError> {
-    let request = request.into();
-    let pools = scheduling::get_volume_pool_candidates(request.clone(), registry).await;
+    let (candidates, rejected) = volume_replica_candidates(registry, request).await;
 
-    if pools.is_empty() {
+    if candidates.is_empty() {
         return Err(SvcError::NotEnoughResources {
-            source: NotEnough::OfPools { have: 0, need: 1 },
+            source: NotEnough::OfPools {
+                have: 0,
+                need: 1,
+                rejected,
+            },
         });
     }
+    Ok(candidates)
+}
+
+/// Return a list of appropriate requests which can be used to create a a replica on a pool,
+/// along with the pools which were rejected and why
+async fn volume_replica_candidates(
+    registry: &Registry,
+    request: impl Into<GetSuitablePools>,
+) -> (Vec<CreateReplica>, Vec<PoolRejection>) {
+    let request = request.into();
+    let (pools, rejected) = scheduling::get_volume_pool_candidates(request.clone(), registry).await;
 
     request.trace(&format!(
         "Creation pool candidates for volume: {:?}",
         pools.iter().map(|p| p.state()).collect::<Vec<_>>()
     ));
 
-    Ok(pools
+    let candidates = pools
         .iter()
         .map(|p| {
             let replica_uuid = ReplicaId::new();
@@ -161,9 +195,12 @@ pub(crate) async fn get_volume_replica_candidates(
                 share: Protocol::None,
                 managed: true,
                 owners: ReplicaOwners::from_volume(&request.uuid),
+                annotations: Default::default(),
+                encryption: request.encryption.clone(),
             }
         })
-        .collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    (candidates, rejected)
 }
 
 /// Return a list of appropriate requests which can be used to create a a replica on a pool
@@ -179,12 +216,13 @@ async fn get_create_volume_replicas(
         return Err(SvcError::InvalidArguments {});
     }
 
-    let node_replicas = get_volume_replica_candidates(registry, request).await?;
+    let (node_replicas, rejected) = volume_replica_candidates(registry, request).await;
 
     if request.replicas > node_replicas.len() as u64 {
         Err(SvcError::from(NotEnough::OfPools {
             have: node_replicas.len() as u64,
             need: request.replicas,
+            rejected,
         }))
     } else {
         Ok(node_replicas)
@@ -392,6 +430,7 @@ impl ResourceSpecsLocked {
             uuid: spec.uuid,
             name: spec.name.into(),
             disowners: by,
+            force: false,
         }
     }
 
@@ -402,9 +441,18 @@ impl ResourceSpecsLocked {
         request: &CreateVolume,
         mode: OperationMode,
     ) -> Result<Volume, SvcError> {
-        let volume = self.get_or_create_volume(request);
+        let snapshot = self.validate_source_snapshot(request)?;
+        let volume = self.get_or_create_volume(request)?;
         let (volume_clone, _guard) =
-            SpecOperations::start_create(&volume, registry, request, mode).await?;
+            match SpecOperations::start_create(&volume, registry, request, mode).await {
+                Err(SvcError::AlreadyExists { .. }) if request.idempotency_key.is_some() => {
+                    // a retry of a creation which has already completed
+                    return self
+                        .get_idempotent_volume(registry, &volume, request, mode)
+                        .await;
+                }
+                result => result?,
+            };
 
         // todo: pick nodes and pools using the Node&Pool Topology
         // todo: virtually increase the pool usage to avoid a race for space with concurrent calls
@@ -413,6 +461,7 @@ impl ResourceSpecsLocked {
             SpecOperations::validate_create_step(registry, result, &volume).await?;
 
         let mut replicas = Vec::<Replica>::new();
+        let mut unsupported = None;
         for replica in &create_replicas {
             if replicas.len() >= request.replicas as usize {
                 break;
@@ -428,10 +477,23 @@ impl ResourceSpecsLocked {
             } else {
                 replica.clone()
             };
-            match self.create_replica(registry, &replica, mode).await {
+            let result = match &snapshot {
+                Some(snapshot) => {
+                    self.create_replica_clone(registry, &replica, snapshot, mode)
+                        .await
+                }
+                None => self.create_replica(registry, &replica, mode).await,
+            };
+            match result {
                 Ok(replica) => {
                     replicas.push(replica);
                 }
+                Err(error @ SvcError::EncryptionUnsupported { .. })
+                | Err(error @ SvcError::CloneUnsupported { .. }) => {
+                    // no point in trying the other candidates
+                    unsupported = Some(error);
+                    break;
+                }
                 Err(error) => {
                     volume_clone.error(&format!(
                         "Failed to create replica {:?} for volume, error: {}",
@@ -458,17 +520,89 @@ impl ResourceSpecsLocked {
                     ));
                 }
             }
-            Err(SvcError::ReplicaCreateNumber {
+            Err(unsupported.unwrap_or(SvcError::ReplicaCreateNumber {
                 id: request.uuid.to_string(),
-            })
+            }))
         } else {
             Ok(())
         };
 
         SpecOperations::complete_create(result, &volume, registry).await?;
+        self.record_volume_event(
+            registry,
+            &volume,
+            VolumeEventType::Created,
+            format!("created with {} replicas", request.replicas),
+        )
+        .await;
+        if let Some(warning) = self.replica_spread_warning(request, &replicas) {
+            volume_clone.warn(&warning);
+            self.record_volume_event(
+                registry,
+                &volume,
+                VolumeEventType::PlacementWarning,
+                warning,
+            )
+            .await;
+        }
         registry.get_volume(&request.uuid).await
     }
 
+    /// Check that the source snapshot of the `CreateVolume` request, if any, can be cloned into
+    /// the new volume and return a copy of its spec
+    fn validate_source_snapshot(
+        &self,
+        request: &CreateVolume,
+    ) -> Result<Option<VolumeSnapshotSpec>, SvcError> {
+        let id = match &request.source_snapshot {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        let snapshot = self.get_snapshot_spec(id).context(errors::NotFound {
+            kind: ResourceKind::VolumeSnapshot,
+            id: id.to_string(),
+        })?;
+        // snapshots taken before the replicas were recorded can't be placed on their pools
+        if !snapshot.complete() || snapshot.replicas.is_empty() {
+            return Err(SvcError::SnapshotIncomplete {
+                snapshot: id.to_string(),
+            });
+        }
+        if snapshot.size != request.size {
+            return Err(SvcError::SnapshotSizeMismatch {
+                snapshot: id.to_string(),
+                size: snapshot.size,
+                requested: request.size,
+            });
+        }
+        Ok(Some(snapshot))
+    }
+
+    /// Check that the replicas are placed on distinct failure domains of the requested spread
+    /// topology key, returning a warning if they are not
+    fn replica_spread_warning(
+        &self,
+        request: &CreateVolume,
+        replicas: &[Replica],
+    ) -> Option<String> {
+        let key = request.topology.as_ref()?.spread_topology_key.as_ref()?;
+        let domains = replicas
+            .iter()
+            .filter_map(|replica| self.get_node(&replica.node).ok())
+            .filter_map(|node| node.labels().get(key).cloned())
+            .collect::<HashSet<_>>();
+        if domains.len() < replicas.len() {
+            Some(format!(
+                "not enough failure domains for '{}': {} replicas placed across {} domain(s)",
+                key,
+                replicas.len(),
+                domains.len()
+            ))
+        } else {
+            None
+        }
+    }
+
     /// Destroy a volume based on the given `DestroyVolume` request.
     /// Volume destruction will succeed even if the nexus or replicas cannot be destroyed (i.e. due
     /// to an inaccessible node). In this case the resources will be destroyed by the garbage
@@ -481,6 +615,13 @@ impl ResourceSpecsLocked {
     ) -> Result<(), SvcError> {
         let volume = self.get_locked_volume(&request.uuid);
         if let Some(volume) = &volume {
+            // the snapshots live on the volume replicas, so they must be deleted first
+            if !self.list_snapshots(&request.uuid)?.entries.is_empty() {
+                return Err(SvcError::InUse {
+                    kind: ResourceKind::Volume,
+                    id: request.uuid.to_string(),
+                });
+            }
             let _guard = SpecOperations::start_destroy(volume, registry, false, mode).await?;
 
             let nexuses = self.get_volume_nexuses(&request.uuid);
@@ -574,7 +715,17 @@ impl ResourceSpecsLocked {
             )
             .await;
 
-        SpecOperations::complete_update(registry, result, volume_spec, spec_clone).await
+        let uri =
+            SpecOperations::complete_update(registry, result, volume_spec.clone(), spec_clone)
+                .await?;
+        self.record_volume_event(
+            registry,
+            &volume_spec,
+            VolumeEventType::Shared,
+            format!("shared as '{}'", uri),
+        )
+        .await;
+        Ok(uri)
     }
 
     /// Unshare a volume based on the given `UnshareVolume` request
@@ -605,7 +756,15 @@ impl ResourceSpecsLocked {
             .unshare_nexus(registry, &UnshareNexus::from(&nexus), mode)
             .await;
 
-        SpecOperations::complete_update(registry, result, volume_spec, spec_clone).await
+        SpecOperations::complete_update(registry, result, volume_spec.clone(), spec_clone).await?;
+        self.record_volume_event(
+            registry,
+            &volume_spec,
+            VolumeEventType::Unshared,
+            "unshared",
+        )
+        .await;
+        Ok(())
     }
 
     /// Publish a volume based on the given `PublishVolume` request
@@ -621,18 +780,40 @@ impl ResourceSpecsLocked {
                 vol_id: request.uuid.to_string(),
             })?;
 
+        validate_publish_access_mode(&spec.lock(), request)?;
         let state = registry.get_volume_state(&request.uuid).await?;
         let nexus_node = get_volume_target_node(registry, &state, request).await?;
         let nexus_id = NexusId::new();
+        let nexus_config = match request.transport_params.is_empty() {
+            true => None,
+            false => Some(
+                NexusNvmfConfig::default()
+                    .with_transport_params(&request.transport_params)
+                    .map_err(|error| SvcError::InvalidTransportParams {
+                        details: error.source,
+                    })?,
+            ),
+        };
 
-        let operation =
-            VolumeOperation::Publish((nexus_node.clone(), nexus_id.clone(), request.share));
+        let operation = VolumeOperation::Publish((
+            nexus_node.clone(),
+            nexus_id.clone(),
+            request.share,
+            request.read_only,
+        ));
         let (spec_clone, _guard) =
             SpecOperations::start_update(registry, &spec, &state, operation, mode).await?;
 
         // Create a Nexus on the requested or auto-selected node
         let result = self
-            .volume_create_nexus(registry, &nexus_node, &nexus_id, &spec_clone, mode)
+            .volume_create_nexus(
+                registry,
+                &nexus_node,
+                &nexus_id,
+                &spec_clone,
+                nexus_config,
+                mode,
+            )
             .await;
 
         let nexus =
@@ -662,7 +843,18 @@ impl ResourceSpecsLocked {
             }
         }
 
-        SpecOperations::complete_update(registry, result, spec, spec_clone.clone()).await?;
+        SpecOperations::complete_update(registry, result, spec.clone(), spec_clone.clone()).await?;
+        let event = match last_nexus_id {
+            Some(_) => VolumeEventType::Republished,
+            None => VolumeEventType::Published,
+        };
+        self.record_volume_event(
+            registry,
+            &spec,
+            event,
+            format!("published on node '{}'", nexus_node),
+        )
+        .await;
 
         // If there was a previous nexus we should delete the persisted NexusInfo structure.
         if let Some(nexus_id) = last_nexus_id {
@@ -677,6 +869,107 @@ impl ResourceSpecsLocked {
         Ok(volume)
     }
 
+    /// Republish a volume on a different node based on the given `RepublishVolume` request
+    /// The new target is created and shared before the current target is destroyed, so that the
+    /// front-end IO can fail over to it with minimal interruption.
+    pub(crate) async fn republish_volume(
+        &self,
+        registry: &Registry,
+        request: &RepublishVolume,
+        mode: OperationMode,
+    ) -> Result<Volume, SvcError> {
+        let spec = self
+            .get_locked_volume(&request.uuid)
+            .context(errors::VolumeNotFound {
+                vol_id: request.uuid.to_string(),
+            })?;
+
+        let state = registry.get_volume_state(&request.uuid).await?;
+        let nexus_node = get_available_target_node(registry, &request.target_node).await?;
+        let nexus_id = NexusId::new();
+        // keep the current share protocol unless a new one is requested
+        let share = request.share.or_else(|| {
+            let spec = spec.lock();
+            spec.target.as_ref().and_then(|t| t.protocol().cloned())
+        });
+
+        let operation = VolumeOperation::Republish((nexus_node.clone(), nexus_id.clone(), share));
+        let (spec_clone, _guard) =
+            SpecOperations::start_update(registry, &spec, &state, operation, mode).await?;
+        let old_target = spec_clone.target.clone().expect("already validated");
+
+        // Create the new Nexus on the requested node, alongside the current one
+        let result = self
+            .volume_create_nexus(registry, &nexus_node, &nexus_id, &spec_clone, None, mode)
+            .await;
+
+        let nexus =
+            SpecOperations::validate_update_step(registry, result, &spec, &spec_clone).await?;
+
+        // Share the new Nexus before tearing down the current one
+        let mut result = Ok(());
+        if let Some(share) = share {
+            if let Err(error) = self
+                .share_nexus(registry, &ShareNexus::from((&nexus, None, share)), mode)
+                .await
+            {
+                // Since we failed to share, we'll revert back to the previous state.
+                // If we fail to do this inline, the reconcilers will pick up the slack.
+                self.destroy_nexus(registry, &DestroyNexus::from(nexus), true, mode)
+                    .await
+                    .ok();
+                result = Err(error);
+            }
+        }
+
+        // The front-end can now connect to the new target, so destroy the current one.
+        // If it's not reachable, eg: its node is down, the garbage collector will destroy it
+        // once it's back.
+        if result.is_ok() {
+            if let Some(old_nexus) = self.get_nexus(old_target.nexus()) {
+                let old_nexus = old_nexus.lock().clone();
+                if let Err(error) = self
+                    .destroy_nexus(registry, &old_nexus.into(), true, mode)
+                    .await
+                {
+                    spec_clone.warn_span(|| {
+                        tracing::warn!(
+                            error = %error,
+                            "Failed to destroy the previous volume target on node '{}'",
+                            old_target.node()
+                        )
+                    });
+                }
+            }
+        }
+
+        SpecOperations::complete_update(registry, result, spec.clone(), spec_clone.clone()).await?;
+        self.record_volume_event(
+            registry,
+            &spec,
+            VolumeEventType::Republished,
+            format!(
+                "republished on node '{}' from node '{}'",
+                nexus_node,
+                old_target.node()
+            ),
+        )
+        .await;
+
+        // The previous nexus is gone, so we should delete its persisted NexusInfo structure.
+        Self::delete_nexus_info(
+            &NexusInfoKey::new(&Some(request.uuid.clone()), old_target.nexus()),
+            registry,
+        )
+        .await;
+
+        let volume = registry.get_volume(&request.uuid).await?;
+        registry
+            .notify_if_degraded(&volume, PollTriggerEvent::VolumeDegraded)
+            .await;
+        Ok(volume)
+    }
+
     // Delete the NexusInfo key from the persistent store.
     // If deletion fails we just log it and continue.
     async fn delete_nexus_info(key: &NexusInfoKey, registry: &Registry) {
@@ -750,6 +1043,13 @@ impl ResourceSpecsLocked {
         };
 
         SpecOperations::complete_update(registry, result, spec.clone(), spec_clone.clone()).await?;
+        self.record_volume_event(
+            registry,
+            &spec,
+            VolumeEventType::Unpublished,
+            format!("unpublished from node '{}'", volume_target.node()),
+        )
+        .await;
         registry.get_volume(&request.uuid).await
     }
 
@@ -983,16 +1283,491 @@ impl ResourceSpecsLocked {
 
         assert_ne!(request.replicas, spec_clone.num_replicas);
         if request.replicas > spec_clone.num_replicas {
-            self.increase_volume_replica(registry, spec, state, spec_clone.clone(), mode)
+            self.increase_volume_replica(registry, spec.clone(), state, spec_clone.clone(), mode)
                 .await?
         } else {
-            self.decrease_volume_replica(registry, spec, state, spec_clone.clone(), mode)
+            self.decrease_volume_replica(registry, spec.clone(), state, spec_clone.clone(), mode)
                 .await?
         };
+        self.record_volume_event(
+            registry,
+            &spec,
+            VolumeEventType::ReplicaCountChanged,
+            format!(
+                "replica count changed from {} to {}",
+                spec_clone.num_replicas, request.replicas
+            ),
+        )
+        .await;
+
+        registry.get_volume(&request.uuid).await
+    }
+
+    /// Adds the existing replica given by the `AddVolumeReplica` request to the volume,
+    /// increasing the volume's replica count by 1.
+    /// The replica must not be owned by another volume or nexus, it must be large enough for the
+    /// volume and it must not live on a node which already has a replica of the volume.
+    /// If the volume is published, the replica is also added to the volume target.
+    pub(crate) async fn add_volume_replica(
+        &self,
+        registry: &Registry,
+        request: &AddVolumeReplica,
+        mode: OperationMode,
+    ) -> Result<Volume, SvcError> {
+        let not_addable = |reason: &str| SvcError::ReplicaNotAddable {
+            replica: request.replica.clone(),
+            volume: request.uuid.to_string(),
+            reason: reason.to_string(),
+        };
+        let spec = self
+            .get_locked_volume(&request.uuid)
+            .context(errors::VolumeNotFound {
+                vol_id: request.uuid.to_string(),
+            })?;
+        let replica_spec = self
+            .get_replica(&request.replica)
+            .context(errors::ReplicaNotFound {
+                replica_id: request.replica.clone(),
+            })?;
+        let replica = registry.get_replica(&request.replica).await?;
+
+        let (owners, size) = {
+            let replica_spec = replica_spec.lock();
+            (replica_spec.owners.clone(), replica_spec.size)
+        };
+        if owners.owned_by(&request.uuid) {
+            return Err(not_addable("it's already a replica of the volume"));
+        } else if owners.is_owned() {
+            return Err(not_addable("it's owned by another resource"));
+        } else if size < spec.lock().size {
+            return Err(not_addable("it's smaller than the volume"));
+        } else if self
+            .get_volume_data_nodes(&request.uuid)
+            .contains(&replica.node)
+        {
+            return Err(not_addable("its node already has a replica of the volume"));
+        }
+
+        let state = registry.get_volume_state(&request.uuid).await?;
+        let replicas = spec.lock().num_replicas.saturating_add(1);
+        let operation = VolumeOperation::SetReplica(replicas);
+        let (spec_clone, _guard) =
+            SpecOperations::start_update(registry, &spec, &state, operation, mode).await?;
+
+        let result = self
+            .own_volume_replica(registry, &request.uuid, &replica_spec)
+            .await;
+        SpecOperations::validate_update_step(registry, result, &spec, &spec_clone).await?;
+
+        // Add the replica to the nexus, if it's up
+        let result = self
+            .add_replica_to_volume(registry, &state, replica, mode)
+            .await;
+        if let Err(error) = &result {
+            // the replica count is reverted, so the replica must be given back as well
+            if let Err(disown_error) = self.disown_volume_replica(registry, &replica_spec).await {
+                tracing::error!(replica.uuid=%request.replica, error=%disown_error,
+                    "Failed to disown the replica after failing to add it to the volume: '{}'",
+                    error
+                );
+            }
+        }
+        SpecOperations::complete_update(registry, result, spec.clone(), spec_clone.clone()).await?;
+
+        self.record_volume_event(
+            registry,
+            &spec,
+            VolumeEventType::ReplicaCountChanged,
+            format!(
+                "replica '{}' added, replica count changed from {} to {}",
+                request.replica, spec_clone.num_replicas, replicas
+            ),
+        )
+        .await;
+
+        registry.get_volume(&request.uuid).await
+    }
+
+    /// Removes the replica given by the `RemoveVolumeReplica` request from the volume,
+    /// decreasing the volume's replica count by 1.
+    /// The replica is removed from the volume target, if any, and then destroyed.
+    /// The last healthy child of the volume target cannot be removed.
+    pub(crate) async fn remove_volume_replica(
+        &self,
+        registry: &Registry,
+        request: &RemoveVolumeReplica,
+        mode: OperationMode,
+    ) -> Result<Volume, SvcError> {
+        let spec = self
+            .get_locked_volume(&request.uuid)
+            .context(errors::VolumeNotFound {
+                vol_id: request.uuid.to_string(),
+            })?;
+        let replica_spec = self
+            .get_replica(&request.replica)
+            .map(|replica| replica.lock().clone())
+            .filter(|replica| replica.owners.owned_by(&request.uuid))
+            .context(errors::ReplicaNotFound {
+                replica_id: request.replica.clone(),
+            })?;
+        let state = registry.get_volume_state(&request.uuid).await?;
+
+        let target_child = state.target.as_ref().and_then(|nexus| {
+            self.get_nexus(&nexus.uuid)?
+                .lock()
+                .children
+                .iter()
+                .filter_map(|child| child.as_replica())
+                .find(|replica| replica.uuid() == &replica_spec.uuid)
+                .map(|replica| (nexus.clone(), replica))
+        });
+        if let Some((nexus, child)) = &target_child {
+            let (healthy, other_healthy): (Vec<_>, Vec<_>) = nexus
+                .children
+                .iter()
+                .filter(|c| c.state == ChildState::Online)
+                .partition(|c| &c.uri == child.uri());
+            if !healthy.is_empty() && other_healthy.is_empty() {
+                return Err(SvcError::LastHealthyReplica {
+                    replica: request.replica.to_string(),
+                    volume: request.uuid.to_string(),
+                });
+            }
+        }
+
+        let replicas = spec.lock().num_replicas.saturating_sub(1);
+        let operation = VolumeOperation::SetReplica(replicas);
+        let (spec_clone, _guard) =
+            SpecOperations::start_update(registry, &spec, &state, operation, mode).await?;
+
+        // Remove the replica from the nexus first, if it's a child of it
+        if let Some((nexus, child)) = target_child {
+            let result = self
+                .remove_nexus_replica(
+                    registry,
+                    &RemoveNexusReplica {
+                        node: nexus.node,
+                        nexus: nexus.uuid,
+                        replica: child,
+                    },
+                    mode,
+                )
+                .await;
+            SpecOperations::validate_update_step(registry, result, &spec, &spec_clone).await?;
+        }
+
+        let result = self
+            .destroy_replica_spec(
+                registry,
+                &replica_spec,
+                ReplicaOwners::from_volume(&request.uuid),
+                false,
+                mode,
+            )
+            .await;
+        SpecOperations::complete_update(registry, result, spec.clone(), spec_clone.clone()).await?;
+
+        self.record_volume_event(
+            registry,
+            &spec,
+            VolumeEventType::ReplicaCountChanged,
+            format!(
+                "replica '{}' removed, replica count changed from {} to {}",
+                request.replica, spec_clone.num_replicas, replicas
+            ),
+        )
+        .await;
+
+        registry.get_volume(&request.uuid).await
+    }
+
+    /// Expands a volume to the size given by the `ExpandVolume` request
+    /// All volume replicas are resized first, followed by the volume target, if any.
+    /// If any replica fails to be resized the expansion is aborted and the volume keeps its
+    /// current size; replicas which are already large enough are skipped, so the expansion can
+    /// simply be retried.
+    pub(crate) async fn expand_volume(
+        &self,
+        registry: &Registry,
+        request: &ExpandVolume,
+        mode: OperationMode,
+    ) -> Result<Volume, SvcError> {
+        let spec = self
+            .get_locked_volume(&request.uuid)
+            .context(errors::VolumeNotFound {
+                vol_id: request.uuid.to_string(),
+            })?;
+        let state = registry.get_volume_state(&request.uuid).await?;
+
+        let operation = VolumeOperation::Expand(request.size);
+        let (spec_clone, _guard) =
+            SpecOperations::start_update(registry, &spec, &state, operation, mode).await?;
+
+        let result = self
+            .expand_volume_replicas(registry, &spec_clone, request.size, mode)
+            .await;
+        SpecOperations::validate_update_step(registry, result, &spec, &spec_clone).await?;
+
+        let result = self
+            .expand_volume_target(registry, &spec_clone, request.size, mode)
+            .await;
+        SpecOperations::complete_update(registry, result, spec.clone(), spec_clone.clone()).await?;
+
+        self.record_volume_event(
+            registry,
+            &spec,
+            VolumeEventType::Expanded,
+            format!(
+                "size changed from {} to {} bytes",
+                spec_clone.size, request.size
+            ),
+        )
+        .await;
+
+        registry.get_volume(&request.uuid).await
+    }
+
+    /// Sets a volume's labels and annotations on the given `SetVolumeLabels` request, either
+    /// replacing or merging into the existing ones.
+    /// Only the volume spec is updated, the volume target and replicas are not disturbed.
+    pub(crate) async fn set_volume_labels(
+        &self,
+        registry: &Registry,
+        request: &SetVolumeLabels,
+        mode: OperationMode,
+    ) -> Result<Volume, SvcError> {
+        let spec = self
+            .get_locked_volume(&request.uuid)
+            .context(errors::VolumeNotFound {
+                vol_id: request.uuid.to_string(),
+            })?;
+        let state = registry.get_volume_state(&request.uuid).await?;
+
+        let operation = {
+            let spec = spec.lock();
+            VolumeOperation::SetLabels(request.apply(spec.labels.as_ref(), &spec.annotations))
+        };
+        let (spec_clone, _guard) =
+            SpecOperations::start_update(registry, &spec, &state, operation, mode).await?;
+
+        SpecOperations::complete_update(registry, Ok(()), spec, spec_clone).await?;
 
         registry.get_volume(&request.uuid).await
     }
 
+    /// Get a copy of the snapshot spec with the given `id`, if any exists
+    pub(crate) fn get_snapshot_spec(&self, id: &SnapshotId) -> Option<VolumeSnapshotSpec> {
+        let specs = self.read();
+        specs
+            .snapshots
+            .get(id)
+            .map(|snapshot| snapshot.lock().clone())
+    }
+
+    /// Get the snapshots of the given volume, oldest first
+    pub(crate) fn list_snapshots(&self, volume: &VolumeId) -> Result<VolumeSnapshots, SvcError> {
+        let specs = self.read();
+        let mut entries = specs
+            .snapshots
+            .values()
+            .map(|snapshot| VolumeSnapshot::from(snapshot.lock().deref()))
+            .filter(|snapshot| &snapshot.volume_id == volume)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|snapshot| snapshot.timestamp);
+        Ok(VolumeSnapshots { entries })
+    }
+
+    /// Snapshot the volume through its target, so the volume must be published.
+    /// Retrying with the same snapshot uuid returns the existing snapshot, or completes it if
+    /// the previous attempt did not.
+    pub(crate) async fn create_snapshot(
+        &self,
+        registry: &Registry,
+        request: &CreateSnapshot,
+        mode: OperationMode,
+    ) -> Result<VolumeSnapshot, SvcError> {
+        let volume_spec =
+            self.get_locked_volume(&request.volume_id)
+                .context(errors::VolumeNotFound {
+                    vol_id: request.volume_id.to_string(),
+                })?;
+        // the volume target must not move whilst the snapshot is taken
+        let _guard = volume_spec.operation_guard_wait(mode).await?;
+
+        if let Some(snapshot) = self.get_snapshot_spec(&request.snapshot_id) {
+            if snapshot.volume != request.volume_id {
+                return Err(SvcError::AlreadyExists {
+                    kind: ResourceKind::VolumeSnapshot,
+                    id: request.snapshot_id.to_string(),
+                });
+            } else if snapshot.complete() {
+                return Ok(VolumeSnapshot::from(&snapshot));
+            }
+        }
+
+        let volume = volume_spec.lock().clone();
+        let target = volume.target.as_ref().context(errors::VolumeNotPublished {
+            vol_id: volume.uuid.to_string(),
+        })?;
+        let nexus = registry.get_nexus(target.nexus()).await?;
+        let node = registry.get_node_wrapper(&nexus.node).await?;
+
+        // only the healthy children of the target are snapshotted
+        let replicas = match self.get_nexus(&nexus.uuid) {
+            Some(nexus_spec) => nexus_spec
+                .lock()
+                .children
+                .iter()
+                .filter_map(|child| child.as_replica())
+                .filter(|replica| {
+                    nexus.children.iter().any(|child| {
+                        &child.uri == replica.uri() && child.state == ChildState::Online
+                    })
+                })
+                .filter_map(|replica| {
+                    self.get_replica(replica.uuid())
+                        .map(|spec| VolumeSnapshotReplica {
+                            uuid: replica.uuid().clone(),
+                            pool: spec.lock().pool.clone(),
+                        })
+                })
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+
+        // persist the snapshot before it's taken, so an interrupted creation is not mistaken
+        // for a complete snapshot
+        let mut spec = VolumeSnapshotSpec {
+            uuid: request.snapshot_id.clone(),
+            volume: volume.uuid.clone(),
+            name: String::new(),
+            size: volume.size,
+            timestamp: std::time::SystemTime::now(),
+            replicas,
+            status: SpecStatus::Creating,
+        };
+        registry.store_obj(&spec).await?;
+        self.write().snapshots.insert(spec.clone());
+
+        match node.create_snapshot(request, &nexus.uuid).await {
+            Ok(name) => {
+                spec.name = name;
+                spec.status = SpecStatus::Created(());
+                registry.store_obj(&spec).await?;
+                let snapshot = VolumeSnapshot::from(&spec);
+                self.write().snapshots.insert(spec);
+                Ok(snapshot)
+            }
+            Err(error) => {
+                let key = VolumeSnapshotKey::from(&request.snapshot_id).key();
+                if registry.delete_kv(&key).await.is_ok() {
+                    self.write().snapshots.remove(&request.snapshot_id);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Delete the given volume snapshot.
+    /// The snapshots of the replicas are destroyed on the io-engine before the spec is deleted,
+    /// so that a failed delete can be retried.
+    pub(crate) async fn delete_snapshot(
+        &self,
+        registry: &Registry,
+        request: &DeleteSnapshot,
+        mode: OperationMode,
+    ) -> Result<(), SvcError> {
+        // the volume may already be gone, in which case its snapshots can still be deleted
+        let volume_spec = self.get_locked_volume(&request.volume_id);
+        let _guard = match &volume_spec {
+            Some(volume_spec) => Some(volume_spec.operation_guard_wait(mode).await?),
+            None => None,
+        };
+
+        let snapshot = match self.get_snapshot_spec(&request.snapshot_id) {
+            Some(snapshot) if snapshot.volume == request.volume_id => snapshot,
+            _ => {
+                return Err(SvcError::NotFound {
+                    kind: ResourceKind::VolumeSnapshot,
+                    id: request.snapshot_id.to_string(),
+                })
+            }
+        };
+
+        for replica in &snapshot.replicas {
+            // an interrupted creation has no snapshot name to destroy
+            if let Some(name) = snapshot.replica_snapshot_name(replica) {
+                let pool = self.get_pool(&replica.pool)?;
+                let node = registry.get_node_wrapper(&pool.node).await?;
+                node.destroy_snapshot(request, &name).await?;
+            }
+        }
+
+        registry
+            .delete_kv(&VolumeSnapshotKey::from(&request.snapshot_id).key())
+            .await?;
+        self.write().snapshots.remove(&request.snapshot_id);
+        Ok(())
+    }
+
+    /// Resize all replicas of the given volume which are smaller than `size` bytes
+    async fn expand_volume_replicas(
+        &self,
+        registry: &Registry,
+        volume: &VolumeSpec,
+        size: u64,
+        mode: OperationMode,
+    ) -> Result<(), SvcError> {
+        let mut failed = vec![];
+        for replica in self.get_volume_replicas(&volume.uuid) {
+            let replica_id = replica.lock().uuid.clone();
+            let result = match registry.get_replica(&replica_id).await {
+                Ok(replica_state) if replica_state.size >= size => continue,
+                Ok(replica_state) => self
+                    .resize_replica(registry, &ResizeReplica::new(&replica_state, size), mode)
+                    .await
+                    .map(|_| ()),
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                volume.error(&format!(
+                    "Failed to resize replica '{}', error: '{}'",
+                    replica_id,
+                    error.full_string()
+                ));
+                failed.push(replica_id.to_string());
+            }
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(SvcError::VolumeExpandReplicas {
+                vol_id: volume.uuid.to_string(),
+                replicas: failed.join(", "),
+            })
+        }
+    }
+
+    /// Resize the target of the given volume, if it's published and smaller than `size` bytes
+    async fn expand_volume_target(
+        &self,
+        registry: &Registry,
+        volume: &VolumeSpec,
+        size: u64,
+        mode: OperationMode,
+    ) -> Result<(), SvcError> {
+        let nexus_id = match self.get_volume_target_nexus(volume) {
+            Some(nexus_spec) => nexus_spec.lock().uuid.clone(),
+            None => return Ok(()),
+        };
+        let nexus = registry.get_nexus(&nexus_id).await?;
+        if nexus.size >= size {
+            return Ok(());
+        }
+        self.resize_nexus(registry, &ResizeNexus::new(&nexus, size), mode)
+            .await
+    }
+
     /// Make the replica accessible on the specified `NodeId`
     /// This means the replica might have to be shared/unshared so it can be open through
     /// the correct protocol (loopback locally, and nvmf remotely)
@@ -1026,7 +1801,8 @@ impl ResourceSpecsLocked {
         }
     }
 
-    /// Create a nexus for the given volume on the specified target_node
+    /// Create a nexus for the given volume on the specified target_node, with the given nvmf
+    /// configuration, if any
     /// Existing replicas may be shared/unshared so we can connect to them
     async fn volume_create_nexus(
         &self,
@@ -1034,6 +1810,7 @@ impl ResourceSpecsLocked {
         target_node: &NodeId,
         nexus_id: &NexusId,
         vol_spec: &VolumeSpec,
+        config: Option<NexusNvmfConfig>,
         mode: OperationMode,
     ) -> Result<Nexus, SvcError> {
         let children = get_healthy_volume_replicas(vol_spec, target_node, registry).await?;
@@ -1076,7 +1853,7 @@ impl ResourceSpecsLocked {
                     &nexus_replicas,
                     true,
                     Some(&vol_spec.uuid),
-                    None,
+                    config,
                 ),
                 mode,
             )
@@ -1120,6 +1897,7 @@ impl ResourceSpecsLocked {
                     nexus: nexus.uuid.clone(),
                     replica: ReplicaUri::new(&replica.uuid, &uri),
                     auto_rebuild: true,
+                    rebuild_source: nexus.preferred_rebuild_source(),
                 },
                 mode,
             )
@@ -1136,6 +1914,130 @@ impl ResourceSpecsLocked {
         }
     }
 
+    /// Move the volume replica to another pool on the same node: a new replica is created on the
+    /// destination pool and added to the volume nexus and, once it's rebuilt, the source replica
+    /// is removed from the nexus and destroyed by the `ReplicaRebalancer`.
+    /// The only healthy child of the volume nexus is not moved unless the move is forced.
+    pub(crate) async fn move_replica(
+        &self,
+        registry: &Registry,
+        request: &MoveReplica,
+        mode: OperationMode,
+    ) -> Result<Replica, SvcError> {
+        let not_movable = |reason: &str| SvcError::ReplicaNotMovable {
+            replica: request.uuid.clone(),
+            reason: reason.to_string(),
+        };
+        let source = self
+            .get_replica(&request.uuid)
+            .map(|replica| replica.lock().clone())
+            .filter(|replica| replica.pool == request.pool)
+            .context(errors::ReplicaNotFound {
+                replica_id: request.uuid.clone(),
+            })?;
+        let destination_node = Self::get_pool_node(registry, request.destination_pool.clone())
+            .await
+            .context(errors::PoolNotFound {
+                pool_id: request.destination_pool.clone(),
+            })?;
+        if destination_node != request.node {
+            return Err(not_movable(&format!(
+                "the destination pool '{}' is not on node '{}'",
+                request.destination_pool, request.node
+            )));
+        }
+        let volume_uuid = source
+            .owners
+            .volume()
+            .cloned()
+            .ok_or_else(|| not_movable("it's not owned by a volume"))?;
+        let volume_spec = self
+            .get_locked_volume(&volume_uuid)
+            .context(errors::VolumeNotFound {
+                vol_id: volume_uuid.to_string(),
+            })?;
+
+        // the volume target must not move whilst the new replica is added to it
+        let _guard = volume_spec.operation_guard_wait(mode).await?;
+        if self.replica_move_pending(&volume_uuid) {
+            return Err(SvcError::Conflict {});
+        }
+
+        let volume_state = registry.get_volume_state(&volume_uuid).await?;
+        let nexus = volume_state
+            .target
+            .clone()
+            .context(errors::VolumeNotPublished {
+                vol_id: volume_uuid.to_string(),
+            })?;
+        let source_uri = self
+            .get_nexus(&nexus.uuid)
+            .and_then(|nexus_spec| {
+                nexus_spec
+                    .lock()
+                    .children
+                    .iter()
+                    .filter_map(|child| child.as_replica())
+                    .find(|replica| replica.uuid() == &source.uuid)
+                    .map(|replica| replica.uri().clone())
+            })
+            .ok_or_else(|| not_movable("it's not a child of the volume target"))?;
+        let other_healthy_children = nexus
+            .children
+            .iter()
+            .filter(|child| child.uri != source_uri && child.state == ChildState::Online)
+            .count();
+        if other_healthy_children == 0 && !request.force {
+            return Err(SvcError::LastHealthyReplica {
+                replica: source.uuid.to_string(),
+                volume: volume_uuid.to_string(),
+            });
+        }
+
+        // the move is registered with the node the replica is moved away from
+        let source_node = self.get_pool(&source.pool)?.node;
+
+        let replica_uuid = ReplicaId::new();
+        let candidate = CreateReplica {
+            node: request.node.clone(),
+            name: Some(ReplicaName::new(&replica_uuid, Some(&volume_uuid))),
+            uuid: replica_uuid,
+            pool: request.destination_pool.clone(),
+            size: source.size,
+            thin: source.thin,
+            share: Protocol::None,
+            managed: true,
+            owners: ReplicaOwners::from_volume(&volume_uuid),
+            annotations: Default::default(),
+            encryption: source.encryption.clone(),
+        };
+        let replica = self
+            .create_volume_replica(registry, &volume_state, &[candidate], mode)
+            .await?;
+        self.attach_replica_to_nexus(registry, &volume_uuid, &nexus, &replica, mode)
+            .await?;
+
+        volume_spec.lock().info_span(|| {
+            tracing::info!(
+                replica.uuid = %source.uuid,
+                replica.target = %replica.uuid,
+                pool.uuid = %request.destination_pool,
+                "Started moving replica, waiting for the new replica to be rebuilt"
+            )
+        });
+        self.add_replica_move(
+            registry,
+            &source_node,
+            ReplicaMove {
+                volume: volume_uuid,
+                source: source.uuid,
+                target: replica.uuid.clone(),
+            },
+        )
+        .await?;
+        Ok(replica)
+    }
+
     /// Remove unused replicas from the volume
     /// (that is, replicas which are not used by a nexus and are in excess to the
     /// volume's replica count).
@@ -1347,15 +2249,75 @@ impl ResourceSpecsLocked {
         Ok(())
     }
 
+    /// Record a lifecycle event in the event history of the given volume and persist it
+    /// The oldest events are evicted when the history is full.
+    /// Failing to persist the event is not fatal as the history is informational only.
+    /// The event is also recorded as a control-plane event, unless it's the volume creation
+    /// which is already recorded as such when the create operation completes.
+    pub(crate) async fn record_volume_event(
+        &self,
+        registry: &Registry,
+        volume_spec: &Arc<Mutex<VolumeSpec>>,
+        event: VolumeEventType,
+        detail: impl Into<String>,
+    ) {
+        let detail = detail.into();
+        let spec_clone = {
+            let mut spec = volume_spec.lock();
+            spec.push_event(
+                VolumeEvent::new(event, detail.clone()),
+                registry.volume_event_history(),
+            );
+            spec.clone()
+        };
+        if event != VolumeEventType::Created {
+            let event_type = match event {
+                VolumeEventType::Degraded | VolumeEventType::PlacementWarning => EventType::Warning,
+                _ => EventType::Normal,
+            };
+            registry.record_event(Event::new(
+                ResourceKind::Volume,
+                spec_clone.uuid.to_string(),
+                event_type,
+                format!("{:?}", event),
+                detail,
+            ));
+        }
+        if let Err(error) = registry.store_obj(&spec_clone).await {
+            spec_clone.warn(&format!(
+                "Failed to persist the volume event history, error: '{}'",
+                error.full_string()
+            ));
+        }
+    }
+
+    /// Make the replica part of the given volume
+    /// The replica is only owned once the owner is persisted.
+    pub(crate) async fn own_volume_replica(
+        &self,
+        registry: &Registry,
+        volume: &VolumeId,
+        replica: &Arc<Mutex<ReplicaSpec>>,
+    ) -> Result<(), SvcError> {
+        let mut clone = replica.lock().clone();
+        clone.owners.set_volume_owner(volume);
+        registry.store_obj(&clone).await?;
+        replica.lock().owners.set_volume_owner(volume);
+        Ok(())
+    }
+
     /// Disown replica from its volume
+    /// The replica is only disowned once the change is persisted.
     pub(crate) async fn disown_volume_replica(
         &self,
         registry: &Registry,
         replica: &Arc<Mutex<ReplicaSpec>>,
     ) -> Result<(), SvcError> {
+        let mut clone = replica.lock().clone();
+        clone.owners.disowned_by_volume();
+        registry.store_obj(&clone).await?;
         replica.lock().owners.disowned_by_volume();
-        let clone = replica.lock().clone();
-        registry.store_obj(&clone).await
+        Ok(())
     }
 
     /// Disown nexus from its owner
@@ -1432,12 +2394,55 @@ impl ResourceSpecsLocked {
         specs.volumes.remove(id);
     }
     /// Get or Create the protected VolumeSpec for the given request
-    fn get_or_create_volume(&self, request: &CreateVolume) -> Arc<Mutex<VolumeSpec>> {
+    /// The idempotency key is checked whilst holding the specs lock, so a concurrent creation of
+    /// another volume with the same key results in an `IdempotencyKeyMismatch` error.
+    fn get_or_create_volume(
+        &self,
+        request: &CreateVolume,
+    ) -> Result<Arc<Mutex<VolumeSpec>>, SvcError> {
         let mut specs = self.write();
-        if let Some(volume) = specs.volumes.get(&request.uuid) {
+        if let Some(key) = &request.idempotency_key {
+            let other = specs.volumes.values().find_map(|volume| {
+                let volume = volume.lock();
+                (volume.idempotency_key.as_ref() == Some(key) && volume.uuid != request.uuid)
+                    .then(|| volume.uuid.clone())
+            });
+            if let Some(id) = other {
+                return Err(SvcError::IdempotencyKeyMismatch {
+                    key: key.clone(),
+                    id: id.to_string(),
+                });
+            }
+        }
+        Ok(if let Some(volume) = specs.volumes.get(&request.uuid) {
             volume.clone()
         } else {
             specs.volumes.insert(VolumeSpec::from(request))
+        })
+    }
+
+    /// Get the volume which was created by a previous request with the same idempotency key.
+    /// The original create parameters are checked under the operation guard, so that the volume
+    /// can't be modified or destroyed whilst they're compared.
+    /// A volume created with different parameters results in an `IdempotencyKeyMismatch` error.
+    async fn get_idempotent_volume(
+        &self,
+        registry: &Registry,
+        volume: &Arc<Mutex<VolumeSpec>>,
+        request: &CreateVolume,
+        mode: OperationMode,
+    ) -> Result<Volume, SvcError> {
+        let _guard = volume.operation_guard_wait(mode).await?;
+        let volume = volume.lock().clone();
+        if !volume.created_with(request) {
+            Err(SvcError::IdempotencyKeyMismatch {
+                key: request.idempotency_key.clone().unwrap_or_default(),
+                id: volume.uuid.to_string(),
+            })
+        } else if !volume.status.created() {
+            Err(SvcError::Deleting {})
+        } else {
+            registry.get_volume(&volume.uuid).await
         }
     }
 
@@ -1473,32 +2478,55 @@ async fn get_volume_target_node(
     }
 
     match request.target_node.as_ref() {
-        None => {
-            // auto select a node
-            let nodes = registry.get_node_wrappers().await;
-            for locked_node in nodes {
-                let node = locked_node.read().await;
-                // todo: use other metrics in order to make the "best" choice
-                if node.is_online() {
-                    return Ok(node.id().clone());
-                }
-            }
-            Err(SvcError::NoNodes {})
-        }
-        Some(node) => {
-            // make sure the requested node is available
-            // todo: check the max number of nexuses per node is respected
-            let node = registry.get_node_wrapper(node).await?;
-            let node = node.read().await;
-            if node.is_online() {
-                Ok(node.id().clone())
-            } else {
-                Err(SvcError::NodeNotOnline {
-                    node: node.id().clone(),
-                })
-            }
+        None => select_target_node(registry).await,
+        Some(node) => get_available_target_node(registry, node).await,
+    }
+}
+
+/// Auto select an online node which is not cordoned to host a volume target
+pub(crate) async fn select_target_node(registry: &Registry) -> Result<NodeId, SvcError> {
+    let nodes = registry.get_node_wrappers().await;
+    for locked_node in nodes {
+        let node = locked_node.read().await;
+        // todo: use other metrics in order to make the "best" choice
+        if node.is_online() && !registry.specs().node_cordoned(node.id()) {
+            return Ok(node.id().clone());
         }
     }
+    Err(SvcError::NoNodes {})
+}
+
+/// A volume which was published read-only, eg: for many readers, may only be published
+/// read-write again when explicitly forced, as its readers may not expect its data to change
+fn validate_publish_access_mode(
+    spec: &VolumeSpec,
+    request: &PublishVolume,
+) -> Result<(), SvcError> {
+    if spec.read_only && !request.read_only && !request.force_read_write {
+        Err(SvcError::VolumePublishedReadOnly {
+            vol_id: spec.uuid.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Make sure the requested node is available to host a volume target
+async fn get_available_target_node(registry: &Registry, node: &NodeId) -> Result<NodeId, SvcError> {
+    // todo: check the max number of nexuses per node is respected
+    let node = registry.get_node_wrapper(node).await?;
+    let node = node.read().await;
+    if registry.specs().node_cordoned(node.id()) {
+        Err(SvcError::NodeCordoned {
+            node: node.id().clone(),
+        })
+    } else if node.is_online() {
+        Ok(node.id().clone())
+    } else {
+        Err(SvcError::NodeNotOnline {
+            node: node.id().clone(),
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -1517,7 +2545,10 @@ impl SpecOperations for VolumeSpec {
     ) -> Result<(), SvcError> {
         if !matches!(
             &operation,
-            VolumeOperation::Publish(..) | VolumeOperation::Unpublish
+            VolumeOperation::Publish(..)
+                | VolumeOperation::Republish(..)
+                | VolumeOperation::Unpublish
+                | VolumeOperation::SetLabels(_)
         ) {
             // don't attempt to modify the volume parameters if the nexus target is not "stable"
             if self.target.is_some() != state.target.is_some() {
@@ -1550,9 +2581,8 @@ impl SpecOperations for VolumeSpec {
                 }),
             },
             VolumeOperation::Unshare => match &self.target {
-                None => Err(SvcError::NotShared {
-                    kind: self.kind(),
-                    id: self.uuid(),
+                None => Err(SvcError::VolumeNotPublished {
+                    vol_id: self.uuid(),
                 }),
                 Some(target) if target.protocol().is_none() => Err(SvcError::NotShared {
                     kind: self.kind(),
@@ -1560,7 +2590,7 @@ impl SpecOperations for VolumeSpec {
                 }),
                 _ => Ok(()),
             },
-            VolumeOperation::Publish((_, _, protocol)) => match protocol {
+            VolumeOperation::Publish((_, _, protocol, _)) => match protocol {
                 None => Ok(()),
                 Some(protocol) => match protocol {
                     VolumeShareProtocol::Nvmf => {
@@ -1581,6 +2611,26 @@ impl SpecOperations for VolumeSpec {
                     }),
                 },
             },
+            VolumeOperation::Republish((node, _, protocol)) => match &self.target {
+                None => Err(SvcError::VolumeNotPublished {
+                    vol_id: self.uuid(),
+                }),
+                Some(target) if target.node() == node => Err(SvcError::VolumeAlreadyPublished {
+                    vol_id: self.uuid(),
+                    node: target.node().to_string(),
+                    protocol: format!("{:?}", target.protocol()),
+                }),
+                Some(_) => match protocol {
+                    Some(protocol @ VolumeShareProtocol::Iscsi) => {
+                        Err(SvcError::InvalidShareProtocol {
+                            kind: ResourceKind::Volume,
+                            id: self.uuid(),
+                            share: format!("{:?}", protocol),
+                        })
+                    }
+                    _ => Ok(()),
+                },
+            },
             VolumeOperation::Unpublish if self.target.is_none() => {
                 Err(SvcError::VolumeNotPublished {
                     vol_id: self.uuid(),
@@ -1663,6 +2713,15 @@ impl SpecOperations for VolumeSpec {
                 }
             }
 
+            VolumeOperation::Expand(size) if *size <= self.size => Err(SvcError::InvalidResize {
+                kind: ResourceKind::Volume,
+                id: self.uuid(),
+                current: self.size,
+                requested: *size,
+            }),
+            VolumeOperation::Expand(_) => Ok(()),
+            VolumeOperation::SetLabels(_) => Ok(()),
+
             VolumeOperation::Create => unreachable!(),
             VolumeOperation::Destroy => unreachable!(),
         }?;
@@ -5,10 +5,11 @@ use common_lib::{
     store::etcd::Etcd,
     types::v0::{
         message_bus::{
-            Child, ChildState, CreateReplica, CreateVolume, DestroyVolume, Filter, GetNexuses,
-            GetReplicas, GetVolumes, Nexus, NodeId, PublishVolume, SetVolumeReplica, ShareVolume,
-            Topology, UnpublishVolume, UnshareVolume, Volume, VolumeShareProtocol, VolumeState,
-            VolumeStatus,
+            AddVolumeReplica, Child, ChildState, CordonNode, CreateReplica, CreateVolume,
+            DestroyVolume, DrainNode, ExpandVolume, Filter, GetNexuses, GetReplicas, GetVolumes,
+            Nexus, NodeId, PublishVolume, RemoveVolumeReplica, RepublishVolume, SetVolumeLabels,
+            SetVolumeReplica, ShareVolume, Topology, UncordonNode, UnpublishVolume, UnshareVolume,
+            Volume, VolumeShareProtocol, VolumeState, VolumeStatus,
         },
         openapi::apis::{StatusCode, Uuid},
         store::{
@@ -19,27 +20,36 @@ use common_lib::{
 };
 
 use deployer_cluster::{Cluster, ClusterBuilder};
-use rpc::io_engine::FaultNexusChildRequest;
+use futures::StreamExt;
+use rpc::io_engine::{ChildAction, ChildNexusRequest, FaultNexusChildRequest};
 
 use common_lib::{
     mbus_api::TimeoutOptions,
     types::v0::{
         message_bus::{
-            ChildUri, CreateNexus, DestroyReplica, GetSpecs, NexusId, ReplicaId, ReplicaOwners,
-            VolumeId,
+            ChildUri, CreateNexus, CreateSnapshot, DeleteSnapshot, DestroyReplica,
+            EncryptionCipher, GetSpecs, ListSnapshots, MoveReplica, NexusId, ReplicaEncryption,
+            ReplicaId, ReplicaOwners, SetRebuildLimit, SnapshotId, Specs, VolumeId,
         },
         openapi::{models, models::NodeStatus, tower::client::Error},
-        store::{definitions::StorableObject, volume::VolumeSpec},
+        store::{
+            definitions::StorableObject,
+            node::{NodeDrainState, NodeSpec, NodeSpecKey},
+            registry::{CoreRegistryConfig, CoreRegistryConfigKey, MaxRebuilds},
+            snapshot::VolumeSnapshotSpec,
+            volume::VolumeSpec,
+            SpecStatus,
+        },
     },
 };
 use grpc::operations::{
     nexus::traits::NexusOperations, node::traits::NodeOperations,
     registry::traits::RegistryOperations, replica::traits::ReplicaOperations,
-    volume::traits::VolumeOperations,
+    volume::traits::VolumeOperations, Pagination,
 };
 use std::{
+    collections::HashMap,
     convert::{TryFrom, TryInto},
-    str::FromStr,
     time::Duration,
 };
 
@@ -57,49 +67,2142 @@ async fn volume() {
         .await
         .unwrap();
 
-    let node_client = cluster.grpc_client().node();
-    let nodes = node_client.get(Filter::None, None).await.unwrap();
-    tracing::info!("Nodes: {:?}", nodes);
-
-    test_volume(&cluster).await;
-}
+    let node_client = cluster.grpc_client().node();
+    let nodes = node_client.get(Filter::None, None).await.unwrap();
+    tracing::info!("Nodes: {:?}", nodes);
+
+    test_volume(&cluster).await;
+}
+
+/// Creates and destroys a volume over gRPC and checks that both requests have been audited.
+#[tokio::test]
+async fn volume_audit_log() {
+    let audit_dir = std::env::temp_dir().join(format!("audit-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&audit_dir).unwrap();
+    let audit_log = audit_dir.join("audit.log");
+    let audit_log_path = audit_log.to_string_lossy().to_string();
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_io_engines(1)
+        .with_pools(1)
+        .with_options(|options| options.with_audit_log(&audit_log_path))
+        .build()
+        .await
+        .unwrap();
+
+    let volume_client = cluster.grpc_client().volume();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::new(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let uuid = volume.uuid().clone();
+    volume_client
+        .destroy(&DestroyVolume { uuid: uuid.clone() }, None)
+        .await
+        .unwrap();
+
+    let records = std::fs::read_to_string(&audit_log).unwrap();
+    let records = records
+        .lines()
+        .map(|record| serde_json::from_str::<serde_json::Value>(record).unwrap())
+        .filter(|record| record["resource"] == uuid.to_string())
+        .map(|record| {
+            (
+                record["sender"].as_str().unwrap().to_string(),
+                record["operation"].as_str().unwrap().to_string(),
+                record["outcome"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let expected = ["createVolume", "destroyVolume"]
+        .iter()
+        .map(|operation| {
+            (
+                "grpc".to_string(),
+                operation.to_string(),
+                "success".to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(records, expected);
+
+    std::fs::remove_dir_all(&audit_dir).ok();
+}
+
+#[tracing::instrument(skip(cluster))]
+async fn test_volume(cluster: &Cluster) {
+    smoke_test(cluster).await;
+    publishing_test(cluster).await;
+    republishing_test(cluster).await;
+    replica_count_test(cluster).await;
+    nexus_persistence_test(cluster).await;
+    event_history_test(cluster).await;
+    expand_test(cluster).await;
+    labels_test(cluster).await;
+    annotations_test(cluster).await;
+    cordon_test(cluster).await;
+    pool_rejections_test(cluster).await;
+    add_remove_replica_test(cluster).await;
+    transport_params_test(cluster).await;
+    filtered_specs_test(cluster).await;
+    specs_stream_test(cluster).await;
+    encryption_test(cluster).await;
+    preferred_pools_test(cluster).await;
+    idempotency_key_test(cluster).await;
+    rebuild_limit_test(cluster).await;
+    snapshot_test(cluster).await;
+    read_only_publish_test(cluster).await;
+}
+
+async fn read_only_publish_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("5e3c1b2a-7d4f-4a6e-9b8c-1f2e3d4c5b6a").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+
+    volume_client
+        .publish(
+            &PublishVolume::new(volume_id.clone(), None, None).with_read_only(true, false),
+            None,
+        )
+        .await
+        .unwrap();
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let volume_spec = specs.volumes.iter().find(|v| v.uuid == volume_id).unwrap();
+    assert!(volume_spec.read_only);
+    assert!(volume_spec.target.as_ref().unwrap().read_only());
+
+    // the access mode outlives the volume target
+    volume_client
+        .unpublish(&UnpublishVolume::new(&volume_id, false), None)
+        .await
+        .unwrap();
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let volume_spec = specs.volumes.iter().find(|v| v.uuid == volume_id).unwrap();
+    assert!(volume_spec.read_only);
+
+    // a read-only volume can't be published read-write unless forced
+    let error = volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .expect_err("the volume was published read-only");
+    assert_eq!(error.kind, ReplyErrorKind::FailedPrecondition);
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(volume_id.clone(), None, None).with_read_only(false, true),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(!volume.spec().read_only);
+    assert!(!volume.spec().target.unwrap().read_only());
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn snapshot_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("5a9f3c1e-8d2b-4e7a-b6c4-2f1d0e9a8b73").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    let snapshot_id = SnapshotId::try_from("c3e8b1a4-6f2d-4b9e-a7c5-1d0f8e3b2a96").unwrap();
+
+    let error = volume_client
+        .create_snapshot(
+            &CreateSnapshot::new(volume_id.clone(), snapshot_id.clone()),
+            None,
+        )
+        .await
+        .expect_err("An unpublished volume cannot be snapshotted");
+    assert_eq!(error.kind, ReplyErrorKind::NotPublished);
+
+    volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    let snapshot = volume_client
+        .create_snapshot(
+            &CreateSnapshot::new(volume_id.clone(), snapshot_id.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(snapshot.snapshot_id, snapshot_id);
+    assert_eq!(snapshot.volume_id, volume_id);
+
+    // creating the same snapshot again is idempotent
+    let again = volume_client
+        .create_snapshot(
+            &CreateSnapshot::new(volume_id.clone(), snapshot_id.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(again, snapshot);
+
+    let snapshots = volume_client
+        .list_snapshots(&ListSnapshots::new(volume_id.clone()), None)
+        .await
+        .unwrap();
+    assert_eq!(snapshots.entries, vec![snapshot]);
+
+    // the volume cannot be destroyed whilst it has snapshots
+    let error = volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .expect_err("The volume has a snapshot");
+    assert_eq!(error.kind, ReplyErrorKind::InUse);
+
+    let replica = cluster
+        .grpc_client()
+        .replica()
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
+    let mut rpc_handle = cluster.grpc_handle(replica.node.as_str()).await.unwrap();
+    let replica_snapshot = format!("{}-snap-", replica.uuid);
+    let replicas = rpc_handle
+        .io_engine
+        .list_replicas_v2(rpc::io_engine::Null {})
+        .await
+        .unwrap()
+        .into_inner()
+        .replicas;
+    assert!(replicas
+        .iter()
+        .any(|r| r.name.starts_with(&replica_snapshot)));
+
+    volume_client
+        .delete_snapshot(
+            &DeleteSnapshot::new(volume_id.clone(), snapshot_id.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    // the snapshot is destroyed on the io-engine as well
+    let replicas = rpc_handle
+        .io_engine
+        .list_replicas_v2(rpc::io_engine::Null {})
+        .await
+        .unwrap()
+        .into_inner()
+        .replicas;
+    assert!(!replicas
+        .iter()
+        .any(|r| r.name.starts_with(&replica_snapshot)));
+    let error = volume_client
+        .delete_snapshot(&DeleteSnapshot::new(volume_id.clone(), snapshot_id), None)
+        .await
+        .expect_err("The snapshot no longer exists");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+    assert_eq!(error.resource, ResourceKind::VolumeSnapshot);
+
+    let snapshots = volume_client
+        .list_snapshots(&ListSnapshots::new(volume_id.clone()), None)
+        .await
+        .unwrap();
+    assert!(snapshots.entries.is_empty());
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn rebuild_limit_test(cluster: &Cluster) {
+    let registry_client = cluster.grpc_client().registry();
+    let limit = registry_client.get_rebuild_limit(None).await.unwrap();
+    assert_eq!(limit.max_rebuilds, None);
+    assert_eq!(limit.num_rebuilds, 0);
+
+    let limit = registry_client
+        .set_rebuild_limit(&SetRebuildLimit::new(Some(2)), None)
+        .await
+        .unwrap();
+    assert_eq!(limit.max_rebuilds, Some(2));
+    assert_eq!(
+        registry_client.get_rebuild_limit(None).await.unwrap(),
+        limit
+    );
+
+    // the override is persisted so it's not lost when the core agent restarts
+    let mut store = Etcd::new("0.0.0.0:2379")
+        .await
+        .expect("Failed to connect to etcd.");
+    let config: CoreRegistryConfig = store
+        .get_obj(&CoreRegistryConfigKey::default())
+        .await
+        .unwrap();
+    assert_eq!(config.max_rebuilds(), Some(MaxRebuilds::Limited(2)));
+
+    let limit = registry_client
+        .set_rebuild_limit(&SetRebuildLimit::new(None), None)
+        .await
+        .unwrap();
+    assert_eq!(limit.max_rebuilds, None);
+    let config: CoreRegistryConfig = store
+        .get_obj(&CoreRegistryConfigKey::default())
+        .await
+        .unwrap();
+    assert_eq!(config.max_rebuilds(), Some(MaxRebuilds::Unlimited));
+}
+
+async fn idempotency_key_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let create = CreateVolume {
+        uuid: VolumeId::try_from("3f6a1c8e-2d4b-4e7a-9c5f-8b1d2e3a4c6f").unwrap(),
+        size: 5242880,
+        replicas: 1,
+        idempotency_key: Some("create-3f6a1c8e".to_string()),
+        ..Default::default()
+    };
+    let volume = volume_client.create(&create, None).await.unwrap();
+    assert_eq!(
+        volume.spec().idempotency_key,
+        Some("create-3f6a1c8e".to_string())
+    );
+
+    let retried = volume_client
+        .create(&create, None)
+        .await
+        .expect("a retry with the same key and parameters returns the volume");
+    assert_eq!(retried.spec(), volume.spec());
+
+    // only the original create parameters are compared, not the later changes to the volume
+    let labels = vec![("app".to_string(), "web".to_string())]
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    volume_client
+        .set_labels(&SetVolumeLabels::new(create.uuid.clone(), labels), None)
+        .await
+        .unwrap();
+    volume_client
+        .create(&create, None)
+        .await
+        .expect("a retry after the volume was changed returns the volume");
+
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("7d2e9b4a-5c1f-4a8e-b3d6-0e9f2a1c8b5d").unwrap(),
+                ..create.clone()
+            },
+            None,
+        )
+        .await
+        .expect_err("the same key for another volume is rejected");
+    assert_eq!(error.kind, ReplyErrorKind::Conflict);
+
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                size: 2 * 5242880,
+                ..create.clone()
+            },
+            None,
+        )
+        .await
+        .expect_err("the same key with different parameters is rejected");
+    assert_eq!(error.kind, ReplyErrorKind::Conflict);
+
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                idempotency_key: None,
+                ..create.clone()
+            },
+            None,
+        )
+        .await
+        .expect_err("a creation without the key is not a retry");
+    assert_eq!(error.kind, ReplyErrorKind::AlreadyExists);
+
+    volume_client
+        .destroy(&DestroyVolume { uuid: create.uuid }, None)
+        .await
+        .unwrap();
+}
+
+async fn preferred_pools_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let replica_client = cluster.grpc_client().replica();
+    let preferred = cluster.pool(2, 0);
+    let uuid = VolumeId::try_from("0c4a2e6f-3b1d-4f8a-9e7c-5d2b8a6f1e3c").unwrap();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: uuid.clone(),
+                size: 5242880,
+                replicas: 1,
+                topology: Some(Topology {
+                    node: None,
+                    pool: None,
+                    spread_topology_key: None,
+                    preferred_pools: vec!["unknown-pool".into(), preferred.clone()],
+                }),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        volume.spec().topology.unwrap().preferred_pools,
+        vec!["unknown-pool".into(), preferred.clone()]
+    );
+    let replicas = replica_client
+        .get(Filter::Volume(uuid.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(replicas.len(), 1);
+    assert_eq!(
+        replicas[0].pool, preferred,
+        "the preferred pool is used first"
+    );
+
+    // the preferred pools must still have enough free space
+    let full = VolumeId::try_from("7e1b5d3a-6c2f-4a9e-8b4d-1f3c7a5e2b6d").unwrap();
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                uuid: full.clone(),
+                size: u64::MAX,
+                replicas: 1,
+                topology: Some(Topology {
+                    node: None,
+                    pool: None,
+                    spread_topology_key: None,
+                    preferred_pools: vec![preferred],
+                }),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .expect_err("no pool has enough free space");
+    assert_eq!(error.kind, ReplyErrorKind::ResourceExhausted);
+
+    volume_client
+        .destroy(&DestroyVolume { uuid }, None)
+        .await
+        .unwrap();
+}
+
+async fn encryption_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let uuid = VolumeId::try_from("5b1e7c3a-8d2f-4e6a-9c1b-7f3d5a2e8b4c").unwrap();
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                uuid: uuid.clone(),
+                size: 5242880,
+                replicas: 1,
+                encryption: Some(ReplicaEncryption::new(EncryptionCipher::AesXts, "key-1")),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .expect_err("the io-engine can't create encrypted replicas");
+    assert_eq!(error.kind, ReplyErrorKind::Unimplemented);
+
+    let volumes = volume_client
+        .get(Filter::Volume(uuid.clone()), None, None)
+        .await
+        .unwrap();
+    assert!(volumes.entries.is_empty());
+    let replicas = cluster
+        .grpc_client()
+        .replica()
+        .get(Filter::Volume(uuid), None, None)
+        .await
+        .unwrap();
+    assert!(replicas.into_inner().is_empty());
+}
+
+async fn specs_stream_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("9d3c6b2a-1e4f-4a7b-8c5d-2f6e8a1b3c4d").unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let chunks = registry_client
+        .list_specs_stream(Some(1), None)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let mut streamed = Specs::default();
+    for chunk in chunks {
+        let sizes = [
+            chunk.volumes.len(),
+            chunk.nexuses.len(),
+            chunk.pools.len(),
+            chunk.replicas.len(),
+        ];
+        // each chunk holds a single spec of a single resource kind
+        assert_eq!(sizes.iter().sum::<usize>(), 1, "{:?}", chunk);
+        streamed.volumes.extend(chunk.volumes);
+        streamed.nexuses.extend(chunk.nexuses);
+        streamed.pools.extend(chunk.pools);
+        streamed.replicas.extend(chunk.replicas);
+    }
+    assert_eq!(streamed, specs);
+
+    let chunks = registry_client
+        .list_specs_stream(None, None)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    let kinds = [
+        specs.volumes.len(),
+        specs.nexuses.len(),
+        specs.pools.len(),
+        specs.replicas.len(),
+    ];
+    assert_eq!(chunks.len(), kinds.iter().filter(|len| **len > 0).count());
+
+    volume_client
+        .destroy(&DestroyVolume::new(volume.uuid()), None)
+        .await
+        .unwrap();
+}
+
+async fn filtered_specs_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("5e7d1c3a-2b4f-4c6e-9a8d-7f1e3b5c2d4a").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    let volume = volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    let nexus = volume.state().target.unwrap();
+
+    let specs = registry_client
+        .get_filtered_specs(Filter::Volume(volume_id.clone()), None)
+        .await
+        .unwrap();
+    assert_eq!(specs.volumes.len(), 1);
+    assert_eq!(specs.volumes[0].uuid, volume_id);
+    assert_eq!(specs.replicas.len(), 1);
+    assert!(specs.replicas[0].owners.owned_by(&volume_id));
+    assert_eq!(specs.nexuses.len(), 1);
+    assert_eq!(specs.nexuses[0].uuid, nexus.uuid);
+    assert_eq!(specs.pools.len(), 1);
+    assert_eq!(specs.pools[0].id, specs.replicas[0].pool);
+
+    let pool = specs.pools[0].id.clone();
+    let pool_specs = registry_client
+        .get_filtered_specs(Filter::Pool(pool.clone()), None)
+        .await
+        .unwrap();
+    assert_eq!(pool_specs.pools, specs.pools);
+    assert!(pool_specs.replicas.iter().all(|r| r.pool == pool));
+    assert!(pool_specs.volumes.iter().any(|v| v.uuid == volume_id));
+    assert!(pool_specs.nexuses.iter().any(|n| n.uuid == nexus.uuid));
+
+    let node_specs = registry_client
+        .get_filtered_specs(Filter::Node(nexus.node.clone()), None)
+        .await
+        .unwrap();
+    assert!(node_specs.nexuses.iter().all(|n| n.node == nexus.node));
+    assert!(node_specs.volumes.iter().any(|v| v.uuid == volume_id));
+
+    let error = registry_client
+        .get_filtered_specs(Filter::Nexus(nexus.uuid.clone()), None)
+        .await
+        .expect_err("unsupported filter");
+    assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn transport_params_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("0c2a5f4e-9b1d-4e7a-8c3f-6d5e4f3a2b1c").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+
+    let params = |params: &[(&str, &str)]| {
+        params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>()
+    };
+    for invalid in [
+        params(&[("ioQueues", "4")]),
+        params(&[("cntlidMin", "abc")]),
+        params(&[("cntlidMin", "100"), ("cntlidMax", "10")]),
+    ] {
+        let error = volume_client
+            .publish(
+                &PublishVolume::new(volume_id.clone(), None, None).with_transport_params(invalid),
+                None,
+            )
+            .await
+            .expect_err("invalid transport parameters");
+        assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+    }
+
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(volume_id.clone(), None, None)
+                .with_transport_params(params(&[("cntlidMin", "10"), ("cntlidMax", "100")])),
+            None,
+        )
+        .await
+        .unwrap();
+    let nexus = volume.state().target.unwrap();
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let nexus_spec = specs.nexuses.iter().find(|n| n.uuid == nexus.uuid).unwrap();
+    let config = nexus_spec.config.clone().unwrap();
+    assert_eq!((config.min_cntl_id(), config.max_cntl_id()), (10, 100));
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn cordon_test(cluster: &Cluster) {
+    let node_client = cluster.grpc_client().node();
+    let volume_client = cluster.grpc_client().volume();
+    let label = "maintenance".to_string();
+    for node in [cluster.node(0), cluster.node(1)] {
+        let node = node_client
+            .cordon(&CordonNode::new(node, label.clone()), None)
+            .await
+            .unwrap();
+        assert!(node.spec().unwrap().cordoned());
+    }
+
+    // only a single node is schedulable
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("6a1f0e2d-4c3b-4a5e-8f9d-0b1c2d3e4f5a").unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .expect_err("only one node is not cordoned");
+    assert_eq!(error.kind, ReplyErrorKind::ResourceExhausted);
+
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("6a1f0e2d-4c3b-4a5e-8f9d-0b1c2d3e4f5b").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    let replicas = cluster
+        .grpc_client()
+        .replica()
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(replicas.len(), 1);
+    assert_eq!(replicas[0].node, cluster.node(2));
+
+    // new targets can't be placed on the cordoned nodes either
+    let error = volume_client
+        .publish(
+            &PublishVolume::new(volume_id.clone(), Some(cluster.node(0)), None),
+            None,
+        )
+        .await
+        .expect_err("the node is cordoned");
+    assert_eq!(error.kind, ReplyErrorKind::FailedPrecondition);
+    let volume = volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    assert_eq!(volume.state().target.unwrap().node, cluster.node(2));
+
+    for node in [cluster.node(0), cluster.node(1)] {
+        let node = node_client
+            .uncordon(&UncordonNode::new(node, label.clone()), None)
+            .await
+            .unwrap();
+        assert!(!node.spec().unwrap().cordoned());
+    }
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn pool_rejections_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+
+    // none of the pools can fit the volume
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("7b2e1f3c-5d4a-4b6f-9a0e-1c2d3e4f5a6b").unwrap(),
+                size: 1024 * 1024 * 1024 * 1024,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .expect_err("the pools are too small");
+    assert_eq!(error.kind, ReplyErrorKind::ResourceExhausted);
+    assert_eq!(error.resource, ResourceKind::Pool);
+    for node in 0 .. 3 {
+        let rejection = format!("'{}' (pool is too small", cluster.pool(node, 0));
+        assert!(error.extra.contains(&rejection), "{}", error.extra);
+    }
+}
+
+async fn add_remove_replica_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let replica_client = cluster.grpc_client().replica();
+
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("2c4f6a8b-1d3e-4f5a-8b7c-9d0e1f2a3b4c").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    let volume_replica = replica_client
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
+    let node = (0 .. 3)
+        .find(|node| cluster.node(*node) != volume_replica.node)
+        .unwrap();
+
+    // a replica which is not owned by any resource
+    let replica = replica_client
+        .create(
+            &CreateReplica {
+                node: cluster.node(node),
+                uuid: ReplicaId::new(),
+                pool: cluster.pool(node, 0),
+                size: volume.spec().size,
+                managed: true,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let volume = volume_client
+        .add_replica(
+            &AddVolumeReplica::new(volume_id.clone(), replica.uuid.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volume.spec().num_replicas, 2);
+    let error = volume_client
+        .add_replica(
+            &AddVolumeReplica::new(volume_id.clone(), replica.uuid.clone()),
+            None,
+        )
+        .await
+        .expect_err("The replica is already part of the volume");
+    assert_eq!(error.kind, ReplyErrorKind::FailedPrecondition);
+
+    let volume = volume_client
+        .remove_replica(
+            &RemoveVolumeReplica::new(volume_id.clone(), replica.uuid.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volume.spec().num_replicas, 1);
+    let replicas = replica_client
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(replicas.len(), 1);
+    assert_eq!(replicas[0].uuid, volume_replica.uuid);
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn annotations_test(cluster: &Cluster) {
+    let annotations = vec![
+        ("owner".to_string(), "bob".to_string()),
+        ("ticket".to_string(), "42".to_string()),
+    ]
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+    let volume_client = cluster.grpc_client().volume();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("0f9d5e4c-8b2a-4c3e-9a7d-5e6f1b2c3d4e").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                annotations: annotations.clone(),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    assert_eq!(volume.spec().annotations, annotations);
+    // annotations are kept apart from the scheduling labels
+    assert_eq!(volume.spec().labels, None);
+
+    // the annotations should be persisted in the store
+    let mut store = Etcd::new("0.0.0.0:2379")
+        .await
+        .expect("Failed to connect to etcd.");
+    let volume_spec: VolumeSpec = store.get_obj(&volume.spec().key()).await.unwrap();
+    assert_eq!(volume_spec.annotations, annotations);
+
+    // and the volumes can be listed by their annotations
+    let volumes_api = cluster.rest_v00().volumes_api();
+    let volumes = volumes_api
+        .get_volumes(0, None, Some("owner=bob"))
+        .await
+        .unwrap()
+        .entries;
+    assert_eq!(volumes.len(), 1);
+    assert_eq!(volumes[0].spec.annotations, Some(annotations.clone()));
+
+    // the annotations are filtered before paginating, so the other volumes don't use up the page
+    let other_volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("3b8e1f2a-7c4d-4a9e-8f6b-2d1c0e9a8b7f").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volumes = volumes_api
+        .get_volumes(1, None, Some("owner=bob"))
+        .await
+        .unwrap();
+    assert_eq!(volumes.entries.len(), 1);
+    assert_eq!(
+        volumes.entries[0].spec.uuid.to_string(),
+        volume_id.to_string()
+    );
+    assert_eq!(volumes.next_token, None);
+    let volumes = volume_client
+        .get(
+            Filter::VolumeAnnotations(annotations),
+            Some(Pagination::new(1, 0)),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volumes.entries.len(), 1);
+    assert_eq!(volumes.entries[0].uuid(), &volume_id);
+    assert_eq!(volumes.next_token, None);
+    volume_client
+        .destroy(&DestroyVolume::new(other_volume.uuid()), None)
+        .await
+        .unwrap();
+    let volumes = volumes_api
+        .get_volumes(0, None, Some("owner=bob,ticket=43"))
+        .await
+        .unwrap()
+        .entries;
+    assert!(volumes.is_empty());
+    let error = volumes_api
+        .get_volumes(0, None, Some("owner"))
+        .await
+        .expect_err("the annotations filter is invalid");
+    match error {
+        Error::Response(response) => assert_eq!(response.status(), StatusCode::BAD_REQUEST),
+        _ => panic!("Expected a response error"),
+    }
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn labels_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("6a3c2f8e-1d4b-4e7a-b5c9-0f2e8d7a6b14").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                labels: Some(
+                    vec![("app".to_string(), "db".to_string())]
+                        .into_iter()
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+
+    let labels = vec![
+        ("app".to_string(), "web".to_string()),
+        ("tier".to_string(), "frontend".to_string()),
+    ]
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+    let volume = volume_client
+        .set_labels(
+            &SetVolumeLabels::new(volume_id.clone(), labels.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volume.spec().labels, Some(labels.clone()));
+
+    // the labels should be persisted in the store
+    let mut store = Etcd::new("0.0.0.0:2379")
+        .await
+        .expect("Failed to connect to etcd.");
+    let volume_spec: VolumeSpec = store.get_obj(&volume.spec().key()).await.unwrap();
+    assert_eq!(volume_spec.labels, Some(labels));
+
+    // an empty set of labels removes all the labels
+    let volume = volume_client
+        .set_labels(
+            &SetVolumeLabels::new(volume_id.clone(), HashMap::new()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volume.spec().labels, None);
+
+    // relabelling a published volume doesn't disturb its target
+    let volume = volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    let target = volume.spec().target;
+    assert!(target.is_some());
+
+    let annotations = vec![("owner".to_string(), "team-a".to_string())]
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let volume = volume_client
+        .set_labels(
+            &SetVolumeLabels::new(volume_id.clone(), labels.clone())
+                .with_annotations(annotations.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volume.spec().labels, Some(labels.clone()));
+    assert_eq!(volume.spec().annotations, annotations);
+    assert_eq!(volume.spec().target, target);
+
+    // merging overwrites the existing keys and keeps the others
+    let volume = volume_client
+        .set_labels(
+            &SetVolumeLabels::new(
+                volume_id.clone(),
+                vec![("app".to_string(), "api".to_string())]
+                    .into_iter()
+                    .collect(),
+            )
+            .with_annotations(
+                vec![("ticket".to_string(), "123".to_string())]
+                    .into_iter()
+                    .collect(),
+            )
+            .with_merge(true),
+            None,
+        )
+        .await
+        .unwrap();
+    let labels = volume.spec().labels.unwrap();
+    assert_eq!(labels.get("app").map(String::as_str), Some("api"));
+    assert_eq!(labels.get("tier").map(String::as_str), Some("frontend"));
+    assert_eq!(volume.spec().annotations.len(), 2);
+    assert_eq!(volume.spec().target, target);
+
+    // not specifying the annotations leaves them unchanged
+    let volume = volume_client
+        .set_labels(
+            &SetVolumeLabels::new(volume_id.clone(), HashMap::new()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volume.spec().labels, None);
+    assert_eq!(volume.spec().annotations.len(), 2);
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn expand_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("0e5d7a4c-3b8f-4a61-9c2d-7f1e6b5a4d38").unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+
+    // shrinking, or "expanding" to the same size is not allowed
+    for size in [5242880 / 2, 5242880] {
+        let error = volume_client
+            .expand(&ExpandVolume::new(volume_id.clone(), size), None)
+            .await
+            .expect_err("The new size must be larger than the current size");
+        assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+    }
+
+    let size = 5242880 * 2;
+    let expanded = volume_client
+        .expand(&ExpandVolume::new(volume_id.clone(), size), None)
+        .await
+        .unwrap();
+    assert_eq!(expanded.spec().size, size);
+    assert!(expanded.state().target.unwrap().size >= size);
+    let replicas = cluster
+        .grpc_client()
+        .replica()
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(replicas.len(), 2);
+    assert!(replicas.iter().all(|r| r.size >= size));
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn republishing_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let nexus_client = cluster.grpc_client().nexus();
+    let volumes_api = cluster.rest_v00().volumes_api();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("6f3c1d2e-8b4a-4f57-a0c9-2e7d5b1a9c64").unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+
+    let error = volume_client
+        .republish(
+            &RepublishVolume::new(volume_id.clone(), cluster.node(1), None),
+            None,
+        )
+        .await
+        .expect_err("The volume is not published");
+    assert_eq!(error.kind, ReplyErrorKind::NotPublished);
+
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(
+                volume_id.clone(),
+                Some(cluster.node(0)),
+                Some(VolumeShareProtocol::Nvmf),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    let old_nexus = volume.state().target.unwrap();
+
+    let error = volume_client
+        .republish(
+            &RepublishVolume::new(volume_id.clone(), cluster.node(0), None),
+            None,
+        )
+        .await
+        .expect_err("The volume is already published on this node");
+    assert_eq!(error.kind, ReplyErrorKind::AlreadyPublished);
+
+    let error = volume_client
+        .republish(
+            &RepublishVolume::new(volume_id.clone(), "unknown".into(), None),
+            None,
+        )
+        .await
+        .expect_err("The node does not exist");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+
+    // the current share protocol is kept
+    let volume = volume_client
+        .republish(
+            &RepublishVolume::new(volume_id.clone(), cluster.node(1), None),
+            None,
+        )
+        .await
+        .unwrap();
+    let nexus = volume.state().target.unwrap();
+    assert_eq!(nexus.node, cluster.node(1));
+    assert_ne!(nexus.uuid, old_nexus.uuid);
+    assert_eq!(
+        volume.state().target_protocol(),
+        Some(VolumeShareProtocol::Nvmf)
+    );
+    assert_eq!(volume.spec().last_nexus_id, Some(nexus.uuid.clone()));
+
+    // the previous target is gone
+    let nexuses = nexus_client
+        .get(Filter::None, None, None)
+        .await
+        .unwrap()
+        .entries;
+    assert!(nexuses.iter().any(|n| n.uuid == nexus.uuid));
+    assert!(!nexuses.iter().any(|n| n.uuid == old_nexus.uuid));
+
+    let volume = volumes_api
+        .put_volume_republish(
+            &volume_id,
+            cluster.node(2).as_str(),
+            Some(models::VolumeShareProtocol::Nvmf),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        volume.spec.target.map(|t| t.node),
+        Some(cluster.node(2).to_string())
+    );
+    let events = volumes_api
+        .get_volume_events(&volume_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.event == models::volume_event::Event::Republished)
+        .count();
+    assert_eq!(events, 2);
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+async fn event_history_test(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let volumes_api = cluster.rest_v00().volumes_api();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("b91b6e2f-4ab5-4c8c-9a3e-5a0d3b6f8d55").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+
+    for _ in 0 .. 2 {
+        volume_client
+            .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+            .await
+            .unwrap();
+        volume_client
+            .unpublish(&UnpublishVolume::new(&volume_id, false), None)
+            .await
+            .unwrap();
+    }
+
+    let events = volumes_api
+        .get_volume_events(&volume_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.event)
+        .collect::<Vec<_>>();
+    use models::volume_event::Event;
+    assert_eq!(
+        events,
+        vec![
+            Event::Created,
+            Event::Published,
+            Event::Unpublished,
+            Event::Republished,
+            Event::Unpublished
+        ]
+    );
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+const RECONCILE_TIMEOUT_SECS: u64 = 7;
+
+#[tokio::test]
+async fn volume_from_snapshot() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_io_engines(1)
+        .with_pools(1)
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1000), Duration::from_secs(1000))
+        .build()
+        .await
+        .unwrap();
+    let volume_client = cluster.grpc_client().volume();
+
+    let source = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("8b3e6f2a-1c4d-4a9e-b7f5-0d2c9e8a1b64").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    volume_client
+        .publish(&PublishVolume::new(source.uuid().clone(), None, None), None)
+        .await
+        .unwrap();
+    let snapshot = volume_client
+        .create_snapshot(
+            &CreateSnapshot::new(source.uuid().clone(), SnapshotId::new()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let clone = CreateVolume {
+        uuid: VolumeId::try_from("2f7a9c1e-5b3d-4e8f-a6c2-9d1b0e7f3a45").unwrap(),
+        size: 5242880,
+        replicas: 1,
+        source_snapshot: Some(SnapshotId::new()),
+        ..Default::default()
+    };
+    let error = volume_client
+        .create(&clone, None)
+        .await
+        .expect_err("The source snapshot does not exist");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+    assert_eq!(error.resource, ResourceKind::VolumeSnapshot);
+
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                size: 5242880 * 2,
+                source_snapshot: Some(snapshot.snapshot_id.clone()),
+                ..clone.clone()
+            },
+            None,
+        )
+        .await
+        .expect_err("The clone must have the size of the snapshot");
+    assert_eq!(error.kind, ReplyErrorKind::InvalidArgument);
+
+    // the snapshot is valid, but the io-engine can't clone its replicas
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                source_snapshot: Some(snapshot.snapshot_id.clone()),
+                ..clone.clone()
+            },
+            None,
+        )
+        .await
+        .expect_err("The io-engine does not support clones");
+    assert_eq!(error.kind, ReplyErrorKind::Unimplemented);
+
+    // leave an incomplete snapshot behind, as if the core agent died whilst taking it
+    cluster.composer().stop("core").await.unwrap();
+    let mut store = Etcd::new("0.0.0.0:2379")
+        .await
+        .expect("Failed to connect to etcd.");
+    let incomplete = VolumeSnapshotSpec {
+        uuid: SnapshotId::new(),
+        volume: source.uuid().clone(),
+        name: String::new(),
+        size: 5242880,
+        timestamp: std::time::SystemTime::now(),
+        replicas: vec![],
+        status: SpecStatus::Creating,
+    };
+    store.put_obj(&incomplete).await.unwrap();
+    cluster.restart_core().await;
+    let timeout_opts = TimeoutOptions::default()
+        .with_max_retries(10)
+        .with_timeout(Duration::from_millis(200))
+        .with_timeout_backoff(Duration::from_millis(50));
+    cluster
+        .volume_service_liveness(Some(timeout_opts))
+        .await
+        .expect("Should have restarted by now");
+
+    let error = volume_client
+        .create(
+            &CreateVolume {
+                source_snapshot: Some(incomplete.uuid.clone()),
+                ..clone.clone()
+            },
+            None,
+        )
+        .await
+        .expect_err("An incomplete snapshot cannot be cloned");
+    assert_eq!(error.kind, ReplyErrorKind::FailedPrecondition);
+    assert_eq!(error.resource, ResourceKind::VolumeSnapshot);
+
+    // none of the failed attempts should have left a volume behind
+    let error = volume_client
+        .get(Filter::Volume(clone.uuid.clone()), None, None)
+        .await
+        .expect_err("The clone should not exist");
+    assert_eq!(error.kind, ReplyErrorKind::NotFound);
+
+    for snapshot in vec![snapshot.snapshot_id, incomplete.uuid] {
+        volume_client
+            .delete_snapshot(&DeleteSnapshot::new(source.uuid().clone(), snapshot), None)
+            .await
+            .unwrap();
+    }
+    volume_client
+        .destroy(&DestroyVolume::new(source.uuid()), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn hotspare() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(true)
+        .with_agents(vec!["core"])
+        .with_io_engines(3)
+        .with_pools(2)
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .build()
+        .await
+        .unwrap();
+
+    let node_client = cluster.grpc_client().node();
+    let nodes = node_client.get(Filter::None, None).await.unwrap();
+    tracing::info!("Nodes: {:?}", nodes);
+
+    hotspare_faulty_children(&cluster).await;
+    hotspare_unknown_children(&cluster).await;
+    hotspare_missing_children(&cluster).await;
+    hotspare_stale_children(&cluster).await;
+    hotspare_unauthoritative_children(&cluster).await;
+    hotspare_replica_count(&cluster).await;
+    hotspare_replica_count_spread(&cluster).await;
+    hotspare_nexus_replica_count(&cluster).await;
+}
+
+#[tokio::test]
+async fn node_drain() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(true)
+        .with_agents(vec!["core"])
+        .with_io_engines(3)
+        .with_pools(1)
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .build()
+        .await
+        .unwrap();
+
+    let node_client = cluster.grpc_client().node();
+    let volume_client = cluster.grpc_client().volume();
+    let replica_client = cluster.grpc_client().replica();
+
+    // an unpublished volume, whose replica on the drained node gets recreated on another node
+    let unpublished = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("5e4d3c2b-1a0f-4e9d-8c7b-6a5f4e3d2c1b").unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let unpublished_id = unpublished.uuid().clone();
+    let replicas = replica_client
+        .get(Filter::Volume(unpublished_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    let drain_node = replicas[0].node.clone();
+
+    // a volume published on the drained node, whose target gets republished on another node
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("2b0f7c1e-5d4a-4e3b-9c8d-7a6b5c4d3e2f").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    volume_client
+        .publish(
+            &PublishVolume::new(volume_id.clone(), Some(drain_node.clone()), None),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let node = node_client
+        .drain(
+            &DrainNode::new(drain_node.clone(), "drain".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    let spec = node.spec().unwrap();
+    assert!(spec.cordoned());
+    assert_eq!(spec.drain_state(), Some(&NodeDrainState::Draining));
+
+    let start = std::time::Instant::now();
+    let timeout = Duration::from_secs(RECONCILE_TIMEOUT_SECS * 3);
+    loop {
+        let node = node_client
+            .get(Filter::Node(drain_node.clone()), None)
+            .await
+            .unwrap()
+            .into_inner()
+            .remove(0);
+        if node.spec().unwrap().drain_state() == Some(&NodeDrainState::Drained) {
+            break;
+        }
+        if std::time::Instant::now() > (start + timeout) {
+            panic!("Timeout waiting for the node to be drained");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    for (volume, replica_count) in [(&volume_id, 1), (&unpublished_id, 2)] {
+        let replicas = replica_client
+            .get(Filter::Volume(volume.clone()), None, None)
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(replicas.len(), replica_count);
+        assert!(replicas.iter().all(|replica| replica.node != drain_node));
+    }
+    let volume = volume_client
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
+    let target = volume
+        .state()
+        .target
+        .expect("Volume should still be published");
+    assert_ne!(target.node, drain_node);
+    // no replica move is left in progress in the persisted node spec
+    let mut store = Etcd::new("0.0.0.0:2379")
+        .await
+        .expect("Failed to connect to etcd.");
+    let spec: NodeSpec = store
+        .get_obj(&NodeSpecKey::from(&drain_node))
+        .await
+        .unwrap();
+    assert!(spec.replica_moves().is_empty());
+
+    // uncordoning the node also clears its drain state
+    let node = node_client
+        .uncordon(&UncordonNode::new(drain_node, "drain".to_string()), None)
+        .await
+        .unwrap();
+    assert_eq!(node.spec().unwrap().drain_state(), None);
+
+    for volume in [volume_id, unpublished_id] {
+        volume_client
+            .destroy(&DestroyVolume::new(&volume), None)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn replica_move() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(true)
+        .with_agents(vec!["core"])
+        .with_io_engines(1)
+        .with_pools(2)
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .build()
+        .await
+        .unwrap();
+
+    let volume_client = cluster.grpc_client().volume();
+    let replica_client = cluster.grpc_client().replica();
+
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::try_from("6d1c2b3a-4e5f-4a7b-8c9d-0e1f2a3b4c5d").unwrap(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    let source = replica_client
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
+    let destination_pool = if source.pool == cluster.pool(0, 0) {
+        cluster.pool(0, 1)
+    } else {
+        cluster.pool(0, 0)
+    };
+
+    // the only healthy child of the volume target is not moved unless forced
+    let error = replica_client
+        .move_replica(&MoveReplica::new(&source, &destination_pool), None)
+        .await
+        .expect_err("The only healthy replica should not be moved");
+    assert_eq!(error.kind, ReplyErrorKind::FailedPrecondition);
+
+    let replica = replica_client
+        .move_replica(
+            &MoveReplica::new(&source, &destination_pool).with_force(true),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(replica.pool, destination_pool);
+    assert_ne!(replica.uuid, source.uuid);
+
+    // once the new replica is rebuilt, the source replica is destroyed
+    let start = std::time::Instant::now();
+    let timeout = Duration::from_secs(RECONCILE_TIMEOUT_SECS * 3);
+    loop {
+        let replicas = replica_client
+            .get(Filter::Volume(volume_id.clone()), None, None)
+            .await
+            .unwrap()
+            .into_inner();
+        if replicas.len() == 1 && replicas[0].uuid == replica.uuid {
+            break;
+        }
+        if std::time::Instant::now() > (start + timeout) {
+            panic!("Timeout waiting for the replica to be moved");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    volume_client
+        .destroy(&DestroyVolume::new(&volume_id), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn hotspare_faulted_child_grace_period() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(true)
+        .with_agents(vec!["core"])
+        .with_io_engines(3)
+        .with_pools(1)
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .with_faulted_child_grace_period(Duration::from_secs(3600))
+        .build()
+        .await
+        .unwrap();
+
+    let volume_client = cluster.grpc_client().volume();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: "9c5d3c0e-3b38-4f2e-8f5e-2b6f0c1e7a44".try_into().unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(volume.spec().uuid.clone(), Some(cluster.node(0)), None),
+            None,
+        )
+        .await
+        .unwrap();
+    let nexus = volume.state().target.unwrap();
+
+    let mut rpc_handle = cluster.grpc_handle(cluster.node(0).as_str()).await.unwrap();
+    let fault_child = nexus.children.first().unwrap().uri.clone();
+    rpc_handle
+        .io_engine
+        .fault_nexus_child(FaultNexusChildRequest {
+            uuid: nexus.uuid.to_string(),
+            uri: fault_child.to_string(),
+        })
+        .await
+        .unwrap();
+
+    // give the reconcilers a few chances to remove the faulted child
+    tokio::time::sleep(Duration::from_secs(RECONCILE_TIMEOUT_SECS)).await;
+
+    // the child has not been faulted for longer than the grace period, so it must not be removed
+    let children = volume_children(volume.uuid(), &volume_client).await;
+    tracing::info!("volume children: {:?}", children);
+    let child = children.iter().find(|c| c.uri == fault_child);
+    assert_eq!(child.map(|c| &c.state), Some(&ChildState::Faulted));
+    assert_eq!(children.len(), 2);
+
+    volume_client
+        .destroy(&DestroyVolume::new(volume.uuid()), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn hotspare_degraded_child_onlined() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_io_engines(3)
+        .with_pools(1)
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .build()
+        .await
+        .unwrap();
+
+    let volume_client = cluster.grpc_client().volume();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: "4d7b2e9a-1c3f-4a8e-b5d6-7e9f0a1b2c3d".try_into().unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(volume.spec().uuid.clone(), Some(cluster.node(0)), None),
+            None,
+        )
+        .await
+        .unwrap();
+    let nexus = volume.state().target.unwrap();
+
+    // an offline child is degraded, but its replica's node is online
+    let mut rpc_handle = cluster.grpc_handle(cluster.node(0).as_str()).await.unwrap();
+    let offline_child = nexus.children.first().unwrap().uri.clone();
+    rpc_handle
+        .io_engine
+        .child_operation(ChildNexusRequest {
+            uuid: nexus.uuid.to_string(),
+            uri: offline_child.to_string(),
+            action: ChildAction::Offline as i32,
+        })
+        .await
+        .unwrap();
+
+    // so the reconciler brings it back online, rather than replacing it
+    let timeout = Duration::from_secs(RECONCILE_TIMEOUT_SECS * 3);
+    let start = std::time::Instant::now();
+    let children = loop {
+        let children = volume_children(volume.uuid(), &volume_client).await;
+        let child = children.iter().find(|c| c.uri == offline_child);
+        if child.map(|c| &c.state) == Some(&ChildState::Online) {
+            break children;
+        }
+        if start.elapsed() > timeout {
+            panic!(
+                "Timeout waiting for the degraded child to be onlined: {:?}",
+                children
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    };
+    assert_eq!(children.len(), 2);
+
+    volume_client
+        .destroy(&DestroyVolume::new(volume.uuid()), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn hotspare_rebuild_limit() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_io_engines(3)
+        .with_pools(1)
+        .with_cache_period("1s")
+        .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .build()
+        .await
+        .unwrap();
+
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::new(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(volume.spec().uuid.clone(), Some(cluster.node(0)), None),
+            None,
+        )
+        .await
+        .unwrap();
+    let nexus = volume.state().target.unwrap();
+
+    // no rebuilds may be started, so the missing child may not be replaced
+    registry_client
+        .set_rebuild_limit(&SetRebuildLimit::new(Some(0)), None)
+        .await
+        .unwrap();
+
+    let mut rpc_handle = cluster.grpc_handle(cluster.node(0).as_str()).await.unwrap();
+    let missing_child = nexus.children.first().unwrap().uri.to_string();
+    rpc_handle
+        .io_engine
+        .remove_child_nexus(rpc::io_engine::RemoveChildNexusRequest {
+            uuid: nexus.uuid.to_string(),
+            uri: missing_child.clone(),
+        })
+        .await
+        .unwrap();
+
+    // wait till the reconcilers have removed the missing child from the nexus spec
+    wait_till_volume_nexus(
+        volume.uuid(),
+        1,
+        &missing_child,
+        &volume_client,
+        &registry_client,
+    )
+    .await;
+    // then for a few reconcile periods they must keep deferring the child addition
+    let timeout = Duration::from_secs(RECONCILE_TIMEOUT_SECS);
+    let start = std::time::Instant::now();
+    while start.elapsed() < timeout {
+        let children = volume_children(volume.uuid(), &volume_client).await;
+        assert_eq!(children.len(), 1, "{:?}", children);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    // once the limit is lifted the deferred child addition is carried out
+    registry_client
+        .set_rebuild_limit(&SetRebuildLimit::new(None), None)
+        .await
+        .unwrap();
+    let children = wait_till_volume_nexus(
+        volume.uuid(),
+        2,
+        &missing_child,
+        &volume_client,
+        &registry_client,
+    )
+    .await;
+    assert!(!children.iter().any(|c| c.uri == missing_child));
+
+    volume_client
+        .destroy(&DestroyVolume::new(volume.uuid()), None)
+        .await
+        .unwrap();
+}
+
+const POOL_SIZE_BYTES: u64 = 128 * 1024 * 1024;
+/// Test that a replica which fails to be added to a volume is not left owned by the volume
+#[tokio::test]
+async fn add_volume_replica_failure() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_agents(vec!["core"])
+        .with_io_engines(2)
+        .with_pools(1)
+        .with_req_timeouts(Duration::from_millis(250), Duration::from_millis(500))
+        .build()
+        .await
+        .unwrap();
+    let volume_client = cluster.grpc_client().volume();
+    let replica_client = cluster.grpc_client().replica();
+    let registry_client = cluster.grpc_client().registry();
+
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::new(),
+                size: 5242880,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    let volume_replica = replica_client
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
+    volume_client
+        .publish(
+            &PublishVolume::new(volume_id.clone(), Some(volume_replica.node.clone()), None),
+            None,
+        )
+        .await
+        .unwrap();
+    let node = (0 .. 2)
+        .find(|node| cluster.node(*node) != volume_replica.node)
+        .unwrap();
+    let replica = replica_client
+        .create(
+            &CreateReplica {
+                node: cluster.node(node),
+                uuid: ReplicaId::new(),
+                pool: cluster.pool(node, 0),
+                size: volume.spec().size,
+                managed: true,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    // the replica cannot be shared with the volume target whilst its io-engine is down
+    cluster
+        .composer()
+        .pause(cluster.node(node).as_str())
+        .await
+        .unwrap();
+    volume_client
+        .add_replica(
+            &AddVolumeReplica::new(volume_id.clone(), replica.uuid.clone()),
+            None,
+        )
+        .await
+        .expect_err("io-engine down");
+    cluster
+        .composer()
+        .thaw(cluster.node(node).as_str())
+        .await
+        .unwrap();
+
+    let specs = registry_client.get_specs(&GetSpecs {}, None).await.unwrap();
+    let replica_spec = specs
+        .replicas
+        .iter()
+        .find(|spec| spec.uuid == replica.uuid)
+        .unwrap();
+    assert!(!replica_spec.owners.owned_by(&volume_id));
+    let volume_spec = specs
+        .volumes
+        .iter()
+        .find(|spec| spec.uuid == volume_id)
+        .unwrap();
+    assert_eq!(volume_spec.num_replicas, 1);
 
-#[tracing::instrument(skip(cluster))]
-async fn test_volume(cluster: &Cluster) {
-    smoke_test(cluster).await;
-    publishing_test(cluster).await;
-    replica_count_test(cluster).await;
-    nexus_persistence_test(cluster).await;
+    // so it can be added once the io-engine is back
+    let volume = volume_client
+        .add_replica(
+            &AddVolumeReplica::new(volume_id.clone(), replica.uuid.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(volume.spec().num_replicas, 2);
 }
 
-const RECONCILE_TIMEOUT_SECS: u64 = 7;
-
+/// Test that the replicas on a pool utilized above the rebalance threshold are moved to less
+/// utilized pools, whilst the replicas on the other pools are left alone
 #[tokio::test]
-async fn hotspare() {
+async fn replica_rebalance() {
     let cluster = ClusterBuilder::builder()
-        .with_rest(true)
+        .with_rest(false)
         .with_agents(vec!["core"])
         .with_io_engines(3)
-        .with_pools(2)
+        .with_tmpfs_pool(POOL_SIZE_BYTES)
         .with_cache_period("1s")
         .with_reconcile_period(Duration::from_secs(1), Duration::from_secs(1))
+        .with_options(|o| o.with_rebalance_threshold(Some(70)))
         .build()
         .await
         .unwrap();
+    let volume_client = cluster.grpc_client().volume();
+    let replica_client = cluster.grpc_client().replica();
+    let replica_size = 48 * 1024 * 1024;
 
-    let node_client = cluster.grpc_client().node();
-    let nodes = node_client.get(Filter::None, None).await.unwrap();
-    tracing::info!("Nodes: {:?}", nodes);
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::new(),
+                size: replica_size,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let volume_id = volume.uuid().clone();
+    volume_client
+        .publish(&PublishVolume::new(volume_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    let volume_replica = replica_client
+        .get(Filter::Volume(volume_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
 
-    hotspare_faulty_children(&cluster).await;
-    hotspare_unknown_children(&cluster).await;
-    hotspare_missing_children(&cluster).await;
-    hotspare_replica_count(&cluster).await;
-    hotspare_replica_count_spread(&cluster).await;
-    hotspare_nexus_replica_count(&cluster).await;
+    // fill up the pool of the volume replica above the threshold
+    replica_client
+        .create(
+            &CreateReplica {
+                node: volume_replica.node.clone(),
+                uuid: ReplicaId::new(),
+                pool: volume_replica.pool.clone(),
+                size: replica_size,
+                managed: true,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    // a volume whose replica is placed on a pool which is under the threshold
+    let balanced = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::new(),
+                size: 16 * 1024 * 1024,
+                replicas: 1,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let balanced_id = balanced.uuid().clone();
+    volume_client
+        .publish(&PublishVolume::new(balanced_id.clone(), None, None), None)
+        .await
+        .unwrap();
+    let balanced_replica = replica_client
+        .get(Filter::Volume(balanced_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner()
+        .remove(0);
+    assert_ne!(balanced_replica.pool, volume_replica.pool);
+
+    let mut moved = None;
+    for _ in 0 .. 30 {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let replicas = replica_client
+            .get(Filter::Volume(volume_id.clone()), None, None)
+            .await
+            .unwrap()
+            .into_inner();
+        if replicas.len() == 1 && replicas[0].uuid != volume_replica.uuid {
+            moved = Some(replicas[0].clone());
+            break;
+        }
+    }
+    let moved = moved.expect("The replica should be moved away from the over utilized pool");
+    assert_ne!(moved.pool, volume_replica.pool);
+    let volume = volume_client
+        .get(GetVolumes::new(&volume_id).filter, None, None)
+        .await
+        .unwrap()
+        .entries
+        .remove(0);
+    assert_eq!(volume.spec().num_replicas, 1);
+
+    // the replica on the pool under the threshold stays where it is
+    let replicas = replica_client
+        .get(Filter::Volume(balanced_id.clone()), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(replicas.len(), 1);
+    assert_eq!(replicas[0].uuid, balanced_replica.uuid);
+    assert_eq!(replicas[0].pool, balanced_replica.pool);
 }
 
-const POOL_SIZE_BYTES: u64 = 128 * 1024 * 1024;
 #[tokio::test]
 async fn volume_nexus_reconcile() {
     let cluster = ClusterBuilder::builder()
@@ -165,6 +2268,9 @@ async fn deleting_volume_reconcile(cluster: &Cluster) {
                 uuid: volume.uuid().clone(),
                 target_node: None,
                 share: None,
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -194,11 +2300,15 @@ async fn deleting_volume_reconcile(cluster: &Cluster) {
     // 5. Volume replicas and nexuses should have been deleted as well
     let specs = cluster.rest_v00().specs_api().get_specs().await.unwrap();
     assert!(specs.nexuses.is_empty());
-    let nexuses = cluster.rest_v00().nexuses_api().get_nexuses().await;
-    assert!(nexuses.unwrap().is_empty());
+    let nexuses = cluster.rest_v00().nexuses_api().get_nexuses(0, None).await;
+    assert!(nexuses.unwrap().entries.is_empty());
     assert!(specs.replicas.is_empty());
-    let replicas = cluster.rest_v00().replicas_api().get_replicas().await;
-    assert!(replicas.unwrap().is_empty());
+    let replicas = cluster
+        .rest_v00()
+        .replicas_api()
+        .get_replicas(0, None)
+        .await;
+    assert!(replicas.unwrap().entries.is_empty());
 }
 
 /// Wait for a volume to reach the provided status
@@ -235,7 +2345,12 @@ async fn offline_replicas_reconcile(cluster: &Cluster, reconcile_period: Duratio
         .unwrap();
 
     let nodes = rest_api.nodes_api().get_nodes().await.unwrap();
-    let replica_nodes = rest_api.replicas_api().get_replicas().await.unwrap();
+    let replica_nodes = rest_api
+        .replicas_api()
+        .get_replicas(0, None)
+        .await
+        .unwrap()
+        .entries;
     let replica_nodes = replica_nodes
         .into_iter()
         .map(|r| r.node)
@@ -257,6 +2372,9 @@ async fn offline_replicas_reconcile(cluster: &Cluster, reconcile_period: Duratio
             &volume.spec.uuid,
             &free_node,
             models::VolumeShareProtocol::Nvmf,
+            None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -267,6 +2385,7 @@ async fn offline_replicas_reconcile(cluster: &Cluster, reconcile_period: Duratio
     let volume_id = volume_state.uuid;
     let volume = volumes_api.get_volume(&volume_id).await.unwrap();
     assert_eq!(volume.state.status, models::VolumeStatus::Online);
+    assert!(volume.state.target_refreshed.is_some());
 
     // 2. kill all replica nodes
     for node in replica_nodes.iter().filter(|n| n != &&free_node) {
@@ -319,6 +2438,9 @@ async fn unused_nexus_reconcile(cluster: &Cluster) {
             &volume.spec.uuid,
             cluster.node(0).as_str(),
             models::VolumeShareProtocol::Nvmf,
+            None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -400,6 +2522,9 @@ async fn unused_reconcile(cluster: &Cluster) {
             &volume.spec.uuid,
             nexus_node.id.as_str(),
             models::VolumeShareProtocol::Nvmf,
+            None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -418,6 +2543,9 @@ async fn unused_reconcile(cluster: &Cluster) {
             &volume.spec.uuid,
             unused_node.id.as_str(),
             models::VolumeShareProtocol::Nvmf,
+            None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -503,6 +2631,9 @@ async fn missing_nexus_reconcile(cluster: &Cluster) {
             &volume.spec().uuid,
             cluster.node(0).as_str(),
             models::VolumeShareProtocol::Nvmf,
+            None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -767,6 +2898,179 @@ async fn hotspare_unknown_children(cluster: &Cluster) {
         .unwrap();
 }
 
+/// Mark a child of a volume nexus as unhealthy in the persisted nexus info, as it would be after a
+/// network partition, and check that the live child is not removed from the nexus
+/// Then remove the stale child from the nexus and wait till it gets replaced rather than re-added
+async fn hotspare_stale_children(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: "7d2a9c4e-5b1f-4e83-9a6d-0c8b3f2e1a57".try_into().unwrap(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(volume.spec().uuid.clone(), Some(cluster.node(0)), None),
+            None,
+        )
+        .await
+        .unwrap();
+    tracing::info!("Volume: {:?}", volume);
+    let nexus = volume.state().target.unwrap();
+
+    let mut store = Etcd::new("0.0.0.0:2379")
+        .await
+        .expect("Failed to connect to etcd.");
+    let nexus_info_key = NexusInfoKey::new(&Some(volume.uuid().clone()), &nexus.uuid);
+    let mut nexus_info: NexusInfo = store.get_obj(&nexus_info_key).await.unwrap();
+    nexus_info.uuid = nexus.uuid.clone();
+    nexus_info.volume_uuid = Some(volume.uuid().clone());
+
+    let stale_child = nexus.children.last().unwrap().clone();
+    let stale_uuid = stale_child.uri.uuid_str().unwrap();
+    nexus_info
+        .children
+        .iter_mut()
+        .find(|c| c.uuid == stale_uuid)
+        .unwrap()
+        .healthy = false;
+    store.put_obj(&nexus_info).await.unwrap();
+
+    // give the reconcilers a few chances to touch the nexus
+    tokio::time::sleep(Duration::from_secs(RECONCILE_TIMEOUT_SECS)).await;
+
+    // the stale child is still in use by the nexus, so it must not be removed
+    let children = volume_children(volume.uuid(), &volume_client).await;
+    tracing::info!("volume children: {:?}", children);
+    assert_eq!(children.len(), 2);
+    assert!(children.iter().any(|c| c.uri == stale_child.uri));
+
+    let mut rpc_handle = cluster.grpc_handle(cluster.node(0).as_str()).await.unwrap();
+    rpc_handle
+        .io_engine
+        .remove_child_nexus(rpc::io_engine::RemoveChildNexusRequest {
+            uuid: nexus.uuid.to_string(),
+            uri: stale_child.uri.to_string(),
+        })
+        .await
+        .unwrap();
+
+    let children = wait_till_volume_nexus(
+        volume.uuid(),
+        2,
+        stale_child.uri.as_str(),
+        &volume_client,
+        &registry_client,
+    )
+    .await;
+    tracing::info!("volume children: {:?}", children);
+
+    // the stale child should have been replaced, not added back!
+    assert_eq!(children.len(), 2);
+    assert!(!children.iter().any(|c| c.uri == stale_child.uri));
+
+    volume_client
+        .destroy(&DestroyVolume::new(volume.uuid()), None)
+        .await
+        .unwrap();
+}
+
+/// Mark all children of a volume nexus as unhealthy in the persisted nexus info and remove one of
+/// them from the nexus, and check that no replica is added to the nexus as none is authoritative
+/// Then mark the remaining child as healthy again and wait till the nexus gets its replicas back
+async fn hotspare_unauthoritative_children(cluster: &Cluster) {
+    let volume_client = cluster.grpc_client().volume();
+    let registry_client = cluster.grpc_client().registry();
+    let volume = volume_client
+        .create(
+            &CreateVolume {
+                uuid: VolumeId::new(),
+                size: 5242880,
+                replicas: 2,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let volume = volume_client
+        .publish(
+            &PublishVolume::new(volume.spec().uuid.clone(), Some(cluster.node(0)), None),
+            None,
+        )
+        .await
+        .unwrap();
+    tracing::info!("Volume: {:?}", volume);
+    let nexus = volume.state().target.unwrap();
+
+    let mut store = Etcd::new("0.0.0.0:2379")
+        .await
+        .expect("Failed to connect to etcd.");
+    let nexus_info_key = NexusInfoKey::new(&Some(volume.uuid().clone()), &nexus.uuid);
+    let mut nexus_info: NexusInfo = store.get_obj(&nexus_info_key).await.unwrap();
+    nexus_info.uuid = nexus.uuid.clone();
+    nexus_info.volume_uuid = Some(volume.uuid().clone());
+    nexus_info
+        .children
+        .iter_mut()
+        .for_each(|c| c.healthy = false);
+    store.put_obj(&nexus_info).await.unwrap();
+
+    let removed_child = nexus.children.last().unwrap().clone();
+    let mut rpc_handle = cluster.grpc_handle(cluster.node(0).as_str()).await.unwrap();
+    rpc_handle
+        .io_engine
+        .remove_child_nexus(rpc::io_engine::RemoveChildNexusRequest {
+            uuid: nexus.uuid.to_string(),
+            uri: removed_child.uri.to_string(),
+        })
+        .await
+        .unwrap();
+
+    // give the reconcilers a few chances to touch the nexus
+    tokio::time::sleep(Duration::from_secs(RECONCILE_TIMEOUT_SECS)).await;
+
+    // no child is authoritative, so no replica may be added to the nexus
+    let children = volume_children(volume.uuid(), &volume_client).await;
+    tracing::info!("volume children: {:?}", children);
+    assert_eq!(children.len(), 1);
+    assert!(!children.iter().any(|c| c.uri == removed_child.uri));
+
+    let healthy_uuid = children.first().unwrap().uri.uuid_str().unwrap();
+    nexus_info
+        .children
+        .iter_mut()
+        .filter(|c| c.uuid == healthy_uuid)
+        .for_each(|c| c.healthy = true);
+    store.put_obj(&nexus_info).await.unwrap();
+
+    let children = wait_till_volume_nexus(
+        volume.uuid(),
+        2,
+        removed_child.uri.as_str(),
+        &volume_client,
+        &registry_client,
+    )
+    .await;
+    tracing::info!("volume children: {:?}", children);
+    assert_eq!(children.len(), 2);
+
+    volume_client
+        .destroy(&DestroyVolume::new(volume.uuid()), None)
+        .await
+        .unwrap();
+}
+
 /// Remove a child from a volume nexus (under the control plane) and waits till it gets added back
 async fn hotspare_missing_children(cluster: &Cluster) {
     let volume_client = cluster.grpc_client().volume();
@@ -847,7 +3151,12 @@ async fn hotspare_replica_count_spread(cluster: &Cluster) {
         nodes.len() >= 3,
         "We need enough nodes to be able to add at least 2 replicas"
     );
-    let pools = cluster.rest_v00().pools_api().get_pools().await.unwrap();
+    let pools = cluster
+        .rest_v00()
+        .pools_api()
+        .get_pools(None)
+        .await
+        .unwrap();
     assert!(
         pools.len() >= nodes.len() * 2,
         "We need at least 2 pools per node to be able to test the failure case"
@@ -955,10 +3264,10 @@ async fn hotspare_replica_count(cluster: &Cluster) {
 
     let replica_spec = specs.replicas.first().cloned().unwrap();
     let replicas = replica_client
-        .get(GetReplicas::new(&replica_spec.uuid).filter, None)
+        .get(GetReplicas::new(&replica_spec.uuid).filter, None, None)
         .await
         .unwrap();
-    let replica = replicas.0.first().unwrap().clone();
+    let replica = replicas.entries.first().unwrap().clone();
 
     // forcefully destroy a volume replica
     let mut destroy = DestroyReplica::from(replica);
@@ -984,6 +3293,8 @@ async fn hotspare_replica_count(cluster: &Cluster) {
                 share: Default::default(),
                 managed: true,
                 owners: ReplicaOwners::from_volume(volume.uuid()),
+                annotations: Default::default(),
+                encryption: None,
             },
             None,
         )
@@ -1220,6 +3531,9 @@ async fn nexus_persistence_test_iteration(
                 // publish it on the remote first, to complicate things
                 target_node: Some(remote.clone()),
                 share: None,
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -1248,7 +3562,7 @@ async fn nexus_persistence_test_iteration(
     tracing::info!("NexusInfo: {:?}", nexus_info);
 
     let replicas = replica_client
-        .get(Filter::Volume(volume_state.uuid.clone()), None)
+        .get(Filter::Volume(volume_state.uuid.clone()), None, None)
         .await
         .unwrap();
 
@@ -1263,8 +3577,7 @@ async fn nexus_persistence_test_iteration(
     };
 
     let mark_child_unhealthy = |c: &Child, ni: &mut NexusInfo| {
-        let uri = url::Url::from_str(c.uri.as_str()).unwrap();
-        let uuid = uri.query_pairs().find(|(q, _)| q == "uuid").unwrap().1;
+        let uuid = c.uri.uuid_str().unwrap();
         let child_info = ni.children.iter_mut().find(|c| c.uuid == uuid);
         child_info.unwrap().healthy = false;
     };
@@ -1296,6 +3609,9 @@ async fn nexus_persistence_test_iteration(
                 uuid: volume_state.uuid.clone(),
                 target_node: Some(local.clone()),
                 share: None,
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -1309,7 +3625,7 @@ async fn nexus_persistence_test_iteration(
     assert_eq!(nexus.children.len(), 1);
 
     let replicas = replica_client
-        .get(Filter::Volume(volume_state.uuid.clone()), None)
+        .get(Filter::Volume(volume_state.uuid.clone()), None, None)
         .await
         .unwrap();
 
@@ -1347,16 +3663,16 @@ async fn nexus_persistence_test_iteration(
         .entries
         .is_empty());
     assert!(nexus_client
-        .get(GetNexuses::default().filter, None)
+        .get(GetNexuses::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
     assert!(replica_client
-        .get(GetReplicas::default().filter, None)
+        .get(GetReplicas::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
 }
 
@@ -1385,17 +3701,47 @@ async fn publishing_test(cluster: &Cluster) {
     tracing::info!("Volumes: {:?}", volumes);
     assert_eq!(Some(&volume), volumes.first());
 
+    // the volume exists but it has no target yet
+    let volumes_api = cluster.rest_v00().volumes_api();
+    let error = volumes_api
+        .get_volume_rebuild_progress(volume.uuid())
+        .await
+        .expect_err("The volume is not published");
+    match error {
+        Error::Response(response) => {
+            assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED)
+        }
+        _ => panic!("Expected a response error"),
+    }
+    let error = volume_client
+        .unshare(
+            &UnshareVolume {
+                uuid: volume.uuid().clone(),
+            },
+            None,
+        )
+        .await
+        .expect_err("The volume is not published");
+    assert_eq!(error.kind, ReplyErrorKind::NotPublished);
+
     let volume = volume_client
         .publish(
             &PublishVolume {
                 uuid: volume.spec().uuid.clone(),
                 target_node: None,
                 share: None,
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
         .await
         .expect("Should be able to publish a newly created volume");
+    volumes_api
+        .get_volume_rebuild_progress(volume.uuid())
+        .await
+        .expect("The volume is published");
 
     let volume_state = volume.state();
 
@@ -1454,6 +3800,9 @@ async fn publishing_test(cluster: &Cluster) {
                 uuid: volume_state.uuid.clone(),
                 target_node: None,
                 share: None,
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -1471,6 +3820,9 @@ async fn publishing_test(cluster: &Cluster) {
                 uuid: volume_state.uuid.clone(),
                 target_node: Some(cluster.node(0)),
                 share: Some(VolumeShareProtocol::Nvmf),
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -1504,6 +3856,9 @@ async fn publishing_test(cluster: &Cluster) {
                 uuid: volume_state.uuid.clone(),
                 target_node: None,
                 share: Some(VolumeShareProtocol::Iscsi),
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -1516,6 +3871,9 @@ async fn publishing_test(cluster: &Cluster) {
                 uuid: volume_state.uuid.clone(),
                 target_node: None,
                 share: Some(VolumeShareProtocol::Nvmf),
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -1533,6 +3891,9 @@ async fn publishing_test(cluster: &Cluster) {
                 uuid: volume_state.uuid.clone(),
                 target_node: Some(cluster.node(1)),
                 share: None,
+                transport_params: Default::default(),
+                read_only: false,
+                force_read_write: false,
             },
             None,
         )
@@ -1598,16 +3959,16 @@ async fn publishing_test(cluster: &Cluster) {
         .entries
         .is_empty());
     assert!(nexus_client
-        .get(GetNexuses::default().filter, None)
+        .get(GetNexuses::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
     assert!(replica_client
-        .get(GetReplicas::default().filter, None)
+        .get(GetReplicas::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
 }
 
@@ -1853,16 +4214,16 @@ async fn replica_count_test(cluster: &Cluster) {
         .entries
         .is_empty());
     assert!(nexus_client
-        .get(GetNexuses::default().filter, None)
+        .get(GetNexuses::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
     assert!(replica_client
-        .get(GetReplicas::default().filter, None)
+        .get(GetReplicas::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
 }
 
@@ -1904,15 +4265,15 @@ async fn smoke_test(cluster: &Cluster) {
         .entries
         .is_empty());
     assert!(nexus_client
-        .get(GetNexuses::default().filter, None)
+        .get(GetNexuses::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
     assert!(replica_client
-        .get(GetReplicas::default().filter, None)
+        .get(GetReplicas::default().filter, None, None)
         .await
         .unwrap()
-        .0
+        .entries
         .is_empty());
 }
@@ -9,7 +9,9 @@ use once_cell::sync::OnceCell;
 use std::sync::Arc;
 use structopt::StructOpt;
 use tracing::{error, info};
-use utils::{DEFAULT_GRPC_CLIENT_ADDR, DEFAULT_JSON_GRPC_SERVER_ADDR};
+use utils::{
+    tracing_telemetry::LogFormat, DEFAULT_GRPC_CLIENT_ADDR, DEFAULT_JSON_GRPC_SERVER_ADDR,
+};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = utils::package_description!(), version = utils::version_info_str!())]
@@ -21,6 +23,18 @@ struct CliArgs {
     /// The CORE gRPC client URL or address to connect to the core services.
     #[structopt(long, short = "z", default_value = DEFAULT_GRPC_CLIENT_ADDR)]
     core_grpc: Uri,
+
+    /// The timeout for every node connection (gRPC)
+    #[structopt(long, default_value = utils::DEFAULT_CONN_TIMEOUT)]
+    connect_timeout: humantime::Duration,
+
+    /// The default timeout for node request timeouts (gRPC)
+    #[structopt(long, short, default_value = utils::DEFAULT_REQ_TIMEOUT)]
+    request_timeout: humantime::Duration,
+
+    /// The format of the logs: text or json.
+    #[structopt(long, env = "LOG_FORMAT", default_value = "text")]
+    log_format: LogFormat,
 }
 
 pub static CORE_CLIENT: OnceCell<CoreClient> = OnceCell::new();
@@ -29,6 +43,13 @@ pub static CORE_CLIENT: OnceCell<CoreClient> = OnceCell::new();
 async fn main() {
     let cli_args = CliArgs::from_args();
     utils::print_package_info!();
+    utils::tracing_telemetry::init_tracing_format(
+        "jsongrpc-agent",
+        vec![],
+        None,
+        None,
+        cli_args.log_format,
+    );
     info!("Using options: {:?}", &cli_args);
 
     let grpc_addr = &cli_args.core_grpc;
@@ -43,7 +64,11 @@ async fn main() {
 
 async fn server(cli_args: CliArgs) {
     let grpc_addr = cli_args.json_grpc_server_addr;
-    let json_grpc_service = JsonGrpcServer::new(Arc::new(JsonGrpcSvc::new())).into_grpc_server();
+    let json_grpc_service = JsonGrpcServer::new(Arc::new(JsonGrpcSvc::new(
+        cli_args.connect_timeout.into(),
+        cli_args.request_timeout.into(),
+    )))
+    .into_grpc_server();
 
     let tonic_router = tonic::transport::Server::builder().add_service(json_grpc_service);
 
@@ -3,7 +3,7 @@
 
 use crate::CORE_CLIENT;
 use ::rpc::io_engine::{JsonRpcReply, JsonRpcRequest};
-use common::errors::{JsonRpcDeserialise, NodeNotOnline, SvcError};
+use common::errors::{GrpcConnect, GrpcConnectUri, JsonRpcDeserialise, NodeNotOnline, SvcError};
 use common_lib::{
     mbus_api::ReplyError,
     types::v0::message_bus::{Filter, JsonGrpcRequest, Node, NodeId},
@@ -18,22 +18,67 @@ use grpc::{
 use rpc::io_engine::json_rpc_client::JsonRpcClient;
 use serde_json::Value;
 use snafu::{OptionExt, ResultExt};
+use std::{str::FromStr, time::Duration};
+use tonic::transport::{Channel, Endpoint};
 
-#[derive(Clone, Default)]
-pub(super) struct JsonGrpcSvc {}
+#[derive(Clone)]
+pub(super) struct JsonGrpcSvc {
+    /// timeout for the connection to the io-engine
+    connect_timeout: Duration,
+    /// timeout for the JSON gRPC requests to the io-engine
+    request_timeout: Duration,
+}
 
 /// JSON gRPC service implementation
 impl JsonGrpcSvc {
-    /// create a new jsongrpc service
-    pub(super) fn new() -> Self {
-        Self {}
+    /// create a new jsongrpc service with the given io-engine connect and request timeouts
+    pub(super) fn new(connect_timeout: Duration, request_timeout: Duration) -> Self {
+        Self {
+            connect_timeout,
+            request_timeout,
+        }
     }
 
     /// Generic JSON gRPC call issued to the IoEngine using the JsonRpcClient.
+    /// The io-engine is reached through the request endpoint, if set, otherwise through the
+    /// endpoint of the request node as found in the registry.
     pub(super) async fn json_grpc_call(
         &self,
         request: &JsonGrpcRequest,
     ) -> Result<serde_json::Value, SvcError> {
+        let mut client = match target_endpoint(request)? {
+            Some(endpoint) => {
+                connect(
+                    &request.node,
+                    endpoint,
+                    self.connect_timeout,
+                    self.request_timeout,
+                )
+                .await?
+            }
+            None => self.node_client(request).await?,
+        };
+        let response: JsonRpcReply = client
+            .json_rpc_call(JsonRpcRequest {
+                method: request.method.to_string(),
+                params: request.params.to_string(),
+            })
+            .await
+            .map_err(|error| SvcError::JsonRpc {
+                method: request.method.to_string(),
+                params: request.params.to_string(),
+                error: error.to_string(),
+            })?
+            .into_inner();
+
+        Ok(serde_json::from_str(&response.result).context(JsonRpcDeserialise)?)
+    }
+
+    /// Connect to the io-engine of the request node, using its endpoint from the registry
+    async fn node_client(
+        &self,
+        request: &JsonGrpcRequest,
+    ) -> Result<JsonRpcClient<Channel>, SvcError> {
         let response = match CORE_CLIENT
             .get()
             .expect("Client is not initialised")
@@ -53,24 +98,13 @@ impl JsonGrpcSvc {
         let node = node.state().context(NodeNotOnline {
             node: request.node.to_owned(),
         })?;
-        // todo: use the cli argument timeouts
-        let mut client = JsonRpcClient::connect(format!("http://{}", node.grpc_endpoint))
-            .await
-            .unwrap();
-        let response: JsonRpcReply = client
-            .json_rpc_call(JsonRpcRequest {
-                method: request.method.to_string(),
-                params: request.params.to_string(),
-            })
-            .await
-            .map_err(|error| SvcError::JsonRpc {
-                method: request.method.to_string(),
-                params: request.params.to_string(),
-                error: error.to_string(),
-            })?
-            .into_inner();
-
-        Ok(serde_json::from_str(&response.result).context(JsonRpcDeserialise)?)
+        connect(
+            &node.id,
+            &node.grpc_endpoint,
+            self.connect_timeout,
+            self.request_timeout,
+        )
+        .await
     }
 
     /// Get a shutdown_signal as a oneshot channel when the process receives either TERM or INT.
@@ -111,6 +145,53 @@ impl JsonGrpcOperations for JsonGrpcSvc {
     }
 }
 
+/// Connect to the JSON gRPC service of the io-engine on the given node, giving up after the
+/// `connect_timeout` rather than waiting on an unresponsive node
+async fn connect(
+    node: &NodeId,
+    endpoint: &str,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+) -> Result<JsonRpcClient<Channel>, SvcError> {
+    let uri = format!("http://{}", endpoint);
+    let uri = http::uri::Uri::from_str(&uri).context(GrpcConnectUri {
+        node_id: node.to_string(),
+        uri: uri.clone(),
+    })?;
+    let endpoint = Endpoint::from(uri)
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout);
+
+    match tokio::time::timeout(connect_timeout, JsonRpcClient::connect(endpoint.clone())).await {
+        Err(_) => Err(SvcError::GrpcConnectTimeout {
+            node_id: node.to_string(),
+            endpoint: endpoint.uri().to_string(),
+            timeout: connect_timeout,
+        }),
+        Ok(client) => client.context(GrpcConnect {
+            node_id: node.to_string(),
+            endpoint: endpoint.uri().to_string(),
+        }),
+    }
+}
+
+/// Returns the endpoint the request should be sent to directly, if any.
+/// The request endpoint and node are mutually exclusive, so it's rejected if both are set.
+fn target_endpoint(request: &JsonGrpcRequest) -> Result<Option<&str>, SvcError> {
+    match &request.endpoint {
+        None => Ok(None),
+        Some(endpoint)
+            if !request.node.as_str().is_empty() && request.node != NodeId::default() =>
+        {
+            Err(SvcError::JsonGrpcTarget {
+                node: request.node.to_string(),
+                endpoint: endpoint.clone(),
+            })
+        }
+        Some(endpoint) => Ok(Some(endpoint)),
+    }
+}
+
 /// returns node from node option and returns an error on non existence
 fn node(node_id: NodeId, node: Option<&Node>) -> Result<Node, SvcError> {
     match node {
@@ -118,3 +199,59 @@ fn node(node_id: NodeId, node: Option<&Node>) -> Result<Node, SvcError> {
         None => Err(SvcError::NodeNotFound { node_id }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_endpoint_exclusive() {
+        let mut request = JsonGrpcRequest {
+            node: NodeId::from("io-engine-1"),
+            ..Default::default()
+        };
+        assert_eq!(target_endpoint(&request).unwrap(), None);
+
+        request.endpoint = Some("127.0.0.1:10124".to_string());
+        let error = target_endpoint(&request).expect_err("both node and endpoint are set");
+        assert!(
+            matches!(error, SvcError::JsonGrpcTarget { .. }),
+            "{:?}",
+            error
+        );
+
+        for node in [NodeId::default(), NodeId::from("")] {
+            request.node = node;
+            assert_eq!(target_endpoint(&request).unwrap(), Some("127.0.0.1:10124"));
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_dead_endpoint() {
+        let node = NodeId::from("io-engine-1");
+        let timeout = Duration::from_millis(500);
+
+        let error = connect(&node, "not a valid endpoint", timeout, timeout)
+            .await
+            .expect_err("the endpoint is not a valid uri");
+        assert!(
+            matches!(error, SvcError::GrpcConnectUri { .. }),
+            "{:?}",
+            error
+        );
+
+        // nothing is listening on this port, so the connection fails or times out, but doesn't
+        // bring the service down
+        let error = connect(&node, "127.0.0.1:1", timeout, timeout)
+            .await
+            .expect_err("nothing is listening on the endpoint");
+        assert!(
+            matches!(
+                error,
+                SvcError::GrpcConnect { .. } | SvcError::GrpcConnectTimeout { .. }
+            ),
+            "{:?}",
+            error
+        );
+    }
+}
@@ -143,7 +143,7 @@ impl IoEngineApiClient {
 
     /// List all pools available in IoEngine cluster.
     pub async fn list_pools(&self) -> Result<Vec<Pool>, ApiClientError> {
-        let response = self.rest_client.pools_api().get_pools().await?;
+        let response = self.rest_client.pools_api().get_pools(None).await?;
         Ok(response.into_body())
     }
 
@@ -167,7 +167,7 @@ impl IoEngineApiClient {
         let response = self
             .rest_client
             .volumes_api()
-            .get_volumes(max_entries, Some(starting_token))
+            .get_volumes(max_entries, Some(starting_token), None)
             .await?;
         Ok(response.into_body())
     }
@@ -207,6 +207,10 @@ impl IoEngineApiClient {
             topology: Some(topology),
             policy: VolumePolicy::new_all(true),
             labels: None,
+            annotations: None,
+            encryption: None,
+            idempotency_key: None,
+            source_snapshot: None,
         };
 
         let result = self
@@ -288,11 +292,12 @@ impl IoEngineApiClient {
         volume_id: &uuid::Uuid,
         node: &str,
         protocol: VolumeShareProtocol,
+        read_only: bool,
     ) -> Result<Volume, ApiClientError> {
         let volume = self
             .rest_client
             .volumes_api()
-            .put_volume_target(volume_id, node, protocol)
+            .put_volume_target(volume_id, node, protocol, None, Some(read_only), None)
             .await?;
         Ok(volume.into_body())
     }
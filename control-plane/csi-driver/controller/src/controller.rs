@@ -383,12 +383,6 @@ impl rpc::csi::controller_server::Controller for CsiControllerSvc {
         let args = request.into_inner();
         tracing::trace!(volume.uuid = %args.volume_id, request = ?args);
 
-        if args.readonly {
-            return Err(Status::invalid_argument(
-                "Read-only volumes are not supported",
-            ));
-        }
-
         let protocol = parse_protocol(args.volume_context.get("protocol"))?;
 
         if args.node_id.is_empty() {
@@ -430,6 +424,15 @@ impl rpc::csi::controller_server::Controller for CsiControllerSvc {
                         return Err(Status::failed_precondition(m));
                     }
 
+                    if target.read_only != args.readonly {
+                        let m = format!(
+                            "Volume {} already published with a different access mode, read-only: {}",
+                            volume_id, target.read_only,
+                        );
+                        error!("{}", m);
+                        return Err(Status::failed_precondition(m));
+                    }
+
                     if let Some((node, uri)) = get_volume_share_location(&volume) {
                         // Make sure volume is published at the same node.
                         if node_id != node {
@@ -455,7 +458,7 @@ impl rpc::csi::controller_server::Controller for CsiControllerSvc {
             _ => {
                 // Volume is not published.
                 let v = IoEngineApiClient::get_client()
-                    .publish_volume(&volume_id, &node_id, protocol)
+                    .publish_volume(&volume_id, &node_id, protocol, args.readonly)
                     .await?;
 
                 if let Some((node, uri)) = get_volume_share_location(&v) {
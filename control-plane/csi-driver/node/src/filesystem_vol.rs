@@ -1,7 +1,8 @@
 //! Functions for CSI stage, unstage, publish and unpublish filesystem volumes.
 
-use std::{fs, io::ErrorKind, path::PathBuf};
+use std::{collections::HashMap, fs, io::ErrorKind, path::PathBuf};
 
+use nix::sys::statvfs::statvfs;
 use tonic::{Code, Status};
 
 macro_rules! failure {
@@ -10,11 +11,54 @@ macro_rules! failure {
 }
 
 use crate::{
-    csi::{volume_capability::MountVolume, *},
+    csi::{
+        volume_capability::{access_mode::Mode, MountVolume},
+        *,
+    },
     format::prepare_device,
     mount::{self, subset, ReadOnly},
 };
 
+/// Volume context key of the filesystem type, from the storage class parameters
+const FS_TYPE: &str = "fsType";
+/// Volume context key of the comma separated mount flags, from the storage class parameters
+const MOUNT_FLAGS: &str = "mountFlags";
+
+/// Merge the filesystem type and the mount flags of the volume context into the
+/// mount capability, whose own values take precedence
+fn context_mount_volume(mnt: &MountVolume, context: &HashMap<String, String>) -> MountVolume {
+    let mut mnt = mnt.clone();
+    if mnt.fs_type.is_empty() {
+        if let Some(fs_type) = context.get(FS_TYPE) {
+            mnt.fs_type = fs_type.clone();
+        }
+    }
+    if let Some(flags) = context.get(MOUNT_FLAGS) {
+        for flag in flags
+            .split(',')
+            .map(str::trim)
+            .filter(|flag| !flag.is_empty())
+        {
+            if !mnt.mount_flags.iter().any(|entry| entry == flag) {
+                mnt.mount_flags.push(flag.to_string());
+            }
+        }
+    }
+    mnt
+}
+
+/// Check if the access mode of the volume capability only allows reading
+fn reader_only(volume_capability: &Option<VolumeCapability>) -> bool {
+    let mode = volume_capability
+        .as_ref()
+        .and_then(|capability| capability.access_mode.as_ref())
+        .and_then(|access| Mode::from_i32(access.mode));
+    matches!(
+        mode,
+        Some(Mode::SingleNodeReaderOnly) | Some(Mode::MultiNodeReaderOnly)
+    )
+}
+
 pub async fn stage_fs_volume(
     msg: &NodeStageVolumeRequest,
     device_path: String,
@@ -24,6 +68,12 @@ pub async fn stage_fs_volume(
     let volume_id = &msg.volume_id;
     let fs_staging_path = &msg.staging_target_path;
 
+    let mut mnt = context_mount_volume(mnt, &msg.volume_context);
+    // a volume which can only be read is staged readonly
+    if reader_only(&msg.volume_capability) && !mnt.mount_flags.readonly() {
+        mnt.mount_flags.push(String::from("ro"));
+    }
+
     // One final check for fs volumes, ignore for block volumes.
     if let Err(err) = fs::create_dir_all(PathBuf::from(&fs_staging_path)) {
         if err.kind() != ErrorKind::AlreadyExists {
@@ -151,6 +201,7 @@ pub fn publish_fs_volume(
     let target_path = &msg.target_path;
     let volume_id = &msg.volume_id;
     let fs_staging_path = &msg.staging_target_path;
+    let mnt = &context_mount_volume(mnt, &msg.volume_context);
 
     debug!(
         "Publishing volume {} from {} to {}",
@@ -328,3 +379,138 @@ pub fn unpublish_fs_volume(msg: &NodeUnpublishVolumeRequest) -> Result<(), Statu
     info!("Volume {} unpublished from {}", volume_id, target_path);
     Ok(())
 }
+
+/// Get the bytes and inodes usage of the filesystem mounted onto the volume path
+pub fn volume_stats(volume_id: &str, volume_path: &str) -> Result<Vec<VolumeUsage>, Status> {
+    if mount::find_mount(None, Some(volume_path)).is_none() {
+        return Err(failure!(
+            Code::FailedPrecondition,
+            "Failed to get stats of volume {}: no mount for volume path {}",
+            volume_id,
+            volume_path
+        ));
+    }
+
+    let stats = statvfs(volume_path).map_err(|error| {
+        failure!(
+            Code::Internal,
+            "Failed to get stats of volume {}: failed to stat {}: {}",
+            volume_id,
+            volume_path,
+            error
+        )
+    })?;
+
+    let block_size = stats.fragment_size() as i64;
+    let blocks = stats.blocks() as i64;
+    let files = stats.files() as i64;
+    Ok(vec![
+        VolumeUsage {
+            available: stats.blocks_available() as i64 * block_size,
+            total: blocks * block_size,
+            used: (blocks - stats.blocks_free() as i64) * block_size,
+            unit: volume_usage::Unit::Bytes as i32,
+        },
+        VolumeUsage {
+            available: stats.files_available() as i64,
+            total: files,
+            used: files - stats.files_free() as i64,
+            unit: volume_usage::Unit::Inodes as i32,
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{context_mount_volume, reader_only, volume_stats, FS_TYPE, MOUNT_FLAGS};
+    use crate::csi::{
+        volume_capability::{access_mode::Mode, AccessMode, MountVolume},
+        volume_usage::Unit,
+        VolumeCapability,
+    };
+    use std::collections::HashMap;
+    use sys_mount::{unmount, FilesystemType, Mount, MountFlags, UnmountFlags};
+    use tonic::Code;
+
+    #[test]
+    fn context_mount_flags() {
+        let context = [
+            (FS_TYPE.to_string(), "xfs".to_string()),
+            (MOUNT_FLAGS.to_string(), " ro, noatime,,nodev ".to_string()),
+        ]
+        .iter()
+        .cloned()
+        .collect::<HashMap<_, _>>();
+
+        // the context fills in what the capability doesn't specify
+        let mnt = context_mount_volume(&MountVolume::default(), &context);
+        assert_eq!(mnt.fs_type, "xfs");
+        assert_eq!(mnt.mount_flags, vec!["ro", "noatime", "nodev"]);
+
+        // the values of the capability take precedence and flags are not duplicated
+        let capability = MountVolume {
+            fs_type: "ext4".to_string(),
+            mount_flags: vec!["noatime".to_string()],
+        };
+        let mnt = context_mount_volume(&capability, &context);
+        assert_eq!(mnt.fs_type, "ext4");
+        assert_eq!(mnt.mount_flags, vec!["noatime", "ro", "nodev"]);
+
+        let mnt = context_mount_volume(&capability, &HashMap::new());
+        assert_eq!(mnt, capability);
+    }
+
+    #[test]
+    fn reader_only_access_modes() {
+        let capability = |mode: Mode| {
+            Some(VolumeCapability {
+                access_mode: Some(AccessMode { mode: mode as i32 }),
+                ..Default::default()
+            })
+        };
+        assert!(reader_only(&capability(Mode::SingleNodeReaderOnly)));
+        assert!(reader_only(&capability(Mode::MultiNodeReaderOnly)));
+
+        assert!(!reader_only(&capability(Mode::SingleNodeWriter)));
+        assert!(!reader_only(&capability(Mode::MultiNodeSingleWriter)));
+        assert!(!reader_only(&capability(Mode::MultiNodeMultiWriter)));
+        assert!(!reader_only(&capability(Mode::Unknown)));
+        assert!(!reader_only(&Some(VolumeCapability::default())));
+        assert!(!reader_only(&None));
+    }
+
+    /// Mounting the tmpfs requires root, so this must be run explicitly in a privileged
+    /// environment, eg: `sudo -E cargo test -- --ignored tmpfs_volume_stats`
+    #[test]
+    #[ignore]
+    fn tmpfs_volume_stats() {
+        let path = std::env::temp_dir().join(format!("csi-stats-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path).unwrap();
+        let target = path.to_string_lossy().to_string();
+
+        let error = volume_stats("tmpfs", &target).expect_err("nothing is mounted yet");
+        assert_eq!(error.code(), Code::FailedPrecondition);
+
+        Mount::new(
+            "tmpfs",
+            &target,
+            FilesystemType::Manual("tmpfs"),
+            MountFlags::empty(),
+            Some("size=16m"),
+        )
+        .unwrap();
+        let usage = volume_stats("tmpfs", &target);
+        unmount(&target, UnmountFlags::DETACH).unwrap();
+        std::fs::remove_dir(&path).unwrap();
+
+        let usage = usage.unwrap();
+        let bytes = usage.iter().find(|u| u.unit == Unit::Bytes as i32).unwrap();
+        assert!(bytes.total > 0);
+        assert!(bytes.available > 0);
+        let inodes = usage
+            .iter()
+            .find(|u| u.unit == Unit::Inodes as i32)
+            .unwrap();
+        assert!(inodes.total > 0);
+    }
+}
@@ -16,7 +16,9 @@ use crate::{
         *,
     },
     dev::Device,
-    filesystem_vol::{publish_fs_volume, stage_fs_volume, unpublish_fs_volume, unstage_fs_volume},
+    filesystem_vol::{
+        publish_fs_volume, stage_fs_volume, unpublish_fs_volume, unstage_fs_volume, volume_stats,
+    },
 };
 
 #[derive(Clone, Debug)]
@@ -134,7 +136,10 @@ impl node_server::Node for Node {
         &self,
         _request: Request<NodeGetCapabilitiesRequest>,
     ) -> Result<Response<NodeGetCapabilitiesResponse>, Status> {
-        let caps = vec![node_service_capability::rpc::Type::StageUnstageVolume];
+        let caps = vec![
+            node_service_capability::rpc::Type::StageUnstageVolume,
+            node_service_capability::rpc::Type::GetVolumeStats,
+        ];
 
         debug!("NodeGetCapabilities request: {:?}", caps);
 
@@ -312,18 +317,23 @@ impl node_server::Node for Node {
         let msg = request.into_inner();
         trace!("node_get_volume_stats {:?}", msg);
 
-        /*
-        Ok(Response::new(NodeGetVolumeStatsResponse {
-            usage: vec![VolumeUsage {
-                total: 0 as i64,
-                unit: volume_usage::Unit::Bytes as i32,
-                available: 0,
-                used: 0,
-            }],
-        }))
-        */
-        error!("Unimplemented {:?}", msg);
-        Err(Status::new(Code::Unimplemented, "Method not implemented"))
+        if msg.volume_id.is_empty() {
+            return Err(failure!(
+                Code::InvalidArgument,
+                "Failed to get stats of volume: missing volume id"
+            ));
+        }
+
+        if msg.volume_path.is_empty() {
+            return Err(failure!(
+                Code::InvalidArgument,
+                "Failed to get stats of volume {}: missing volume path",
+                &msg.volume_id
+            ));
+        }
+
+        let usage = volume_stats(&msg.volume_id, &msg.volume_path)?;
+        Ok(Response::new(NodeGetVolumeStatsResponse { usage }))
     }
 
     async fn node_expand_volume(
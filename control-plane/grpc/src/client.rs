@@ -1,7 +1,11 @@
 use crate::{
-    context::Context,
+    context::{Context, Endpoints},
     operations::{
-        nexus::{client::NexusClient, traits::NexusOperations},
+        blockdevice::{client::BlockDeviceClient, traits::BlockDeviceOperations},
+        nexus::{
+            client::NexusClient,
+            traits::{NexusOperations, RebuildOperations},
+        },
         node::{client::NodeClient, traits::NodeOperations},
         pool::{client::PoolClient, traits::PoolOperations},
         registry::{client::RegistryClient, traits::RegistryOperations},
@@ -11,7 +15,6 @@ use crate::{
 };
 use common_lib::mbus_api::TimeoutOptions;
 use std::time::Duration;
-use tonic::transport::Uri;
 
 /// CoreClient encapsulates all the individual clients needed for gRPC transport
 pub struct CoreClient {
@@ -19,29 +22,43 @@ pub struct CoreClient {
     replica: ReplicaClient,
     volume: VolumeClient,
     node: NodeClient,
+    blockdevice: BlockDeviceClient,
     registry: RegistryClient,
     nexus: NexusClient,
+    endpoints: Endpoints,
 }
 
 impl CoreClient {
     /// generates a new CoreClient to get the individual clients
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    /// All the individual clients share the same endpoints, and so they fail over together.
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        endpoints: E,
+        opts: O,
+    ) -> Self {
+        let addr = endpoints.into();
         let timeout_opts = opts.into();
         let pool_client = PoolClient::new(addr.clone(), timeout_opts.clone()).await;
         let replica_client = ReplicaClient::new(addr.clone(), timeout_opts.clone()).await;
         let volume_client = VolumeClient::new(addr.clone(), timeout_opts.clone()).await;
         let node_client = NodeClient::new(addr.clone(), timeout_opts.clone()).await;
+        let blockdevice_client = BlockDeviceClient::from(node_client.clone());
         let registry_client = RegistryClient::new(addr.clone(), timeout_opts.clone()).await;
-        let nexus_client = NexusClient::new(addr, timeout_opts).await;
+        let nexus_client = NexusClient::new(addr.clone(), timeout_opts).await;
         Self {
             pool: pool_client,
             replica: replica_client,
             volume: volume_client,
             node: node_client,
+            blockdevice: blockdevice_client,
             registry: registry_client,
             nexus: nexus_client,
+            endpoints: addr,
         }
     }
+    /// retrieve the endpoints of the core agent
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
     /// retrieve the corresponding pool client
     pub fn pool(&self) -> impl PoolOperations {
         self.pool.clone()
@@ -58,6 +75,10 @@ impl CoreClient {
     pub fn node(&self) -> impl NodeOperations {
         self.node.clone()
     }
+    /// retrieve the corresponding blockdevice client
+    pub fn blockdevice(&self) -> impl BlockDeviceOperations {
+        self.blockdevice.clone()
+    }
     /// retrieve the corresponding registry client
     pub fn registry(&self) -> impl RegistryOperations {
         self.registry.clone()
@@ -66,7 +87,13 @@ impl CoreClient {
     pub fn nexus(&self) -> impl NexusOperations {
         self.nexus.clone()
     }
+    /// retrieve the corresponding rebuild client
+    pub fn rebuild(&self) -> impl RebuildOperations {
+        self.nexus.clone()
+    }
     /// Try to wait until the Core Agent is ready, up to a timeout, by using the Probe method.
+    /// If the Core Agent reports that it's not ready, eg: it's not the active instance, we fail
+    /// over to the next endpoint.
     pub async fn wait_ready(&self, timeout_opts: Option<TimeoutOptions>) -> Result<(), ()> {
         let timeout_opts = match timeout_opts {
             Some(opts) => opts,
@@ -81,12 +108,13 @@ impl CoreClient {
                 .await
             {
                 Ok(true) => return Ok(()),
-                _ => {
-                    let delay = std::time::Duration::from_millis(100);
-                    tracing::trace!(%attempt, delay=?delay, "Not available, retrying after...");
-                    tokio::time::sleep(delay).await;
-                }
+                // transport errors already fail over to the next endpoint
+                Ok(false) => self.endpoints.failover(),
+                Err(_) => {}
             }
+            let delay = std::time::Duration::from_millis(100);
+            tracing::trace!(%attempt, delay=?delay, "Not available, retrying after...");
+            tokio::time::sleep(delay).await;
         }
         match self
             .volume
@@ -1,8 +1,15 @@
-use crate::tracing::OpenTelClient;
+use crate::tracing::{BoxedFuture, MetadataInjector, OpenTelClient, TonicClientRequest};
 pub use common_lib::mbus_api::TimeoutOptions;
-use common_lib::types::v0::message_bus::MessageIdVs;
-use opentelemetry::trace::FutureExt;
-use std::time::Duration;
+use common_lib::{mbus_api::traceparent_context, types::v0::message_bus::MessageIdVs};
+use opentelemetry::{global, trace::FutureExt};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+    time::Duration,
+};
 use tonic::{
     transport::{Channel, Uri},
     IntoRequest,
@@ -45,13 +52,22 @@ pub fn timeout_grpc(op_id: MessageIdVs, min_timeout: Duration) -> Duration {
         MessageIdVs::CreateVolume => base_timeout.replica() * 3 + base_timeout.nexus(),
         MessageIdVs::DestroyVolume => base_timeout.replica() * 3 + base_timeout.nexus(),
         MessageIdVs::PublishVolume => base_timeout.nexus(),
+        MessageIdVs::RepublishVolume => base_timeout.nexus() * 2,
         MessageIdVs::UnpublishVolume => base_timeout.nexus(),
+        MessageIdVs::ExpandVolume => base_timeout.replica() * 3 + base_timeout.nexus(),
+        MessageIdVs::AddVolumeReplica => base_timeout.replica() + base_timeout.nexus(),
+        MessageIdVs::RemoveVolumeReplica => base_timeout.replica() + base_timeout.nexus(),
+        MessageIdVs::CreateSnapshot => base_timeout.nexus(),
 
         MessageIdVs::CreateNexus => base_timeout.nexus(),
         MessageIdVs::DestroyNexus => base_timeout.nexus(),
+        MessageIdVs::ResizeNexus => base_timeout.nexus(),
 
         MessageIdVs::CreateReplica => base_timeout.replica(),
         MessageIdVs::DestroyReplica => base_timeout.replica(),
+        MessageIdVs::ResizeReplica => base_timeout.replica(),
+        MessageIdVs::MoveReplica => base_timeout.replica() * 2 + base_timeout.nexus(),
+        MessageIdVs::CheckOrphanedReplicas => base_timeout.replica() * 3,
         _ => min_timeout,
     };
     timeout.max(min_timeout).min(Duration::from_secs(59))
@@ -62,6 +78,7 @@ pub fn timeout_grpc(op_id: MessageIdVs, min_timeout: Duration) -> Duration {
 #[derive(Clone, Debug)]
 pub struct Context {
     timeout_opts: Option<TimeoutOptions>,
+    parent_context: Option<opentelemetry::Context>,
 }
 
 impl Context {
@@ -69,9 +86,18 @@ impl Context {
     pub fn new(timeout_opts: impl Into<Option<TimeoutOptions>>) -> Self {
         Self {
             timeout_opts: timeout_opts.into(),
+            parent_context: None,
         }
     }
 
+    /// Parent the request span to the trace context of the given W3C `traceparent` (and
+    /// optional `tracestate`) header value, rather than to the current span, eg: when the
+    /// trace context of an incoming request wasn't installed into the current context.
+    pub fn with_traceparent(mut self, traceparent: &str, tracestate: Option<&str>) -> Self {
+        self.parent_context = Some(traceparent_context(traceparent, tracestate));
+        self
+    }
+
     /// Get the optional `TimeoutOptions`.
     pub fn timeout_opts(&self) -> Option<TimeoutOptions> {
         self.timeout_opts.clone()
@@ -124,8 +150,123 @@ impl Context {
     }
 }
 
+/// Candidate endpoints of a gRPC server which may have multiple instances, eg: the core agents
+/// in an HA setup, of which only the active one is serving requests.
+/// Requests are sent to the current endpoint, failing over to the next one when the current one
+/// cannot be connected to or it reports that it's not ready.
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    uris: Vec<Uri>,
+    current: Arc<AtomicUsize>,
+}
+
+impl Endpoints {
+    /// Return a new `Self` with the given candidate uris, starting with the first one.
+    /// # Panics
+    /// If no uri is provided.
+    pub fn new(uris: Vec<Uri>) -> Self {
+        assert!(!uris.is_empty(), "At least one endpoint uri is required");
+        Self {
+            uris,
+            current: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+    /// Get the candidate uris.
+    pub fn uris(&self) -> &[Uri] {
+        &self.uris
+    }
+    /// Get the uri of the current endpoint.
+    pub fn current_uri(&self) -> &Uri {
+        &self.uris[self.current()]
+    }
+    /// Fail over from the current endpoint to the next one.
+    pub fn failover(&self) {
+        self.failover_from(self.current());
+    }
+    fn current(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+    /// Fail over from the endpoint with the given index to the next one, unless that was done
+    /// already, eg: by a concurrent request which failed on the same endpoint.
+    fn failover_from(&self, index: usize) {
+        if self.uris.len() < 2 {
+            return;
+        }
+        let next = (index + 1) % self.uris.len();
+        if self
+            .current
+            .compare_exchange(index, next, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            tracing::warn!(
+                from = %self.uris[index],
+                to = %self.uris[next],
+                "Failing over to the next endpoint"
+            );
+        }
+    }
+}
+
+impl From<Uri> for Endpoints {
+    fn from(uri: Uri) -> Self {
+        Self::new(vec![uri])
+    }
+}
+
+/// Tonic Channels to each of the `Endpoints` which send the requests to the current endpoint,
+/// failing over to the next endpoint on a transport error, eg: when it cannot be connected to.
+#[derive(Clone)]
+pub struct FailoverChannel {
+    endpoints: Endpoints,
+    channels: Vec<Channel>,
+    /// index of the channel which was polled ready
+    ready: Option<usize>,
+}
+
+impl FailoverChannel {
+    fn new(endpoints: Endpoints, channels: Vec<Channel>) -> Self {
+        Self {
+            endpoints,
+            channels,
+            ready: None,
+        }
+    }
+}
+
+impl tower::Service<TonicClientRequest> for FailoverChannel {
+    type Response = <Channel as tower::Service<TonicClientRequest>>::Response;
+    type Error = <Channel as tower::Service<TonicClientRequest>>::Error;
+    type Future = BoxedFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let index = self.endpoints.current();
+        let result = self.channels[index].poll_ready(cx);
+        if let Poll::Ready(Ok(())) = result {
+            self.ready = Some(index);
+        }
+        result
+    }
+
+    fn call(&mut self, request: TonicClientRequest) -> Self::Future {
+        // the request must go to the channel which was polled ready
+        let index = self
+            .ready
+            .take()
+            .unwrap_or_else(|| self.endpoints.current());
+        let response = self.channels[index].call(request);
+        let endpoints = self.endpoints.clone();
+        Box::pin(async move {
+            let result = response.await;
+            if result.is_err() {
+                endpoints.failover_from(index);
+            }
+            result
+        })
+    }
+}
+
 /// Tonic Channel with added gRPC tracing
-pub(crate) type TracedChannel = crate::tracing::OpenTelClientService<Channel>;
+pub(crate) type TracedChannel = crate::tracing::OpenTelClientService<FailoverChannel>;
 
 /// Generic RPC Client.
 #[derive(Clone)]
@@ -136,16 +277,23 @@ pub struct Client<C: Clone> {
 
 impl<C: Clone> Client<C> {
     /// Creates a generic RPC client based on the provided arguments.
+    /// endpoints: Candidate endpoints of the server, see `Endpoints`.
     /// options: Timeout options which are used for connection and request timeouts.
     /// make_client: Creates a client of the appropriate type.
-    pub(crate) async fn new<O, M>(uri: Uri, options: O, make_client: M) -> Self
+    pub(crate) async fn new<E, O, M>(endpoints: E, options: O, make_client: M) -> Self
     where
+        E: Into<Endpoints>,
         O: Into<Option<TimeoutOptions>>,
         M: FnOnce(TracedChannel) -> C,
     {
         let context = Context::new(options);
-        let endpoint = context.endpoint(uri);
-        let channel = endpoint.connect_lazy().unwrap();
+        let endpoints = endpoints.into();
+        let channels = endpoints
+            .uris()
+            .iter()
+            .map(|uri| context.endpoint(uri.clone()).connect_lazy().unwrap())
+            .collect();
+        let channel = FailoverChannel::new(endpoints, channels);
 
         let channel = tower::ServiceBuilder::new()
             .layer(OpenTelClient::new())
@@ -164,10 +312,16 @@ impl<C: Clone> Client<C> {
         op_id: MessageIdVs,
     ) -> tonic::Request<T> {
         let timeout = context
+            .as_ref()
             .map(|c| c.base_timeout())
             .unwrap_or_else(|| timeout_grpc(op_id, self.context.base_timeout()));
         let mut request = request.into().into_request();
         request.set_timeout(timeout);
+        if let Some(parent) = context.and_then(|c| c.parent_context) {
+            global::get_text_map_propagator(|propagator| {
+                propagator.inject_context(&parent, &mut MetadataInjector(request.metadata_mut()))
+            });
+        }
         request
     }
     /// Returns a new client.
@@ -175,3 +329,66 @@ impl<C: Clone> Client<C> {
         self.client.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Client, Context, Endpoints};
+    use common_lib::types::v0::message_bus::MessageIdVs;
+    use opentelemetry::{
+        sdk::propagation::TraceContextPropagator,
+        trace::{TraceContextExt, TraceId},
+    };
+    use tonic::transport::Uri;
+
+    #[test]
+    fn failover() {
+        let uris = ["https://core-1:50051", "https://core-2:50051"]
+            .iter()
+            .map(|uri| uri.parse::<Uri>().unwrap())
+            .collect::<Vec<_>>();
+        let endpoints = Endpoints::new(uris.clone());
+        assert_eq!(endpoints.current_uri(), &uris[0]);
+
+        // clones share the current endpoint
+        endpoints.clone().failover();
+        assert_eq!(endpoints.current_uri(), &uris[1]);
+
+        // a concurrent request which also failed on the first endpoint doesn't fail over again
+        endpoints.failover_from(0);
+        assert_eq!(endpoints.current_uri(), &uris[1]);
+
+        endpoints.failover();
+        assert_eq!(endpoints.current_uri(), &uris[0]);
+
+        // nowhere to fail over to
+        let endpoints = Endpoints::from(uris[0].clone());
+        endpoints.failover();
+        assert_eq!(endpoints.current_uri(), &uris[0]);
+    }
+
+    #[test]
+    fn traceparent() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let traceparent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let client = Client {
+            context: Context::new(None),
+            client: (),
+        };
+
+        // the traceparent of the context is carried by the request metadata
+        let context = Context::new(None).with_traceparent(traceparent, None);
+        let request: tonic::Request<()> = client.request((), Some(context), MessageIdVs::GetNodes);
+        assert_eq!(request.metadata().get("traceparent").unwrap(), traceparent);
+
+        // and the client span is parented to it
+        let headers = request.metadata().clone().into_headers();
+        let parent = crate::tracing::client_parent_context(&headers);
+        assert_eq!(
+            parent.span().span_context().trace_id(),
+            TraceId::from_hex("0af7651916cd43dd8448eb211c80319c")
+        );
+
+        let request: tonic::Request<()> = client.request((), None, MessageIdVs::GetNodes);
+        assert!(request.metadata().get("traceparent").is_none());
+    }
+}
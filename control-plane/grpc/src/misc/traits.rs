@@ -1,5 +1,9 @@
 use crate::common;
-use common_lib::mbus_api::{ReplyError, ReplyErrorKind, ResourceKind};
+use common_lib::{
+    mbus_api::{ReplyError, ReplyErrorKind, ResourceKind},
+    types::v0::message_bus::{NodeId, ResourceRef},
+};
+use std::convert::TryFrom;
 
 /// Trait to validate the Grpc type by an intermediate conversion
 pub trait ValidateRequestTypes {
@@ -25,6 +29,7 @@ impl From<ResourceKind> for common::ResourceKind {
             ResourceKind::Block => Self::Block,
             ResourceKind::Watch => Self::Watch,
             ResourceKind::Spec => Self::Spec,
+            ResourceKind::VolumeSnapshot => Self::VolumeSnapshot,
         }
     }
 }
@@ -45,6 +50,7 @@ impl From<common::ResourceKind> for ResourceKind {
             common::ResourceKind::Block => Self::Block,
             common::ResourceKind::Watch => Self::Watch,
             common::ResourceKind::Spec => Self::Spec,
+            common::ResourceKind::VolumeSnapshot => Self::VolumeSnapshot,
         }
     }
 }
@@ -82,6 +88,7 @@ impl From<ReplyErrorKind> for common::ReplyErrorKind {
             ReplyErrorKind::ReplicaCreateNumber => Self::ReplicaCreateNumber,
             ReplyErrorKind::VolumeNoReplicas => Self::VolumeNoReplicas,
             ReplyErrorKind::InUse => Self::InUse,
+            ReplyErrorKind::DeviceInUse => Self::DeviceInUse,
         }
     }
 }
@@ -119,6 +126,7 @@ impl From<common::ReplyErrorKind> for ReplyErrorKind {
             common::ReplyErrorKind::ReplicaCreateNumber => Self::ReplicaCreateNumber,
             common::ReplyErrorKind::VolumeNoReplicas => Self::VolumeNoReplicas,
             common::ReplyErrorKind::InUse => Self::InUse,
+            common::ReplyErrorKind::DeviceInUse => Self::DeviceInUse,
         }
     }
 }
@@ -151,6 +159,32 @@ impl From<crate::common::ReplyError> for ReplyError {
     }
 }
 
+impl From<ResourceRef> for common::ResourceRef {
+    fn from(resource: ResourceRef) -> Self {
+        let kind: common::ResourceKind = resource.kind.into();
+        Self {
+            kind: kind as i32,
+            id: resource.id,
+            node_id: resource.node.map(|node| node.to_string()),
+        }
+    }
+}
+
+impl TryFrom<common::ResourceRef> for ResourceRef {
+    type Error = ReplyError;
+
+    fn try_from(resource: common::ResourceRef) -> Result<Self, Self::Error> {
+        let kind = common::ResourceKind::from_i32(resource.kind).ok_or_else(|| {
+            ReplyError::invalid_argument(
+                ResourceKind::Unknown,
+                "resource_kind",
+                format!("Unknown resource kind: {}", resource.kind),
+            )
+        })?;
+        ResourceRef::new(kind.into(), resource.id, resource.node_id.map(NodeId::from))
+    }
+}
+
 /// A newtype that is similar to a google StringValue generated code
 /// for simpler conversion to uuids
 pub struct StringValue(pub Option<String>);
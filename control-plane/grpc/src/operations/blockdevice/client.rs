@@ -0,0 +1,51 @@
+use crate::{
+    context::{Context, Endpoints},
+    operations::{
+        blockdevice::traits::{BlockDeviceOperations, GetBlockDeviceInfo},
+        node::{client::NodeClient, traits::NodeOperations},
+    },
+};
+use common_lib::mbus_api::{v0::BlockDevices, ReplyError, TimeoutOptions};
+
+/// RPC BlockDevice Client
+/// The block devices are served by the node service, so this reuses its `GetBlockDevices`
+/// operation rather than a service of its own.
+#[derive(Clone)]
+pub struct BlockDeviceClient {
+    node: NodeClient,
+}
+
+impl BlockDeviceClient {
+    /// creates a new base tonic endpoint with the timeout options and the address
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
+        Self::from(NodeClient::new(addr, opts).await)
+    }
+}
+
+impl From<NodeClient> for BlockDeviceClient {
+    fn from(node: NodeClient) -> Self {
+        Self { node }
+    }
+}
+
+/// Implement blockdevice operations supported by the BlockDevice RPC client.
+/// This converts the client side data into a RPC request.
+#[tonic::async_trait]
+impl BlockDeviceOperations for BlockDeviceClient {
+    #[tracing::instrument(
+        name = "BlockDeviceClient::get",
+        level = "debug",
+        skip(self, request),
+        err
+    )]
+    async fn get(
+        &self,
+        request: &dyn GetBlockDeviceInfo,
+        ctx: Option<Context>,
+    ) -> Result<BlockDevices, ReplyError> {
+        self.node.get_block_devices(request, ctx).await
+    }
+}
@@ -0,0 +1,5 @@
+/// BlockDevice grpc Client related code
+pub mod client;
+
+/// BlockDevice traits for the transport
+pub mod traits;
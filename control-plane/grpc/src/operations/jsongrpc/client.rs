@@ -1,5 +1,5 @@
 use crate::{
-    context::{Client, Context, TracedChannel},
+    context::{Client, Context, Endpoints, TracedChannel},
     jsongrpc::{json_grpc_client, json_grpc_reply, ProbeRequest},
     operations::jsongrpc::traits::{JsonGrpcOperations, JsonGrpcRequestInfo},
 };
@@ -9,7 +9,6 @@ use common_lib::{
 };
 use serde_json::Value;
 use std::{ops::Deref, time::Duration};
-use tonic::transport::Uri;
 
 /// RPC JsonGrpc Client
 #[derive(Clone)]
@@ -19,7 +18,10 @@ pub struct JsonGrpcClient {
 
 impl JsonGrpcClient {
     /// creates a new base tonic endpoint with the timeout options and the address
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
         let client = Client::new(addr, opts, json_grpc_client::JsonGrpcClient::new).await;
         Self { inner: client }
     }
@@ -25,6 +25,8 @@ pub trait JsonGrpcRequestInfo: Send + Sync + std::fmt::Debug {
     fn method(&self) -> JsonGrpcMethod;
     /// parameters to be passed to the above method
     fn params(&self) -> JsonGrpcParams;
+    /// data-plane endpoint of the io-engine instance, bypassing the node lookup
+    fn endpoint(&self) -> Option<String>;
 }
 
 impl JsonGrpcRequestInfo for JsonGrpcRequest {
@@ -39,6 +41,10 @@ impl JsonGrpcRequestInfo for JsonGrpcRequest {
     fn params(&self) -> JsonGrpcParams {
         self.params.clone()
     }
+
+    fn endpoint(&self) -> Option<String> {
+        self.endpoint.clone()
+    }
 }
 
 impl JsonGrpcRequestInfo for jsongrpc::JsonGrpcRequest {
@@ -53,6 +59,10 @@ impl JsonGrpcRequestInfo for jsongrpc::JsonGrpcRequest {
     fn params(&self) -> JsonGrpcParams {
         self.json_grpc_params.clone().into()
     }
+
+    fn endpoint(&self) -> Option<String> {
+        self.endpoint.clone()
+    }
 }
 
 impl From<&dyn JsonGrpcRequestInfo> for JsonGrpcRequest {
@@ -61,6 +71,7 @@ impl From<&dyn JsonGrpcRequestInfo> for JsonGrpcRequest {
             node: data.node(),
             method: data.method(),
             params: data.params(),
+            endpoint: data.endpoint(),
         }
     }
 }
@@ -71,6 +82,7 @@ impl From<&dyn JsonGrpcRequestInfo> for jsongrpc::JsonGrpcRequest {
             node_id: data.node().to_string(),
             json_grpc_method: data.method().to_string(),
             json_grpc_params: data.params().to_string(),
+            endpoint: data.endpoint(),
         }
     }
 }
@@ -15,6 +15,9 @@ pub mod volume;
 /// module for all corresponding client, server, traits for node transport
 pub mod node;
 
+/// module for all corresponding client, server, traits for blockdevice transport
+pub mod blockdevice;
+
 /// module for all corresponding client, server, traits for registration transport
 pub mod registration;
 
@@ -47,6 +50,20 @@ impl<T> PaginatedResult<T> {
         }
     }
 
+    /// Create a new `PaginatedResult` instance with the subset of the `entries` which is
+    /// selected by the `pagination`.
+    pub fn paginate(entries: Vec<T>, pagination: &Pagination) -> Self {
+        let num_entries = entries.len() as u64;
+        let offset = std::cmp::min(pagination.starting_token(), num_entries);
+        let last_result = offset + pagination.max_entries() >= num_entries;
+        let result = entries
+            .into_iter()
+            .skip(offset as usize)
+            .take(pagination.max_entries() as usize)
+            .collect();
+        Self::new(result, last_result)
+    }
+
     /// Returns the result vector.
     pub fn result(self) -> Vec<T> {
         self.result
@@ -114,3 +131,25 @@ impl From<crate::common::Pagination> for Pagination {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{PaginatedResult, Pagination};
+
+    #[test]
+    fn paginate() {
+        let entries = (0 .. 5).collect::<Vec<u64>>();
+
+        let page = PaginatedResult::paginate(entries.clone(), &Pagination::new(2, 0));
+        assert!(!page.last());
+        assert_eq!(page.result(), vec![0, 1]);
+
+        let page = PaginatedResult::paginate(entries.clone(), &Pagination::new(2, 4));
+        assert!(page.last());
+        assert_eq!(page.result(), vec![4]);
+
+        let page = PaginatedResult::paginate(entries, &Pagination::new(2, 10));
+        assert!(page.last());
+        assert!(page.is_empty());
+    }
+}
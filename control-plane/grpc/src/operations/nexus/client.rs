@@ -1,21 +1,25 @@
 use crate::{
     common::{NexusFilter, NodeFilter, NodeNexusFilter},
-    context::{Client, Context, TracedChannel},
+    context::{Client, Context, Endpoints, TracedChannel},
     nexus::{
         add_nexus_child_reply, create_nexus_reply, get_nexuses_reply, get_nexuses_request,
-        nexus_grpc_client::NexusGrpcClient, share_nexus_reply, GetNexusesRequest,
+        get_rebuild_history_reply, get_rebuilds_reply, nexus_grpc_client::NexusGrpcClient,
+        share_nexus_reply, GetNexusesRequest, ProbeRequest,
     },
-    operations::nexus::traits::{
-        AddNexusChildInfo, CreateNexusInfo, DestroyNexusInfo, NexusOperations,
-        RemoveNexusChildInfo, ShareNexusInfo, UnshareNexusInfo,
+    operations::{
+        nexus::traits::{
+            AddNexusChildInfo, CancelRebuildInfo, CreateNexusInfo, DestroyNexusInfo,
+            GetRebuildHistoryInfo, GetRebuildsInfo, NexusOperations, RebuildOperations,
+            RemoveNexusChildInfo, ShareNexusInfo, UnshareNexusInfo,
+        },
+        Pagination,
     },
 };
 use common_lib::{
     mbus_api::{v0::Nexuses, ReplyError, ResourceKind, TimeoutOptions},
-    types::v0::message_bus::{Child, Filter, MessageIdVs, Nexus},
+    types::v0::message_bus::{Child, Filter, MessageIdVs, Nexus, RebuildHistory, Rebuilds},
 };
 use std::{convert::TryFrom, ops::Deref};
-use tonic::transport::Uri;
 
 /// RPC Nexus Client
 #[derive(Clone)]
@@ -32,7 +36,10 @@ impl Deref for NexusClient {
 
 impl NexusClient {
     /// creates a new base tonic endpoint with the timeout options and the address
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
         let client = Client::new(addr, opts, NexusGrpcClient::new).await;
         Self { inner: client }
     }
@@ -58,25 +65,36 @@ impl NexusOperations for NexusClient {
     }
 
     #[tracing::instrument(name = "NexusClient::get", level = "debug", skip(self), err)]
-    async fn get(&self, filter: Filter, ctx: Option<Context>) -> Result<Nexuses, ReplyError> {
+    async fn get(
+        &self,
+        filter: Filter,
+        pagination: Option<Pagination>,
+        ctx: Option<Context>,
+    ) -> Result<Nexuses, ReplyError> {
         let req: GetNexusesRequest = match filter {
             Filter::Node(id) => GetNexusesRequest {
                 filter: Some(get_nexuses_request::Filter::Node(NodeFilter {
                     node_id: id.into(),
                 })),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::NodeNexus(node_id, nexus_id) => GetNexusesRequest {
                 filter: Some(get_nexuses_request::Filter::NodeNexus(NodeNexusFilter {
                     node_id: node_id.into(),
                     nexus_id: nexus_id.to_string(),
                 })),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::Nexus(nexus_id) => GetNexusesRequest {
                 filter: Some(get_nexuses_request::Filter::Nexus(NexusFilter {
                     nexus_id: nexus_id.to_string(),
                 })),
+                pagination: pagination.map(|p| p.into()),
+            },
+            _ => GetNexusesRequest {
+                filter: None,
+                pagination: pagination.map(|p| p.into()),
             },
-            _ => GetNexusesRequest { filter: None },
         };
         let req = self.request(req, ctx, MessageIdVs::GetNexuses);
         let response = self.client().get_nexuses(req).await?.into_inner();
@@ -174,4 +192,70 @@ impl NexusOperations for NexusClient {
             Some(err) => Err(err.into()),
         }
     }
+
+    #[tracing::instrument(
+        name = "NexusClient::get_nexus_rebuild_history",
+        level = "debug",
+        skip(self),
+        err
+    )]
+    async fn get_nexus_rebuild_history(
+        &self,
+        request: &dyn GetRebuildHistoryInfo,
+        ctx: Option<Context>,
+    ) -> Result<RebuildHistory, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::GetRebuildHistory);
+        let response = self.client().get_rebuild_history(req).await?.into_inner();
+        match response.reply {
+            Some(get_rebuild_history_reply) => match get_rebuild_history_reply {
+                get_rebuild_history_reply::Reply::History(history) => {
+                    Ok(RebuildHistory::try_from(history)?)
+                }
+                get_rebuild_history_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Nexus)),
+        }
+    }
+
+    #[tracing::instrument(name = "NexusClient::probe", level = "debug", skip(self))]
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        match self.client().probe(ProbeRequest {}).await {
+            Ok(resp) => Ok(resp.into_inner().ready),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl RebuildOperations for NexusClient {
+    #[tracing::instrument(name = "NexusClient::get_rebuilds", level = "debug", skip(self), err)]
+    async fn get_rebuilds(
+        &self,
+        request: &dyn GetRebuildsInfo,
+        ctx: Option<Context>,
+    ) -> Result<Rebuilds, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::GetRebuilds);
+        let response = self.client().get_rebuilds(req).await?.into_inner();
+        match response.reply {
+            Some(get_rebuilds_reply) => match get_rebuilds_reply {
+                get_rebuilds_reply::Reply::Rebuilds(rebuilds) => Ok(Rebuilds::try_from(rebuilds)?),
+                get_rebuilds_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Nexus)),
+        }
+    }
+
+    #[tracing::instrument(name = "NexusClient::cancel_rebuild", level = "debug", skip(self), err)]
+    async fn cancel_rebuild(
+        &self,
+        request: &dyn CancelRebuildInfo,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::CancelRebuild);
+        let response = self.client().cancel_rebuild(req).await?.into_inner();
+        match response.error {
+            None => Ok(()),
+            Some(err) => Err(err.into()),
+        }
+    }
 }
@@ -1,14 +1,20 @@
 use crate::{
     misc::traits::ValidateRequestTypes,
     nexus::{
-        add_nexus_child_reply, create_nexus_reply, get_nexuses_reply,
+        add_nexus_child_reply, create_nexus_reply, get_nexuses_reply, get_rebuild_history_reply,
+        get_rebuilds_reply,
         nexus_grpc_server::{NexusGrpc, NexusGrpcServer},
-        share_nexus_reply, AddNexusChildReply, AddNexusChildRequest, CreateNexusReply,
-        CreateNexusRequest, DestroyNexusReply, DestroyNexusRequest, GetNexusesReply,
-        GetNexusesRequest, RemoveNexusChildReply, RemoveNexusChildRequest, ShareNexusReply,
+        share_nexus_reply, AddNexusChildReply, AddNexusChildRequest, CancelRebuildReply,
+        CancelRebuildRequest, CreateNexusReply, CreateNexusRequest, DestroyNexusReply,
+        DestroyNexusRequest, GetNexusesReply, GetNexusesRequest, GetRebuildHistoryReply,
+        GetRebuildHistoryRequest, GetRebuildsReply, GetRebuildsRequest, ProbeRequest,
+        ProbeResponse, RemoveNexusChildReply, RemoveNexusChildRequest, ShareNexusReply,
         ShareNexusRequest, UnshareNexusReply, UnshareNexusRequest,
     },
-    operations::nexus::traits::NexusOperations,
+    operations::{
+        nexus::traits::{NexusOperations, RebuildOperations},
+        Pagination,
+    },
 };
 use common_lib::types::v0::message_bus::Filter;
 use std::{convert::TryFrom, sync::Arc};
@@ -19,12 +25,17 @@ use tonic::Response;
 pub struct NexusServer {
     /// Service which executes the operations.
     service: Arc<dyn NexusOperations>,
+    /// Service which executes the rebuild operations.
+    rebuild_service: Arc<dyn RebuildOperations>,
 }
 
 impl NexusServer {
-    /// returns a new nexus server with the service implementing nexus operations
-    pub fn new(service: Arc<dyn NexusOperations>) -> Self {
-        Self { service }
+    /// returns a new nexus server with the service implementing nexus and rebuild operations
+    pub fn new<S: NexusOperations + RebuildOperations + 'static>(service: Arc<S>) -> Self {
+        Self {
+            service: service.clone(),
+            rebuild_service: service,
+        }
     }
     /// coverts the nexus server to its corresponding grpc server type
     pub fn into_grpc_server(self) -> NexusGrpcServer<NexusServer> {
@@ -126,7 +137,8 @@ impl NexusGrpc for NexusServer {
             Some(filter) => Filter::try_from(filter)?,
             None => Filter::None,
         };
-        match self.service.get(filter, None).await {
+        let pagination: Option<Pagination> = req.pagination.map(|p| p.into());
+        match self.service.get(filter, pagination, None).await {
             Ok(nexuses) => Ok(Response::new(GetNexusesReply {
                 reply: Some(get_nexuses_reply::Reply::Nexuses(nexuses.into())),
             })),
@@ -135,4 +147,56 @@ impl NexusGrpc for NexusServer {
             })),
         }
     }
+
+    async fn get_rebuild_history(
+        &self,
+        request: tonic::Request<GetRebuildHistoryRequest>,
+    ) -> Result<tonic::Response<GetRebuildHistoryReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.get_nexus_rebuild_history(&req, None).await {
+            Ok(history) => Ok(Response::new(GetRebuildHistoryReply {
+                reply: Some(get_rebuild_history_reply::Reply::History(history.into())),
+            })),
+            Err(err) => Ok(Response::new(GetRebuildHistoryReply {
+                reply: Some(get_rebuild_history_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+
+    async fn get_rebuilds(
+        &self,
+        request: tonic::Request<GetRebuildsRequest>,
+    ) -> Result<tonic::Response<GetRebuildsReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.rebuild_service.get_rebuilds(&req, None).await {
+            Ok(rebuilds) => Ok(Response::new(GetRebuildsReply {
+                reply: Some(get_rebuilds_reply::Reply::Rebuilds(rebuilds.into())),
+            })),
+            Err(err) => Ok(Response::new(GetRebuildsReply {
+                reply: Some(get_rebuilds_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+
+    async fn cancel_rebuild(
+        &self,
+        request: tonic::Request<CancelRebuildRequest>,
+    ) -> Result<tonic::Response<CancelRebuildReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.rebuild_service.cancel_rebuild(&req, None).await {
+            Ok(()) => Ok(Response::new(CancelRebuildReply { error: None })),
+            Err(e) => Ok(Response::new(CancelRebuildReply {
+                error: Some(e.into()),
+            })),
+        }
+    }
+    async fn probe(
+        &self,
+        _request: tonic::Request<ProbeRequest>,
+    ) -> Result<tonic::Response<ProbeResponse>, tonic::Status> {
+        match self.service.probe(None).await {
+            Ok(resp) => Ok(Response::new(ProbeResponse { ready: resp })),
+            Err(_) => Ok(Response::new(ProbeResponse { ready: false })),
+        }
+    }
 }
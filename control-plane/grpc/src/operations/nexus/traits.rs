@@ -4,17 +4,21 @@ use crate::{
     misc::traits::{StringValue, ValidateRequestTypes},
     nexus,
     nexus::{
-        get_nexuses_request, AddNexusChildRequest, CreateNexusRequest, DestroyNexusRequest,
-        RemoveNexusChildRequest, ShareNexusRequest, UnshareNexusRequest,
+        get_nexuses_request, AddNexusChildRequest, CancelRebuildRequest, CreateNexusRequest,
+        DestroyNexusRequest, GetRebuildHistoryRequest, GetRebuildsRequest, RemoveNexusChildRequest,
+        ShareNexusRequest, UnshareNexusRequest,
     },
+    operations::Pagination,
 };
 use common_lib::{
     mbus_api::{v0::Nexuses, ReplyError, ResourceKind},
     types::v0::{
         message_bus::{
-            AddNexusChild, Child, ChildState, ChildUri, CreateNexus, DestroyNexus, Filter, Nexus,
-            NexusId, NexusNvmfConfig, NexusShareProtocol, NexusStatus, NodeId,
-            NvmfControllerIdRange, RemoveNexusChild, ReplicaId, ShareNexus, UnshareNexus, VolumeId,
+            AddNexusChild, CancelRebuild, Child, ChildState, ChildUri, CreateNexus, DestroyNexus,
+            Filter, GetRebuildHistory, GetRebuilds, Nexus, NexusId, NexusNvmfConfig,
+            NexusShareProtocol, NexusStatus, NodeId, NvmfControllerIdRange, NvmfTransportParams,
+            Rebuild, RebuildHistory, RebuildJobState, RebuildRecord, Rebuilds, RemoveNexusChild,
+            ReplicaId, ShareNexus, UnshareNexus, VolumeId,
         },
         store::{
             nexus::{NexusOperation, NexusOperationState, NexusSpec, NexusSpecStatus, ReplicaUri},
@@ -34,7 +38,12 @@ pub trait NexusOperations: Send + Sync {
         ctx: Option<Context>,
     ) -> Result<Nexus, ReplyError>;
     /// Get Nexuses based on filters
-    async fn get(&self, filter: Filter, ctx: Option<Context>) -> Result<Nexuses, ReplyError>;
+    async fn get(
+        &self,
+        filter: Filter,
+        pagination: Option<Pagination>,
+        ctx: Option<Context>,
+    ) -> Result<Nexuses, ReplyError>;
     /// Destroy a Nexus
     async fn destroy(
         &self,
@@ -65,6 +74,31 @@ pub trait NexusOperations: Send + Sync {
         req: &dyn RemoveNexusChildInfo,
         ctx: Option<Context>,
     ) -> Result<(), ReplyError>;
+    /// Get the rebuild history of a Nexus
+    async fn get_nexus_rebuild_history(
+        &self,
+        req: &dyn GetRebuildHistoryInfo,
+        ctx: Option<Context>,
+    ) -> Result<RebuildHistory, ReplyError>;
+    /// Liveness probe for nexus service
+    async fn probe(&self, ctx: Option<Context>) -> Result<bool, ReplyError>;
+}
+
+/// Trait implemented by services which support rebuild operations.
+#[tonic::async_trait]
+pub trait RebuildOperations: Send + Sync {
+    /// Get the rebuilds in progress
+    async fn get_rebuilds(
+        &self,
+        req: &dyn GetRebuildsInfo,
+        ctx: Option<Context>,
+    ) -> Result<Rebuilds, ReplyError>;
+    /// Cancel the rebuild of a Nexus child
+    async fn cancel_rebuild(
+        &self,
+        req: &dyn CancelRebuildInfo,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError>;
 }
 
 impl TryFrom<nexus::Nexus> for Nexus {
@@ -146,7 +180,10 @@ impl TryFrom<nexus::Nexuses> for Nexuses {
         for nexus in grpc_nexuses_type.nexuses {
             nexuses.push(Nexus::try_from(nexus.clone())?)
         }
-        Ok(Nexuses(nexuses))
+        Ok(Nexuses {
+            entries: nexuses,
+            next_token: grpc_nexuses_type.next_token,
+        })
     }
 }
 
@@ -154,10 +191,11 @@ impl From<Nexuses> for nexus::Nexuses {
     fn from(nexuses: Nexuses) -> Self {
         nexus::Nexuses {
             nexuses: nexuses
-                .into_inner()
+                .entries
                 .iter()
                 .map(|nexuses| nexuses.clone().into())
                 .collect(),
+            next_token: nexuses.next_token,
         }
     }
 }
@@ -222,6 +260,19 @@ impl TryFrom<nexus::Child> for Child {
                 }
             },
             rebuild_progress: child_grpc_type.rebuild_progress.map(|i| i as u8),
+            state_changed_at: match child_grpc_type.state_changed_at {
+                Some(timestamp) => match std::time::SystemTime::try_from(timestamp) {
+                    Ok(time) => Some(time),
+                    Err(err) => {
+                        return Err(ReplyError::invalid_argument(
+                            ResourceKind::Nexus,
+                            "child.state_changed_at",
+                            format!("{:?}", err),
+                        ))
+                    }
+                },
+                None => None,
+            },
         };
         Ok(child)
     }
@@ -234,6 +285,7 @@ impl From<Child> for nexus::Child {
             uri: child.uri.to_string(),
             state: child_state as i32,
             rebuild_progress: child.rebuild_progress.map(|i| i.into()),
+            state_changed_at: child.state_changed_at.map(Into::into),
         }
     }
 }
@@ -316,6 +368,10 @@ impl TryFrom<nexus::NexusSpec> for NexusSpec {
                 operation: NexusOperation::Create,
                 result: op.result,
             }),
+            config: match value.config {
+                Some(config) => Some(NexusNvmfConfig::try_from(config)?),
+                None => None,
+            },
         })
     }
 }
@@ -341,6 +397,7 @@ impl From<NexusSpec> for nexus::NexusSpec {
             operation: value.operation.map(|operation| common::SpecOperation {
                 result: operation.result,
             }),
+            config: value.config.map(|config| config.into()),
         }
     }
 }
@@ -717,6 +774,8 @@ pub trait ShareNexusInfo: Send + Sync + std::fmt::Debug {
     fn key(&self) -> Option<String>;
     /// Protocol used for exposing the nexus
     fn protocol(&self) -> NexusShareProtocol;
+    /// NVMe-oF transport parameters
+    fn transport_params(&self) -> NvmfTransportParams;
 }
 
 impl ShareNexusInfo for ShareNexus {
@@ -735,6 +794,10 @@ impl ShareNexusInfo for ShareNexus {
     fn protocol(&self) -> NexusShareProtocol {
         self.protocol
     }
+
+    fn transport_params(&self) -> NvmfTransportParams {
+        self.transport_params.clone()
+    }
 }
 
 impl From<nexus::NexusShareProtocol> for NexusShareProtocol {
@@ -779,6 +842,10 @@ impl ShareNexusInfo for ValidatedShareNexusRequest {
     fn uuid(&self) -> NexusId {
         self.uuid.clone()
     }
+
+    fn transport_params(&self) -> NvmfTransportParams {
+        self.inner.transport_params.clone()
+    }
 }
 
 impl ValidateRequestTypes for ShareNexusRequest {
@@ -809,6 +876,7 @@ impl From<&dyn ShareNexusInfo> for ShareNexusRequest {
             nexus_id: Some(data.uuid().to_string()),
             protocol: protocol as i32,
             key: data.key(),
+            transport_params: data.transport_params(),
         }
     }
 }
@@ -820,6 +888,7 @@ impl From<&dyn ShareNexusInfo> for ShareNexus {
             uuid: data.uuid(),
             key: data.key(),
             protocol: data.protocol(),
+            transport_params: data.transport_params(),
         }
     }
 }
@@ -899,6 +968,8 @@ pub trait AddNexusChildInfo: Send + Sync + std::fmt::Debug {
     fn uri(&self) -> ChildUri;
     /// auto start rebuilding
     fn auto_rebuild(&self) -> bool;
+    /// preferred healthy child to rebuild from
+    fn rebuild_source(&self) -> Option<ChildUri>;
 }
 
 impl AddNexusChildInfo for AddNexusChild {
@@ -917,6 +988,10 @@ impl AddNexusChildInfo for AddNexusChild {
     fn auto_rebuild(&self) -> bool {
         self.auto_rebuild
     }
+
+    fn rebuild_source(&self) -> Option<ChildUri> {
+        self.rebuild_source.clone()
+    }
 }
 
 /// Intermediate structure that validates the conversion to AddNexusChildRequest type
@@ -942,6 +1017,10 @@ impl AddNexusChildInfo for ValidatedAddNexusChildRequest {
     fn auto_rebuild(&self) -> bool {
         self.inner.auto_rebuild
     }
+
+    fn rebuild_source(&self) -> Option<ChildUri> {
+        self.inner.rebuild_source.clone().map(ChildUri::from)
+    }
 }
 
 impl ValidateRequestTypes for AddNexusChildRequest {
@@ -961,6 +1040,7 @@ impl From<&dyn AddNexusChildInfo> for AddNexusChildRequest {
             nexus_id: Some(data.nexus().to_string()),
             uri: data.uri().to_string(),
             auto_rebuild: data.auto_rebuild(),
+            rebuild_source: data.rebuild_source().map(|uri| uri.to_string()),
         }
     }
 }
@@ -972,6 +1052,7 @@ impl From<&dyn AddNexusChildInfo> for AddNexusChild {
             nexus: data.nexus(),
             uri: data.uri(),
             auto_rebuild: data.auto_rebuild(),
+            rebuild_source: data.rebuild_source(),
         }
     }
 }
@@ -1051,3 +1132,331 @@ impl From<&dyn RemoveNexusChildInfo> for RemoveNexusChild {
         }
     }
 }
+
+/// GetRebuildHistoryInfo trait for the nexus rebuild history to be implemented by entities which
+/// want to use this operation
+pub trait GetRebuildHistoryInfo: Send + Sync + std::fmt::Debug {
+    /// uuid of the nexus
+    fn nexus(&self) -> NexusId;
+}
+
+impl GetRebuildHistoryInfo for GetRebuildHistory {
+    fn nexus(&self) -> NexusId {
+        self.nexus.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to GetRebuildHistoryRequest type
+#[derive(Debug)]
+pub struct ValidatedGetRebuildHistoryRequest {
+    nexus: NexusId,
+}
+
+impl GetRebuildHistoryInfo for ValidatedGetRebuildHistoryRequest {
+    fn nexus(&self) -> NexusId {
+        self.nexus.clone()
+    }
+}
+
+impl ValidateRequestTypes for GetRebuildHistoryRequest {
+    type Validated = ValidatedGetRebuildHistoryRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedGetRebuildHistoryRequest {
+            nexus: NexusId::try_from(StringValue(self.nexus_id))?,
+        })
+    }
+}
+
+impl From<&dyn GetRebuildHistoryInfo> for GetRebuildHistoryRequest {
+    fn from(data: &dyn GetRebuildHistoryInfo) -> Self {
+        Self {
+            nexus_id: Some(data.nexus().to_string()),
+        }
+    }
+}
+
+impl From<&dyn GetRebuildHistoryInfo> for GetRebuildHistory {
+    fn from(data: &dyn GetRebuildHistoryInfo) -> Self {
+        Self {
+            nexus: data.nexus(),
+        }
+    }
+}
+
+impl From<nexus::RebuildJobState> for RebuildJobState {
+    fn from(src: nexus::RebuildJobState) -> Self {
+        match src {
+            nexus::RebuildJobState::Init => Self::Init,
+            nexus::RebuildJobState::Rebuilding => Self::Rebuilding,
+            nexus::RebuildJobState::Stopped => Self::Stopped,
+            nexus::RebuildJobState::Paused => Self::Paused,
+            nexus::RebuildJobState::Failed => Self::Failed,
+            nexus::RebuildJobState::Completed => Self::Completed,
+        }
+    }
+}
+
+impl From<RebuildJobState> for nexus::RebuildJobState {
+    fn from(src: RebuildJobState) -> Self {
+        match src {
+            RebuildJobState::Init => Self::Init,
+            RebuildJobState::Rebuilding => Self::Rebuilding,
+            RebuildJobState::Stopped => Self::Stopped,
+            RebuildJobState::Paused => Self::Paused,
+            RebuildJobState::Failed => Self::Failed,
+            RebuildJobState::Completed => Self::Completed,
+        }
+    }
+}
+
+impl TryFrom<nexus::RebuildRecord> for RebuildRecord {
+    type Error = ReplyError;
+    fn try_from(record: nexus::RebuildRecord) -> Result<Self, Self::Error> {
+        let time = |timestamp: Option<prost_types::Timestamp>, arg_name: &str| match timestamp {
+            Some(timestamp) => std::time::SystemTime::try_from(timestamp).map_err(|error| {
+                ReplyError::invalid_argument(ResourceKind::Nexus, arg_name, format!("{:?}", error))
+            }),
+            None => Err(ReplyError::missing_argument(ResourceKind::Nexus, arg_name)),
+        };
+        Ok(RebuildRecord {
+            child_uri: record.child_uri.into(),
+            src_uri: record.src_uri.into(),
+            state: match nexus::RebuildJobState::from_i32(record.state) {
+                Some(state) => state.into(),
+                None => {
+                    return Err(ReplyError::invalid_argument(
+                        ResourceKind::Nexus,
+                        "record.state",
+                        "".to_string(),
+                    ))
+                }
+            },
+            blocks_total: record.blocks_total,
+            blocks_recovered: record.blocks_recovered,
+            blocks_transferred: record.blocks_transferred,
+            block_size: record.block_size,
+            is_partial: record.is_partial,
+            start_time: time(record.start_time, "record.start_time")?,
+            end_time: time(record.end_time, "record.end_time")?,
+        })
+    }
+}
+
+impl From<RebuildRecord> for nexus::RebuildRecord {
+    fn from(record: RebuildRecord) -> Self {
+        let state: nexus::RebuildJobState = record.state.into();
+        Self {
+            child_uri: record.child_uri.to_string(),
+            src_uri: record.src_uri.to_string(),
+            state: state as i32,
+            blocks_total: record.blocks_total,
+            blocks_recovered: record.blocks_recovered,
+            blocks_transferred: record.blocks_transferred,
+            block_size: record.block_size,
+            is_partial: record.is_partial,
+            start_time: Some(record.start_time.into()),
+            end_time: Some(record.end_time.into()),
+        }
+    }
+}
+
+impl TryFrom<nexus::RebuildHistory> for RebuildHistory {
+    type Error = ReplyError;
+    fn try_from(history: nexus::RebuildHistory) -> Result<Self, Self::Error> {
+        Ok(RebuildHistory {
+            nexus: NexusId::try_from(StringValue(history.nexus_id))?,
+            records: history
+                .records
+                .into_iter()
+                .map(RebuildRecord::try_from)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl From<RebuildHistory> for nexus::RebuildHistory {
+    fn from(history: RebuildHistory) -> Self {
+        Self {
+            nexus_id: Some(history.nexus.to_string()),
+            records: history.records.into_iter().map(From::from).collect(),
+        }
+    }
+}
+
+/// GetRebuildsInfo trait for the rebuilds in progress to be implemented by entities which want to
+/// use this operation
+pub trait GetRebuildsInfo: Send + Sync + std::fmt::Debug {
+    /// uuid of the nexus, or all nexuses if None
+    fn nexus(&self) -> Option<NexusId>;
+}
+
+impl GetRebuildsInfo for GetRebuilds {
+    fn nexus(&self) -> Option<NexusId> {
+        self.nexus.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to GetRebuildsRequest type
+#[derive(Debug)]
+pub struct ValidatedGetRebuildsRequest {
+    nexus: Option<NexusId>,
+}
+
+impl GetRebuildsInfo for ValidatedGetRebuildsRequest {
+    fn nexus(&self) -> Option<NexusId> {
+        self.nexus.clone()
+    }
+}
+
+impl ValidateRequestTypes for GetRebuildsRequest {
+    type Validated = ValidatedGetRebuildsRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedGetRebuildsRequest {
+            nexus: match self.nexus_id {
+                Some(nexus_id) => Some(NexusId::try_from(StringValue(Some(nexus_id)))?),
+                None => None,
+            },
+        })
+    }
+}
+
+impl From<&dyn GetRebuildsInfo> for GetRebuildsRequest {
+    fn from(data: &dyn GetRebuildsInfo) -> Self {
+        Self {
+            nexus_id: data.nexus().map(|nexus| nexus.to_string()),
+        }
+    }
+}
+
+impl From<&dyn GetRebuildsInfo> for GetRebuilds {
+    fn from(data: &dyn GetRebuildsInfo) -> Self {
+        Self {
+            nexus: data.nexus(),
+        }
+    }
+}
+
+impl TryFrom<nexus::Rebuild> for Rebuild {
+    type Error = ReplyError;
+    fn try_from(rebuild: nexus::Rebuild) -> Result<Self, Self::Error> {
+        Ok(Rebuild {
+            nexus: NexusId::try_from(StringValue(rebuild.nexus_id))?,
+            child_uri: rebuild.child_uri.into(),
+            progress: u8::try_from(rebuild.progress).map_err(|error| {
+                ReplyError::invalid_argument(
+                    ResourceKind::Nexus,
+                    "rebuild.progress",
+                    error.to_string(),
+                )
+            })?,
+            start_time: match rebuild.start_time {
+                Some(timestamp) => {
+                    Some(std::time::SystemTime::try_from(timestamp).map_err(|error| {
+                        ReplyError::invalid_argument(
+                            ResourceKind::Nexus,
+                            "rebuild.start_time",
+                            format!("{:?}", error),
+                        )
+                    })?)
+                }
+                None => None,
+            },
+        })
+    }
+}
+
+impl From<Rebuild> for nexus::Rebuild {
+    fn from(rebuild: Rebuild) -> Self {
+        Self {
+            nexus_id: Some(rebuild.nexus.to_string()),
+            child_uri: rebuild.child_uri.to_string(),
+            progress: rebuild.progress as u32,
+            start_time: rebuild.start_time.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<nexus::Rebuilds> for Rebuilds {
+    type Error = ReplyError;
+    fn try_from(rebuilds: nexus::Rebuilds) -> Result<Self, Self::Error> {
+        Ok(Rebuilds {
+            entries: rebuilds
+                .rebuilds
+                .into_iter()
+                .map(Rebuild::try_from)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl From<Rebuilds> for nexus::Rebuilds {
+    fn from(rebuilds: Rebuilds) -> Self {
+        Self {
+            rebuilds: rebuilds.entries.into_iter().map(From::from).collect(),
+        }
+    }
+}
+
+/// CancelRebuildInfo trait for the cancellation of a nexus child rebuild to be implemented by
+/// entities which want to use this operation
+pub trait CancelRebuildInfo: Send + Sync + std::fmt::Debug {
+    /// uuid of the nexus
+    fn nexus(&self) -> NexusId;
+    /// URI of the child being rebuilt
+    fn child_uri(&self) -> ChildUri;
+}
+
+impl CancelRebuildInfo for CancelRebuild {
+    fn nexus(&self) -> NexusId {
+        self.nexus.clone()
+    }
+
+    fn child_uri(&self) -> ChildUri {
+        self.child_uri.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to CancelRebuildRequest type
+#[derive(Debug)]
+pub struct ValidatedCancelRebuildRequest {
+    inner: CancelRebuildRequest,
+    nexus: NexusId,
+}
+
+impl CancelRebuildInfo for ValidatedCancelRebuildRequest {
+    fn nexus(&self) -> NexusId {
+        self.nexus.clone()
+    }
+
+    fn child_uri(&self) -> ChildUri {
+        ChildUri::from(self.inner.child_uri.clone())
+    }
+}
+
+impl ValidateRequestTypes for CancelRebuildRequest {
+    type Validated = ValidatedCancelRebuildRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedCancelRebuildRequest {
+            nexus: NexusId::try_from(StringValue(self.nexus_id.clone()))?,
+            inner: self,
+        })
+    }
+}
+
+impl From<&dyn CancelRebuildInfo> for CancelRebuildRequest {
+    fn from(data: &dyn CancelRebuildInfo) -> Self {
+        Self {
+            nexus_id: Some(data.nexus().to_string()),
+            child_uri: data.child_uri().to_string(),
+        }
+    }
+}
+
+impl From<&dyn CancelRebuildInfo> for CancelRebuild {
+    fn from(data: &dyn CancelRebuildInfo) -> Self {
+        Self {
+            nexus: data.nexus(),
+            child_uri: data.child_uri(),
+        }
+    }
+}
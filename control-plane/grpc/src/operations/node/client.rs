@@ -1,22 +1,27 @@
 use crate::{
     blockdevice::get_block_devices_reply,
     common::NodeFilter,
-    context::{Client, Context, TracedChannel},
+    context::{Client, Context, Endpoints, TracedChannel},
     node::{
-        get_nodes_reply, get_nodes_request, node_grpc_client::NodeGrpcClient, GetNodesRequest,
-        ProbeRequest,
+        cordon_node_reply, drain_node_reply, get_nodes_reply, get_nodes_request,
+        node_grpc_client::NodeGrpcClient, set_node_timeouts_reply, uncordon_node_reply,
+        GetNodesRequest, ProbeRequest,
+    },
+    operations::{
+        blockdevice::traits::GetBlockDeviceInfo,
+        node::traits::{
+            CordonNodeInfo, DrainNodeInfo, NodeOperations, SetNodeTimeoutsInfo, UncordonNodeInfo,
+        },
     },
-    operations::node::traits::{GetBlockDeviceInfo, NodeOperations},
 };
 use common_lib::{
     mbus_api::{
         v0::{BlockDevices, Nodes},
         ReplyError, ResourceKind, TimeoutOptions,
     },
-    types::v0::message_bus::{Filter, MessageIdVs},
+    types::v0::message_bus::{Filter, MessageIdVs, Node},
 };
 use std::{convert::TryFrom, ops::Deref};
-use tonic::transport::Uri;
 
 /// RPC Node Client
 #[derive(Clone)]
@@ -32,7 +37,10 @@ impl Deref for NodeClient {
 
 impl NodeClient {
     /// creates a new base tonic endpoint with the timeout options and the address
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
         let client = Client::new(addr, opts, NodeGrpcClient::new).await;
         Self { inner: client }
     }
@@ -84,4 +92,68 @@ impl NodeOperations for NodeClient {
             None => Err(ReplyError::invalid_response(ResourceKind::Block)),
         }
     }
+    #[tracing::instrument(name = "NodeClient::cordon", level = "debug", skip(self), err)]
+    async fn cordon(
+        &self,
+        request: &dyn CordonNodeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::CordonNode);
+        let response = self.client().cordon_node(req).await?.into_inner();
+        match response.reply {
+            Some(cordon_node_reply) => match cordon_node_reply {
+                cordon_node_reply::Reply::Node(node) => Ok(Node::try_from(node)?),
+                cordon_node_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Node)),
+        }
+    }
+    #[tracing::instrument(name = "NodeClient::uncordon", level = "debug", skip(self), err)]
+    async fn uncordon(
+        &self,
+        request: &dyn UncordonNodeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::UncordonNode);
+        let response = self.client().uncordon_node(req).await?.into_inner();
+        match response.reply {
+            Some(uncordon_node_reply) => match uncordon_node_reply {
+                uncordon_node_reply::Reply::Node(node) => Ok(Node::try_from(node)?),
+                uncordon_node_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Node)),
+        }
+    }
+    #[tracing::instrument(name = "NodeClient::drain", level = "debug", skip(self), err)]
+    async fn drain(
+        &self,
+        request: &dyn DrainNodeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::DrainNode);
+        let response = self.client().drain_node(req).await?.into_inner();
+        match response.reply {
+            Some(drain_node_reply) => match drain_node_reply {
+                drain_node_reply::Reply::Node(node) => Ok(Node::try_from(node)?),
+                drain_node_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Node)),
+        }
+    }
+    #[tracing::instrument(name = "NodeClient::set_timeouts", level = "debug", skip(self), err)]
+    async fn set_timeouts(
+        &self,
+        request: &dyn SetNodeTimeoutsInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::SetNodeTimeouts);
+        let response = self.client().set_node_timeouts(req).await?.into_inner();
+        match response.reply {
+            Some(set_node_timeouts_reply) => match set_node_timeouts_reply {
+                set_node_timeouts_reply::Reply::Node(node) => Ok(Node::try_from(node)?),
+                set_node_timeouts_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Node)),
+        }
+    }
 }
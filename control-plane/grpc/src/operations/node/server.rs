@@ -2,9 +2,12 @@ use crate::{
     blockdevice::{get_block_devices_reply, GetBlockDevicesReply, GetBlockDevicesRequest},
     node,
     node::{
-        get_nodes_reply,
+        cordon_node_reply, drain_node_reply, get_nodes_reply,
         node_grpc_server::{NodeGrpc, NodeGrpcServer},
-        GetNodesReply, GetNodesRequest, ProbeRequest, ProbeResponse,
+        set_node_timeouts_reply, uncordon_node_reply, CordonNodeReply, CordonNodeRequest,
+        DrainNodeReply, DrainNodeRequest, GetNodesReply, GetNodesRequest, ProbeRequest,
+        ProbeResponse, SetNodeTimeoutsReply, SetNodeTimeoutsRequest, UncordonNodeReply,
+        UncordonNodeRequest,
     },
     operations::node::traits::NodeOperations,
 };
@@ -71,4 +74,60 @@ impl NodeGrpc for NodeServer {
             })),
         }
     }
+    async fn cordon_node(
+        &self,
+        request: tonic::Request<CordonNodeRequest>,
+    ) -> Result<tonic::Response<CordonNodeReply>, tonic::Status> {
+        let req: CordonNodeRequest = request.into_inner();
+        match self.service.cordon(&req, None).await {
+            Ok(node) => Ok(Response::new(CordonNodeReply {
+                reply: Some(cordon_node_reply::Reply::Node(node.into())),
+            })),
+            Err(err) => Ok(Response::new(CordonNodeReply {
+                reply: Some(cordon_node_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn uncordon_node(
+        &self,
+        request: tonic::Request<UncordonNodeRequest>,
+    ) -> Result<tonic::Response<UncordonNodeReply>, tonic::Status> {
+        let req: UncordonNodeRequest = request.into_inner();
+        match self.service.uncordon(&req, None).await {
+            Ok(node) => Ok(Response::new(UncordonNodeReply {
+                reply: Some(uncordon_node_reply::Reply::Node(node.into())),
+            })),
+            Err(err) => Ok(Response::new(UncordonNodeReply {
+                reply: Some(uncordon_node_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn drain_node(
+        &self,
+        request: tonic::Request<DrainNodeRequest>,
+    ) -> Result<tonic::Response<DrainNodeReply>, tonic::Status> {
+        let req: DrainNodeRequest = request.into_inner();
+        match self.service.drain(&req, None).await {
+            Ok(node) => Ok(Response::new(DrainNodeReply {
+                reply: Some(drain_node_reply::Reply::Node(node.into())),
+            })),
+            Err(err) => Ok(Response::new(DrainNodeReply {
+                reply: Some(drain_node_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn set_node_timeouts(
+        &self,
+        request: tonic::Request<SetNodeTimeoutsRequest>,
+    ) -> Result<tonic::Response<SetNodeTimeoutsReply>, tonic::Status> {
+        let req: SetNodeTimeoutsRequest = request.into_inner();
+        match self.service.set_timeouts(&req, None).await {
+            Ok(node) => Ok(Response::new(SetNodeTimeoutsReply {
+                reply: Some(set_node_timeouts_reply::Reply::Node(node.into())),
+            })),
+            Err(err) => Ok(Response::new(SetNodeTimeoutsReply {
+                reply: Some(set_node_timeouts_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
 }
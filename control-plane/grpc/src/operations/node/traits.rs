@@ -1,6 +1,11 @@
 use crate::{
-    blockdevice, blockdevice::GetBlockDevicesRequest, context::Context, node,
-    node::get_nodes_request,
+    context::Context,
+    node,
+    node::{
+        get_nodes_request, CordonNodeRequest, DrainNodeRequest, SetNodeTimeoutsRequest,
+        UncordonNodeRequest,
+    },
+    operations::blockdevice::traits::GetBlockDeviceInfo,
 };
 use common_lib::{
     mbus_api::{
@@ -9,13 +14,13 @@ use common_lib::{
     },
     types::v0::{
         message_bus::{
-            BlockDevice, Filesystem, Filter, GetBlockDevices, Node, NodeId, NodeState, NodeStatus,
-            Partition,
+            CordonNode, DrainNode, Filter, Node, NodeId, NodeState, NodeStatus, SetNodeTimeouts,
+            UncordonNode,
         },
-        store::node::NodeSpec,
+        store::node::{NodeDrainState, NodeSpec, NodeTimeouts},
     },
 };
-use std::convert::TryFrom;
+use std::{convert::TryFrom, time::Duration};
 
 /// Trait implemented by services which support node operations.
 #[tonic::async_trait]
@@ -30,17 +35,51 @@ pub trait NodeOperations: Send + Sync {
         get_blockdevice: &dyn GetBlockDeviceInfo,
         ctx: Option<Context>,
     ) -> Result<BlockDevices, ReplyError>;
+    /// Cordon the node with the given label, so that no new resources are placed on it
+    async fn cordon(
+        &self,
+        req: &dyn CordonNodeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError>;
+    /// Remove the cordon with the given label from the node
+    async fn uncordon(
+        &self,
+        req: &dyn UncordonNodeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError>;
+    /// Drain the node, moving all of its volume replicas to other nodes
+    async fn drain(
+        &self,
+        req: &dyn DrainNodeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError>;
+    /// Set the communication timeouts of the node, overriding the defaults
+    async fn set_timeouts(
+        &self,
+        req: &dyn SetNodeTimeoutsInfo,
+        ctx: Option<Context>,
+    ) -> Result<Node, ReplyError>;
 }
 
 impl TryFrom<node::Node> for Node {
     type Error = ReplyError;
     fn try_from(node_grpc_type: node::Node) -> Result<Self, Self::Error> {
         let node_spec = node_grpc_type.spec.map(|spec| {
-            NodeSpec::new(
+            let mut node_spec = NodeSpec::new(
                 spec.node_id.into(),
                 spec.endpoint,
                 spec.labels.unwrap_or_default().value,
-            )
+            );
+            for label in spec.cordon_labels {
+                node_spec.cordon(label);
+            }
+            node_spec.set_drain_state(
+                spec.drain_state
+                    .and_then(node::NodeDrainState::from_i32)
+                    .map(Into::into),
+            );
+            node_spec.set_timeouts(spec.timeouts.map(Into::into).unwrap_or_default());
+            node_spec
         });
         let node_state = match node_grpc_type.state {
             Some(state) => {
@@ -74,6 +113,12 @@ impl From<Node> for node::Node {
             labels: Some(crate::common::StringMapValue {
                 value: spec.labels().clone(),
             }),
+            cordon_labels: spec.cordon_labels().clone(),
+            drain_state: spec.drain_state().map(|state| {
+                let state: node::NodeDrainState = state.clone().into();
+                state as i32
+            }),
+            timeouts: Some(spec.timeouts().clone().into()),
         });
         let node_state = match node.state() {
             None => None,
@@ -133,6 +178,7 @@ impl From<node::NodeStatus> for NodeStatus {
             node::NodeStatus::Unknown => Self::Unknown,
             node::NodeStatus::Online => Self::Online,
             node::NodeStatus::Offline => Self::Offline,
+            node::NodeStatus::Suspect => Self::Suspect,
         }
     }
 }
@@ -143,155 +189,227 @@ impl From<NodeStatus> for node::NodeStatus {
             NodeStatus::Unknown => Self::Unknown,
             NodeStatus::Online => Self::Online,
             NodeStatus::Offline => Self::Offline,
+            NodeStatus::Suspect => Self::Suspect,
+        }
+    }
+}
+
+impl From<node::NodeDrainState> for NodeDrainState {
+    fn from(src: node::NodeDrainState) -> Self {
+        match src {
+            node::NodeDrainState::Draining => Self::Draining,
+            node::NodeDrainState::Drained => Self::Drained,
+        }
+    }
+}
+
+impl From<NodeDrainState> for node::NodeDrainState {
+    fn from(src: NodeDrainState) -> Self {
+        match src {
+            NodeDrainState::Draining => Self::Draining,
+            NodeDrainState::Drained => Self::Drained,
+        }
+    }
+}
+
+impl From<node::NodeTimeouts> for NodeTimeouts {
+    fn from(src: node::NodeTimeouts) -> Self {
+        Self::new(
+            src.connect.map(Duration::from_millis),
+            src.request.map(Duration::from_millis),
+        )
+    }
+}
+
+impl From<NodeTimeouts> for node::NodeTimeouts {
+    fn from(src: NodeTimeouts) -> Self {
+        Self {
+            connect: src.connect.map(|timeout| timeout.as_millis() as u64),
+            request: src.request.map(|timeout| timeout.as_millis() as u64),
         }
     }
 }
 
-/// GetBlockDeviceInfo trait for the getblockdevices
-/// operation
-pub trait GetBlockDeviceInfo: Send + Sync {
+/// Trait to be implemented for the CordonNode operation
+pub trait CordonNodeInfo: Send + Sync + std::fmt::Debug {
     /// id of the IoEngine instance
     fn node_id(&self) -> NodeId;
-    /// specifies whether to get all devices or only usable devices
-    fn all(&self) -> bool;
+    /// label of the cordon
+    fn label(&self) -> String;
 }
 
-impl GetBlockDeviceInfo for GetBlockDevices {
+impl CordonNodeInfo for CordonNode {
     fn node_id(&self) -> NodeId {
-        self.node.clone()
+        self.id.clone()
     }
 
-    fn all(&self) -> bool {
-        self.all
+    fn label(&self) -> String {
+        self.label.clone()
     }
 }
 
-impl GetBlockDeviceInfo for GetBlockDevicesRequest {
+impl CordonNodeInfo for CordonNodeRequest {
     fn node_id(&self) -> NodeId {
         self.node_id.clone().into()
     }
 
-    fn all(&self) -> bool {
-        self.all
+    fn label(&self) -> String {
+        self.label.clone()
     }
 }
 
-impl From<&dyn GetBlockDeviceInfo> for GetBlockDevices {
-    fn from(data: &dyn GetBlockDeviceInfo) -> Self {
+impl From<&dyn CordonNodeInfo> for CordonNode {
+    fn from(data: &dyn CordonNodeInfo) -> Self {
         Self {
-            node: data.node_id(),
-            all: data.all(),
+            id: data.node_id(),
+            label: data.label(),
         }
     }
 }
 
-impl From<&dyn GetBlockDeviceInfo> for GetBlockDevicesRequest {
-    fn from(data: &dyn GetBlockDeviceInfo) -> Self {
+impl From<&dyn CordonNodeInfo> for CordonNodeRequest {
+    fn from(data: &dyn CordonNodeInfo) -> Self {
         Self {
             node_id: data.node_id().to_string(),
-            all: data.all(),
+            label: data.label(),
         }
     }
 }
 
-impl From<BlockDevice> for blockdevice::BlockDevice {
-    fn from(bd: BlockDevice) -> Self {
+/// Trait to be implemented for the UncordonNode operation
+pub trait UncordonNodeInfo: Send + Sync + std::fmt::Debug {
+    /// id of the IoEngine instance
+    fn node_id(&self) -> NodeId;
+    /// label of the cordon
+    fn label(&self) -> String;
+}
+
+impl UncordonNodeInfo for UncordonNode {
+    fn node_id(&self) -> NodeId {
+        self.id.clone()
+    }
+
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+}
+
+impl UncordonNodeInfo for UncordonNodeRequest {
+    fn node_id(&self) -> NodeId {
+        self.node_id.clone().into()
+    }
+
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+}
+
+impl From<&dyn UncordonNodeInfo> for UncordonNode {
+    fn from(data: &dyn UncordonNodeInfo) -> Self {
         Self {
-            devname: bd.devname,
-            devtype: bd.devtype,
-            devmajor: bd.devmajor,
-            devminor: bd.devminor,
-            model: bd.model,
-            devpath: bd.devpath,
-            devlinks: bd.devlinks,
-            size: bd.size,
-            partition: Some(blockdevice::Partition {
-                parent: bd.partition.parent,
-                number: bd.partition.number,
-                name: bd.partition.name,
-                scheme: bd.partition.scheme,
-                typeid: bd.partition.typeid,
-                uuid: bd.partition.uuid,
-            }),
-            filesystem: Some(blockdevice::Filesystem {
-                fstype: bd.filesystem.fstype,
-                label: bd.filesystem.label,
-                uuid: bd.filesystem.uuid,
-                mountpoint: bd.filesystem.mountpoint,
-            }),
-            available: bd.available,
+            id: data.node_id(),
+            label: data.label(),
         }
     }
 }
 
-impl TryFrom<blockdevice::BlockDevice> for BlockDevice {
-    type Error = ReplyError;
-    fn try_from(bd: blockdevice::BlockDevice) -> Result<Self, Self::Error> {
-        Ok(Self {
-            devname: bd.devname,
-            devtype: bd.devtype,
-            devmajor: bd.devmajor,
-            devminor: bd.devminor,
-            model: bd.model,
-            devpath: bd.devpath,
-            devlinks: bd.devlinks,
-            size: bd.size,
-            partition: match bd.partition {
-                Some(partition) => Partition {
-                    parent: partition.parent,
-                    number: partition.number,
-                    name: partition.name,
-                    scheme: partition.scheme,
-                    typeid: partition.typeid,
-                    uuid: partition.uuid,
-                },
-                None => {
-                    return Err(ReplyError::invalid_argument(
-                        ResourceKind::Block,
-                        "bd.partition",
-                        "".to_string(),
-                    ))
-                }
-            },
-            filesystem: match bd.filesystem {
-                Some(filesystem) => Filesystem {
-                    fstype: filesystem.fstype,
-                    label: filesystem.label,
-                    uuid: filesystem.uuid,
-                    mountpoint: filesystem.mountpoint,
-                },
-                None => {
-                    return Err(ReplyError::invalid_argument(
-                        ResourceKind::Block,
-                        "bd.partition",
-                        "".to_string(),
-                    ))
-                }
-            },
-            available: bd.available,
-        })
-    }
-}
-
-impl TryFrom<blockdevice::BlockDevices> for BlockDevices {
-    type Error = ReplyError;
-    fn try_from(bds: blockdevice::BlockDevices) -> Result<Self, Self::Error> {
-        let mut blockdevices: Vec<BlockDevice> = vec![];
-        for bd in bds.blockdevices {
-            blockdevices.push(BlockDevice::try_from(bd)?)
+impl From<&dyn UncordonNodeInfo> for UncordonNodeRequest {
+    fn from(data: &dyn UncordonNodeInfo) -> Self {
+        Self {
+            node_id: data.node_id().to_string(),
+            label: data.label(),
         }
-        Ok(BlockDevices(blockdevices))
     }
 }
 
-impl From<BlockDevices> for blockdevice::BlockDevices {
-    fn from(blockdevices: BlockDevices) -> Self {
-        blockdevice::BlockDevices {
-            blockdevices: blockdevices
-                .into_inner()
-                .into_iter()
-                .map(|bd| bd.into())
-                .collect(),
+/// Trait to be implemented for the DrainNode operation
+pub trait DrainNodeInfo: Send + Sync + std::fmt::Debug {
+    /// id of the IoEngine instance
+    fn node_id(&self) -> NodeId;
+    /// label of the cordon
+    fn label(&self) -> String;
+}
+
+impl DrainNodeInfo for DrainNode {
+    fn node_id(&self) -> NodeId {
+        self.id.clone()
+    }
+
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+}
+
+impl DrainNodeInfo for DrainNodeRequest {
+    fn node_id(&self) -> NodeId {
+        self.node_id.clone().into()
+    }
+
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+}
+
+impl From<&dyn DrainNodeInfo> for DrainNode {
+    fn from(data: &dyn DrainNodeInfo) -> Self {
+        Self {
+            id: data.node_id(),
+            label: data.label(),
+        }
+    }
+}
+
+impl From<&dyn DrainNodeInfo> for DrainNodeRequest {
+    fn from(data: &dyn DrainNodeInfo) -> Self {
+        Self {
+            node_id: data.node_id().to_string(),
+            label: data.label(),
+        }
+    }
+}
+
+/// Trait to be implemented for the SetNodeTimeouts operation
+pub trait SetNodeTimeoutsInfo: Send + Sync + std::fmt::Debug {
+    /// id of the IoEngine instance
+    fn node_id(&self) -> NodeId;
+    /// the timeouts overrides
+    fn timeouts(&self) -> NodeTimeouts;
+}
+
+impl SetNodeTimeoutsInfo for SetNodeTimeouts {
+    fn node_id(&self) -> NodeId {
+        self.id.clone()
+    }
+
+    fn timeouts(&self) -> NodeTimeouts {
+        self.timeouts.clone()
+    }
+}
+
+impl SetNodeTimeoutsInfo for SetNodeTimeoutsRequest {
+    fn node_id(&self) -> NodeId {
+        self.node_id.clone().into()
+    }
+
+    fn timeouts(&self) -> NodeTimeouts {
+        self.timeouts.clone().map(Into::into).unwrap_or_default()
+    }
+}
+
+impl From<&dyn SetNodeTimeoutsInfo> for SetNodeTimeouts {
+    fn from(data: &dyn SetNodeTimeoutsInfo) -> Self {
+        Self {
+            id: data.node_id(),
+            timeouts: data.timeouts(),
+        }
+    }
+}
+
+impl From<&dyn SetNodeTimeoutsInfo> for SetNodeTimeoutsRequest {
+    fn from(data: &dyn SetNodeTimeoutsInfo) -> Self {
+        Self {
+            node_id: data.node_id().to_string(),
+            timeouts: Some(data.timeouts().into()),
         }
     }
 }
@@ -1,10 +1,14 @@
 use crate::{
-    common::{NodeFilter, NodePoolFilter, PoolFilter},
-    context::{Client, Context, TracedChannel},
-    operations::pool::traits::{CreatePoolInfo, DestroyPoolInfo, PoolOperations},
+    common::{NodeFilter, NodePoolFilter, PoolFilter, PoolLabelFilter},
+    context::{Client, Context, Endpoints, TracedChannel},
+    operations::pool::traits::{
+        CreatePoolInfo, DestroyPoolInfo, ImportPoolInfo, PoolOperations, ReconcilePoolInfo,
+        SetPoolMaintenanceInfo,
+    },
     pool::{
-        create_pool_reply, get_pools_reply, get_pools_request, pool_grpc_client::PoolGrpcClient,
-        GetPoolsRequest,
+        create_pool_reply, get_pools_reply, get_pools_request, import_pool_reply,
+        pool_grpc_client::PoolGrpcClient, reconcile_pool_reply, set_pool_maintenance_reply,
+        GetPoolsRequest, ProbeRequest,
     },
 };
 use common_lib::{
@@ -12,7 +16,6 @@ use common_lib::{
     types::v0::message_bus::{Filter, MessageIdVs, Pool},
 };
 use std::{convert::TryFrom, ops::Deref};
-use tonic::transport::Uri;
 
 /// RPC Pool Client
 #[derive(Clone)]
@@ -28,7 +31,10 @@ impl Deref for PoolClient {
 
 impl PoolClient {
     /// creates a new base tonic endpoint with the timeout options and the address
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
         let client = Client::new(addr, opts, PoolGrpcClient::new).await;
         Self { inner: client }
     }
@@ -55,6 +61,23 @@ impl PoolOperations for PoolClient {
         }
     }
 
+    #[tracing::instrument(name = "PoolClient::import", level = "debug", skip(self), err)]
+    async fn import(
+        &self,
+        request: &dyn ImportPoolInfo,
+        ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::ImportPool);
+        let response = self.client().import_pool(req).await?.into_inner();
+        match response.reply {
+            Some(import_pool_reply) => match import_pool_reply {
+                import_pool_reply::Reply::Pool(pool) => Ok(Pool::try_from(pool)?),
+                import_pool_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Pool)),
+        }
+    }
+
     #[tracing::instrument(name = "PoolClient::destroy", level = "debug", skip(self), err)]
     async fn destroy(
         &self,
@@ -88,6 +111,11 @@ impl PoolOperations for PoolClient {
                     pool_id: pool_id.into(),
                 })),
             },
+            Filter::PoolLabel(labels) => GetPoolsRequest {
+                filter: Some(get_pools_request::Filter::PoolLabel(PoolLabelFilter {
+                    labels,
+                })),
+            },
             _ => GetPoolsRequest { filter: None },
         };
         let req = self.request(req, ctx, MessageIdVs::GetPools);
@@ -100,4 +128,46 @@ impl PoolOperations for PoolClient {
             None => Err(ReplyError::invalid_response(ResourceKind::Pool)),
         }
     }
+
+    #[tracing::instrument(name = "PoolClient::reconcile", level = "debug", skip(self), err)]
+    async fn reconcile(
+        &self,
+        request: &dyn ReconcilePoolInfo,
+        ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::ReconcilePool);
+        let response = self.client().reconcile_pool(req).await?.into_inner();
+        match response.reply {
+            Some(reconcile_pool_reply) => match reconcile_pool_reply {
+                reconcile_pool_reply::Reply::Pool(pool) => Ok(Pool::try_from(pool)?),
+                reconcile_pool_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Pool)),
+        }
+    }
+
+    #[tracing::instrument(name = "PoolClient::set_maintenance", level = "debug", skip(self), err)]
+    async fn set_maintenance(
+        &self,
+        request: &dyn SetPoolMaintenanceInfo,
+        ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::SetPoolMaintenance);
+        let response = self.client().set_pool_maintenance(req).await?.into_inner();
+        match response.reply {
+            Some(set_pool_maintenance_reply) => match set_pool_maintenance_reply {
+                set_pool_maintenance_reply::Reply::Pool(pool) => Ok(Pool::try_from(pool)?),
+                set_pool_maintenance_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Pool)),
+        }
+    }
+
+    #[tracing::instrument(name = "PoolClient::probe", level = "debug", skip(self))]
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        match self.client().probe(ProbeRequest {}).await {
+            Ok(resp) => Ok(resp.into_inner().ready),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
@@ -100,7 +100,10 @@ mod test {
             context::Context,
             operations::pool::{
                 test::TimeoutTester,
-                traits::{CreatePoolInfo, DestroyPoolInfo, PoolOperations},
+                traits::{
+                    CreatePoolInfo, DestroyPoolInfo, ImportPoolInfo, PoolOperations,
+                    ReconcilePoolInfo, SetPoolMaintenanceInfo,
+                },
             },
         };
         use common_lib::{
@@ -119,6 +122,13 @@ mod test {
             ) -> Result<Pool, ReplyError> {
                 todo!()
             }
+            async fn import(
+                &self,
+                _pool: &dyn ImportPoolInfo,
+                _ctx: Option<Context>,
+            ) -> Result<Pool, ReplyError> {
+                todo!()
+            }
             async fn destroy(
                 &self,
                 _pool: &dyn DestroyPoolInfo,
@@ -136,6 +146,23 @@ mod test {
                 tester.complete();
                 Ok(Pools(vec![]))
             }
+            async fn reconcile(
+                &self,
+                _pool: &dyn ReconcilePoolInfo,
+                _ctx: Option<Context>,
+            ) -> Result<Pool, ReplyError> {
+                todo!()
+            }
+            async fn set_maintenance(
+                &self,
+                _pool: &dyn SetPoolMaintenanceInfo,
+                _ctx: Option<Context>,
+            ) -> Result<Pool, ReplyError> {
+                todo!()
+            }
+            async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+                todo!()
+            }
         }
     }
 }
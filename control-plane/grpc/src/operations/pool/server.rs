@@ -1,11 +1,14 @@
 use crate::{
+    misc::traits::ValidateRequestTypes,
     operations::pool::traits::PoolOperations,
     pool,
     pool::{
-        create_pool_reply, get_pools_reply,
+        create_pool_reply, get_pools_reply, import_pool_reply,
         pool_grpc_server::{PoolGrpc, PoolGrpcServer},
-        CreatePoolReply, CreatePoolRequest, DestroyPoolReply, DestroyPoolRequest, GetPoolsReply,
-        GetPoolsRequest,
+        reconcile_pool_reply, set_pool_maintenance_reply, CreatePoolReply, CreatePoolRequest,
+        DestroyPoolReply, DestroyPoolRequest, GetPoolsReply, GetPoolsRequest, ImportPoolReply,
+        ImportPoolRequest, ProbeRequest, ProbeResponse, ReconcilePoolReply, ReconcilePoolRequest,
+        SetPoolMaintenanceReply, SetPoolMaintenanceRequest,
     },
 };
 use std::sync::Arc;
@@ -46,6 +49,21 @@ impl PoolGrpc for PoolServer {
         }
     }
 
+    async fn import_pool(
+        &self,
+        request: Request<ImportPoolRequest>,
+    ) -> Result<tonic::Response<ImportPoolReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.import(&req, None).await {
+            Ok(pool) => Ok(Response::new(ImportPoolReply {
+                reply: Some(import_pool_reply::Reply::Pool(pool.into())),
+            })),
+            Err(err) => Ok(Response::new(ImportPoolReply {
+                reply: Some(import_pool_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+
     async fn destroy_pool(
         &self,
         request: Request<DestroyPoolRequest>,
@@ -74,4 +92,43 @@ impl PoolGrpc for PoolServer {
             })),
         }
     }
+
+    async fn reconcile_pool(
+        &self,
+        request: Request<ReconcilePoolRequest>,
+    ) -> Result<tonic::Response<ReconcilePoolReply>, tonic::Status> {
+        let req: ReconcilePoolRequest = request.into_inner();
+        match self.service.reconcile(&req, None).await {
+            Ok(pool) => Ok(Response::new(ReconcilePoolReply {
+                reply: Some(reconcile_pool_reply::Reply::Pool(pool.into())),
+            })),
+            Err(err) => Ok(Response::new(ReconcilePoolReply {
+                reply: Some(reconcile_pool_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+
+    async fn set_pool_maintenance(
+        &self,
+        request: Request<SetPoolMaintenanceRequest>,
+    ) -> Result<tonic::Response<SetPoolMaintenanceReply>, tonic::Status> {
+        let req: SetPoolMaintenanceRequest = request.into_inner();
+        match self.service.set_maintenance(&req, None).await {
+            Ok(pool) => Ok(Response::new(SetPoolMaintenanceReply {
+                reply: Some(set_pool_maintenance_reply::Reply::Pool(pool.into())),
+            })),
+            Err(err) => Ok(Response::new(SetPoolMaintenanceReply {
+                reply: Some(set_pool_maintenance_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn probe(
+        &self,
+        _request: tonic::Request<ProbeRequest>,
+    ) -> Result<tonic::Response<ProbeResponse>, tonic::Status> {
+        match self.service.probe(None).await {
+            Ok(resp) => Ok(Response::new(ProbeResponse { ready: resp })),
+            Err(_) => Ok(Response::new(ProbeResponse { ready: false })),
+        }
+    }
 }
@@ -1,15 +1,20 @@
 use crate::{
     common,
     context::Context,
+    misc::traits::{StringValue, ValidateRequestTypes},
     pool,
-    pool::{get_pools_request, CreatePoolRequest, DestroyPoolRequest},
+    pool::{
+        get_pools_request, CreatePoolRequest, DestroyPoolRequest, ImportPoolRequest,
+        ReconcilePoolRequest, SetPoolMaintenanceRequest,
+    },
 };
 use common_lib::{
     mbus_api::{v0::Pools, ReplyError, ResourceKind},
     types::v0::{
         message_bus,
         message_bus::{
-            CreatePool, DestroyPool, Filter, NodeId, Pool, PoolDeviceUri, PoolId, PoolState,
+            CapacityForecast, CreatePool, DestroyPool, Filter, ImportPool, NodeId, Pool,
+            PoolDeviceUri, PoolId, PoolState, PoolUuid, ReconcilePool, SetPoolMaintenance,
         },
         store::pool::{PoolLabel, PoolSpec, PoolSpecStatus},
     },
@@ -25,6 +30,12 @@ pub trait PoolOperations: Send + Sync {
         pool: &dyn CreatePoolInfo,
         ctx: Option<Context>,
     ) -> Result<Pool, ReplyError>;
+    /// Import a pre-existing pool by its uuid
+    async fn import(
+        &self,
+        pool: &dyn ImportPoolInfo,
+        ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError>;
     /// Destroy a pool
     async fn destroy(
         &self,
@@ -33,6 +44,20 @@ pub trait PoolOperations: Send + Sync {
     ) -> Result<(), ReplyError>;
     /// Get pools based on the filters
     async fn get(&self, filter: Filter, ctx: Option<Context>) -> Result<Pools, ReplyError>;
+    /// Force an immediate re-check of the pool state
+    async fn reconcile(
+        &self,
+        pool: &dyn ReconcilePoolInfo,
+        ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError>;
+    /// Set or clear the maintenance flag of a pool
+    async fn set_maintenance(
+        &self,
+        pool: &dyn SetPoolMaintenanceInfo,
+        ctx: Option<Context>,
+    ) -> Result<Pool, ReplyError>;
+    /// Liveness probe for pool service
+    async fn probe(&self, ctx: Option<Context>) -> Result<bool, ReplyError>;
 }
 
 impl TryFrom<pool::PoolDefinition> for PoolSpec {
@@ -76,6 +101,14 @@ impl TryFrom<pool::PoolDefinition> for PoolSpec {
                 Some(labels) => Some(labels.value),
                 None => None,
             },
+            maintenance: pool_spec.maintenance,
+            reserved_capacity_percent: pool_spec
+                .reserved_capacity_percent
+                .map(|percent| u8::try_from(percent).unwrap_or(u8::MAX)),
+            uuid: match pool_meta.uuid {
+                Some(uuid) => Some(PoolUuid::try_from(StringValue(Some(uuid)))?),
+                None => None,
+            },
             sequencer: Default::default(),
             operation: None,
         })
@@ -109,8 +142,12 @@ impl TryFrom<pool::Pool> for Pool {
             None => None,
             Some(pool_definition) => Some(PoolSpec::try_from(pool_definition)?),
         };
+        let forecast = match pool.forecast {
+            None => None,
+            Some(forecast) => Some(CapacityForecast::try_from(forecast)?),
+        };
         match Pool::try_new(pool_spec, pool_state) {
-            Some(pool) => Ok(pool),
+            Some(pool) => Ok(pool.with_forecast(forecast)),
             None => Err(ReplyError::missing_argument(
                 ResourceKind::Pool,
                 "pool.spec and pool.state",
@@ -130,9 +167,11 @@ impl From<PoolSpec> for pool::PoolDefinition {
                 labels: pool_spec
                     .labels
                     .map(|labels| crate::common::StringMapValue { value: labels }),
+                maintenance: pool_spec.maintenance,
+                reserved_capacity_percent: pool_spec.reserved_capacity_percent.map(u32::from),
             }),
             metadata: Some(pool::Metadata {
-                uuid: None,
+                uuid: pool_spec.uuid.map(String::from),
                 spec_status: spec_status as i32,
             }),
         }
@@ -156,10 +195,54 @@ impl From<Pool> for pool::Pool {
         pool::Pool {
             definition: pool_definition,
             state: pool_state,
+            forecast: pool.forecast().map(Into::into),
+        }
+    }
+}
+
+impl From<CapacityForecast> for pool::CapacityForecast {
+    fn from(forecast: CapacityForecast) -> Self {
+        pool::CapacityForecast {
+            capacity: forecast.capacity,
+            used: forecast.used,
+            growth_rate: forecast.growth_rate,
+            samples: forecast.samples,
+            timestamp: Some(forecast.timestamp.into()),
+            exhaustion: forecast.exhaustion.map(Into::into),
         }
     }
 }
 
+impl TryFrom<pool::CapacityForecast> for CapacityForecast {
+    type Error = ReplyError;
+    fn try_from(forecast: pool::CapacityForecast) -> Result<Self, Self::Error> {
+        let time = |timestamp: prost_types::Timestamp, arg_name: &str| {
+            std::time::SystemTime::try_from(timestamp).map_err(|error| {
+                ReplyError::invalid_argument(ResourceKind::Pool, arg_name, format!("{:?}", error))
+            })
+        };
+        Ok(CapacityForecast {
+            capacity: forecast.capacity,
+            used: forecast.used,
+            growth_rate: forecast.growth_rate,
+            samples: forecast.samples,
+            timestamp: match forecast.timestamp {
+                Some(timestamp) => time(timestamp, "pool.forecast.timestamp")?,
+                None => {
+                    return Err(ReplyError::missing_argument(
+                        ResourceKind::Pool,
+                        "pool.forecast.timestamp",
+                    ))
+                }
+            },
+            exhaustion: match forecast.exhaustion {
+                Some(timestamp) => Some(time(timestamp, "pool.forecast.exhaustion")?),
+                None => None,
+            },
+        })
+    }
+}
+
 impl TryFrom<pool::Pools> for Pools {
     type Error = ReplyError;
     fn try_from(grpc_pool_type: pool::Pools) -> Result<Self, Self::Error> {
@@ -196,6 +279,9 @@ impl From<get_pools_request::Filter> for Filter {
             get_pools_request::Filter::Pool(pool_filter) => {
                 Filter::Pool(pool_filter.pool_id.into())
             }
+            get_pools_request::Filter::PoolLabel(pool_label_filter) => {
+                Filter::PoolLabel(pool_label_filter.labels)
+            }
         }
     }
 }
@@ -211,6 +297,23 @@ pub trait CreatePoolInfo: Send + Sync + std::fmt::Debug {
     fn disks(&self) -> Vec<PoolDeviceUri>;
     /// Labels to be set on the pool
     fn labels(&self) -> Option<PoolLabel>;
+    /// Percentage of the pool capacity kept free when placing new replicas
+    fn reserved_capacity_percent(&self) -> Option<u8>;
+}
+
+/// ImportPoolInfo trait for the pool import to be implemented by entities which want to avail
+/// this operation
+pub trait ImportPoolInfo: Send + Sync + std::fmt::Debug {
+    /// Id of the pool
+    fn pool_id(&self) -> PoolId;
+    /// Uuid of the pool
+    fn pool_uuid(&self) -> PoolUuid;
+    /// Id of the IoEngine instance
+    fn node_id(&self) -> NodeId;
+    /// Disk device paths or URIs claimed by the pool
+    fn disks(&self) -> Vec<PoolDeviceUri>;
+    /// Labels to be set on the pool
+    fn labels(&self) -> Option<PoolLabel>;
 }
 
 /// DestroyPoolInfo trait for the pool deletion to be implemented by entities which want to avail
@@ -222,6 +325,22 @@ pub trait DestroyPoolInfo: Sync + Send + std::fmt::Debug {
     fn node_id(&self) -> NodeId;
 }
 
+/// ReconcilePoolInfo trait for the pool reconciliation to be implemented by entities which want
+/// to avail this operation
+pub trait ReconcilePoolInfo: Sync + Send + std::fmt::Debug {
+    /// Id of the pool
+    fn pool_id(&self) -> PoolId;
+}
+
+/// SetPoolMaintenanceInfo trait for setting the pool maintenance flag to be implemented by
+/// entities which want to avail this operation
+pub trait SetPoolMaintenanceInfo: Sync + Send + std::fmt::Debug {
+    /// Id of the pool
+    fn pool_id(&self) -> PoolId;
+    /// Whether the pool is under maintenance
+    fn maintenance(&self) -> bool;
+}
+
 impl CreatePoolInfo for CreatePool {
     fn pool_id(&self) -> PoolId {
         self.id.clone()
@@ -238,6 +357,10 @@ impl CreatePoolInfo for CreatePool {
     fn labels(&self) -> Option<PoolLabel> {
         self.labels.clone()
     }
+
+    fn reserved_capacity_percent(&self) -> Option<u8> {
+        self.reserved_capacity_percent
+    }
 }
 
 impl CreatePoolInfo for CreatePoolRequest {
@@ -259,6 +382,12 @@ impl CreatePoolInfo for CreatePoolRequest {
             Some(labels) => Some(labels.value),
         }
     }
+
+    fn reserved_capacity_percent(&self) -> Option<u8> {
+        // out of range percentages are rejected by the core agent
+        self.reserved_capacity_percent
+            .map(|percent| u8::try_from(percent).unwrap_or(u8::MAX))
+    }
 }
 
 impl From<&dyn CreatePoolInfo> for CreatePoolRequest {
@@ -270,6 +399,7 @@ impl From<&dyn CreatePoolInfo> for CreatePoolRequest {
             labels: data
                 .labels()
                 .map(|labels| crate::common::StringMapValue { value: labels }),
+            reserved_capacity_percent: data.reserved_capacity_percent().map(u32::from),
         }
     }
 }
@@ -281,6 +411,94 @@ impl From<&dyn CreatePoolInfo> for CreatePool {
             id: data.pool_id(),
             disks: data.disks(),
             labels: data.labels(),
+            reserved_capacity_percent: data.reserved_capacity_percent(),
+        }
+    }
+}
+
+impl ImportPoolInfo for ImportPool {
+    fn pool_id(&self) -> PoolId {
+        self.id.clone()
+    }
+
+    fn pool_uuid(&self) -> PoolUuid {
+        self.uuid.clone()
+    }
+
+    fn node_id(&self) -> NodeId {
+        self.node.clone()
+    }
+
+    fn disks(&self) -> Vec<PoolDeviceUri> {
+        self.disks.clone()
+    }
+
+    fn labels(&self) -> Option<PoolLabel> {
+        self.labels.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to ImportPoolRequest type
+#[derive(Debug)]
+pub struct ValidatedImportPoolRequest {
+    inner: ImportPoolRequest,
+    uuid: PoolUuid,
+}
+
+impl ImportPoolInfo for ValidatedImportPoolRequest {
+    fn pool_id(&self) -> PoolId {
+        self.inner.pool_id.clone().into()
+    }
+
+    fn pool_uuid(&self) -> PoolUuid {
+        self.uuid.clone()
+    }
+
+    fn node_id(&self) -> NodeId {
+        self.inner.node_id.clone().into()
+    }
+
+    fn disks(&self) -> Vec<PoolDeviceUri> {
+        self.inner.disks.iter().map(|disk| disk.into()).collect()
+    }
+
+    fn labels(&self) -> Option<PoolLabel> {
+        self.inner.labels.clone().map(|labels| labels.value)
+    }
+}
+
+impl ValidateRequestTypes for ImportPoolRequest {
+    type Validated = ValidatedImportPoolRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedImportPoolRequest {
+            uuid: PoolUuid::try_from(StringValue(self.pool_uuid.clone()))?,
+            inner: self,
+        })
+    }
+}
+
+impl From<&dyn ImportPoolInfo> for ImportPoolRequest {
+    fn from(data: &dyn ImportPoolInfo) -> Self {
+        Self {
+            pool_id: data.pool_id().to_string(),
+            node_id: data.node_id().to_string(),
+            pool_uuid: Some(data.pool_uuid().to_string()),
+            disks: data.disks().iter().map(|disk| disk.to_string()).collect(),
+            labels: data
+                .labels()
+                .map(|labels| crate::common::StringMapValue { value: labels }),
+        }
+    }
+}
+
+impl From<&dyn ImportPoolInfo> for ImportPool {
+    fn from(data: &dyn ImportPoolInfo) -> Self {
+        Self {
+            node: data.node_id(),
+            id: data.pool_id(),
+            uuid: data.pool_uuid(),
+            disks: data.disks(),
+            labels: data.labels(),
         }
     }
 }
@@ -323,6 +541,68 @@ impl From<&dyn DestroyPoolInfo> for DestroyPool {
     }
 }
 
+impl ReconcilePoolInfo for ReconcilePool {
+    fn pool_id(&self) -> PoolId {
+        self.id.clone()
+    }
+}
+
+impl ReconcilePoolInfo for ReconcilePoolRequest {
+    fn pool_id(&self) -> PoolId {
+        self.pool_id.clone().into()
+    }
+}
+
+impl From<&dyn ReconcilePoolInfo> for ReconcilePoolRequest {
+    fn from(data: &dyn ReconcilePoolInfo) -> Self {
+        Self {
+            pool_id: data.pool_id().to_string(),
+        }
+    }
+}
+
+impl From<&dyn ReconcilePoolInfo> for ReconcilePool {
+    fn from(data: &dyn ReconcilePoolInfo) -> Self {
+        Self { id: data.pool_id() }
+    }
+}
+
+impl SetPoolMaintenanceInfo for SetPoolMaintenance {
+    fn pool_id(&self) -> PoolId {
+        self.id.clone()
+    }
+    fn maintenance(&self) -> bool {
+        self.maintenance
+    }
+}
+
+impl SetPoolMaintenanceInfo for SetPoolMaintenanceRequest {
+    fn pool_id(&self) -> PoolId {
+        self.pool_id.clone().into()
+    }
+    fn maintenance(&self) -> bool {
+        self.maintenance
+    }
+}
+
+impl From<&dyn SetPoolMaintenanceInfo> for SetPoolMaintenanceRequest {
+    fn from(data: &dyn SetPoolMaintenanceInfo) -> Self {
+        Self {
+            pool_id: data.pool_id().to_string(),
+            maintenance: data.maintenance(),
+        }
+    }
+}
+
+impl From<&dyn SetPoolMaintenanceInfo> for SetPoolMaintenance {
+    fn from(data: &dyn SetPoolMaintenanceInfo) -> Self {
+        Self {
+            id: data.pool_id(),
+            maintenance: data.maintenance(),
+        }
+    }
+}
+
 impl From<pool::PoolStatus> for message_bus::PoolStatus {
     fn from(src: pool::PoolStatus) -> Self {
         match src {
@@ -366,3 +646,24 @@ impl From<PoolSpecStatus> for common::SpecStatus {
         }
     }
 }
+
+impl TryFrom<StringValue> for PoolUuid {
+    type Error = ReplyError;
+
+    fn try_from(value: StringValue) -> Result<Self, Self::Error> {
+        match value.0 {
+            Some(uuid) => match PoolUuid::try_from(uuid) {
+                Ok(pool_uuid) => Ok(pool_uuid),
+                Err(err) => Err(ReplyError::invalid_argument(
+                    ResourceKind::Pool,
+                    "pool_uuid",
+                    err.to_string(),
+                )),
+            },
+            None => Err(ReplyError::missing_argument(
+                ResourceKind::Pool,
+                "pool_uuid",
+            )),
+        }
+    }
+}
@@ -1,14 +1,27 @@
 use crate::{
-    context::{Client, Context, TracedChannel},
-    operations::registry::traits::{GetSpecsInfo, RegistryOperations},
-    registry::{get_specs_reply, registry_grpc_client::RegistryGrpcClient},
+    common::{NexusFilter, NodeFilter, PoolFilter, ReplicaFilter, VolumeFilter},
+    context::{Client, Context, Endpoints, TracedChannel},
+    operations::registry::traits::{
+        CheckOrphanedReplicasInfo, GetSpecsInfo, RegistryOperations, SetRebuildLimitInfo,
+        SpecsStream,
+    },
+    registry::{
+        check_orphaned_replicas_reply, get_events_reply, get_events_request,
+        get_filtered_specs_request, get_operations_reply, get_specs_reply, rebuild_limit_reply,
+        registry_grpc_client::RegistryGrpcClient, ClearOperationRequest, GetEventsRequest,
+        GetFilteredSpecsRequest, GetOperationsRequest, GetRebuildLimitRequest,
+        ListSpecsStreamRequest, ProbeRequest, ReconcileResourceRequest,
+    },
 };
 use common_lib::{
-    mbus_api::{ReplyError, ResourceKind, TimeoutOptions},
-    types::v0::message_bus::{MessageIdVs, Specs},
+    mbus_api::{v0::Events, ReplyError, ResourceKind, TimeoutOptions},
+    types::v0::message_bus::{
+        ClearOperation, Filter, GetOperations, MessageIdVs, OrphanedReplicas, PendingOperations,
+        RebuildLimit, ReconcileResource, Specs,
+    },
 };
+use futures::StreamExt;
 use std::{convert::TryFrom, ops::Deref};
-use tonic::transport::Uri;
 
 /// RPC Registry Client
 #[derive(Clone)]
@@ -24,7 +37,10 @@ impl Deref for RegistryClient {
 
 impl RegistryClient {
     /// creates a new base tonic endpoint with the timeout options and the address
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
         let client = Client::new(addr, opts, RegistryGrpcClient::new).await;
         Self { inner: client }
     }
@@ -48,4 +64,211 @@ impl RegistryOperations for RegistryClient {
             None => Err(ReplyError::invalid_response(ResourceKind::Spec)),
         }
     }
+
+    async fn get_filtered_specs(
+        &self,
+        filter: Filter,
+        ctx: Option<Context>,
+    ) -> Result<Specs, ReplyError> {
+        let filter = match filter {
+            Filter::Node(id) => {
+                get_filtered_specs_request::Filter::Node(NodeFilter { node_id: id.into() })
+            }
+            Filter::Pool(id) => {
+                get_filtered_specs_request::Filter::Pool(PoolFilter { pool_id: id.into() })
+            }
+            Filter::Volume(id) => get_filtered_specs_request::Filter::Volume(VolumeFilter {
+                volume_id: id.to_string(),
+            }),
+            _ => {
+                return Err(ReplyError::invalid_argument(
+                    ResourceKind::Spec,
+                    "filter",
+                    format!("{:?}", filter),
+                ))
+            }
+        };
+        let req = GetFilteredSpecsRequest {
+            filter: Some(filter),
+        };
+        let req = self.request(req, ctx, MessageIdVs::GetSpecs);
+        let response = self.client().get_filtered_specs(req).await?.into_inner();
+        match response.reply {
+            Some(get_specs_reply) => match get_specs_reply {
+                get_specs_reply::Reply::Specs(specs) => Ok(Specs::try_from(specs)?),
+                get_specs_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Spec)),
+        }
+    }
+
+    async fn list_specs_stream(
+        &self,
+        chunk_size: Option<usize>,
+        ctx: Option<Context>,
+    ) -> Result<SpecsStream, ReplyError> {
+        let req = ListSpecsStreamRequest {
+            chunk_size: chunk_size.unwrap_or_default() as u32,
+        };
+        let req = self.request(req, ctx, MessageIdVs::GetSpecs);
+        let stream = self.client().list_specs_stream(req).await?.into_inner();
+        Ok(Box::pin(stream.map(|response| match response?.reply {
+            Some(get_specs_reply) => match get_specs_reply {
+                get_specs_reply::Reply::Specs(specs) => Ok(Specs::try_from(specs)?),
+                get_specs_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Spec)),
+        })))
+    }
+
+    async fn get_rebuild_limit(&self, ctx: Option<Context>) -> Result<RebuildLimit, ReplyError> {
+        let req = self.request(GetRebuildLimitRequest {}, ctx, MessageIdVs::GetRebuildLimit);
+        let response = self.client().get_rebuild_limit(req).await?.into_inner();
+        match response.reply {
+            Some(rebuild_limit_reply) => match rebuild_limit_reply {
+                rebuild_limit_reply::Reply::Limit(limit) => Ok(limit.into()),
+                rebuild_limit_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Nexus)),
+        }
+    }
+
+    async fn set_rebuild_limit(
+        &self,
+        request: &dyn SetRebuildLimitInfo,
+        ctx: Option<Context>,
+    ) -> Result<RebuildLimit, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::SetRebuildLimit);
+        let response = self.client().set_rebuild_limit(req).await?.into_inner();
+        match response.reply {
+            Some(rebuild_limit_reply) => match rebuild_limit_reply {
+                rebuild_limit_reply::Reply::Limit(limit) => Ok(limit.into()),
+                rebuild_limit_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Nexus)),
+        }
+    }
+
+    async fn check_orphaned_replicas(
+        &self,
+        request: &dyn CheckOrphanedReplicasInfo,
+        ctx: Option<Context>,
+    ) -> Result<OrphanedReplicas, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::CheckOrphanedReplicas);
+        let response = self
+            .client()
+            .check_orphaned_replicas(req)
+            .await?
+            .into_inner();
+        match response.reply {
+            Some(orphaned_replicas_reply) => match orphaned_replicas_reply {
+                check_orphaned_replicas_reply::Reply::Replicas(replicas) => {
+                    OrphanedReplicas::try_from(replicas)
+                }
+                check_orphaned_replicas_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Replica)),
+        }
+    }
+
+    async fn get_events(&self, filter: Filter, ctx: Option<Context>) -> Result<Events, ReplyError> {
+        let filter = match filter {
+            Filter::None => None,
+            Filter::Node(id) => Some(get_events_request::Filter::Node(NodeFilter {
+                node_id: id.into(),
+            })),
+            Filter::Pool(id) => Some(get_events_request::Filter::Pool(PoolFilter {
+                pool_id: id.into(),
+            })),
+            Filter::Replica(id) => Some(get_events_request::Filter::Replica(ReplicaFilter {
+                replica_id: id.to_string(),
+            })),
+            Filter::Nexus(id) => Some(get_events_request::Filter::Nexus(NexusFilter {
+                nexus_id: id.to_string(),
+            })),
+            Filter::Volume(id) => Some(get_events_request::Filter::Volume(VolumeFilter {
+                volume_id: id.to_string(),
+            })),
+            _ => {
+                return Err(ReplyError::invalid_argument(
+                    ResourceKind::Unknown,
+                    "filter",
+                    format!("{:?}", filter),
+                ))
+            }
+        };
+        let req = self.request(GetEventsRequest { filter }, ctx, MessageIdVs::GetEvents);
+        let response = self.client().get_events(req).await?.into_inner();
+        match response.reply {
+            Some(get_events_reply) => match get_events_reply {
+                get_events_reply::Reply::Events(events) => Events::try_from(events),
+                get_events_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Unknown)),
+        }
+    }
+
+    async fn get_operations(
+        &self,
+        request: &GetOperations,
+        ctx: Option<Context>,
+    ) -> Result<PendingOperations, ReplyError> {
+        let req = self.request(
+            GetOperationsRequest::from(request),
+            ctx,
+            MessageIdVs::GetOperations,
+        );
+        let response = self.client().get_operations(req).await?.into_inner();
+        match response.reply {
+            Some(get_operations_reply) => match get_operations_reply {
+                get_operations_reply::Reply::Operations(operations) => {
+                    PendingOperations::try_from(operations)
+                }
+                get_operations_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Spec)),
+        }
+    }
+
+    async fn clear_operation(
+        &self,
+        request: &ClearOperation,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        let req = self.request(
+            ClearOperationRequest::from(request),
+            ctx,
+            MessageIdVs::ClearOperation,
+        );
+        let response = self.client().clear_operation(req).await?.into_inner();
+        match response.error {
+            None => Ok(()),
+            Some(err) => Err(err.into()),
+        }
+    }
+
+    async fn reconcile_resource(
+        &self,
+        request: &ReconcileResource,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        let req = self.request(
+            ReconcileResourceRequest::from(request),
+            ctx,
+            MessageIdVs::ReconcileResource,
+        );
+        let response = self.client().reconcile_resource(req).await?.into_inner();
+        match response.error {
+            None => Ok(()),
+            Some(err) => Err(err.into()),
+        }
+    }
+
+    #[tracing::instrument(name = "RegistryClient::probe", level = "debug", skip(self))]
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        match self.client().probe(ProbeRequest {}).await {
+            Ok(resp) => Ok(resp.into_inner().ready),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
@@ -1,12 +1,22 @@
 use crate::{
     operations::registry::traits::RegistryOperations,
     registry::{
-        get_specs_reply,
+        check_orphaned_replicas_reply, get_events_reply, get_operations_reply, get_specs_reply,
+        rebuild_limit_reply,
         registry_grpc_server::{RegistryGrpc, RegistryGrpcServer},
-        GetSpecsReply, GetSpecsRequest,
+        CheckOrphanedReplicasReply, CheckOrphanedReplicasRequest, ClearOperationReply,
+        ClearOperationRequest, GetEventsReply, GetEventsRequest, GetFilteredSpecsRequest,
+        GetOperationsReply, GetOperationsRequest, GetRebuildLimitRequest, GetSpecsReply,
+        GetSpecsRequest, ListSpecsStreamRequest, ProbeRequest, ProbeResponse, RebuildLimitReply,
+        ReconcileResourceReply, ReconcileResourceRequest, SetRebuildLimitRequest,
     },
 };
-use std::sync::Arc;
+use common_lib::{
+    mbus_api::{ReplyError, ResourceKind},
+    types::v0::message_bus::{ClearOperation, Filter, GetOperations, ReconcileResource},
+};
+use futures::{Stream, StreamExt};
+use std::{convert::TryFrom, pin::Pin, sync::Arc};
 use tonic::Response;
 
 /// gRPC Registry Server
@@ -27,8 +37,14 @@ impl RegistryServer {
     }
 }
 
+/// The stream of replies of the `list_specs_stream` rpc
+type GetSpecsReplyStream =
+    Pin<Box<dyn Stream<Item = Result<GetSpecsReply, tonic::Status>> + Send + Sync>>;
+
 #[tonic::async_trait]
 impl RegistryGrpc for RegistryServer {
+    type ListSpecsStreamStream = GetSpecsReplyStream;
+
     async fn get_specs(
         &self,
         request: tonic::Request<GetSpecsRequest>,
@@ -43,4 +59,176 @@ impl RegistryGrpc for RegistryServer {
             })),
         }
     }
+
+    async fn get_filtered_specs(
+        &self,
+        request: tonic::Request<GetFilteredSpecsRequest>,
+    ) -> Result<tonic::Response<GetSpecsReply>, tonic::Status> {
+        let req: GetFilteredSpecsRequest = request.into_inner();
+        let filter = match req.filter {
+            Some(filter) => Filter::try_from(filter),
+            None => Err(ReplyError::missing_argument(ResourceKind::Spec, "filter")),
+        };
+        let result = match filter {
+            Ok(filter) => self.service.get_filtered_specs(filter, None).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(specs) => Ok(Response::new(GetSpecsReply {
+                reply: Some(get_specs_reply::Reply::Specs(specs.into())),
+            })),
+            Err(err) => Ok(Response::new(GetSpecsReply {
+                reply: Some(get_specs_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+
+    async fn list_specs_stream(
+        &self,
+        request: tonic::Request<ListSpecsStreamRequest>,
+    ) -> Result<tonic::Response<Self::ListSpecsStreamStream>, tonic::Status> {
+        let req: ListSpecsStreamRequest = request.into_inner();
+        let chunk_size = match req.chunk_size {
+            0 => None,
+            chunk_size => Some(chunk_size as usize),
+        };
+        let stream: GetSpecsReplyStream =
+            match self.service.list_specs_stream(chunk_size, None).await {
+                Ok(stream) => Box::pin(stream.map(|chunk| {
+                    Ok(GetSpecsReply {
+                        reply: Some(match chunk {
+                            Ok(specs) => get_specs_reply::Reply::Specs(specs.into()),
+                            Err(err) => get_specs_reply::Reply::Error(err.into()),
+                        }),
+                    })
+                })),
+                Err(err) => Box::pin(futures::stream::iter(vec![Ok(GetSpecsReply {
+                    reply: Some(get_specs_reply::Reply::Error(err.into())),
+                })])),
+            };
+        Ok(Response::new(stream))
+    }
+
+    async fn get_rebuild_limit(
+        &self,
+        _request: tonic::Request<GetRebuildLimitRequest>,
+    ) -> Result<tonic::Response<RebuildLimitReply>, tonic::Status> {
+        match self.service.get_rebuild_limit(None).await {
+            Ok(limit) => Ok(Response::new(RebuildLimitReply {
+                reply: Some(rebuild_limit_reply::Reply::Limit(limit.into())),
+            })),
+            Err(err) => Ok(Response::new(RebuildLimitReply {
+                reply: Some(rebuild_limit_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+
+    async fn set_rebuild_limit(
+        &self,
+        request: tonic::Request<SetRebuildLimitRequest>,
+    ) -> Result<tonic::Response<RebuildLimitReply>, tonic::Status> {
+        let req: SetRebuildLimitRequest = request.into_inner();
+        match self.service.set_rebuild_limit(&req, None).await {
+            Ok(limit) => Ok(Response::new(RebuildLimitReply {
+                reply: Some(rebuild_limit_reply::Reply::Limit(limit.into())),
+            })),
+            Err(err) => Ok(Response::new(RebuildLimitReply {
+                reply: Some(rebuild_limit_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn check_orphaned_replicas(
+        &self,
+        request: tonic::Request<CheckOrphanedReplicasRequest>,
+    ) -> Result<tonic::Response<CheckOrphanedReplicasReply>, tonic::Status> {
+        let req: CheckOrphanedReplicasRequest = request.into_inner();
+        match self.service.check_orphaned_replicas(&req, None).await {
+            Ok(replicas) => Ok(Response::new(CheckOrphanedReplicasReply {
+                reply: Some(check_orphaned_replicas_reply::Reply::Replicas(
+                    replicas.into(),
+                )),
+            })),
+            Err(err) => Ok(Response::new(CheckOrphanedReplicasReply {
+                reply: Some(check_orphaned_replicas_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn get_events(
+        &self,
+        request: tonic::Request<GetEventsRequest>,
+    ) -> Result<tonic::Response<GetEventsReply>, tonic::Status> {
+        let req: GetEventsRequest = request.into_inner();
+        let filter = match req.filter {
+            Some(filter) => Filter::try_from(filter),
+            None => Ok(Filter::None),
+        };
+        let result = match filter {
+            Ok(filter) => self.service.get_events(filter, None).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(events) => Ok(Response::new(GetEventsReply {
+                reply: Some(get_events_reply::Reply::Events(events.into())),
+            })),
+            Err(err) => Ok(Response::new(GetEventsReply {
+                reply: Some(get_events_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn get_operations(
+        &self,
+        request: tonic::Request<GetOperationsRequest>,
+    ) -> Result<tonic::Response<GetOperationsReply>, tonic::Status> {
+        let result = match GetOperations::try_from(request.into_inner()) {
+            Ok(req) => self.service.get_operations(&req, None).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(operations) => Ok(Response::new(GetOperationsReply {
+                reply: Some(get_operations_reply::Reply::Operations(operations.into())),
+            })),
+            Err(err) => Ok(Response::new(GetOperationsReply {
+                reply: Some(get_operations_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn clear_operation(
+        &self,
+        request: tonic::Request<ClearOperationRequest>,
+    ) -> Result<tonic::Response<ClearOperationReply>, tonic::Status> {
+        let result = match ClearOperation::try_from(request.into_inner()) {
+            Ok(req) => self.service.clear_operation(&req, None).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => Ok(Response::new(ClearOperationReply { error: None })),
+            Err(err) => Ok(Response::new(ClearOperationReply {
+                error: Some(err.into()),
+            })),
+        }
+    }
+    async fn reconcile_resource(
+        &self,
+        request: tonic::Request<ReconcileResourceRequest>,
+    ) -> Result<tonic::Response<ReconcileResourceReply>, tonic::Status> {
+        let result = match ReconcileResource::try_from(request.into_inner()) {
+            Ok(req) => self.service.reconcile_resource(&req, None).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => Ok(Response::new(ReconcileResourceReply { error: None })),
+            Err(err) => Ok(Response::new(ReconcileResourceReply {
+                error: Some(err.into()),
+            })),
+        }
+    }
+    async fn probe(
+        &self,
+        _request: tonic::Request<ProbeRequest>,
+    ) -> Result<tonic::Response<ProbeResponse>, tonic::Status> {
+        match self.service.probe(None).await {
+            Ok(resp) => Ok(Response::new(ProbeResponse { ready: resp })),
+            Err(_) => Ok(Response::new(ProbeResponse { ready: false })),
+        }
+    }
 }
@@ -1,13 +1,35 @@
-use crate::{context::Context, registry, registry::GetSpecsRequest};
+use crate::{
+    common,
+    context::Context,
+    misc::traits::StringValue,
+    registry,
+    registry::{
+        get_events_request, get_filtered_specs_request, CheckOrphanedReplicasRequest,
+        ClearOperationRequest, GetOperationsRequest, GetSpecsRequest, ReconcileResourceRequest,
+        SetRebuildLimitRequest,
+    },
+};
 use common_lib::{
-    mbus_api::ReplyError,
+    mbus_api::{v0::Events, ReplyError, ResourceKind},
     types::v0::{
         message_bus,
-        message_bus::{GetSpecs, Specs},
+        message_bus::{
+            CheckOrphanedReplicas, ClearOperation, Event, EventType, Filter, GetOperations,
+            GetSpecs, NexusId, OrphanedReplica, OrphanedReplicas, PendingOperation,
+            PendingOperations, RebuildLimit, ReconcileResource, Replica, ReplicaId, ResourceRef,
+            SetRebuildLimit, Specs, VolumeId,
+        },
         store::{nexus::NexusSpec, pool::PoolSpec, replica::ReplicaSpec, volume::VolumeSpec},
     },
 };
-use std::convert::TryFrom;
+use futures::Stream;
+use std::{convert::TryFrom, pin::Pin};
+
+/// Default maximum number of specs in each chunk of a specs stream
+pub const DEFAULT_SPECS_CHUNK_SIZE: usize = 256;
+
+/// Stream of chunks of specs, each chunk holding specs of a single resource kind
+pub type SpecsStream = Pin<Box<dyn Stream<Item = Result<Specs, ReplyError>> + Send + Sync>>;
 
 /// Trait implemented by services which support registry operations.
 #[tonic::async_trait]
@@ -18,6 +40,60 @@ pub trait RegistryOperations: Send + Sync {
         get_spec: &dyn GetSpecsInfo,
         ctx: Option<Context>,
     ) -> Result<message_bus::Specs, ReplyError>;
+    /// Get the resource specs related to the resource of the filter, which may be a
+    /// `Filter::Node`, a `Filter::Pool` or a `Filter::Volume`
+    async fn get_filtered_specs(
+        &self,
+        filter: Filter,
+        ctx: Option<Context>,
+    ) -> Result<message_bus::Specs, ReplyError>;
+    /// Stream all resource specs in chunks of at most `chunk_size` specs of a single resource
+    /// kind, or `DEFAULT_SPECS_CHUNK_SIZE` if not specified, so that big clusters don't exceed
+    /// the gRPC message size limit
+    async fn list_specs_stream(
+        &self,
+        chunk_size: Option<usize>,
+        ctx: Option<Context>,
+    ) -> Result<SpecsStream, ReplyError>;
+    /// Get the system-wide limit on the number of concurrent rebuilds
+    async fn get_rebuild_limit(&self, ctx: Option<Context>) -> Result<RebuildLimit, ReplyError>;
+    /// Set the system-wide limit on the number of concurrent rebuilds
+    async fn set_rebuild_limit(
+        &self,
+        req: &dyn SetRebuildLimitInfo,
+        ctx: Option<Context>,
+    ) -> Result<RebuildLimit, ReplyError>;
+    /// Check for replicas which are not referenced by any spec, optionally destroying them
+    async fn check_orphaned_replicas(
+        &self,
+        req: &dyn CheckOrphanedReplicasInfo,
+        ctx: Option<Context>,
+    ) -> Result<OrphanedReplicas, ReplyError>;
+    /// Get the recent control-plane events, oldest first, optionally only those about the
+    /// resource of the filter, which may be a `Filter::None`, a `Filter::Node`, a
+    /// `Filter::Pool`, a `Filter::Replica`, a `Filter::Nexus` or a `Filter::Volume`
+    async fn get_events(&self, filter: Filter, ctx: Option<Context>) -> Result<Events, ReplyError>;
+    /// Get the operations which were started on the resource specs but which haven't completed,
+    /// optionally only those of the referenced resource or, for a node, of the resources on it
+    async fn get_operations(
+        &self,
+        req: &GetOperations,
+        ctx: Option<Context>,
+    ) -> Result<PendingOperations, ReplyError>;
+    /// Clear the pending operation of the referenced resource
+    async fn clear_operation(
+        &self,
+        req: &ClearOperation,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError>;
+    /// Reconcile the referenced resource now, rather than waiting for the next reconcile period
+    async fn reconcile_resource(
+        &self,
+        req: &ReconcileResource,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError>;
+    /// Liveness probe for registry service, ready when the persistent store is online
+    async fn probe(&self, ctx: Option<Context>) -> Result<bool, ReplyError>;
 }
 
 /// GetSpecsInfo trait for the get_specs operation
@@ -39,6 +115,23 @@ impl From<&dyn GetSpecsInfo> for GetSpecs {
     }
 }
 
+impl TryFrom<get_filtered_specs_request::Filter> for Filter {
+    type Error = ReplyError;
+    fn try_from(filter: get_filtered_specs_request::Filter) -> Result<Self, Self::Error> {
+        Ok(match filter {
+            get_filtered_specs_request::Filter::Node(node_filter) => {
+                Filter::Node(node_filter.node_id.into())
+            }
+            get_filtered_specs_request::Filter::Pool(pool_filter) => {
+                Filter::Pool(pool_filter.pool_id.into())
+            }
+            get_filtered_specs_request::Filter::Volume(volume_filter) => Filter::Volume(
+                VolumeId::try_from(StringValue(Some(volume_filter.volume_id)))?,
+            ),
+        })
+    }
+}
+
 impl TryFrom<registry::Specs> for message_bus::Specs {
     type Error = ReplyError;
 
@@ -102,3 +195,347 @@ impl From<message_bus::Specs> for registry::Specs {
         }
     }
 }
+
+/// SetRebuildLimitInfo trait for the set_rebuild_limit operation
+pub trait SetRebuildLimitInfo: Send + Sync + std::fmt::Debug {
+    /// maximum number of concurrent rebuilds, or unlimited if None
+    fn max_rebuilds(&self) -> Option<u32>;
+}
+
+impl SetRebuildLimitInfo for SetRebuildLimit {
+    fn max_rebuilds(&self) -> Option<u32> {
+        self.max_rebuilds
+    }
+}
+
+impl SetRebuildLimitInfo for SetRebuildLimitRequest {
+    fn max_rebuilds(&self) -> Option<u32> {
+        self.max_rebuilds
+    }
+}
+
+impl From<&dyn SetRebuildLimitInfo> for SetRebuildLimitRequest {
+    fn from(data: &dyn SetRebuildLimitInfo) -> Self {
+        Self {
+            max_rebuilds: data.max_rebuilds(),
+        }
+    }
+}
+
+impl From<&dyn SetRebuildLimitInfo> for SetRebuildLimit {
+    fn from(data: &dyn SetRebuildLimitInfo) -> Self {
+        Self {
+            max_rebuilds: data.max_rebuilds(),
+        }
+    }
+}
+
+impl From<registry::RebuildLimit> for RebuildLimit {
+    fn from(limit: registry::RebuildLimit) -> Self {
+        Self {
+            max_rebuilds: limit.max_rebuilds,
+            num_rebuilds: limit.num_rebuilds,
+        }
+    }
+}
+
+impl From<RebuildLimit> for registry::RebuildLimit {
+    fn from(limit: RebuildLimit) -> Self {
+        Self {
+            max_rebuilds: limit.max_rebuilds,
+            num_rebuilds: limit.num_rebuilds,
+        }
+    }
+}
+
+/// CheckOrphanedReplicasInfo trait for the check_orphaned_replicas operation
+pub trait CheckOrphanedReplicasInfo: Send + Sync + std::fmt::Debug {
+    /// destroy the orphaned replicas rather than only reporting them
+    fn destroy(&self) -> bool;
+}
+
+impl CheckOrphanedReplicasInfo for CheckOrphanedReplicas {
+    fn destroy(&self) -> bool {
+        self.destroy
+    }
+}
+
+impl CheckOrphanedReplicasInfo for CheckOrphanedReplicasRequest {
+    fn destroy(&self) -> bool {
+        self.destroy
+    }
+}
+
+impl From<&dyn CheckOrphanedReplicasInfo> for CheckOrphanedReplicasRequest {
+    fn from(data: &dyn CheckOrphanedReplicasInfo) -> Self {
+        Self {
+            destroy: data.destroy(),
+        }
+    }
+}
+
+impl From<&dyn CheckOrphanedReplicasInfo> for CheckOrphanedReplicas {
+    fn from(data: &dyn CheckOrphanedReplicasInfo) -> Self {
+        Self {
+            destroy: data.destroy(),
+        }
+    }
+}
+
+impl TryFrom<registry::OrphanedReplicas> for OrphanedReplicas {
+    type Error = ReplyError;
+
+    fn try_from(value: registry::OrphanedReplicas) -> Result<Self, Self::Error> {
+        let mut entries = Vec::with_capacity(value.entries.len());
+        for orphan in value.entries {
+            let replica = match orphan.replica {
+                Some(replica) => Replica::try_from(replica)?,
+                None => {
+                    return Err(ReplyError::missing_argument(
+                        ResourceKind::Replica,
+                        "orphaned_replica.replica",
+                    ))
+                }
+            };
+            entries.push(OrphanedReplica {
+                replica,
+                destroyed: orphan.destroyed,
+            });
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl From<OrphanedReplicas> for registry::OrphanedReplicas {
+    fn from(value: OrphanedReplicas) -> Self {
+        Self {
+            entries: value
+                .entries
+                .into_iter()
+                .map(|orphan| registry::OrphanedReplica {
+                    replica: Some(orphan.replica.into()),
+                    destroyed: orphan.destroyed,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<get_events_request::Filter> for Filter {
+    type Error = ReplyError;
+    fn try_from(filter: get_events_request::Filter) -> Result<Self, Self::Error> {
+        Ok(match filter {
+            get_events_request::Filter::Node(node_filter) => {
+                Filter::Node(node_filter.node_id.into())
+            }
+            get_events_request::Filter::Pool(pool_filter) => {
+                Filter::Pool(pool_filter.pool_id.into())
+            }
+            get_events_request::Filter::Replica(replica_filter) => Filter::Replica(
+                ReplicaId::try_from(StringValue(Some(replica_filter.replica_id)))?,
+            ),
+            get_events_request::Filter::Nexus(nexus_filter) => {
+                Filter::Nexus(NexusId::try_from(StringValue(Some(nexus_filter.nexus_id)))?)
+            }
+            get_events_request::Filter::Volume(volume_filter) => Filter::Volume(
+                VolumeId::try_from(StringValue(Some(volume_filter.volume_id)))?,
+            ),
+        })
+    }
+}
+
+impl From<EventType> for registry::EventType {
+    fn from(src: EventType) -> Self {
+        match src {
+            EventType::Normal => Self::Normal,
+            EventType::Warning => Self::Warning,
+        }
+    }
+}
+
+impl From<registry::EventType> for EventType {
+    fn from(src: registry::EventType) -> Self {
+        match src {
+            registry::EventType::Normal => Self::Normal,
+            registry::EventType::Warning => Self::Warning,
+        }
+    }
+}
+
+impl From<Event> for registry::Event {
+    fn from(src: Event) -> Self {
+        let kind: common::ResourceKind = src.kind.into();
+        let event_type: registry::EventType = src.event_type.into();
+        Self {
+            timestamp: Some(src.timestamp.into()),
+            kind: kind as i32,
+            id: src.id,
+            event_type: event_type as i32,
+            reason: src.reason,
+            message: src.message,
+        }
+    }
+}
+
+impl TryFrom<registry::Event> for Event {
+    type Error = ReplyError;
+    fn try_from(src: registry::Event) -> Result<Self, Self::Error> {
+        Ok(Self {
+            timestamp: match src.timestamp {
+                Some(timestamp) => match std::time::SystemTime::try_from(timestamp) {
+                    Ok(time) => time,
+                    Err(err) => {
+                        return Err(ReplyError::invalid_argument(
+                            ResourceKind::Unknown,
+                            "event.timestamp",
+                            format!("{:?}", err),
+                        ))
+                    }
+                },
+                None => {
+                    return Err(ReplyError::missing_argument(
+                        ResourceKind::Unknown,
+                        "event.timestamp",
+                    ))
+                }
+            },
+            kind: match common::ResourceKind::from_i32(src.kind) {
+                Some(kind) => kind.into(),
+                None => {
+                    return Err(ReplyError::invalid_argument(
+                        ResourceKind::Unknown,
+                        "event.kind",
+                        "".to_string(),
+                    ))
+                }
+            },
+            id: src.id,
+            event_type: match registry::EventType::from_i32(src.event_type) {
+                Some(event_type) => event_type.into(),
+                None => {
+                    return Err(ReplyError::invalid_argument(
+                        ResourceKind::Unknown,
+                        "event.event_type",
+                        "".to_string(),
+                    ))
+                }
+            },
+            reason: src.reason,
+            message: src.message,
+        })
+    }
+}
+
+impl TryFrom<registry::Events> for Events {
+    type Error = ReplyError;
+    fn try_from(src: registry::Events) -> Result<Self, Self::Error> {
+        let mut events = Vec::with_capacity(src.events.len());
+        for event in src.events {
+            events.push(Event::try_from(event)?);
+        }
+        Ok(Events(events))
+    }
+}
+
+impl From<Events> for registry::Events {
+    fn from(src: Events) -> Self {
+        Self {
+            events: src.into_inner().into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Get the referenced resource of a request, which is required
+fn required_resource(resource: Option<common::ResourceRef>) -> Result<ResourceRef, ReplyError> {
+    match resource {
+        Some(resource) => ResourceRef::try_from(resource),
+        None => Err(ReplyError::missing_argument(
+            ResourceKind::Unknown,
+            "resource",
+        )),
+    }
+}
+
+impl TryFrom<GetOperationsRequest> for GetOperations {
+    type Error = ReplyError;
+
+    fn try_from(value: GetOperationsRequest) -> Result<Self, Self::Error> {
+        let resource = match value.resource {
+            Some(resource) => Some(ResourceRef::try_from(resource)?),
+            None => None,
+        };
+        Ok(Self::new(resource))
+    }
+}
+
+impl From<&GetOperations> for GetOperationsRequest {
+    fn from(value: &GetOperations) -> Self {
+        Self {
+            resource: value.resource.clone().map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<ClearOperationRequest> for ClearOperation {
+    type Error = ReplyError;
+
+    fn try_from(value: ClearOperationRequest) -> Result<Self, Self::Error> {
+        Ok(Self::new(required_resource(value.resource)?))
+    }
+}
+
+impl From<&ClearOperation> for ClearOperationRequest {
+    fn from(value: &ClearOperation) -> Self {
+        Self {
+            resource: Some(value.resource.clone().into()),
+        }
+    }
+}
+
+impl TryFrom<ReconcileResourceRequest> for ReconcileResource {
+    type Error = ReplyError;
+
+    fn try_from(value: ReconcileResourceRequest) -> Result<Self, Self::Error> {
+        Ok(Self::new(required_resource(value.resource)?))
+    }
+}
+
+impl From<&ReconcileResource> for ReconcileResourceRequest {
+    fn from(value: &ReconcileResource) -> Self {
+        Self {
+            resource: Some(value.resource.clone().into()),
+        }
+    }
+}
+
+impl TryFrom<registry::PendingOperations> for PendingOperations {
+    type Error = ReplyError;
+
+    fn try_from(value: registry::PendingOperations) -> Result<Self, Self::Error> {
+        let mut entries = Vec::with_capacity(value.entries.len());
+        for pending in value.entries {
+            entries.push(PendingOperation {
+                resource: required_resource(pending.resource)?,
+                operation: pending.operation,
+                result: pending.result,
+            });
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl From<PendingOperations> for registry::PendingOperations {
+    fn from(value: PendingOperations) -> Self {
+        Self {
+            entries: value
+                .entries
+                .into_iter()
+                .map(|pending| registry::PendingOperation {
+                    resource: Some(pending.resource.into()),
+                    operation: pending.operation,
+                    result: pending.result,
+                })
+                .collect(),
+        }
+    }
+}
@@ -3,23 +3,24 @@ use crate::{
         NodeFilter, NodePoolFilter, NodePoolReplicaFilter, NodeReplicaFilter, PoolFilter,
         PoolReplicaFilter, ReplicaFilter, VolumeFilter,
     },
-    context::{Client, Context, TracedChannel},
-    operations::replica::traits::ReplicaOperations,
+    context::{Client, Context, Endpoints, TracedChannel},
+    operations::{replica::traits::ReplicaOperations, Pagination},
     replica::{
-        create_replica_reply, get_replicas_reply, get_replicas_request,
-        replica_grpc_client::ReplicaGrpcClient, share_replica_reply, GetReplicasRequest,
+        create_replica_reply, get_replica_reply, get_replicas_reply, get_replicas_request,
+        move_replica_reply, replica_grpc_client::ReplicaGrpcClient, resize_replica_reply,
+        share_replica_reply, GetReplicaRequest, GetReplicasRequest, ProbeRequest,
     },
 };
 
 use std::{convert::TryFrom, ops::Deref};
-use tonic::transport::Uri;
 
 use crate::operations::replica::traits::{
-    CreateReplicaInfo, DestroyReplicaInfo, ShareReplicaInfo, UnshareReplicaInfo,
+    CreateReplicaInfo, DestroyReplicaInfo, MoveReplicaInfo, ResizeReplicaInfo, ShareReplicaInfo,
+    UnshareReplicaInfo,
 };
 use common_lib::{
     mbus_api::{v0::Replicas, ReplyError, ResourceKind, TimeoutOptions},
-    types::v0::message_bus::{Filter, MessageIdVs, Replica},
+    types::v0::message_bus::{Filter, GetReplica, MessageIdVs, NodeId, PoolId, Replica, ReplicaId},
 };
 
 /// RPC Replica Client
@@ -35,7 +36,10 @@ impl Deref for ReplicaClient {
 }
 impl ReplicaClient {
     /// creates a new base tonic endpoint with the timeout options and the address
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
         let client = Client::new(addr, opts, ReplicaGrpcClient::new).await;
         Self { inner: client }
     }
@@ -61,23 +65,31 @@ impl ReplicaOperations for ReplicaClient {
     }
 
     #[tracing::instrument(name = "ReplicaClient::get", level = "debug", skip(self), err)]
-    async fn get(&self, filter: Filter, ctx: Option<Context>) -> Result<Replicas, ReplyError> {
+    async fn get(
+        &self,
+        filter: Filter,
+        pagination: Option<Pagination>,
+        ctx: Option<Context>,
+    ) -> Result<Replicas, ReplyError> {
         let req: GetReplicasRequest = match filter {
             Filter::Node(id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::Node(NodeFilter {
                     node_id: id.into(),
                 })),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::Pool(id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::Pool(PoolFilter {
                     pool_id: id.into(),
                 })),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::NodePool(node_id, pool_id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::NodePool(NodePoolFilter {
                     node_id: node_id.into(),
                     pool_id: pool_id.into(),
                 })),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::NodePoolReplica(node_id, pool_id, replica_id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::NodePoolReplica(
@@ -87,6 +99,7 @@ impl ReplicaOperations for ReplicaClient {
                         replica_id: replica_id.to_string(),
                     },
                 )),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::NodeReplica(node_id, replica_id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::NodeReplica(
@@ -95,6 +108,7 @@ impl ReplicaOperations for ReplicaClient {
                         replica_id: replica_id.to_string(),
                     },
                 )),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::PoolReplica(pool_id, replica_id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::PoolReplica(
@@ -103,18 +117,24 @@ impl ReplicaOperations for ReplicaClient {
                         replica_id: replica_id.to_string(),
                     },
                 )),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::Replica(replica_id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::Replica(ReplicaFilter {
                     replica_id: replica_id.to_string(),
                 })),
+                pagination: pagination.map(|p| p.into()),
             },
             Filter::Volume(volume_id) => GetReplicasRequest {
                 filter: Some(get_replicas_request::Filter::Volume(VolumeFilter {
                     volume_id: volume_id.to_string(),
                 })),
+                pagination: pagination.map(|p| p.into()),
+            },
+            _ => GetReplicasRequest {
+                filter: None,
+                pagination: pagination.map(|p| p.into()),
             },
-            _ => GetReplicasRequest { filter: None },
         };
         let req = self.request(req, ctx, MessageIdVs::GetReplicas);
         let response = self.client().get_replicas(req).await?.into_inner();
@@ -127,6 +147,26 @@ impl ReplicaOperations for ReplicaClient {
         }
     }
 
+    #[tracing::instrument(name = "ReplicaClient::get_one", level = "debug", skip(self), err)]
+    async fn get_one(
+        &self,
+        node: &NodeId,
+        pool: &PoolId,
+        uuid: &ReplicaId,
+        ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError> {
+        let req: GetReplicaRequest = (&GetReplica::new(node, pool, uuid)).into();
+        let req = self.request(req, ctx, MessageIdVs::GetReplica);
+        let response = self.client().get_replica(req).await?.into_inner();
+        match response.reply {
+            Some(get_replica_reply) => match get_replica_reply {
+                get_replica_reply::Reply::Replica(replica) => Ok(Replica::try_from(replica)?),
+                get_replica_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Replica)),
+        }
+    }
+
     #[tracing::instrument(name = "ReplicaClient::destroy", level = "debug", skip(self), err)]
     async fn destroy(
         &self,
@@ -171,4 +211,46 @@ impl ReplicaOperations for ReplicaClient {
             Some(err) => Err(err.into()),
         }
     }
+
+    #[tracing::instrument(name = "ReplicaClient::resize", level = "debug", skip(self), err)]
+    async fn resize(
+        &self,
+        request: &dyn ResizeReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::ResizeReplica);
+        let response = self.client().resize_replica(req).await?.into_inner();
+        match response.reply {
+            Some(resize_replica_reply) => match resize_replica_reply {
+                resize_replica_reply::Reply::Replica(replica) => Ok(Replica::try_from(replica)?),
+                resize_replica_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Replica)),
+        }
+    }
+
+    #[tracing::instrument(name = "ReplicaClient::move_replica", level = "debug", skip(self), err)]
+    async fn move_replica(
+        &self,
+        request: &dyn MoveReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::MoveReplica);
+        let response = self.client().move_replica(req).await?.into_inner();
+        match response.reply {
+            Some(move_replica_reply) => match move_replica_reply {
+                move_replica_reply::Reply::Replica(replica) => Ok(Replica::try_from(replica)?),
+                move_replica_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Replica)),
+        }
+    }
+
+    #[tracing::instrument(name = "ReplicaClient::probe", level = "debug", skip(self))]
+    async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
+        match self.client().probe(ProbeRequest {}).await {
+            Ok(resp) => Ok(resp.into_inner().ready),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
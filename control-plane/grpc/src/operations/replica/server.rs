@@ -1,15 +1,17 @@
 use crate::{
     misc::traits::ValidateRequestTypes,
-    operations::replica::traits::ReplicaOperations,
+    operations::{replica::traits::ReplicaOperations, Pagination},
     replica::{
-        create_replica_reply, get_replicas_reply,
+        create_replica_reply, get_replica_reply, get_replicas_reply, move_replica_reply,
         replica_grpc_server::{ReplicaGrpc, ReplicaGrpcServer},
-        share_replica_reply, CreateReplicaReply, CreateReplicaRequest, DestroyReplicaReply,
-        DestroyReplicaRequest, GetReplicasReply, GetReplicasRequest, ShareReplicaReply,
+        resize_replica_reply, share_replica_reply, CreateReplicaReply, CreateReplicaRequest,
+        DestroyReplicaReply, DestroyReplicaRequest, GetReplicaReply, GetReplicaRequest,
+        GetReplicasReply, GetReplicasRequest, MoveReplicaReply, MoveReplicaRequest, ProbeRequest,
+        ProbeResponse, ResizeReplicaReply, ResizeReplicaRequest, ShareReplicaReply,
         ShareReplicaRequest, UnshareReplicaReply, UnshareReplicaRequest,
     },
 };
-use common_lib::types::v0::message_bus::Filter;
+use common_lib::types::v0::message_bus::{Filter, GetReplica};
 use std::{convert::TryFrom, sync::Arc};
 use tonic::Response;
 
@@ -69,7 +71,8 @@ impl ReplicaGrpc for ReplicaServer {
             Some(filter) => Filter::try_from(filter)?,
             None => Filter::None,
         };
-        match self.service.get(filter, None).await {
+        let pagination: Option<Pagination> = req.pagination.map(|p| p.into());
+        match self.service.get(filter, pagination, None).await {
             Ok(replicas) => Ok(Response::new(GetReplicasReply {
                 reply: Some(get_replicas_reply::Reply::Replicas(replicas.into())),
             })),
@@ -78,6 +81,24 @@ impl ReplicaGrpc for ReplicaServer {
             })),
         }
     }
+    async fn get_replica(
+        &self,
+        request: tonic::Request<GetReplicaRequest>,
+    ) -> Result<tonic::Response<GetReplicaReply>, tonic::Status> {
+        let req = GetReplica::try_from(request.into_inner())?;
+        match self
+            .service
+            .get_one(&req.node, &req.pool, &req.uuid, None)
+            .await
+        {
+            Ok(replica) => Ok(Response::new(GetReplicaReply {
+                reply: Some(get_replica_reply::Reply::Replica(replica.into())),
+            })),
+            Err(err) => Ok(Response::new(GetReplicaReply {
+                reply: Some(get_replica_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
     async fn share_replica(
         &self,
         request: tonic::Request<ShareReplicaRequest>,
@@ -104,4 +125,41 @@ impl ReplicaGrpc for ReplicaServer {
             })),
         }
     }
+    async fn resize_replica(
+        &self,
+        request: tonic::Request<ResizeReplicaRequest>,
+    ) -> Result<tonic::Response<ResizeReplicaReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.resize(&req, None).await {
+            Ok(replica) => Ok(Response::new(ResizeReplicaReply {
+                reply: Some(resize_replica_reply::Reply::Replica(replica.into())),
+            })),
+            Err(err) => Ok(Response::new(ResizeReplicaReply {
+                reply: Some(resize_replica_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn move_replica(
+        &self,
+        request: tonic::Request<MoveReplicaRequest>,
+    ) -> Result<tonic::Response<MoveReplicaReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.move_replica(&req, None).await {
+            Ok(replica) => Ok(Response::new(MoveReplicaReply {
+                reply: Some(move_replica_reply::Reply::Replica(replica.into())),
+            })),
+            Err(err) => Ok(Response::new(MoveReplicaReply {
+                reply: Some(move_replica_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn probe(
+        &self,
+        _request: tonic::Request<ProbeRequest>,
+    ) -> Result<tonic::Response<ProbeResponse>, tonic::Status> {
+        match self.service.probe(None).await {
+            Ok(resp) => Ok(Response::new(ProbeResponse { ready: resp })),
+            Err(_) => Ok(Response::new(ProbeResponse { ready: false })),
+        }
+    }
 }
@@ -2,10 +2,11 @@ use crate::{
     common,
     context::Context,
     misc::traits::{StringValue, ValidateRequestTypes},
+    operations::Pagination,
     replica,
     replica::{
-        get_replicas_request, CreateReplicaRequest, DestroyReplicaRequest, ShareReplicaRequest,
-        UnshareReplicaRequest,
+        get_replicas_request, CreateReplicaRequest, DestroyReplicaRequest, GetReplicaRequest,
+        MoveReplicaRequest, ResizeReplicaRequest, ShareReplicaRequest, UnshareReplicaRequest,
     },
 };
 use common_lib::{
@@ -13,8 +14,9 @@ use common_lib::{
     types::v0::{
         message_bus,
         message_bus::{
-            CreateReplica, DestroyReplica, Filter, NexusId, NodeId, PoolId, Replica, ReplicaId,
-            ReplicaName, ReplicaOwners, ShareReplica, UnshareReplica, VolumeId,
+            CreateReplica, DestroyReplica, EncryptionCipher, Filter, GetReplica, MoveReplica,
+            NexusId, NodeId, PoolId, Replica, ReplicaAnnotations, ReplicaEncryption, ReplicaId,
+            ReplicaName, ReplicaOwners, ResizeReplica, ShareReplica, UnshareReplica, VolumeId,
         },
         store::replica::{ReplicaOperation, ReplicaOperationState, ReplicaSpec, ReplicaSpecStatus},
     },
@@ -31,7 +33,20 @@ pub trait ReplicaOperations: Send + Sync {
         ctx: Option<Context>,
     ) -> Result<Replica, ReplyError>;
     /// Get replicas based on filters
-    async fn get(&self, filter: Filter, ctx: Option<Context>) -> Result<Replicas, ReplyError>;
+    async fn get(
+        &self,
+        filter: Filter,
+        pagination: Option<Pagination>,
+        ctx: Option<Context>,
+    ) -> Result<Replicas, ReplyError>;
+    /// Get the replica `uuid` from the `pool` on the `node`
+    async fn get_one(
+        &self,
+        node: &NodeId,
+        pool: &PoolId,
+        uuid: &ReplicaId,
+        ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError>;
     /// Destroy a replica
     async fn destroy(
         &self,
@@ -50,6 +65,20 @@ pub trait ReplicaOperations: Send + Sync {
         req: &dyn UnshareReplicaInfo,
         ctx: Option<Context>,
     ) -> Result<(), ReplyError>;
+    /// Resize a replica, returning the replica with its new size
+    async fn resize(
+        &self,
+        req: &dyn ResizeReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError>;
+    /// Move a replica to another pool on the same node, returning the new replica
+    async fn move_replica(
+        &self,
+        req: &dyn MoveReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Replica, ReplyError>;
+    /// Liveness probe for replica service
+    async fn probe(&self, ctx: Option<Context>) -> Result<bool, ReplyError>;
 }
 
 impl From<Replica> for replica::Replica {
@@ -66,6 +95,7 @@ impl From<Replica> for replica::Replica {
             share: share as i32,
             uri: replica.uri,
             status: status as i32,
+            state_changed_at: replica.state_changed_at.map(Into::into),
         }
     }
 }
@@ -101,6 +131,19 @@ impl TryFrom<replica::Replica> for Replica {
                     ))
                 }
             },
+            state_changed_at: match replica.state_changed_at {
+                Some(timestamp) => match std::time::SystemTime::try_from(timestamp) {
+                    Ok(time) => Some(time),
+                    Err(err) => {
+                        return Err(ReplyError::invalid_argument(
+                            ResourceKind::Replica,
+                            "replica.state_changed_at",
+                            format!("{:?}", err),
+                        ))
+                    }
+                },
+                None => None,
+            },
         })
     }
 }
@@ -191,7 +234,10 @@ impl TryFrom<replica::Replicas> for Replicas {
         for replica in grpc_replicas_type.replicas {
             replicas.push(Replica::try_from(replica.clone())?)
         }
-        Ok(Replicas(replicas))
+        Ok(Replicas {
+            entries: replicas,
+            next_token: grpc_replicas_type.next_token,
+        })
     }
 }
 
@@ -199,14 +245,36 @@ impl From<Replicas> for replica::Replicas {
     fn from(replicas: Replicas) -> Self {
         replica::Replicas {
             replicas: replicas
-                .into_inner()
+                .entries
                 .iter()
                 .map(|replicas| replicas.clone().into())
                 .collect(),
+            next_token: replicas.next_token,
+        }
+    }
+}
+
+impl From<&GetReplica> for GetReplicaRequest {
+    fn from(req: &GetReplica) -> Self {
+        Self {
+            node_id: req.node.clone().into(),
+            pool_id: req.pool.clone().into(),
+            replica_id: Some(req.uuid.clone().into()),
         }
     }
 }
 
+impl TryFrom<GetReplicaRequest> for GetReplica {
+    type Error = ReplyError;
+    fn try_from(req: GetReplicaRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            node: req.node_id.into(),
+            pool: req.pool_id.into(),
+            uuid: ReplicaId::try_from(StringValue(req.replica_id))?,
+        })
+    }
+}
+
 /// CreateReplicaInfo trait for the replica creation to be implemented by entities which want to
 /// avail this operation
 pub trait CreateReplicaInfo: Send + Sync + std::fmt::Debug {
@@ -228,6 +296,10 @@ pub trait CreateReplicaInfo: Send + Sync + std::fmt::Debug {
     fn managed(&self) -> bool;
     /// Owners of the resource
     fn owners(&self) -> ReplicaOwners;
+    /// Free-form annotations of the replica
+    fn annotations(&self) -> ReplicaAnnotations;
+    /// At-rest encryption of the replica data
+    fn encryption(&self) -> Option<ReplicaEncryption>;
 }
 
 impl CreateReplicaInfo for CreateReplica {
@@ -266,6 +338,14 @@ impl CreateReplicaInfo for CreateReplica {
     fn owners(&self) -> ReplicaOwners {
         self.owners.clone()
     }
+
+    fn annotations(&self) -> ReplicaAnnotations {
+        self.annotations.clone()
+    }
+
+    fn encryption(&self) -> Option<ReplicaEncryption> {
+        self.encryption.clone()
+    }
 }
 
 /// Intermediate structure that validates the conversion to CreateVolumeRequest type
@@ -275,6 +355,7 @@ pub struct ValidatedCreateReplicaRequest {
     uuid: ReplicaId,
     share: message_bus::Protocol,
     owners: ReplicaOwners,
+    encryption: Option<ReplicaEncryption>,
 }
 
 impl CreateReplicaInfo for ValidatedCreateReplicaRequest {
@@ -313,6 +394,17 @@ impl CreateReplicaInfo for ValidatedCreateReplicaRequest {
     fn owners(&self) -> ReplicaOwners {
         self.owners.clone()
     }
+
+    fn annotations(&self) -> ReplicaAnnotations {
+        match self.inner.annotations.clone() {
+            None => ReplicaAnnotations::new(),
+            Some(annotations) => annotations.value,
+        }
+    }
+
+    fn encryption(&self) -> Option<ReplicaEncryption> {
+        self.encryption.clone()
+    }
 }
 
 impl ValidateRequestTypes for CreateReplicaRequest {
@@ -339,6 +431,10 @@ impl ValidateRequestTypes for CreateReplicaRequest {
                     ))
                 }
             },
+            encryption: match self.encryption.clone() {
+                Some(encryption) => Some(ReplicaEncryption::try_from(encryption)?),
+                None => None,
+            },
             inner: self,
         })
     }
@@ -357,6 +453,8 @@ pub trait DestroyReplicaInfo: Send + Sync + std::fmt::Debug {
     fn uuid(&self) -> ReplicaId;
     /// Delete by owners
     fn disowners(&self) -> ReplicaOwners;
+    /// Destroy the replica regardless of its owners
+    fn force(&self) -> bool;
 }
 
 impl DestroyReplicaInfo for DestroyReplica {
@@ -379,6 +477,10 @@ impl DestroyReplicaInfo for DestroyReplica {
     fn disowners(&self) -> ReplicaOwners {
         self.disowners.clone()
     }
+
+    fn force(&self) -> bool {
+        self.force
+    }
 }
 
 /// Intermediate structure that validates the conversion to DestroyVolumeRequest type
@@ -409,6 +511,10 @@ impl DestroyReplicaInfo for ValidatedDestroyReplicaRequest {
     fn disowners(&self) -> ReplicaOwners {
         self.disowners.clone()
     }
+
+    fn force(&self) -> bool {
+        self.inner.force
+    }
 }
 
 impl ValidateRequestTypes for DestroyReplicaRequest {
@@ -583,6 +689,172 @@ impl ValidateRequestTypes for UnshareReplicaRequest {
     }
 }
 
+/// ResizeReplicaInfo trait for the replica resizing to be implemented by entities which want to
+/// avail this operation
+pub trait ResizeReplicaInfo: Send + Sync + std::fmt::Debug {
+    /// Id of the IoEngine instance
+    fn node(&self) -> NodeId;
+    /// Id of the pool
+    fn pool(&self) -> PoolId;
+    /// Name of the replica
+    fn name(&self) -> Option<ReplicaName>;
+    /// Uuid of the replica
+    fn uuid(&self) -> ReplicaId;
+    /// Requested new size of the replica in bytes
+    fn requested_size(&self) -> u64;
+}
+
+impl ResizeReplicaInfo for ResizeReplica {
+    fn node(&self) -> NodeId {
+        self.node.clone()
+    }
+
+    fn pool(&self) -> PoolId {
+        self.pool.clone()
+    }
+
+    fn name(&self) -> Option<ReplicaName> {
+        self.name.clone()
+    }
+
+    fn uuid(&self) -> ReplicaId {
+        self.uuid.clone()
+    }
+
+    fn requested_size(&self) -> u64 {
+        self.requested_size
+    }
+}
+
+/// Intermediate structure that validates the conversion to ResizeReplicaRequest type
+#[derive(Debug)]
+pub struct ValidatedResizeReplicaRequest {
+    inner: ResizeReplicaRequest,
+    uuid: ReplicaId,
+}
+
+impl ResizeReplicaInfo for ValidatedResizeReplicaRequest {
+    fn node(&self) -> NodeId {
+        self.inner.node_id.clone().into()
+    }
+
+    fn pool(&self) -> PoolId {
+        self.inner.pool_id.clone().into()
+    }
+
+    fn name(&self) -> Option<ReplicaName> {
+        self.inner.name.clone().map(|e| e.into())
+    }
+
+    fn uuid(&self) -> ReplicaId {
+        self.uuid.clone()
+    }
+
+    fn requested_size(&self) -> u64 {
+        self.inner.requested_size
+    }
+}
+
+impl ValidateRequestTypes for ResizeReplicaRequest {
+    type Validated = ValidatedResizeReplicaRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        if self.requested_size == 0 {
+            return Err(ReplyError::invalid_argument(
+                ResourceKind::Replica,
+                "resize_replica_request.requested_size",
+                "requested size must be greater than zero".to_string(),
+            ));
+        }
+        Ok(ValidatedResizeReplicaRequest {
+            uuid: ReplicaId::try_from(StringValue(self.replica_id.clone()))?,
+            inner: self,
+        })
+    }
+}
+
+/// MoveReplicaInfo trait for the replica move to be implemented by entities which want to avail
+/// this operation
+pub trait MoveReplicaInfo: Send + Sync + std::fmt::Debug {
+    /// Id of the IoEngine instance
+    fn node(&self) -> NodeId;
+    /// Id of the pool which the replica is moved from
+    fn pool(&self) -> PoolId;
+    /// Id of the pool which the replica is moved to
+    fn destination_pool(&self) -> PoolId;
+    /// Uuid of the replica
+    fn uuid(&self) -> ReplicaId;
+    /// Move the replica even if it's the only healthy child of its nexus
+    fn force(&self) -> bool;
+}
+
+impl MoveReplicaInfo for MoveReplica {
+    fn node(&self) -> NodeId {
+        self.node.clone()
+    }
+
+    fn pool(&self) -> PoolId {
+        self.pool.clone()
+    }
+
+    fn destination_pool(&self) -> PoolId {
+        self.destination_pool.clone()
+    }
+
+    fn uuid(&self) -> ReplicaId {
+        self.uuid.clone()
+    }
+
+    fn force(&self) -> bool {
+        self.force
+    }
+}
+
+/// Intermediate structure that validates the conversion to MoveReplicaRequest type
+#[derive(Debug)]
+pub struct ValidatedMoveReplicaRequest {
+    inner: MoveReplicaRequest,
+    uuid: ReplicaId,
+}
+
+impl MoveReplicaInfo for ValidatedMoveReplicaRequest {
+    fn node(&self) -> NodeId {
+        self.inner.node_id.clone().into()
+    }
+
+    fn pool(&self) -> PoolId {
+        self.inner.pool_id.clone().into()
+    }
+
+    fn destination_pool(&self) -> PoolId {
+        self.inner.destination_pool_id.clone().into()
+    }
+
+    fn uuid(&self) -> ReplicaId {
+        self.uuid.clone()
+    }
+
+    fn force(&self) -> bool {
+        self.inner.force
+    }
+}
+
+impl ValidateRequestTypes for MoveReplicaRequest {
+    type Validated = ValidatedMoveReplicaRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        if self.pool_id == self.destination_pool_id {
+            return Err(ReplyError::invalid_argument(
+                ResourceKind::Replica,
+                "move_replica_request.destination_pool_id",
+                "the destination pool must differ from the source pool".to_string(),
+            ));
+        }
+        Ok(ValidatedMoveReplicaRequest {
+            uuid: ReplicaId::try_from(StringValue(self.replica_id.clone()))?,
+            inner: self,
+        })
+    }
+}
+
 impl From<&dyn CreateReplicaInfo> for CreateReplicaRequest {
     fn from(data: &dyn CreateReplicaInfo) -> Self {
         let share: common::Protocol = data.share().into();
@@ -596,6 +868,10 @@ impl From<&dyn CreateReplicaInfo> for CreateReplicaRequest {
             share: share as i32,
             managed: data.managed(),
             owners: Some(data.owners().into()),
+            annotations: Some(crate::common::StringMapValue {
+                value: data.annotations(),
+            }),
+            encryption: data.encryption().map(Into::into),
         }
     }
 }
@@ -612,6 +888,8 @@ impl From<&dyn CreateReplicaInfo> for CreateReplica {
             share: data.share(),
             managed: data.managed(),
             owners: data.owners(),
+            annotations: data.annotations(),
+            encryption: data.encryption(),
         }
     }
 }
@@ -624,6 +902,7 @@ impl From<&dyn DestroyReplicaInfo> for DestroyReplicaRequest {
             name: data.name().map(|name| name.to_string()),
             replica_id: Some(data.uuid().to_string()),
             disowners: Some(data.disowners().into()),
+            force: data.force(),
         }
     }
 }
@@ -636,6 +915,7 @@ impl From<&dyn DestroyReplicaInfo> for DestroyReplica {
             uuid: data.uuid(),
             name: data.name(),
             disowners: data.disowners(),
+            force: data.force(),
         }
     }
 }
@@ -687,6 +967,54 @@ impl From<&dyn UnshareReplicaInfo> for UnshareReplica {
     }
 }
 
+impl From<&dyn ResizeReplicaInfo> for ResizeReplicaRequest {
+    fn from(data: &dyn ResizeReplicaInfo) -> Self {
+        Self {
+            node_id: data.node().to_string(),
+            pool_id: data.pool().to_string(),
+            name: data.name().map(|name| name.to_string()),
+            replica_id: Some(data.uuid().to_string()),
+            requested_size: data.requested_size(),
+        }
+    }
+}
+
+impl From<&dyn ResizeReplicaInfo> for ResizeReplica {
+    fn from(data: &dyn ResizeReplicaInfo) -> Self {
+        Self {
+            node: data.node(),
+            pool: data.pool(),
+            uuid: data.uuid(),
+            name: data.name(),
+            requested_size: data.requested_size(),
+        }
+    }
+}
+
+impl From<&dyn MoveReplicaInfo> for MoveReplicaRequest {
+    fn from(data: &dyn MoveReplicaInfo) -> Self {
+        Self {
+            node_id: data.node().to_string(),
+            replica_id: Some(data.uuid().to_string()),
+            pool_id: data.pool().to_string(),
+            destination_pool_id: data.destination_pool().to_string(),
+            force: data.force(),
+        }
+    }
+}
+
+impl From<&dyn MoveReplicaInfo> for MoveReplica {
+    fn from(data: &dyn MoveReplicaInfo) -> Self {
+        Self {
+            node: data.node(),
+            pool: data.pool(),
+            destination_pool: data.destination_pool(),
+            uuid: data.uuid(),
+            force: data.force(),
+        }
+    }
+}
+
 impl From<common::Protocol> for message_bus::Protocol {
     fn from(src: common::Protocol) -> Self {
         match src {
@@ -854,6 +1182,14 @@ impl TryFrom<replica::ReplicaSpec> for ReplicaSpec {
                 operation: ReplicaOperation::Create,
                 result: op.result,
             }),
+            annotations: value
+                .annotations
+                .map(|annotations| annotations.value)
+                .unwrap_or_default(),
+            encryption: match value.encryption {
+                Some(encryption) => Some(ReplicaEncryption::try_from(encryption)?),
+                None => None,
+            },
         })
     }
 }
@@ -875,6 +1211,42 @@ impl From<ReplicaSpec> for replica::ReplicaSpec {
             operation: value.operation.map(|operation| common::SpecOperation {
                 result: operation.result,
             }),
+            annotations: Some(crate::common::StringMapValue {
+                value: value.annotations,
+            }),
+            encryption: value.encryption.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<replica::ReplicaEncryption> for ReplicaEncryption {
+    type Error = ReplyError;
+
+    fn try_from(value: replica::ReplicaEncryption) -> Result<Self, Self::Error> {
+        let cipher = match replica::EncryptionCipher::from_i32(value.cipher) {
+            Some(replica::EncryptionCipher::AesCbc) => EncryptionCipher::AesCbc,
+            Some(replica::EncryptionCipher::AesXts) => EncryptionCipher::AesXts,
+            None => {
+                return Err(ReplyError::invalid_argument(
+                    ResourceKind::Replica,
+                    "encryption.cipher",
+                    value.cipher.to_string(),
+                ))
+            }
+        };
+        Ok(Self::new(cipher, value.key))
+    }
+}
+
+impl From<ReplicaEncryption> for replica::ReplicaEncryption {
+    fn from(value: ReplicaEncryption) -> Self {
+        let cipher = match value.cipher {
+            EncryptionCipher::AesCbc => replica::EncryptionCipher::AesCbc,
+            EncryptionCipher::AesXts => replica::EncryptionCipher::AesXts,
+        };
+        Self {
+            cipher: cipher as i32,
+            key: value.key,
         }
     }
 }
@@ -1,25 +1,28 @@
 use crate::{
-    common::VolumeFilter,
-    context::{Client, Context, TracedChannel},
+    common::{VolumeAnnotationsFilter, VolumeFilter},
+    context::{Client, Context, Endpoints, TracedChannel},
     operations::{
         volume::traits::{
-            CreateVolumeInfo, DestroyVolumeInfo, PublishVolumeInfo, SetVolumeReplicaInfo,
-            ShareVolumeInfo, UnpublishVolumeInfo, UnshareVolumeInfo, VolumeOperations,
+            CreateSnapshotInfo, CreateVolumeInfo, DeleteSnapshotInfo, DestroyVolumeInfo,
+            ExpandVolumeInfo, ListSnapshotsInfo, PublishVolumeInfo, RepublishVolumeInfo,
+            SetVolumeLabelsInfo, SetVolumeReplicaInfo, ShareVolumeInfo, UnpublishVolumeInfo,
+            UnshareVolumeInfo, VolumeOperations, VolumeReplicaInfo,
         },
         Pagination,
     },
     volume::{
-        create_volume_reply, get_volumes_reply, get_volumes_request, publish_volume_reply,
+        add_volume_replica_reply, create_snapshot_reply, create_volume_reply, expand_volume_reply,
+        get_volumes_reply, get_volumes_request, list_snapshots_reply, publish_volume_reply,
+        remove_volume_replica_reply, republish_volume_reply, set_volume_labels_reply,
         set_volume_replica_reply, share_volume_reply, unpublish_volume_reply,
         volume_grpc_client::VolumeGrpcClient, GetVolumesRequest, ProbeRequest,
     },
 };
 use common_lib::{
     mbus_api::{v0::Volumes, ReplyError, ResourceKind, TimeoutOptions},
-    types::v0::message_bus::{Filter, MessageIdVs, Volume},
+    types::v0::message_bus::{Filter, MessageIdVs, Volume, VolumeSnapshot, VolumeSnapshots},
 };
 use std::{convert::TryFrom, ops::Deref};
-use tonic::transport::Uri;
 
 /// RPC Volume Client
 #[derive(Clone)]
@@ -29,7 +32,10 @@ pub struct VolumeClient {
 
 impl VolumeClient {
     /// creates a new base tonic endpoint with the timeout options and the address
-    pub async fn new<O: Into<Option<TimeoutOptions>>>(addr: Uri, opts: O) -> Self {
+    pub async fn new<E: Into<Endpoints>, O: Into<Option<TimeoutOptions>>>(
+        addr: E,
+        opts: O,
+    ) -> Self {
         let client = Client::new(addr, opts, VolumeGrpcClient::new).await;
         Self { inner: client }
     }
@@ -77,6 +83,12 @@ impl VolumeOperations for VolumeClient {
                 })),
                 pagination: pagination.map(|p| p.into()),
             },
+            Filter::VolumeAnnotations(annotations) => GetVolumesRequest {
+                filter: Some(get_volumes_request::Filter::VolumeAnnotations(
+                    VolumeAnnotationsFilter { annotations },
+                )),
+                pagination: pagination.map(|p| p.into()),
+            },
             _ => GetVolumesRequest {
                 filter: None,
                 pagination: pagination.map(|p| p.into()),
@@ -155,6 +167,23 @@ impl VolumeOperations for VolumeClient {
         }
     }
 
+    #[tracing::instrument(name = "VolumeClient::republish", level = "debug", skip(self), err)]
+    async fn republish(
+        &self,
+        request: &dyn RepublishVolumeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::RepublishVolume);
+        let response = self.client().republish_volume(req).await?.into_inner();
+        match response.reply {
+            Some(republish_volume_reply) => match republish_volume_reply {
+                republish_volume_reply::Reply::Volume(volume) => Ok(Volume::try_from(volume)?),
+                republish_volume_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Volume)),
+        }
+    }
+
     #[tracing::instrument(name = "VolumeClient::unpublish", level = "debug", skip(self), err)]
     async fn unpublish(
         &self,
@@ -189,6 +218,146 @@ impl VolumeOperations for VolumeClient {
         }
     }
 
+    #[tracing::instrument(name = "VolumeClient::add_replica", level = "debug", skip(self), err)]
+    async fn add_replica(
+        &self,
+        request: &dyn VolumeReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::AddVolumeReplica);
+        let response = self.client().add_volume_replica(req).await?.into_inner();
+        match response.reply {
+            Some(add_volume_replica_reply) => match add_volume_replica_reply {
+                add_volume_replica_reply::Reply::Volume(volume) => Ok(Volume::try_from(volume)?),
+                add_volume_replica_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Volume)),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "VolumeClient::remove_replica",
+        level = "debug",
+        skip(self),
+        err
+    )]
+    async fn remove_replica(
+        &self,
+        request: &dyn VolumeReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::RemoveVolumeReplica);
+        let response = self.client().remove_volume_replica(req).await?.into_inner();
+        match response.reply {
+            Some(remove_volume_replica_reply) => match remove_volume_replica_reply {
+                remove_volume_replica_reply::Reply::Volume(volume) => Ok(Volume::try_from(volume)?),
+                remove_volume_replica_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Volume)),
+        }
+    }
+
+    #[tracing::instrument(name = "VolumeClient::expand", level = "debug", skip(self), err)]
+    async fn expand(
+        &self,
+        request: &dyn ExpandVolumeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::ExpandVolume);
+        let response = self.client().expand_volume(req).await?.into_inner();
+        match response.reply {
+            Some(expand_volume_reply) => match expand_volume_reply {
+                expand_volume_reply::Reply::Volume(volume) => Ok(Volume::try_from(volume)?),
+                expand_volume_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Volume)),
+        }
+    }
+
+    #[tracing::instrument(name = "VolumeClient::set_labels", level = "debug", skip(self), err)]
+    async fn set_labels(
+        &self,
+        request: &dyn SetVolumeLabelsInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::SetVolumeLabels);
+        let response = self.client().set_volume_labels(req).await?.into_inner();
+        match response.reply {
+            Some(set_volume_labels_reply) => match set_volume_labels_reply {
+                set_volume_labels_reply::Reply::Volume(volume) => Ok(Volume::try_from(volume)?),
+                set_volume_labels_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::Volume)),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "VolumeClient::create_snapshot",
+        level = "debug",
+        skip(self),
+        err
+    )]
+    async fn create_snapshot(
+        &self,
+        request: &dyn CreateSnapshotInfo,
+        ctx: Option<Context>,
+    ) -> Result<VolumeSnapshot, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::CreateSnapshot);
+        let response = self.client().create_snapshot(req).await?.into_inner();
+        match response.reply {
+            Some(create_snapshot_reply) => match create_snapshot_reply {
+                create_snapshot_reply::Reply::Snapshot(snapshot) => {
+                    Ok(VolumeSnapshot::try_from(snapshot)?)
+                }
+                create_snapshot_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::VolumeSnapshot)),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "VolumeClient::list_snapshots",
+        level = "debug",
+        skip(self),
+        err
+    )]
+    async fn list_snapshots(
+        &self,
+        request: &dyn ListSnapshotsInfo,
+        ctx: Option<Context>,
+    ) -> Result<VolumeSnapshots, ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::ListSnapshots);
+        let response = self.client().list_snapshots(req).await?.into_inner();
+        match response.reply {
+            Some(list_snapshots_reply) => match list_snapshots_reply {
+                list_snapshots_reply::Reply::Snapshots(snapshots) => {
+                    Ok(VolumeSnapshots::try_from(snapshots)?)
+                }
+                list_snapshots_reply::Reply::Error(err) => Err(err.into()),
+            },
+            None => Err(ReplyError::invalid_response(ResourceKind::VolumeSnapshot)),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "VolumeClient::delete_snapshot",
+        level = "debug",
+        skip(self),
+        err
+    )]
+    async fn delete_snapshot(
+        &self,
+        request: &dyn DeleteSnapshotInfo,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError> {
+        let req = self.request(request, ctx, MessageIdVs::DeleteSnapshot);
+        let response = self.client().delete_snapshot(req).await?.into_inner();
+        match response.error {
+            None => Ok(()),
+            Some(err) => Err(err.into()),
+        }
+    }
+
     #[tracing::instrument(name = "VolumeClient::probe", level = "debug", skip(self))]
     async fn probe(&self, _ctx: Option<Context>) -> Result<bool, ReplyError> {
         match self.client().probe(ProbeRequest {}).await {
@@ -2,14 +2,20 @@ use crate::{
     misc::traits::ValidateRequestTypes,
     operations::{volume::traits::VolumeOperations, Pagination},
     volume::{
-        create_volume_reply, get_volumes_reply, publish_volume_reply, set_volume_replica_reply,
+        add_volume_replica_reply, create_snapshot_reply, create_volume_reply, expand_volume_reply,
+        get_volumes_reply, list_snapshots_reply, publish_volume_reply, remove_volume_replica_reply,
+        republish_volume_reply, set_volume_labels_reply, set_volume_replica_reply,
         share_volume_reply, unpublish_volume_reply,
         volume_grpc_server::{VolumeGrpc, VolumeGrpcServer},
-        CreateVolumeReply, CreateVolumeRequest, DestroyVolumeReply, DestroyVolumeRequest,
-        GetVolumesReply, GetVolumesRequest, ProbeRequest, ProbeResponse, PublishVolumeReply,
-        PublishVolumeRequest, SetVolumeReplicaReply, SetVolumeReplicaRequest, ShareVolumeReply,
-        ShareVolumeRequest, UnpublishVolumeReply, UnpublishVolumeRequest, UnshareVolumeReply,
-        UnshareVolumeRequest,
+        AddVolumeReplicaReply, AddVolumeReplicaRequest, CreateSnapshotReply, CreateSnapshotRequest,
+        CreateVolumeReply, CreateVolumeRequest, DeleteSnapshotReply, DeleteSnapshotRequest,
+        DestroyVolumeReply, DestroyVolumeRequest, ExpandVolumeReply, ExpandVolumeRequest,
+        GetVolumesReply, GetVolumesRequest, ListSnapshotsReply, ListSnapshotsRequest, ProbeRequest,
+        ProbeResponse, PublishVolumeReply, PublishVolumeRequest, RemoveVolumeReplicaReply,
+        RemoveVolumeReplicaRequest, RepublishVolumeReply, RepublishVolumeRequest,
+        SetVolumeLabelsReply, SetVolumeLabelsRequest, SetVolumeReplicaReply,
+        SetVolumeReplicaRequest, ShareVolumeReply, ShareVolumeRequest, UnpublishVolumeReply,
+        UnpublishVolumeRequest, UnshareVolumeReply, UnshareVolumeRequest,
     },
 };
 use common_lib::types::v0::message_bus::Filter;
@@ -104,6 +110,20 @@ impl VolumeGrpc for VolumeServer {
             })),
         }
     }
+    async fn republish_volume(
+        &self,
+        request: tonic::Request<RepublishVolumeRequest>,
+    ) -> Result<tonic::Response<RepublishVolumeReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.republish(&req, None).await {
+            Ok(volume) => Ok(Response::new(RepublishVolumeReply {
+                reply: Some(republish_volume_reply::Reply::Volume(volume.into())),
+            })),
+            Err(err) => Ok(Response::new(RepublishVolumeReply {
+                reply: Some(republish_volume_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
     async fn unpublish_volume(
         &self,
         request: tonic::Request<UnpublishVolumeRequest>,
@@ -158,6 +178,102 @@ impl VolumeGrpc for VolumeServer {
             })),
         }
     }
+    async fn add_volume_replica(
+        &self,
+        request: tonic::Request<AddVolumeReplicaRequest>,
+    ) -> Result<tonic::Response<AddVolumeReplicaReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.add_replica(&req, None).await {
+            Ok(volume) => Ok(Response::new(AddVolumeReplicaReply {
+                reply: Some(add_volume_replica_reply::Reply::Volume(volume.into())),
+            })),
+            Err(err) => Ok(Response::new(AddVolumeReplicaReply {
+                reply: Some(add_volume_replica_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn remove_volume_replica(
+        &self,
+        request: tonic::Request<RemoveVolumeReplicaRequest>,
+    ) -> Result<tonic::Response<RemoveVolumeReplicaReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.remove_replica(&req, None).await {
+            Ok(volume) => Ok(Response::new(RemoveVolumeReplicaReply {
+                reply: Some(remove_volume_replica_reply::Reply::Volume(volume.into())),
+            })),
+            Err(err) => Ok(Response::new(RemoveVolumeReplicaReply {
+                reply: Some(remove_volume_replica_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn expand_volume(
+        &self,
+        request: tonic::Request<ExpandVolumeRequest>,
+    ) -> Result<tonic::Response<ExpandVolumeReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.expand(&req, None).await {
+            Ok(volume) => Ok(Response::new(ExpandVolumeReply {
+                reply: Some(expand_volume_reply::Reply::Volume(volume.into())),
+            })),
+            Err(err) => Ok(Response::new(ExpandVolumeReply {
+                reply: Some(expand_volume_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn set_volume_labels(
+        &self,
+        request: tonic::Request<SetVolumeLabelsRequest>,
+    ) -> Result<tonic::Response<SetVolumeLabelsReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.set_labels(&req, None).await {
+            Ok(volume) => Ok(Response::new(SetVolumeLabelsReply {
+                reply: Some(set_volume_labels_reply::Reply::Volume(volume.into())),
+            })),
+            Err(err) => Ok(Response::new(SetVolumeLabelsReply {
+                reply: Some(set_volume_labels_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn create_snapshot(
+        &self,
+        request: tonic::Request<CreateSnapshotRequest>,
+    ) -> Result<tonic::Response<CreateSnapshotReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.create_snapshot(&req, None).await {
+            Ok(snapshot) => Ok(Response::new(CreateSnapshotReply {
+                reply: Some(create_snapshot_reply::Reply::Snapshot(snapshot.into())),
+            })),
+            Err(err) => Ok(Response::new(CreateSnapshotReply {
+                reply: Some(create_snapshot_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn list_snapshots(
+        &self,
+        request: tonic::Request<ListSnapshotsRequest>,
+    ) -> Result<tonic::Response<ListSnapshotsReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.list_snapshots(&req, None).await {
+            Ok(snapshots) => Ok(Response::new(ListSnapshotsReply {
+                reply: Some(list_snapshots_reply::Reply::Snapshots(snapshots.into())),
+            })),
+            Err(err) => Ok(Response::new(ListSnapshotsReply {
+                reply: Some(list_snapshots_reply::Reply::Error(err.into())),
+            })),
+        }
+    }
+    async fn delete_snapshot(
+        &self,
+        request: tonic::Request<DeleteSnapshotRequest>,
+    ) -> Result<tonic::Response<DeleteSnapshotReply>, tonic::Status> {
+        let req = request.into_inner().validated()?;
+        match self.service.delete_snapshot(&req, None).await {
+            Ok(()) => Ok(Response::new(DeleteSnapshotReply { error: None })),
+            Err(e) => Ok(Response::new(DeleteSnapshotReply {
+                error: Some(e.into()),
+            })),
+        }
+    }
     async fn probe(
         &self,
         _request: tonic::Request<ProbeRequest>,
@@ -6,21 +6,27 @@ use crate::{
     operations::Pagination,
     replica, volume,
     volume::{
-        get_volumes_request, CreateVolumeRequest, DestroyVolumeRequest, PublishVolumeRequest,
-        SetVolumeReplicaRequest, ShareVolumeRequest, UnpublishVolumeRequest, UnshareVolumeRequest,
+        get_volumes_request, AddVolumeReplicaRequest, CreateSnapshotRequest, CreateVolumeRequest,
+        DeleteSnapshotRequest, DestroyVolumeRequest, ExpandVolumeRequest, ListSnapshotsRequest,
+        PublishVolumeRequest, RemoveVolumeReplicaRequest, RepublishVolumeRequest,
+        SetVolumeLabelsRequest, SetVolumeReplicaRequest, ShareVolumeRequest,
+        UnpublishVolumeRequest, UnshareVolumeRequest,
     },
 };
 use common_lib::{
     mbus_api::{v0::Volumes, ReplyError, ResourceKind},
     types::v0::{
         message_bus::{
-            CreateVolume, DestroyVolume, ExplicitNodeTopology, Filter, LabelledTopology, Nexus,
-            NexusId, NodeId, NodeTopology, PoolTopology, PublishVolume, ReplicaId, ReplicaStatus,
-            ReplicaTopology, SetVolumeReplica, ShareVolume, Topology, UnpublishVolume,
-            UnshareVolume, Volume, VolumeId, VolumeLabels, VolumePolicy, VolumeShareProtocol,
-            VolumeState,
+            AddVolumeReplica, CreateSnapshot, CreateVolume, DeleteSnapshot, DestroyVolume,
+            ExpandVolume, ExplicitNodeTopology, Filter, LabelledTopology, ListSnapshots, Nexus,
+            NexusId, NodeId, NodeTopology, NvmfTransportParams, PoolId, PoolTopology,
+            PublishVolume, RemoveVolumeReplica, ReplicaEncryption, ReplicaId, ReplicaStatus,
+            ReplicaTopology, RepublishVolume, SetVolumeLabels, SetVolumeReplica, ShareVolume,
+            SnapshotId, Topology, UnpublishVolume, UnshareVolume, Volume, VolumeAnnotations,
+            VolumeId, VolumeLabels, VolumePolicy, VolumeShareProtocol, VolumeSnapshot,
+            VolumeSnapshots, VolumeState,
         },
-        store::volume::{VolumeSpec, VolumeTarget},
+        store::volume::{VolumeEvent, VolumeEventType, VolumeSpec, VolumeTarget},
     },
 };
 use std::{collections::HashMap, convert::TryFrom};
@@ -65,6 +71,12 @@ pub trait VolumeOperations: Send + Sync {
         req: &dyn PublishVolumeInfo,
         ctx: Option<Context>,
     ) -> Result<Volume, ReplyError>;
+    /// Republish a volume on a different node
+    async fn republish(
+        &self,
+        req: &dyn RepublishVolumeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError>;
     /// Unpublish a volume
     async fn unpublish(
         &self,
@@ -77,6 +89,48 @@ pub trait VolumeOperations: Send + Sync {
         req: &dyn SetVolumeReplicaInfo,
         ctx: Option<Context>,
     ) -> Result<Volume, ReplyError>;
+    /// Add a specific replica to the volume
+    async fn add_replica(
+        &self,
+        req: &dyn VolumeReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError>;
+    /// Remove a specific replica from the volume
+    async fn remove_replica(
+        &self,
+        req: &dyn VolumeReplicaInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError>;
+    /// Expand the volume size
+    async fn expand(
+        &self,
+        req: &dyn ExpandVolumeInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError>;
+    /// Set the volume labels
+    async fn set_labels(
+        &self,
+        req: &dyn SetVolumeLabelsInfo,
+        ctx: Option<Context>,
+    ) -> Result<Volume, ReplyError>;
+    /// Create a snapshot of a volume
+    async fn create_snapshot(
+        &self,
+        req: &dyn CreateSnapshotInfo,
+        ctx: Option<Context>,
+    ) -> Result<VolumeSnapshot, ReplyError>;
+    /// List the snapshots of a volume
+    async fn list_snapshots(
+        &self,
+        req: &dyn ListSnapshotsInfo,
+        ctx: Option<Context>,
+    ) -> Result<VolumeSnapshots, ReplyError>;
+    /// Delete a snapshot of a volume
+    async fn delete_snapshot(
+        &self,
+        req: &dyn DeleteSnapshotInfo,
+        ctx: Option<Context>,
+    ) -> Result<(), ReplyError>;
     /// Liveness probe for volume service
     async fn probe(&self, ctx: Option<Context>) -> Result<bool, ReplyError>;
 }
@@ -96,6 +150,14 @@ impl From<VolumeSpec> for volume::VolumeDefinition {
                 policy: Some(volume_spec.policy.into()),
                 topology: volume_spec.topology.map(|topology| topology.into()),
                 last_nexus_id: volume_spec.last_nexus_id.map(|id| id.to_string()),
+                events: volume_spec.events.into_iter().map(Into::into).collect(),
+                annotations: Some(crate::common::StringMapValue {
+                    value: volume_spec.annotations,
+                }),
+                encryption: volume_spec.encryption.map(Into::into),
+                idempotency_key: volume_spec.idempotency_key,
+                read_only: volume_spec.read_only,
+                source_snapshot: volume_spec.source_snapshot.map(|id| id.to_string()),
             }),
             metadata: Some(volume::Metadata {
                 spec_status: spec_status as i32,
@@ -114,6 +176,7 @@ impl From<Volume> for volume::Volume {
             status: status as i32,
             target: volume.state().target.map(|target| target.into()),
             replica_topology: to_grpc_replica_topology_map(volume.state().replica_topology),
+            target_refreshed: volume.state().target_refreshed.map(Into::into),
         };
         volume::Volume {
             definition: Some(volume_definition),
@@ -161,6 +224,20 @@ impl TryFrom<volume::VolumeDefinition> for VolumeSpec {
                 Some(labels) => Some(labels.value),
                 None => None,
             },
+            annotations: volume_spec
+                .annotations
+                .map(|annotations| annotations.value)
+                .unwrap_or_default(),
+            encryption: match volume_spec.encryption {
+                Some(encryption) => Some(ReplicaEncryption::try_from(encryption)?),
+                None => None,
+            },
+            idempotency_key: volume_spec.idempotency_key,
+            create_request: None,
+            source_snapshot: match volume_spec.source_snapshot {
+                Some(id) => Some(SnapshotId::try_from(StringValue(Some(id)))?),
+                None => None,
+            },
             num_replicas: volume_spec.num_replicas as u8,
             status: volume_spec_status,
             target: match volume_spec.target {
@@ -176,6 +253,7 @@ impl TryFrom<volume::VolumeDefinition> for VolumeSpec {
                 },
                 None => None,
             },
+            read_only: volume_spec.read_only,
             policy: match volume_spec.policy {
                 Some(policy) => policy.into(),
                 None => {
@@ -213,6 +291,11 @@ impl TryFrom<volume::VolumeDefinition> for VolumeSpec {
                 None => None,
             },
             operation: None,
+            events: volume_spec
+                .events
+                .into_iter()
+                .map(VolumeEvent::try_from)
+                .collect::<Result<_, _>>()?,
         };
         Ok(volume_spec)
     }
@@ -276,6 +359,19 @@ impl TryFrom<volume::Volume> for Volume {
                     ))
                 }
             },
+            target_refreshed: match grpc_volume_state.target_refreshed {
+                Some(timestamp) => match std::time::SystemTime::try_from(timestamp) {
+                    Ok(time) => Some(time),
+                    Err(err) => {
+                        return Err(ReplyError::invalid_argument(
+                            ResourceKind::Volume,
+                            "volume.state.target_refreshed",
+                            format!("{:?}", err),
+                        ))
+                    }
+                },
+                None => None,
+            },
         };
         Ok(Volume::new(volume_spec, volume_state))
     }
@@ -370,6 +466,12 @@ impl TryFrom<volume::Topology> for Topology {
                 },
                 None => None,
             },
+            spread_topology_key: topology_grpc_type.spread_topology_key,
+            preferred_pools: topology_grpc_type
+                .preferred_pools
+                .into_iter()
+                .map(PoolId::from)
+                .collect(),
         };
         Ok(topo)
     }
@@ -380,6 +482,12 @@ impl From<Topology> for volume::Topology {
         volume::Topology {
             node: topology.node.map(|topo| topo.into()),
             pool: topology.pool.map(|topo| topo.into()),
+            spread_topology_key: topology.spread_topology_key,
+            preferred_pools: topology
+                .preferred_pools
+                .into_iter()
+                .map(|pool| pool.to_string())
+                .collect(),
         }
     }
 }
@@ -547,7 +655,8 @@ impl TryFrom<volume::VolumeTarget> for VolumeTarget {
                 },
                 None => None,
             },
-        );
+        )
+        .with_read_only(volume_target_grpc_type.read_only);
         Ok(target)
     }
 }
@@ -564,6 +673,7 @@ impl From<VolumeTarget> for volume::VolumeTarget {
                     Some(protocol as i32)
                 }
             },
+            read_only: target.read_only(),
         }
     }
 }
@@ -586,6 +696,90 @@ impl From<VolumeShareProtocol> for volume::VolumeShareProtocol {
     }
 }
 
+impl From<volume::VolumeEventType> for VolumeEventType {
+    fn from(src: volume::VolumeEventType) -> Self {
+        match src {
+            volume::VolumeEventType::Created => Self::Created,
+            volume::VolumeEventType::Published => Self::Published,
+            volume::VolumeEventType::Republished => Self::Republished,
+            volume::VolumeEventType::Unpublished => Self::Unpublished,
+            volume::VolumeEventType::Shared => Self::Shared,
+            volume::VolumeEventType::Unshared => Self::Unshared,
+            volume::VolumeEventType::ReplicaCountChanged => Self::ReplicaCountChanged,
+            volume::VolumeEventType::Degraded => Self::Degraded,
+            volume::VolumeEventType::Healed => Self::Healed,
+            volume::VolumeEventType::Expanded => Self::Expanded,
+            volume::VolumeEventType::PlacementWarning => Self::PlacementWarning,
+        }
+    }
+}
+
+impl From<VolumeEventType> for volume::VolumeEventType {
+    fn from(src: VolumeEventType) -> Self {
+        match src {
+            VolumeEventType::Created => Self::Created,
+            VolumeEventType::Published => Self::Published,
+            VolumeEventType::Republished => Self::Republished,
+            VolumeEventType::Unpublished => Self::Unpublished,
+            VolumeEventType::Shared => Self::Shared,
+            VolumeEventType::Unshared => Self::Unshared,
+            VolumeEventType::ReplicaCountChanged => Self::ReplicaCountChanged,
+            VolumeEventType::Degraded => Self::Degraded,
+            VolumeEventType::Healed => Self::Healed,
+            VolumeEventType::Expanded => Self::Expanded,
+            VolumeEventType::PlacementWarning => Self::PlacementWarning,
+        }
+    }
+}
+
+impl From<VolumeEvent> for volume::VolumeEvent {
+    fn from(src: VolumeEvent) -> Self {
+        let event: volume::VolumeEventType = src.event.into();
+        Self {
+            timestamp: Some(src.timestamp.into()),
+            event: event as i32,
+            detail: src.detail,
+        }
+    }
+}
+
+impl TryFrom<volume::VolumeEvent> for VolumeEvent {
+    type Error = ReplyError;
+    fn try_from(src: volume::VolumeEvent) -> Result<Self, Self::Error> {
+        Ok(Self {
+            timestamp: match src.timestamp {
+                Some(timestamp) => match std::time::SystemTime::try_from(timestamp) {
+                    Ok(time) => time,
+                    Err(err) => {
+                        return Err(ReplyError::invalid_argument(
+                            ResourceKind::Volume,
+                            "volume.definition.spec.events.timestamp",
+                            format!("{:?}", err),
+                        ))
+                    }
+                },
+                None => {
+                    return Err(ReplyError::missing_argument(
+                        ResourceKind::Volume,
+                        "volume.definition.spec.events.timestamp",
+                    ))
+                }
+            },
+            event: match volume::VolumeEventType::from_i32(src.event) {
+                Some(event) => event.into(),
+                None => {
+                    return Err(ReplyError::invalid_argument(
+                        ResourceKind::Volume,
+                        "volume.definition.spec.events.event",
+                        "".to_string(),
+                    ))
+                }
+            },
+            detail: src.detail,
+        })
+    }
+}
+
 impl TryFrom<get_volumes_request::Filter> for Filter {
     type Error = ReplyError;
     fn try_from(filter: get_volumes_request::Filter) -> Result<Self, Self::Error> {
@@ -593,6 +787,9 @@ impl TryFrom<get_volumes_request::Filter> for Filter {
             get_volumes_request::Filter::Volume(volume_filter) => Filter::Volume(
                 VolumeId::try_from(StringValue(Some(volume_filter.volume_id)))?,
             ),
+            get_volumes_request::Filter::VolumeAnnotations(annotations_filter) => {
+                Filter::VolumeAnnotations(annotations_filter.annotations)
+            }
         })
     }
 }
@@ -611,6 +808,14 @@ pub trait CreateVolumeInfo: Send + Sync + std::fmt::Debug {
     fn topology(&self) -> Option<Topology>;
     /// Labels to be added to the volumes for topology based scheduling
     fn labels(&self) -> Option<VolumeLabels>;
+    /// Free-form annotations of the volume, not used for scheduling
+    fn annotations(&self) -> VolumeAnnotations;
+    /// At-rest encryption of the data of all the volume replicas
+    fn encryption(&self) -> Option<ReplicaEncryption>;
+    /// Key which makes retries of the volume creation safe
+    fn idempotency_key(&self) -> Option<String>;
+    /// Snapshot whose replicas are cloned to provision the volume
+    fn source_snapshot(&self) -> Option<SnapshotId>;
 }
 
 impl CreateVolumeInfo for CreateVolume {
@@ -637,6 +842,22 @@ impl CreateVolumeInfo for CreateVolume {
     fn labels(&self) -> Option<VolumeLabels> {
         self.labels.clone()
     }
+
+    fn annotations(&self) -> VolumeAnnotations {
+        self.annotations.clone()
+    }
+
+    fn encryption(&self) -> Option<ReplicaEncryption> {
+        self.encryption.clone()
+    }
+
+    fn idempotency_key(&self) -> Option<String> {
+        self.idempotency_key.clone()
+    }
+
+    fn source_snapshot(&self) -> Option<SnapshotId> {
+        self.source_snapshot.clone()
+    }
 }
 
 /// Intermediate structure that validates the conversion to CreateVolumeRequest type
@@ -645,6 +866,8 @@ pub struct ValidatedCreateVolumeRequest {
     inner: CreateVolumeRequest,
     uuid: VolumeId,
     topology: Option<Topology>,
+    encryption: Option<ReplicaEncryption>,
+    source_snapshot: Option<SnapshotId>,
 }
 
 impl CreateVolumeInfo for ValidatedCreateVolumeRequest {
@@ -677,6 +900,25 @@ impl CreateVolumeInfo for ValidatedCreateVolumeRequest {
             Some(labels) => Some(labels.value),
         }
     }
+
+    fn annotations(&self) -> VolumeAnnotations {
+        match self.inner.annotations.clone() {
+            None => VolumeAnnotations::new(),
+            Some(annotations) => annotations.value,
+        }
+    }
+
+    fn encryption(&self) -> Option<ReplicaEncryption> {
+        self.encryption.clone()
+    }
+
+    fn idempotency_key(&self) -> Option<String> {
+        self.inner.idempotency_key.clone()
+    }
+
+    fn source_snapshot(&self) -> Option<SnapshotId> {
+        self.source_snapshot.clone()
+    }
 }
 
 impl ValidateRequestTypes for CreateVolumeRequest {
@@ -697,6 +939,14 @@ impl ValidateRequestTypes for CreateVolumeRequest {
                 },
                 None => None,
             },
+            encryption: match self.encryption.clone() {
+                Some(encryption) => Some(ReplicaEncryption::try_from(encryption)?),
+                None => None,
+            },
+            source_snapshot: match self.source_snapshot.clone() {
+                Some(id) => Some(SnapshotId::try_from(StringValue(Some(id)))?),
+                None => None,
+            },
             inner: self,
         })
     }
@@ -711,6 +961,10 @@ impl From<&dyn CreateVolumeInfo> for CreateVolume {
             policy: data.policy(),
             topology: data.topology(),
             labels: data.labels(),
+            annotations: data.annotations(),
+            encryption: data.encryption(),
+            idempotency_key: data.idempotency_key(),
+            source_snapshot: data.source_snapshot(),
         }
     }
 }
@@ -726,6 +980,12 @@ impl From<&dyn CreateVolumeInfo> for CreateVolumeRequest {
             labels: data
                 .labels()
                 .map(|labels| crate::common::StringMapValue { value: labels }),
+            annotations: Some(crate::common::StringMapValue {
+                value: data.annotations(),
+            }),
+            encryption: data.encryption().map(Into::into),
+            idempotency_key: data.idempotency_key(),
+            source_snapshot: data.source_snapshot().map(|id| id.to_string()),
         }
     }
 }
@@ -905,6 +1165,12 @@ pub trait PublishVolumeInfo: Send + Sync + std::fmt::Debug {
     fn target_node(&self) -> Option<NodeId>;
     /// The protocol over which volume be published
     fn share(&self) -> Option<VolumeShareProtocol>;
+    /// NVMe-oF transport parameters used to create the volume target
+    fn transport_params(&self) -> NvmfTransportParams;
+    /// Publish the volume read-only
+    fn read_only(&self) -> bool;
+    /// Allow publishing read-write a volume which is currently published read-only
+    fn force_read_write(&self) -> bool;
 }
 
 impl PublishVolumeInfo for PublishVolume {
@@ -919,6 +1185,18 @@ impl PublishVolumeInfo for PublishVolume {
     fn share(&self) -> Option<VolumeShareProtocol> {
         self.share
     }
+
+    fn transport_params(&self) -> NvmfTransportParams {
+        self.transport_params.clone()
+    }
+
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn force_read_write(&self) -> bool {
+        self.force_read_write
+    }
 }
 
 /// Intermediate structure that validates the conversion to PublishVolumeRequest type
@@ -944,6 +1222,18 @@ impl PublishVolumeInfo for ValidatedPublishVolumeRequest {
     fn share(&self) -> Option<VolumeShareProtocol> {
         self.share
     }
+
+    fn transport_params(&self) -> NvmfTransportParams {
+        self.inner.transport_params.clone()
+    }
+
+    fn read_only(&self) -> bool {
+        self.inner.read_only
+    }
+
+    fn force_read_write(&self) -> bool {
+        self.inner.force_read_write
+    }
 }
 
 impl ValidateRequestTypes for PublishVolumeRequest {
@@ -975,6 +1265,9 @@ impl From<&dyn PublishVolumeInfo> for PublishVolume {
             uuid: data.uuid(),
             target_node: data.target_node(),
             share: data.share(),
+            transport_params: data.transport_params(),
+            read_only: data.read_only(),
+            force_read_write: data.force_read_write(),
         }
     }
 }
@@ -992,6 +1285,101 @@ impl From<&dyn PublishVolumeInfo> for PublishVolumeRequest {
             uuid: Some(data.uuid().to_string()),
             target_node: data.target_node().map(|node_id| node_id.to_string()),
             share,
+            transport_params: data.transport_params(),
+            read_only: data.read_only(),
+            force_read_write: data.force_read_write(),
+        }
+    }
+}
+
+/// Trait to be implemented for RepublishVolume operation
+pub trait RepublishVolumeInfo: Send + Sync + std::fmt::Debug {
+    /// Uuid of the volume to be republished
+    fn uuid(&self) -> VolumeId;
+    /// The node where front-end IO will be sent to
+    fn target_node(&self) -> NodeId;
+    /// The protocol over which volume be republished
+    fn share(&self) -> Option<VolumeShareProtocol>;
+}
+
+impl RepublishVolumeInfo for RepublishVolume {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+
+    fn target_node(&self) -> NodeId {
+        self.target_node.clone()
+    }
+
+    fn share(&self) -> Option<VolumeShareProtocol> {
+        self.share
+    }
+}
+
+/// Intermediate structure that validates the conversion to RepublishVolumeRequest type
+#[derive(Debug)]
+pub struct ValidatedRepublishVolumeRequest {
+    inner: RepublishVolumeRequest,
+    uuid: VolumeId,
+    share: Option<VolumeShareProtocol>,
+}
+
+impl RepublishVolumeInfo for ValidatedRepublishVolumeRequest {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+
+    fn target_node(&self) -> NodeId {
+        self.inner.target_node.clone().into()
+    }
+
+    fn share(&self) -> Option<VolumeShareProtocol> {
+        self.share
+    }
+}
+
+impl ValidateRequestTypes for RepublishVolumeRequest {
+    type Validated = ValidatedRepublishVolumeRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedRepublishVolumeRequest {
+            uuid: VolumeId::try_from(StringValue(self.uuid.clone()))?,
+            share: match self.share {
+                Some(share) => match volume::VolumeShareProtocol::from_i32(share) {
+                    Some(share) => Some(share.into()),
+                    None => {
+                        return Err(ReplyError::invalid_argument(
+                            ResourceKind::Volume,
+                            "republish_volume_request.share",
+                            "".to_string(),
+                        ))
+                    }
+                },
+                None => None,
+            },
+            inner: self,
+        })
+    }
+}
+
+impl From<&dyn RepublishVolumeInfo> for RepublishVolume {
+    fn from(data: &dyn RepublishVolumeInfo) -> Self {
+        Self::new(data.uuid(), data.target_node(), data.share())
+    }
+}
+
+impl From<&dyn RepublishVolumeInfo> for RepublishVolumeRequest {
+    fn from(data: &dyn RepublishVolumeInfo) -> Self {
+        let share: Option<i32> = match data.share() {
+            None => None,
+            Some(protocol) => {
+                let protocol: volume::VolumeShareProtocol = protocol.into();
+                Some(protocol as i32)
+            }
+        };
+        Self {
+            uuid: Some(data.uuid().to_string()),
+            target_node: data.target_node().to_string(),
+            share,
         }
     }
 }
@@ -1117,6 +1505,493 @@ impl From<&dyn SetVolumeReplicaInfo> for SetVolumeReplicaRequest {
     }
 }
 
+/// Trait to be implemented for the AddVolumeReplica and RemoveVolumeReplica operations
+pub trait VolumeReplicaInfo: Send + Sync + std::fmt::Debug {
+    /// Uuid of the concerned volume
+    fn uuid(&self) -> VolumeId;
+    /// Uuid of the replica to be added to or removed from the volume
+    fn replica(&self) -> ReplicaId;
+}
+
+impl VolumeReplicaInfo for AddVolumeReplica {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+
+    fn replica(&self) -> ReplicaId {
+        self.replica.clone()
+    }
+}
+
+impl VolumeReplicaInfo for RemoveVolumeReplica {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+
+    fn replica(&self) -> ReplicaId {
+        self.replica.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to AddVolumeReplicaRequest and
+/// RemoveVolumeReplicaRequest types
+#[derive(Debug)]
+pub struct ValidatedVolumeReplicaRequest {
+    uuid: VolumeId,
+    replica: ReplicaId,
+}
+
+impl VolumeReplicaInfo for ValidatedVolumeReplicaRequest {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+
+    fn replica(&self) -> ReplicaId {
+        self.replica.clone()
+    }
+}
+
+impl ValidateRequestTypes for AddVolumeReplicaRequest {
+    type Validated = ValidatedVolumeReplicaRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedVolumeReplicaRequest {
+            uuid: VolumeId::try_from(StringValue(self.uuid))?,
+            replica: ReplicaId::try_from(StringValue(self.replica_id))?,
+        })
+    }
+}
+
+impl ValidateRequestTypes for RemoveVolumeReplicaRequest {
+    type Validated = ValidatedVolumeReplicaRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedVolumeReplicaRequest {
+            uuid: VolumeId::try_from(StringValue(self.uuid))?,
+            replica: ReplicaId::try_from(StringValue(self.replica_id))?,
+        })
+    }
+}
+
+impl From<&dyn VolumeReplicaInfo> for AddVolumeReplica {
+    fn from(data: &dyn VolumeReplicaInfo) -> Self {
+        Self::new(data.uuid(), data.replica())
+    }
+}
+
+impl From<&dyn VolumeReplicaInfo> for RemoveVolumeReplica {
+    fn from(data: &dyn VolumeReplicaInfo) -> Self {
+        Self::new(data.uuid(), data.replica())
+    }
+}
+
+impl From<&dyn VolumeReplicaInfo> for AddVolumeReplicaRequest {
+    fn from(data: &dyn VolumeReplicaInfo) -> Self {
+        Self {
+            uuid: Some(data.uuid().to_string()),
+            replica_id: Some(data.replica().to_string()),
+        }
+    }
+}
+
+impl From<&dyn VolumeReplicaInfo> for RemoveVolumeReplicaRequest {
+    fn from(data: &dyn VolumeReplicaInfo) -> Self {
+        Self {
+            uuid: Some(data.uuid().to_string()),
+            replica_id: Some(data.replica().to_string()),
+        }
+    }
+}
+
+/// Trait to be implemented for ExpandVolume operation
+pub trait ExpandVolumeInfo: Send + Sync + std::fmt::Debug {
+    /// Uuid of the concerned volume
+    fn uuid(&self) -> VolumeId;
+    /// New size of the volume in bytes
+    fn size(&self) -> u64;
+}
+
+impl ExpandVolumeInfo for ExpandVolume {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Intermediate structure that validates the conversion to ExpandVolumeRequest type
+#[derive(Debug)]
+pub struct ValidatedExpandVolumeRequest {
+    inner: ExpandVolumeRequest,
+    uuid: VolumeId,
+}
+
+impl ExpandVolumeInfo for ValidatedExpandVolumeRequest {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+    fn size(&self) -> u64 {
+        self.inner.size
+    }
+}
+
+impl ValidateRequestTypes for ExpandVolumeRequest {
+    type Validated = ValidatedExpandVolumeRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedExpandVolumeRequest {
+            uuid: VolumeId::try_from(StringValue(self.uuid.clone()))?,
+            inner: self,
+        })
+    }
+}
+
+impl From<&dyn ExpandVolumeInfo> for ExpandVolume {
+    fn from(data: &dyn ExpandVolumeInfo) -> Self {
+        Self {
+            uuid: data.uuid(),
+            size: data.size(),
+        }
+    }
+}
+
+impl From<&dyn ExpandVolumeInfo> for ExpandVolumeRequest {
+    fn from(data: &dyn ExpandVolumeInfo) -> Self {
+        Self {
+            uuid: Some(data.uuid().to_string()),
+            size: data.size(),
+        }
+    }
+}
+
+/// Trait to be implemented for SetVolumeLabels operation
+pub trait SetVolumeLabelsInfo: Send + Sync + std::fmt::Debug {
+    /// Uuid of the concerned volume
+    fn uuid(&self) -> VolumeId;
+    /// New labels of the volume
+    fn labels(&self) -> VolumeLabels;
+    /// New annotations of the volume, if they're to be changed
+    fn annotations(&self) -> Option<VolumeAnnotations>;
+    /// Merge into the existing labels and annotations rather than replacing them
+    fn merge(&self) -> bool;
+}
+
+impl SetVolumeLabelsInfo for SetVolumeLabels {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+
+    fn labels(&self) -> VolumeLabels {
+        self.labels.clone()
+    }
+
+    fn annotations(&self) -> Option<VolumeAnnotations> {
+        self.annotations.clone()
+    }
+
+    fn merge(&self) -> bool {
+        self.merge
+    }
+}
+
+/// Intermediate structure that validates the conversion to SetVolumeLabelsRequest type
+#[derive(Debug)]
+pub struct ValidatedSetVolumeLabelsRequest {
+    inner: SetVolumeLabelsRequest,
+    uuid: VolumeId,
+}
+
+impl SetVolumeLabelsInfo for ValidatedSetVolumeLabelsRequest {
+    fn uuid(&self) -> VolumeId {
+        self.uuid.clone()
+    }
+    fn labels(&self) -> VolumeLabels {
+        match self.inner.labels.clone() {
+            None => VolumeLabels::new(),
+            Some(labels) => labels.value,
+        }
+    }
+    fn annotations(&self) -> Option<VolumeAnnotations> {
+        self.inner
+            .annotations
+            .clone()
+            .map(|annotations| annotations.value)
+    }
+    fn merge(&self) -> bool {
+        self.inner.merge
+    }
+}
+
+impl ValidateRequestTypes for SetVolumeLabelsRequest {
+    type Validated = ValidatedSetVolumeLabelsRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedSetVolumeLabelsRequest {
+            uuid: VolumeId::try_from(StringValue(self.uuid.clone()))?,
+            inner: self,
+        })
+    }
+}
+
+impl From<&dyn SetVolumeLabelsInfo> for SetVolumeLabels {
+    fn from(data: &dyn SetVolumeLabelsInfo) -> Self {
+        Self {
+            uuid: data.uuid(),
+            labels: data.labels(),
+            annotations: data.annotations(),
+            merge: data.merge(),
+        }
+    }
+}
+
+impl From<&dyn SetVolumeLabelsInfo> for SetVolumeLabelsRequest {
+    fn from(data: &dyn SetVolumeLabelsInfo) -> Self {
+        Self {
+            uuid: Some(data.uuid().to_string()),
+            labels: Some(crate::common::StringMapValue {
+                value: data.labels(),
+            }),
+            annotations: data
+                .annotations()
+                .map(|annotations| crate::common::StringMapValue { value: annotations }),
+            merge: data.merge(),
+        }
+    }
+}
+
+impl TryFrom<volume::VolumeSnapshot> for VolumeSnapshot {
+    type Error = ReplyError;
+    fn try_from(snapshot: volume::VolumeSnapshot) -> Result<Self, Self::Error> {
+        Ok(VolumeSnapshot {
+            snapshot_id: SnapshotId::try_from(StringValue(snapshot.snapshot_id))?,
+            volume_id: VolumeId::try_from(StringValue(snapshot.volume_id))?,
+            name: snapshot.name,
+            size: snapshot.size,
+            timestamp: match snapshot.timestamp {
+                Some(timestamp) => std::time::SystemTime::try_from(timestamp).map_err(|error| {
+                    ReplyError::invalid_argument(
+                        ResourceKind::VolumeSnapshot,
+                        "snapshot.timestamp",
+                        format!("{:?}", error),
+                    )
+                })?,
+                None => {
+                    return Err(ReplyError::missing_argument(
+                        ResourceKind::VolumeSnapshot,
+                        "snapshot.timestamp",
+                    ))
+                }
+            },
+        })
+    }
+}
+
+impl From<VolumeSnapshot> for volume::VolumeSnapshot {
+    fn from(snapshot: VolumeSnapshot) -> Self {
+        Self {
+            snapshot_id: Some(snapshot.snapshot_id.to_string()),
+            volume_id: Some(snapshot.volume_id.to_string()),
+            name: snapshot.name,
+            size: snapshot.size,
+            timestamp: Some(snapshot.timestamp.into()),
+        }
+    }
+}
+
+impl TryFrom<volume::VolumeSnapshots> for VolumeSnapshots {
+    type Error = ReplyError;
+    fn try_from(snapshots: volume::VolumeSnapshots) -> Result<Self, Self::Error> {
+        Ok(VolumeSnapshots {
+            entries: snapshots
+                .entries
+                .into_iter()
+                .map(VolumeSnapshot::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<VolumeSnapshots> for volume::VolumeSnapshots {
+    fn from(snapshots: VolumeSnapshots) -> Self {
+        Self {
+            entries: snapshots.entries.into_iter().map(From::from).collect(),
+        }
+    }
+}
+
+/// Trait to be implemented for CreateSnapshot operation
+pub trait CreateSnapshotInfo: Send + Sync + std::fmt::Debug {
+    /// Uuid of the concerned volume
+    fn volume_id(&self) -> VolumeId;
+    /// Uuid of the snapshot
+    fn snapshot_id(&self) -> SnapshotId;
+}
+
+impl CreateSnapshotInfo for CreateSnapshot {
+    fn volume_id(&self) -> VolumeId {
+        self.volume_id.clone()
+    }
+
+    fn snapshot_id(&self) -> SnapshotId {
+        self.snapshot_id.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to CreateSnapshotRequest type
+#[derive(Debug)]
+pub struct ValidatedCreateSnapshotRequest {
+    volume_id: VolumeId,
+    snapshot_id: SnapshotId,
+}
+
+impl CreateSnapshotInfo for ValidatedCreateSnapshotRequest {
+    fn volume_id(&self) -> VolumeId {
+        self.volume_id.clone()
+    }
+
+    fn snapshot_id(&self) -> SnapshotId {
+        self.snapshot_id.clone()
+    }
+}
+
+impl ValidateRequestTypes for CreateSnapshotRequest {
+    type Validated = ValidatedCreateSnapshotRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedCreateSnapshotRequest {
+            volume_id: VolumeId::try_from(StringValue(self.volume_id))?,
+            snapshot_id: SnapshotId::try_from(StringValue(self.snapshot_id))?,
+        })
+    }
+}
+
+impl From<&dyn CreateSnapshotInfo> for CreateSnapshot {
+    fn from(data: &dyn CreateSnapshotInfo) -> Self {
+        Self {
+            volume_id: data.volume_id(),
+            snapshot_id: data.snapshot_id(),
+        }
+    }
+}
+
+impl From<&dyn CreateSnapshotInfo> for CreateSnapshotRequest {
+    fn from(data: &dyn CreateSnapshotInfo) -> Self {
+        Self {
+            volume_id: Some(data.volume_id().to_string()),
+            snapshot_id: Some(data.snapshot_id().to_string()),
+        }
+    }
+}
+
+/// Trait to be implemented for ListSnapshots operation
+pub trait ListSnapshotsInfo: Send + Sync + std::fmt::Debug {
+    /// Uuid of the concerned volume
+    fn volume_id(&self) -> VolumeId;
+}
+
+impl ListSnapshotsInfo for ListSnapshots {
+    fn volume_id(&self) -> VolumeId {
+        self.volume_id.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to ListSnapshotsRequest type
+#[derive(Debug)]
+pub struct ValidatedListSnapshotsRequest {
+    volume_id: VolumeId,
+}
+
+impl ListSnapshotsInfo for ValidatedListSnapshotsRequest {
+    fn volume_id(&self) -> VolumeId {
+        self.volume_id.clone()
+    }
+}
+
+impl ValidateRequestTypes for ListSnapshotsRequest {
+    type Validated = ValidatedListSnapshotsRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedListSnapshotsRequest {
+            volume_id: VolumeId::try_from(StringValue(self.volume_id))?,
+        })
+    }
+}
+
+impl From<&dyn ListSnapshotsInfo> for ListSnapshots {
+    fn from(data: &dyn ListSnapshotsInfo) -> Self {
+        Self {
+            volume_id: data.volume_id(),
+        }
+    }
+}
+
+impl From<&dyn ListSnapshotsInfo> for ListSnapshotsRequest {
+    fn from(data: &dyn ListSnapshotsInfo) -> Self {
+        Self {
+            volume_id: Some(data.volume_id().to_string()),
+        }
+    }
+}
+
+/// Trait to be implemented for DeleteSnapshot operation
+pub trait DeleteSnapshotInfo: Send + Sync + std::fmt::Debug {
+    /// Uuid of the concerned volume
+    fn volume_id(&self) -> VolumeId;
+    /// Uuid of the snapshot
+    fn snapshot_id(&self) -> SnapshotId;
+}
+
+impl DeleteSnapshotInfo for DeleteSnapshot {
+    fn volume_id(&self) -> VolumeId {
+        self.volume_id.clone()
+    }
+
+    fn snapshot_id(&self) -> SnapshotId {
+        self.snapshot_id.clone()
+    }
+}
+
+/// Intermediate structure that validates the conversion to DeleteSnapshotRequest type
+#[derive(Debug)]
+pub struct ValidatedDeleteSnapshotRequest {
+    volume_id: VolumeId,
+    snapshot_id: SnapshotId,
+}
+
+impl DeleteSnapshotInfo for ValidatedDeleteSnapshotRequest {
+    fn volume_id(&self) -> VolumeId {
+        self.volume_id.clone()
+    }
+
+    fn snapshot_id(&self) -> SnapshotId {
+        self.snapshot_id.clone()
+    }
+}
+
+impl ValidateRequestTypes for DeleteSnapshotRequest {
+    type Validated = ValidatedDeleteSnapshotRequest;
+    fn validated(self) -> Result<Self::Validated, ReplyError> {
+        Ok(ValidatedDeleteSnapshotRequest {
+            volume_id: VolumeId::try_from(StringValue(self.volume_id))?,
+            snapshot_id: SnapshotId::try_from(StringValue(self.snapshot_id))?,
+        })
+    }
+}
+
+impl From<&dyn DeleteSnapshotInfo> for DeleteSnapshot {
+    fn from(data: &dyn DeleteSnapshotInfo) -> Self {
+        Self {
+            volume_id: data.volume_id(),
+            snapshot_id: data.snapshot_id(),
+        }
+    }
+}
+
+impl From<&dyn DeleteSnapshotInfo> for DeleteSnapshotRequest {
+    fn from(data: &dyn DeleteSnapshotInfo) -> Self {
+        Self {
+            volume_id: Some(data.volume_id().to_string()),
+            snapshot_id: Some(data.snapshot_id().to_string()),
+        }
+    }
+}
+
 /// A helper to convert the replica topology map form grpc type to corresponding control plane type
 fn to_replica_topology_map(
     map: HashMap<String, volume::ReplicaTopology>,
@@ -1170,3 +2045,24 @@ impl TryFrom<StringValue> for VolumeId {
         }
     }
 }
+
+impl TryFrom<StringValue> for SnapshotId {
+    type Error = ReplyError;
+
+    fn try_from(value: StringValue) -> Result<Self, Self::Error> {
+        match value.0 {
+            Some(id) => match SnapshotId::try_from(id) {
+                Ok(snapshot_id) => Ok(snapshot_id),
+                Err(err) => Err(ReplyError::invalid_argument(
+                    ResourceKind::VolumeSnapshot,
+                    "snapshot_id",
+                    err.to_string(),
+                )),
+            },
+            None => Err(ReplyError::missing_argument(
+                ResourceKind::VolumeSnapshot,
+                "snapshot_id",
+            )),
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use crate::context::FailoverChannel;
+use common_lib::mbus_api::traceparent_context;
 use opentelemetry::{
     global,
+    propagation::Injector,
     trace::{FutureExt, SpanKind, TraceContextExt, Tracer},
     KeyValue,
 };
@@ -7,8 +10,8 @@ use opentelemetry_http::HeaderInjector;
 use opentelemetry_semantic_conventions::trace::{HTTP_STATUS_CODE, RPC_GRPC_STATUS_CODE};
 use std::{future::Future, pin::Pin};
 use tonic::{
-    codegen::http::{Request, Response},
-    transport::Channel,
+    codegen::http::{HeaderMap, Request, Response},
+    metadata::{Ascii, MetadataKey, MetadataMap, MetadataValue},
 };
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
@@ -40,13 +43,13 @@ impl<S> OpenTelClientService<S> {
     }
 }
 
-type TonicClientRequest =
+pub(crate) type TonicClientRequest =
     Request<http_body::combinators::BoxBody<prost::bytes::Bytes, tonic::Status>>;
-type BoxedFuture<Resp, Err> = Pin<Box<dyn Future<Output = Result<Resp, Err>> + Send>>;
+pub(crate) type BoxedFuture<Resp, Err> = Pin<Box<dyn Future<Output = Result<Resp, Err>> + Send>>;
 
-impl tower::Service<TonicClientRequest> for OpenTelClientService<Channel> {
-    type Response = <Channel as tower::Service<TonicClientRequest>>::Response;
-    type Error = <Channel as tower::Service<TonicClientRequest>>::Error;
+impl tower::Service<TonicClientRequest> for OpenTelClientService<FailoverChannel> {
+    type Response = <FailoverChannel as tower::Service<TonicClientRequest>>::Response;
+    type Error = <FailoverChannel as tower::Service<TonicClientRequest>>::Error;
     type Future = BoxedFuture<Self::Response, Self::Error>;
 
     fn poll_ready(
@@ -58,7 +61,7 @@ impl tower::Service<TonicClientRequest> for OpenTelClientService<Channel> {
 
     fn call(&mut self, mut request: TonicClientRequest) -> Self::Future {
         let tracer = global::tracer("grpc-client");
-        let context = tracing::Span::current().context();
+        let context = client_parent_context(request.headers());
 
         let span = tracer
             .span_builder(format!("{}", request.uri()))
@@ -79,6 +82,29 @@ impl tower::Service<TonicClientRequest> for OpenTelClientService<Channel> {
     }
 }
 
+/// Get the parent of the client span: the trace context which the request already carries, eg:
+/// from the `traceparent` of its `Context`, otherwise the current span.
+pub(crate) fn client_parent_context(headers: &HeaderMap) -> opentelemetry::Context {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    match header("traceparent") {
+        Some(traceparent) => traceparent_context(traceparent, header("tracestate")),
+        None => tracing::Span::current().context(),
+    }
+}
+
+/// Inject a trace context into the gRPC request metadata
+pub(crate) struct MetadataInjector<'a>(pub(crate) &'a mut MetadataMap);
+impl Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            MetadataKey::<Ascii>::from_bytes(key.as_bytes()),
+            MetadataValue::<Ascii>::from_str(&value),
+        ) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
 /// Extract OpenTelemetry Spans from Http Headers
 #[derive(Clone, Default)]
 pub struct OpenTelServer {}
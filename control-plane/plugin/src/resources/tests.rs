@@ -34,6 +34,10 @@ async fn setup() {
                 size: 5242880,
                 topology: None,
                 labels: None,
+                annotations: None,
+                encryption: None,
+                idempotency_key: None,
+                source_snapshot: None,
             },
         )
         .await
@@ -73,7 +77,7 @@ async fn get_volumes() {
         .await
         .rest_v00()
         .volumes_api()
-        .get_volumes(0, None)
+        .get_volumes(0, None, None)
         .await
         .unwrap();
     let volume_state = volumes.entries[0].state.clone();
@@ -104,6 +108,10 @@ async fn get_volumes_paginated() {
                     size: 5242880,
                     topology: None,
                     labels: None,
+                    annotations: None,
+                    encryption: None,
+                    idempotency_key: None,
+                    source_snapshot: None,
                 },
             )
             .await
@@ -114,7 +122,7 @@ async fn get_volumes_paginated() {
         .await
         .rest_v00()
         .volumes_api()
-        .get_volumes(0, None)
+        .get_volumes(0, None, None)
         .await
         .unwrap()
         .entries
@@ -131,7 +139,7 @@ async fn get_volumes_paginated() {
             .await
             .rest_v00()
             .volumes_api()
-            .get_volumes(max_entries, starting_token)
+            .get_volumes(max_entries, starting_token, None)
             .await
             .unwrap();
         // The number of returned volumes should be equal to the number of specified max entries.
@@ -161,7 +169,7 @@ async fn get_pools() {
         .await
         .rest_v00()
         .pools_api()
-        .get_pools()
+        .get_pools(None)
         .await
         .unwrap();
     let pool_state = pools[0].state.as_ref().unwrap().clone();
@@ -0,0 +1,191 @@
+use actix_web::{
+    body::{BodySize, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderValue, CONTENT_ENCODING},
+    Error,
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+/// Default minimum size, in bytes, of a response body before it gets compressed
+pub(crate) const DEFAULT_COMPRESS_MIN_SIZE: &str = "1024";
+
+/// The content encoding which marks a response to be left uncompressed
+const IDENTITY: &str = "identity";
+
+/// Middleware which prevents the compression of small response bodies.
+/// This must be wrapped by the actix `Compress` middleware, which leaves alone any responses
+/// which already have a content encoding set: since the compression of small bodies yields
+/// little to no savings for the added overhead, they're marked as `identity` encoded.
+/// The `Compress` middleware must in turn be wrapped by `StripIdentityEncoding`, which removes
+/// the mark before the response is sent.
+pub(crate) struct CompressMinSize {
+    min_size: u64,
+}
+
+impl CompressMinSize {
+    /// Return a new `Self` which skips compression for bodies smaller than `min_size` bytes
+    pub(crate) fn new(min_size: u64) -> Self {
+        Self { min_size }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CompressMinSize
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = CompressMinSizeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CompressMinSizeMiddleware {
+            service,
+            min_size: self.min_size,
+        }))
+    }
+}
+
+/// The `CompressMinSize` middleware service
+pub(crate) struct CompressMinSizeMiddleware<S> {
+    service: S,
+    min_size: u64,
+}
+
+impl<S, B> Service<ServiceRequest> for CompressMinSizeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let min_size = self.min_size;
+        let response = self.service.call(request);
+        Box::pin(async move {
+            let mut response = response.await?;
+            let small = match response.response().body().size() {
+                BodySize::None => true,
+                BodySize::Sized(size) => size < min_size,
+                // streams are compressed as we don't know how big they will be
+                BodySize::Stream => false,
+            };
+            if small && !response.headers().contains_key(CONTENT_ENCODING) {
+                response
+                    .headers_mut()
+                    .insert(CONTENT_ENCODING, HeaderValue::from_static(IDENTITY));
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Middleware which removes the `identity` content encoding set by `CompressMinSize`, as it's
+/// not a valid value for the `Content-Encoding` header.
+/// This must wrap the actix `Compress` middleware.
+pub(crate) struct StripIdentityEncoding;
+
+impl<S, B> Transform<S, ServiceRequest> for StripIdentityEncoding
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = StripIdentityEncodingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(StripIdentityEncodingMiddleware { service }))
+    }
+}
+
+/// The `StripIdentityEncoding` middleware service
+pub(crate) struct StripIdentityEncodingMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for StripIdentityEncodingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let response = self.service.call(request);
+        Box::pin(async move {
+            let mut response = response.await?;
+            let encoding = response.headers().get(CONTENT_ENCODING);
+            if encoding
+                .map(|encoding| encoding == IDENTITY)
+                .unwrap_or_default()
+            {
+                response.headers_mut().remove(CONTENT_ENCODING);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompressMinSize, StripIdentityEncoding};
+    use actix_web::{
+        http::header::{ACCEPT_ENCODING, CONTENT_ENCODING},
+        middleware::Compress,
+        test, web, App, HttpResponse,
+    };
+
+    #[actix_web::test]
+    async fn compress_min_size() {
+        let app = test::init_service(
+            App::new()
+                .wrap(CompressMinSize::new(1024))
+                .wrap(Compress::default())
+                .wrap(StripIdentityEncoding)
+                .route(
+                    "/small",
+                    web::get().to(|| async { HttpResponse::Ok().body("a".repeat(1023)) }),
+                )
+                .route(
+                    "/large",
+                    web::get().to(|| async { HttpResponse::Ok().body("a".repeat(1024)) }),
+                ),
+        )
+        .await;
+
+        // a body below the minimum size is left uncompressed, without a content encoding
+        let request = test::TestRequest::get()
+            .uri("/small")
+            .insert_header((ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(test::read_body(response).await, "a".repeat(1023));
+
+        // whereas a body of at least the minimum size is compressed
+        let request = test::TestRequest::get()
+            .uri("/large")
+            .insert_header((ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert!(test::read_body(response).await.len() < 1024);
+    }
+}
@@ -1,7 +1,8 @@
 mod authentication;
+mod compression;
 mod v0;
 
-use crate::v0::{CORE_CLIENT, JSON_GRPC_CLIENT};
+use crate::v0::{PageLimits, CORE_CLIENT, JSON_GRPC_CLIENT, PAGE_LIMITS};
 use actix_service::ServiceFactory;
 use actix_web::{
     body::MessageBody,
@@ -28,9 +29,59 @@ pub(crate) struct CliArgs {
     #[structopt(long, short)]
     nats: Option<String>,
 
+    /// The username used to authenticate with the Nats Server.
+    /// Requires `--nats-password`
+    #[structopt(long, requires = "nats-password")]
+    nats_user: Option<String>,
+
+    /// The password used to authenticate with the Nats Server.
+    /// Requires `--nats-user`
+    #[structopt(
+        long,
+        env = "NATS_PASSWORD",
+        hide_env_values = true,
+        requires = "nats-user"
+    )]
+    nats_password: Option<BusSecret>,
+
+    /// The token used to authenticate with the Nats Server
+    #[structopt(long, env = "NATS_TOKEN", hide_env_values = true)]
+    nats_token: Option<BusSecret>,
+
+    /// The Nats credentials file, holding the user JWT and NKey seed, used to authenticate with
+    /// the Nats Server
+    #[structopt(long)]
+    nats_creds: Option<std::path::PathBuf>,
+
+    /// The PEM encoded CA certificate used to verify the Nats Server's certificate
+    #[structopt(long)]
+    nats_ca: Option<std::path::PathBuf>,
+
+    /// The PEM encoded client certificate used to authenticate with the Nats Server.
+    /// Requires `--nats-key`
+    #[structopt(long, requires = "nats-key")]
+    nats_cert: Option<std::path::PathBuf>,
+
+    /// The PEM encoded client key used to authenticate with the Nats Server.
+    /// Requires `--nats-cert`
+    #[structopt(long, requires = "nats-cert")]
+    nats_key: Option<std::path::PathBuf>,
+
+    /// Connect to the Nats Server over TLS without verifying its certificate (for testing)
+    #[structopt(long)]
+    nats_insecure_skip_verify: bool,
+
     /// The CORE gRPC Server URL or address to connect to the services.
-    #[structopt(long, short = "z", default_value = DEFAULT_GRPC_CLIENT_ADDR)]
-    core_grpc: Uri,
+    /// Multiple comma separated candidates may be specified, eg: one per core agent instance in
+    /// an HA setup, in which case the requests fail over to the next endpoint whenever the
+    /// current one cannot be connected to.
+    #[structopt(
+        long,
+        short = "z",
+        default_value = DEFAULT_GRPC_CLIENT_ADDR,
+        value_delimiter = ","
+    )]
+    core_grpc: Vec<Uri>,
 
     /// The json gRPC Server URL or address to connect to the service.
     #[structopt(long, short = "J")]
@@ -70,6 +121,24 @@ pub(crate) struct CliArgs {
     /// Don't use minimum timeouts for specific requests
     #[structopt(long)]
     no_min_timeouts: bool,
+
+    /// Minimum size, in bytes, of a response body before it gets compressed, as per the
+    /// client's `Accept-Encoding`
+    #[structopt(long, default_value = compression::DEFAULT_COMPRESS_MIN_SIZE)]
+    compress_min_size: u64,
+
+    /// Number of entries returned by a paginated request which doesn't specify `max_entries`
+    #[structopt(long, default_value = v0::DEFAULT_PAGE_SIZE)]
+    default_page_size: u64,
+
+    /// Maximum number of entries returned by a paginated request, even if more are requested
+    #[structopt(long, default_value = v0::MAX_PAGE_SIZE)]
+    max_page_size: u64,
+
+    /// Don't limit the number of entries returned by paginated requests, which then return all
+    /// entries when `max_entries` is 0
+    #[structopt(long)]
+    no_page_limits: bool,
 }
 impl CliArgs {
     fn args() -> Self {
@@ -77,10 +146,32 @@ impl CliArgs {
     }
 }
 
+/// connection options for the message bus, eg: its credentials and TLS
+fn bus_connect_options() -> BusConnectOptions {
+    let args = CliArgs::args();
+    BusConnectOptions::new(
+        args.nats_user.clone(),
+        args.nats_password.clone(),
+        args.nats_token.clone(),
+        args.nats_creds.clone(),
+    )
+    .and_then(|options| {
+        options.with_tls(
+            args.nats_ca,
+            args.nats_cert,
+            args.nats_key,
+            args.nats_insecure_skip_verify,
+        )
+    })
+    .expect("Should have valid message bus credentials and TLS configuration")
+}
+
 /// default timeout options for every bus request
 fn bus_timeout_opts() -> TimeoutOptions {
-    let timeout_opts =
-        TimeoutOptions::new_no_retries().with_timeout(CliArgs::args().request_timeout.into());
+    let message_timeouts = MessageTimeouts::from_env().expect("Invalid message timeout overrides");
+    let timeout_opts = TimeoutOptions::new_no_retries()
+        .with_timeout(CliArgs::args().request_timeout.into())
+        .with_message_timeouts(message_timeouts);
 
     if CliArgs::args().no_min_timeouts {
         timeout_opts.with_req_timeout(None)
@@ -92,9 +183,11 @@ fn bus_timeout_opts() -> TimeoutOptions {
 use actix_web_opentelemetry::RequestTracing;
 use common_lib::{
     mbus_api,
-    mbus_api::{BusClient, RequestMinTimeout, TimeoutOptions},
+    mbus_api::{
+        BusClient, BusConnectOptions, BusSecret, MessageTimeouts, RequestMinTimeout, TimeoutOptions,
+    },
 };
-use grpc::{client::CoreClient, operations::jsongrpc::client::JsonGrpcClient};
+use grpc::{client::CoreClient, context::Endpoints, operations::jsongrpc::client::JsonGrpcClient};
 use http::Uri;
 use opentelemetry::{global, KeyValue};
 
@@ -188,8 +281,12 @@ async fn main() -> anyhow::Result<()> {
         cli_args.jaeger.clone(),
     );
 
+    let compress_min_size = cli_args.compress_min_size;
     let app = move || {
         App::new()
+            .wrap(compression::CompressMinSize::new(compress_min_size))
+            .wrap(middleware::Compress::default())
+            .wrap(compression::StripIdentityEncoding)
             .wrap(RequestTracing::new())
             .wrap(middleware::Logger::default())
             .app_data(authentication::init(get_jwk_path()))
@@ -197,15 +294,32 @@ async fn main() -> anyhow::Result<()> {
     };
 
     if let Some(addr) = CliArgs::args().nats {
-        mbus_api::message_bus_init_options(BusClient::RestServer, addr, bus_timeout_opts()).await;
+        mbus_api::message_bus_init_options(
+            BusClient::RestServer,
+            addr,
+            bus_connect_options(),
+            bus_timeout_opts(),
+        )
+        .await
+        .expect("Should connect to the nats server");
     }
 
     // Initialise the core client to be used in rest
     CORE_CLIENT
-        .set(CoreClient::new(CliArgs::args().core_grpc, None).await)
+        .set(CoreClient::new(Endpoints::new(CliArgs::args().core_grpc), None).await)
         .ok()
         .expect("Expect to be initialised only once");
 
+    if !cli_args.no_page_limits {
+        PAGE_LIMITS
+            .set(PageLimits::new(
+                cli_args.default_page_size,
+                cli_args.max_page_size,
+            ))
+            .ok()
+            .expect("Expect to be initialised only once");
+    }
+
     // Initialise the json grpc client to be used in rest
     if CliArgs::args().json_grpc.is_some() {
         JSON_GRPC_CLIENT
@@ -1,14 +1,11 @@
 use super::*;
 use crate::v0::nexuses::nexus;
 use common_lib::types::v0::{
-    message_bus::{AddNexusChild, Child, ChildUri, Filter, Nexus, RemoveNexusChild},
+    message_bus::{AddNexusChild, CancelRebuild, Child, ChildUri, Filter, Nexus, RemoveNexusChild},
     openapi::apis::Uuid,
 };
-use grpc::operations::nexus::traits::NexusOperations;
-use mbus_api::{
-    message_bus::v0::{BusError, MessageBus, MessageBusTrait},
-    ReplyErrorKind, ResourceKind,
-};
+use grpc::operations::nexus::traits::{NexusOperations, RebuildOperations};
+use mbus_api::{message_bus::v0::BusError, ReplyErrorKind, ResourceKind};
 
 fn client() -> impl NexusOperations {
     core_grpc().nexus()
@@ -23,7 +20,7 @@ async fn get_children_response(
             Filter::Nexus(id) => Some(id.to_string()),
             _ => None,
         },
-        client().get(filter, None).await?.into_inner().get(0),
+        client().get(filter, None, None).await?.into_inner().get(0),
     )?;
     Ok(nexus.children.into_iter().map(From::from).collect())
 }
@@ -40,7 +37,7 @@ async fn get_child_response(
             Filter::Nexus(id) => Some(id.to_string()),
             _ => None,
         },
-        client().get(filter, None).await?.into_inner().get(0),
+        client().get(filter, None, None).await?.into_inner().get(0),
     )?;
     let child = find_nexus_child(&nexus, &child_id)?;
     Ok(child.into())
@@ -64,7 +61,8 @@ async fn add_child_filtered(
     query: &str,
     filter: Filter,
 ) -> Result<models::Child, RestError<RestJsonError>> {
-    let child_uri = build_child_uri(child_id, query);
+    let (query, auto_rebuild) = split_auto_rebuild(query)?;
+    let child_uri = build_child_uri(child_id, &query);
 
     let nexus = match nexus(
         match &filter {
@@ -72,7 +70,7 @@ async fn add_child_filtered(
             Filter::Nexus(id) => Some(id.to_string()),
             _ => None,
         },
-        client().get(filter, None).await?.into_inner().get(0),
+        client().get(filter, None, None).await?.into_inner().get(0),
     ) {
         Ok(nexus) => nexus,
         Err(error) => return Err(RestError::from(error)),
@@ -82,7 +80,8 @@ async fn add_child_filtered(
         node: nexus.node,
         nexus: nexus.uuid,
         uri: child_uri,
-        auto_rebuild: true,
+        auto_rebuild,
+        rebuild_source: None,
     };
     let child = client().add_nexus_child(&create, None).await?;
     Ok(child.into())
@@ -101,7 +100,7 @@ async fn delete_child_filtered(
             Filter::Nexus(id) => Some(id.to_string()),
             _ => None,
         },
-        client().get(filter, None).await?.into_inner().get(0),
+        client().get(filter, None, None).await?.into_inner().get(0),
     ) {
         Ok(nexus) => nexus,
         Err(error) => return Err(RestError::from(error)),
@@ -112,27 +111,43 @@ async fn delete_child_filtered(
         nexus: nexus.uuid,
         uri: child_uri,
     };
-    MessageBus::remove_nexus_child(destroy).await?;
+    client().remove_nexus_child(&destroy, None).await?;
     Ok(())
 }
 
-/// The child uri should be in the "percent-encode" format, but if it's not try to use
-/// the query string to build up the url
-fn build_child_uri(child_id: ChildUri, query: &str) -> ChildUri {
-    let child_id = child_id.to_string();
-    ChildUri::from(match url::Url::parse(child_id.as_str()) {
-        Ok(_) => {
-            if query.is_empty() {
-                child_id
-            } else {
-                format!("{}?{}", child_id, query)
+/// Split the `auto_rebuild` parameter out of the query string, returning the remaining query,
+/// which may still be part of a legacy child uri, and whether the child should be rebuilt
+/// automatically (defaults to true).
+fn split_auto_rebuild(query: &str) -> Result<(String, bool), BusError> {
+    let mut auto_rebuild = true;
+    let mut remaining = vec![];
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        match pair.strip_prefix("auto_rebuild=") {
+            Some(value) => {
+                auto_rebuild = value.parse().map_err(|_| BusError {
+                    kind: ReplyErrorKind::InvalidArgument,
+                    resource: ResourceKind::Child,
+                    source: "add_nexus_child".to_string(),
+                    extra: format!("invalid auto_rebuild value: '{}'", value),
+                })?;
             }
+            None => remaining.push(pair),
         }
+    }
+    Ok((remaining.join("&"), auto_rebuild))
+}
+
+/// The child uri should be in the "percent-encode" format, but if it's not try to use
+/// the query string to build up the url.
+/// The `child_id` has already been percent-decoded, so it must not be decoded again.
+fn build_child_uri(child_id: ChildUri, query: &str) -> ChildUri {
+    match url::Url::parse(child_id.as_str()) {
+        Ok(_) => child_id.with_query(query),
         _ => {
             // not a URL, it's probably legacy, default to AIO
-            format!("aio://{}", child_id)
+            ChildUri::from_decoded(format!("aio://{}", child_id))
         }
-    })
+    }
 }
 
 #[async_trait::async_trait]
@@ -144,6 +159,16 @@ impl apis::actix_server::Children for RestApi {
         delete_child_filtered(child_id.into(), query, Filter::Nexus(nexus_id.into())).await
     }
 
+    async fn del_nexus_child_rebuild(
+        query: &str,
+        Path((nexus_id, child_id)): Path<(Uuid, String)>,
+    ) -> Result<(), RestError<RestJsonError>> {
+        let child_uri = build_child_uri(child_id.into(), query);
+        let cancel = CancelRebuild::new(&nexus_id.into(), &child_uri);
+        core_grpc().rebuild().cancel_rebuild(&cancel, None).await?;
+        Ok(())
+    }
+
     async fn del_node_nexus_child(
         query: &str,
         Path((node_id, nexus_id, child_id)): Path<(String, Uuid, String)>,
@@ -206,3 +231,48 @@ impl apis::actix_server::Children for RestApi {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHILD_URI: &str = "nvmf://10.1.0.5:4420/nqn.2019-05.io.openebs:e6e7d39d-e343-42f7-936a-1ab05f1839db?uuid=b940f4f2-d45d-4404-8167-3b0366f9e2b0";
+    const CHILD_URI_ENCODED: &str = "nvmf%3A%2F%2F10.1.0.5%3A4420%2Fnqn.2019-05.io.openebs%3Ae6e7d39d-e343-42f7-936a-1ab05f1839db%3Fuuid%3Db940f4f2-d45d-4404-8167-3b0366f9e2b0";
+    const CHILD_UUID: &str = "b940f4f2-d45d-4404-8167-3b0366f9e2b0";
+
+    #[test]
+    fn child_uri_encoded() {
+        let (query, auto_rebuild) = split_auto_rebuild("auto_rebuild=false").unwrap();
+        let uri = build_child_uri(ChildUri::from(CHILD_URI_ENCODED), &query);
+        assert_eq!(uri.as_str(), CHILD_URI);
+        assert_eq!(uri.uuid_str().as_deref(), Some(CHILD_UUID));
+        assert!(!auto_rebuild);
+
+        // the extra query parameters extend the query of the child uri
+        let uri = build_child_uri(ChildUri::from(CHILD_URI_ENCODED), "size_mb=100");
+        assert_eq!(uri.as_str(), format!("{}&size_mb=100", CHILD_URI));
+        assert_eq!(uri.uuid_str().as_deref(), Some(CHILD_UUID));
+    }
+
+    #[test]
+    fn child_uri_query() {
+        // the query of a child uri which is not percent-encoded ends up in the query string
+        let (path, query) = CHILD_URI.split_once('?').unwrap();
+        let query = format!("{}&auto_rebuild=true", query);
+        let (query, auto_rebuild) = split_auto_rebuild(&query).unwrap();
+        let uri = build_child_uri(ChildUri::from(path), &query);
+        assert_eq!(uri.as_str(), CHILD_URI);
+        assert_eq!(uri.uuid_str().as_deref(), Some(CHILD_UUID));
+        assert!(auto_rebuild);
+    }
+
+    #[test]
+    fn child_uri_decoded_once() {
+        // "%25" is the encoding of '%' itself, which must not be decoded again
+        let uri = build_child_uri(ChildUri::from("aio:///dev/disk%2541"), "");
+        assert_eq!(uri.as_str(), "aio:///dev/disk%41");
+
+        let uri = build_child_uri(ChildUri::from("%2Fdev%2Fsda"), "");
+        assert_eq!(uri.as_str(), "aio:///dev/sda");
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+use common_lib::types::v0::message_bus::{Filter, NexusId, ReplicaId, VolumeId};
+use grpc::operations::registry::traits::RegistryOperations;
+use std::convert::TryFrom;
+
+fn client() -> impl RegistryOperations {
+    core_grpc().registry()
+}
+
+/// Get the filter of the events about the resource of the given `kind` and `id`, which must
+/// either both be specified or neither
+fn events_filter(kind: Option<String>, id: Option<String>) -> Result<Filter, ReplyError> {
+    fn invalid_id(kind: ResourceKind, error: impl std::fmt::Display) -> ReplyError {
+        ReplyError::invalid_argument(kind, "id", error.to_string())
+    }
+    Ok(match (kind.as_deref(), id) {
+        (None, None) => Filter::None,
+        (Some("node"), Some(id)) => Filter::Node(id.into()),
+        (Some("pool"), Some(id)) => Filter::Pool(id.into()),
+        (Some("replica"), Some(id)) => Filter::Replica(
+            ReplicaId::try_from(id.as_str()).map_err(|e| invalid_id(ResourceKind::Replica, e))?,
+        ),
+        (Some("nexus"), Some(id)) => Filter::Nexus(
+            NexusId::try_from(id.as_str()).map_err(|e| invalid_id(ResourceKind::Nexus, e))?,
+        ),
+        (Some("volume"), Some(id)) => Filter::Volume(
+            VolumeId::try_from(id.as_str()).map_err(|e| invalid_id(ResourceKind::Volume, e))?,
+        ),
+        (Some(kind), Some(_)) => {
+            return Err(ReplyError::invalid_argument(
+                ResourceKind::Unknown,
+                "kind",
+                format!(
+                    "expected one of node, pool, replica, nexus or volume but found '{}'",
+                    kind
+                ),
+            ))
+        }
+        (Some(_), None) => {
+            return Err(ReplyError::missing_argument(ResourceKind::Unknown, "id"));
+        }
+        (None, Some(_)) => {
+            return Err(ReplyError::missing_argument(ResourceKind::Unknown, "kind"));
+        }
+    })
+}
+
+#[async_trait::async_trait]
+impl apis::actix_server::Events for RestApi {
+    async fn get_events(
+        Query((kind, id)): Query<(Option<String>, Option<String>)>,
+    ) -> Result<Vec<models::Event>, RestError<RestJsonError>> {
+        let filter = events_filter(kind, id)?;
+        let events = client().get_events(filter, None).await?;
+        Ok(events.into_inner().into_iter().map(From::from).collect())
+    }
+}
@@ -0,0 +1,55 @@
+//! Provides a REST interface which summarises the health of the control plane.
+
+use super::*;
+use grpc::operations::{
+    jsongrpc::traits::JsonGrpcOperations, nexus::traits::NexusOperations,
+    node::traits::NodeOperations, pool::traits::PoolOperations,
+    registry::traits::RegistryOperations, replica::traits::ReplicaOperations,
+    volume::traits::VolumeOperations,
+};
+
+/// Get the health of a component from the result of its readiness probe.
+fn component_health(
+    name: &str,
+    probe: Result<bool, ReplyError>,
+    not_ready: &str,
+) -> models::ComponentHealth {
+    match probe {
+        Ok(true) => models::ComponentHealth::new(name, true),
+        Ok(false) => models::ComponentHealth::new_all(name, false, not_ready.to_string()),
+        Err(error) => models::ComponentHealth::new_all(name, false, error.to_string()),
+    }
+}
+
+#[async_trait::async_trait]
+impl apis::actix_server::Health for RestApi {
+    async fn get_health() -> Result<models::HealthSummary, RestError<RestJsonError>> {
+        let core = core_grpc();
+        let (volume, replica, pool, nexus, node, store) = futures::join!(
+            core.volume().probe(None),
+            core.replica().probe(None),
+            core.pool().probe(None),
+            core.nexus().probe(None),
+            core.node().probe(None),
+            core.registry().probe(None),
+        );
+        let mut components = vec![
+            component_health("volume", volume, "the volume service is not ready"),
+            component_health("replica", replica, "the replica service is not ready"),
+            component_health("pool", pool, "the pool service is not ready"),
+            component_health("nexus", nexus, "the nexus service is not ready"),
+            component_health("node", node, "the node service is not ready"),
+            component_health("store", store, "the persistent store is offline"),
+        ];
+        // the json-grpc service is optional, so it's only probed when it's configured
+        if let Some(json_grpc) = JSON_GRPC_CLIENT.get() {
+            components.push(component_health(
+                "jsongrpc",
+                json_grpc.probe(None).await,
+                "the json-grpc service is not ready",
+            ));
+        }
+        let healthy = components.iter().all(|component| component.healthy);
+        Ok(models::HealthSummary::new(healthy, components))
+    }
+}
@@ -28,6 +28,7 @@ impl apis::actix_server::JsonGrpc for RestApi {
                     node: node.into(),
                     method: method.into(),
                     params: body.to_string().into(),
+                    endpoint: None,
                 },
                 None,
             )
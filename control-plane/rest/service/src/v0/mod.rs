@@ -4,6 +4,8 @@
 
 pub mod block_devices;
 pub mod children;
+pub mod events;
+pub mod health;
 pub mod jsongrpc;
 pub mod nexuses;
 pub mod nodes;
@@ -35,12 +37,81 @@ use mbus_api::{ReplyError, ReplyErrorKind, ResourceKind};
 use once_cell::sync::OnceCell;
 use rest_client::versions::v0::*;
 use serde::Deserialize;
+use std::collections::HashMap;
 
 /// Once cell static variable to store the grpc client and initialise once at startup
 pub static CORE_CLIENT: OnceCell<CoreClient> = OnceCell::new();
 /// Once cell static variable to store the json grpc client and initialise once at startup
 pub static JSON_GRPC_CLIENT: OnceCell<JsonGrpcClient> = OnceCell::new();
 
+/// Default number of entries returned by a paginated request which doesn't specify how many
+pub(crate) const DEFAULT_PAGE_SIZE: &str = "500";
+/// Default maximum number of entries returned by a paginated request
+pub(crate) const MAX_PAGE_SIZE: &str = "1000";
+/// Once cell static variable to store the pagination limits and initialise once at startup
+/// If not set, pagination is unbounded
+pub static PAGE_LIMITS: OnceCell<PageLimits> = OnceCell::new();
+
+/// Server side limits of the number of entries returned by a paginated request
+#[derive(Debug, Clone, Copy)]
+pub struct PageLimits {
+    /// number of entries returned when the request doesn't specify it
+    default_size: u64,
+    /// maximum number of entries returned, regardless of how many are requested
+    max_size: u64,
+}
+
+impl PageLimits {
+    /// Return a new `Self` with the given default and maximum page sizes (at least 1 entry)
+    pub fn new(default_size: u64, max_size: u64) -> Self {
+        let max_size = max_size.max(1);
+        Self {
+            default_size: default_size.clamp(1, max_size),
+            max_size,
+        }
+    }
+}
+
+/// Get the number of entries to return for the requested `max_entries`, where 0 means the
+/// default page size, capped by the maximum page size.
+/// Returns None when pagination is disabled, ie: all entries should be returned.
+pub(crate) fn page_size(max_entries: isize) -> Option<u64> {
+    match PAGE_LIMITS.get() {
+        // no limits were configured, so 0 disables pagination
+        None => (max_entries > 0).then(|| max_entries as u64),
+        Some(limits) if max_entries > 0 => Some((max_entries as u64).min(limits.max_size)),
+        Some(limits) => Some(limits.default_size),
+    }
+}
+
+/// Parse the `name` query parameter from a comma separated list of `key=value` pairs
+pub(crate) fn parse_key_values(
+    query: Option<&str>,
+    name: &str,
+    resource: ResourceKind,
+) -> Result<HashMap<String, String>, ReplyError> {
+    let mut key_values = HashMap::new();
+    for pair in query
+        .unwrap_or_default()
+        .split(',')
+        .filter(|p| !p.is_empty())
+    {
+        match pair.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                key_values.insert(key.to_string(), value.to_string());
+            }
+            _ => {
+                return Err(ReplyError::invalid_argument(
+                    resource,
+                    name,
+                    format!("expected 'key=value' but found '{}'", pair),
+                ))
+            }
+        }
+    }
+    Ok(key_values)
+}
+
 /// Get Core gRPC Client
 pub(crate) fn core_grpc<'a>() -> &'a CoreClient {
     CORE_CLIENT
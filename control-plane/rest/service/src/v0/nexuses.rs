@@ -1,9 +1,16 @@
 use super::*;
 use common_lib::types::v0::{
-    message_bus::{DestroyNexus, Filter, ShareNexus, UnshareNexus},
+    message_bus::{
+        DestroyNexus, Filter, GetRebuildHistory, GetRebuilds, SetRebuildLimit, ShareNexus,
+        UnshareNexus,
+    },
     openapi::apis::Uuid,
 };
-use grpc::operations::nexus::traits::NexusOperations;
+use grpc::operations::{
+    nexus::traits::{NexusOperations, RebuildOperations},
+    registry::traits::RegistryOperations,
+    MaxEntries, Pagination, StartingToken,
+};
 use mbus_api::{
     message_bus::v0::{BusError, MessageBus, MessageBusTrait},
     ReplyErrorKind, ResourceKind,
@@ -77,7 +84,7 @@ impl apis::actix_server::Nexuses for RestApi {
         let nexus = nexus(
             Some(nexus_id.to_string()),
             client()
-                .get(Filter::Nexus(nexus_id.into()), None)
+                .get(Filter::Nexus(nexus_id.into()), None, None)
                 .await?
                 .into_inner()
                 .get(0),
@@ -85,9 +92,58 @@ impl apis::actix_server::Nexuses for RestApi {
         Ok(nexus.into())
     }
 
-    async fn get_nexuses() -> Result<Vec<models::Nexus>, RestError<RestJsonError>> {
-        let nexuses = client().get(Filter::None, None).await?;
-        Ok(nexuses.into_inner().into_iter().map(From::from).collect())
+    async fn get_nexus_rebuild_history(
+        Path(nexus_id): Path<Uuid>,
+    ) -> Result<models::RebuildHistory, RestError<RestJsonError>> {
+        let history = client()
+            .get_nexus_rebuild_history(&GetRebuildHistory::new(&nexus_id.into()), None)
+            .await?;
+        Ok(history.into())
+    }
+
+    async fn get_nexuses(
+        Query((max_entries, starting_token)): Query<(isize, Option<isize>)>,
+    ) -> Result<models::Nexuses, RestError<RestJsonError>> {
+        let starting_token = starting_token.unwrap_or_default();
+        let pagination = page_size(max_entries).map(|page_size| {
+            Pagination::new(page_size as MaxEntries, starting_token as StartingToken)
+        });
+        let nexuses = client().get(Filter::None, pagination, None).await?;
+        Ok(models::Nexuses {
+            entries: nexuses.entries.into_iter().map(From::from).collect(),
+            next_token: nexuses.next_token.map(|t| t as isize),
+        })
+    }
+
+    async fn get_rebuilds() -> Result<Vec<models::Rebuild>, RestError<RestJsonError>> {
+        let rebuilds = core_grpc()
+            .rebuild()
+            .get_rebuilds(&GetRebuilds::all(), None)
+            .await?;
+        Ok(rebuilds.into())
+    }
+
+    async fn get_rebuild_limit() -> Result<models::RebuildLimit, RestError<RestJsonError>> {
+        let limit = core_grpc().registry().get_rebuild_limit(None).await?;
+        Ok(limit.into())
+    }
+
+    async fn put_rebuild_limit(
+        Path(max_rebuilds): Path<u32>,
+    ) -> Result<models::RebuildLimit, RestError<RestJsonError>> {
+        let limit = core_grpc()
+            .registry()
+            .set_rebuild_limit(&SetRebuildLimit::new(Some(max_rebuilds)), None)
+            .await?;
+        Ok(limit.into())
+    }
+
+    async fn del_rebuild_limit() -> Result<models::RebuildLimit, RestError<RestJsonError>> {
+        let limit = core_grpc()
+            .registry()
+            .set_rebuild_limit(&SetRebuildLimit::new(None), None)
+            .await?;
+        Ok(limit.into())
     }
 
     async fn get_node_nexus(
@@ -96,7 +152,11 @@ impl apis::actix_server::Nexuses for RestApi {
         let nexus = nexus(
             Some(nexus_id.to_string()),
             client()
-                .get(Filter::NodeNexus(node_id.into(), nexus_id.into()), None)
+                .get(
+                    Filter::NodeNexus(node_id.into(), nexus_id.into()),
+                    None,
+                    None,
+                )
                 .await?
                 .into_inner()
                 .get(0),
@@ -107,7 +167,7 @@ impl apis::actix_server::Nexuses for RestApi {
     async fn get_node_nexuses(
         Path(id): Path<String>,
     ) -> Result<Vec<models::Nexus>, RestError<RestJsonError>> {
-        let nexuses = client().get(Filter::Node(id.into()), None).await?;
+        let nexuses = client().get(Filter::Node(id.into()), None, None).await?;
         Ok(nexuses.into_inner().into_iter().map(From::from).collect())
     }
 
@@ -129,6 +189,7 @@ impl apis::actix_server::Nexuses for RestApi {
             uuid: nexus_id.into(),
             key: None,
             protocol: protocol.into(),
+            transport_params: Default::default(),
         };
         let share_uri = client().share(&share, None).await?;
         Ok(share_uri)
@@ -1,5 +1,10 @@
 use super::*;
+use common_lib::types::v0::{
+    message_bus::{CordonNode, DrainNode, SetNodeTimeouts, UncordonNode},
+    store::node::NodeTimeouts,
+};
 use grpc::operations::node::traits::NodeOperations;
+use std::time::Duration;
 
 fn client() -> impl NodeOperations {
     core_grpc().node()
@@ -23,6 +28,47 @@ impl apis::actix_server::Nodes for RestApi {
         let nodes = client().get(Filter::None, None).await?;
         Ok(nodes.into_inner().into_vec())
     }
+
+    async fn put_node_cordon(
+        Path((id, label)): Path<(String, String)>,
+    ) -> Result<models::Node, RestError<RestJsonError>> {
+        let node = client()
+            .cordon(&CordonNode::new(id.into(), label), None)
+            .await?;
+        Ok(node.into())
+    }
+
+    async fn put_node_drain(
+        Path((id, label)): Path<(String, String)>,
+    ) -> Result<models::Node, RestError<RestJsonError>> {
+        let node = client()
+            .drain(&DrainNode::new(id.into(), label), None)
+            .await?;
+        Ok(node.into())
+    }
+
+    async fn put_node_timeouts(
+        Path(id): Path<String>,
+        Body(timeouts): Body<models::SetNodeTimeoutsBody>,
+    ) -> Result<models::Node, RestError<RestJsonError>> {
+        let timeouts = NodeTimeouts::new(
+            timeouts.connect_timeout.map(Duration::from_millis),
+            timeouts.request_timeout.map(Duration::from_millis),
+        );
+        let node = client()
+            .set_timeouts(&SetNodeTimeouts::new(id.into(), timeouts), None)
+            .await?;
+        Ok(node.into())
+    }
+
+    async fn delete_node_cordon(
+        Path((id, label)): Path<(String, String)>,
+    ) -> Result<models::Node, RestError<RestJsonError>> {
+        let node = client()
+            .uncordon(&UncordonNode::new(id.into(), label), None)
+            .await?;
+        Ok(node.into())
+    }
 }
 
 /// returns node from node option and returns an error on non existence
@@ -1,12 +1,19 @@
 use super::*;
-use common_lib::types::v0::message_bus::{DestroyPool, Filter};
-use grpc::operations::pool::traits::PoolOperations;
+use common_lib::types::v0::message_bus::{
+    CapacityForecast, DestroyPool, Filter, ImportPool, PoolConsistency, ReconcilePool,
+    SetPoolMaintenance, POOL_CONSISTENCY_THRESHOLD,
+};
+use grpc::operations::{pool::traits::PoolOperations, replica::traits::ReplicaOperations};
 use mbus_api::{message_bus::v0::BusError, ReplyErrorKind, ResourceKind};
 
 fn client() -> impl PoolOperations {
     core_grpc().pool()
 }
 
+fn replica_client() -> impl ReplicaOperations {
+    core_grpc().replica()
+}
+
 async fn destroy_pool(filter: Filter) -> Result<(), RestError<RestJsonError>> {
     let destroy = match filter.clone() {
         Filter::NodePool(node_id, pool_id) => DestroyPool {
@@ -36,6 +43,16 @@ async fn destroy_pool(filter: Filter) -> Result<(), RestError<RestJsonError>> {
     Ok(())
 }
 
+/// Error for when a capacity forecast can't be made as there are no usage samples yet
+fn no_forecast(source: &str, extra: String) -> RestError<RestJsonError> {
+    RestError::from(ReplyError {
+        kind: ReplyErrorKind::FailedPrecondition,
+        resource: ResourceKind::Pool,
+        source: source.to_string(),
+        extra,
+    })
+}
+
 #[async_trait::async_trait]
 impl apis::actix_server::Pools for RestApi {
     async fn del_node_pool(
@@ -48,6 +65,14 @@ impl apis::actix_server::Pools for RestApi {
         destroy_pool(Filter::Pool(pool_id.into())).await
     }
 
+    async fn del_pool_maintenance(
+        Path(pool_id): Path<String>,
+    ) -> Result<models::Pool, RestError<RestJsonError>> {
+        let request = SetPoolMaintenance::new(&pool_id.into(), false);
+        let pool = client().set_maintenance(&request, None).await?;
+        Ok(pool.into())
+    }
+
     async fn get_node_pool(
         Path((node_id, pool_id)): Path<(String, String)>,
     ) -> Result<models::Pool, RestError<RestJsonError>> {
@@ -83,8 +108,88 @@ impl apis::actix_server::Pools for RestApi {
         Ok(pool.into())
     }
 
-    async fn get_pools() -> Result<Vec<models::Pool>, RestError<RestJsonError>> {
-        let pools = client().get(Filter::None, None).await?;
+    async fn get_pool_consistency(
+        Path(pool_id): Path<String>,
+        Query(threshold): Query<Option<u64>>,
+    ) -> Result<models::PoolConsistency, RestError<RestJsonError>> {
+        let pool = pool(
+            pool_id.clone(),
+            client()
+                .get(Filter::Pool(pool_id.clone().into()), None)
+                .await?
+                .into_inner()
+                .get(0),
+        )?;
+        let state = match pool.state() {
+            Some(state) => state,
+            None => {
+                return Err(RestError::from(ReplyError {
+                    kind: ReplyErrorKind::FailedPrecondition,
+                    resource: ResourceKind::Pool,
+                    source: "get_pool_consistency".to_string(),
+                    extra: format!("Pool '{}' has no runtime state", pool_id),
+                }))
+            }
+        };
+        let replicas = replica_client()
+            .get(Filter::Pool(pool_id.into()), None, None)
+            .await?
+            .into_inner();
+        let consistency = PoolConsistency::new(
+            &state,
+            &replicas,
+            threshold.unwrap_or(POOL_CONSISTENCY_THRESHOLD),
+        );
+        Ok(consistency.into())
+    }
+
+    async fn get_pool_forecast(
+        Path(pool_id): Path<String>,
+    ) -> Result<models::CapacityForecast, RestError<RestJsonError>> {
+        let pool = pool(
+            pool_id.clone(),
+            client()
+                .get(Filter::Pool(pool_id.clone().into()), None)
+                .await?
+                .into_inner()
+                .get(0),
+        )?;
+        match pool.forecast() {
+            Some(forecast) => Ok(forecast.into()),
+            None => Err(no_forecast(
+                "get_pool_forecast",
+                format!("Pool '{}' has no capacity usage samples", pool_id),
+            )),
+        }
+    }
+
+    async fn get_capacity_forecast() -> Result<models::CapacityForecast, RestError<RestJsonError>> {
+        let forecasts = client()
+            .get(Filter::None, None)
+            .await?
+            .into_inner()
+            .into_iter()
+            .filter_map(|pool| pool.forecast())
+            .collect::<Vec<_>>();
+        match CapacityForecast::aggregate(&forecasts) {
+            Some(forecast) => Ok(forecast.into()),
+            None => Err(no_forecast(
+                "get_capacity_forecast",
+                "No pool has capacity usage samples".to_string(),
+            )),
+        }
+    }
+
+    async fn get_pools(
+        Query(label): Query<Option<String>>,
+    ) -> Result<Vec<models::Pool>, RestError<RestJsonError>> {
+        let selector = parse_key_values(label.as_deref(), "label", ResourceKind::Pool)?;
+        let filter = if selector.is_empty() {
+            Filter::None
+        } else {
+            Filter::PoolLabel(selector)
+        };
+        let pools = client().get(filter, None).await?;
         Ok(pools.into_inner().into_iter().map(From::from).collect())
     }
 
@@ -97,6 +202,42 @@ impl apis::actix_server::Pools for RestApi {
         let pool = client().create(&create, None).await?;
         Ok(pool.into())
     }
+
+    async fn put_node_pool_import(
+        Path((node_id, pool_id)): Path<(String, String)>,
+        Body(import_pool_body): Body<models::ImportPoolBody>,
+    ) -> Result<models::Pool, RestError<RestJsonError>> {
+        let disks = import_pool_body
+            .disks
+            .into_iter()
+            .map(From::from)
+            .collect::<Vec<_>>();
+        let import = ImportPool::new(
+            &node_id.into(),
+            &pool_id.into(),
+            &import_pool_body.uuid.into(),
+            &disks,
+            &import_pool_body.labels,
+        );
+        let pool = client().import(&import, None).await?;
+        Ok(pool.into())
+    }
+
+    async fn put_pool_maintenance(
+        Path(pool_id): Path<String>,
+    ) -> Result<models::Pool, RestError<RestJsonError>> {
+        let request = SetPoolMaintenance::new(&pool_id.into(), true);
+        let pool = client().set_maintenance(&request, None).await?;
+        Ok(pool.into())
+    }
+
+    async fn reconcile_pool(
+        Path(pool_id): Path<String>,
+    ) -> Result<models::Pool, RestError<RestJsonError>> {
+        let reconcile = ReconcilePool::new(&pool_id.into());
+        let pool = client().reconcile(&reconcile, None).await?;
+        Ok(pool.into())
+    }
 }
 
 /// returns pool from pool option and returns an error on non existence
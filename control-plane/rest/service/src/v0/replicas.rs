@@ -1,7 +1,13 @@
 use super::*;
 use crate::v0::pools::pool;
-use common_lib::{mbus_api::message_bus::v0::BusError, types::v0::openapi::apis::Uuid};
-use grpc::operations::{pool::traits::PoolOperations, replica::traits::ReplicaOperations};
+use common_lib::{
+    mbus_api::message_bus::v0::BusError,
+    types::v0::{message_bus::MoveReplica, openapi::apis::Uuid},
+};
+use grpc::operations::{
+    pool::traits::PoolOperations, registry::traits::RegistryOperations,
+    replica::traits::ReplicaOperations, MaxEntries, Pagination, StartingToken,
+};
 use mbus_api::{ReplyErrorKind, ResourceKind};
 
 fn pool_client() -> impl PoolOperations {
@@ -40,7 +46,7 @@ async fn put_replica(
     Ok(replica.into())
 }
 
-async fn destroy_replica(filter: Filter) -> Result<(), RestError<RestJsonError>> {
+async fn destroy_replica(filter: Filter, force: bool) -> Result<(), RestError<RestJsonError>> {
     let destroy = match filter.clone() {
         Filter::NodePoolReplica(node_id, pool_id, replica_id) => DestroyReplica {
             node: node_id,
@@ -50,7 +56,7 @@ async fn destroy_replica(filter: Filter) -> Result<(), RestError<RestJsonError>>
             ..Default::default()
         },
         Filter::PoolReplica(pool_id, replica_id) => {
-            let node_id = match replica_client().get(filter, None).await {
+            let node_id = match replica_client().get(filter, None, None).await {
                 Ok(replicas) => replica(replica_id.to_string(), replicas.into_inner().get(0))?.node,
                 Err(error) => return Err(RestError::from(error)),
             };
@@ -72,10 +78,22 @@ async fn destroy_replica(filter: Filter) -> Result<(), RestError<RestJsonError>>
             }))
         }
     };
-    replica_client().destroy(&destroy, None).await?;
+    replica_client()
+        .destroy(&destroy.with_force(force), None)
+        .await?;
     Ok(())
 }
 
+async fn check_orphaned_replicas(
+    destroy: bool,
+) -> Result<Vec<models::OrphanedReplica>, RestError<RestJsonError>> {
+    let orphans = core_grpc()
+        .registry()
+        .check_orphaned_replicas(&CheckOrphanedReplicas::new(destroy), None)
+        .await?;
+    Ok(orphans.into())
+}
+
 async fn share_replica(
     filter: Filter,
     protocol: ReplicaShareProtocol,
@@ -89,7 +107,7 @@ async fn share_replica(
             protocol,
         },
         Filter::PoolReplica(pool_id, replica_id) => {
-            let node_id = match replica_client().get(filter, None).await {
+            let node_id = match replica_client().get(filter, None, None).await {
                 Ok(replicas) => replica(replica_id.to_string(), replicas.into_inner().get(0))?.node,
                 Err(error) => return Err(RestError::from(error)),
             };
@@ -124,7 +142,7 @@ async fn unshare_replica(filter: Filter) -> Result<(), RestError<RestJsonError>>
             uuid: replica_id,
         },
         Filter::PoolReplica(pool_id, replica_id) => {
-            let node_id = match replica_client().get(filter, None).await {
+            let node_id = match replica_client().get(filter, None, None).await {
                 Ok(replicas) => replica(replica_id.to_string(), replicas.into_inner().get(0))?.node,
                 Err(error) => return Err(RestError::from(error)),
             };
@@ -153,12 +171,12 @@ async fn unshare_replica(filter: Filter) -> Result<(), RestError<RestJsonError>>
 impl apis::actix_server::Replicas for RestApi {
     async fn del_node_pool_replica(
         Path((node_id, pool_id, replica_id)): Path<(String, String, Uuid)>,
+        Query(force): Query<Option<bool>>,
     ) -> Result<(), RestError<RestJsonError>> {
-        destroy_replica(Filter::NodePoolReplica(
-            node_id.into(),
-            pool_id.into(),
-            replica_id.into(),
-        ))
+        destroy_replica(
+            Filter::NodePoolReplica(node_id.into(), pool_id.into(), replica_id.into()),
+            force.unwrap_or(false),
+        )
         .await
     }
 
@@ -173,10 +191,20 @@ impl apis::actix_server::Replicas for RestApi {
         .await
     }
 
+    async fn del_orphaned_replicas(
+    ) -> Result<Vec<models::OrphanedReplica>, RestError<RestJsonError>> {
+        check_orphaned_replicas(true).await
+    }
+
     async fn del_pool_replica(
         Path((pool_id, replica_id)): Path<(String, Uuid)>,
+        Query(force): Query<Option<bool>>,
     ) -> Result<(), RestError<RestJsonError>> {
-        destroy_replica(Filter::PoolReplica(pool_id.into(), replica_id.into())).await
+        destroy_replica(
+            Filter::PoolReplica(pool_id.into(), replica_id.into()),
+            force.unwrap_or(false),
+        )
+        .await
     }
 
     async fn del_pool_replica_share(
@@ -188,17 +216,9 @@ impl apis::actix_server::Replicas for RestApi {
     async fn get_node_pool_replica(
         Path((node_id, pool_id, replica_id)): Path<(String, String, Uuid)>,
     ) -> Result<models::Replica, RestError<RestJsonError>> {
-        let replica = replica(
-            replica_id.to_string(),
-            replica_client()
-                .get(
-                    Filter::NodePoolReplica(node_id.into(), pool_id.into(), replica_id.into()),
-                    None,
-                )
-                .await?
-                .into_inner()
-                .get(0),
-        )?;
+        let replica = replica_client()
+            .get_one(&node_id.into(), &pool_id.into(), &replica_id.into(), None)
+            .await?;
         Ok(replica.into())
     }
 
@@ -206,7 +226,7 @@ impl apis::actix_server::Replicas for RestApi {
         Path((node_id, pool_id)): Path<(String, String)>,
     ) -> Result<Vec<models::Replica>, RestError<RestJsonError>> {
         let replicas = replica_client()
-            .get(Filter::NodePool(node_id.into(), pool_id.into()), None)
+            .get(Filter::NodePool(node_id.into(), pool_id.into()), None, None)
             .await?;
         Ok(replicas.into_inner().into_iter().map(From::from).collect())
     }
@@ -214,17 +234,24 @@ impl apis::actix_server::Replicas for RestApi {
     async fn get_node_replicas(
         Path(id): Path<String>,
     ) -> Result<Vec<models::Replica>, RestError<RestJsonError>> {
-        let replicas = replica_client().get(Filter::Node(id.into()), None).await?;
+        let replicas = replica_client()
+            .get(Filter::Node(id.into()), None, None)
+            .await?;
         Ok(replicas.into_inner().into_iter().map(From::from).collect())
     }
 
+    async fn get_orphaned_replicas(
+    ) -> Result<Vec<models::OrphanedReplica>, RestError<RestJsonError>> {
+        check_orphaned_replicas(false).await
+    }
+
     async fn get_replica(
         Path(id): Path<Uuid>,
     ) -> Result<models::Replica, RestError<RestJsonError>> {
         let replica = replica(
             id.to_string(),
             replica_client()
-                .get(Filter::Replica(id.into()), None)
+                .get(Filter::Replica(id.into()), None, None)
                 .await?
                 .into_inner()
                 .get(0),
@@ -232,9 +259,18 @@ impl apis::actix_server::Replicas for RestApi {
         Ok(replica.into())
     }
 
-    async fn get_replicas() -> Result<Vec<models::Replica>, RestError<RestJsonError>> {
-        let replicas = replica_client().get(Filter::None, None).await?;
-        Ok(replicas.into_inner().into_iter().map(From::from).collect())
+    async fn get_replicas(
+        Query((max_entries, starting_token)): Query<(isize, Option<isize>)>,
+    ) -> Result<models::Replicas, RestError<RestJsonError>> {
+        let starting_token = starting_token.unwrap_or_default();
+        let pagination = page_size(max_entries).map(|page_size| {
+            Pagination::new(page_size as MaxEntries, starting_token as StartingToken)
+        });
+        let replicas = replica_client().get(Filter::None, pagination, None).await?;
+        Ok(models::Replicas {
+            entries: replicas.entries.into_iter().map(From::from).collect(),
+            next_token: replicas.next_token.map(|t| t as isize),
+        })
     }
 
     async fn put_node_pool_replica(
@@ -248,6 +284,26 @@ impl apis::actix_server::Replicas for RestApi {
         .await
     }
 
+    async fn put_node_pool_replica_move(
+        Path((node_id, pool_id, replica_id, destination_pool_id)): Path<(
+            String,
+            String,
+            Uuid,
+            String,
+        )>,
+        Query(force): Query<Option<bool>>,
+    ) -> Result<models::Replica, RestError<RestJsonError>> {
+        let request = MoveReplica {
+            node: node_id.into(),
+            pool: pool_id.into(),
+            destination_pool: destination_pool_id.into(),
+            uuid: replica_id.into(),
+            force: force.unwrap_or(false),
+        };
+        let replica = replica_client().move_replica(&request, None).await?;
+        Ok(replica.into())
+    }
+
     async fn put_node_pool_replica_share(
         Path((node_id, pool_id, replica_id)): Path<(String, String, Uuid)>,
     ) -> Result<String, RestError<RestJsonError>> {
@@ -1,5 +1,7 @@
 use super::*;
-use common_lib::types::v0::message_bus::GetSpecs;
+use common_lib::types::v0::message_bus::{
+    ClearOperation, GetOperations, GetSpecs, ReconcileResource, ResourceRef,
+};
 use grpc::operations::registry::traits::RegistryOperations;
 
 fn client() -> impl RegistryOperations {
@@ -8,8 +10,37 @@ fn client() -> impl RegistryOperations {
 
 #[async_trait::async_trait]
 impl apis::actix_server::Specs for RestApi {
+    async fn del_operation(Query(resource): Query<String>) -> Result<(), RestError<RestJsonError>> {
+        let resource = resource.parse::<ResourceRef>()?;
+        client()
+            .clear_operation(&ClearOperation::new(resource), None)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_operations(
+        Query(resource): Query<Option<String>>,
+    ) -> Result<Vec<models::PendingOperation>, RestError<RestJsonError>> {
+        let resource = match resource {
+            Some(resource) => Some(resource.parse::<ResourceRef>()?),
+            None => None,
+        };
+        let operations = client()
+            .get_operations(&GetOperations::new(resource), None)
+            .await?;
+        Ok(operations.into())
+    }
+
     async fn get_specs() -> Result<models::Specs, RestError<RestJsonError>> {
         let specs = client().get_specs(&GetSpecs {}, None).await?;
         Ok(specs.into())
     }
+
+    async fn put_reconcile(Query(resource): Query<String>) -> Result<(), RestError<RestJsonError>> {
+        let resource = resource.parse::<ResourceRef>()?;
+        client()
+            .reconcile_resource(&ReconcileResource::new(resource), None)
+            .await?;
+        Ok(())
+    }
 }
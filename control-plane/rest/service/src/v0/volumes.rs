@@ -1,12 +1,15 @@
 use super::*;
 use common_lib::types::v0::{
     message_bus::{
-        DestroyVolume, Filter, PublishVolume, SetVolumeReplica, ShareVolume, UnpublishVolume,
-        UnshareVolume, Volume,
+        AddVolumeReplica, CreateSnapshot, DeleteSnapshot, DestroyVolume, ExpandVolume, Filter,
+        ListSnapshots, Nexus, PublishVolume, RemoveVolumeReplica, RepublishVolume, SetVolumeLabels,
+        SetVolumeReplica, ShareVolume, UnpublishVolume, UnshareVolume, Volume,
+        VolumeRebuildProgress,
     },
     openapi::{apis::Uuid, models::VolumeShareProtocol},
 };
 use grpc::operations::{volume::traits::VolumeOperations, MaxEntries, Pagination, StartingToken};
+use std::collections::HashMap;
 
 fn client() -> impl VolumeOperations {
     core_grpc().volume()
@@ -51,6 +54,30 @@ impl apis::actix_server::Volumes for RestApi {
         Ok(volume.into())
     }
 
+    async fn del_volume_replica(
+        Path((volume_id, replica_id)): Path<(Uuid, Uuid)>,
+    ) -> Result<models::Volume, RestError<RestJsonError>> {
+        let volume = client()
+            .remove_replica(
+                &RemoveVolumeReplica::new(volume_id.into(), replica_id.into()),
+                None,
+            )
+            .await?;
+        Ok(volume.into())
+    }
+
+    async fn del_volume_snapshot(
+        Path((volume_id, snapshot_id)): Path<(Uuid, Uuid)>,
+    ) -> Result<(), RestError<RestJsonError>> {
+        client()
+            .delete_snapshot(
+                &DeleteSnapshot::new(volume_id.into(), snapshot_id.into()),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn get_volume(
         Path(volume_id): Path<Uuid>,
     ) -> Result<models::Volume, RestError<RestJsonError>> {
@@ -65,22 +92,65 @@ impl apis::actix_server::Volumes for RestApi {
         Ok(volume.into())
     }
 
+    async fn get_volume_events(
+        Path(volume_id): Path<Uuid>,
+    ) -> Result<Vec<models::VolumeEvent>, RestError<RestJsonError>> {
+        let volume = volume(
+            volume_id.to_string(),
+            client()
+                .get(Filter::Volume(volume_id.into()), None, None)
+                .await?
+                .entries
+                .get(0),
+        )?;
+        Ok(volume.spec().events.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_volume_rebuild_progress(
+        Path(volume_id): Path<Uuid>,
+    ) -> Result<models::VolumeRebuildProgress, RestError<RestJsonError>> {
+        let volume = volume(
+            volume_id.to_string(),
+            client()
+                .get(Filter::Volume(volume_id.into()), None, None)
+                .await?
+                .entries
+                .get(0),
+        )?;
+        volume_target(&volume)?;
+        Ok(VolumeRebuildProgress::new(&volume.state()).into())
+    }
+
+    async fn get_volume_snapshots(
+        Path(volume_id): Path<Uuid>,
+    ) -> Result<Vec<models::VolumeSnapshot>, RestError<RestJsonError>> {
+        let snapshots = client()
+            .list_snapshots(&ListSnapshots::new(volume_id.into()), None)
+            .await?;
+        Ok(snapshots.into())
+    }
+
     async fn get_volumes(
-        Query((max_entries, starting_token)): Query<(isize, Option<isize>)>,
+        Query((max_entries, starting_token, annotations)): Query<(
+            isize,
+            Option<isize>,
+            Option<String>,
+        )>,
     ) -> Result<models::Volumes, RestError<RestJsonError>> {
         let starting_token = starting_token.unwrap_or_default();
+        let annotations =
+            parse_key_values(annotations.as_deref(), "annotations", ResourceKind::Volume)?;
 
-        // If max entries is 0, pagination is disabled. All volumes will be returned in a single
-        // call.
-        let pagination = if max_entries > 0 {
-            Some(Pagination::new(
-                max_entries as MaxEntries,
-                starting_token as StartingToken,
-            ))
-        } else {
-            None
+        // The number of volumes returned is bounded by the server page limits, unless they're
+        // disabled, in which case a max entries of 0 returns all volumes in a single call.
+        let pagination = page_size(max_entries).map(|page_size| {
+            Pagination::new(page_size as MaxEntries, starting_token as StartingToken)
+        });
+        let filter = match annotations.is_empty() {
+            true => Filter::None,
+            false => Filter::VolumeAnnotations(annotations),
         };
-        let volumes = client().get(Filter::None, pagination, None).await?;
+        let volumes = client().get(filter, pagination, None).await?;
         Ok(models::Volumes {
             entries: volumes.entries.into_iter().map(|e| e.into()).collect(),
             next_token: volumes.next_token.map(|t| t as isize),
@@ -96,6 +166,49 @@ impl apis::actix_server::Volumes for RestApi {
         Ok(volume.into())
     }
 
+    async fn put_volume_labels(
+        Path(volume_id): Path<Uuid>,
+        Query((merge, annotations)): Query<(Option<bool>, Option<String>)>,
+        Body(labels): Body<HashMap<String, String>>,
+    ) -> Result<models::Volume, RestError<RestJsonError>> {
+        let mut request =
+            SetVolumeLabels::new(volume_id.into(), labels).with_merge(merge.unwrap_or_default());
+        if let Some(annotations) = annotations {
+            request = request.with_annotations(parse_key_values(
+                Some(&annotations),
+                "annotations",
+                ResourceKind::Volume,
+            )?);
+        }
+        let volume = client().set_labels(&request, None).await?;
+        Ok(volume.into())
+    }
+
+    async fn put_volume_republish(
+        Path(volume_id): Path<Uuid>,
+        Query((node, protocol)): Query<(String, Option<VolumeShareProtocol>)>,
+    ) -> Result<models::Volume, RestError<RestJsonError>> {
+        let volume = client()
+            .republish(
+                &RepublishVolume::new(volume_id.into(), node.into(), protocol.map(Into::into)),
+                None,
+            )
+            .await?;
+        Ok(volume.into())
+    }
+
+    async fn put_volume_replica(
+        Path((volume_id, replica_id)): Path<(Uuid, Uuid)>,
+    ) -> Result<models::Volume, RestError<RestJsonError>> {
+        let volume = client()
+            .add_replica(
+                &AddVolumeReplica::new(volume_id.into(), replica_id.into()),
+                None,
+            )
+            .await?;
+        Ok(volume.into())
+    }
+
     async fn put_volume_replica_count(
         Path((volume_id, replica_count)): Path<(Uuid, u8)>,
     ) -> Result<models::Volume, RestError<RestJsonError>> {
@@ -111,6 +224,27 @@ impl apis::actix_server::Volumes for RestApi {
         Ok(volume.into())
     }
 
+    async fn put_volume_snapshot(
+        Path((volume_id, snapshot_id)): Path<(Uuid, Uuid)>,
+    ) -> Result<models::VolumeSnapshot, RestError<RestJsonError>> {
+        let snapshot = client()
+            .create_snapshot(
+                &CreateSnapshot::new(volume_id.into(), snapshot_id.into()),
+                None,
+            )
+            .await?;
+        Ok(snapshot.into())
+    }
+
+    async fn put_volume_size(
+        Path((volume_id, size)): Path<(Uuid, u64)>,
+    ) -> Result<models::Volume, RestError<RestJsonError>> {
+        let volume = client()
+            .expand(&ExpandVolume::new(volume_id.into(), size), None)
+            .await?;
+        Ok(volume.into())
+    }
+
     async fn put_volume_share(
         Path((volume_id, protocol)): Path<(Uuid, models::VolumeShareProtocol)>,
     ) -> Result<String, RestError<RestJsonError>> {
@@ -128,15 +262,27 @@ impl apis::actix_server::Volumes for RestApi {
 
     async fn put_volume_target(
         Path(volume_id): Path<Uuid>,
-        Query((node, protocol)): Query<(String, VolumeShareProtocol)>,
+        Query((node, protocol, transport_params, read_only, force_read_write)): Query<(
+            String,
+            VolumeShareProtocol,
+            Option<String>,
+            Option<bool>,
+            Option<bool>,
+        )>,
     ) -> Result<models::Volume, RestError<RestJsonError>> {
+        let transport_params = parse_key_values(
+            transport_params.as_deref(),
+            "transport_params",
+            ResourceKind::Volume,
+        )?;
         let volume = client()
             .publish(
-                &PublishVolume {
-                    uuid: volume_id.into(),
-                    target_node: Some(node.into()),
-                    share: Some(protocol.into()),
-                },
+                &PublishVolume::new(volume_id.into(), Some(node.into()), Some(protocol.into()))
+                    .with_transport_params(transport_params)
+                    .with_read_only(
+                        read_only.unwrap_or(false),
+                        force_read_write.unwrap_or(false),
+                    ),
                 None,
             )
             .await?;
@@ -144,6 +290,16 @@ impl apis::actix_server::Volumes for RestApi {
     }
 }
 
+/// returns the target of the volume and returns an error if the volume is not published
+fn volume_target(volume: &Volume) -> Result<Nexus, ReplyError> {
+    volume.state().target.ok_or_else(|| ReplyError {
+        kind: ReplyErrorKind::NotPublished,
+        resource: ResourceKind::Volume,
+        source: "Requested volume is not published".to_string(),
+        extra: format!("Volume id : {}", volume.uuid()),
+    })
+}
+
 /// returns volume from volume option and returns an error on non existence
 fn volume(volume_id: String, volume: Option<&Volume>) -> Result<Volume, ReplyError> {
     match volume {
@@ -8,9 +8,10 @@ pub use common_lib::{
             AddNexusChild, BlockDevice, Child, ChildUri, CreateNexus, CreatePool, CreateReplica,
             CreateVolume, DestroyNexus, DestroyPool, DestroyReplica, DestroyVolume, Filter,
             GetBlockDevices, JsonGrpcRequest, Nexus, NexusId, Node, NodeId, Pool, PoolDeviceUri,
-            PoolId, Protocol, RemoveNexusChild, Replica, ReplicaId, ReplicaShareProtocol,
-            ShareNexus, ShareReplica, Specs, Topology, UnshareNexus, UnshareReplica, VolumeId,
-            VolumeLabels, VolumePolicy, Watch, WatchCallback, WatchResourceId,
+            PoolId, Protocol, RemoveNexusChild, Replica, ReplicaAnnotations, ReplicaEncryption,
+            ReplicaId, ReplicaShareProtocol, ShareNexus, ShareReplica, SnapshotId, Specs, Topology,
+            UnshareNexus, UnshareReplica, VolumeAnnotations, VolumeId, VolumeLabels, VolumePolicy,
+            Watch, WatchCallback, WatchResourceId,
         },
         openapi::{apis, apis::actix_server::RestError, models, tower::client},
         store::pool::PoolLabel,
@@ -32,6 +33,10 @@ pub struct CreateReplicaBody {
     pub thin: bool,
     /// protocol to expose the replica over
     pub share: Protocol,
+    /// replica annotations, free-form metadata which doesn't affect placement
+    pub annotations: ReplicaAnnotations,
+    /// at-rest encryption of the replica data
+    pub encryption: Option<ReplicaEncryption>,
 }
 impl From<models::CreateReplicaBody> for CreateReplicaBody {
     fn from(src: models::CreateReplicaBody) -> Self {
@@ -42,6 +47,8 @@ impl From<models::CreateReplicaBody> for CreateReplicaBody {
                 None => Protocol::None,
                 Some(models::ReplicaShareProtocol::Nvmf) => Protocol::Nvmf,
             },
+            annotations: src.annotations.unwrap_or_default(),
+            encryption: src.encryption.into_opt(),
         }
     }
 }
@@ -53,12 +60,15 @@ pub struct CreatePoolBody {
     pub disks: Vec<PoolDeviceUri>,
     /// labels to be set on the pool
     pub labels: Option<PoolLabel>,
+    /// percentage of the pool capacity kept free when placing new replicas
+    pub reserved_capacity_percent: Option<u8>,
 }
 impl From<models::CreatePoolBody> for CreatePoolBody {
     fn from(src: models::CreatePoolBody) -> Self {
         Self {
             disks: src.disks.iter().cloned().map(From::from).collect(),
             labels: src.labels,
+            reserved_capacity_percent: src.reserved_capacity_percent,
         }
     }
 }
@@ -67,6 +77,7 @@ impl From<CreatePool> for CreatePoolBody {
         CreatePoolBody {
             disks: create.disks,
             labels: create.labels,
+            reserved_capacity_percent: create.reserved_capacity_percent,
         }
     }
 }
@@ -78,6 +89,7 @@ impl CreatePoolBody {
             id: pool_id,
             disks: self.disks.clone(),
             labels: self.labels.clone(),
+            reserved_capacity_percent: self.reserved_capacity_percent,
         }
     }
 }
@@ -87,6 +99,8 @@ impl From<CreateReplica> for CreateReplicaBody {
             size: create.size,
             thin: create.thin,
             share: create.share,
+            annotations: create.annotations,
+            encryption: create.encryption,
         }
     }
 }
@@ -103,6 +117,8 @@ impl CreateReplicaBody {
             share: self.share,
             managed: false,
             owners: Default::default(),
+            annotations: self.annotations.clone(),
+            encryption: self.encryption.clone(),
         }
     }
 }
@@ -162,6 +178,14 @@ pub struct CreateVolumeBody {
     pub topology: Option<Topology>,
     /// Volume labels, used ot store custom volume information
     pub labels: Option<VolumeLabels>,
+    /// Volume annotations, free-form metadata which doesn't affect placement
+    pub annotations: VolumeAnnotations,
+    /// At-rest encryption of the data of all the volume replicas
+    pub encryption: Option<ReplicaEncryption>,
+    /// Key which makes retries of the volume creation safe
+    pub idempotency_key: Option<String>,
+    /// Snapshot whose replicas are cloned to provision the volume
+    pub source_snapshot: Option<SnapshotId>,
 }
 impl From<models::CreateVolumeBody> for CreateVolumeBody {
     fn from(src: models::CreateVolumeBody) -> Self {
@@ -171,6 +195,10 @@ impl From<models::CreateVolumeBody> for CreateVolumeBody {
             policy: src.policy.into(),
             topology: src.topology.into_opt(),
             labels: src.labels,
+            annotations: src.annotations.unwrap_or_default(),
+            encryption: src.encryption.into_opt(),
+            idempotency_key: src.idempotency_key,
+            source_snapshot: src.source_snapshot.map(Into::into),
         }
     }
 }
@@ -182,6 +210,10 @@ impl From<CreateVolume> for CreateVolumeBody {
             policy: create.policy,
             topology: create.topology,
             labels: create.labels,
+            annotations: create.annotations,
+            encryption: create.encryption,
+            idempotency_key: create.idempotency_key,
+            source_snapshot: create.source_snapshot,
         }
     }
 }
@@ -195,6 +227,10 @@ impl CreateVolumeBody {
             policy: self.policy.clone(),
             topology: self.topology.clone(),
             labels: self.labels.clone(),
+            annotations: self.annotations.clone(),
+            encryption: self.encryption.clone(),
+            idempotency_key: self.idempotency_key.clone(),
+            source_snapshot: self.source_snapshot.clone(),
         }
     }
 }
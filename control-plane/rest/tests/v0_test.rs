@@ -87,6 +87,14 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
     let io_engine1 = cluster.node(0);
     let io_engine2 = cluster.node(1);
 
+    let health = client.health_api().get_health().await.unwrap();
+    info!("Health: {:#?}", health);
+    assert!(health.healthy);
+    assert!(health
+        .components
+        .iter()
+        .any(|component| component.name == "store" && component.healthy));
+
     let listed_node = client.nodes_api().get_node(io_engine1.as_str()).await;
     let mut node = models::Node {
         id: io_engine1.to_string(),
@@ -96,6 +104,10 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
                 "{}:10124",
                 cluster.composer().container_ip(cluster.node(0).as_str())
             ),
+            cordon_labels: None,
+            drain_state: None,
+            connect_timeout: None,
+            request_timeout: None,
         }),
         state: Some(models::NodeState {
             id: io_engine1.to_string(),
@@ -108,7 +120,7 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
     };
     assert_eq!(listed_node.unwrap(), node);
 
-    let _ = client.pools_api().get_pools().await.unwrap();
+    let _ = client.pools_api().get_pools(None).await.unwrap();
     let pool = client
         .pools_api()
         .put_node_pool(
@@ -126,14 +138,14 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
         pool,
         models::Pool::new_all(
             "pooloop",
-            models::PoolSpec::new(vec!["malloc:///malloc0?blk_size=512&size_mb=100&uuid=b940f4f2-d45d-4404-8167-3b0366f9e2b0"], "pooloop", &io_engine1, models::SpecStatus::Created),
+            models::PoolSpec::new(vec!["malloc:///malloc0?blk_size=512&size_mb=100&uuid=b940f4f2-d45d-4404-8167-3b0366f9e2b0"], "pooloop", false, &io_engine1, models::SpecStatus::Created),
             models::PoolState::new(100663296u64, vec!["malloc:///malloc0?blk_size=512&size_mb=100&uuid=b940f4f2-d45d-4404-8167-3b0366f9e2b0"], "pooloop", &io_engine1, models::PoolStatus::Online, 0u64)
         )
     );
 
     assert_eq!(
         Some(&pool),
-        client.pools_api().get_pools().await.unwrap().first()
+        client.pools_api().get_pools(None).await.unwrap().first()
     );
 
     let pool = client
@@ -150,7 +162,12 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
 
     info!("Pools: {:#?}", pool);
 
-    let _ = client.replicas_api().get_replicas().await.unwrap();
+    let _ = client
+        .replicas_api()
+        .get_replicas(0, None)
+        .await
+        .unwrap()
+        .entries;
     let replica = client
         .replicas_api()
         .put_node_pool_replica(
@@ -163,6 +180,8 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
                 models::ReplicaShareProtocol::Nvmf,
                 12582912u64,
                 false,
+                None,
+                None,
             ),
         )
         .await
@@ -170,6 +189,7 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
     info!("Replica: {:#?}", replica);
 
     let uri = replica.uri.clone();
+    assert!(replica.state_changed_at.is_some());
     assert_eq!(
         replica,
         models::Replica {
@@ -180,23 +200,40 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
             size: 12582912,
             share: models::Protocol::Nvmf,
             uri,
-            state: models::ReplicaState::Online
+            state: models::ReplicaState::Online,
+            state_changed_at: replica.state_changed_at.clone(),
         }
     );
     assert_eq!(
         Some(&replica),
-        client.replicas_api().get_replicas().await.unwrap().first()
+        client
+            .replicas_api()
+            .get_replicas(0, None)
+            .await
+            .unwrap()
+            .entries
+            .first()
     );
     client
         .replicas_api()
-        .del_node_pool_replica(&replica.node, &replica.pool, &replica.uuid)
+        .del_node_pool_replica(&replica.node, &replica.pool, &replica.uuid, None)
         .await
         .unwrap();
 
-    let replicas = client.replicas_api().get_replicas().await.unwrap();
+    let replicas = client
+        .replicas_api()
+        .get_replicas(0, None)
+        .await
+        .unwrap()
+        .entries;
     assert!(replicas.is_empty());
 
-    let nexuses = client.nexuses_api().get_nexuses().await.unwrap();
+    let nexuses = client
+        .nexuses_api()
+        .get_nexuses(0, None)
+        .await
+        .unwrap()
+        .entries;
     assert_eq!(nexuses.len(), 0);
     let nexus = client
         .nexuses_api()
@@ -211,6 +248,7 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
         .await
         .unwrap();
     info!("Nexus: {:#?}", nexus);
+    assert!(nexus.children[0].state_changed_at.is_some());
 
     assert_eq!(
         nexus,
@@ -222,7 +260,8 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
             children: vec![models::Child {
                 uri: "malloc:///malloc1?blk_size=512&size_mb=100&uuid=b940f4f2-d45d-4404-8167-3b0366f9e2b0".into(),
                 state: models::ChildState::Online,
-                rebuild_progress: None
+                rebuild_progress: None,
+                state_changed_at: nexus.children[0].state_changed_at.clone(),
             }],
             device_uri: "".to_string(),
             rebuilds: 0,
@@ -261,7 +300,12 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
         .del_node_nexus(&nexus.node, &nexus.uuid)
         .await
         .unwrap();
-    let nexuses = client.nexuses_api().get_nexuses().await.unwrap();
+    let nexuses = client
+        .nexuses_api()
+        .get_nexuses(0, None)
+        .await
+        .unwrap()
+        .entries;
     assert!(nexuses.is_empty());
     let volume_uuid: VolumeId = "058a95e5-cee6-4e81-b682-fe864ca99b9c".try_into().unwrap();
 
@@ -286,6 +330,9 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
             &volume.state.uuid,
             io_engine1.as_str(),
             models::VolumeShareProtocol::Nvmf,
+            None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -303,6 +350,23 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
     let nexus = volume_state.target.unwrap();
     assert_eq!(nexus.children.len(), 2);
 
+    // the new replica is rebuilt, which may complete before its progress is checked
+    let progress = client
+        .volumes_api()
+        .get_volume_rebuild_progress(&volume_state.uuid)
+        .await
+        .unwrap();
+    tracing::info!("Rebuild progress: {:#?}", progress);
+    assert_eq!(progress.children.len(), 2);
+    let rebuilding = progress
+        .children
+        .iter()
+        .filter(|c| c.rebuild_progress.is_some())
+        .count();
+    assert_eq!(progress.rebuilding, rebuilding > 0);
+    assert!(rebuilding <= 1);
+    assert!(progress.rebuilding || progress.progress == 100);
+
     let volume = client
         .volumes_api()
         .put_volume_replica_count(&volume_state.uuid, 1)
@@ -355,7 +419,11 @@ async fn client_test(cluster: &Cluster, auth: &bool) {
 
     client.volumes_api().del_volume(&volume_uuid).await.unwrap();
 
-    let volumes = client.volumes_api().get_volumes(0, None).await.unwrap();
+    let volumes = client
+        .volumes_api()
+        .get_volumes(0, None, None)
+        .await
+        .unwrap();
     assert!(volumes.entries.is_empty());
 
     client
@@ -28,6 +28,9 @@ impl ComponentAction for Core {
         if let Some(deadline) = &options.node_deadline {
             binary = binary.with_args(vec!["-d", &deadline.to_string()]);
         }
+        if let Some(misses) = &options.node_offline_misses {
+            binary = binary.with_args(vec!["--offline-misses", &misses.to_string()]);
+        }
         if let Some(timeout) = &options.node_conn_timeout {
             binary = binary.with_args(vec!["--connect-timeout", &timeout.to_string()]);
         }
@@ -43,12 +46,18 @@ impl ComponentAction for Core {
         if let Some(ttl) = &options.store_lease_ttl {
             binary = binary.with_args(vec!["--store-lease-ttl", &ttl.to_string()]);
         }
+        if let Some(prefix) = &options.store_key_prefix {
+            binary = binary.with_args(vec!["--store-key-prefix", prefix]);
+        }
         if let Some(period) = &options.reconcile_period {
             binary = binary.with_args(vec!["--reconcile-period", &period.to_string()]);
         }
         if let Some(period) = &options.reconcile_idle_period {
             binary = binary.with_args(vec!["--reconcile-idle-period", &period.to_string()]);
         }
+        if let Some(period) = &options.faulted_child_grace_period {
+            binary = binary.with_args(vec!["--faulted-child-grace-period", &period.to_string()]);
+        }
         if cfg.container_exists("jaeger") {
             let jaeger_config = format!("jaeger.{}:6831", cfg.get_name());
             binary = binary.with_args(vec!["--jaeger", &jaeger_config]);
@@ -59,6 +68,18 @@ impl ComponentAction for Core {
         if let Some(max_rebuilds) = &options.max_rebuilds {
             binary = binary.with_args(vec!["--max-rebuilds", &max_rebuilds.to_string()]);
         }
+        if let Some(threshold) = &options.rebalance_threshold {
+            binary = binary.with_args(vec!["--rebalance-threshold", &threshold.to_string()]);
+        }
+        if let Some(file) = &options.audit_log {
+            binary = binary.with_args(vec!["--audit-log", file]);
+            if let Some(dir) = std::path::Path::new(file).parent() {
+                let dir = dir.to_string_lossy();
+                return Ok(cfg.add_container_spec(
+                    ContainerSpec::from_binary(name, binary).with_bind(&dir, &dir),
+                ));
+            }
+        }
         Ok(cfg.add_container_bin(name, binary))
     }
     async fn start(&self, _options: &StartOptions, cfg: &ComposeTest) -> Result<(), Error> {
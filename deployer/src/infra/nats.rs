@@ -1,5 +1,5 @@
 use super::*;
-use common_lib::mbus_api::{BusOptions, DynBus, NatsMessageBus, TimeoutOptions};
+use common_lib::mbus_api::{BusConnectOptions, DynBus, NatsMessageBus, TimeoutOptions};
 use once_cell::sync::OnceCell;
 use std::time::Duration;
 
@@ -23,7 +23,7 @@ impl ComponentAction for Nats {
             cfg.start("nats").await?;
         }
         if !options.no_nats || cfg.container_exists("nats").await {
-            message_bus_init_options("localhost:4222", bus_timeout_opts()).await;
+            message_bus_init_options("localhost:4222", bus_timeout_opts()).await?;
         }
         Ok(())
     }
@@ -38,11 +38,12 @@ fn bus_timeout_opts() -> TimeoutOptions {
         .with_timeout_backoff(Duration::from_millis(500))
         .with_max_retries(10)
 }
-async fn message_bus_init_options(server: &str, timeouts: TimeoutOptions) {
+async fn message_bus_init_options(server: &str, timeouts: TimeoutOptions) -> Result<(), Error> {
     if NATS_MSG_BUS.get().is_none() {
-        let nc = NatsMessageBus::new(None, server, BusOptions::new(), timeouts).await;
+        let nc = NatsMessageBus::new(None, server, BusConnectOptions::default(), timeouts).await?;
         NATS_MSG_BUS.set(nc).ok();
     }
+    Ok(())
 }
 
 /// Get the static `NatsMessageBus` as a boxed `MessageBus`
@@ -210,6 +210,11 @@ pub struct StartOptions {
     #[structopt(long)]
     pub node_deadline: Option<humantime::Duration>,
 
+    /// Override the number of consecutive node deadlines which a node may miss before the
+    /// Core Agent deems it offline
+    #[structopt(long)]
+    pub node_offline_misses: Option<u32>,
+
     /// Override the base request timeout for NATS and GRPC requests
     #[structopt(long)]
     pub request_timeout: Option<humantime::Duration>,
@@ -231,6 +236,10 @@ pub struct StartOptions {
     #[structopt(long)]
     pub store_lease_ttl: Option<humantime::Duration>,
 
+    /// Override the core agent's persistent store key prefix
+    #[structopt(long)]
+    pub store_key_prefix: Option<String>,
+
     /// Override the core agent's reconcile period
     #[structopt(long)]
     pub reconcile_period: Option<humantime::Duration>,
@@ -239,6 +248,10 @@ pub struct StartOptions {
     #[structopt(long)]
     pub reconcile_idle_period: Option<humantime::Duration>,
 
+    /// Override the core agent's faulted child grace period
+    #[structopt(long)]
+    pub faulted_child_grace_period: Option<humantime::Duration>,
+
     /// Override the core agent's reconcile idle period
     #[structopt(long, env = "OTEL_BSP_MAX_EXPORT_BATCH_SIZE")]
     pub otel_max_batch_size: Option<String>,
@@ -265,6 +278,15 @@ pub struct StartOptions {
     /// Maximum number of concurrent rebuilds across the cluster.
     #[structopt(long)]
     max_rebuilds: Option<u32>,
+
+    /// Pool utilization (%) above which the core agent moves volume replicas to other pools.
+    #[structopt(long)]
+    rebalance_threshold: Option<u8>,
+
+    /// Append the core agent's audit records to this file.
+    /// Its directory is mounted into the core agent container at the same path.
+    #[structopt(long)]
+    audit_log: Option<String>,
 }
 
 /// List of KeyValues
@@ -323,6 +345,11 @@ impl StartOptions {
         self
     }
     #[must_use]
+    pub fn with_node_offline_misses(mut self, misses: u32) -> Self {
+        self.node_offline_misses = Some(misses);
+        self
+    }
+    #[must_use]
     pub fn with_store_timeout(mut self, timeout: Duration) -> Self {
         self.store_timeout = Some(timeout.into());
         self
@@ -333,12 +360,22 @@ impl StartOptions {
         self
     }
     #[must_use]
+    pub fn with_store_key_prefix(mut self, prefix: &str) -> Self {
+        self.store_key_prefix = Some(prefix.to_string());
+        self
+    }
+    #[must_use]
     pub fn with_reconcile_period(mut self, busy: Duration, idle: Duration) -> Self {
         self.reconcile_period = Some(busy.into());
         self.reconcile_idle_period = Some(idle.into());
         self
     }
     #[must_use]
+    pub fn with_faulted_child_grace_period(mut self, period: Duration) -> Self {
+        self.faulted_child_grace_period = Some(period.into());
+        self
+    }
+    #[must_use]
     pub fn with_req_timeouts(mut self, no_min: bool, connect: Duration, request: Duration) -> Self {
         self.no_min_timeouts = no_min;
         self.node_conn_timeout = Some(connect.into());
@@ -430,6 +467,16 @@ impl StartOptions {
         self.max_rebuilds = max;
         self
     }
+    #[must_use]
+    pub fn with_rebalance_threshold(mut self, threshold: Option<u8>) -> Self {
+        self.rebalance_threshold = threshold;
+        self
+    }
+    #[must_use]
+    pub fn with_audit_log(mut self, file: &str) -> Self {
+        self.audit_log = Some(file.to_string());
+        self
+    }
 }
 
 impl CliArgs {
@@ -1,3 +1,4 @@
+use chrono::{SecondsFormat, Utc};
 use kube::CustomResource;
 use openapi::models::Pool;
 use schemars::JsonSchema;
@@ -28,6 +29,9 @@ pub struct DiskPoolSpec {
     node: String,
     /// The disk device the pool is located on
     disks: Vec<String>,
+    /// The uuid of a pre-existing pool to import from the disks, preserving its identity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uuid: Option<String>,
 }
 
 impl DiskPoolSpec {
@@ -39,6 +43,10 @@ impl DiskPoolSpec {
     pub fn disks(&self) -> Vec<String> {
         self.disks.clone()
     }
+    /// The uuid of a pre-existing pool to import from the disks
+    pub fn uuid(&self) -> Option<String> {
+        self.uuid.clone()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -75,6 +83,9 @@ pub struct DiskPoolStatus {
     used: u64,
     /// Available number of bytes
     available: u64,
+    /// Kubernetes style conditions of the pool, eg: `Ready`
+    #[serde(default)]
+    pub conditions: Vec<DiskPoolCondition>,
 }
 
 impl Default for DiskPoolStatus {
@@ -84,6 +95,7 @@ impl Default for DiskPoolStatus {
             capacity: 0,
             used: 0,
             available: 0,
+            conditions: vec![],
         }
     }
 }
@@ -96,6 +108,7 @@ impl DiskPoolStatus {
             capacity: 0,
             used: 0,
             available: 0,
+            conditions: vec![],
         }
     }
     /// created pool status
@@ -105,6 +118,7 @@ impl DiskPoolStatus {
             capacity: 0,
             used: 0,
             available: 0,
+            conditions: vec![],
         }
     }
     /// unknown pool status
@@ -114,6 +128,70 @@ impl DiskPoolStatus {
             capacity: 0,
             used: 0,
             available: 0,
+            conditions: vec![],
+        }
+    }
+}
+
+impl DiskPoolStatus {
+    /// Set the `Ready` condition according to the pool state. The transition time of the
+    /// `previous` status' `Ready` condition is kept if its status has not changed.
+    pub fn with_ready_condition(mut self, previous: Option<&DiskPoolStatus>) -> Self {
+        let mut ready = DiskPoolCondition::ready(&self.state);
+        if let Some(previous) = previous.and_then(|p| p.condition(DiskPoolCondition::READY)) {
+            if previous.status == ready.status {
+                ready.last_transition_time = previous.last_transition_time.clone();
+            }
+        }
+        self.conditions
+            .retain(|condition| condition.type_ != DiskPoolCondition::READY);
+        self.conditions.push(ready);
+        self
+    }
+    /// Get the condition of the given type, if present
+    pub fn condition(&self, type_: &str) -> Option<&DiskPoolCondition> {
+        self.conditions
+            .iter()
+            .find(|condition| condition.type_ == type_)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+/// A Kubernetes style condition of the pool
+pub struct DiskPoolCondition {
+    /// The type of the condition, eg: `Ready`
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// The status of the condition: `True`, `False` or `Unknown`
+    pub status: String,
+    /// Machine readable reason for the condition's last transition
+    pub reason: String,
+    /// Human readable description of the condition's last transition
+    pub message: String,
+    /// The last time the condition's status changed, in RFC 3339 format
+    pub last_transition_time: String,
+}
+
+impl DiskPoolCondition {
+    /// The type of the condition which reflects whether the pool is ready for use
+    pub const READY: &'static str = "Ready";
+
+    /// The `Ready` condition of a pool in the given state
+    fn ready(state: &PoolState) -> Self {
+        let (status, message) = match state {
+            PoolState::Creating => ("False", "The pool is being created"),
+            PoolState::Created => ("False", "The pool has been created but is not online yet"),
+            PoolState::Online => ("True", "The pool is online"),
+            PoolState::Unknown => ("Unknown", "The pool state could not be determined"),
+            PoolState::Error => ("False", "The pool exceeded its retry limit"),
+        };
+        Self {
+            type_: Self::READY.to_string(),
+            status: status.to_string(),
+            reason: state.to_string(),
+            message: message.to_string(),
+            last_transition_time: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
         }
     }
 }
@@ -132,6 +210,7 @@ impl From<Pool> for DiskPoolStatus {
             capacity: state.capacity,
             used: state.used,
             available: free,
+            conditions: vec![],
         }
     }
 }
@@ -155,3 +234,34 @@ impl From<PoolState> for String {
         p.to_string()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ready_condition() {
+        let creating = DiskPoolStatus::default().with_ready_condition(None);
+        let ready = creating.condition(DiskPoolCondition::READY).unwrap();
+        assert_eq!(ready.status, "False");
+        assert_eq!(ready.reason, "Creating");
+
+        let mut created = DiskPoolStatus::created().with_ready_condition(Some(&creating));
+        assert_eq!(created.conditions.len(), 1);
+        // the status has not changed, so neither should the transition time
+        created.conditions[0].last_transition_time = "2022-01-01T00:00:00Z".to_string();
+        let error = DiskPoolStatus::error().with_ready_condition(Some(&created));
+        let ready = error.condition(DiskPoolCondition::READY).unwrap();
+        assert_eq!(ready.reason, "Error");
+        assert_eq!(ready.last_transition_time, "2022-01-01T00:00:00Z");
+
+        let unknown = DiskPoolStatus::unknown().with_ready_condition(Some(&error));
+        let ready = unknown.condition(DiskPoolCondition::READY).unwrap();
+        assert_eq!(ready.status, "Unknown");
+        assert_ne!(ready.last_transition_time, "2022-01-01T00:00:00Z");
+        assert_eq!(
+            unknown.clone().with_ready_condition(Some(&unknown)),
+            unknown
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+/// Default maximum number of resources kept in the inventory
+pub(crate) const DEFAULT_INVENTORY_CAPACITY: &str = "1024";
+
+/// An entry of the inventory along with when it was last seen
+struct Entry<T> {
+    last_seen: Instant,
+    value: T,
+}
+
+/// Bounded inventory of the last seen resources, keyed by their name.
+/// When the capacity is reached the least recently seen resource is evicted: this only drops
+/// the volatile context we hold for it (eg: the retry counter) which is rebuilt on its next
+/// reconciliation.
+pub(crate) struct Inventory<T> {
+    entries: HashMap<String, Entry<T>>,
+    capacity: usize,
+}
+
+impl<T> Inventory<T> {
+    /// Return a new empty `Self` which holds at most `capacity` resources
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+        }
+    }
+    /// Number of resources in the inventory
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Get a mutable reference to the resource with the given name, marking it as seen
+    pub(crate) fn get_mut(&mut self, name: &str) -> Option<&mut T> {
+        self.entries.get_mut(name).map(|entry| {
+            entry.last_seen = Instant::now();
+            &mut entry.value
+        })
+    }
+    /// Insert the resource with the given name, returning the previous resource if any.
+    /// If the inventory is full, the least recently seen resource is evicted first.
+    pub(crate) fn insert(&mut self, name: String, value: T) -> Option<T> {
+        if !self.entries.contains_key(&name) && self.entries.len() >= self.capacity {
+            self.evict_oldest();
+        }
+        self.entries
+            .insert(
+                name,
+                Entry {
+                    last_seen: Instant::now(),
+                    value,
+                },
+            )
+            .map(|entry| entry.value)
+    }
+    /// Remove the resource with the given name
+    pub(crate) fn remove(&mut self, name: &str) -> Option<T> {
+        self.entries.remove(name).map(|entry| entry.value)
+    }
+    /// Remove all resources which are not part of the `existing` names, returning the names of
+    /// the pruned resources
+    pub(crate) fn prune(&mut self, existing: &HashSet<String>) -> Vec<String> {
+        let pruned = self
+            .entries
+            .keys()
+            .filter(|name| !existing.contains(*name))
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in &pruned {
+            self.entries.remove(name);
+        }
+        pruned
+    }
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_seen)
+            .map(|(name, _)| name.clone());
+        if let Some(name) = oldest {
+            tracing::warn!(name = ?name, capacity = self.capacity, "inventory full, evicting");
+            self.entries.remove(&name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prune_deleted() {
+        let mut inventory = Inventory::new(10);
+        inventory.insert("pool-1".to_string(), 1);
+        inventory.insert("pool-2".to_string(), 2);
+        inventory.insert("pool-3".to_string(), 3);
+
+        // pool-2 was deleted but we never got notified about it
+        let existing = vec!["pool-1".to_string(), "pool-3".to_string()]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(inventory.prune(&existing), vec!["pool-2".to_string()]);
+        assert_eq!(inventory.len(), 2);
+        assert!(inventory.get_mut("pool-2").is_none());
+        assert_eq!(inventory.get_mut("pool-1"), Some(&mut 1));
+        assert_eq!(inventory.get_mut("pool-3"), Some(&mut 3));
+
+        assert!(inventory.prune(&existing).is_empty());
+    }
+
+    #[test]
+    fn evict_stale() {
+        let mut inventory = Inventory::new(2);
+        inventory.insert("pool-1".to_string(), 1);
+        inventory.insert("pool-2".to_string(), 2);
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        // pool-1 is seen again, making pool-2 the stale one
+        assert!(inventory.get_mut("pool-1").is_some());
+
+        inventory.insert("pool-3".to_string(), 3);
+        assert_eq!(inventory.len(), 2);
+        assert!(inventory.get_mut("pool-2").is_none());
+        assert!(inventory.get_mut("pool-1").is_some());
+        assert!(inventory.get_mut("pool-3").is_some());
+
+        // replacing an existing resource doesn't evict anything
+        assert_eq!(inventory.insert("pool-3".to_string(), 4), Some(3));
+        assert_eq!(inventory.len(), 2);
+    }
+}
@@ -0,0 +1,69 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{sync::Mutex, time::Duration};
+
+/// The default maximum deviation of the requeue durations, as a percentage of the duration
+pub(crate) const DEFAULT_JITTER_PERCENT: &str = "10";
+
+/// Randomizes the requeue durations by up to ±`percent` so that the CRs affected by the same
+/// event (eg: a node reboot) don't all retry at the same time
+pub(crate) struct Jitter {
+    /// The maximum deviation, as a percentage of the duration
+    percent: u8,
+    /// The source of randomness, seeded so that the jitter can be reproduced
+    rng: Mutex<StdRng>,
+}
+
+impl Jitter {
+    /// Create a new `Self` with the given maximum deviation percentage (capped at 100%)
+    /// and an RNG seeded from the given `seed` or, if `None`, from the OS entropy
+    pub(crate) fn new(percent: u8, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            percent: percent.min(100),
+            rng: Mutex::new(rng),
+        }
+    }
+    /// Apply the jitter to the given duration
+    pub(crate) fn apply(&self, duration: Duration) -> Duration {
+        if self.percent == 0 || duration == Duration::ZERO {
+            return duration;
+        }
+        let max = duration.as_secs_f64() * f64::from(self.percent) / 100.0;
+        let offset = self
+            .rng
+            .lock()
+            .expect("jitter rng lock is not poisoned")
+            .gen_range(-max ..= max);
+        Duration::from_secs_f64((duration.as_secs_f64() + offset).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn jitter() {
+        let duration = Duration::from_secs(10);
+
+        let none = Jitter::new(0, Some(1));
+        assert_eq!(none.apply(duration), duration);
+
+        let jitter = Jitter::new(20, Some(1));
+        let durations = (0 .. 100).map(|_| jitter.apply(duration)).collect::<Vec<_>>();
+        assert!(durations
+            .iter()
+            .all(|d| *d >= Duration::from_secs(8) && *d <= Duration::from_secs(12)));
+        assert!(durations.iter().any(|d| *d != duration));
+
+        // the same seed yields the same jitter
+        let same = Jitter::new(20, Some(1));
+        assert_eq!(
+            (0 .. 100).map(|_| same.apply(duration)).collect::<Vec<_>>(),
+            durations
+        );
+    }
+}
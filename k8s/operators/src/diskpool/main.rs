@@ -4,11 +4,17 @@
 //! Successfully created pools are recreated by the control plane.
 
 mod crd;
+mod inventory;
+mod jitter;
+mod metrics;
+mod validate;
 
 use chrono::Utc;
-use clap::{App, Arg, ArgMatches};
+use clap::{App, Arg, ArgMatches, SubCommand};
 use crd::{DiskPool, DiskPoolStatus, PoolState};
 use futures::StreamExt;
+use inventory::Inventory;
+use jitter::Jitter;
 use k8s_openapi::{
     api::core::v1::{Event as k8Event, ObjectReference},
     apimachinery::pkg::apis::meta::v1::MicroTime,
@@ -21,19 +27,29 @@ use kube_runtime::{
     controller::{Context, Controller, ReconcilerAction},
     finalizer::{finalizer, Event},
 };
+use metrics::Metrics;
 use openapi::{
     clients::{self, tower::Url},
-    models::{CreatePoolBody, Pool, RestJsonError},
+    models::{
+        rest_json_error::Kind, BlockDevice, CreatePoolBody, ImportPoolBody, Pool, RestJsonError,
+    },
 };
 use opentelemetry::global;
 
 use serde_json::json;
 use snafu::Snafu;
-use std::{collections::HashMap, ops::Deref, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Deref,
+    sync::Arc,
+    time::Duration,
+};
 use tracing::{debug, error, info, trace, warn};
 
 const WHO_AM_I: &str = "DiskPool Operator";
 const WHO_AM_I_SHORT: &str = "dsp-operator";
+/// How long to wait between attempts to reach the control plane rest endpoint at startup
+const ENDPOINT_PROBE_PERIOD: Duration = Duration::from_secs(5);
 
 /// Errors generated during the reconciliation loop
 #[derive(Debug, Snafu)]
@@ -105,8 +121,8 @@ impl Deref for ResourceContext {
 pub(crate) struct OperatorContext {
     /// Reference to our k8s client
     k8s: Client,
-    /// Hashtable of name and the full last seen CRD
-    inventory: tokio::sync::RwLock<HashMap<String, ResourceContext>>,
+    /// Bounded inventory of name and the full last seen CRD
+    inventory: tokio::sync::RwLock<Inventory<ResourceContext>>,
     /// HTTP client
     http: clients::tower::ApiClient,
     /// Interval
@@ -115,6 +131,10 @@ pub(crate) struct OperatorContext {
     retries: u32,
     /// Disable device validation before attempting to create the pool
     disable_device_validation: bool,
+    /// Health and reconciliation metrics
+    metrics: Arc<Metrics>,
+    /// Jitter applied to the requeue durations
+    jitter: Jitter,
 }
 
 impl OperatorContext {
@@ -129,7 +149,7 @@ impl OperatorContext {
         };
 
         let mut i = self.inventory.write().await;
-        debug!(count = ?i.keys().count(), "current number of CRDS");
+        debug!(count = ?i.len(), "current number of CRDS");
 
         match i.get_mut(&resource.name()) {
             Some(p) => {
@@ -149,6 +169,7 @@ impl OperatorContext {
                     // The status should be the same here as well
                     assert_eq!(&p.status, &resource.status);
                     p.num_retries += 1;
+                    self.metrics.retry();
                     return p.clone();
                 }
 
@@ -172,12 +193,21 @@ impl OperatorContext {
     pub(crate) async fn remove(&self, name: String) -> Option<ResourceContext> {
         let mut i = self.inventory.write().await;
         let removed = i.remove(&name);
+        self.metrics.remove_pool(&name);
         if let Some(removed) = removed {
             info!(name =? removed.name(), "removed from inventory");
             return Some(removed);
         }
         None
     }
+    /// Prune the resources which no longer exist in the cluster from the operator
+    pub(crate) async fn prune(&self, existing: &HashSet<String>) {
+        let pruned = self.inventory.write().await.prune(existing);
+        for name in pruned {
+            self.metrics.remove_pool(&name);
+            info!(name =? name, "pruned from inventory");
+        }
+    }
 }
 
 impl ResourceContext {
@@ -207,9 +237,20 @@ impl ResourceContext {
         self.inner.clone()
     }
 
-    /// Construct an API handle for the resource
+    /// Construct an API handle for the resource, within its own namespace as the operator may be
+    /// watching all namespaces
     fn api(&self) -> Api<DiskPool> {
-        Api::namespaced(self.ctx.k8s.clone(), &self.namespace().unwrap())
+        Api::namespaced(
+            self.ctx.k8s.clone(),
+            &self.namespace().expect("DiskPool must be namespaced"),
+        )
+    }
+
+    /// The interval based requeue duration, with jitter
+    fn interval_requeue(&self) -> Duration {
+        self.ctx
+            .jitter
+            .apply(Duration::from_secs(self.ctx.interval))
     }
 
     fn pools_api(&self) -> &dyn openapi::apis::pools_api::tower::client::Pools {
@@ -223,8 +264,9 @@ impl ResourceContext {
     }
 
     /// Patch the given dsp status to the state provided. When not online the
-    /// size should be assumed to be zero.
+    /// size should be assumed to be zero. The `Ready` condition is kept in line with the state.
     async fn patch_status(&self, status: DiskPoolStatus) -> Result<DiskPool, Error> {
+        let status = status.with_ready_condition(self.status.as_ref());
         let status = json!({ "status": status });
 
         let ps = PatchParams::apply(WHO_AM_I);
@@ -273,7 +315,7 @@ impl ResourceContext {
     async fn mark_unknown(&self) -> Result<ReconcilerAction, Error> {
         self.patch_status(DiskPoolStatus::unknown()).await?;
         Ok(ReconcilerAction {
-            requeue_after: Some(std::time::Duration::from_secs(self.ctx.interval)),
+            requeue_after: Some(self.interval_requeue()),
         })
     }
 
@@ -299,19 +341,30 @@ impl ResourceContext {
         if self.num_retries >= self.ctx.retries {
             return self.stop_reconciliation().await;
         }
+        let uuid = match self.spec.uuid() {
+            None => None,
+            Some(uuid) => match uuid::Uuid::parse_str(&uuid) {
+                Ok(uuid) if !uuid.is_nil() => Some(uuid),
+                _ => {
+                    self.k8s_notify(
+                        "Create or import",
+                        "InvalidUuid",
+                        &format!("The pool uuid: {} is not a valid uuid", uuid),
+                        "Error",
+                    )
+                    .await;
+                    return self.mark_error().await;
+                }
+            },
+        };
         if !self.ctx.disable_device_validation {
             match self
                 .block_devices_api()
                 .get_node_block_devices(&self.spec.node(), Some(true))
                 .await
             {
-                Ok(response) => {
-                    if !response.into_body().into_iter().any(|b| {
-                        b.devname == normalize_disk(&self.spec.disks()[0])
-                            || b.devlinks
-                                .iter()
-                                .any(|d| *d == normalize_disk(&self.spec.disks()[0]))
-                    }) {
+                Ok(response) => match find_disk(&response.into_body(), &self.spec.disks()[0]) {
+                    None => {
                         self.k8s_notify(
                             "Create or import",
                             "Missing",
@@ -328,7 +381,30 @@ impl ResourceContext {
                             timeout: u32::pow(2, self.num_retries),
                         });
                     }
-                }
+                    // the device may only be claimed by this very pool, eg: when it's imported
+                    Some(BlockDevice {
+                        claimed_by: Some(pool),
+                        ..
+                    }) if pool != &self.name() => {
+                        self.k8s_notify(
+                            "Create or import",
+                            "InUse",
+                            &format!(
+                                "The block device(s): {} is already in use by pool {}",
+                                &self.spec.disks()[0],
+                                pool
+                            ),
+                            "Warn",
+                        )
+                        .await;
+
+                        return Err(Error::SpecError {
+                            value: self.spec.disks()[0].clone(),
+                            timeout: u32::pow(2, self.num_retries),
+                        });
+                    }
+                    Some(_) => {}
+                },
                 // We would land here if some error occurred ex, precondition failed, i.e. node
                 // down, in that case we check for pool existence before setting a status.
                 Err(_) => match self.pools_api().get_pool(&self.name()).await {
@@ -362,12 +438,36 @@ impl ResourceContext {
             String::from(utils::DSP_OPERATOR),
         );
 
-        let body = CreatePoolBody::new_all(self.spec.disks(), labels);
-        match self
-            .pools_api()
-            .put_node_pool(&self.spec.node(), &self.name(), body)
-            .await
-        {
+        let imported = match uuid {
+            Some(uuid) => {
+                let body = ImportPoolBody::new_all(self.spec.disks(), labels.clone(), uuid);
+                match self
+                    .pools_api()
+                    .put_node_pool_import(&self.spec.node(), &self.name(), body)
+                    .await
+                {
+                    Err(clients::tower::Error::Response(response))
+                        if response.status() == clients::tower::StatusCode::NOT_FOUND =>
+                    {
+                        // The pool could not be imported, so create it instead.
+                        debug!(name = ?self.name(), "pool import failed, creating it instead");
+                        None
+                    }
+                    result => Some(result),
+                }
+            }
+            None => None,
+        };
+        let result = match imported {
+            Some(result) => result,
+            None => {
+                let body = CreatePoolBody::new_all(self.spec.disks(), labels, None);
+                self.pools_api()
+                    .put_node_pool(&self.spec.node(), &self.name(), body)
+                    .await
+            }
+        };
+        match result {
             Ok(_) => {}
             Err(clients::tower::Error::Response(response))
                 if response.status() == clients::tower::StatusCode::UNPROCESSABLE_ENTITY =>
@@ -376,6 +476,23 @@ impl ResourceContext {
                 // control plane. So we want to update the CRD to
                 // 'Created' to reflect this.
             }
+            Err(clients::tower::Error::Response(clients::tower::ResponseError::Expected(
+                clients::tower::ResponseContent { body, .. },
+            ))) if body.kind == Kind::DeviceInUse => {
+                // The disk is claimed by another pool, retrying won't help.
+                self.k8s_notify(
+                    "Create or import",
+                    "DeviceInUse",
+                    &format!(
+                        "The block device(s): {} already in use: {}",
+                        &self.spec.disks()[0],
+                        body.details
+                    ),
+                    "Error",
+                )
+                .await;
+                return self.mark_error().await;
+            }
             Err(e) => {
                 return Err(e.into());
             }
@@ -532,7 +649,7 @@ impl ResourceContext {
     async fn set_status_or_unknown(&self, pool: Pool) -> Result<ReconcilerAction, Error> {
         if pool.state.is_some() {
             if let Some(status) = &self.status {
-                let new_status = DiskPoolStatus::from(pool);
+                let new_status = DiskPoolStatus::from(pool).with_ready_condition(Some(status));
                 if status != &new_status {
                     // update the usage state such that users can see the values changes
                     // as replica's are added and/or removed.
@@ -540,6 +657,18 @@ impl ResourceContext {
                 }
             }
         } else {
+            // There is no pool state, which may be due to a transient node failure, so ask the
+            // control plane to re-check it right away rather than waiting for its next refresh.
+            if let Ok(response) = self.pools_api().reconcile_pool(&self.name()).await {
+                let pool = response.into_body();
+                if pool.state.is_some() {
+                    debug!("Pool state recovered after a forced reconcile.");
+                    let _ = self.patch_status(DiskPoolStatus::from(pool)).await?;
+                    return Ok(ReconcilerAction {
+                        requeue_after: Some(self.interval_requeue()),
+                    });
+                }
+            }
             // There is no pool state, so we can't determine the health of the pool. Reflect this in
             // the CRD as an 'Unknown' state.
             if let Some(status) = &self.status {
@@ -559,7 +688,7 @@ impl ResourceContext {
 
         // always reschedule though
         Ok(ReconcilerAction {
-            requeue_after: Some(std::time::Duration::from_secs(self.ctx.interval)),
+            requeue_after: Some(self.interval_requeue()),
         })
     }
 
@@ -580,7 +709,14 @@ impl ResourceContext {
 
     async fn k8s_notify(&self, action: &str, reason: &str, message: &str, type_: &str) {
         let client = self.ctx.k8s.clone();
-        let ns = self.namespace().expect("must be namespaced");
+        // the event is posted in the namespace of the resource it refers to
+        let ns = match self.namespace() {
+            Some(ns) => ns,
+            None => {
+                warn!(name = ?self.name(), action, reason, "not namespaced, skipping the event");
+                return;
+            }
+        };
         let e: Api<k8Event> = Api::namespaced(client, &ns);
         let pp = PostParams::default();
         let time = Utc::now();
@@ -689,7 +825,8 @@ async fn ensure_crd(k8s: Client) {
 
 /// Determine what we want to do when dealing with errors from the
 /// reconciliation loop
-fn error_policy(error: &Error, _ctx: Context<OperatorContext>) -> ReconcilerAction {
+fn error_policy(error: &Error, ctx: Context<OperatorContext>) -> ReconcilerAction {
+    ctx.get_ref().metrics.reconcile_error();
     let duration = Duration::from_secs(match error {
         Error::Duplicate { timeout } | Error::SpecError { timeout, .. } => (*timeout).into(),
 
@@ -700,6 +837,7 @@ fn error_policy(error: &Error, _ctx: Context<OperatorContext>) -> ReconcilerActi
         }
         _ => 5,
     });
+    let duration = ctx.get_ref().jitter.apply(duration);
 
     let when = Utc::now()
         .checked_add_signed(chrono::Duration::from_std(duration).unwrap())
@@ -715,6 +853,24 @@ fn error_policy(error: &Error, _ctx: Context<OperatorContext>) -> ReconcilerActi
     }
 }
 
+/// Periodically prune the inventory resources whose CRs no longer exist in the cluster, in case
+/// their removal events were missed
+async fn inventory_pruner(dsp: Api<DiskPool>, ctx: Context<OperatorContext>, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+        match dsp.list(&ListParams::default()).await {
+            Ok(list) => {
+                let existing = list.iter().map(|d| d.name()).collect::<HashSet<_>>();
+                ctx.get_ref().prune(&existing).await;
+            }
+            Err(error) => {
+                warn!(%error, "failed to list the CRDS, skipping the inventory pruning");
+            }
+        }
+    }
+}
+
 /// The main work horse
 #[tracing::instrument(fields(name = %dsp.spec.node(), status = ?dsp.status) skip(dsp, ctx))]
 async fn reconcile(
@@ -722,6 +878,7 @@ async fn reconcile(
     ctx: Context<OperatorContext>,
 ) -> Result<ReconcilerAction, Error> {
     let ctx = ctx.into_inner();
+    ctx.metrics.reconcile(dsp.name(), &dsp.status);
     let dsp = ctx.upsert(ctx.clone(), dsp).await;
 
     let _ = dsp.finalizer().await;
@@ -766,14 +923,70 @@ async fn reconcile(
     }
 }
 
-async fn pool_controller(args: ArgMatches<'_>) -> anyhow::Result<()> {
-    let k8s = Client::try_default().await?;
-    let namespace = args.value_of("namespace").unwrap();
-    ensure_crd(k8s.clone()).await;
+/// Construct an API handle for the DiskPools of the given namespace or, if `None`, of all
+/// namespaces
+pub(crate) fn diskpool_api(k8s: Client, namespace: Option<&str>) -> Api<DiskPool> {
+    match namespace {
+        Some(namespace) => Api::namespaced(k8s, namespace),
+        None => Api::all(k8s),
+    }
+}
 
-    let dsp: Api<DiskPool> = Api::namespaced(k8s.clone(), namespace);
-    let lp = ListParams::default();
-    let url = Url::parse(args.value_of("endpoint").unwrap()).expect("endpoint is not a valid URL");
+/// Get the namespace to operate in from the command line arguments or, if all namespaces are to
+/// be watched, `None`
+fn operator_namespace<'a>(args: &'a ArgMatches<'_>) -> Option<&'a str> {
+    if args.is_present("all-namespaces") {
+        None
+    } else {
+        args.value_of("namespace")
+    }
+}
+
+/// Parse and validate the control plane rest endpoint URL
+fn parse_endpoint(endpoint: &str) -> anyhow::Result<Url> {
+    let url = Url::parse(endpoint)
+        .map_err(|error| anyhow::anyhow!("Invalid endpoint URL '{}': {}", endpoint, error))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!(
+            "Invalid endpoint URL '{}': unsupported scheme '{}', expected 'http' or 'https'",
+            endpoint,
+            url.scheme()
+        );
+    }
+    if url.host_str().map(str::is_empty).unwrap_or(true) {
+        anyhow::bail!("Invalid endpoint URL '{}': missing host", endpoint);
+    }
+    Ok(url)
+}
+
+/// Wait until the control plane rest endpoint is reachable.
+/// Any response, even an error one, means the server is reachable.
+async fn wait_for_endpoint(http: &clients::tower::ApiClient, url: &Url) {
+    let mut attempt = 1;
+    loop {
+        match http.nodes_api().get_nodes().await {
+            Ok(_) | Err(clients::tower::Error::Response(_)) => {
+                info!(endpoint = %url, "control plane rest endpoint is reachable");
+                return;
+            }
+            Err(clients::tower::Error::Request(error)) => {
+                warn!(
+                    endpoint = %url,
+                    attempt,
+                    %error,
+                    "control plane rest endpoint is not reachable, retrying in {:?}",
+                    ENDPOINT_PROBE_PERIOD
+                );
+            }
+        }
+        attempt += 1;
+        tokio::time::sleep(ENDPOINT_PROBE_PERIOD).await;
+    }
+}
+
+/// Create the control plane rest client from the command line arguments
+fn rest_client(args: &ArgMatches<'_>) -> anyhow::Result<(clients::tower::ApiClient, Url)> {
+    let url = parse_endpoint(args.value_of("endpoint").unwrap())?;
 
     let timeout: Duration = args
         .value_of("request-timeout")
@@ -782,18 +995,55 @@ async fn pool_controller(args: ArgMatches<'_>) -> anyhow::Result<()> {
         .expect("timeout value is invalid")
         .into();
 
-    let cfg =
-        clients::tower::Configuration::new(url, timeout, None, None, true).map_err(|error| {
+    let cfg = clients::tower::Configuration::new(url.clone(), timeout, None, None, true).map_err(
+        |error| {
             anyhow::anyhow!(
                 "Failed to create openapi configuration, Error: '{:?}'",
                 error
             )
-        })?;
+        },
+    )?;
+
+    Ok((clients::tower::ApiClient::new(cfg), url))
+}
+
+async fn pool_controller(args: ArgMatches<'_>) -> anyhow::Result<()> {
+    if let Some(validate_args) = args.subcommand_matches("validate") {
+        // validation must not mutate anything, so don't even attempt to install the CRD
+        let (http, _) = rest_client(&args)?;
+        let files = validate_args
+            .values_of("manifest")
+            .map(|files| files.map(ToString::to_string).collect())
+            .unwrap_or_default();
+        return validate::validate(&http, files, operator_namespace(&args)).await;
+    }
+
+    let metrics = Arc::new(Metrics::default());
+    let metrics_address = args
+        .value_of("metrics-address")
+        .unwrap()
+        .parse()
+        .map_err(|error| anyhow::anyhow!("Invalid metrics address: {}", error))?;
+    tokio::spawn(metrics::serve(metrics_address, metrics.clone()));
+
+    let k8s = Client::try_default().await?;
+    let namespace = operator_namespace(&args);
+    ensure_crd(k8s.clone()).await;
+
+    let dsp = diskpool_api(k8s.clone(), namespace);
+    let lp = ListParams::default();
+    let (http, url) = rest_client(&args)?;
+    wait_for_endpoint(&http, &url).await;
 
     let context = Context::new(OperatorContext {
         k8s,
-        inventory: tokio::sync::RwLock::new(HashMap::new()),
-        http: clients::tower::ApiClient::new(cfg),
+        inventory: tokio::sync::RwLock::new(Inventory::new(
+            args.value_of("inventory-capacity")
+                .unwrap()
+                .parse::<usize>()
+                .expect("inventory capacity value is invalid"),
+        )),
+        http,
         interval: args
             .value_of("interval")
             .unwrap()
@@ -806,12 +1056,36 @@ async fn pool_controller(args: ArgMatches<'_>) -> anyhow::Result<()> {
             .parse::<u32>()
             .expect("retries value is invalid"),
         disable_device_validation: args.is_present("disable_device_validation"),
+        metrics: metrics.clone(),
+        jitter: Jitter::new(
+            args.value_of("requeue-jitter")
+                .unwrap()
+                .parse::<u8>()
+                .expect("requeue jitter value is invalid"),
+            None,
+        ),
     });
 
-    info!(
-        "Starting DiskPool Operator (dsp) in namespace {}",
-        namespace
-    );
+    let prune_interval: Duration = args
+        .value_of("inventory-prune-interval")
+        .unwrap()
+        .parse::<humantime::Duration>()
+        .expect("inventory prune interval value is invalid")
+        .into();
+    tokio::spawn(inventory_pruner(
+        dsp.clone(),
+        context.clone(),
+        prune_interval,
+    ));
+
+    match namespace {
+        Some(namespace) => info!(
+            "Starting DiskPool Operator (dsp) in namespace {}",
+            namespace
+        ),
+        None => info!("Starting DiskPool Operator (dsp) in all namespaces"),
+    }
+    metrics.set_ready();
 
     Controller::new(dsp, lp)
         .run(reconcile, error_policy, context)
@@ -878,6 +1152,13 @@ async fn main() -> anyhow::Result<()> {
                 .default_value("mayastor")
                 .help("the default namespace we are supposed to operate in"),
         )
+        .arg(
+            Arg::with_name("all-namespaces")
+                .long("all-namespaces")
+                .short("-A")
+                .takes_value(false)
+                .help("operate on the DiskPools of all namespaces, ignoring --namespace"),
+        )
         .arg(
             Arg::with_name("jaeger")
                 .short("-j")
@@ -885,12 +1166,61 @@ async fn main() -> anyhow::Result<()> {
                 .env("JAEGER_ENDPOINT")
                 .help("enable open telemetry and forward to jaeger"),
         )
+        .arg(
+            Arg::with_name("log-format")
+                .long("log-format")
+                .env("LOG_FORMAT")
+                .default_value("text")
+                .possible_values(&["text", "json"])
+                .help("the format of the logs"),
+        )
+        .arg(
+            Arg::with_name("inventory-capacity")
+                .long("inventory-capacity")
+                .env("INVENTORY_CAPACITY")
+                .default_value(inventory::DEFAULT_INVENTORY_CAPACITY)
+                .help(
+                    "the maximum number of CRDS kept in the inventory, evicting the stalest ones",
+                ),
+        )
+        .arg(
+            Arg::with_name("inventory-prune-interval")
+                .long("inventory-prune-interval")
+                .env("INVENTORY_PRUNE_INTERVAL")
+                .default_value("5m")
+                .help("specify how often the inventory is pruned of CRDS no longer in the cluster"),
+        )
+        .arg(
+            Arg::with_name("metrics-address")
+                .long("metrics-address")
+                .env("METRICS_ADDRESS")
+                .default_value(metrics::DEFAULT_METRICS_ADDRESS)
+                .help("the address to serve the /healthz, /readyz and /metrics endpoints on"),
+        )
+        .arg(
+            Arg::with_name("requeue-jitter")
+                .long("requeue-jitter")
+                .env("REQUEUE_JITTER")
+                .default_value(jitter::DEFAULT_JITTER_PERCENT)
+                .help("the maximum percentage by which the requeue durations are randomized"),
+        )
         .arg(
             Arg::with_name("disable_device_validation")
                 .long("disable-device-validation")
                 .takes_value(false)
                 .help("do not attempt to validate the block device prior to pool creation"),
         )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about(
+                    "validate that the DiskPool nodes are known and their disks exist, without \
+                     creating any pools",
+                )
+                .arg(Arg::with_name("manifest").multiple(true).help(
+                    "DiskPool manifest files to validate, otherwise the DiskPools of the \
+                     namespace are validated",
+                )),
+        )
         .get_matches();
 
     utils::print_package_info!();
@@ -899,10 +1229,16 @@ async fn main() -> anyhow::Result<()> {
         utils::raw_version_str(),
         env!("CARGO_PKG_VERSION"),
     );
-    utils::tracing_telemetry::init_tracing(
+    utils::tracing_telemetry::init_tracing_format(
         "dsp-operator",
         tags,
         matches.value_of("jaeger").map(|s| s.to_string()),
+        None,
+        matches
+            .value_of("log-format")
+            .unwrap()
+            .parse()
+            .expect("log format is one of the possible values"),
     );
 
     pool_controller(matches).await?;
@@ -922,6 +1258,14 @@ fn normalize_disk(disk: &str) -> String {
     })
 }
 
+/// Find the block device which matches the given disk, either by its name or one of its links
+fn find_disk<'a>(devices: &'a [BlockDevice], disk: &str) -> Option<&'a BlockDevice> {
+    let disk = normalize_disk(disk);
+    devices
+        .iter()
+        .find(|b| b.devname == disk || b.devlinks.iter().any(|d| *d == disk))
+}
+
 #[cfg(test)]
 mod test {
 
@@ -938,4 +1282,16 @@ mod test {
         assert_eq!(normalize_disk(disks[1]), "/dev/null");
         assert_eq!(normalize_disk(disks[2]), "uring://dev/null");
     }
+
+    #[test]
+    fn parse_endpoint() {
+        use super::*;
+        assert!(parse_endpoint("http://ksnode-1:30011").is_ok());
+        assert!(parse_endpoint("https://10.0.0.1:30011").is_ok());
+
+        assert!(parse_endpoint("ksnode-1:30011").is_err());
+        assert!(parse_endpoint("ftp://ksnode-1:30011").is_err());
+        assert!(parse_endpoint("http://").is_err());
+        assert!(parse_endpoint("not a url").is_err());
+    }
 }
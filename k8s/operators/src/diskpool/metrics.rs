@@ -0,0 +1,195 @@
+use crate::crd::{DiskPoolStatus, PoolState};
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tracing::{error, info};
+
+/// Default address the health and metrics http server binds to
+pub(crate) const DEFAULT_METRICS_ADDRESS: &str = "0.0.0.0:8080";
+
+/// Operator health and reconciliation metrics
+#[derive(Default)]
+pub(crate) struct Metrics {
+    /// Set once the operator has started reconciling
+    ready: AtomicBool,
+    /// Number of reconciliations
+    reconciles: AtomicU64,
+    /// Number of reconciliations which failed
+    reconcile_errors: AtomicU64,
+    /// Number of reconciliations retried within the same resource version
+    retries: AtomicU64,
+    /// Last seen state of each pool, keyed by name
+    pools: Mutex<HashMap<String, &'static str>>,
+}
+
+impl Metrics {
+    /// Mark the operator as ready
+    pub(crate) fn set_ready(&self) {
+        self.ready.store(true, Ordering::Relaxed);
+    }
+    /// Check if the operator is ready
+    pub(crate) fn ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+    /// Account for a reconciliation of the given pool, setting its last seen state
+    pub(crate) fn reconcile(&self, name: String, status: &Option<DiskPoolStatus>) {
+        self.reconciles.fetch_add(1, Ordering::Relaxed);
+        self.pools
+            .lock()
+            .expect("not poisoned")
+            .insert(name, state_label(status));
+    }
+    /// Account for a failed reconciliation
+    pub(crate) fn reconcile_error(&self) {
+        self.reconcile_errors.fetch_add(1, Ordering::Relaxed);
+    }
+    /// Account for a retried reconciliation
+    pub(crate) fn retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+    /// Stop reporting the state of the given pool
+    pub(crate) fn remove_pool(&self, name: &str) {
+        self.pools.lock().expect("not poisoned").remove(name);
+    }
+
+    /// Render the metrics in the prometheus text format
+    fn render(&self) -> String {
+        let mut pools = BTreeMap::new();
+        for state in STATE_LABELS {
+            pools.insert(*state, 0u64);
+        }
+        for state in self.pools.lock().expect("not poisoned").values() {
+            *pools.entry(*state).or_default() += 1;
+        }
+
+        let mut text = String::new();
+        let mut counter = |name: &str, help: &str, value: &AtomicU64| {
+            let _ = writeln!(text, "# HELP {} {}", name, help);
+            let _ = writeln!(text, "# TYPE {} counter", name);
+            let _ = writeln!(text, "{} {}", name, value.load(Ordering::Relaxed));
+        };
+        counter(
+            "dsp_operator_reconciles_total",
+            "Number of DiskPool reconciliations",
+            &self.reconciles,
+        );
+        counter(
+            "dsp_operator_reconcile_errors_total",
+            "Number of failed DiskPool reconciliations",
+            &self.reconcile_errors,
+        );
+        counter(
+            "dsp_operator_retries_total",
+            "Number of retried DiskPool reconciliations",
+            &self.retries,
+        );
+        let _ = writeln!(
+            text,
+            "# HELP dsp_operator_pools Number of DiskPools per state"
+        );
+        let _ = writeln!(text, "# TYPE dsp_operator_pools gauge");
+        for (state, count) in pools {
+            let _ = writeln!(text, "dsp_operator_pools{{state=\"{}\"}} {}", state, count);
+        }
+        text
+    }
+}
+
+/// All the pool state labels, so that every state is reported even when it has no pools
+const STATE_LABELS: &[&str] = &["New", "Creating", "Created", "Online", "Unknown", "Error"];
+
+/// The label of the state of the given pool status
+fn state_label(status: &Option<DiskPoolStatus>) -> &'static str {
+    match status.as_ref().map(|s| &s.state) {
+        None => "New",
+        Some(PoolState::Creating) => "Creating",
+        Some(PoolState::Created) => "Created",
+        Some(PoolState::Online) => "Online",
+        Some(PoolState::Unknown) => "Unknown",
+        Some(PoolState::Error) => "Error",
+    }
+}
+
+async fn handle(
+    metrics: Arc<Metrics>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/healthz") => Response::new(Body::from("ok")),
+        (&Method::GET, "/readyz") if metrics.ready() => Response::new(Body::from("ok")),
+        (&Method::GET, "/readyz") => Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .body(Body::from("not ready"))
+            .expect("valid response"),
+        (&Method::GET, "/metrics") => Response::builder()
+            .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+            .body(Body::from(metrics.render()))
+            .expect("valid response"),
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .expect("valid response"),
+    };
+    Ok(response)
+}
+
+/// Serve the `/healthz`, `/readyz` and `/metrics` endpoints on the given address
+pub(crate) async fn serve(address: SocketAddr, metrics: Arc<Metrics>) {
+    let service = make_service_fn(move |_| {
+        let metrics = metrics.clone();
+        let service = service_fn(move |request| handle(metrics.clone(), request));
+        async move { Ok::<_, Infallible>(service) }
+    });
+    let server = match Server::try_bind(&address) {
+        Ok(builder) => builder.serve(service),
+        Err(error) => {
+            error!(%address, %error, "failed to bind the health and metrics server");
+            return;
+        }
+    };
+    info!(%address, "serving the health and metrics endpoints");
+    if let Err(error) = server.await {
+        error!(%error, "health and metrics server failed");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let metrics = Metrics::default();
+        metrics.reconcile("pool-1".to_string(), &None);
+        metrics.reconcile("pool-2".to_string(), &None);
+        metrics.reconcile("pool-1".to_string(), &Some(DiskPoolStatus::default()));
+        metrics.retry();
+        metrics.reconcile_error();
+        metrics.remove_pool("pool-3");
+
+        let text = metrics.render();
+        assert!(text.contains("dsp_operator_reconciles_total 3\n"));
+        assert!(text.contains("dsp_operator_reconcile_errors_total 1\n"));
+        assert!(text.contains("dsp_operator_retries_total 1\n"));
+        assert!(text.contains("dsp_operator_pools{state=\"New\"} 1\n"));
+        assert!(text.contains("dsp_operator_pools{state=\"Creating\"} 1\n"));
+        assert!(text.contains("dsp_operator_pools{state=\"Online\"} 0\n"));
+
+        metrics.remove_pool("pool-2");
+        assert!(metrics
+            .render()
+            .contains("dsp_operator_pools{state=\"New\"} 0\n"));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{crd::DiskPool, diskpool_api, find_disk, Error};
+use kube::{api::ListParams, Client, ResourceExt};
+use openapi::clients;
+use std::fmt::{Display, Formatter};
+
+/// What was found about a single disk of a DiskPool
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum DiskFinding {
+    /// the disk exists on the node and is available for use
+    Found,
+    /// the disk exists on the node but it's currently in use, eg: by an existing pool
+    InUse,
+    /// the disk can not be found on the node
+    Missing,
+}
+
+impl Display for DiskFinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiskFinding::Found => write!(f, "found"),
+            DiskFinding::InUse => write!(f, "found, currently in use"),
+            DiskFinding::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// Validation report of a DiskPool
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct PoolReport {
+    /// name of the DiskPool
+    name: String,
+    /// node the pool is placed on
+    node: String,
+    /// reason why the node or its block devices could not be checked
+    node_error: Option<String>,
+    /// findings for each disk of the pool
+    disks: Vec<(String, DiskFinding)>,
+}
+
+impl PoolReport {
+    /// The pool is valid when its node is known and all of its disks can be found
+    pub(crate) fn valid(&self) -> bool {
+        self.node_error.is_none()
+            && !self.disks.is_empty()
+            && self.disks.iter().all(|(_, f)| f != &DiskFinding::Missing)
+    }
+}
+
+impl Display for PoolReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let result = if self.valid() { "ok" } else { "invalid" };
+        writeln!(
+            f,
+            "DiskPool '{}' on node '{}': {}",
+            self.name, self.node, result
+        )?;
+        if let Some(error) = &self.node_error {
+            writeln!(f, "  node: {}", error)?;
+        }
+        if self.disks.is_empty() {
+            writeln!(f, "  no disks specified")?;
+        }
+        for (disk, finding) in &self.disks {
+            writeln!(f, "  disk '{}': {}", disk, finding)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check that the node of the given DiskPool is known to the control plane and that its disks
+/// can be found on the node, without creating anything
+pub(crate) async fn validate_pool(http: &clients::tower::ApiClient, dsp: &DiskPool) -> PoolReport {
+    let mut report = PoolReport {
+        name: dsp.name(),
+        node: dsp.spec.node(),
+        node_error: None,
+        disks: vec![],
+    };
+
+    match http.nodes_api().get_node(&report.node).await {
+        Ok(_) => {}
+        Err(clients::tower::Error::Response(response))
+            if response.status() == clients::tower::StatusCode::NOT_FOUND =>
+        {
+            report.node_error = Some("not known to the control plane".to_string());
+            return report;
+        }
+        Err(error) => {
+            report.node_error = Some(format!("failed to get the node: {}", Error::from(error)));
+            return report;
+        }
+    }
+
+    let devices = match http
+        .block_devices_api()
+        .get_node_block_devices(&report.node, Some(true))
+        .await
+    {
+        Ok(response) => response.into_body(),
+        Err(error) => {
+            report.node_error = Some(format!(
+                "failed to get the block devices: {}",
+                Error::from(error)
+            ));
+            return report;
+        }
+    };
+
+    report.disks = dsp
+        .spec
+        .disks()
+        .into_iter()
+        .map(|disk| {
+            let finding = match find_disk(&devices, &disk) {
+                Some(device) if device.available => DiskFinding::Found,
+                Some(_) => DiskFinding::InUse,
+                None => DiskFinding::Missing,
+            };
+            (disk, finding)
+        })
+        .collect();
+    report
+}
+
+/// Validate the DiskPools from the given manifest files or, if none are given, the DiskPool CRs
+/// in the namespace (all namespaces if `None`). Each pool report is printed and an error is
+/// returned if any is invalid.
+pub(crate) async fn validate(
+    http: &clients::tower::ApiClient,
+    files: Vec<String>,
+    namespace: Option<&str>,
+) -> anyhow::Result<()> {
+    let pools = if files.is_empty() {
+        let k8s = Client::try_default().await?;
+        let dsp = diskpool_api(k8s, namespace);
+        dsp.list(&ListParams::default()).await?.items
+    } else {
+        let mut pools = vec![];
+        for file in files {
+            let manifest = std::fs::read_to_string(&file)
+                .map_err(|error| anyhow::anyhow!("Failed to read '{}': {}", file, error))?;
+            let pool: DiskPool = serde_yaml::from_str(&manifest)
+                .map_err(|error| anyhow::anyhow!("Invalid DiskPool in '{}': {}", file, error))?;
+            pools.push(pool);
+        }
+        pools
+    };
+
+    let mut invalid = 0;
+    for pool in &pools {
+        let report = validate_pool(http, pool).await;
+        if !report.valid() {
+            invalid += 1;
+        }
+        print!("{}", report);
+    }
+
+    if invalid > 0 {
+        anyhow::bail!(
+            "{} of {} DiskPool(s) failed validation",
+            invalid,
+            pools.len()
+        );
+    }
+    println!("{} DiskPool(s) validated", pools.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let mut report = PoolReport {
+            name: "pool-1".to_string(),
+            node: "node-1".to_string(),
+            node_error: None,
+            disks: vec![
+                ("/dev/sda".to_string(), DiskFinding::Found),
+                ("/dev/sdb".to_string(), DiskFinding::InUse),
+            ],
+        };
+        assert!(report.valid());
+        assert_eq!(
+            report.to_string(),
+            "DiskPool 'pool-1' on node 'node-1': ok\n  disk '/dev/sda': found\n  disk '/dev/sdb': found, currently in use\n"
+        );
+
+        report
+            .disks
+            .push(("/dev/sdc".to_string(), DiskFinding::Missing));
+        assert!(!report.valid());
+
+        report.disks.clear();
+        assert!(!report.valid());
+
+        report.node_error = Some("not known to the control plane".to_string());
+        assert_eq!(
+            report.to_string(),
+            "DiskPool 'pool-1' on node 'node-1': invalid\n  node: not known to the control plane\n  no disks specified\n"
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use crate::collect::{
-    error::Error, resources::traits::Topologer, rest_wrapper::rest_wrapper_client::RestClient,
+    error::Error, logs::LogDirection, resources::traits::Topologer,
+    rest_wrapper::rest_wrapper_client::RestClient,
 };
 use chrono::Local;
+use std::collections::HashMap;
 
 /// DumpConfig helps to create new instance of Dumper
 #[derive(Debug)]
@@ -18,6 +20,18 @@ pub(crate) struct DumpConfig {
     pub(crate) etcd_uri: Option<String>,
     /// Period states to collect logs from specified duration
     pub(crate) since: humantime::Duration,
+    /// Period states to collect logs up to specified duration, defaults to now
+    pub(crate) until: Option<humantime::Duration>,
+    /// Order in which the logs are collected
+    pub(crate) log_direction: LogDirection,
+    /// Loki label selectors overriding the default ones, keyed by container name
+    pub(crate) loki_label_selectors: HashMap<String, Vec<String>>,
+    /// Maximum number of log entries fetched from Loki on one http call
+    pub(crate) loki_limit: u64,
+    /// Maximum number of times a request which failed to connect to Loki is retried
+    pub(crate) loki_retries: u32,
+    /// Write the logs collected from Loki gzip compressed
+    pub(crate) compress: bool,
     /// Path to kubeconfig file, which requires to interact with Kube-Apiserver
     pub(crate) kube_config_path: Option<std::path::PathBuf>,
     /// Specifies the timeout value to interact with other systems
@@ -1,23 +1,68 @@
 use crate::{collect::utils::write_to_log_file, log};
 use chrono::Utc;
+use flate2::{write::GzEncoder, Compression};
 use serde::{Deserialize, Serialize};
-use std::{io::Write, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+use tokio_util::sync::CancellationToken;
 
 /// Loki endpoint to query for logs
 const ENDPOINT: &str = "/loki/api/v1/query_range";
 
 const SERVICE_NAME: &str = "loki";
 
+/// Default maximum number of log entries returned by Loki on one http call
+pub(crate) const DEFAULT_LIMIT: &str = "3000";
+
+/// Default maximum number of times a request which failed to connect to Loki is retried
+pub(crate) const DEFAULT_RETRIES: &str = "3";
+/// Delay before the first retry of a request which failed to connect to Loki, doubled on each
+/// subsequent retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Upper bound of the delay before retrying a request which failed to connect to Loki
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Maximum number of times a request throttled by Loki is retried
+const MAX_THROTTLED_RETRIES: u32 = 5;
+/// Delay before retrying a throttled request when Loki doesn't specify one
+const DEFAULT_THROTTLED_DELAY: Duration = Duration::from_secs(1);
+/// Upper bound of the delay before retrying a throttled request
+const MAX_THROTTLED_DELAY: Duration = Duration::from_secs(30);
+
 /// Possible errors can occur while interacting with Loki service
 #[derive(Debug)]
 pub(crate) enum LokiError {
     ReqError(reqwest::Error),
+    /// Failed to send the request to Loki or to receive its response, which may be transient
+    Connection(reqwest::Error),
+    /// Failed to decode the response from Loki
+    Decode(reqwest::Error),
     IOError(std::io::Error),
+    /// Loki is busy (too many outstanding requests) and asked us to retry after the given delay
+    Throttled {
+        retry_after: Option<Duration>,
+    },
+    /// Loki failed to serve the request
+    Server {
+        status: reqwest::StatusCode,
+        message: String,
+    },
 }
 
 impl From<reqwest::Error> for LokiError {
     fn from(e: reqwest::Error) -> LokiError {
-        LokiError::ReqError(e)
+        if e.is_decode() {
+            LokiError::Decode(e)
+        } else if e.is_connect() || e.is_timeout() {
+            LokiError::Connection(e)
+        } else {
+            LokiError::ReqError(e)
+        }
     }
 }
 
@@ -59,35 +104,53 @@ struct LokiResponse {
 type SinceTime = u128;
 
 impl LokiResponse {
-    // fetch last stream log epoch timestamp in nanoseconds
-    fn get_last_stream_unix_time(&self) -> SinceTime {
-        let unix_time = match self.data.result.last() {
-            Some(last_stream) => last_stream
-                .values
-                .last()
-                .unwrap_or(&vec![])
-                .get(0)
-                .unwrap_or(&"0".to_string())
-                .parse::<SinceTime>()
-                .unwrap_or(0),
-            None => {
-                return 0;
-            }
-        };
-        unix_time
+    // fetch the epoch timestamp in nanoseconds of the log entry furthest along the given
+    // direction, across all the streams, ie: the latest entry when going forward and the
+    // earliest entry when going backward
+    fn get_boundary_unix_time(&self, direction: &LogDirection) -> Option<SinceTime> {
+        let timestamps = self
+            .data
+            .result
+            .iter()
+            .flat_map(|stream| stream.values.iter())
+            .filter_map(|value| value.get(0))
+            .filter_map(|timestamp| timestamp.parse::<SinceTime>().ok());
+        match direction {
+            LogDirection::Forward => timestamps.max(),
+            LogDirection::Backward => timestamps.min(),
+        }
     }
 }
 
-// Determines the sort order of logs
-#[derive(Debug, Clone)]
-enum LogDirection {
+/// Determines the sort order of logs
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum LogDirection {
+    /// From the oldest to the most recent logs
     Forward,
+    /// From the most recent to the oldest logs
+    Backward,
 }
 
 impl LogDirection {
     fn as_string(&self) -> String {
         match self {
             LogDirection::Forward => "forward".to_string(),
+            LogDirection::Backward => "backward".to_string(),
+        }
+    }
+}
+
+impl FromStr for LogDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(LogDirection::Forward),
+            "backward" => Ok(LogDirection::Backward),
+            _ => Err(format!(
+                "Invalid log direction '{}', expected 'forward' or 'backward'",
+                s
+            )),
         }
     }
 }
@@ -102,63 +165,129 @@ pub(crate) struct LokiClient {
     logs_endpoint: String,
     // Defines period from which logs needs to collect
     since: SinceTime,
+    // Defines period up to which logs needs to collect, defaults to now
+    until: Option<SinceTime>,
     // Determines the sort order of logs. Supported values are "forward" or "backward".
     // Defaults to forward
     direction: LogDirection,
     // maximum number of entries to return on one http call
     limit: u64,
-    // specifies the timeout value to interact with Loki service
-    timeout: humantime::Duration,
+    // label selectors to use instead of the default ones, keyed by container name
+    label_selectors: HashMap<String, Vec<String>>,
+    // maximum number of times a request which failed to connect to Loki is retried
+    retries: u32,
+    // write the log files gzip compressed, as .log.gz
+    compress: bool,
+    // http client used for all the requests to the Loki service, reusing its connections
+    client: reqwest::Client,
+    // cancelled when the log collection should be stopped
+    cancel: CancellationToken,
 }
 
 impl LokiClient {
     /// Instantiate new instance of Http Loki client
+    /// The log collection is stopped once the given `cancel` token is cancelled.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         uri: String,
         since: humantime::Duration,
+        until: Option<humantime::Duration>,
+        direction: LogDirection,
+        label_selectors: HashMap<String, Vec<String>>,
+        limit: u64,
+        retries: u32,
+        compress: bool,
         timeout: humantime::Duration,
-    ) -> Self {
-        LokiClient {
+        cancel: CancellationToken,
+    ) -> Result<Self, LokiError> {
+        // TODO: Test timeouts when Loki service is dropped unexpectedly
+        let client = reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_secs(timeout.as_secs()))
+            .build()?;
+        Ok(LokiClient {
             uri,
             since: get_epoch_unix_time(since),
+            until: until.map(get_epoch_unix_time),
             logs_endpoint: ENDPOINT.to_string(),
-            direction: LogDirection::Forward,
-            limit: 3000,
-            timeout,
-        }
+            direction,
+            limit: limit.max(1),
+            label_selectors,
+            retries,
+            compress,
+            client,
+            cancel,
+        })
     }
 
-    /// fetch_and_dump_logs will do the following steps:
+    /// fetch_and_dump_logs will do the following steps for each of the label selectors:
     /// 1. Creates poller to interact with Loki service based on provided arguments
-    ///     1.1. Use poller to fetch all available logs
-    ///     1.2. Write fetched logs into file
-    ///     Continue above steps till extraction all logs
+    /// 2. Use poller to fetch all available logs
+    /// 3. Write fetched logs into file
+    ///
+    /// Continue steps 2 and 3 till extraction of all logs.
+    /// If the collection is cancelled, the logs fetched so far are flushed into the file and
+    /// no further requests are made.
+    /// When compression is enabled the logs are written into a gzip compressed `.log.gz` file.
+    /// When there's more than one label selector the logs of each selector are written into
+    /// their own file, suffixed with the selector.
     pub(crate) async fn fetch_and_dump_logs(
         &self,
-        label_selector: String,
+        label_selectors: Vec<String>,
         container_name: String,
         host_name: Option<String>,
         service_dir: PathBuf,
     ) -> Result<(), LokiError> {
-        // Build query params: Convert label selector into Loki supported query field
-        // Below snippet convert app=mayastor,openebs.io/storage=mayastor into
-        //  app="mayastor",openebs_io_storage="mayastor"(Loki supported values)
-        let mut label_filters: String = label_selector
-            .split(',')
-            .into_iter()
-            .map(|key_value_pair| {
-                let pairs = key_value_pair.split('=').collect::<Vec<&str>>();
-                format!("{}=\"{}\",", pairs[0], pairs[1])
-                    .replace(".", "_")
-                    .replace("/", "_")
-            })
-            .collect::<String>();
-        if !label_filters.is_empty() {
-            label_filters.pop();
+        // Some containers are labelled differently in Loki, so use their own selectors if any
+        let label_selectors = self
+            .label_selectors
+            .get(&container_name)
+            .cloned()
+            .unwrap_or(label_selectors);
+        let multiple = label_selectors.len() > 1;
+        let mut result = Ok(());
+        for label_selector in label_selectors {
+            let file_suffix = multiple.then(|| file_name_suffix(&label_selector));
+            if let Err(error) = self
+                .fetch_and_dump_selector_logs(
+                    &label_selector,
+                    &container_name,
+                    host_name.as_deref(),
+                    file_suffix,
+                    &service_dir,
+                )
+                .await
+            {
+                // carry on with the remaining selectors
+                result = Err(error);
+            }
+            if self.cancel.is_cancelled() {
+                break;
+            }
         }
+        result
+    }
+
+    /// Fetch the logs of the container matching the given label selector and dump them into a
+    /// file, suffixed with `file_suffix` if any.
+    async fn fetch_and_dump_selector_logs(
+        &self,
+        label_selector: &str,
+        container_name: &str,
+        host_name: Option<&str>,
+        file_suffix: Option<String>,
+        service_dir: &std::path::Path,
+    ) -> Result<(), LokiError> {
+        let label_filters = label_filters(label_selector);
+        let extension = if self.compress { "log.gz" } else { "log" };
+        let file_suffix = file_suffix
+            .map(|suffix| format!("-{}", suffix))
+            .unwrap_or_default();
         let (file_name, new_query_field) = match host_name {
             Some(host_name) => {
-                let file_name = format!("{}-{}-{}.log", host_name, SERVICE_NAME, container_name);
+                let file_name = format!(
+                    "{}-{}-{}{}.{}",
+                    host_name, SERVICE_NAME, container_name, file_suffix, extension
+                );
                 let new_query_field = format!(
                     "{{{},container=\"{}\",hostname=~\"{}.*\"}}",
                     label_filters, container_name, host_name
@@ -166,7 +295,10 @@ impl LokiClient {
                 (file_name, new_query_field)
             }
             None => {
-                let file_name = format!("{}-{}.log", SERVICE_NAME, container_name);
+                let file_name = format!(
+                    "{}-{}{}.{}",
+                    SERVICE_NAME, container_name, file_suffix, extension
+                );
                 let new_query_field =
                     format!("{{{},container=\"{}\"}}", label_filters, container_name);
                 (file_name, new_query_field)
@@ -181,33 +313,84 @@ impl LokiClient {
         );
 
         let mut poller = LokiPoll {
+            client: &self.client,
             uri: self.uri.clone(),
             endpoint: self.logs_endpoint.clone(),
             since: self.since,
+            until: self.until,
+            direction: self.direction.clone(),
+            limit: self.limit,
             query_params,
             next_start_epoch_timestamp: 0,
-            timeout: self.timeout,
+            seen: HashSet::new(),
+            done: false,
         };
         let mut is_written = false;
-        let file_path = service_dir.join(file_name.clone());
-        let mut log_file: std::fs::File = std::fs::File::create(file_path.clone())?;
+        let file_path = service_dir.join(file_name);
+        let mut log_file = LogFile::create(&file_path, self.compress)?;
+        let mut throttled = 0;
+        let mut failures = 0;
 
         loop {
-            let result = match poller.poll_next().await {
+            let poll_result = tokio::select! {
+                _ = self.cancel.cancelled() => {
+                    if !is_written {
+                        remove_empty_log_file(file_path);
+                    }
+                    write_to_log_file(format!(
+                        "[Warning] Cancelled fetching logs of container {} from Loki",
+                        container_name
+                    ))?;
+                    break;
+                }
+                result = poller.poll_next() => result,
+            };
+            let result = match poll_result {
                 Ok(value) => match value {
-                    Some(v) => v,
+                    Some(v) => {
+                        throttled = 0;
+                        failures = 0;
+                        v
+                    }
                     None => {
                         break;
                     }
                 },
+                Err(LokiError::Throttled { retry_after }) if throttled < MAX_THROTTLED_RETRIES => {
+                    throttled += 1;
+                    let delay = retry_after
+                        .unwrap_or(DEFAULT_THROTTLED_DELAY)
+                        .min(MAX_THROTTLED_DELAY);
+                    write_to_log_file(format!(
+                        "[Warning] Loki is throttling requests, retrying in {:?}",
+                        delay
+                    ))?;
+                    tokio::select! {
+                        _ = self.cancel.cancelled() => {}
+                        _ = tokio::time::sleep(delay) => {}
+                    }
+                    continue;
+                }
+                Err(LokiError::Connection(e)) if failures < self.retries => {
+                    failures += 1;
+                    let delay =
+                        (RETRY_BASE_DELAY * 2u32.saturating_pow(failures - 1)).min(MAX_RETRY_DELAY);
+                    write_to_log_file(format!(
+                        "[Warning] Failed to connect to Loki ({}/{}), retrying in {:?}: {:?}",
+                        failures, self.retries, delay, e
+                    ))?;
+                    tokio::select! {
+                        _ = self.cancel.cancelled() => {}
+                        _ = tokio::time::sleep(delay) => {}
+                    }
+                    continue;
+                }
                 Err(e) => {
-                    if !is_written {
-                        if let Err(e) = std::fs::remove_file(file_path) {
-                            log(format!(
-                                "[Warning] Failed to remove empty historic log file {}",
-                                e
-                            ));
-                        }
+                    // keep the logs collected so far, if any
+                    if is_written {
+                        log_file.finish()?;
+                    } else {
+                        remove_empty_log_file(file_path);
                     }
                     write_to_log_file(format!("[Warning] While fetching logs from Loki {:?}", e))?;
                     return Err(e);
@@ -218,67 +401,417 @@ impl LokiClient {
                 write!(log_file, "{}", msg)?;
             }
         }
+        log_file.finish()?;
         Ok(())
     }
 }
 
+/// Convert the label selector into the Loki supported label filters, eg:
+/// app=mayastor,openebs.io/storage=mayastor into
+///  app="mayastor",openebs_io_storage="mayastor"
+fn label_filters(label_selector: &str) -> String {
+    label_selector
+        .split(',')
+        .filter(|key_value_pair| !key_value_pair.is_empty())
+        .map(|key_value_pair| {
+            let (key, value) = key_value_pair
+                .split_once('=')
+                .unwrap_or((key_value_pair, ""));
+            format!("{}=\"{}\"", key, value)
+                .replace(".", "_")
+                .replace("/", "_")
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Suffix of the log file of the given label selector, eg: app=mayastor,openebs.io/storage=mayastor
+/// into app_mayastor_openebs_io_storage_mayastor
+fn file_name_suffix(label_selector: &str) -> String {
+    label_selector
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Log file which is written either as plain text or gzip compressed
+enum LogFile {
+    Plain(std::fs::File),
+    Compressed(GzEncoder<std::fs::File>),
+}
+
+impl LogFile {
+    /// Create the log file at the given path, compressing its contents if `compress` is set
+    fn create(path: &std::path::Path, compress: bool) -> std::io::Result<Self> {
+        let file = std::fs::File::create(path)?;
+        Ok(match compress {
+            true => Self::Compressed(GzEncoder::new(file, Compression::default())),
+            false => Self::Plain(file),
+        })
+    }
+    /// Flush all the written logs into the file, finalizing the gzip stream if compressed
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(mut file) => file.flush(),
+            Self::Compressed(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Compressed(encoder) => encoder.write(buf),
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Compressed(encoder) => encoder.flush(),
+        }
+    }
+}
+
+fn remove_empty_log_file(file_path: PathBuf) {
+    if let Err(e) = std::fs::remove_file(file_path) {
+        log(format!(
+            "[Warning] Failed to remove empty historic log file {}",
+            e
+        ));
+    }
+}
+
 fn get_epoch_unix_time(since: humantime::Duration) -> SinceTime {
     Utc::now().timestamp_nanos() as SinceTime - since.as_nanos()
 }
 
-struct LokiPoll {
+struct LokiPoll<'a> {
+    client: &'a reqwest::Client,
     uri: String,
     endpoint: String,
     since: SinceTime,
-    timeout: humantime::Duration,
+    until: Option<SinceTime>,
+    direction: LogDirection,
+    limit: u64,
     query_params: String,
+    // timestamp from which the next page starts when going forward, ie: the last seen
+    // timestamp, or 0 for the first page which starts from `since`
     next_start_epoch_timestamp: SinceTime,
+    // entries already returned at the boundary timestamp, which is queried again by the next
+    // page, so they're not returned twice
+    seen: HashSet<(SinceTime, String)>,
+    // set once Loki returns a page with fewer entries than the limit, ie: the last page
+    done: bool,
 }
 
-impl LokiPoll {
+impl LokiPoll<'_> {
     // poll_next will extract response from Loki service and perform following actions:
     // 1. Get last log epoch timestamp
     // 2. Extract logs from response
     async fn poll_next(&mut self) -> Result<Option<Vec<String>>, LokiError> {
-        let mut start_time = self.since;
-        if self.next_start_epoch_timestamp != 0 {
-            start_time = self.since;
+        if self.done {
+            return Ok(None);
+        }
+        let start_time = match self.next_start_epoch_timestamp {
+            0 => self.since,
+            next_start_time => next_start_time,
+        };
+        if matches!(self.until, Some(until) if until <= start_time) {
+            return Ok(None);
         }
-        let request_str = format!(
+        let mut request_str = format!(
             "{}{}{}&start={}",
             self.uri, self.endpoint, self.query_params, start_time
         );
+        if let Some(until) = self.until {
+            request_str = format!("{}&end={}", request_str, until);
+        }
 
-        // Build client & make a request to Loki
-        // TODO: Test timeouts when Loki service is dropped unexpectedly
-        let client = reqwest::Client::builder()
-            .connect_timeout(std::time::Duration::from_secs(self.timeout.as_secs()))
-            .build()?;
-        let loki_response: LokiResponse = client.get(request_str).send().await?.json().await?;
-        if loki_response.status == "success" && loki_response.data.result.is_empty() {
-            return Ok(None);
+        // Make a request to Loki, checking the status first as error responses aren't in the shape
+        // of a `LokiResponse`
+        let response = self
+            .client
+            .get(request_str)
+            .send()
+            .await
+            .map_err(LokiError::Connection)?;
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(LokiError::Throttled {
+                retry_after: retry_after(response.headers()),
+            });
         }
-        let last_unix_time = loki_response.get_last_stream_unix_time();
-        if last_unix_time == 0 {
+        if !status.is_success() {
+            return Err(LokiError::Server {
+                status,
+                message: response.text().await.unwrap_or_default(),
+            });
+        }
+        let loki_response: LokiResponse = response.json().await?;
+        if loki_response.status == "success" && loki_response.data.result.is_empty() {
             return Ok(None);
         }
-        // Next time when poll_next is invoked it will continue to fetch logs after last timestamp
-        // TODO: Do we need to just add 1 nanosecond instead of 1 mill second?
-        self.since = last_unix_time + (1000000);
-        let logs = loki_response
+        Ok(self.next_page(loki_response))
+    }
+
+    // next_page extracts the new logs from a page returned by Loki, skipping the entries which
+    // were already returned by the previous page, and sets up the query for the next page
+    fn next_page(&mut self, loki_response: LokiResponse) -> Option<Vec<String>> {
+        let boundary_unix_time = match loki_response.get_boundary_unix_time(&self.direction) {
+            Some(unix_time) if unix_time != 0 => unix_time,
+            _ => return None,
+        };
+        let entries = loki_response
             .data
             .result
+            .into_iter()
+            .flat_map(|stream| stream.values.into_iter())
+            .filter_map(|mut value| {
+                let line = if value.len() > 1 {
+                    value.swap_remove(1)
+                } else {
+                    String::new()
+                };
+                let unix_time = value.get(0)?.parse::<SinceTime>().ok()?;
+                Some((unix_time, line))
+            })
+            .collect::<Vec<_>>();
+        let page_full = entries.len() as u64 >= self.limit;
+        if !page_full {
+            self.done = true;
+        }
+        let single_timestamp = entries
             .iter()
-            .map(|stream| -> Vec<String> {
-                stream
-                    .values
+            .all(|(unix_time, _)| *unix_time == boundary_unix_time);
+
+        let new_entries = entries
+            .into_iter()
+            .filter(|entry| !self.seen.contains(entry))
+            .collect::<Vec<_>>();
+        if page_full && single_timestamp {
+            // the page is filled with entries sharing a single timestamp so we can't page through
+            // them, skip past that timestamp instead
+            self.seen.clear();
+            match self.direction {
+                LogDirection::Forward => self.next_start_epoch_timestamp = boundary_unix_time + 1,
+                LogDirection::Backward => self.until = Some(boundary_unix_time),
+            }
+        } else {
+            if new_entries.is_empty() {
+                // the whole page was returned already, we can't make any progress
+                return None;
+            }
+            // the next page starts at the boundary timestamp, as there may be more entries with
+            // the same timestamp which didn't fit in this page
+            self.seen
+                .retain(|(unix_time, _)| *unix_time == boundary_unix_time);
+            self.seen.extend(
+                new_entries
                     .iter()
-                    .map(|value| value.get(1).unwrap_or(&"".to_string()).to_owned())
-                    .filter(|val| !val.is_empty())
-                    .collect::<Vec<String>>()
-            })
-            .flatten()
+                    .filter(|(unix_time, _)| *unix_time == boundary_unix_time)
+                    .cloned(),
+            );
+            match self.direction {
+                LogDirection::Forward => self.next_start_epoch_timestamp = boundary_unix_time,
+                // end is exclusive
+                LogDirection::Backward => self.until = Some(boundary_unix_time + 1),
+            }
+        }
+
+        let logs = new_entries
+            .into_iter()
+            .map(|(_, line)| line)
+            .filter(|line| !line.is_empty())
             .collect::<Vec<String>>();
-        Ok(Some(logs))
+        Some(logs)
+    }
+}
+
+/// Get the delay specified by the `Retry-After` header, in seconds
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn page(entries: &[(&str, &str)]) -> LokiResponse {
+        LokiResponse {
+            status: "success".to_string(),
+            data: Data {
+                result: vec![StreamContent {
+                    stream_metadata: StreamMetaData {
+                        host_name: "node-1".to_string(),
+                        pod_name: "io-engine-1".to_string(),
+                        container_name: "io-engine".to_string(),
+                    },
+                    values: entries
+                        .iter()
+                        .map(|(unix_time, line)| vec![unix_time.to_string(), line.to_string()])
+                        .collect(),
+                }],
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn pages() {
+        let client = reqwest::Client::new();
+        let mut poller = LokiPoll {
+            client: &client,
+            uri: "http://loki".to_string(),
+            endpoint: ENDPOINT.to_string(),
+            since: 1,
+            until: None,
+            direction: LogDirection::Forward,
+            limit: 3,
+            query_params: "".to_string(),
+            next_start_epoch_timestamp: 0,
+            seen: HashSet::new(),
+            done: false,
+        };
+
+        let logs = poller.next_page(page(&[("10", "a\n"), ("20", "b\n"), ("20", "c\n")]));
+        assert_eq!(logs, Some(vec!["a\n".into(), "b\n".into(), "c\n".into()]));
+        assert_eq!(poller.next_start_epoch_timestamp, 20);
+        assert!(!poller.done);
+
+        // the second page starts at the last timestamp, so it returns some entries again
+        let logs = poller.next_page(page(&[("20", "b\n"), ("20", "c\n"), ("30", "d\n")]));
+        assert_eq!(logs, Some(vec!["d\n".into()]));
+        assert_eq!(poller.next_start_epoch_timestamp, 30);
+        assert!(!poller.done);
+
+        // the last page has fewer entries than the limit and nothing new
+        let logs = poller.next_page(page(&[("30", "d\n")]));
+        assert_eq!(logs, None);
+        assert!(poller.done);
+        assert_eq!(poller.poll_next().await.unwrap(), None);
+    }
+
+    // feed the poller with the pages Loki would return for the given log entries, by applying
+    // the query of each page to them, returning all the collected logs
+    fn collect(poller: &mut LokiPoll<'_>, entries: &[(SinceTime, &str)]) -> Vec<String> {
+        let mut logs = vec![];
+        while !poller.done {
+            let start = match poller.next_start_epoch_timestamp {
+                0 => poller.since,
+                next_start_time => next_start_time,
+            };
+            let end = poller.until.unwrap_or(SinceTime::MAX);
+            let mut page_entries = entries
+                .iter()
+                .filter(|(unix_time, _)| *unix_time >= start && *unix_time < end)
+                .map(|(unix_time, line)| (unix_time.to_string(), *line))
+                .collect::<Vec<_>>();
+            if poller.direction == LogDirection::Backward {
+                page_entries.reverse();
+            }
+            page_entries.truncate(poller.limit as usize);
+            let page_entries = page_entries
+                .iter()
+                .map(|(unix_time, line)| (unix_time.as_str(), *line))
+                .collect::<Vec<_>>();
+            match poller.next_page(page(&page_entries)) {
+                Some(page_logs) => logs.extend(page_logs),
+                None => break,
+            }
+        }
+        logs
+    }
+
+    #[test]
+    fn page_boundaries() {
+        // several entries share the timestamps at the page boundaries
+        let entries = vec![
+            (10, "a"),
+            (20, "b"),
+            (20, "c"),
+            (20, "d"),
+            (30, "e"),
+            (40, "f"),
+            (40, "g"),
+            (50, "h"),
+        ];
+        let client = reqwest::Client::new();
+        for direction in vec![LogDirection::Forward, LogDirection::Backward] {
+            let mut poller = LokiPoll {
+                client: &client,
+                uri: "http://loki".to_string(),
+                endpoint: ENDPOINT.to_string(),
+                since: 1,
+                until: None,
+                direction: direction.clone(),
+                limit: 3,
+                query_params: "".to_string(),
+                next_start_epoch_timestamp: 0,
+                seen: HashSet::new(),
+                done: false,
+            };
+            let logs = collect(&mut poller, &entries);
+
+            let mut expected = entries
+                .iter()
+                .map(|(_, line)| line.to_string())
+                .collect::<Vec<_>>();
+            if direction == LogDirection::Backward {
+                expected.reverse();
+            }
+            assert_eq!(logs, expected, "{:?}", direction);
+        }
+    }
+
+    #[test]
+    fn log_file() {
+        use std::io::Read;
+        let dir = std::env::temp_dir().join(format!("loki-log-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for compress in [false, true] {
+            let path = dir.join(format!("io-engine-{}.log", compress));
+            let mut log_file = LogFile::create(&path, compress).unwrap();
+            write!(log_file, "line 1\nline 2\n").unwrap();
+            log_file.finish().unwrap();
+
+            let mut logs = String::new();
+            let mut file = std::fs::File::open(&path).unwrap();
+            match compress {
+                // the flate2 0.2 decoder reads the gzip header on creation, so it may fail
+                true => flate2::read::GzDecoder::new(file)
+                    .and_then(|mut decoder| decoder.read_to_string(&mut logs)),
+                false => file.read_to_string(&mut logs),
+            }
+            .unwrap();
+            assert_eq!(logs, "line 1\nline 2\n", "compress: {}", compress);
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn label_selectors() {
+        assert_eq!(
+            label_filters("app=mayastor,openebs.io/storage=mayastor"),
+            "app=\"mayastor\",openebs_io_storage=\"mayastor\""
+        );
+        assert_eq!(
+            label_filters("app.kubernetes.io/name=etcd"),
+            "app_kubernetes_io_name=\"etcd\""
+        );
+        assert_eq!(
+            file_name_suffix("app.kubernetes.io/name=etcd"),
+            "app_kubernetes_io_name_etcd"
+        );
     }
 }
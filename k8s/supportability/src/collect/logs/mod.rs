@@ -1,6 +1,10 @@
 mod k8s_log;
 mod loki;
 
+pub(crate) use loki::{
+    LogDirection, DEFAULT_LIMIT as DEFAULT_LOKI_LIMIT, DEFAULT_RETRIES as DEFAULT_LOKI_RETRIES,
+};
+
 use crate::collect::{
     constants::{
         CONTROL_PLANE_SERVICES, DATA_PLANE_SERVICES, HOST_NAME_REQUIRED_SERVICES,
@@ -15,7 +19,12 @@ use crate::collect::{
 };
 use async_trait::async_trait;
 use k8s_openapi::api::core::v1::Pod;
-use std::{collections::HashSet, iter::Iterator, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    iter::Iterator,
+    path::PathBuf,
+};
+use tokio_util::sync::CancellationToken;
 
 /// Error that can occur while interacting with logs module
 #[derive(Debug)]
@@ -79,6 +88,7 @@ pub(crate) struct LogResource {
 pub(crate) struct LogCollection {
     loki_client: Option<loki::LokiClient>,
     k8s_logger_client: K8sLoggerClient,
+    cancel: CancellationToken,
 }
 
 impl LogCollection {
@@ -87,12 +97,27 @@ impl LogCollection {
     /// Kube-API server param 'namespace' --> Defines the namespace of the product
     /// param 'loki_uri' --> Defines the address of loki instance
     /// param 'since'  --> Defines period from which logs needs to collect
+    /// param 'until'  --> Defines period up to which logs needs to collect, defaults to now
+    /// param 'direction' --> Defines the order in which logs are collected from Loki
+    /// param 'label_selectors' --> Loki label selectors overriding the default ones, keyed by
+    /// container name, the logs matching any of a container's selectors are collected
+    /// param 'loki_limit' --> Maximum number of log entries fetched from Loki on one http call
+    /// param 'loki_retries' --> Maximum number of times a request which failed to connect to
+    /// Loki is retried
+    /// param 'compress' --> Write the logs collected from Loki gzip compressed
     /// param 'timeout' --> Specifies the timeout while interacting with Loki Service
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn new_logger(
         kube_config_path: Option<std::path::PathBuf>,
         namespace: String,
         loki_uri: Option<String>,
         since: humantime::Duration,
+        until: Option<humantime::Duration>,
+        direction: LogDirection,
+        label_selectors: HashMap<String, Vec<String>>,
+        loki_limit: u64,
+        loki_retries: u32,
+        compress: bool,
         timeout: humantime::Duration,
     ) -> Result<Box<dyn Logger>, LogError> {
         let client_set = ClientSet::new(kube_config_path, namespace).await?;
@@ -112,9 +137,27 @@ impl LogCollection {
                 }
             }
         };
+        let cancel = CancellationToken::new();
+        let loki_client = match loki_endpoint {
+            Some(uri) => Some(loki::LokiClient::new(
+                uri,
+                since,
+                until,
+                direction,
+                label_selectors,
+                loki_limit,
+                loki_retries,
+                compress,
+                timeout,
+                cancel.clone(),
+            )?),
+            None => None,
+        };
+        cancel_on_interrupt(cancel.clone());
         Ok(Box::new(Self {
-            loki_client: loki_endpoint.map(|uri| loki::LokiClient::new(uri, since, timeout)),
+            loki_client,
             k8s_logger_client: K8sLoggerClient::new(client_set),
+            cancel,
         }))
     }
 
@@ -223,6 +266,13 @@ impl Logger for LogCollection {
     ) -> Result<(), LogError> {
         let mut errors = Vec::new();
         for resource in resources.iter() {
+            if self.cancel.is_cancelled() {
+                log(
+                    "\t Log collection was interrupted, skipping the remaining services"
+                        .to_string(),
+                );
+                break;
+            }
             log(format!(
                 "\t Collecting logs of service: {}, container: {} of host: {:?}",
                 resource.service_type, resource.container_name, resource.host_name,
@@ -235,7 +285,7 @@ impl Logger for LogCollection {
             if let Some(loki_client) = self.loki_client.clone() {
                 if loki_client
                     .fetch_and_dump_logs(
-                        resource.label_selector.clone(),
+                        vec![resource.label_selector.clone()],
                         resource.container_name.clone(),
                         resource.host_name.clone(),
                         service_dir.clone(),
@@ -324,6 +374,20 @@ impl Logger for LogCollection {
     }
 }
 
+/// Cancel the given token when the tool is interrupted (ctrl-c), so that the log collection is
+/// stopped promptly while keeping the logs collected so far. If interrupted again, the tool
+/// exits immediately.
+fn cancel_on_interrupt(cancel: CancellationToken) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            cancel.cancel();
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
+        }
+    });
+}
+
 fn is_host_name_required(service_name: String) -> bool {
     HOST_NAME_REQUIRED_SERVICES.contains_key(service_name.as_str())
 }
@@ -66,6 +66,12 @@ impl ResourceDumper {
             config.namespace.clone(),
             config.loki_uri,
             config.since,
+            config.until,
+            config.log_direction,
+            config.loki_label_selectors,
+            config.loki_limit,
+            config.loki_retries,
+            config.compress,
             config.timeout,
         )
         .await
@@ -164,7 +164,12 @@ impl PoolClientWrapper {
 
     // TODO: Add pagination support when REST service supports it
     async fn list_pools(&self) -> Result<Vec<Pool>, ResourceError> {
-        let pools = self.rest_client.pools_api().get_pools().await?.into_body();
+        let pools = self
+            .rest_client
+            .pools_api()
+            .get_pools(None)
+            .await?
+            .into_body();
         Ok(pools)
     }
 
@@ -4,7 +4,7 @@ use crate::collect::{
         pool::{PoolClientWrapper, PoolTopology},
         traits,
         traits::Topologer,
-        Resourcer,
+        utils, Resourcer,
     },
     rest_wrapper::rest_wrapper_client::RestClient,
 };
@@ -78,15 +78,24 @@ impl ReplicaClientWrapper {
         }
     }
 
-    // TODO: Add pagination support when REST service supports it
     #[allow(dead_code)]
     async fn list_replicas(&self) -> Result<Vec<Replica>, ResourceError> {
-        let replicas = self
-            .rest_client
-            .replicas_api()
-            .get_replicas()
-            .await?
-            .into_body();
+        let mut replicas: Vec<Replica> = Vec::new();
+        let mut next_token: Option<isize> = Some(0);
+        let max_entries: isize = utils::MAX_RESOURCE_ENTRIES;
+        loop {
+            let replicas_api_resp = self
+                .rest_client
+                .replicas_api()
+                .get_replicas(max_entries, next_token)
+                .await?
+                .into_body();
+            replicas.extend(replicas_api_resp.entries);
+            if replicas_api_resp.next_token.is_none() {
+                break;
+            }
+            next_token = replicas_api_resp.next_token;
+        }
         Ok(replicas)
     }
 
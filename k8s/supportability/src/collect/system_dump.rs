@@ -71,6 +71,12 @@ impl SystemDumper {
             config.namespace.clone(),
             config.loki_uri,
             config.since,
+            config.until,
+            config.log_direction,
+            config.loki_label_selectors,
+            config.loki_limit,
+            config.loki_retries,
+            config.compress,
             config.timeout,
         )
         .await
@@ -7,6 +7,7 @@ pub mod operations;
 use collect::{
     common::DumpConfig,
     error::Error,
+    logs::{LogDirection, DEFAULT_LOKI_LIMIT, DEFAULT_LOKI_RETRIES},
     resource_dump::ResourceDumper,
     resources::{
         node::NodeClientWrapper, pool::PoolClientWrapper, traits::Topologer,
@@ -17,7 +18,7 @@ use collect::{
 use operations::{Operations, Resource};
 
 use crate::collect::utils::log;
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 /// Collects state & log information of mayastor services running in the system and dump them.
 #[derive(Debug, Clone, clap::Args)]
@@ -30,6 +31,35 @@ pub struct SupportArgs {
     #[clap(global = true, long, short, default_value = "24h")]
     since: humantime::Duration,
 
+    /// Period states to collect logs up to the last specified duration, defaults to now
+    #[clap(global = true, long)]
+    until: Option<humantime::Duration>,
+
+    /// Order in which the logs are collected from Loki, either "forward" (oldest first) or
+    /// "backward" (most recent first)
+    #[clap(global = true, long, default_value = "forward")]
+    log_direction: LogDirection,
+
+    /// Loki label selector to use for a container instead of the default one, in the format
+    /// <container>:<selector>, eg: etcd:app.kubernetes.io/name=etcd
+    /// Can be specified multiple times, specifying several selectors for the same container
+    /// collects the logs matching any of them, each into its own file
+    #[clap(global = true, long, parse(try_from_str = parse_label_selector_override))]
+    loki_label_selector: Vec<(String, String)>,
+
+    /// Maximum number of log entries fetched from Loki on one http call
+    #[clap(global = true, long, default_value = DEFAULT_LOKI_LIMIT)]
+    loki_limit: u64,
+
+    /// Maximum number of times a request which failed to connect to Loki is retried, with an
+    /// exponential backoff, before giving up
+    #[clap(global = true, long, default_value = DEFAULT_LOKI_RETRIES)]
+    loki_retries: u32,
+
+    /// Write the logs collected from Loki gzip compressed, as .log.gz files
+    #[clap(global = true, long)]
+    compress: bool,
+
     /// Endpoint of LOKI service, if left empty then it will try to parse endpoint
     /// from Loki service(K8s service resource), if the tool is unable to parse
     /// from service then logs will be collected using Kube-apiserver
@@ -49,6 +79,19 @@ pub struct SupportArgs {
     namespace: String,
 }
 
+/// Parse a label selector override in the format <container>:<selector>
+fn parse_label_selector_override(value: &str) -> Result<(String, String), String> {
+    match value.split_once(':') {
+        Some((container, selector)) if !container.is_empty() && !selector.is_empty() => {
+            Ok((container.to_string(), selector.to_string()))
+        }
+        _ => Err(format!(
+            "Invalid label selector override '{}', expected <container>:<selector>",
+            value
+        )),
+    }
+}
+
 /// Supportability - collects state & log information of services and dumps it to a tar file.
 #[derive(Debug, Clone, clap::Args)]
 #[clap(
@@ -108,6 +151,21 @@ impl SupportArgs {
             loki_uri: cli_args.loki_endpoint,
             etcd_uri: cli_args.etcd_endpoint,
             since: cli_args.since,
+            until: cli_args.until,
+            log_direction: cli_args.log_direction,
+            loki_label_selectors: cli_args.loki_label_selector.into_iter().fold(
+                HashMap::new(),
+                |mut selectors, (container, selector)| {
+                    selectors
+                        .entry(container)
+                        .or_insert_with(Vec::new)
+                        .push(selector);
+                    selectors
+                },
+            ),
+            loki_limit: cli_args.loki_limit,
+            loki_retries: cli_args.loki_retries,
+            compress: cli_args.compress,
             kube_config_path,
             timeout: cli_args.timeout,
             topologer: None,
@@ -20,6 +20,11 @@ fn main() {
         .compile(&["api/protobuf/mayastor.proto"], &["api/protobuf"])
         .unwrap_or_else(|e| panic!("io-engine protobuf compilation failed: {}", e));
 
+    tonic_build::configure()
+        .build_server(false)
+        .compile(&["api/protobuf/v1/pool.proto"], &["api/protobuf/v1"])
+        .unwrap_or_else(|e| panic!("io-engine v1 pool protobuf compilation failed: {}", e));
+
     tonic_build::configure()
         .build_server(true)
         .compile(&["api/protobuf/csi.proto"], &["api/protobuf"])
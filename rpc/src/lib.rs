@@ -11,6 +11,7 @@ extern crate tonic;
 #[allow(clippy::redundant_closure)]
 #[allow(clippy::upper_case_acronyms)]
 pub mod io_engine {
+    use crate::v1::pool::pool_rpc_client::PoolRpcClient;
     use bdev_rpc_client::BdevRpcClient;
     /// AutoGenerated Io Engine Client
     pub use mayastor_client::MayastorClient as IoEngineClient;
@@ -48,13 +49,14 @@ pub mod io_engine {
     include!(concat!(env!("OUT_DIR"), "/mayastor.rs"));
 
     /// Test Rpc Handle to connect to an io-engine instance via an endpoint.
-    /// Gives access to the io-engine client and the bdev client.
+    /// Gives access to the io-engine client, the bdev client and the v1 pool client.
     #[derive(Clone)]
     pub struct RpcHandle {
         pub name: String,
         pub endpoint: SocketAddr,
         pub io_engine: IoEngineClient<Channel>,
         pub bdev: BdevRpcClient<Channel>,
+        pub pool: PoolRpcClient<Channel>,
     }
 
     impl RpcHandle {
@@ -80,17 +82,29 @@ pub mod io_engine {
             let bdev = BdevRpcClient::connect(format!("http://{}", endpoint))
                 .await
                 .unwrap();
+            let pool = PoolRpcClient::connect(format!("http://{}", endpoint))
+                .await
+                .unwrap();
 
             Ok(Self {
                 name: name.to_string(),
                 io_engine,
                 bdev,
+                pool,
                 endpoint,
             })
         }
     }
 }
 
+/// The v1 io-engine api, which is served alongside the v0 api
+pub mod v1 {
+    /// Pool management, including importing pools from their disks
+    pub mod pool {
+        include!(concat!(env!("OUT_DIR"), "/mayastor.v1.rs"));
+    }
+}
+
 pub mod csi {
     include!(concat!(env!("OUT_DIR"), "/csi.v1.rs"));
 }
@@ -5,14 +5,11 @@ use grpc::operations::replica::traits::ReplicaOperations;
 
 use deployer_cluster::{result_either, test_result_grpc, ClusterBuilder};
 
-// FIXME: CAS-721
 #[tokio::test]
-#[allow_fail]
 async fn create_replica() {
     let cluster = ClusterBuilder::builder()
         .with_pools(1)
-        // don't log whilst we have the allow_fail
-        .compose_build(|c| c.with_logs(false))
+        .build()
         .await
         .unwrap();
 
@@ -34,11 +31,49 @@ async fn create_replica() {
 
     // todo: why is this not the same?
     // assert_eq!(created_replica.size, replica.size);
-    // fixme: replicas are always created without thin provisioning
     assert_eq!(created_replica.thin, replica.thin);
     assert_eq!(created_replica.share, replica.share);
 }
 
+#[tokio::test]
+async fn create_default_id_replicas() {
+    let cluster = ClusterBuilder::builder()
+        .with_pools(1)
+        .build()
+        .await
+        .unwrap();
+
+    let rep_client = cluster.grpc_client().replica();
+
+    let replica = v0::CreateReplica {
+        node: cluster.node(0),
+        pool: cluster.pool(0, 0),
+        size: 5 * 1024 * 1024,
+        thin: true,
+        ..Default::default()
+    };
+    let first = rep_client.create(&replica, None).await.unwrap();
+    let second = rep_client
+        .create(
+            &v0::CreateReplica {
+                uuid: Default::default(),
+                ..replica.clone()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert_ne!(first.uuid, second.uuid);
+    assert_ne!(first.name, second.name);
+
+    let replicas = rep_client
+        .get(v0::Filter::Pool(cluster.pool(0, 0)), None, None)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(replicas.len(), 2);
+}
+
 #[tokio::test]
 async fn create_replica_protocols() {
     let cluster = ClusterBuilder::builder()
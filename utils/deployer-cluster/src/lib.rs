@@ -210,7 +210,7 @@ impl Cluster {
         if replica > 254 || pool > 254 || node > 254 {
             panic!("too large");
         }
-        let mut uuid = message_bus::ReplicaId::default().to_string();
+        let mut uuid = message_bus::ReplicaId::nil().to_string();
         // we can't use a uuid with all zeroes, as spdk seems to ignore it and generate new one
         let replica = replica + 1;
         let _ = uuid.drain(24 .. uuid.len());
@@ -306,11 +306,17 @@ impl Cluster {
     /// connect to message bus helper for the cargo test code with bus timeouts
     async fn connect_to_bus_timeout(&self, name: &str, bus_timeout: TimeoutOptions) {
         tokio::time::timeout(std::time::Duration::from_secs(2), async {
-            mbus_api::message_bus_init_options(None, self.composer.container_ip(name), bus_timeout)
-                .await
+            mbus_api::message_bus_init_options(
+                None,
+                self.composer.container_ip(name),
+                Default::default(),
+                bus_timeout,
+            )
+            .await
         })
         .await
-        .unwrap();
+        .unwrap()
+        .expect("Should connect to the nats server");
     }
 }
 
@@ -620,6 +626,13 @@ impl ClusterBuilder {
         self.opts = self.opts.with_node_deadline(deadline);
         self
     }
+    /// Specify the number of consecutive node deadlines a node may miss before it's deemed
+    /// offline, being deemed suspect until then
+    #[must_use]
+    pub fn with_node_offline_misses(mut self, misses: u32) -> Self {
+        self.opts = self.opts.with_node_offline_misses(misses);
+        self
+    }
     /// The period at which the registry updates its cache of all
     /// resources from all nodes
     #[must_use]
@@ -636,6 +649,12 @@ impl ClusterBuilder {
         self.opts = self.opts.with_reconcile_period(busy, idle);
         self
     }
+    /// With the period for which a nexus child must be faulted before it's removed
+    #[must_use]
+    pub fn with_faulted_child_grace_period(mut self, period: Duration) -> Self {
+        self.opts = self.opts.with_faulted_child_grace_period(period);
+        self
+    }
     /// With store operation timeout
     #[must_use]
     pub fn with_store_timeout(mut self, timeout: Duration) -> Self {
@@ -648,6 +667,12 @@ impl ClusterBuilder {
         self.opts = self.opts.with_store_lease_ttl(ttl);
         self
     }
+    /// With store key prefix
+    #[must_use]
+    pub fn with_store_key_prefix(mut self, prefix: &str) -> Self {
+        self.opts = self.opts.with_store_key_prefix(prefix);
+        self
+    }
     /// Specify the node connect and request timeouts
     #[must_use]
     pub fn with_req_timeouts(mut self, connect: Duration, request: Duration) -> Self {
@@ -821,6 +846,7 @@ impl ClusterBuilder {
                         id: pool.id(),
                         disks: vec![pool.disk()],
                         labels: None,
+                        reserved_capacity_percent: None,
                     },
                     None,
                 )
@@ -857,6 +883,8 @@ impl ClusterBuilder {
                         share: self.replicas.share,
                         managed: false,
                         owners: Default::default(),
+                        annotations: Default::default(),
+                        encryption: None,
                     });
                 }
                 pools.push(pool);
@@ -84,6 +84,9 @@ impl ResourceUpdates for Vec<models::Volume> {
                     &volume.spec.uuid,
                     node_id,
                     models::VolumeShareProtocol::Nvmf,
+                    None,
+                    None,
+                    None,
                 )
                 .await?;
             node_index = (node_index + 1) % node_ids.len();
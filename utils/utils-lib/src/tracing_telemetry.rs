@@ -1,7 +1,19 @@
 /// OpenTelemetry KeyVal for Processor Tags
 pub use opentelemetry::KeyValue;
-use opentelemetry::{global, sdk::propagation::TraceContextPropagator};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Registry};
+use opentelemetry::{global, sdk::propagation::TraceContextPropagator, trace::TraceContextExt};
+use std::{fmt::Write, str::FromStr};
+use tracing::{Event, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    fmt::{
+        format::{Format, Json, JsonFields},
+        FmtContext, FormatEvent, FormatFields,
+    },
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+    Registry,
+};
 
 /// Parse KeyValues from structopt's cmdline arguments
 pub fn parse_key_value(source: &str) -> Result<KeyValue, String> {
@@ -49,6 +61,90 @@ fn rust_log_add_quiet_defaults(
     tracing_subscriber::EnvFilter::try_new(logs).unwrap()
 }
 
+/// The format of the logs written to stdout
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LogFormat {
+    /// Human readable, pretty formatted logs
+    Text,
+    /// One JSON object per line, which log aggregation pipelines can parse reliably
+    Json,
+}
+impl Default for LogFormat {
+    fn default() -> Self {
+        Self::Text
+    }
+}
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "Invalid log format '{}', expected one of: text, json",
+                source
+            )),
+        }
+    }
+}
+impl std::fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// JSON event format which also includes the current span and its parents and, when the event
+/// is emitted as part of an opentelemetry trace, the `trace_id` and the `request_id`, ie: the id
+/// of the span of the request being served.
+struct JsonTraceFormat(Format<Json>);
+impl JsonTraceFormat {
+    fn new() -> Self {
+        Self(
+            tracing_subscriber::fmt::format()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true),
+        )
+    }
+    /// The trace and span ids of the current opentelemetry context, if any
+    fn trace_ids() -> Option<(String, String)> {
+        let span_context = |context: opentelemetry::Context| context.span().span_context().clone();
+        let mut span = span_context(tracing::Span::current().context());
+        if !span.is_valid() {
+            span = span_context(opentelemetry::Context::current());
+        }
+        span.is_valid()
+            .then(|| (span.trace_id().to_hex(), span.span_id().to_hex()))
+    }
+}
+impl<S, N> FormatEvent<S, N> for JsonTraceFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        writer: &mut dyn Write,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let mut json = String::new();
+        self.0.format_event(ctx, &mut json, event)?;
+        match (Self::trace_ids(), json.strip_prefix('{')) {
+            (Some((trace_id, request_id)), Some(fields)) => write!(
+                writer,
+                "{{\"trace_id\":\"{}\",\"request_id\":\"{}\",{}",
+                trace_id, request_id, fields
+            ),
+            _ => writer.write_str(&json),
+        }
+    }
+}
+
 /// Initialise tracing and optionally opentelemetry.
 /// Tracing will have a stdout subscriber with pretty formatting.
 pub fn init_tracing(service_name: &str, tracing_tags: Vec<KeyValue>, jaeger: Option<String>) {
@@ -58,10 +154,22 @@ pub fn init_tracing(service_name: &str, tracing_tags: Vec<KeyValue>, jaeger: Opt
 /// Initialise tracing and optionally opentelemetry.
 /// Tracing will have a stdout subscriber with pretty formatting.
 pub fn init_tracing_level(
+    service_name: &str,
+    tracing_tags: Vec<KeyValue>,
+    jaeger: Option<String>,
+    level: Option<&str>,
+) {
+    init_tracing_format(service_name, tracing_tags, jaeger, level, LogFormat::Text);
+}
+
+/// Initialise tracing and optionally opentelemetry.
+/// Tracing will have a stdout subscriber with the given log format.
+pub fn init_tracing_format(
     service_name: &str,
     mut tracing_tags: Vec<KeyValue>,
     jaeger: Option<String>,
     level: Option<&str>,
+    format: LogFormat,
 ) {
     let level = level.unwrap_or("info");
     let filter = rust_log_add_quiet_defaults(
@@ -69,9 +177,18 @@ pub fn init_tracing_level(
             .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(level)),
     );
 
-    let subscriber = Registry::default()
-        .with(filter)
-        .with(tracing_subscriber::fmt::layer().pretty());
+    let (text, json) = match format {
+        LogFormat::Text => (Some(tracing_subscriber::fmt::layer().pretty()), None),
+        LogFormat::Json => (
+            None,
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .fmt_fields(JsonFields::new())
+                    .event_format(JsonTraceFormat::new()),
+            ),
+        ),
+    };
+    let subscriber = Registry::default().with(filter).with(text).with(json);
 
     match jaeger {
         Some(jaeger) => {